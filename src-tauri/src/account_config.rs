@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use std::fs;
+use tauri::Emitter;
+
+use crate::redact::strip_userinfo;
+use crate::tasks::{new_id, now_iso};
+use crate::tls::TlsSettings;
+use crate::{db, lock, read_only};
+
+// bumped when the file layout changes in a way older versions can't read
+const FORMAT_VERSION: u32 = 1;
+
+/// accounts and their calendars as moved between machines: everything needed to set them up
+/// again except the credentials, which stay in the keyring they were saved in
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountConfigFile {
+    version: u32,
+    exported_at: String,
+    accounts: Vec<AccountConfig>,
+    /// the app's sync settings, handed through from the frontend as they are
+    #[serde(default)]
+    sync_settings: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct AccountConfig {
+    #[serde(skip)]
+    id: String,
+    name: String,
+    server_url: String,
+    username: String,
+    server_type: Option<String>,
+    /// files from before there was a choice of backend hold CalDAV accounts
+    #[serde(default = "default_backend")]
+    backend: String,
+    is_active: bool,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    collapsed: bool,
+    #[serde(default)]
+    sync_interval: Option<i64>,
+    #[serde(default)]
+    sync_quiet_start: Option<String>,
+    #[serde(default)]
+    sync_quiet_end: Option<String>,
+    #[sqlx(flatten)]
+    tls: TlsSettings,
+    #[sqlx(skip)]
+    calendars: Vec<CalendarConfig>,
+}
+
+fn default_backend() -> String {
+    "caldav".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct CalendarConfig {
+    display_name: String,
+    url: String,
+    color: Option<String>,
+    icon: Option<String>,
+    supported_components: Option<String>,
+    #[serde(default)]
+    checklist_as_tasks: bool,
+    #[serde(default)]
+    archived: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedAccountConfig {
+    pub accounts: usize,
+    pub calendars: usize,
+    /// accounts that were already set up here; only their missing calendars were added
+    pub existing_accounts: usize,
+    /// set when the file has them, for the frontend to apply
+    pub sync_settings: Option<Value>,
+    /// ids of the new accounts, which still need their passwords
+    pub needs_password: Vec<String>,
+}
+
+/// write every account with its calendars (urls, names, colors, icons, order, tls settings,
+/// sync schedule) and the given sync settings to `path`. passwords and tokens are never part
+/// of it
+#[tauri::command]
+pub async fn export_account_config(
+    app_handle: tauri::AppHandle,
+    path: String,
+    sync_settings: Option<Value>,
+) -> Result<(), String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let mut accounts: Vec<AccountConfig> = sqlx::query_as(
+        "SELECT id, name, server_url, username, server_type, backend, is_active, color, collapsed,
+                sync_interval, sync_quiet_start, sync_quiet_end, tls_min_version,
+                tls_pinned_certificate, tls_allow_invalid_hostnames
+         FROM accounts ORDER BY sort_order",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    for account in &mut accounts {
+        // a password typed into the url would leave with the file
+        account.server_url = strip_userinfo(&account.server_url);
+        account.calendars = sqlx::query_as(
+            "SELECT display_name, url, color, icon, supported_components,
+                    COALESCE(checklist_as_tasks, 0) AS checklist_as_tasks, archived
+             FROM calendars WHERE account_id = ? ORDER BY sort_order",
+        )
+        .bind(&account.id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let file = AccountConfigFile {
+        version: FORMAT_VERSION,
+        exported_at: now_iso(),
+        accounts,
+        sync_settings,
+    };
+    let json = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+    log::info!("Exported {} accounts", file.accounts.len());
+    Ok(())
+}
+
+/// set up the accounts and calendars of an exported file. an account that already exists
+/// here (same server and username) only gets the calendars it's missing; new accounts start
+/// without a password and sync once one is entered
+#[tauri::command]
+pub async fn import_account_config(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<ImportedAccountConfig, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    read_only::ensure_writable()?;
+    let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let file: AccountConfigFile = serde_json::from_str(&json)
+        .map_err(|e| format!("Not an account configuration file: {e}"))?;
+    if file.version > FORMAT_VERSION {
+        return Err(format!(
+            "The file was exported by a newer version (format {})",
+            file.version
+        ));
+    }
+    for account in &file.accounts {
+        account
+            .tls
+            .validate()
+            .map_err(|e| format!("{}: {e}", account.name))?;
+    }
+
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut imported = ImportedAccountConfig {
+        accounts: 0,
+        calendars: 0,
+        existing_accounts: 0,
+        sync_settings: file.sync_settings,
+        needs_password: Vec::new(),
+    };
+    for account in &file.accounts {
+        let existing: Option<String> =
+            sqlx::query_scalar("SELECT id FROM accounts WHERE server_url = ? AND username = ?")
+                .bind(&account.server_url)
+                .bind(&account.username)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        let account_id = match existing {
+            Some(id) => {
+                imported.existing_accounts += 1;
+                id
+            }
+            None => {
+                let id = new_id();
+                sqlx::query(
+                    "INSERT INTO accounts (id, name, server_url, username, password, server_type,
+                        backend, is_active, color, collapsed, sort_order, sync_interval,
+                        sync_quiet_start, sync_quiet_end, tls_min_version,
+                        tls_pinned_certificate, tls_allow_invalid_hostnames)
+                     VALUES (?, ?, ?, ?, '', ?, ?, ?, ?, ?,
+                        (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM accounts),
+                        ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&id)
+                .bind(&account.name)
+                .bind(&account.server_url)
+                .bind(&account.username)
+                .bind(&account.server_type)
+                .bind(&account.backend)
+                .bind(account.is_active)
+                .bind(&account.color)
+                .bind(account.collapsed)
+                .bind(account.sync_interval)
+                .bind(&account.sync_quiet_start)
+                .bind(&account.sync_quiet_end)
+                .bind(&account.tls.min_version)
+                .bind(&account.tls.pinned_certificate)
+                .bind(account.tls.allow_invalid_hostnames)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+                imported.accounts += 1;
+                imported.needs_password.push(id.clone());
+                id
+            }
+        };
+
+        for calendar in &account.calendars {
+            // no ctag or sync token: the first sync fetches everything
+            let added = sqlx::query(
+                "INSERT INTO calendars (id, account_id, display_name, url, color, icon,
+                    supported_components, checklist_as_tasks, archived, sort_order)
+                 SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                    (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM calendars)
+                 WHERE NOT EXISTS (SELECT 1 FROM calendars WHERE account_id = ? AND url = ?)",
+            )
+            .bind(new_id())
+            .bind(&account_id)
+            .bind(&calendar.display_name)
+            .bind(&calendar.url)
+            .bind(&calendar.color)
+            .bind(&calendar.icon)
+            .bind(&calendar.supported_components)
+            .bind(calendar.checklist_as_tasks)
+            .bind(calendar.archived)
+            .bind(&account_id)
+            .bind(&calendar.url)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            imported.calendars += added.rows_affected() as usize;
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Imported {} accounts and {} calendars",
+        imported.accounts,
+        imported.calendars
+    );
+    let _ = app_handle.emit("database-changed", ());
+    Ok(imported)
+}
@@ -0,0 +1,431 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use tauri::Emitter;
+
+use crate::auth::{self, AuthMethod};
+use crate::caldav::{self, AccountConfig, ConnectionStage, ConnectionTestResult};
+use crate::tasks::now_iso;
+use crate::tls::TlsSettings;
+use crate::{credentials, db, i18n, locale, lock, oauth, read_only, tray};
+
+// failed syncs in a row before a network or server problem needs attention; a rejected
+// password needs it right away. from then on the server counts as unreachable and scheduled
+// syncs back off
+const ATTENTION_AFTER: i64 = 3;
+// minutes the first backoff waits; every further failure doubles it, up to the max
+const BACKOFF_BASE: i64 = 2;
+const BACKOFF_MAX: i64 = 120;
+
+/// why the last sync of an account failed, or that it didn't
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthState {
+    Healthy,
+    /// the server rejected the credentials (401/403)
+    AuthFailed,
+    /// the server couldn't be reached (dns, tls, timeouts, offline)
+    NetworkError,
+    ServerError,
+}
+
+impl HealthState {
+    fn name(self) -> &'static str {
+        match self {
+            HealthState::Healthy => "healthy",
+            HealthState::AuthFailed => "authFailed",
+            HealthState::NetworkError => "networkError",
+            HealthState::ServerError => "serverError",
+        }
+    }
+
+    fn parse(name: &str) -> Self {
+        match name {
+            "authFailed" => HealthState::AuthFailed,
+            "networkError" => HealthState::NetworkError,
+            "serverError" => HealthState::ServerError,
+            _ => HealthState::Healthy,
+        }
+    }
+
+    // sync errors reach the backend as the messages the frontend saw
+    fn of_error(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let any = |words: &[&str]| words.iter().any(|word| error.contains(word));
+        if any(&[
+            "401",
+            "403",
+            "wrong username or password",
+            "authentication failed",
+            "unauthorized",
+            "forbidden",
+        ]) {
+            HealthState::AuthFailed
+        } else if any(&[
+            "connect",
+            "dns",
+            "timed out",
+            "timeout",
+            "tls",
+            "certificate",
+            "offline",
+            "network",
+            "error sending request",
+        ]) {
+            HealthState::NetworkError
+        } else {
+            HealthState::ServerError
+        }
+    }
+
+    fn of_test(result: &ConnectionTestResult) -> Self {
+        match (result.failed_stage, result.status) {
+            _ if result.success => HealthState::Healthy,
+            (_, Some(401 | 403)) | (Some(ConnectionStage::Auth), _) => HealthState::AuthFailed,
+            (Some(ConnectionStage::Dns | ConnectionStage::Tls), _) | (_, None) => {
+                HealthState::NetworkError
+            }
+            _ => HealthState::ServerError,
+        }
+    }
+}
+
+fn is_unreachable(state: HealthState, consecutive_failures: i64) -> bool {
+    matches!(state, HealthState::NetworkError | HealthState::ServerError)
+        && consecutive_failures >= ATTENTION_AFTER
+}
+
+/// when a scheduled sync may try an unreachable server again; None when it isn't backing off
+pub fn retry_at(
+    state: &str,
+    consecutive_failures: i64,
+    last_failure_at: Option<&str>,
+) -> Option<DateTime<Utc>> {
+    if !is_unreachable(HealthState::parse(state), consecutive_failures) {
+        return None;
+    }
+    let last_failure = DateTime::parse_from_rfc3339(last_failure_at?).ok()?;
+    let doublings = (consecutive_failures - ATTENTION_AFTER).min(10) as u32;
+    let minutes = (BACKOFF_BASE << doublings).min(BACKOFF_MAX);
+    Some(last_failure.with_timezone(&Utc) + Duration::minutes(minutes))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountHealth {
+    pub account_id: String,
+    pub state: HealthState,
+    /// failed syncs since the last one that worked
+    pub consecutive_failures: i64,
+    pub last_error: Option<String>,
+    pub last_failure_at: Option<String>,
+    pub last_success_at: Option<String>,
+    /// show the account as needing the user: its password was rejected, or it kept failing
+    pub attention_needed: bool,
+    /// the server kept failing to answer, so scheduled syncs back off
+    pub unreachable: bool,
+    /// when a scheduled sync tries the account again, while it's unreachable
+    pub next_retry_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct AccountHealthRow {
+    account_id: String,
+    state: String,
+    consecutive_failures: i64,
+    last_error: Option<String>,
+    last_failure_at: Option<String>,
+    last_success_at: Option<String>,
+}
+
+impl From<AccountHealthRow> for AccountHealth {
+    fn from(row: AccountHealthRow) -> Self {
+        let state = HealthState::parse(&row.state);
+        let next_retry_at = retry_at(
+            &row.state,
+            row.consecutive_failures,
+            row.last_failure_at.as_deref(),
+        );
+        AccountHealth {
+            attention_needed: state == HealthState::AuthFailed
+                || (state != HealthState::Healthy && row.consecutive_failures >= ATTENTION_AFTER),
+            unreachable: is_unreachable(state, row.consecutive_failures),
+            next_retry_at: next_retry_at.map(|at| at.to_rfc3339()),
+            account_id: row.account_id,
+            state,
+            consecutive_failures: row.consecutive_failures,
+            last_error: row.last_error,
+            last_failure_at: row.last_failure_at,
+            last_success_at: row.last_success_at,
+        }
+    }
+}
+
+async fn load(pool: &SqlitePool, account_id: &str) -> Result<AccountHealth, String> {
+    let row: Option<AccountHealthRow> =
+        sqlx::query_as("SELECT * FROM account_health WHERE account_id = ?")
+            .bind(account_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(row.map(AccountHealth::from).unwrap_or(AccountHealth {
+        account_id: account_id.to_string(),
+        state: HealthState::Healthy,
+        consecutive_failures: 0,
+        last_error: None,
+        last_failure_at: None,
+        last_success_at: None,
+        attention_needed: false,
+        unreachable: false,
+        next_retry_at: None,
+    }))
+}
+
+/// an account whose server has been failing for a while
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreachableServer {
+    pub account_id: String,
+    pub account_name: String,
+    pub server_url: String,
+    /// network or server error
+    pub reason: HealthState,
+    pub last_error: Option<String>,
+    pub consecutive_failures: i64,
+    /// when it was last reached, if ever
+    pub last_success_at: Option<String>,
+    pub next_retry_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct UnreachableRow {
+    account_name: String,
+    server_url: String,
+    #[sqlx(flatten)]
+    health: AccountHealthRow,
+}
+
+async fn unreachable_servers(pool: &SqlitePool) -> Result<Vec<UnreachableServer>, String> {
+    let rows: Vec<UnreachableRow> = sqlx::query_as(
+        "SELECT h.*, a.name AS account_name, a.server_url FROM account_health h
+         JOIN accounts a ON a.id = h.account_id
+         WHERE a.is_active = 1
+         ORDER BY a.sort_order",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let health = AccountHealth::from(row.health);
+            health.unreachable.then_some(UnreachableServer {
+                account_id: health.account_id,
+                account_name: row.account_name,
+                server_url: row.server_url,
+                reason: health.state,
+                last_error: health.last_error,
+                consecutive_failures: health.consecutive_failures,
+                last_success_at: health.last_success_at,
+                next_retry_at: health.next_retry_at,
+            })
+        })
+        .collect())
+}
+
+// tell the frontend and the tray which servers are unreachable
+async fn announce_unreachable(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+) -> Result<(), String> {
+    let servers = unreachable_servers(pool).await?;
+    let hint = match servers.as_slice() {
+        [] => None,
+        [server] => Some(
+            match server
+                .next_retry_at
+                .as_deref()
+                .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            {
+                Some(at) => i18n::t_with(
+                    "Can't reach {}, retrying at {}",
+                    &[
+                        &server.account_name,
+                        &locale::current().format_time(&at.with_timezone(&Local)),
+                    ],
+                ),
+                None => i18n::t_with("Can't reach {}", &[&server.account_name]),
+            },
+        ),
+        servers => Some(i18n::t_with("Can't reach {} servers", &[&servers.len()])),
+    };
+    tray::set_tray_warning(app_handle, hint.as_deref());
+    if let Err(e) = app_handle.emit("servers-unreachable-changed", &servers) {
+        log::warn!("Failed to emit servers-unreachable-changed: {e}");
+    }
+    Ok(())
+}
+
+/// save the outcome of a sync or check; emits `account-health-changed` when whether the
+/// account needs attention changes
+pub async fn record(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    account_id: &str,
+    failure: Option<(HealthState, String)>,
+) -> Result<AccountHealth, String> {
+    let before = load(pool, account_id).await?;
+    let now = now_iso();
+    let query = match &failure {
+        None => sqlx::query(
+            "INSERT INTO account_health (account_id, state, consecutive_failures, last_success_at)
+             VALUES (?1, 'healthy', 0, ?2)
+             ON CONFLICT(account_id) DO UPDATE SET
+                state = 'healthy', consecutive_failures = 0, last_success_at = ?2",
+        )
+        .bind(account_id)
+        .bind(&now),
+        Some((state, error)) => sqlx::query(
+            "INSERT INTO account_health
+                (account_id, state, consecutive_failures, last_error, last_failure_at)
+             VALUES (?1, ?2, 1, ?3, ?4)
+             ON CONFLICT(account_id) DO UPDATE SET
+                state = ?2, consecutive_failures = consecutive_failures + 1, last_error = ?3,
+                last_failure_at = ?4",
+        )
+        .bind(account_id)
+        .bind(state.name())
+        .bind(error)
+        .bind(&now),
+    };
+    query.execute(pool).await.map_err(|e| e.to_string())?;
+
+    let after = load(pool, account_id).await?;
+    if after.attention_needed != before.attention_needed {
+        if after.attention_needed {
+            log::warn!(
+                "Account {account_id} needs attention: {:?} after {} failed syncs",
+                after.state,
+                after.consecutive_failures
+            );
+        }
+        if let Err(e) = app_handle.emit("account-health-changed", &after) {
+            log::warn!("Failed to emit account-health-changed: {e}");
+        }
+    }
+    // every failure pushes the next retry further out
+    if before.unreachable || after.unreachable {
+        if !before.unreachable {
+            log::warn!("Server of account {account_id} is unreachable, backing off");
+        }
+        announce_unreachable(app_handle, pool).await?;
+    }
+    Ok(after)
+}
+
+#[tauri::command]
+pub async fn get_account_health(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<AccountHealth>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let rows: Vec<AccountHealthRow> = sqlx::query_as(
+        "SELECT account_health.* FROM account_health
+         JOIN accounts ON accounts.id = account_health.account_id",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(AccountHealth::from).collect())
+}
+
+/// the servers that have been failing for a while, with why and when they're tried again
+#[tauri::command]
+pub async fn get_unreachable_servers(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<UnreachableServer>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    unreachable_servers(&pool).await
+}
+
+/// the outcome of syncing an account, None when everything worked
+#[tauri::command]
+#[tracing::instrument(level = "debug", skip(app_handle, error), fields(failed = error.is_some()))]
+pub async fn report_sync_result(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    error: Option<String>,
+) -> Result<AccountHealth, String> {
+    let pool = db::pool(&app_handle).await?;
+    let failure = error.map(|error| (HealthState::of_error(&error), error));
+    match &failure {
+        None => tracing::info!("Sync finished"),
+        Some((state, error)) => tracing::warn!(state = state.name(), "Sync failed: {error}"),
+    }
+    record(&app_handle, &pool, &account_id, failure).await
+}
+
+/// check the account's credentials against the server right away, with `password` instead
+/// of the saved one if given; a password that works is saved
+#[tauri::command]
+pub async fn revalidate_credentials(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    password: Option<String>,
+) -> Result<AccountHealth, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    if password.is_some() {
+        read_only::ensure_writable()?;
+    }
+    let pool = db::pool(&app_handle).await?;
+    let (server_url, username, server_type, auth_method) =
+        sqlx::query_as::<_, (String, String, Option<String>, Option<String>)>(
+            "SELECT server_url, username, server_type, auth_method FROM accounts WHERE id = ?",
+        )
+        .bind(&account_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Account not found: {account_id}"))?;
+    let tls: TlsSettings = sqlx::query_as(
+        "SELECT tls_min_version, tls_pinned_certificate, tls_allow_invalid_hostnames
+         FROM accounts WHERE id = ?",
+    )
+    .bind(&account_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let saved = credentials::get_password(&account_id).await?;
+
+    let account = AccountConfig {
+        server_url,
+        username,
+        password: password.clone().or(saved).unwrap_or_default(),
+        server_type,
+        tls,
+        bearer_token: oauth::access_token(&app_handle, &account_id).await?,
+        auth_method: auth_method.as_deref().and_then(AuthMethod::parse),
+    };
+    let result = caldav::test_connection(account).await?;
+    let failure = match HealthState::of_test(&result) {
+        HealthState::Healthy => {
+            let accepted = result.auth_method.map(AuthMethod::name);
+            if accepted.is_some() && accepted != auth_method.as_deref() {
+                auth::remember(&pool, &account_id, result.auth_method).await?;
+            }
+            if let Some(password) = password {
+                credentials::set_password(&account_id, password).await?;
+                log::info!("Saved the new password of account {account_id}");
+            }
+            None
+        }
+        state => Some((
+            state,
+            result
+                .message
+                .unwrap_or_else(|| "Connection check failed".to_string()),
+        )),
+    };
+    record(&app_handle, &pool, &account_id, failure).await
+}
@@ -0,0 +1,213 @@
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri::Emitter;
+
+use crate::{db, read_only};
+
+// a paused account keeps its calendars and tasks but doesn't sync, and its tasks can't be
+// edited until it's active again; edits would otherwise pile up unsynced for the whole pause
+
+fn paused_error(name: &str) -> String {
+    format!("The account {name} is paused")
+}
+
+/// fail when one of the tasks (by `column`, "id" or "uid") belongs to a paused account
+pub async fn ensure_tasks_editable(
+    pool: &SqlitePool,
+    column: &str,
+    values: &[String],
+) -> Result<(), String> {
+    if values.is_empty() {
+        return Ok(());
+    }
+    let mut query = QueryBuilder::<Sqlite>::new(
+        "SELECT accounts.name FROM tasks JOIN accounts ON accounts.id = tasks.account_id
+         WHERE accounts.is_active = 0 AND tasks.",
+    );
+    query.push(column).push(" IN (");
+    let mut separated = query.separated(", ");
+    for value in values {
+        separated.push_bind(value);
+    }
+    query.push(") LIMIT 1");
+
+    let paused: Option<String> = query
+        .build_query_scalar()
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    match paused {
+        Some(name) => Err(paused_error(&name)),
+        None => Ok(()),
+    }
+}
+
+/// fail when the calendar belongs to a paused account
+pub async fn ensure_calendar_editable(pool: &SqlitePool, calendar_id: &str) -> Result<(), String> {
+    let paused: Option<String> = sqlx::query_scalar(
+        "SELECT accounts.name FROM calendars JOIN accounts ON accounts.id = calendars.account_id
+         WHERE calendars.id = ? AND accounts.is_active = 0",
+    )
+    .bind(calendar_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    match paused {
+        Some(name) => Err(paused_error(&name)),
+        None => Ok(()),
+    }
+}
+
+/// pause an account or make it active again; nothing of its local data is touched
+#[tauri::command]
+pub async fn set_account_active(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    active: bool,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let result = sqlx::query("UPDATE accounts SET is_active = ? WHERE id = ?")
+        .bind(active)
+        .bind(&account_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("Account not found: {account_id}"));
+    }
+
+    log::info!(
+        "Account {account_id} {}",
+        if active { "reactivated" } else { "paused" }
+    );
+    let _ = app_handle.emit("database-changed", ());
+    Ok(())
+}
+
+/// archive a calendar or restore it; an archived calendar keeps its tasks but doesn't sync and
+/// is left out of the task lists and counts, for finished projects
+#[tauri::command]
+pub async fn set_calendar_archived(
+    app_handle: tauri::AppHandle,
+    calendar_id: String,
+    archived: bool,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let result = sqlx::query("UPDATE calendars SET archived = ? WHERE id = ?")
+        .bind(archived)
+        .bind(&calendar_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("Calendar not found: {calendar_id}"));
+    }
+
+    log::info!(
+        "Calendar {calendar_id} {}",
+        if archived { "archived" } else { "restored" }
+    );
+    let _ = app_handle.emit("database-changed", ());
+    Ok(())
+}
+
+// give the rows of `table` their position in `ordered_ids` as sort order, optionally only
+// those matching `scope` (column, value)
+async fn reorder(
+    app_handle: &tauri::AppHandle,
+    table: &str,
+    ordered_ids: &[String],
+    scope: Option<(&str, &str)>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (position, id) in ordered_ids.iter().enumerate() {
+        let mut query = QueryBuilder::<Sqlite>::new(format!("UPDATE {table} SET sort_order = "));
+        query
+            .push_bind(position as i64)
+            .push(" WHERE id = ")
+            .push_bind(id);
+        if let Some((column, value)) = scope {
+            query.push(format!(" AND {column} = ")).push_bind(value);
+        }
+        query
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    let _ = app_handle.emit("database-changed", ());
+    Ok(())
+}
+
+/// the sidebar order of the accounts, top first
+#[tauri::command]
+pub async fn reorder_accounts(
+    app_handle: tauri::AppHandle,
+    ordered_ids: Vec<String>,
+) -> Result<(), String> {
+    reorder(&app_handle, "accounts", &ordered_ids, None).await
+}
+
+/// the sidebar order of an account's calendars, top first
+#[tauri::command]
+pub async fn reorder_calendars(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    ordered_ids: Vec<String>,
+) -> Result<(), String> {
+    reorder(
+        &app_handle,
+        "calendars",
+        &ordered_ids,
+        Some(("account_id", &account_id)),
+    )
+    .await
+}
+
+/// the color the account is shown with in the sidebar, "#rrggbb"; None for the default
+#[tauri::command]
+pub async fn set_account_color(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    color: Option<String>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    if let Some(color) = &color {
+        let hex = color.strip_prefix('#').unwrap_or_default();
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid color: {color}"));
+        }
+    }
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("UPDATE accounts SET color = ? WHERE id = ?")
+        .bind(&color)
+        .bind(&account_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = app_handle.emit("database-changed", ());
+    Ok(())
+}
+
+/// whether the account's calendars are hidden in the sidebar; the frontend updates its own
+/// copy, so there's no reload
+#[tauri::command]
+pub async fn set_account_collapsed(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    collapsed: bool,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("UPDATE accounts SET collapsed = ? WHERE id = ?")
+        .bind(collapsed)
+        .bind(&account_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
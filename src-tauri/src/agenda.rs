@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::tasks::{Task, TaskRow, BLOCKED_BY, NOT_ARCHIVED_CALENDAR};
+use crate::{db, locale, lock};
+
+// open tasks by when they're due, for the upcoming view and the widget. the buckets are worked
+// out in SQL in local time, so both get the same answer around midnight and across time zones
+
+// the local day a task is due on
+const DAY: &str = "date(tasks.due_date, 'localtime')";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AgendaBucket {
+    /// past its due date; an all-day task from the day after
+    Overdue,
+    Today,
+    Tomorrow,
+    /// later this week (starting on the locale's first day)
+    ThisWeek,
+    Later,
+}
+
+// in the order of the CASE in `agenda`
+const BUCKETS: [AgendaBucket; 5] = [
+    AgendaBucket::Overdue,
+    AgendaBucket::Today,
+    AgendaBucket::Tomorrow,
+    AgendaBucket::ThisWeek,
+    AgendaBucket::Later,
+];
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgendaRange {
+    /// overdue and today
+    Today,
+    /// up to the end of this week
+    Week,
+    /// everything with a due date
+    All,
+}
+
+impl AgendaRange {
+    fn last_bucket(self) -> i64 {
+        match self {
+            AgendaRange::Today => 1,
+            AgendaRange::Week => 3,
+            AgendaRange::All => 4,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct AgendaRow {
+    #[sqlx(flatten)]
+    task: TaskRow,
+    day: String,
+    bucket: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgendaTask {
+    #[serde(flatten)]
+    pub task: Task,
+    /// local yyyy-mm-dd the task is due on
+    pub day: String,
+}
+
+/// the tasks of one bucket, by due date
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgendaGroup {
+    pub bucket: AgendaBucket,
+    pub tasks: Vec<AgendaTask>,
+}
+
+/// open tasks with a due date grouped into overdue, today, tomorrow, this week and later;
+/// empty buckets are left out
+#[tauri::command]
+pub async fn agenda(
+    app_handle: tauri::AppHandle,
+    range: AgendaRange,
+) -> Result<Vec<AgendaGroup>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let week_end = format!(
+        "date('now', 'localtime', {}, '+7 days')",
+        locale::current().week_start_modifiers()
+    );
+    let sql = format!(
+        "SELECT * FROM (
+            SELECT tasks.*, {BLOCKED_BY}, {DAY} AS day,
+                CASE
+                    WHEN CASE WHEN tasks.due_date_all_day = 1
+                        THEN {DAY} < date('now', 'localtime')
+                        ELSE julianday(tasks.due_date) < julianday('now') END THEN 0
+                    WHEN {DAY} = date('now', 'localtime') THEN 1
+                    WHEN {DAY} = date('now', 'localtime', '+1 day') THEN 2
+                    WHEN {DAY} < {week_end} THEN 3
+                    ELSE 4
+                END AS bucket
+            FROM tasks
+            WHERE tasks.completed = 0 AND tasks.archived_at IS NULL
+                AND tasks.due_date IS NOT NULL AND {NOT_ARCHIVED_CALENDAR}
+        )
+        WHERE bucket <= ?
+        ORDER BY bucket, day, due_date_all_day DESC, julianday(due_date), sort_order"
+    );
+    let rows: Vec<AgendaRow> = sqlx::query_as(&sql)
+        .bind(range.last_bucket())
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut groups: Vec<AgendaGroup> = Vec::new();
+    for row in rows {
+        let bucket = BUCKETS[row.bucket.clamp(0, 4) as usize];
+        let task = AgendaTask {
+            task: Task::from(row.task),
+            day: row.day,
+        };
+        match groups.last_mut() {
+            Some(group) if group.bucket == bucket => group.tasks.push(task),
+            _ => groups.push(AgendaGroup {
+                bucket,
+                tasks: vec![task],
+            }),
+        }
+    }
+    Ok(groups)
+}
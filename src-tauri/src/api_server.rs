@@ -0,0 +1,747 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tauri_plugin_http::reqwest::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::bulk::{self, BulkOp};
+use crate::rules::{self, RuleEvent};
+use crate::tasks::{
+    fetch_by_ids, new_id, now_iso, push_filter, save_task, SortConfig, Task, TaskChanges,
+    TaskFilter, TaskPage, TaskRow,
+};
+use crate::{credentials, db, fuzzy, ics_feed, lock, mcp, read_only, settings};
+
+const ENABLED_KEY: &str = "api_server_enabled";
+const PORT_KEY: &str = "api_server_port";
+const DEFAULT_PORT: u16 = 47_820;
+// credential store entry of the bearer token
+const TOKEN_ENTRY: &str = "api_server_token";
+const MAX_REQUEST_SIZE: usize = 1 << 20;
+const MAX_HEADERS: usize = 64;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 500;
+
+lazy_static! {
+    // the running server: its port and accept loop
+    static ref SERVER: Mutex<Option<(u16, tauri::async_runtime::JoinHandle<()>)>> =
+        Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// whether the server is listening right now
+    pub running: bool,
+}
+
+struct Request {
+    method: String,
+    url: Url,
+    authorization: Option<String>,
+    host: Option<String>,
+    body: Vec<u8>,
+}
+
+/// what a request gets back; errors have an `error` message as their body
+pub struct Response {
+    pub status: u16,
+    pub body: Option<Value>,
+    /// an iCalendar body sent instead of json
+    pub ics: Option<String>,
+}
+
+impl Response {
+    pub fn json(status: u16, body: impl Serialize) -> Self {
+        match serde_json::to_value(body) {
+            Ok(body) => Response {
+                status,
+                body: Some(body),
+                ics: None,
+            },
+            Err(e) => Response::error(500, e.to_string()),
+        }
+    }
+
+    pub fn empty(status: u16) -> Self {
+        Response {
+            status,
+            body: None,
+            ics: None,
+        }
+    }
+
+    pub fn calendar(ics: String) -> Self {
+        Response {
+            status: 200,
+            body: None,
+            ics: Some(ics),
+        }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: Some(json!({ "error": message.into() })),
+            ics: None,
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        421 => "Misdirected Request",
+        423 => "Locked",
+        _ => "Internal Server Error",
+    }
+}
+
+/// a field that can be left out (None) or set to null (Some(None))
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTask {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// the first list when unset, a local task when there are none
+    pub calendar_id: Option<String>,
+    pub priority: Option<String>,
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub due_date_all_day: bool,
+    /// tag ids
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub parent_uid: Option<String>,
+    pub url: Option<String>,
+}
+
+/// fields that are left out stay as they are
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPatch {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub completed: Option<bool>,
+    pub priority: Option<String>,
+    #[serde(default, deserialize_with = "present")]
+    pub due_date: Option<Option<String>>,
+    pub due_date_all_day: Option<bool>,
+    pub tags: Option<Vec<String>>,
+}
+
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// the token, created the first time it's needed
+async fn token() -> Result<String, String> {
+    if let Some(token) = credentials::get_password(TOKEN_ENTRY).await? {
+        return Ok(token);
+    }
+    let token = generate_token();
+    credentials::set_password(TOKEN_ENTRY, token.clone()).await?;
+    Ok(token)
+}
+
+// compares every byte so the time taken doesn't give the token away
+pub fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+pub async fn load_settings(pool: &SqlitePool) -> Result<ApiServerSettings, String> {
+    let enabled = settings::get(pool, ENABLED_KEY).await?.as_deref() == Some("true");
+    let port = settings::get_u64(pool, PORT_KEY, DEFAULT_PORT.into()).await?;
+    Ok(ApiServerSettings {
+        enabled,
+        port: u16::try_from(port).unwrap_or(DEFAULT_PORT),
+        running: SERVER.lock().expect("Failed to lock SERVER").is_some(),
+    })
+}
+
+// read one request; Err is the response to send instead
+async fn read_request(stream: &mut TcpStream) -> Result<Request, Response> {
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = stream
+            .read(&mut buffer)
+            .await
+            .map_err(|e| Response::error(400, e.to_string()))?;
+        if read == 0 {
+            return Err(Response::error(400, "Incomplete request"));
+        }
+        data.extend_from_slice(&buffer[..read]);
+        if data.len() > MAX_REQUEST_SIZE {
+            return Err(Response::error(413, "Request too large"));
+        }
+
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut parsed = httparse::Request::new(&mut headers);
+        let httparse::Status::Complete(head) = parsed
+            .parse(&data)
+            .map_err(|e| Response::error(400, e.to_string()))?
+        else {
+            continue;
+        };
+        let header = |name: &str| {
+            parsed
+                .headers
+                .iter()
+                .find(|header| header.name.eq_ignore_ascii_case(name))
+                .map(|header| String::from_utf8_lossy(header.value).trim().to_string())
+        };
+        let length = match header("content-length") {
+            Some(length) => length
+                .parse::<usize>()
+                .map_err(|_| Response::error(400, "Invalid Content-Length"))?,
+            None => 0,
+        };
+        if head + length > MAX_REQUEST_SIZE {
+            return Err(Response::error(413, "Request too large"));
+        }
+        if data.len() < head + length {
+            continue;
+        }
+
+        let url = Url::parse("http://localhost")
+            .and_then(|base| base.join(parsed.path.unwrap_or("/")))
+            .map_err(|e| Response::error(400, e.to_string()))?;
+        return Ok(Request {
+            method: parsed.method.unwrap_or("GET").to_string(),
+            url,
+            authorization: header("authorization"),
+            host: header("host"),
+            body: data[head..head + length].to_vec(),
+        });
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, response: Response) -> std::io::Result<()> {
+    let (content_type, body) = match response.ics {
+        Some(ics) => ("text/calendar; charset=utf-8", ics),
+        None => (
+            "application/json",
+            response
+                .body
+                .map(|body| body.to_string())
+                .unwrap_or_default(),
+        ),
+    };
+    // browser extensions call from their own origin; the token is what keeps others out
+    let head = format!(
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Headers: Authorization, Content-Type\r\n\
+         Access-Control-Allow-Methods: GET, POST, PATCH, DELETE, OPTIONS\r\n\
+         Connection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(request: &Request) -> Result<T, Response> {
+    serde_json::from_slice(&request.body)
+        .map_err(|e| Response::error(400, format!("Invalid body: {e}")))
+}
+
+fn check_priority(priority: &str) -> Result<(), Response> {
+    match priority {
+        "high" | "medium" | "low" | "none" => Ok(()),
+        _ => Err(Response::error(
+            400,
+            format!("Unknown priority \"{priority}\""),
+        )),
+    }
+}
+
+pub async fn list_lists(pool: &SqlitePool) -> Result<Response, Response> {
+    let lists: Vec<(String, String, String, String)> = sqlx::query_as(
+        "SELECT calendars.id, calendars.display_name, accounts.id, accounts.name FROM calendars
+         JOIN accounts ON accounts.id = calendars.account_id
+         ORDER BY accounts.name COLLATE NOCASE, calendars.display_name COLLATE NOCASE",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| Response::error(500, e.to_string()))?;
+    let lists: Vec<Value> = lists
+        .into_iter()
+        .map(|(id, name, account_id, account)| {
+            json!({ "id": id, "name": name, "accountId": account_id, "account": account })
+        })
+        .collect();
+    Ok(Response::json(200, lists))
+}
+
+// GET /find?q=&limit=, fuzzy matches for pickers in scripts and on the command line
+async fn fuzzy_find(pool: &SqlitePool, url: &Url) -> Result<Response, Response> {
+    let (mut query, mut limit) = (String::new(), None);
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "q" => query = value.into_owned(),
+            "limit" => {
+                limit = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| Response::error(400, "Invalid limit"))?,
+                )
+            }
+            _ => {}
+        }
+    }
+    let matches = fuzzy::find(pool, &query, limit)
+        .await
+        .map_err(|e| Response::error(500, e))?;
+    Ok(Response::json(200, matches))
+}
+
+// the filter and page of GET /tasks?search=&list=&tag=&completed=&offset=&limit=
+fn task_query(url: &Url) -> Result<(TaskFilter, u32, u32), Response> {
+    let mut filter = TaskFilter::default();
+    let (mut offset, mut limit) = (0, DEFAULT_PAGE_SIZE);
+    for (key, value) in url.query_pairs() {
+        let value = value.into_owned();
+        let number = || {
+            value
+                .parse::<u32>()
+                .map_err(|_| Response::error(400, format!("Invalid {key}")))
+        };
+        match key.as_ref() {
+            "search" => filter.search = Some(value),
+            "list" => filter.calendar_id = Some(value),
+            "tag" => filter.tag_id = Some(value),
+            "completed" => filter.completed = Some(value == "true"),
+            "offset" => offset = number()?,
+            "limit" => limit = number()?,
+            _ => {}
+        }
+    }
+    Ok((filter, offset, limit))
+}
+
+/// one page of tasks in manual order
+pub async fn list_tasks(
+    pool: &SqlitePool,
+    filter: &TaskFilter,
+    offset: u32,
+    limit: u32,
+) -> Result<Response, Response> {
+    let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM tasks");
+    push_filter(&mut count, filter);
+    let total: i64 = count
+        .build_query_scalar()
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Response::error(500, e.to_string()))?;
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM tasks");
+    push_filter(&mut query, filter);
+    query
+        .push(" ORDER BY ")
+        .push(SortConfig::default().order_by())
+        .push(" LIMIT ")
+        .push_bind(limit.min(MAX_PAGE_SIZE))
+        .push(" OFFSET ")
+        .push_bind(offset);
+    let rows: Vec<TaskRow> = query
+        .build_query_as()
+        .fetch_all(pool)
+        .await
+        .map_err(|e| Response::error(500, e.to_string()))?;
+    Ok(Response::json(
+        200,
+        TaskPage {
+            tasks: rows.into_iter().map(Task::from).collect(),
+            total,
+            offset,
+        },
+    ))
+}
+
+pub async fn find_task(pool: &SqlitePool, id: &str) -> Result<Task, Response> {
+    fetch_by_ids(pool, &[id.to_string()])
+        .await
+        .map_err(|e| Response::error(500, e))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Response::error(404, format!("Task {id} not found")))
+}
+
+// run the rules for the event, save and report the task to the frontend
+async fn store(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    task: Task,
+    event: RuleEvent,
+) -> Result<Task, String> {
+    let rules = rules::load(pool, event).await?;
+    let task = rules::apply(&rules, &task).unwrap_or(task);
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    save_task(&mut tx, &task).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let ids = vec![task.id.clone()];
+    let changes = match event {
+        RuleEvent::Created => TaskChanges {
+            created: ids,
+            ..TaskChanges::default()
+        },
+        _ => TaskChanges {
+            updated: ids,
+            ..TaskChanges::default()
+        },
+    };
+    changes.emit(app_handle, pool).await;
+    Ok(task)
+}
+
+pub async fn create_task(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    new: NewTask,
+) -> Result<Response, Response> {
+    if new.title.trim().is_empty() {
+        return Err(Response::error(400, "Tasks need a title"));
+    }
+    let priority = new.priority.unwrap_or_else(|| "none".to_string());
+    check_priority(&priority)?;
+
+    let calendar: Option<(String, String)> = match &new.calendar_id {
+        Some(calendar_id) => Some(
+            sqlx::query_as("SELECT id, account_id FROM calendars WHERE id = ?")
+                .bind(calendar_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| Response::error(500, e.to_string()))?
+                .ok_or_else(|| Response::error(400, format!("Unknown list {calendar_id}")))?,
+        ),
+        None => sqlx::query_as(
+            "SELECT calendars.id, calendars.account_id FROM calendars
+             JOIN accounts ON accounts.id = calendars.account_id
+             ORDER BY accounts.rowid, calendars.rowid LIMIT 1",
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| Response::error(500, e.to_string()))?,
+    };
+    let sort_order: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(sort_order), 0) + 1 FROM tasks")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| Response::error(500, e.to_string()))?;
+
+    let now = now_iso();
+    let (calendar_id, account_id) = calendar.unzip();
+    let task: Task = serde_json::from_value(json!({
+        "id": new_id(),
+        "uid": new_id(),
+        "title": new.title.trim(),
+        "description": new.description,
+        "priority": priority,
+        "dueDate": new.due_date,
+        "dueDateAllDay": new.due_date_all_day,
+        "tags": new.tags,
+        "parentUid": new.parent_uid,
+        "url": new.url,
+        "sortOrder": sort_order,
+        "createdAt": now,
+        "modifiedAt": now,
+        "localOnly": calendar_id.is_none(),
+        "calendarId": calendar_id.unwrap_or_default(),
+        "accountId": account_id.unwrap_or_default(),
+    }))
+    .map_err(|e| Response::error(400, e.to_string()))?;
+
+    let task = store(app_handle, pool, task, RuleEvent::Created)
+        .await
+        .map_err(|e| Response::error(500, e))?;
+    Ok(Response::json(201, task))
+}
+
+pub async fn update_task(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    id: &str,
+    patch: TaskPatch,
+) -> Result<Response, Response> {
+    let mut task = find_task(pool, id).await?;
+    if let Some(title) = patch.title {
+        if title.trim().is_empty() {
+            return Err(Response::error(400, "Tasks need a title"));
+        }
+        task.title = title.trim().to_string();
+    }
+    if let Some(description) = patch.description {
+        task.description = description;
+    }
+    if let Some(completed) = patch.completed {
+        if completed != task.completed {
+            task.completed = completed;
+            task.completed_at = completed.then(now_iso);
+        }
+    }
+    if let Some(priority) = patch.priority {
+        check_priority(&priority)?;
+        task.priority = priority;
+    }
+    if let Some(due_date) = patch.due_date {
+        task.due_date = due_date;
+    }
+    if let Some(all_day) = patch.due_date_all_day {
+        task.due_date_all_day = all_day;
+    }
+    if let Some(tags) = patch.tags {
+        task.tags = tags;
+    }
+    task.modified_at = now_iso();
+    task.synced = false;
+
+    let task = store(app_handle, pool, task, RuleEvent::Updated)
+        .await
+        .map_err(|e| Response::error(500, e))?;
+    Ok(Response::json(200, task))
+}
+
+async fn route(app_handle: &tauri::AppHandle, request: Request) -> Result<Response, Response> {
+    let pool = db::pool(app_handle)
+        .await
+        .map_err(|e| Response::error(500, e))?;
+    let segments: Vec<&str> = request
+        .url
+        .path_segments()
+        .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+        .unwrap_or_default();
+    let method = request.method.as_str();
+    // mcp tools check this per call, listing has to keep working
+    if method != "GET" && segments != ["mcp"] {
+        read_only::ensure_writable().map_err(|e| Response::error(403, e))?;
+    }
+
+    let mcp_enabled = segments == ["mcp"]
+        && mcp::is_enabled(&pool)
+            .await
+            .map_err(|e| Response::error(500, e))?;
+
+    match (method, segments.as_slice()) {
+        ("GET", ["lists"]) => list_lists(&pool).await,
+        ("GET", ["find"]) => fuzzy_find(&pool, &request.url).await,
+        ("GET", ["feed.ics"]) => ics_feed::render(&pool)
+            .await
+            .map(Response::calendar)
+            .map_err(|e| Response::error(500, e)),
+        ("GET", ["tasks"]) => {
+            let (filter, offset, limit) = task_query(&request.url)?;
+            list_tasks(&pool, &filter, offset, limit).await
+        }
+        ("POST", ["tasks"]) => create_task(app_handle, &pool, parse_body(&request)?).await,
+        ("GET", ["tasks", id]) => Ok(Response::json(200, find_task(&pool, id).await?)),
+        ("PATCH", ["tasks", id]) => update_task(app_handle, &pool, id, parse_body(&request)?).await,
+        ("DELETE", ["tasks", id]) => {
+            find_task(&pool, id).await?;
+            bulk::update(app_handle, &[id.to_string()], &BulkOp::Delete)
+                .await
+                .map_err(|e| Response::error(500, e))?;
+            Ok(Response::empty(204))
+        }
+        // the same as the tray's "Sync Now"; the sync itself runs in the frontend
+        ("POST", ["sync"]) => {
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.emit("tray-sync", ());
+            }
+            Ok(Response::empty(202))
+        }
+        ("POST", ["mcp"]) if mcp_enabled => Ok(mcp::handle(app_handle, &pool, &request.body).await),
+        // no server-initiated messages, so there's no event stream to open
+        (_, ["mcp"]) if mcp_enabled => Err(Response::error(405, "Method not allowed")),
+        (_, ["lists"] | ["find"] | ["tasks"] | ["tasks", _] | ["sync"] | ["feed.ics"]) => {
+            Err(Response::error(405, "Method not allowed"))
+        }
+        _ => Err(Response::error(404, "Not found")),
+    }
+}
+
+// Err is the response to send instead
+async fn authorize(request: &Request, port: u16) -> Result<(), Response> {
+    if request.method == "OPTIONS" {
+        return Err(Response::empty(204));
+    }
+    // a page that rebinds its own host name to 127.0.0.1 still sends that name
+    let local = [format!("127.0.0.1:{port}"), format!("localhost:{port}")];
+    if !request
+        .host
+        .as_ref()
+        .is_some_and(|host| local.contains(host))
+    {
+        return Err(Response::error(421, "Unexpected Host header"));
+    }
+    // calendar apps can't send a header, the feed has a read-only token of its own in the url
+    if request.url.path() == ics_feed::FEED_PATH {
+        ics_feed::authorize(&request.url).await?;
+    } else {
+        let token = token().await.map_err(|e| Response::error(500, e))?;
+        let given = request
+            .authorization
+            .as_deref()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        if !same_token(given, &token) {
+            return Err(Response::error(401, "Missing or wrong token"));
+        }
+    }
+    if lock::is_locked() {
+        return Err(Response::error(423, "App is locked"));
+    }
+    Ok(())
+}
+
+async fn handle(app_handle: tauri::AppHandle, mut stream: TcpStream, port: u16) {
+    let response = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Err(_) => Response::error(408, "Request timed out"),
+        Ok(Err(response)) => response,
+        Ok(Ok(request)) => match authorize(&request, port).await {
+            Ok(()) => route(&app_handle, request)
+                .await
+                .unwrap_or_else(|response| response),
+            Err(response) => response,
+        },
+    };
+    if let Err(e) = write_response(&mut stream, response).await {
+        log::debug!("Failed to answer an API request: {e}");
+    }
+}
+
+fn stop() {
+    if let Some((port, server)) = SERVER.lock().expect("Failed to lock SERVER").take() {
+        server.abort();
+        log::info!("API server on port {port} stopped");
+    }
+}
+
+// (re)start listening on localhost
+async fn start(app_handle: &tauri::AppHandle, port: u16) -> Result<(), String> {
+    stop();
+    // make sure requests find a token even before the settings showed one
+    token().await?;
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {port}: {e}"))?;
+    let app_handle = app_handle.clone();
+    let server = tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(handle(app_handle.clone(), stream, port));
+                }
+                Err(e) => log::warn!("API server failed to accept a connection: {e}"),
+            }
+        }
+    });
+    *SERVER.lock().expect("Failed to lock SERVER") = Some((port, server));
+    log::info!("API server listening on 127.0.0.1:{port}");
+    Ok(())
+}
+
+/// start the server when it's turned on
+pub fn start_if_enabled(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let result = async {
+            let pool = db::pool(&app_handle).await?;
+            let settings = load_settings(&pool).await?;
+            if settings.enabled {
+                start(&app_handle, settings.port).await?;
+            }
+            Ok::<_, String>(())
+        }
+        .await;
+        if let Err(e) = result {
+            log::warn!("Failed to start the API server: {e}");
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_api_server_settings(
+    app_handle: tauri::AppHandle,
+) -> Result<ApiServerSettings, String> {
+    let pool = db::pool(&app_handle).await?;
+    load_settings(&pool).await
+}
+
+/// turn the server on or off, or move it to another port
+#[tauri::command]
+pub async fn set_api_server_settings(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+    port: u16,
+) -> Result<ApiServerSettings, String> {
+    read_only::ensure_writable()?;
+    if port < 1024 {
+        return Err("Use a port from 1024 up".to_string());
+    }
+    let pool = db::pool(&app_handle).await?;
+    if enabled {
+        start(&app_handle, port).await?;
+    } else {
+        stop();
+    }
+    settings::set(&pool, ENABLED_KEY, &enabled.to_string()).await?;
+    settings::set(&pool, PORT_KEY, &port.to_string()).await?;
+    load_settings(&pool).await
+}
+
+/// the bearer token clients have to send
+#[tauri::command]
+pub async fn get_api_server_token(app_handle: tauri::AppHandle) -> Result<String, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    token().await
+}
+
+/// replace the token; clients using the old one are shut out
+#[tauri::command]
+pub async fn regenerate_api_server_token(app_handle: tauri::AppHandle) -> Result<String, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let token = generate_token();
+    credentials::set_password(TOKEN_ENTRY, token.clone()).await?;
+    Ok(token)
+}
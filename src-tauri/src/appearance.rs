@@ -0,0 +1,245 @@
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+// the system's dark mode and accent color, so the UI and the things the backend draws can
+// follow the system theme. changes are pushed as `appearance-changed`: on Linux the desktop
+// portal tells us, elsewhere they're picked up when the theme changes or the window gets focus
+
+/// how the system looks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Appearance {
+    pub dark: bool,
+    /// `#rrggbb`; None when the system has no accent color or it can't be read
+    pub accent_color: Option<String>,
+}
+
+lazy_static! {
+    // the last appearance sent to the frontend, to only emit real changes
+    static ref LAST_APPEARANCE: Mutex<Option<Appearance>> = Mutex::new(None);
+}
+
+fn hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// the accent color from System Settings; the default (blue) isn't stored
+#[cfg(target_os = "macos")]
+async fn accent_color() -> Option<String> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleAccentColor"])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout);
+    let color = match value.trim() {
+        "-1" => hex(0x8c, 0x8c, 0x8c),
+        "0" => hex(0xff, 0x52, 0x57),
+        "1" => hex(0xf7, 0x82, 0x1b),
+        "2" => hex(0xff, 0xc6, 0x00),
+        "3" => hex(0x62, 0xba, 0x46),
+        "5" => hex(0xa5, 0x50, 0xa7),
+        "6" => hex(0xf7, 0x4f, 0x9e),
+        _ => hex(0x00, 0x7a, 0xff),
+    };
+    Some(color)
+}
+
+/// the accent color DWM draws with, stored as 0xAABBGGRR
+#[cfg(target_os = "windows")]
+async fn accent_color() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+
+    // don't flash a console window
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Microsoft\Windows\DWM",
+            "/v",
+            "AccentColor",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let value = stdout
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("0x"))?;
+    let abgr = u32::from_str_radix(value, 16).ok()?;
+    Some(hex(abgr as u8, (abgr >> 8) as u8, (abgr >> 16) as u8))
+}
+
+#[cfg(target_os = "linux")]
+const PORTAL_SETTINGS: &str = "org.freedesktop.portal.Settings";
+#[cfg(target_os = "linux")]
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+
+/// read a setting through the desktop portal
+#[cfg(target_os = "linux")]
+async fn portal_setting(key: &str) -> zbus::Result<zbus::zvariant::OwnedValue> {
+    let connection = zbus::Connection::session().await?;
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            Some(PORTAL_SETTINGS),
+            "ReadOne",
+            &(APPEARANCE_NAMESPACE, key),
+        )
+        .await?;
+    reply.body().deserialize()
+}
+
+/// the portal's accent color, three doubles from 0 to 1; out of range means none is set
+#[cfg(target_os = "linux")]
+fn portal_color(value: &zbus::zvariant::Value) -> Option<String> {
+    use zbus::zvariant::Value;
+
+    let fields = match value {
+        Value::Value(inner) => return portal_color(inner),
+        Value::Structure(structure) => structure.fields(),
+        _ => return None,
+    };
+    let channels: Vec<u8> = fields
+        .iter()
+        .map(|field| match field {
+            Value::F64(channel) if (0.0..=1.0).contains(channel) => {
+                Some((channel * 255.0).round() as u8)
+            }
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    match channels[..] {
+        [r, g, b] => Some(hex(r, g, b)),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+async fn accent_color() -> Option<String> {
+    match portal_setting("accent-color").await {
+        Ok(value) => portal_color(&value),
+        Err(e) => {
+            log::debug!("No accent color from the desktop portal: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+async fn accent_color() -> Option<String> {
+    None
+}
+
+/// the portal's color scheme: 1 is dark, 2 light and 0 no preference
+#[cfg(target_os = "linux")]
+async fn portal_dark() -> Option<bool> {
+    use zbus::zvariant::Value;
+
+    let value = portal_setting("color-scheme").await.ok()?;
+    let scheme = match &*value {
+        Value::Value(inner) => match **inner {
+            Value::U32(scheme) => scheme,
+            _ => return None,
+        },
+        Value::U32(scheme) => *scheme,
+        _ => return None,
+    };
+    match scheme {
+        1 => Some(true),
+        2 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn portal_dark() -> Option<bool> {
+    None
+}
+
+async fn current(app_handle: &tauri::AppHandle) -> Appearance {
+    let window_dark = || {
+        app_handle
+            .get_webview_window("main")
+            .and_then(|window| window.theme().ok())
+            .is_some_and(|theme| theme == tauri::Theme::Dark)
+    };
+    let dark = match portal_dark().await {
+        Some(dark) => dark,
+        None => window_dark(),
+    };
+    Appearance {
+        dark,
+        accent_color: accent_color().await,
+    }
+}
+
+/// the system's dark mode and accent color
+#[tauri::command]
+pub async fn get_system_appearance(app_handle: tauri::AppHandle) -> Result<Appearance, String> {
+    let appearance = current(&app_handle).await;
+    *LAST_APPEARANCE
+        .lock()
+        .expect("Failed to lock LAST_APPEARANCE") = Some(appearance.clone());
+    Ok(appearance)
+}
+
+/// look at the system again and emit `appearance-changed` if it looks different
+pub fn refresh_appearance(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let appearance = current(&app_handle).await;
+        {
+            let mut last = LAST_APPEARANCE
+                .lock()
+                .expect("Failed to lock LAST_APPEARANCE");
+            if last.as_ref() == Some(&appearance) {
+                return;
+            }
+            *last = Some(appearance.clone());
+        }
+        log::debug!("System appearance changed: {appearance:?}");
+        if let Err(e) = app_handle.emit("appearance-changed", appearance) {
+            log::warn!("Failed to emit appearance-changed: {e}");
+        }
+    });
+}
+
+/// follow the portal's appearance settings, it signals every change
+#[cfg(target_os = "linux")]
+pub fn watch_appearance(app_handle: tauri::AppHandle) {
+    use std::pin::Pin;
+    use zbus::export::futures_core::Stream;
+
+    tauri::async_runtime::spawn(async move {
+        let subscribe = async {
+            let connection = zbus::Connection::session().await?;
+            let rule = zbus::MatchRule::builder()
+                .msg_type(zbus::message::Type::Signal)
+                .interface(PORTAL_SETTINGS)?
+                .member("SettingChanged")?
+                .arg(0, APPEARANCE_NAMESPACE)?
+                .build();
+            zbus::MessageStream::for_match_rule(rule, &connection, None).await
+        };
+        let mut stream = match subscribe.await {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Failed to watch the desktop appearance: {e}");
+                return;
+            }
+        };
+        while std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .is_some()
+        {
+            refresh_appearance(&app_handle);
+        }
+    });
+}
+
+/// macOS and Windows have no signal for it, the window events cover it
+#[cfg(not(target_os = "linux"))]
+pub fn watch_appearance(_app_handle: tauri::AppHandle) {}
@@ -0,0 +1,216 @@
+use chrono::{Duration as ChronoDuration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::time::Duration;
+
+use crate::tasks::{now_iso, Task, TaskChanges, TaskPage, TaskRow, BLOCKED_BY};
+use crate::{collation, db, lock, read_only, settings};
+
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(60);
+const RUN_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const AFTER_DAYS_KEY: &str = "archive_after_days";
+// upper bound for a single page of the archive
+const MAX_PAGE_SIZE: u32 = 1000;
+
+// completed long enough ago, not below an open task and with nothing open below it, so
+// whole finished trees are archived together and no open task loses its parent
+const ARCHIVABLE: &str = "archived_at IS NULL AND completed = 1 AND completed_at < ?2
+        AND NOT EXISTS (
+            SELECT 1 FROM tasks AS parent
+            WHERE parent.uid = tasks.parent_uid AND parent.completed = 0
+        )
+        AND NOT EXISTS (
+            WITH RECURSIVE below(uid) AS (
+                SELECT tasks.uid
+                UNION SELECT child.uid FROM tasks AS child JOIN below ON child.parent_uid = below.uid
+            )
+            SELECT 1 FROM tasks AS task JOIN below ON task.uid = below.uid WHERE task.completed = 0
+        )";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivePolicy {
+    /// archive tasks completed this many days ago; 0 turns archiving off
+    pub after_days: u64,
+}
+
+async fn load_policy(pool: &SqlitePool) -> Result<ArchivePolicy, String> {
+    Ok(ArchivePolicy {
+        after_days: settings::get_u64(pool, AFTER_DAYS_KEY, 0).await?,
+    })
+}
+
+/// archive what the policy allows; archived tasks leave the frontend cache like deleted
+/// ones. returns how many tasks were archived
+#[tracing::instrument(name = "archive", level = "trace", skip_all, err(level = "debug"))]
+pub async fn run(app_handle: &tauri::AppHandle) -> Result<usize, String> {
+    if read_only::is_enabled() {
+        return Ok(0);
+    }
+    let pool = db::pool(app_handle).await?;
+    let policy = load_policy(&pool).await?;
+    if policy.after_days == 0 {
+        return Ok(0);
+    }
+
+    let cutoff = (Utc::now() - ChronoDuration::days(policy.after_days as i64))
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+    let archived: Vec<String> = sqlx::query_scalar(&format!(
+        "UPDATE tasks SET archived_at = ?1 WHERE {ARCHIVABLE} RETURNING id"
+    ))
+    .bind(now_iso())
+    .bind(&cutoff)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let count = archived.len();
+    if count > 0 {
+        log::info!("Archived {count} tasks completed before {cutoff}");
+        TaskChanges {
+            deleted: archived,
+            ..TaskChanges::default()
+        }
+        .emit(app_handle, &pool)
+        .await;
+    }
+    Ok(count)
+}
+
+/// apply the archive policy shortly after startup and then every hour
+pub fn start_scheduled_runs(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_RUN_DELAY).await;
+        loop {
+            if let Err(e) = run(&app_handle).await {
+                log::warn!("Scheduled archiving failed: {e}");
+            }
+            tokio::time::sleep(RUN_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_archive_policy(app_handle: tauri::AppHandle) -> Result<ArchivePolicy, String> {
+    let pool = db::pool(&app_handle).await?;
+    load_policy(&pool).await
+}
+
+/// save the policy and apply it right away; returns how many tasks were archived
+#[tauri::command]
+pub async fn set_archive_policy(
+    app_handle: tauri::AppHandle,
+    policy: ArchivePolicy,
+) -> Result<usize, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    settings::set(&pool, AFTER_DAYS_KEY, &policy.after_days.to_string()).await?;
+    run(&app_handle).await
+}
+
+/// one page of archived tasks, most recently completed first
+#[tauri::command]
+pub async fn query_archived_tasks(
+    app_handle: tauri::AppHandle,
+    search: Option<String>,
+    offset: u32,
+    limit: u32,
+) -> Result<TaskPage, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let push_filter = |query: &mut QueryBuilder<'_, Sqlite>| {
+        query.push(" WHERE archived_at IS NOT NULL");
+        if let Some(search) = search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            let pattern = collation::contains_pattern(search);
+            query
+                .push(" AND (title REGEXP ")
+                .push_bind(pattern.clone())
+                .push(" OR description REGEXP ")
+                .push_bind(pattern)
+                .push(")");
+        }
+    };
+
+    let mut count = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM tasks");
+    push_filter(&mut count);
+    let total: i64 = count
+        .build_query_scalar()
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut query = QueryBuilder::<Sqlite>::new(format!("SELECT *, {BLOCKED_BY} FROM tasks"));
+    push_filter(&mut query);
+    query
+        .push(" ORDER BY completed_at DESC LIMIT ")
+        .push_bind(limit.min(MAX_PAGE_SIZE))
+        .push(" OFFSET ")
+        .push_bind(offset);
+    let rows: Vec<TaskRow> = query
+        .build_query_as()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(TaskPage {
+        tasks: rows.into_iter().map(Task::from).collect(),
+        total,
+        offset,
+    })
+}
+
+/// bring archived tasks back together with their archived sub-tasks; returns how many
+/// tasks were restored
+#[tauri::command]
+pub async fn restore_archived_tasks(
+    app_handle: tauri::AppHandle,
+    ids: Vec<String>,
+) -> Result<usize, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut restored = Vec::new();
+    for id in &ids {
+        let subtree: Vec<String> = sqlx::query_scalar(
+            "WITH RECURSIVE subtree(uid) AS (
+                SELECT uid FROM tasks WHERE id = ?
+                UNION SELECT tasks.uid FROM tasks JOIN subtree ON tasks.parent_uid = subtree.uid
+             )
+             UPDATE tasks SET archived_at = NULL
+             WHERE uid IN subtree AND archived_at IS NOT NULL
+             RETURNING id",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        restored.extend(subtree);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let count = restored.len();
+    TaskChanges {
+        created: restored,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(count)
+}
+
+/// uids of the archived tasks of a calendar, so a sync doesn't download them again
+#[tauri::command]
+pub async fn get_archived_uids(
+    app_handle: tauri::AppHandle,
+    calendar_id: String,
+) -> Result<Vec<String>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_scalar("SELECT uid FROM tasks WHERE calendar_id = ? AND archived_at IS NOT NULL")
+        .bind(&calendar_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,228 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, SqlitePool};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+use crate::caldav::{AccountConfig, CalDavClient};
+use crate::tasks::now_iso;
+use crate::{db, lock, read_only, settings};
+
+// attachments fetched from the server are kept in the cache directory, so opening one again
+// doesn't download it again. the cache has a size limit; when it's full the attachments that
+// were opened longest ago are removed first
+
+const MAX_BYTES_KEY: &str = "attachment_cache_max_bytes";
+const DEFAULT_MAX_BYTES: u64 = 500 * 1024 * 1024;
+const DIR_NAME: &str = "attachments";
+
+/// an attachment in the cache
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedAttachment {
+    /// the cached file, to open or show
+    pub path: String,
+    pub content_type: Option<String>,
+    pub size: u64,
+}
+
+/// how full the attachment cache is
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentCacheUsage {
+    pub used_bytes: u64,
+    pub max_bytes: u64,
+    pub files: u64,
+}
+
+#[derive(FromRow)]
+struct CacheRow {
+    url: String,
+    file_name: String,
+    content_type: Option<String>,
+    size: i64,
+}
+
+fn cache_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?;
+    Ok(dir.join(DIR_NAME))
+}
+
+// urls can be anything, the file is named after their hash
+fn file_name(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+async fn max_bytes(pool: &SqlitePool) -> Result<u64, String> {
+    settings::get_u64(pool, MAX_BYTES_KEY, DEFAULT_MAX_BYTES).await
+}
+
+async fn remove_entry(pool: &SqlitePool, dir: &Path, row: &CacheRow) -> Result<(), String> {
+    if let Err(e) = fs::remove_file(dir.join(&row.file_name)) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            return Err(e.to_string());
+        }
+    }
+    sqlx::query("DELETE FROM attachment_cache WHERE url = ?")
+        .bind(&row.url)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// remove the least recently used attachments until the cache fits in `max_bytes`
+async fn evict(pool: &SqlitePool, dir: &Path, max_bytes: u64) -> Result<(), String> {
+    let rows: Vec<CacheRow> = sqlx::query_as(
+        "SELECT url, file_name, content_type, size FROM attachment_cache
+         ORDER BY last_used_at DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut kept: u64 = 0;
+    let mut evicted = 0;
+    for row in rows {
+        kept += row.size.max(0) as u64;
+        if kept > max_bytes {
+            remove_entry(pool, dir, &row).await?;
+            evicted += 1;
+        }
+    }
+    if evicted > 0 {
+        log::info!("Evicted {evicted} attachments from the cache");
+    }
+    Ok(())
+}
+
+/// the attachment at `url`, downloaded with the account's credentials unless it's cached
+#[tauri::command]
+pub async fn fetch_attachment(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    url: String,
+) -> Result<CachedAttachment, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let dir = cache_dir(&app_handle)?;
+
+    let cached: Option<CacheRow> = sqlx::query_as(
+        "SELECT url, file_name, content_type, size FROM attachment_cache WHERE url = ?",
+    )
+    .bind(&url)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Some(row) = cached {
+        let path = dir.join(&row.file_name);
+        // the system may have cleaned up the cache directory
+        if path.exists() {
+            sqlx::query("UPDATE attachment_cache SET last_used_at = ? WHERE url = ?")
+                .bind(now_iso())
+                .bind(&url)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            return Ok(CachedAttachment {
+                path: path.to_string_lossy().to_string(),
+                content_type: row.content_type,
+                size: row.size.max(0) as u64,
+            });
+        }
+    }
+
+    // an attachment bigger than the whole cache couldn't be kept anyway
+    let max_bytes = max_bytes(&pool).await?;
+    let download = CalDavClient::new(&account)?
+        .download(&url, max_bytes)
+        .await
+        .map_err(|e| format!("Failed to download the attachment: {e}"))?;
+    if !(200..=299).contains(&download.status) {
+        return Err(format!(
+            "Failed to download the attachment (HTTP {})",
+            download.status
+        ));
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let file_name = file_name(&url);
+    let path = dir.join(&file_name);
+    fs::write(&path, &download.bytes).map_err(|e| e.to_string())?;
+    let size = download.bytes.len() as u64;
+    let now = now_iso();
+    sqlx::query(
+        "INSERT INTO attachment_cache (url, file_name, content_type, size, fetched_at,
+                                       last_used_at)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(url) DO UPDATE SET
+            file_name = excluded.file_name, content_type = excluded.content_type,
+            size = excluded.size, fetched_at = excluded.fetched_at,
+            last_used_at = excluded.last_used_at",
+    )
+    .bind(&url)
+    .bind(&file_name)
+    .bind(&download.content_type)
+    .bind(size as i64)
+    .bind(&now)
+    .bind(&now)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    evict(&pool, &dir, max_bytes).await?;
+
+    Ok(CachedAttachment {
+        path: path.to_string_lossy().to_string(),
+        content_type: download.content_type,
+        size,
+    })
+}
+
+/// how much of the attachment cache is used, and its limit
+#[tauri::command]
+pub async fn get_attachment_cache_usage(
+    app_handle: tauri::AppHandle,
+) -> Result<AttachmentCacheUsage, String> {
+    let pool = db::pool(&app_handle).await?;
+    let (used_bytes, files): (i64, i64) =
+        sqlx::query_as("SELECT COALESCE(SUM(size), 0), COUNT(*) FROM attachment_cache")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(AttachmentCacheUsage {
+        used_bytes: used_bytes.max(0) as u64,
+        max_bytes: max_bytes(&pool).await?,
+        files: files.max(0) as u64,
+    })
+}
+
+/// change the size limit of the attachment cache, evicting what no longer fits; None goes
+/// back to the default
+#[tauri::command]
+pub async fn set_attachment_cache_limit(
+    app_handle: tauri::AppHandle,
+    max_bytes: Option<u64>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    match max_bytes {
+        Some(max_bytes) => settings::set(&pool, MAX_BYTES_KEY, &max_bytes.to_string()).await?,
+        None => settings::delete(&pool, MAX_BYTES_KEY).await?,
+    }
+    let limit = self::max_bytes(&pool).await?;
+    evict(&pool, &cache_dir(&app_handle)?, limit).await
+}
+
+/// remove every cached attachment; they're downloaded again when opened
+#[tauri::command]
+pub async fn clear_attachment_cache(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let pool = db::pool(&app_handle).await?;
+    evict(&pool, &cache_dir(&app_handle)?, 0).await
+}
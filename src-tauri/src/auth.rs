@@ -0,0 +1,264 @@
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::{db, read_only};
+
+// how an account authenticates: remembered once negotiated, and negotiated again whenever the
+// server answers 401 and offers a different scheme (Basic turned into OAuth, Digest enabled)
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthMethod {
+    Basic,
+    Digest,
+    Bearer,
+}
+
+impl AuthMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            AuthMethod::Basic => "basic",
+            AuthMethod::Digest => "digest",
+            AuthMethod::Bearer => "bearer",
+        }
+    }
+
+    /// the method stored in the accounts table
+    pub fn parse(name: &str) -> Option<Self> {
+        [AuthMethod::Basic, AuthMethod::Digest, AuthMethod::Bearer]
+            .into_iter()
+            .find(|method| method.name() == name)
+    }
+}
+
+/// the Digest parameters a server sent in its WWW-Authenticate header
+#[derive(Debug, Clone, Default)]
+pub struct DigestChallenge {
+    params: HashMap<String, String>,
+}
+
+impl DigestChallenge {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    /// the nonce merely expired, the credentials themselves were fine
+    pub fn is_stale(&self) -> bool {
+        self.param("stale")
+            .is_some_and(|stale| stale.eq_ignore_ascii_case("true"))
+    }
+}
+
+// the schemes of a WWW-Authenticate value with their parameters. several challenges can share
+// one header, separated by commas like the parameters, so a token without `=` starts the next
+fn challenges(header: &str) -> Vec<(String, HashMap<String, String>)> {
+    let mut challenges: Vec<(String, HashMap<String, String>)> = Vec::new();
+    let mut rest = header.trim();
+    while !rest.is_empty() {
+        rest = rest.trim_start_matches([',', ' ', '\t']);
+        let token_end = rest
+            .find(|c: char| c == '=' || c == ',' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let token = &rest[..token_end];
+        rest = rest[token_end..].trim_start();
+        if token.is_empty() {
+            break;
+        }
+
+        let Some(value_start) = rest.strip_prefix('=') else {
+            challenges.push((token.to_ascii_lowercase(), HashMap::new()));
+            continue;
+        };
+        let value_start = value_start.trim_start();
+        let (value, after) = if let Some(quoted) = value_start.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let mut end = quoted.len();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => {
+                        end = i + 1;
+                        break;
+                    }
+                    c => value.push(c),
+                }
+            }
+            (value, &quoted[end..])
+        } else {
+            let end = value_start.find(',').unwrap_or(value_start.len());
+            (value_start[..end].trim().to_string(), &value_start[end..])
+        };
+        // token68 values like `Basic abc=` have no scheme of their own to attach to
+        if let Some((_, params)) = challenges.last_mut() {
+            params.insert(token.to_ascii_lowercase(), value);
+        }
+        rest = after;
+    }
+    challenges
+}
+
+/// the Digest challenge of a WWW-Authenticate value, if it has one
+pub fn digest_challenge(header: &str) -> Option<DigestChallenge> {
+    challenges(header)
+        .into_iter()
+        .find(|(scheme, _)| scheme == "digest")
+        .map(|(_, params)| DigestChallenge { params })
+}
+
+/// the method to retry with after a 401 that offered `header`, None when retrying can't help:
+/// the current method is the only one offered, or no scheme this client speaks is
+pub fn fallback(header: &str, current: AuthMethod, has_token: bool) -> Option<AuthMethod> {
+    let offered: Vec<String> = challenges(header)
+        .into_iter()
+        .map(|(scheme, _)| scheme)
+        .collect();
+    let offers = |scheme: &str| offered.iter().any(|offered| offered == scheme);
+
+    // a stale nonce only needs the new one
+    if current == AuthMethod::Digest && digest_challenge(header).is_some_and(|c| c.is_stale()) {
+        return Some(AuthMethod::Digest);
+    }
+    [
+        (AuthMethod::Bearer, has_token && offers("bearer")),
+        (AuthMethod::Digest, offers("digest")),
+        (AuthMethod::Basic, offers("basic")),
+    ]
+    .into_iter()
+    .find(|(method, offered)| *offered && *method != current)
+    .map(|(method, _)| method)
+}
+
+fn hash(algorithm: &str, text: &str) -> String {
+    let bytes = if algorithm.starts_with("SHA-256") {
+        Sha256::digest(text.as_bytes()).to_vec()
+    } else {
+        Md5::digest(text.as_bytes()).to_vec()
+    };
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// the Authorization value answering `challenge` for a request (RFC 7616); `nc` counts the
+/// requests made with this nonce, starting at 1
+pub fn digest_authorization(
+    challenge: &DigestChallenge,
+    username: &str,
+    password: &str,
+    method: &str,
+    uri: &str,
+    nc: u32,
+) -> Result<String, String> {
+    let realm = challenge.param("realm").unwrap_or_default();
+    let nonce = challenge
+        .param("nonce")
+        .ok_or("The Digest challenge has no nonce")?;
+    let algorithm = challenge
+        .param("algorithm")
+        .unwrap_or("MD5")
+        .to_ascii_uppercase();
+    if !matches!(
+        algorithm.as_str(),
+        "MD5" | "MD5-SESS" | "SHA-256" | "SHA-256-SESS"
+    ) {
+        return Err(format!("Unsupported Digest algorithm {algorithm}"));
+    }
+    let qop = challenge
+        .param("qop")
+        .filter(|qop| qop.split(',').any(|qop| qop.trim() == "auth"))
+        .map(|_| "auth");
+    let cnonce = uuid::Uuid::new_v4().simple().to_string();
+    let nc = format!("{nc:08x}");
+
+    let mut ha1 = hash(&algorithm, &format!("{username}:{realm}:{password}"));
+    if algorithm.ends_with("-SESS") {
+        ha1 = hash(&algorithm, &format!("{ha1}:{nonce}:{cnonce}"));
+    }
+    let ha2 = hash(&algorithm, &format!("{method}:{uri}"));
+    let response = match qop {
+        Some(qop) => hash(
+            &algorithm,
+            &format!("{ha1}:{nonce}:{nc}:{cnonce}:{qop}:{ha2}"),
+        ),
+        None => hash(&algorithm, &format!("{ha1}:{nonce}:{ha2}")),
+    };
+
+    let quote = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut header = format!(
+        r#"Digest username="{}", realm="{}", nonce="{}", uri="{}", algorithm={algorithm}, response="{response}""#,
+        quote(username),
+        quote(realm),
+        quote(nonce),
+        quote(uri)
+    );
+    if let Some(qop) = qop {
+        header.push_str(&format!(r#", qop={qop}, nc={nc}, cnonce="{cnonce}""#));
+    }
+    if let Some(opaque) = challenge.param("opaque") {
+        header.push_str(&format!(r#", opaque="{}""#, quote(opaque)));
+    }
+    Ok(header)
+}
+
+/// the method to retry a request with after a 401, see `fallback`; for the frontend's own
+/// requests
+#[tauri::command]
+pub fn negotiate_auth(
+    www_authenticate: String,
+    current: AuthMethod,
+    has_bearer_token: bool,
+) -> Option<AuthMethod> {
+    fallback(&www_authenticate, current, has_bearer_token)
+}
+
+/// the Digest Authorization value for a frontend request, see `digest_authorization`
+#[tauri::command]
+pub fn digest_auth_header(
+    www_authenticate: String,
+    username: String,
+    password: String,
+    method: String,
+    uri: String,
+    nc: u32,
+) -> Result<String, String> {
+    let challenge =
+        digest_challenge(&www_authenticate).ok_or("The server sent no Digest challenge")?;
+    digest_authorization(&challenge, &username, &password, &method, &uri, nc)
+}
+
+/// store the method the account authenticates with from now on
+pub async fn remember(
+    pool: &SqlitePool,
+    account_id: &str,
+    method: Option<AuthMethod>,
+) -> Result<(), String> {
+    sqlx::query("UPDATE accounts SET auth_method = ? WHERE id = ?")
+        .bind(method.map(AuthMethod::name))
+        .bind(account_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    log::info!(
+        "Account {account_id} authenticates with {}",
+        method.map_or("the default method", AuthMethod::name)
+    );
+    Ok(())
+}
+
+/// remember the method the server accepted for the account; None to negotiate it again
+#[tauri::command]
+pub async fn set_account_auth_method(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    method: Option<AuthMethod>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    remember(&pool, &account_id, method).await
+}
@@ -0,0 +1,373 @@
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::caldav::{AccountConfig, CalDavClient};
+use crate::tasks::{self, SortConfig, SortMode, Task, TaskFilter, TaskRow, TaskView};
+use crate::tls::TlsSettings;
+use crate::{db, lock};
+
+/// bumped whenever the report layout changes, so old and new reports aren't mixed up
+const REPORT_VERSION: u32 = 1;
+
+const DB_ITERATIONS: usize = 20;
+const PAGE_SIZE: i64 = 100;
+// objects served by the mock server
+const SYNC_OBJECTS: usize = 2_000;
+const SYNC_WRITE_CHUNK: usize = 16 * 1024;
+const SERIALIZATION_TASKS: usize = 5_000;
+const SERIALIZATION_ROUNDS: usize = 5;
+
+/// timings of one repeated measurement, in milliseconds
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub name: String,
+    pub iterations: usize,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncBenchmark {
+    pub objects: usize,
+    pub bytes: usize,
+    pub duration_ms: f64,
+    pub objects_per_sec: f64,
+    pub megabytes_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializationBenchmark {
+    pub tasks: usize,
+    pub bytes: usize,
+    pub serialize: LatencyStats,
+    pub deserialize: LatencyStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub report_version: u32,
+    pub app_version: String,
+    pub os: String,
+    pub os_version: String,
+    pub arch: String,
+    pub started_at: String,
+    /// tasks in the database the queries ran against
+    pub task_count: i64,
+    pub database: Vec<LatencyStats>,
+    pub sync: SyncBenchmark,
+    pub serialization: SerializationBenchmark,
+    pub total_ms: f64,
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn stats(name: &str, mut samples: Vec<Duration>) -> LatencyStats {
+    samples.sort();
+    let at = |fraction: f64| {
+        let index = ((samples.len() - 1) as f64 * fraction).round() as usize;
+        ms(samples[index])
+    };
+    LatencyStats {
+        name: name.to_string(),
+        iterations: samples.len(),
+        min_ms: at(0.0),
+        median_ms: at(0.5),
+        p95_ms: at(0.95),
+        max_ms: at(1.0),
+    }
+}
+
+async fn time<F, Fut>(name: &str, iterations: usize, mut run: F) -> Result<LatencyStats, String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let started = Instant::now();
+        run().await?;
+        samples.push(started.elapsed());
+    }
+    Ok(stats(name, samples))
+}
+
+async fn page(pool: &SqlitePool, filter: &TaskFilter, sort: SortConfig) -> Result<(), String> {
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM tasks");
+    tasks::push_filter(&mut query, filter);
+    query
+        .push(format!(" ORDER BY {} LIMIT ", sort.order_by()))
+        .push_bind(PAGE_SIZE);
+    query
+        .build_query_as::<TaskRow>()
+        .fetch_all(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// the queries the task list runs most, against the user's real data
+async fn database(pool: &SqlitePool) -> Result<Vec<LatencyStats>, String> {
+    let open = TaskFilter {
+        completed: Some(false),
+        ..TaskFilter::default()
+    };
+    let search = TaskFilter {
+        search: Some("re".to_string()),
+        ..TaskFilter::default()
+    };
+    let by_due_date = SortConfig {
+        mode: SortMode::DueDate,
+        ..SortConfig::default()
+    };
+
+    Ok(vec![
+        time("count", DB_ITERATIONS, || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tasks")
+                .fetch_one(pool)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .await?,
+        time("page-manual", DB_ITERATIONS, || {
+            page(pool, &open, SortConfig::default())
+        })
+        .await?,
+        time("page-due-date", DB_ITERATIONS, || {
+            page(pool, &open, by_due_date)
+        })
+        .await?,
+        time("search", DB_ITERATIONS, || {
+            page(pool, &search, SortConfig::default())
+        })
+        .await?,
+        time("visible-tasks", DB_ITERATIONS, || async {
+            let rows: Vec<TaskRow> = sqlx::query_as("SELECT * FROM tasks")
+                .fetch_all(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            let loaded: Vec<Task> = rows.into_iter().map(Task::from).collect();
+            tasks::visible_tasks(&loaded, &TaskView::default(), None);
+            Ok(())
+        })
+        .await?,
+    ])
+}
+
+fn mock_multistatus(objects: usize) -> String {
+    let mut body = String::from(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">"#,
+    );
+    for i in 0..objects {
+        body.push_str(&format!(
+            r#"<d:response><d:href>/calendars/bench/tasks/{i}.ics</d:href><d:propstat><d:prop><d:getetag>"{i}-1"</d:getetag><c:calendar-data>BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//caldav-tasks//benchmark//EN
+BEGIN:VTODO
+UID:bench-{i}
+DTSTAMP:20240101T000000Z
+SUMMARY:Benchmark task {i}
+DESCRIPTION:Some text so the payload has a realistic size.
+PRIORITY:5
+STATUS:NEEDS-ACTION
+END:VTODO
+END:VCALENDAR
+</c:calendar-data></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>"#
+        ));
+    }
+    body.push_str("</d:multistatus>");
+    body
+}
+
+// answers a single REPORT on a local port with a canned multistatus body
+async fn serve_once(listener: TcpListener, body: String) -> Result<(), String> {
+    let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+
+    // read the request headers and body before answering
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut buffer).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+        let text = String::from_utf8_lossy(&request);
+        if let Some(end) = text.find("\r\n\r\n") {
+            let length = text[..end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            if request.len() >= end + 4 + length {
+                break;
+            }
+        }
+    }
+
+    let head = format!(
+        "HTTP/1.1 207 Multi-Status\r\nContent-Type: application/xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    for chunk in body.as_bytes().chunks(SYNC_WRITE_CHUNK) {
+        stream.write_all(chunk).await.map_err(|e| e.to_string())?;
+    }
+    stream.shutdown().await.map_err(|e| e.to_string())
+}
+
+// fetch and parse a calendar from a mock server, the same way a sync does
+async fn sync() -> Result<SyncBenchmark, String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| e.to_string())?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    let body = mock_multistatus(SYNC_OBJECTS);
+    let bytes = body.len();
+    let server = tauri::async_runtime::spawn(serve_once(listener, body));
+
+    let client = CalDavClient::new(&AccountConfig {
+        server_url: format!("http://{address}/"),
+        username: "benchmark".to_string(),
+        password: "benchmark".to_string(),
+        server_type: None,
+        tls: TlsSettings::default(),
+        bearer_token: None,
+        auth_method: None,
+    })?;
+    let url = format!("http://{address}/calendars/bench/tasks/");
+
+    let started = Instant::now();
+    let mut objects = 0;
+    let result = client
+        .report_stream(&url, "1", "<c:calendar-query/>", None, |items| {
+            objects += items
+                .iter()
+                .filter(|item| item.calendar_data.is_some())
+                .count();
+            Ok(())
+        })
+        .await;
+    let elapsed = started.elapsed();
+    server.abort();
+    result?;
+
+    let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+    Ok(SyncBenchmark {
+        objects,
+        bytes,
+        duration_ms: ms(elapsed),
+        objects_per_sec: objects as f64 / seconds,
+        megabytes_per_sec: bytes as f64 / seconds / 1_000_000.0,
+    })
+}
+
+fn synthetic_tasks(count: usize) -> Result<Vec<Task>, String> {
+    (0..count)
+        .map(|i| {
+            serde_json::from_value(json!({
+                "id": format!("bench-{i}"),
+                "uid": format!("bench-{i}@caldav-tasks"),
+                "etag": format!("{i}-1"),
+                "href": format!("/calendars/bench/tasks/{i}.ics"),
+                "title": format!("Benchmark task {i}"),
+                "description": "Some text so the payload has a realistic size.",
+                "tags": ["bench-tag-1", "bench-tag-2"],
+                "priority": "medium",
+                "dueDate": "2024-01-01T09:00:00.000Z",
+                "createdAt": "2024-01-01T00:00:00.000Z",
+                "modifiedAt": "2024-01-01T00:00:00.000Z",
+                "subtasks": [{ "id": format!("bench-{i}-item"), "title": "Checklist item", "completed": false }],
+                "parentUid": (i % 10 != 0).then(|| format!("bench-{}@caldav-tasks", i - i % 10)),
+                "sortOrder": i,
+                "accountId": "bench-account",
+                "calendarId": "bench-calendar",
+                "synced": true,
+            }))
+            .map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+// json round trip of a task list, which is what every command returning tasks pays for
+fn serialization() -> Result<SerializationBenchmark, String> {
+    let tasks = synthetic_tasks(SERIALIZATION_TASKS)?;
+
+    let mut json = String::new();
+    let mut serialize = Vec::with_capacity(SERIALIZATION_ROUNDS);
+    for _ in 0..SERIALIZATION_ROUNDS {
+        let started = Instant::now();
+        json = serde_json::to_string(&tasks).map_err(|e| e.to_string())?;
+        serialize.push(started.elapsed());
+    }
+
+    let mut deserialize = Vec::with_capacity(SERIALIZATION_ROUNDS);
+    for _ in 0..SERIALIZATION_ROUNDS {
+        let started = Instant::now();
+        let parsed: Vec<Task> = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        deserialize.push(started.elapsed());
+        drop(parsed);
+    }
+
+    Ok(SerializationBenchmark {
+        tasks: tasks.len(),
+        bytes: json.len(),
+        serialize: stats("serialize", serialize),
+        deserialize: stats("deserialize", deserialize),
+    })
+}
+
+/// measure database query latency, sync throughput against a local mock server and
+/// task serialization speed; the report is meant to be compared across releases
+#[tauri::command]
+pub async fn run_benchmark(app_handle: tauri::AppHandle) -> Result<BenchmarkReport, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let started_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let started = Instant::now();
+
+    let task_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tasks")
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let database = database(&pool).await?;
+    let sync = sync().await?;
+    let serialization = serialization()?;
+
+    let report = BenchmarkReport {
+        report_version: REPORT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: tauri_plugin_os::platform().to_string(),
+        os_version: tauri_plugin_os::version().to_string(),
+        arch: tauri_plugin_os::arch().to_string(),
+        started_at,
+        task_count,
+        database,
+        sync,
+        serialization,
+        total_ms: ms(started.elapsed()),
+    };
+    log::info!("Benchmark finished in {:.0} ms", report.total_ms);
+    Ok(report)
+}
@@ -0,0 +1,229 @@
+use serde::Deserialize;
+use sqlx::{QueryBuilder, Sqlite, Transaction};
+use std::collections::HashSet;
+use tauri::Emitter;
+
+use crate::tasks::{now_iso, save_task, Task, TaskChanges, TaskRow};
+use crate::{accounts, db, read_only, transfer};
+
+// ids per IN (...) query, well below sqlite's variable limit
+const ID_CHUNK: usize = 500;
+
+/// one change applied to every selected task
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum BulkOp {
+    Complete {
+        #[serde(default = "default_true")]
+        completed: bool,
+    },
+    /// sub-tasks go with their parent
+    Delete,
+    /// see `transfer::move_to`
+    MoveToList {
+        calendar_id: String,
+    },
+    SetDue {
+        due_date: Option<String>,
+        #[serde(default)]
+        all_day: bool,
+    },
+    AddTag {
+        tag_id: String,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// the selected tasks, and with `descendants` every task below them too
+async fn load(
+    tx: &mut Transaction<'_, Sqlite>,
+    ids: &[String],
+    descendants: bool,
+) -> Result<Vec<Task>, String> {
+    let mut tasks = Vec::with_capacity(ids.len());
+    let mut seen = HashSet::new();
+    for chunk in ids.chunks(ID_CHUNK) {
+        let mut query = QueryBuilder::<Sqlite>::new(if descendants {
+            // UNION stops at uids it has already seen, so a parent cycle can't loop forever
+            "WITH RECURSIVE tree(uid) AS (
+                SELECT uid FROM tasks WHERE id IN ("
+        } else {
+            "SELECT * FROM tasks WHERE id IN ("
+        });
+        let mut separated = query.separated(", ");
+        for id in chunk {
+            separated.push_bind(id.clone());
+        }
+        query.push(")");
+        if descendants {
+            query.push(
+                " UNION
+                SELECT tasks.uid FROM tasks JOIN tree ON tasks.parent_uid = tree.uid
+             )
+             SELECT tasks.* FROM tasks JOIN tree ON tasks.uid = tree.uid",
+            );
+        }
+        let rows: Vec<TaskRow> = query
+            .build_query_as()
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        // chunks can share descendants
+        tasks.extend(
+            rows.into_iter()
+                .map(Task::from)
+                .filter(|task| seen.insert(task.id.clone())),
+        );
+    }
+    Ok(tasks)
+}
+
+/// drop the tasks and remember the synced ones so the next sync removes them from the server
+pub async fn delete(tx: &mut Transaction<'_, Sqlite>, tasks: &[Task]) -> Result<(), String> {
+    for task in tasks {
+        if let Some(href) = &task.href {
+            sqlx::query(
+                "INSERT OR REPLACE INTO pending_deletions (uid, href, account_id, calendar_id)
+                 VALUES (?, ?, ?, ?)",
+            )
+            .bind(&task.uid)
+            .bind(href)
+            .bind(&task.account_id)
+            .bind(&task.calendar_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    for chunk in tasks.chunks(ID_CHUNK) {
+        let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM tasks WHERE id IN (");
+        let mut separated = query.separated(", ");
+        for task in chunk {
+            separated.push_bind(task.id.clone());
+        }
+        query.push(")");
+        query
+            .build()
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    sqlx::query(
+        "UPDATE ui_state SET selected_task_id = NULL
+         WHERE selected_task_id IS NOT NULL AND selected_task_id NOT IN (SELECT id FROM tasks)",
+    )
+    .execute(&mut **tx)
+    .await
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+// the task after the change, or None when it already matches
+fn apply(op: &BulkOp, task: &Task) -> Option<Task> {
+    let mut changed = task.clone();
+    match op {
+        BulkOp::Complete { completed } => {
+            if task.completed == *completed {
+                return None;
+            }
+            changed.completed = *completed;
+            changed.completed_at = completed.then(now_iso);
+        }
+        BulkOp::SetDue { due_date, all_day } => {
+            if task.due_date == *due_date && task.due_date_all_day == *all_day {
+                return None;
+            }
+            changed.due_date = due_date.clone();
+            changed.due_date_all_day = *all_day;
+        }
+        BulkOp::AddTag { tag_id } => {
+            if task.tags.contains(tag_id) {
+                return None;
+            }
+            changed.tags.push(tag_id.clone());
+        }
+        BulkOp::Delete | BulkOp::MoveToList { .. } => return None,
+    }
+    changed.modified_at = now_iso();
+    changed.synced = false;
+    Some(changed)
+}
+
+/// apply one operation to many tasks in a single transaction; changed tasks are marked
+/// unsynced so the next sync uploads them together. returns how many tasks changed
+pub async fn update(
+    app_handle: &tauri::AppHandle,
+    ids: &[String],
+    op: &BulkOp,
+) -> Result<usize, String> {
+    let pool = db::pool(app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // deleting and moving change pending deletions too, so the frontend reloads everything
+    let changed = match op {
+        BulkOp::Delete => {
+            let tasks = load(&mut tx, ids, true).await?;
+            delete(&mut tx, &tasks).await?;
+            Some(tasks.len())
+        }
+        BulkOp::MoveToList { calendar_id } => {
+            Some(transfer::move_to(&mut tx, ids, calendar_id).await?.len())
+        }
+        _ => None,
+    };
+    if let Some(count) = changed {
+        tx.commit().await.map_err(|e| e.to_string())?;
+        log::info!("Bulk {:?} changed {} tasks", op, count);
+        if count > 0 {
+            let _ = app_handle.emit("database-changed", ());
+        }
+        return Ok(count);
+    }
+
+    let tasks = load(&mut tx, ids, false).await?;
+    let mut updated = Vec::new();
+    for task in &tasks {
+        if let Some(changed) = apply(op, task) {
+            save_task(&mut tx, &changed).await?;
+            updated.push(changed.id);
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Bulk {:?} changed {} of {} tasks",
+        op,
+        updated.len(),
+        tasks.len()
+    );
+    let count = updated.len();
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(app_handle, &pool)
+    .await;
+    Ok(count)
+}
+
+#[tauri::command]
+pub async fn bulk_update(
+    app_handle: tauri::AppHandle,
+    ids: Vec<String>,
+    op: BulkOp,
+) -> Result<usize, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    accounts::ensure_tasks_editable(&pool, "id", &ids).await?;
+    if let BulkOp::MoveToList { calendar_id } = &op {
+        accounts::ensure_calendar_editable(&pool, calendar_id).await?;
+    }
+    update(&app_handle, &ids, &op).await
+}
@@ -0,0 +1,1127 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri_plugin_http::reqwest::{self, header, redirect, Method, Url};
+
+use crate::auth::{self, AuthMethod, DigestChallenge};
+use crate::db;
+use crate::href;
+use crate::ics_cache::{self, CachedIcs};
+use crate::lock;
+use crate::multistatus::{DavItem, MultistatusStream};
+use crate::payload_limits;
+use crate::redact;
+use crate::tls::TlsSettings;
+use crate::wire_trace::{self, WireTraceEntry};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const XML: &str = "application/xml; charset=utf-8";
+const ICS: &str = "text/calendar; charset=utf-8";
+const JSON: &str = "application/json; charset=utf-8";
+const MAX_REDIRECTS: usize = 5;
+
+const PRINCIPAL_PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:current-user-principal/>
+  </d:prop>
+</d:propfind>"#;
+
+const HOME_SET_PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <c:calendar-home-set/>
+  </d:prop>
+</d:propfind>"#;
+
+const CALENDARS_PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+    <d:displayname/>
+  </d:prop>
+</d:propfind>"#;
+
+const VTODO_REPORT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VTODO"/>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+pub const VTODO_DATA_REPORT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <d:getetag/>
+    <c:calendar-data/>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VTODO"/>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#;
+
+// calendar objects per message to the frontend
+const OBJECT_CHUNK: usize = 250;
+
+// clients for `dav_request`, one per tls configuration so connections are reused
+lazy_static! {
+    static ref CLIENTS: Mutex<HashMap<TlsSettings, reqwest::Client>> = Mutex::new(HashMap::new());
+}
+
+/// account connection details, as entered in the account dialog
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountConfig {
+    pub server_url: String,
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub server_type: Option<String>,
+    #[serde(default)]
+    pub tls: TlsSettings,
+    /// OAuth access token, sent instead of the username and password
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    /// the method the server accepted last time; None to start with the token or Basic
+    #[serde(default)]
+    pub auth_method: Option<AuthMethod>,
+}
+
+impl AccountConfig {
+    /// server type, defaulting to rustical like the frontend does
+    pub fn server_type(&self) -> &str {
+        self.server_type.as_deref().unwrap_or("rustical")
+    }
+
+    /// server url without trailing slash; for generic servers also without a pasted dav path
+    pub fn base_url(&self) -> String {
+        let base = self.server_url.trim().trim_end_matches('/');
+        if self.server_type() != "generic" {
+            return base.to_string();
+        }
+
+        let dav_paths =
+            Regex::new(r"(?i)/(remote\.php/dav|dav\.php|caldav|\.well-known/caldav)(/.*)?$")
+                .expect("invalid dav path regex");
+        match Url::parse(base) {
+            Ok(url) => match dav_paths.find(url.path()) {
+                Some(m) => {
+                    let cut = base.len() - (url.path().len() - m.start());
+                    base[..cut].to_string()
+                }
+                None => base.to_string(),
+            },
+            Err(_) => base.to_string(),
+        }
+    }
+
+    /// principal and calendar home urls for servers with a fixed layout (None for generic)
+    pub fn known_layout(&self) -> Option<(String, String)> {
+        let base = self.base_url();
+        let user = &self.username;
+        match self.server_type() {
+            "rustical" => {
+                let principal = format!("{base}/caldav/principal/{user}/");
+                Some((principal.clone(), principal))
+            }
+            "radicale" => {
+                let principal = format!("{base}/{user}/");
+                Some((principal.clone(), principal))
+            }
+            "baikal" => {
+                let principal = format!("{base}/dav.php/principals/{user}/");
+                Some((principal.clone(), principal))
+            }
+            "nextcloud" => Some((
+                format!("{base}/remote.php/dav/principals/users/{user}/"),
+                format!("{base}/remote.php/dav/calendars/{user}/"),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DavResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl DavResponse {
+    pub fn is_multistatus(&self) -> bool {
+        self.status == 207
+    }
+}
+
+/// a file fetched with `CalDavClient::download`
+#[derive(Debug, Clone)]
+pub struct Download {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+/// http client for CalDAV requests with basic, digest or bearer auth, manual redirects and
+/// wire tracing
+pub struct CalDavClient {
+    http: reqwest::Client,
+    username: String,
+    password: String,
+    bearer_token: Option<String>,
+    auth: Mutex<AuthState>,
+}
+
+struct AuthState {
+    method: AuthMethod,
+    digest: Option<DigestChallenge>,
+    // requests made with the current digest nonce
+    nc: u32,
+}
+
+/// full error message including the underlying causes (dns, tls, io, ...), redacted
+/// because reqwest errors embed the request url
+pub fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let text = cause.to_string();
+        if !message.contains(&text) {
+            message.push_str(": ");
+            message.push_str(&text);
+        }
+        source = cause.source();
+    }
+    redact::redact(&message)
+}
+
+impl CalDavClient {
+    pub fn new(account: &AccountConfig) -> Result<Self, String> {
+        // redirects are followed by hand: reqwest would turn a redirected PROPFIND into a GET
+        let builder = reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("caldav-tasks/", env!("CARGO_PKG_VERSION")));
+        let http = account
+            .tls
+            .apply(builder)?
+            .build()
+            .map_err(|e| error_chain(&e))?;
+
+        Ok(Self {
+            http,
+            username: account.username.clone(),
+            password: account.password.clone(),
+            bearer_token: account.bearer_token.clone(),
+            auth: Mutex::new(AuthState {
+                method: account.auth_method.unwrap_or(match account.bearer_token {
+                    Some(_) => AuthMethod::Bearer,
+                    None => AuthMethod::Basic,
+                }),
+                digest: None,
+                nc: 0,
+            }),
+        })
+    }
+
+    /// the method the requests authenticate with, after any renegotiation
+    pub fn auth_method(&self) -> AuthMethod {
+        self.auth.lock().expect("Failed to lock auth state").method
+    }
+
+    // switch to what the server asks for in a 401; false when retrying can't help
+    fn renegotiate(&self, www_authenticate: &str) -> bool {
+        let mut auth = self.auth.lock().expect("Failed to lock auth state");
+        // a remembered Digest method still needs its first challenge
+        let first_challenge = auth.method == AuthMethod::Digest && auth.digest.is_none();
+        let next = if first_challenge {
+            Some(AuthMethod::Digest)
+        } else {
+            auth::fallback(www_authenticate, auth.method, self.bearer_token.is_some())
+        };
+        let Some(next) = next else {
+            return false;
+        };
+        if next == AuthMethod::Digest {
+            let Some(challenge) = auth::digest_challenge(www_authenticate) else {
+                return false;
+            };
+            auth.digest = Some(challenge);
+            auth.nc = 0;
+        }
+        if next != auth.method {
+            log::info!(
+                "Server asks for {next:?} authentication instead of {:?}",
+                auth.method
+            );
+        }
+        auth.method = next;
+        true
+    }
+
+    // the Authorization value for a request, with the scheme name for the wire trace
+    fn authorization(&self, method: &Method, url: &Url) -> Option<(String, &'static str)> {
+        let mut auth = self.auth.lock().expect("Failed to lock auth state");
+        match (auth.method, &self.bearer_token) {
+            (AuthMethod::Bearer, Some(token)) => Some((format!("Bearer {token}"), "Bearer")),
+            (AuthMethod::Digest, _) => {
+                auth.nc += 1;
+                let nc = auth.nc;
+                let challenge = auth.digest.as_ref()?;
+                let uri = match url.query() {
+                    Some(query) => format!("{}?{query}", url.path()),
+                    None => url.path().to_string(),
+                };
+                match auth::digest_authorization(
+                    challenge,
+                    &self.username,
+                    &self.password,
+                    method.as_str(),
+                    &uri,
+                    nc,
+                ) {
+                    Ok(header) => Some((header, "Digest")),
+                    Err(e) => {
+                        log::warn!("Digest authentication failed: {e}");
+                        None
+                    }
+                }
+            }
+            _ => {
+                let credentials = STANDARD.encode(format!("{}:{}", self.username, self.password));
+                Some((format!("Basic {credentials}"), "Basic"))
+            }
+        }
+    }
+
+    /// send a request, following redirects with the same method and body
+    pub async fn request(
+        &self,
+        method: &str,
+        url: &str,
+        depth: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<DavResponse, String> {
+        self.send(method, url, depth, body.map(|body| (XML, body)), true)
+            .await
+    }
+
+    /// upload an ICS object
+    pub async fn put_ics(&self, url: &str, ics: &str) -> Result<DavResponse, String> {
+        self.send("PUT", url, None, Some((ICS, ics)), true).await
+    }
+
+    /// upload a json document to plain WebDAV storage
+    pub async fn put_json(&self, url: &str, json: &str) -> Result<DavResponse, String> {
+        self.send("PUT", url, None, Some((JSON, json)), true).await
+    }
+
+    /// send a request without credentials (used to probe connectivity)
+    pub async fn request_anonymous(&self, method: &str, url: &str) -> Result<DavResponse, String> {
+        self.send(method, url, None, None, false).await
+    }
+
+    pub async fn propfind(
+        &self,
+        url: &str,
+        depth: &str,
+        body: &str,
+    ) -> Result<DavResponse, String> {
+        self.request("PROPFIND", url, Some(depth), Some(body)).await
+    }
+
+    pub async fn report(&self, url: &str, depth: &str, body: &str) -> Result<DavResponse, String> {
+        self.request("REPORT", url, Some(depth), Some(body)).await
+    }
+
+    /// GET a file as bytes, authenticated and following redirects like the other requests;
+    /// fails once the body grows past `max_bytes`
+    pub async fn download(&self, url: &str, max_bytes: u64) -> Result<Download, String> {
+        let mut url =
+            Url::parse(url).map_err(|e| redact::redact(&format!("Invalid URL {url}: {e}")))?;
+
+        let mut renegotiated = false;
+        for _ in 0..=MAX_REDIRECTS {
+            let (request, _) = self.prepare(Method::GET, url.clone(), None, None, true);
+            let mut response = request.send().await.map_err(|e| error_chain(&e))?;
+            let status = response.status().as_u16();
+            let headers = header_map(response.headers());
+
+            if status == 401 && !renegotiated {
+                if let Some(offered) = headers.get("www-authenticate") {
+                    if self.renegotiate(offered) {
+                        renegotiated = true;
+                        continue;
+                    }
+                }
+            }
+            if matches!(status, 301 | 302 | 307 | 308) {
+                if let Some(location) = headers.get("location") {
+                    url = url.join(location).map_err(|e| e.to_string())?;
+                    continue;
+                }
+            }
+
+            let too_big = || format!("The file is bigger than {max_bytes} bytes");
+            if response
+                .content_length()
+                .is_some_and(|length| length > max_bytes)
+            {
+                return Err(too_big());
+            }
+            let mut bytes = Vec::new();
+            while let Some(chunk) = response.chunk().await.map_err(|e| error_chain(&e))? {
+                if (bytes.len() + chunk.len()) as u64 > max_bytes {
+                    return Err(too_big());
+                }
+                bytes.extend_from_slice(&chunk);
+            }
+            return Ok(Download {
+                status,
+                content_type: headers.get("content-type").cloned(),
+                bytes,
+            });
+        }
+        Err(format!("Too many redirects for {url}"))
+    }
+
+    async fn send(
+        &self,
+        method: &str,
+        url: &str,
+        depth: Option<&str>,
+        // content type and text
+        body: Option<(&str, &str)>,
+        authenticate: bool,
+    ) -> Result<DavResponse, String> {
+        let method = Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+        let mut url =
+            Url::parse(url).map_err(|e| redact::redact(&format!("Invalid URL {url}: {e}")))?;
+
+        let mut renegotiated = false;
+        for _ in 0..=MAX_REDIRECTS {
+            let response = self
+                .send_once(method.clone(), url.clone(), depth, body, authenticate)
+                .await?;
+
+            // the server may have switched schemes since the method was remembered
+            if authenticate && response.status == 401 && !renegotiated {
+                if let Some(offered) = response.headers.get("www-authenticate") {
+                    if self.renegotiate(offered) {
+                        renegotiated = true;
+                        continue;
+                    }
+                }
+            }
+
+            if matches!(response.status, 301 | 302 | 307 | 308) {
+                if let Some(location) = response.headers.get("location") {
+                    url = url.join(location).map_err(|e| e.to_string())?;
+                    log::debug!("Following redirect to {}", url.path());
+                    continue;
+                }
+            }
+            return Ok(response);
+        }
+        Err(format!("Too many redirects for {url}"))
+    }
+
+    // request with credentials, depth and body; also returns the headers for the wire trace
+    fn prepare(
+        &self,
+        method: Method,
+        url: Url,
+        depth: Option<&str>,
+        // content type and text
+        body: Option<(&str, &str)>,
+        authenticate: bool,
+    ) -> (reqwest::RequestBuilder, BTreeMap<String, String>) {
+        let mut request_headers = BTreeMap::new();
+        let authorization = authenticate
+            .then(|| self.authorization(&method, &url))
+            .flatten();
+        let mut request = self.http.request(method, url);
+        if let Some((value, scheme)) = authorization {
+            request = request.header(header::AUTHORIZATION, value);
+            request_headers.insert("authorization".to_string(), scheme.to_string());
+        }
+        if let Some(depth) = depth {
+            request = request.header("Depth", depth);
+            request_headers.insert("depth".to_string(), depth.to_string());
+        }
+        if let Some((content_type, body)) = body {
+            request = request
+                .header(header::CONTENT_TYPE, content_type)
+                .body(body.to_string());
+            request_headers.insert("content-type".to_string(), content_type.to_string());
+        }
+        (request, request_headers)
+    }
+
+    async fn send_once(
+        &self,
+        method: Method,
+        url: Url,
+        depth: Option<&str>,
+        // content type and text
+        body: Option<(&str, &str)>,
+        authenticate: bool,
+    ) -> Result<DavResponse, String> {
+        let (request, request_headers) =
+            self.prepare(method.clone(), url.clone(), depth, body, authenticate);
+
+        let started = Instant::now();
+        let result = async {
+            let response = request.send().await?;
+            let status = response.status().as_u16();
+            let headers = header_map(response.headers());
+            let body = response.text().await?;
+            Ok::<_, reqwest::Error>(DavResponse {
+                status,
+                headers,
+                body,
+            })
+        }
+        .await
+        .map_err(|e| error_chain(&e));
+
+        if wire_trace::is_enabled() {
+            let (status, response_headers, response_body, error) = match &result {
+                Ok(response) => (
+                    Some(response.status),
+                    response.headers.clone(),
+                    Some(response.body.clone()),
+                    None,
+                ),
+                Err(error) => (None, BTreeMap::new(), None, Some(error.clone())),
+            };
+            wire_trace::record(WireTraceEntry {
+                method: method.to_string(),
+                url: url.to_string(),
+                request_headers,
+                request_body: body.map(|(_, body)| body.to_string()),
+                status,
+                response_headers,
+                response_body,
+                duration_ms: Some(started.elapsed().as_millis() as u64),
+                error,
+                ..Default::default()
+            });
+        }
+
+        result
+    }
+
+    /// send a REPORT and hand its multistatus responses to `on_items` while the body is
+    /// still arriving, so large calendars are never held in memory as a whole. responses over
+    /// `max_response` bytes come out as oversized items without their data
+    pub async fn report_stream(
+        &self,
+        url: &str,
+        depth: &str,
+        body: &str,
+        max_response: Option<usize>,
+        mut on_items: impl FnMut(Vec<DavItem>) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut url =
+            Url::parse(url).map_err(|e| redact::redact(&format!("Invalid URL {url}: {e}")))?;
+
+        for _ in 0..=MAX_REDIRECTS {
+            let (request, request_headers) = self.prepare(
+                Method::from_bytes(b"REPORT").map_err(|e| e.to_string())?,
+                url.clone(),
+                Some(depth),
+                Some((XML, body)),
+                true,
+            );
+            let started = Instant::now();
+            let mut response = request.send().await.map_err(|e| error_chain(&e))?;
+            let status = response.status().as_u16();
+            let response_headers = header_map(response.headers());
+
+            if matches!(status, 301 | 302 | 307 | 308) {
+                if let Some(location) = response_headers.get("location") {
+                    url = url.join(location).map_err(|e| e.to_string())?;
+                    log::debug!("Following redirect to {}", url.path());
+                    continue;
+                }
+            }
+
+            let mut received = 0;
+            let result = async {
+                if status != 207 {
+                    return Err(status_message(status, url.as_str()));
+                }
+                let mut parser = match max_response {
+                    Some(max) => MultistatusStream::with_limit(max),
+                    None => MultistatusStream::new(),
+                };
+                while let Some(chunk) = response.chunk().await.map_err(|e| error_chain(&e))? {
+                    received += chunk.len();
+                    on_items(parser.feed(&chunk)?)?;
+                }
+                on_items(parser.finish()?)
+            }
+            .await;
+
+            if wire_trace::is_enabled() {
+                wire_trace::record(WireTraceEntry {
+                    method: "REPORT".to_string(),
+                    url: url.to_string(),
+                    request_headers,
+                    request_body: Some(body.to_string()),
+                    status: Some(status),
+                    response_headers,
+                    response_body: Some(format!("[streamed, {received} bytes]")),
+                    duration_ms: Some(started.elapsed().as_millis() as u64),
+                    error: result.as_ref().err().cloned(),
+                    ..Default::default()
+                });
+            }
+            return result;
+        }
+        Err(format!("Too many redirects for {url}"))
+    }
+}
+
+// repeated headers (like several WWW-Authenticate challenges) are joined with commas
+fn header_map(headers: &header::HeaderMap) -> BTreeMap<String, String> {
+    let mut map: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in headers {
+        let value = value.to_str().unwrap_or_default();
+        map.entry(name.as_str().to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    map
+}
+
+/// resolve an href from a multistatus response against the url it came from, normalized
+pub fn resolve_href(base: &str, href: &str) -> Option<String> {
+    href::resolve(base, href)
+}
+
+/// href inside the given property, e.g. `current-user-principal` (any namespace prefix)
+pub fn property_href(body: &str, property: &str) -> Option<String> {
+    let pattern = format!(
+        r"(?is)<[^:>]*:?{}[^>]*>\s*<[^:>]*:?href[^>]*>([^<]+)</[^:>]*:?href>",
+        regex::escape(property)
+    );
+    Regex::new(&pattern)
+        .ok()?
+        .captures(body)
+        .map(|captures| captures[1].trim().to_string())
+}
+
+/// hrefs of all calendar collections in a depth 1 PROPFIND response
+pub fn calendar_hrefs(body: &str) -> Vec<String> {
+    let response = Regex::new(r"(?is)<[^:>/]*:?response[\s>].*?</[^:>]*:?response>")
+        .expect("invalid response regex");
+    let href = Regex::new(r"(?is)<[^:>/]*:?href[^>]*>([^<]+)<").expect("invalid href regex");
+    let calendar = Regex::new(r"(?is)<[^:>/]*:?resourcetype[^>]*>.*?<[^:>/]*:?calendar[\s/>]")
+        .expect("invalid resourcetype regex");
+
+    response
+        .find_iter(body)
+        .map(|m| m.as_str())
+        .filter(|response| calendar.is_match(response))
+        .filter_map(|response| href.captures(response))
+        .map(|captures| captures[1].trim().to_string())
+        .collect()
+}
+
+/// stage of a connection test, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionStage {
+    Dns,
+    Tls,
+    Auth,
+    Principal,
+    HomeSet,
+    Report,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageResult {
+    pub stage: ConnectionStage,
+    pub success: bool,
+    pub status: Option<u16>,
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    pub stages: Vec<StageResult>,
+    pub failed_stage: Option<ConnectionStage>,
+    pub status: Option<u16>,
+    pub message: Option<String>,
+    pub principal_url: Option<String>,
+    pub calendar_home: Option<String>,
+    /// the method the server accepted, once past the auth stage
+    pub auth_method: Option<AuthMethod>,
+}
+
+impl ConnectionTestResult {
+    fn pass(
+        &mut self,
+        stage: ConnectionStage,
+        started: Instant,
+        status: Option<u16>,
+        message: Option<String>,
+    ) {
+        self.stages.push(StageResult {
+            stage,
+            success: true,
+            status,
+            message,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+
+    fn fail(
+        mut self,
+        stage: ConnectionStage,
+        started: Instant,
+        status: Option<u16>,
+        message: String,
+    ) -> Self {
+        let message = redact::redact(&message);
+        log::warn!("Connection test failed at {stage:?}: {message}");
+        self.stages.push(StageResult {
+            stage,
+            success: false,
+            status,
+            message: Some(message.clone()),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        self.failed_stage = Some(stage);
+        self.status = status;
+        self.message = Some(message);
+        self
+    }
+}
+
+/// user facing explanation for an unexpected status code
+fn status_message(status: u16, url: &str) -> String {
+    match status {
+        401 => "Wrong username or password".to_string(),
+        403 => "Access forbidden, check the account's permissions".to_string(),
+        404 => format!("Nothing found at {url}, check the server URL and server type"),
+        405 | 501 => format!("{url} is not a CalDAV endpoint"),
+        429 => "Rate limit exceeded, wait a moment and try again".to_string(),
+        500.. => format!("Server error (HTTP {status}), try again later"),
+        _ => format!("Unexpected response (HTTP {status})"),
+    }
+}
+
+/// check an account step by step (dns, tls, auth, principal, home-set, first REPORT)
+/// and report which stage failed with the exact status code
+#[tauri::command]
+pub async fn test_connection(account: AccountConfig) -> Result<ConnectionTestResult, String> {
+    let mut result = ConnectionTestResult::default();
+    let base_url = account.base_url();
+
+    // dns
+    let started = Instant::now();
+    let url = match Url::parse(&base_url) {
+        Ok(url) if url.host_str().is_some() => url,
+        _ => {
+            return Ok(result.fail(
+                ConnectionStage::Dns,
+                started,
+                None,
+                format!("Invalid server URL: {base_url}"),
+            ))
+        }
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(443);
+    match tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map(|mut addrs| addrs.next())
+    {
+        Ok(Some(_)) => result.pass(ConnectionStage::Dns, started, None, None),
+        Ok(None) => {
+            return Ok(result.fail(
+                ConnectionStage::Dns,
+                started,
+                None,
+                format!("{host} has no addresses"),
+            ))
+        }
+        Err(e) => {
+            return Ok(result.fail(
+                ConnectionStage::Dns,
+                started,
+                None,
+                format!("Could not resolve {host}: {e}"),
+            ))
+        }
+    }
+
+    // tls: any http response means the connection and handshake worked
+    let started = Instant::now();
+    let client = match CalDavClient::new(&account) {
+        Ok(client) => client,
+        Err(e) => return Ok(result.fail(ConnectionStage::Tls, started, None, e)),
+    };
+    match client.request_anonymous("OPTIONS", &base_url).await {
+        Ok(response) => {
+            let message = (url.scheme() == "http")
+                .then(|| "Plain HTTP, the connection is not encrypted".to_string());
+            result.pass(
+                ConnectionStage::Tls,
+                started,
+                Some(response.status),
+                message,
+            )
+        }
+        Err(e) => {
+            return Ok(result.fail(
+                ConnectionStage::Tls,
+                started,
+                None,
+                format!("Could not connect to {host}: {e}"),
+            ))
+        }
+    }
+
+    // auth: the principal url for known layouts, .well-known for generic servers
+    let started = Instant::now();
+    let layout = account.known_layout();
+    let entry_url = match &layout {
+        Some((principal, _)) => principal.clone(),
+        None => format!("{base_url}/.well-known/caldav"),
+    };
+    let entry = match client.propfind(&entry_url, "0", PRINCIPAL_PROPFIND).await {
+        Ok(response) if matches!(response.status, 401 | 403) => {
+            let status = response.status;
+            return Ok(result.fail(
+                ConnectionStage::Auth,
+                started,
+                Some(status),
+                status_message(status, &entry_url),
+            ));
+        }
+        Ok(response) => {
+            result.pass(ConnectionStage::Auth, started, Some(response.status), None);
+            result.auth_method = Some(client.auth_method());
+            response
+        }
+        Err(e) => return Ok(result.fail(ConnectionStage::Auth, started, None, e)),
+    };
+
+    // principal
+    let started = Instant::now();
+    let principal_url = match &layout {
+        Some((principal, _)) if entry.is_multistatus() => Some(principal.clone()),
+        Some(_) => None,
+        None => match property_href(&entry.body, "current-user-principal") {
+            Some(href) => resolve_href(&entry_url, &href),
+            // some servers only answer on the dav root
+            None => match client.propfind(&base_url, "0", PRINCIPAL_PROPFIND).await {
+                Ok(root) => property_href(&root.body, "current-user-principal")
+                    .and_then(|href| resolve_href(&base_url, &href)),
+                Err(e) => return Ok(result.fail(ConnectionStage::Principal, started, None, e)),
+            },
+        },
+    };
+    let Some(principal_url) = principal_url else {
+        let message = if entry.is_multistatus() {
+            "The server did not report a principal, it may not support auto-discovery".to_string()
+        } else {
+            status_message(entry.status, &entry_url)
+        };
+        return Ok(result.fail(
+            ConnectionStage::Principal,
+            started,
+            Some(entry.status),
+            message,
+        ));
+    };
+    result.pass(
+        ConnectionStage::Principal,
+        started,
+        Some(entry.status),
+        None,
+    );
+    result.principal_url = Some(principal_url.clone());
+
+    // home-set
+    let started = Instant::now();
+    let home = match client
+        .propfind(&principal_url, "0", HOME_SET_PROPFIND)
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return Ok(result.fail(ConnectionStage::HomeSet, started, None, e)),
+    };
+    let calendar_home = property_href(&home.body, "calendar-home-set")
+        .and_then(|href| resolve_href(&principal_url, &href))
+        .or_else(|| layout.map(|(_, home)| home));
+    let Some(calendar_home) = calendar_home else {
+        let message = if home.is_multistatus() {
+            "The server did not report a calendar home, it may not support CalDAV".to_string()
+        } else {
+            status_message(home.status, &principal_url)
+        };
+        return Ok(result.fail(
+            ConnectionStage::HomeSet,
+            started,
+            Some(home.status),
+            message,
+        ));
+    };
+    result.pass(ConnectionStage::HomeSet, started, Some(home.status), None);
+    result.calendar_home = Some(calendar_home.clone());
+
+    // first REPORT on the first calendar in the home
+    let started = Instant::now();
+    let calendars = match client
+        .propfind(&calendar_home, "1", CALENDARS_PROPFIND)
+        .await
+    {
+        Ok(response) if response.is_multistatus() => calendar_hrefs(&response.body),
+        Ok(response) => {
+            let status = response.status;
+            return Ok(result.fail(
+                ConnectionStage::Report,
+                started,
+                Some(status),
+                status_message(status, &calendar_home),
+            ));
+        }
+        Err(e) => return Ok(result.fail(ConnectionStage::Report, started, None, e)),
+    };
+    let Some(calendar_url) = calendars
+        .first()
+        .and_then(|href| resolve_href(&calendar_home, href))
+    else {
+        result.pass(
+            ConnectionStage::Report,
+            started,
+            None,
+            Some("No calendars yet".to_string()),
+        );
+        result.success = true;
+        return Ok(result);
+    };
+    match client.report(&calendar_url, "1", VTODO_REPORT).await {
+        Ok(response) if response.is_multistatus() => result.pass(
+            ConnectionStage::Report,
+            started,
+            Some(response.status),
+            None,
+        ),
+        Ok(response) => {
+            let status = response.status;
+            return Ok(result.fail(
+                ConnectionStage::Report,
+                started,
+                Some(status),
+                status_message(status, &calendar_url),
+            ));
+        }
+        Err(e) => return Ok(result.fail(ConnectionStage::Report, started, None, e)),
+    }
+
+    log::info!("Connection test for {host} succeeded");
+    result.success = true;
+    Ok(result)
+}
+
+/// a VTODO resource as fetched from the server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarObject {
+    pub href: String,
+    pub etag: Option<String>,
+    /// None when the etag matches the cached copy, i.e. the object is unchanged since the
+    /// last fetch; its ICS can be looked up with `get_cached_objects` if needed
+    pub data: Option<String>,
+    /// over the object size limit, so skipped; the local copy stays as it is
+    pub too_large: bool,
+}
+
+/// fetch all VTODOs of a calendar; objects are sent to the frontend in chunks while the
+/// response is still streaming in. the raw ICS is cached by etag so unchanged objects are
+/// sent without data. returns the number of objects
+#[tauri::command]
+#[tracing::instrument(
+    level = "debug",
+    skip_all,
+    fields(calendar = %calendar_id),
+    err(level = "warn")
+)]
+pub async fn fetch_calendar_objects(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    calendar_id: String,
+    calendar_url: String,
+    on_chunk: Channel<Vec<CalendarObject>>,
+) -> Result<usize, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let started = Instant::now();
+    let calendar_url = resolve_href(&calendar_url, &calendar_url).unwrap_or(calendar_url);
+    let client = CalDavClient::new(&account)?;
+    let pool = db::pool(&app_handle).await?;
+    let mut cached = ics_cache::etags(&pool, &calendar_id).await?;
+    let max_object = payload_limits::max_object_size(&pool).await?;
+
+    let mut changed = Vec::new();
+    let mut pending = Vec::with_capacity(OBJECT_CHUNK);
+    let mut total = 0;
+    client
+        .report_stream(
+            &calendar_url,
+            "1",
+            VTODO_DATA_REPORT,
+            Some(max_object),
+            |items| {
+                for item in items {
+                    if item.oversized {
+                        let href = resolve_href(&calendar_url, &item.href).unwrap_or(item.href);
+                        // keeps the cached copy, the object may still be there
+                        cached.remove(&href);
+                        pending.push(CalendarObject {
+                            href,
+                            etag: None,
+                            data: None,
+                            too_large: true,
+                        });
+                        continue;
+                    }
+                    let Some(data) = item.calendar_data else {
+                        continue;
+                    };
+                    let href = resolve_href(&calendar_url, &item.href).unwrap_or(item.href);
+                    let unchanged = match (cached.remove(&href), &item.etag) {
+                        (Some(cached_etag), Some(etag)) => cached_etag == *etag,
+                        _ => false,
+                    };
+                    // objects without an etag can't be validated later, so they aren't cached
+                    if let Some(etag) = item.etag.as_ref().filter(|_| !unchanged) {
+                        changed.push(CachedIcs {
+                            href: href.clone(),
+                            etag: etag.clone(),
+                            ics: data.clone(),
+                        });
+                    }
+                    pending.push(CalendarObject {
+                        href,
+                        etag: item.etag,
+                        data: (!unchanged).then_some(data),
+                        too_large: false,
+                    });
+                    if pending.len() >= OBJECT_CHUNK {
+                        total += pending.len();
+                        on_chunk
+                            .send(std::mem::take(&mut pending))
+                            .map_err(|e| e.to_string())?;
+                    }
+                }
+                Ok(())
+            },
+        )
+        .await?;
+
+    if !pending.is_empty() {
+        total += pending.len();
+        on_chunk.send(pending).map_err(|e| e.to_string())?;
+    }
+
+    // whatever is still in `cached` wasn't in the response anymore
+    let removed: Vec<String> = cached.into_keys().collect();
+    ics_cache::remove(&pool, &removed).await?;
+    ics_cache::store(&pool, &calendar_id, &changed).await?;
+    tracing::debug!(
+        total,
+        changed = changed.len(),
+        removed = removed.len(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Fetched calendar objects"
+    );
+    Ok(total)
+}
+
+// the client for requests with these tls settings, built on first use
+fn client_for(tls: &TlsSettings) -> Result<reqwest::Client, String> {
+    let mut clients = CLIENTS.lock().expect("Failed to lock CLIENTS");
+    if let Some(client) = clients.get(tls) {
+        return Ok(client.clone());
+    }
+    // the frontend follows redirects itself, with the same method and body
+    let builder = reqwest::Client::builder()
+        .redirect(redirect::Policy::none())
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("caldav-tasks/", env!("CARGO_PKG_VERSION")));
+    let client = tls.apply(builder)?.build().map_err(|e| error_chain(&e))?;
+    clients.insert(tls.clone(), client.clone());
+    Ok(client)
+}
+
+/// send one request for the frontend's sync, with the account's tls settings (the http
+/// plugin has no way to set a minimum version or pin a certificate). authentication and
+/// redirects stay with the caller
+#[tauri::command]
+pub async fn dav_request(
+    app_handle: tauri::AppHandle,
+    tls: Option<TlsSettings>,
+    method: String,
+    url: String,
+    headers: HashMap<String, String>,
+    body: Option<String>,
+) -> Result<DavResponse, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let client = client_for(&tls.unwrap_or_default())?;
+    let method = Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+
+    let mut request = client.request(method, &url);
+    for (name, value) in &headers {
+        request = request.header(name, value);
+    }
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+    let response = request.send().await.map_err(|e| error_chain(&e))?;
+
+    let status = response.status().as_u16();
+    let mut headers = BTreeMap::<String, String>::new();
+    for (name, value) in response.headers() {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        headers
+            .entry(name.to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(&value);
+            })
+            .or_insert_with(|| value.into_owned());
+    }
+    let body = response.text().await.map_err(|e| error_chain(&e))?;
+    Ok(DavResponse {
+        status,
+        headers,
+        body,
+    })
+}
@@ -0,0 +1,192 @@
+use lazy_static::lazy_static;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// name of the natural-sort collation registered on backend connections; the frontend's
+/// connection doesn't have it, so it may only be used in queries, never in the schema
+pub const NATURAL: &str = "NATURAL";
+
+/// name of the collation for names that count as the same (tags, lists): case- and
+/// accent-insensitive, so "Café", "cafe" and a decomposed "café" are equal. like NATURAL it
+/// only exists on backend connections
+pub const FOLDED: &str = "FOLDED";
+
+lazy_static! {
+    // the precomposed letters of each base letter, e.g. 'e' -> "éèêëē…", for patterns that
+    // match text whether it was stored composed (NFC) or decomposed (NFD)
+    static ref VARIANTS: HashMap<char, String> = {
+        let mut variants: HashMap<char, String> = HashMap::new();
+        for c in ('\u{c0}'..='\u{2fff}').filter(|c| c.is_alphabetic()) {
+            let mut parts = c.nfd();
+            let (Some(base), rest) = (parts.next(), parts) else {
+                continue;
+            };
+            let mut rest = rest.peekable();
+            if base == c || rest.peek().is_none() || !rest.all(is_combining_mark) {
+                continue;
+            }
+            let mut lower = base.to_lowercase();
+            if let (Some(base), None) = (lower.next(), lower.next()) {
+                variants.entry(base).or_default().push(c);
+            }
+        }
+        variants
+    };
+}
+
+// leading emoji, punctuation and whitespace don't decide where a title sorts
+fn sort_key(text: &str) -> &str {
+    text.trim_start_matches(|c: char| !c.is_alphanumeric())
+}
+
+fn digits(chars: &mut Peekable<impl Iterator<Item = char>>) -> String {
+    let mut run = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        run.push(c);
+    }
+    run
+}
+
+// numbers compare by value; with equal values the one with fewer leading zeros goes first
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let (a_value, b_value) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+    a_value
+        .len()
+        .cmp(&b_value.len())
+        .then_with(|| a_value.cmp(b_value))
+        .then_with(|| a.len().cmp(&b.len()))
+}
+
+// letters are compared without accents, so "émigré" sorts with the other e's
+fn folded(text: &str) -> Peekable<impl Iterator<Item = char> + '_> {
+    text.nfd().filter(|c| !is_combining_mark(*c)).peekable()
+}
+
+fn compare_keys(a: &str, b: &str) -> Ordering {
+    let mut a_chars = folded(a);
+    let mut b_chars = folded(b);
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let ordering = compare_numbers(&digits(&mut a_chars), &digits(&mut b_chars));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// text as names and search terms are compared: without accents and lowercase
+pub fn fold(text: &str) -> String {
+    folded(text).flat_map(char::to_lowercase).collect()
+}
+
+/// order for FOLDED: equal when the folded texts are
+pub fn folded_cmp(a: &str, b: &str) -> Ordering {
+    fold(a).cmp(&fold(b))
+}
+
+/// a REGEXP pattern finding `text` in a value regardless of case, accents and whether either
+/// is composed or decomposed; use `^` and `$` around it for whole-value matches
+pub fn contains_pattern(text: &str) -> String {
+    let mut pattern = String::from("(?i)");
+    for c in fold(text).chars() {
+        match VARIANTS.get(&c) {
+            Some(variants) => {
+                pattern.push('[');
+                pattern.push(c);
+                pattern.push_str(variants);
+                pattern.push(']');
+            }
+            None => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+        // the accents of decomposed text
+        pattern.push_str(r"\p{M}*");
+    }
+    pattern
+}
+
+/// natural order for titles: case- and accent-insensitive, runs of digits compare as numbers
+/// ("Task 2" before "Task 10") and leading symbols or emoji are skipped. titles that only
+/// differ in those respects fall back to byte order, so the order is total
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    compare_keys(sort_key(a), sort_key(b))
+        .then_with(|| compare_keys(a, b))
+        .then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn natural_order() {
+        assert_eq!(natural_cmp("Task 2", "Task 10"), Ordering::Less);
+        assert_eq!(natural_cmp("task 10", "Task 9"), Ordering::Greater);
+        // fewer leading zeros first when the values are equal
+        assert_eq!(natural_cmp("v1", "v01"), Ordering::Less);
+        // longer than any integer type
+        assert_eq!(
+            natural_cmp("item 99999999999999999999", "item 100000000000000000000"),
+            Ordering::Less
+        );
+        assert_eq!(natural_cmp("émigré", "Ezra"), Ordering::Less);
+    }
+
+    #[test]
+    fn leading_symbols_are_skipped() {
+        assert_eq!(natural_cmp("🎉 Zebra", "Apple"), Ordering::Greater);
+        assert_eq!(natural_cmp("✅ Buy milk", "Call mom"), Ordering::Less);
+        assert_eq!(natural_cmp("- 3 eggs", "20 eggs"), Ordering::Less);
+    }
+
+    #[test]
+    fn natural_order_is_total() {
+        assert_eq!(natural_cmp("A", "a"), Ordering::Less);
+        assert_eq!(natural_cmp("a", "A"), Ordering::Greater);
+        assert_eq!(natural_cmp("🎉 party", "party"), Ordering::Greater);
+        assert_eq!(natural_cmp("same", "same"), Ordering::Equal);
+    }
+
+    #[test]
+    fn folding() {
+        assert_eq!(fold("Café"), "cafe");
+        assert_eq!(fold("Cafe\u{301}"), "cafe");
+        assert_eq!(fold("ÅNGSTRÖM"), "angstrom");
+        assert_eq!(folded_cmp("CAFÉ", "cafe\u{301}"), Ordering::Equal);
+        assert_eq!(folded_cmp("cafe", "cafes"), Ordering::Less);
+    }
+
+    #[test]
+    fn patterns_match_either_form() {
+        let pattern = Regex::new(&contains_pattern("café")).unwrap();
+        assert!(pattern.is_match("Le Café"));
+        assert!(pattern.is_match("LE CAFE\u{301}"));
+        assert!(pattern.is_match("cafe au lait"));
+        assert!(!pattern.is_match("caff"));
+
+        let pattern = Regex::new(&contains_pattern("a.b")).unwrap();
+        assert!(pattern.is_match("A.B"));
+        assert!(!pattern.is_match("axb"));
+
+        let whole = Regex::new(&format!("^{}$", contains_pattern("Work"))).unwrap();
+        assert!(whole.is_match("work"));
+        assert!(!whole.is_match("homework"));
+    }
+}
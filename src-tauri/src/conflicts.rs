@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::tasks::{new_id, now_iso};
+use crate::{db, i18n, lock, read_only};
+
+// a task edited here that was changed (or deleted) on the server since the last sync. the
+// edit stays local and unsynced until the user picks a side, instead of failing on every sync
+
+/// how a conflict was settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Resolution {
+    /// the local edit went up, replacing the server's version
+    KeepLocal,
+    /// the server's version replaced the local edit
+    KeepRemote,
+}
+
+impl Resolution {
+    fn name(self) -> &'static str {
+        match self {
+            Resolution::KeepLocal => "keepLocal",
+            Resolution::KeepRemote => "keepRemote",
+        }
+    }
+}
+
+/// the conflict as the sync found it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSyncConflict {
+    pub task_id: String,
+    pub task_uid: String,
+    pub account_id: String,
+    pub calendar_id: String,
+    pub title: String,
+    /// the task as edited here, as the frontend has it
+    pub local: Value,
+    /// the server's version; None when it was deleted there
+    pub remote: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub id: String,
+    pub task_id: String,
+    pub task_uid: String,
+    pub account_id: String,
+    pub calendar_id: String,
+    pub title: String,
+    pub local: Value,
+    pub remote: Option<Value>,
+    pub detected_at: String,
+    pub resolved_at: Option<String>,
+    pub resolution: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct SyncConflictRow {
+    id: String,
+    task_id: String,
+    task_uid: String,
+    account_id: String,
+    calendar_id: String,
+    title: String,
+    local_data: String,
+    remote_data: Option<String>,
+    detected_at: String,
+    resolved_at: Option<String>,
+    resolution: Option<String>,
+}
+
+impl From<SyncConflictRow> for SyncConflict {
+    fn from(row: SyncConflictRow) -> Self {
+        let parse = |json: &str| serde_json::from_str(json).unwrap_or(Value::Null);
+        SyncConflict {
+            local: parse(&row.local_data),
+            remote: row.remote_data.as_deref().map(parse),
+            id: row.id,
+            task_id: row.task_id,
+            task_uid: row.task_uid,
+            account_id: row.account_id,
+            calendar_id: row.calendar_id,
+            title: row.title,
+            detected_at: row.detected_at,
+            resolved_at: row.resolved_at,
+            resolution: row.resolution,
+        }
+    }
+}
+
+fn notify(app_handle: &tauri::AppHandle, conflict: &SyncConflict) {
+    let body = match conflict.remote {
+        Some(_) => i18n::t_with("\"{}\" was also changed on the server", &[&conflict.title]),
+        None => i18n::t_with("\"{}\" was deleted on the server", &[&conflict.title]),
+    };
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(i18n::t("Sync conflict"))
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show conflict notification: {e}");
+    }
+}
+
+/// add a conflict to the review queue and tell the user about it. a task that already
+/// waits for review gets the newer versions without another notification
+#[tauri::command]
+pub async fn record_sync_conflict(
+    app_handle: tauri::AppHandle,
+    conflict: NewSyncConflict,
+) -> Result<SyncConflict, String> {
+    read_only::ensure_writable()?;
+    tracing::info!(task = %conflict.task_id, account = %conflict.account_id, "Sync conflict");
+    let pool = db::pool(&app_handle).await?;
+    let local = serde_json::to_string(&conflict.local).map_err(|e| e.to_string())?;
+    let remote = conflict
+        .remote
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    let open: Option<String> = sqlx::query_scalar(
+        "SELECT id FROM sync_conflicts WHERE task_id = ? AND resolved_at IS NULL",
+    )
+    .bind(&conflict.task_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let id = match &open {
+        Some(id) => {
+            sqlx::query(
+                "UPDATE sync_conflicts SET title = ?, local_data = ?, remote_data = ? WHERE id = ?",
+            )
+            .bind(&conflict.title)
+            .bind(&local)
+            .bind(&remote)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            id.clone()
+        }
+        None => {
+            let id = new_id();
+            sqlx::query(
+                "INSERT INTO sync_conflicts (id, task_id, task_uid, account_id, calendar_id,
+                    title, local_data, remote_data, detected_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(&id)
+            .bind(&conflict.task_id)
+            .bind(&conflict.task_uid)
+            .bind(&conflict.account_id)
+            .bind(&conflict.calendar_id)
+            .bind(&conflict.title)
+            .bind(&local)
+            .bind(&remote)
+            .bind(now_iso())
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            id
+        }
+    };
+
+    let recorded: SyncConflict =
+        sqlx::query_as::<_, SyncConflictRow>("SELECT * FROM sync_conflicts WHERE id = ?")
+            .bind(&id)
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .into();
+    if open.is_none() {
+        log::warn!("Sync conflict on task {}", recorded.task_uid);
+        notify(&app_handle, &recorded);
+    }
+    if let Err(e) = app_handle.emit("sync-conflicts-changed", ()) {
+        log::warn!("Failed to emit sync-conflicts-changed: {e}");
+    }
+    Ok(recorded)
+}
+
+/// the conflicts waiting for review, oldest first; with `include_resolved` also the settled
+/// ones, newest first after them
+#[tauri::command]
+pub async fn get_sync_conflicts(
+    app_handle: tauri::AppHandle,
+    include_resolved: Option<bool>,
+) -> Result<Vec<SyncConflict>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let rows: Vec<SyncConflictRow> = sqlx::query_as(
+        "SELECT * FROM sync_conflicts WHERE resolved_at IS NULL OR ? ORDER BY detected_at",
+    )
+    .bind(include_resolved.unwrap_or(false))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (mut open, mut resolved): (Vec<SyncConflict>, Vec<SyncConflict>) = rows
+        .into_iter()
+        .map(SyncConflict::from)
+        .partition(|conflict| conflict.resolved_at.is_none());
+    resolved.reverse();
+    open.append(&mut resolved);
+    Ok(open)
+}
+
+/// mark the conflict as settled once the frontend applied the side the user picked
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    app_handle: tauri::AppHandle,
+    id: String,
+    resolution: Resolution,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let result = sqlx::query(
+        "UPDATE sync_conflicts SET resolved_at = ?, resolution = ?
+         WHERE id = ? AND resolved_at IS NULL",
+    )
+    .bind(now_iso())
+    .bind(resolution.name())
+    .bind(&id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("No open sync conflict {id}"));
+    }
+    log::info!("Resolved sync conflict {id}: {}", resolution.name());
+    if let Err(e) = app_handle.emit("sync-conflicts-changed", ()) {
+        log::warn!("Failed to emit sync-conflicts-changed: {e}");
+    }
+    Ok(())
+}
@@ -0,0 +1,227 @@
+use lazy_static::lazy_static;
+use quick_xml::escape::escape;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use crate::caldav::{property_href, resolve_href, AccountConfig, CalDavClient};
+use crate::events::{parse_property, unescape, unfold, Property};
+use crate::lock;
+use crate::multistatus::MultistatusStream;
+
+// name and email lookup in the account's CardDAV address books, e.g. for assigning tasks
+
+const ADDRESSBOOK_HOME_PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav">
+  <d:prop>
+    <card:addressbook-home-set/>
+  </d:prop>
+</d:propfind>"#;
+
+const ADDRESSBOOKS_PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+  </d:prop>
+</d:propfind>"#;
+
+const DEFAULT_LIMIT: u32 = 10;
+// shorter queries would match most of the address book
+const MIN_QUERY: usize = 2;
+
+lazy_static! {
+    static ref RESPONSE: Regex = Regex::new(r"(?is)<[^:>/]*:?response[\s>].*?</[^:>]*:?response>")
+        .expect("invalid response regex");
+    static ref HREF: Regex =
+        Regex::new(r"(?is)<[^:>/]*:?href[^>]*>([^<]+)<").expect("invalid href regex");
+    static ref ADDRESSBOOK: Regex =
+        Regex::new(r"(?is)<[^:>/]*:?resourcetype[^>]*>.*?<[^:>/]*:?addressbook[\s/>]")
+            .expect("invalid resourcetype regex");
+    // address book urls per principal; found once, since every keystroke searches
+    static ref ADDRESSBOOKS: Mutex<HashMap<String, Vec<String>>> = Mutex::new(HashMap::new());
+}
+
+/// one email address of a contact
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contact {
+    pub name: String,
+    pub email: String,
+}
+
+// the contacts whose name or one of whose emails contains the query
+fn contacts_report(query: &str, limit: u32) -> String {
+    let query = escape(query);
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<card:addressbook-query xmlns:d="DAV:" xmlns:card="urn:ietf:params:xml:ns:carddav">
+  <d:prop>
+    <card:address-data>
+      <card:prop name="FN"/>
+      <card:prop name="N"/>
+      <card:prop name="EMAIL"/>
+    </card:address-data>
+  </d:prop>
+  <card:filter test="anyof">
+    <card:prop-filter name="FN">
+      <card:text-match collation="i;unicode-casemap" match-type="contains">{query}</card:text-match>
+    </card:prop-filter>
+    <card:prop-filter name="EMAIL">
+      <card:text-match collation="i;unicode-casemap" match-type="contains">{query}</card:text-match>
+    </card:prop-filter>
+  </card:filter>
+  <card:limit>
+    <card:nresults>{limit}</card:nresults>
+  </card:limit>
+</card:addressbook-query>"#
+    )
+}
+
+async fn addressbooks(client: &CalDavClient, principal_url: &str) -> Result<Vec<String>, String> {
+    if let Some(urls) = ADDRESSBOOKS
+        .lock()
+        .expect("Failed to lock ADDRESSBOOKS")
+        .get(principal_url)
+    {
+        return Ok(urls.clone());
+    }
+
+    let home = client
+        .propfind(principal_url, "0", ADDRESSBOOK_HOME_PROPFIND)
+        .await?;
+    let Some(home) = property_href(&home.body, "addressbook-home-set")
+        .and_then(|href| resolve_href(principal_url, &href))
+    else {
+        return Err("The server has no address books for this account".to_string());
+    };
+    let response = client.propfind(&home, "1", ADDRESSBOOKS_PROPFIND).await?;
+    if !response.is_multistatus() {
+        return Err(format!(
+            "Failed to list address books (HTTP {})",
+            response.status
+        ));
+    }
+    let urls: Vec<String> = RESPONSE
+        .find_iter(&response.body)
+        .map(|m| m.as_str())
+        .filter(|response| ADDRESSBOOK.is_match(response))
+        .filter_map(|response| resolve_href(&home, HREF.captures(response)?[1].trim()))
+        .collect();
+
+    ADDRESSBOOKS
+        .lock()
+        .expect("Failed to lock ADDRESSBOOKS")
+        .insert(principal_url.to_string(), urls.clone());
+    Ok(urls)
+}
+
+// the name shown for a vCard: FN, else the parts of N
+fn display_name(fn_value: Option<&str>, n_value: Option<&str>) -> String {
+    if let Some(name) = fn_value
+        .map(unescape)
+        .filter(|name| !name.trim().is_empty())
+    {
+        return name.trim().to_string();
+    }
+    // N is family;given;additional;prefix;suffix
+    let parts: Vec<String> = n_value
+        .unwrap_or_default()
+        .split(';')
+        .map(unescape)
+        .collect();
+    [parts.get(1), parts.first()]
+        .into_iter()
+        .flatten()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// grouped properties like `item1.EMAIL` count too
+fn is(property: &Property, name: &str) -> bool {
+    property.name == name || property.name.ends_with(&format!(".{name}"))
+}
+
+fn parse_vcard(vcard: &str) -> Vec<Contact> {
+    let unfolded = unfold(vcard);
+    let properties: Vec<Property> = unfolded.lines().filter_map(parse_property).collect();
+    let value = |name: &str| {
+        properties
+            .iter()
+            .find(|property| is(property, name))
+            .map(|property| property.value)
+    };
+    let name = display_name(value("FN"), value("N"));
+    properties
+        .iter()
+        .filter(|property| is(property, "EMAIL"))
+        .map(|email| email.value.trim().trim_start_matches("mailto:").to_string())
+        .filter(|email| email.contains('@'))
+        .map(|email| Contact {
+            name: if name.is_empty() {
+                email.clone()
+            } else {
+                name.clone()
+            },
+            email,
+        })
+        .collect()
+}
+
+/// contacts whose name or email contains `query`, from every address book of the account;
+/// names that start with it come first. contacts without an email are left out
+#[tauri::command]
+pub async fn search_contacts(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    principal_url: String,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<Contact>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let query = query.trim();
+    if query.chars().count() < MIN_QUERY {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).max(1);
+
+    let client = CalDavClient::new(&account)?;
+    let report = contacts_report(query, limit);
+    let mut contacts = Vec::new();
+    for url in addressbooks(&client, &principal_url).await? {
+        let response = match client.report(&url, "1", &report).await {
+            Ok(response) if response.is_multistatus() => response,
+            Ok(response) => {
+                log::warn!("Contact search failed (HTTP {})", response.status);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Contact search failed: {e}");
+                continue;
+            }
+        };
+        let mut items = MultistatusStream::new();
+        let mut parsed = items.feed(response.body.as_bytes())?;
+        parsed.extend(items.finish()?);
+        for vcard in parsed.into_iter().filter_map(|item| item.calendar_data) {
+            contacts.extend(parse_vcard(&vcard));
+        }
+    }
+
+    // servers that ignore the filter send the whole address book
+    let query = query.to_lowercase();
+    let mut seen = HashSet::new();
+    contacts.retain(|contact| {
+        (contact.name.to_lowercase().contains(&query)
+            || contact.email.to_lowercase().contains(&query))
+            && seen.insert(contact.email.to_lowercase())
+    });
+    contacts.sort_by_cached_key(|contact| {
+        let name = contact.name.to_lowercase();
+        (!name.starts_with(&query), name)
+    });
+    contacts.truncate(limit as usize);
+    Ok(contacts)
+}
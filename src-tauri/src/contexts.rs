@@ -0,0 +1,225 @@
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::dependencies::BLOCKED;
+use crate::tasks::{now_iso, Task, TaskChanges, TaskRow, BLOCKED_BY, NOT_ARCHIVED_CALENDAR};
+use crate::{db, lock, read_only};
+
+// every context name: the ones in the table and the ones only tasks know about (tasks
+// written by the frontend or fetched from the server)
+const NAMES: &str = "WITH names(name) AS (
+        SELECT name FROM contexts
+        UNION SELECT context.value FROM tasks JOIN json_each(tasks.contexts) AS context
+     )";
+
+/// a GTD context with the number of its open tasks
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Context {
+    pub name: String,
+    pub color: Option<String>,
+    pub open_tasks: i64,
+}
+
+/// the next actions in one context
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NextActions {
+    pub context: String,
+    pub tasks: Vec<Task>,
+}
+
+#[derive(FromRow)]
+struct ContextTaskRow {
+    context: String,
+    #[sqlx(flatten)]
+    task: TaskRow,
+}
+
+/// contexts are written with a leading @
+pub fn normalize(name: &str) -> Result<String, String> {
+    let name = name.trim().trim_start_matches('@').trim();
+    if name.is_empty() {
+        return Err("Context name can't be empty".to_string());
+    }
+    Ok(format!("@{name}"))
+}
+
+/// every context, alphabetically
+#[tauri::command]
+pub async fn get_contexts(app_handle: tauri::AppHandle) -> Result<Vec<Context>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as(&format!(
+        "{NAMES}
+         SELECT names.name AS name, contexts.color AS color,
+            (SELECT COUNT(*) FROM tasks JOIN json_each(tasks.contexts) AS context
+             WHERE context.value = names.name COLLATE NOCASE AND tasks.completed = 0
+                AND {NOT_ARCHIVED_CALENDAR}) AS open_tasks
+         FROM names LEFT JOIN contexts ON contexts.name = names.name
+         ORDER BY names.name COLLATE NOCASE"
+    ))
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// add a context (or change its color); returns the name as stored
+#[tauri::command]
+pub async fn save_context(
+    app_handle: tauri::AppHandle,
+    name: String,
+    color: Option<String>,
+) -> Result<String, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let name = normalize(&name)?;
+    sqlx::query(
+        "INSERT INTO contexts (name, color) VALUES (?, ?)
+         ON CONFLICT(name) DO UPDATE SET color = excluded.color",
+    )
+    .bind(&name)
+    .bind(&color)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(name)
+}
+
+/// rename a context on every task that has it; those tasks are uploaded again
+#[tauri::command]
+pub async fn rename_context(
+    app_handle: tauri::AppHandle,
+    name: String,
+    new_name: String,
+) -> Result<String, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let new_name = normalize(&new_name)?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // merging into a context that already exists keeps its color
+    for statement in [
+        "UPDATE OR IGNORE contexts SET name = ?2 WHERE name = ?1",
+        "DELETE FROM contexts WHERE name = ?1 AND name != ?2",
+    ] {
+        sqlx::query(statement)
+            .bind(&name)
+            .bind(&new_name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let updated: Vec<String> = sqlx::query_scalar(
+        "UPDATE tasks SET
+            contexts = (
+                SELECT json_group_array(DISTINCT
+                    CASE WHEN context.value = ?1 COLLATE NOCASE THEN ?2 ELSE context.value END)
+                FROM json_each(tasks.contexts) AS context
+            ),
+            synced = 0, modified_at = ?3
+         WHERE EXISTS (
+            SELECT 1 FROM json_each(tasks.contexts) AS context
+            WHERE context.value = ?1 COLLATE NOCASE
+         )
+         RETURNING id",
+    )
+    .bind(&name)
+    .bind(&new_name)
+    .bind(now_iso())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(new_name)
+}
+
+/// remove a context from the table and from every task that has it
+#[tauri::command]
+pub async fn delete_context(app_handle: tauri::AppHandle, name: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query("DELETE FROM contexts WHERE name = ?")
+        .bind(&name)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let updated: Vec<String> = sqlx::query_scalar(
+        "UPDATE tasks SET
+            contexts = (
+                SELECT NULLIF(json_group_array(context.value), '[]')
+                FROM json_each(tasks.contexts) AS context
+                WHERE context.value != ?1 COLLATE NOCASE
+            ),
+            synced = 0, modified_at = ?2
+         WHERE EXISTS (
+            SELECT 1 FROM json_each(tasks.contexts) AS context
+            WHERE context.value = ?1 COLLATE NOCASE
+         )
+         RETURNING id",
+    )
+    .bind(&name)
+    .bind(now_iso())
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(())
+}
+
+/// next actions per context: open tasks that nothing blocks and whose start date has come,
+/// soonest due first. a task with several contexts shows up in each of them
+#[tauri::command]
+pub async fn query_next_actions(
+    app_handle: tauri::AppHandle,
+    context: Option<String>,
+) -> Result<Vec<NextActions>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let rows: Vec<ContextTaskRow> = sqlx::query_as(&format!(
+        "SELECT context.value AS context, tasks.*, {BLOCKED_BY}
+         FROM tasks JOIN json_each(tasks.contexts) AS context
+         WHERE tasks.completed = 0 AND tasks.archived_at IS NULL AND NOT {BLOCKED}
+            AND (tasks.start_date IS NULL OR tasks.start_date <= ?1)
+            AND (?2 IS NULL OR context.value = ?2 COLLATE NOCASE)
+         ORDER BY context.value COLLATE NOCASE, tasks.due_date IS NULL, tasks.due_date,
+            tasks.sort_order"
+    ))
+    .bind(now_iso())
+    .bind(&context)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut grouped: Vec<NextActions> = Vec::new();
+    for row in rows {
+        let task = Task::from(row.task);
+        match grouped.last_mut() {
+            Some(group) if group.context.eq_ignore_ascii_case(&row.context) => {
+                group.tasks.push(task)
+            }
+            _ => grouped.push(NextActions {
+                context: row.context,
+                tasks: vec![task],
+            }),
+        }
+    }
+    Ok(grouped)
+}
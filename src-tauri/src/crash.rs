@@ -0,0 +1,151 @@
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+use crate::lock;
+use crate::logging::LOG_FILE_NAME;
+use crate::redact::redact;
+
+// how many lines of the log to include in a crash report
+const RECENT_LOG_LINES: usize = 200;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub file_name: String,
+    pub created_at: u64,
+    pub contents: String,
+}
+
+fn crash_dir(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let log_dir = app_handle.path().app_log_dir().map_err(|e| e.to_string())?;
+    Ok(log_dir.join("crashes"))
+}
+
+/// last lines of the log file, so the report shows what led up to the crash
+fn recent_log_lines(log_dir: &Path) -> String {
+    let Ok(log) = fs::read_to_string(log_dir.join(format!("{LOG_FILE_NAME}.log"))) else {
+        return "(no log file)".to_string();
+    };
+    let lines: Vec<&str> = log.lines().collect();
+    lines[lines.len().saturating_sub(RECENT_LOG_LINES)..].join("\n")
+}
+
+// the app_log_dir of tauri, known before the app is built
+fn log_dir(identifier: &str) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    return dirs::home_dir().map(|dir| dir.join("Library/Logs").join(identifier));
+    #[cfg(not(target_os = "macos"))]
+    return dirs::data_local_dir().map(|dir| dir.join(identifier).join("logs"));
+}
+
+// the crash time of a report, from its file name
+fn created_at(file_name: &str) -> Option<u64> {
+    file_name
+        .strip_prefix("crash-")?
+        .strip_suffix(".txt")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// install a panic hook that writes a crash report to disk before the process dies
+///
+/// release builds abort on panic, so this is the only chance to capture anything. it's
+/// installed before the app is built so panics in plugin setup are caught too
+pub fn install_panic_hook(identifier: &str) {
+    let Some(log_dir) = log_dir(identifier) else {
+        eprintln!("No log directory available, crash reports are disabled");
+        return;
+    };
+    let dir = log_dir.join("crashes");
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let thread = std::thread::current();
+
+        let report = format!(
+            "caldav-tasks {version} crashed\n\
+             os: {os} ({arch})\n\
+             time: {timestamp}\n\
+             thread: {thread}\n\n\
+             {message}\n\n\
+             backtrace:\n{backtrace}\n\n\
+             recent log:\n{log}\n",
+            version = env!("CARGO_PKG_VERSION"),
+            os = std::env::consts::OS,
+            arch = std::env::consts::ARCH,
+            thread = thread.name().unwrap_or("<unnamed>"),
+            // the message can quote a url or a server reply
+            message = redact(&info.to_string()),
+            backtrace = Backtrace::force_capture(),
+            log = recent_log_lines(&log_dir),
+        );
+
+        let written = fs::create_dir_all(&dir).and_then(|_| {
+            // two threads can panic in the same second
+            let name = format!("crash-{timestamp}-{}.txt", uuid::Uuid::new_v4().simple());
+            let mut file = fs::File::create_new(dir.join(name))?;
+            file.write_all(report.as_bytes())
+        });
+        if let Err(e) = written {
+            eprintln!("Failed to write crash report: {e}");
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// get the most recent crash report, if there is one the user hasn't dismissed yet
+#[tauri::command]
+pub async fn get_last_crash_report(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<CrashReport>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let dir = crash_dir(&app_handle)?;
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(None);
+    };
+
+    // file names embed the crash time, so the newest report sorts last
+    let newest = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            Some((created_at(&name)?, name))
+        })
+        .max();
+
+    let Some((created_at, file_name)) = newest else {
+        return Ok(None);
+    };
+    let contents = fs::read_to_string(dir.join(&file_name)).map_err(|e| e.to_string())?;
+
+    Ok(Some(CrashReport {
+        file_name,
+        created_at,
+        contents,
+    }))
+}
+
+/// delete a crash report after it was shared or dismissed
+#[tauri::command]
+pub async fn dismiss_crash_report(
+    app_handle: tauri::AppHandle,
+    file_name: String,
+) -> Result<(), String> {
+    // only plain file names from get_last_crash_report are accepted
+    if file_name.contains(['/', '\\']) || !file_name.starts_with("crash-") {
+        return Err(format!("Invalid crash report: {file_name}"));
+    }
+    fs::remove_file(crash_dir(&app_handle)?.join(file_name)).map_err(|e| e.to_string())
+}
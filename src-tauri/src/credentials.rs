@@ -0,0 +1,217 @@
+use keyring::Entry;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sqlx::{FromRow, SqlitePool};
+use std::sync::Mutex;
+
+use crate::{lock, read_only, redact};
+
+// service name the account passwords are filed under in the os credential store
+const SERVICE: &str = "caldav-tasks";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigratedAccount {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedAccount {
+    pub id: String,
+    pub name: String,
+    pub error: String,
+}
+
+/// outcome of the startup audit for plaintext passwords in the database
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialMigrationReport {
+    pub migrated: Vec<MigratedAccount>,
+    pub failed: Vec<FailedAccount>,
+}
+
+#[derive(FromRow)]
+struct PlaintextAccount {
+    id: String,
+    name: String,
+    password: String,
+}
+
+// global storage for the report of the audit run at startup
+lazy_static! {
+    static ref MIGRATION_REPORT: Mutex<Option<CredentialMigrationReport>> = Mutex::new(None);
+}
+
+// the os credential stores are blocking apis, keep them off the async runtime
+async fn with_entry<T: Send + 'static>(
+    account_id: &str,
+    action: impl FnOnce(Entry) -> keyring::Result<T> + Send + 'static,
+) -> Result<T, String> {
+    let account_id = account_id.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        let entry = Entry::new(SERVICE, &account_id).map_err(|e| e.to_string())?;
+        action(entry).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// read an account password from the credential store (None if there is none)
+pub async fn get_password(account_id: &str) -> Result<Option<String>, String> {
+    with_entry(account_id, |entry| match entry.get_password() {
+        Ok(password) => {
+            redact::add_secret(&password);
+            Ok(Some(password))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e),
+    })
+    .await
+}
+
+/// save an account password in the credential store
+pub async fn set_password(account_id: &str, password: String) -> Result<(), String> {
+    redact::add_secret(&password);
+    with_entry(account_id, move |entry| entry.set_password(&password)).await
+}
+
+/// remove an account password from the credential store (missing entries are fine)
+pub async fn delete_password(account_id: &str) -> Result<(), String> {
+    with_entry(account_id, |entry| {
+        if let Ok(password) = entry.get_password() {
+            redact::remove_secret(&password);
+        }
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e),
+        }
+    })
+    .await
+}
+
+// other per-account secrets live next to the password as `<account id>:<kind>`
+fn secret_name(account_id: &str, kind: &str) -> String {
+    format!("{account_id}:{kind}")
+}
+
+/// read another per-account secret (e.g. an encryption key) from the credential store
+pub async fn get_account_secret(account_id: &str, kind: &str) -> Result<Option<String>, String> {
+    get_password(&secret_name(account_id, kind)).await
+}
+
+/// save another per-account secret in the credential store
+pub async fn set_account_secret(
+    account_id: &str,
+    kind: &str,
+    secret: String,
+) -> Result<(), String> {
+    set_password(&secret_name(account_id, kind), secret).await
+}
+
+/// remove another per-account secret from the credential store
+pub async fn delete_account_secret(account_id: &str, kind: &str) -> Result<(), String> {
+    delete_password(&secret_name(account_id, kind)).await
+}
+
+/// move passwords that older versions kept in the accounts table into the credential
+/// store and blank the column; accounts that can't be migrated keep their password
+pub async fn migrate_plaintext(pool: &SqlitePool) -> Result<CredentialMigrationReport, String> {
+    let accounts = sqlx::query_as::<_, PlaintextAccount>(
+        "SELECT id, name, password FROM accounts WHERE password IS NOT NULL AND password != ''",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut report = CredentialMigrationReport::default();
+    for account in accounts {
+        let stored = match set_password(&account.id, account.password.clone()).await {
+            // only scrub once the store really hands the password back
+            Ok(()) => match get_password(&account.id).await {
+                Ok(Some(saved)) if saved == account.password => Ok(()),
+                Ok(_) => Err("credential store did not keep the password".to_string()),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+
+        match stored {
+            Ok(()) => {
+                sqlx::query("UPDATE accounts SET password = '' WHERE id = ?")
+                    .bind(&account.id)
+                    .execute(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                log::info!(
+                    "Moved password of account {} to the credential store",
+                    account.id
+                );
+                report.migrated.push(MigratedAccount {
+                    id: account.id,
+                    name: account.name,
+                });
+            }
+            Err(error) => {
+                log::warn!(
+                    "Could not migrate password of account {}: {error}",
+                    account.id
+                );
+                report.failed.push(FailedAccount {
+                    id: account.id,
+                    name: account.name,
+                    error,
+                });
+            }
+        }
+    }
+
+    if !report.migrated.is_empty() || !report.failed.is_empty() {
+        log::info!(
+            "Credential audit: {} migrated, {} failed",
+            report.migrated.len(),
+            report.failed.len()
+        );
+    }
+    *MIGRATION_REPORT
+        .lock()
+        .expect("Failed to lock MIGRATION_REPORT") = Some(report.clone());
+    Ok(report)
+}
+
+/// get an account password from the credential store
+#[tauri::command]
+pub async fn get_account_password(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+) -> Result<Option<String>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    get_password(&account_id).await
+}
+
+/// save an account password in the credential store
+#[tauri::command]
+pub async fn set_account_password(account_id: String, password: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    set_password(&account_id, password).await
+}
+
+/// remove an account password from the credential store
+#[tauri::command]
+pub async fn delete_account_password(account_id: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    delete_password(&account_id).await
+}
+
+/// get what the startup credential audit migrated (None until it has run)
+#[tauri::command]
+pub async fn get_credential_migration_report(
+    app_handle: tauri::AppHandle,
+) -> Result<Option<CredentialMigrationReport>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    Ok(MIGRATION_REPORT
+        .lock()
+        .expect("Failed to lock MIGRATION_REPORT")
+        .clone())
+}
@@ -0,0 +1,272 @@
+use serde::Serialize;
+use sqlx::error::BoxDynError;
+use sqlx::migrate::{Migration as SqlxMigration, MigrationSource, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::borrow::Cow;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use tauri::{Emitter, Manager};
+use tokio::sync::OnceCell;
+
+use tauri_plugin_sql::MigrationKind;
+
+use crate::{
+    collation, credentials, db_watch, demo, effects, i18n, lock, migrations, read_only, snapshots,
+};
+
+/// database url as registered with the sql plugin (shared with the frontend)
+pub const DB_URL: &str = "sqlite:caldav-tasks.db";
+
+/// database file name inside the app config dir
+pub const DB_FILE: &str = "caldav-tasks.db";
+
+// backend connection pool, opened (and migrated) once on first use
+static POOL: OnceCell<SqlitePool> = OnceCell::const_new();
+
+// the schema version of a database written by a newer release, set when opening one
+static NEWER_SCHEMA: OnceCell<i64> = OnceCell::const_new();
+
+/// whether this version can work with the database's schema
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaCompatibility {
+    pub database_version: i64,
+    /// the newest schema this version knows
+    pub supported_version: i64,
+    /// false for a database written by a newer release, which is opened read-only and not
+    /// migrated
+    pub compatible: bool,
+}
+
+/// app migrations in the form sqlx expects, converted exactly like the sql plugin does
+/// so that the plugin finds them already applied when the frontend loads the database
+#[derive(Debug)]
+struct MigrationList(Vec<tauri_plugin_sql::Migration>);
+
+impl MigrationSource<'static> for MigrationList {
+    fn resolve(
+        self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<SqlxMigration>, BoxDynError>> + Send + 'static>>
+    {
+        Box::pin(async move {
+            Ok(self
+                .0
+                .into_iter()
+                .filter(|migration| matches!(migration.kind, MigrationKind::Up))
+                .map(|migration| {
+                    SqlxMigration::new(
+                        migration.version,
+                        Cow::Borrowed(migration.description),
+                        migration.kind.into(),
+                        Cow::Borrowed(migration.sql),
+                        false,
+                    )
+                })
+                .collect())
+        })
+    }
+}
+
+/// full path of the database file (same location the sql plugin uses); the temporary one in
+/// demo mode
+pub fn db_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    if let Some(dir) = demo::profile_dir() {
+        return Ok(dir.join(DB_FILE));
+    }
+    let dir = app_handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| e.to_string())?;
+    Ok(dir.join(DB_FILE))
+}
+
+/// open the database and apply pending migrations
+#[tracing::instrument(level = "info", skip_all, err)]
+async fn open(app_handle: &tauri::AppHandle) -> Result<SqlitePool, String> {
+    let path = db_path(app_handle)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    snapshots::apply_staged_rollback(&path)?;
+
+    let options = SqliteConnectOptions::new()
+        .filename(&path)
+        .create_if_missing(true)
+        // deleting a calendar or account relies on the cascades in the schema
+        .foreign_keys(true)
+        .collation(collation::NATURAL, collation::natural_cmp)
+        .collation(collation::FOLDED, collation::folded_cmp)
+        // REGEXP, which search uses for accent-insensitive matching
+        .with_regexp();
+    let pool = SqlitePoolOptions::new()
+        // the watcher for changes by other programs has to know the writes of this one
+        .after_connect(|connection, _| {
+            Box::pin(async move {
+                connection.lock_handle().await?.set_commit_hook(|| {
+                    db_watch::note_write();
+                    true
+                });
+                Ok(())
+            })
+        })
+        .connect_with(options.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let migrations = migrations::get_migrations();
+    let versions: Vec<i64> = migrations
+        .iter()
+        .map(|migration| migration.version)
+        .collect();
+
+    // a newer release (e.g. another package of the app) migrated it past what this version
+    // knows: migrating would fail and writing could corrupt it, so only read it
+    let supported = versions.iter().copied().max().unwrap_or(0);
+    let current = schema_version(&pool).await?;
+    tracing::info!(path = %path.display(), current, supported, "Opened the database");
+    if current > supported {
+        pool.close().await;
+        let _ = NEWER_SCHEMA.set(current);
+        read_only::force(&i18n::t_with(
+            "The database was written by a newer version of the app (schema {}, this version \
+             knows up to {}), update the app to make changes",
+            &[&current, &supported],
+        ));
+        let pool = SqlitePool::connect_with(options.read_only(true))
+            .await
+            .map_err(|e| e.to_string())?;
+        lock::load(&pool).await?;
+        return Ok(pool);
+    }
+
+    if let Err(e) = snapshots::before_migrations(&pool, &path, current, &versions).await {
+        log::error!("{e}");
+    }
+    let migrator = Migrator::new(MigrationList(migrations))
+        .await
+        .map_err(|e| e.to_string())?;
+    let started = std::time::Instant::now();
+    migrator.run(&pool).await.map_err(|e| e.to_string())?;
+    if current < supported {
+        tracing::info!(
+            from = current,
+            to = supported,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "Migrated the database"
+        );
+    }
+    lock::load(&pool).await?;
+
+    Ok(pool)
+}
+
+/// whether the database was written by a newer release (and is only read)
+pub fn has_newer_schema() -> bool {
+    NEWER_SCHEMA.get().is_some()
+}
+
+// the newest applied migration, 0 for a new database
+async fn schema_version(pool: &SqlitePool) -> Result<i64, String> {
+    let has_history: bool = sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master
+                        WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if !has_history {
+        return Ok(0);
+    }
+    sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// get the backend connection pool, opening the database on first use
+pub async fn pool(app_handle: &tauri::AppHandle) -> Result<SqlitePool, String> {
+    POOL.get_or_try_init(|| open(app_handle)).await.cloned()
+}
+
+/// open and migrate the database in the background, then tell the frontend it's ready
+///
+/// plaintext passwords from older versions are moved to the credential store first,
+/// so the frontend never loads them
+pub fn init_in_background(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        match pool(&app_handle).await {
+            // nothing may be written to a newer database
+            Ok(pool) if NEWER_SCHEMA.get().is_some() => {
+                if let Err(e) = i18n::load(&app_handle, &pool).await {
+                    log::warn!("Failed to load the language: {e}");
+                }
+                if let Err(e) = effects::load(&app_handle, &pool).await {
+                    log::warn!("Failed to apply the window effect: {e}");
+                }
+                log::warn!("Database ready, read-only");
+                let _ = app_handle.emit("database-ready", ());
+            }
+            Ok(pool) => {
+                if let Err(e) = i18n::load(&app_handle, &pool).await {
+                    log::warn!("Failed to load the language: {e}");
+                }
+                if let Err(e) = effects::load(&app_handle, &pool).await {
+                    log::warn!("Failed to apply the window effect: {e}");
+                }
+                match credentials::migrate_plaintext(&pool).await {
+                    Ok(report) if !report.migrated.is_empty() || !report.failed.is_empty() => {
+                        let _ = app_handle.emit("credentials-migrated", report);
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::error!("Credential audit failed: {e}"),
+                }
+                log::info!("Database ready");
+                let _ = app_handle.emit("database-ready", ());
+            }
+            Err(e) => {
+                log::error!("Failed to open database: {e}");
+                let _ = app_handle.emit("database-error", e);
+            }
+        }
+    });
+}
+
+/// wait until the database is opened and migrated (frontend calls this before loading it)
+#[tauri::command]
+pub async fn wait_for_database(app_handle: tauri::AppHandle) -> Result<(), String> {
+    pool(&app_handle).await.map(|_| ())
+}
+
+/// the url the frontend loads the database with; DB_URL unless in demo mode
+#[tauri::command]
+pub fn get_database_url(app_handle: tauri::AppHandle) -> Result<String, String> {
+    if demo::profile_dir().is_none() {
+        return Ok(DB_URL.to_string());
+    }
+    // the sql plugin takes an absolute path as it is
+    Ok(format!("sqlite:{}", db_path(&app_handle)?.display()))
+}
+
+/// the database's schema version against the one this version knows (frontend calls this
+/// before loading the database, to load a newer one read-only)
+#[tauri::command]
+pub async fn get_schema_compatibility(
+    app_handle: tauri::AppHandle,
+) -> Result<SchemaCompatibility, String> {
+    let pool = pool(&app_handle).await?;
+    let supported_version = migrations::get_migrations()
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0);
+    let database_version = match NEWER_SCHEMA.get() {
+        Some(version) => *version,
+        None => schema_version(&pool).await?,
+    };
+    Ok(SchemaCompatibility {
+        database_version,
+        supported_version,
+        compatible: database_version <= supported_version,
+    })
+}
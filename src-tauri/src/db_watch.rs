@@ -0,0 +1,83 @@
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::Connection;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::db;
+
+// notices when another process (a second instance, a script, a restore with the sqlite tool)
+// changed the database, and has the frontend reload instead of showing what it cached. a
+// connection of its own reads sqlite's data_version, which changes with every commit of any
+// other connection, so the writes of this app are counted and left out: the backend pool's
+// through a commit hook, the frontend's as it reports them
+
+const TICK: Duration = Duration::from_secs(2);
+// the frontend reports its writes right after they're committed, give the report time to
+// arrive before calling a change foreign
+const GRACE: Duration = Duration::from_millis(500);
+
+// writes of this app so far
+static LOCAL_WRITES: AtomicU64 = AtomicU64::new(0);
+
+/// a connection of this app committed a write
+pub fn note_write() {
+    LOCAL_WRITES.fetch_add(1, Ordering::SeqCst);
+}
+
+/// the frontend wrote to the database (it has its own connection)
+#[tauri::command]
+pub fn note_local_write() {
+    note_write();
+}
+
+async fn data_version(connection: &mut SqliteConnection) -> Result<i64, String> {
+    sqlx::query_scalar("PRAGMA data_version")
+        .fetch_one(connection)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn watch(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    // migrations have run once the pool is there
+    db::pool(app_handle).await?;
+    let options = SqliteConnectOptions::new()
+        .filename(db::db_path(app_handle)?)
+        .read_only(true);
+    let mut connection = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut version = data_version(&mut connection).await?;
+    let mut writes = LOCAL_WRITES.load(Ordering::SeqCst);
+    let mut tick = tokio::time::interval(TICK);
+    loop {
+        tick.tick().await;
+        let current = data_version(&mut connection).await?;
+        if current == version {
+            writes = LOCAL_WRITES.load(Ordering::SeqCst);
+            continue;
+        }
+        tokio::time::sleep(GRACE).await;
+        // a change from elsewhere at the same time as one from here goes unnoticed until the
+        // next one
+        let local = LOCAL_WRITES.load(Ordering::SeqCst) != writes;
+        version = data_version(&mut connection).await?;
+        writes = LOCAL_WRITES.load(Ordering::SeqCst);
+        if !local {
+            log::info!("The database was changed by another program, reloading");
+            if let Err(e) = app_handle.emit("database-changed", ()) {
+                log::warn!("Failed to emit database-changed: {e}");
+            }
+        }
+    }
+}
+
+/// watch the database for changes made by other processes
+pub fn start_watching(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = watch(&app_handle).await {
+            log::warn!("Stopped watching the database for outside changes: {e}");
+        }
+    });
+}
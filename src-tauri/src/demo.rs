@@ -0,0 +1,395 @@
+use chrono::{DateTime, Duration, SecondsFormat, TimeZone, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{Sqlite, Transaction};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::Emitter;
+
+use crate::{db, i18n, read_only};
+
+// demo data lives in a throwaway profile: started with --demo, the app opens a fresh database
+// in the temp dir instead of the user's, and removes it on exit
+
+/// command line flag that starts the app on a temporary demo profile
+pub const DEMO_FLAG: &str = "--demo";
+
+/// id of the local account that owns all demo data
+pub const DEMO_ACCOUNT_ID: &str = "demo-account";
+/// server type of the demo account; sync skips it
+pub const DEMO_SERVER_TYPE: &str = "demo";
+
+// ids of demo rows start with this so teardown never touches real data
+const DEMO_PREFIX: &str = "demo-";
+const MAX_TASKS: u32 = 50_000;
+
+// the profile directory in demo mode
+static PROFILE: OnceLock<PathBuf> = OnceLock::new();
+
+const LISTS: &[(&str, &str, &str)] = &[
+    ("Work", "#3b82f6", "briefcase"),
+    ("Home", "#22c55e", "house"),
+    ("Errands", "#f97316", "shopping-cart"),
+    ("Side Project", "#a855f7", "rocket"),
+    ("Reading List", "#eab308", "book-open"),
+];
+
+const TAGS: &[(&str, &str)] = &[
+    ("urgent", "#ef4444"),
+    ("waiting", "#f59e0b"),
+    ("someday", "#64748b"),
+    ("phone", "#06b6d4"),
+    ("computer", "#6366f1"),
+    ("outside", "#10b981"),
+];
+
+const VERBS: &[&str] = &[
+    "Review", "Write", "Call", "Plan", "Fix", "Order", "Clean", "Email", "Prepare", "Schedule",
+    "Update", "Research", "Book", "Return", "Pay", "Organize", "Draft", "Finish",
+];
+
+const OBJECTS: &[&str] = &[
+    "quarterly report",
+    "dentist appointment",
+    "garage",
+    "team offsite agenda",
+    "insurance paperwork",
+    "release notes",
+    "birthday present",
+    "bike repair",
+    "onboarding docs",
+    "travel itinerary",
+    "budget spreadsheet",
+    "kitchen shelves",
+    "conference talk",
+    "library books",
+    "car registration",
+    "landing page copy",
+    "backup drive",
+    "tax documents",
+];
+
+const DESCRIPTIONS: &[&str] = &[
+    "Check with the others before sending anything out.",
+    "Notes from last week are in the shared folder.",
+    "Needs about an hour of focused time.",
+    "Ask for a quote first, then decide.",
+    "Low effort, just don't forget it.",
+];
+
+const CHECKLIST: &[&str] = &[
+    "Collect inputs",
+    "First draft",
+    "Get feedback",
+    "Final pass",
+    "Send out",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DemoDataSummary {
+    pub account_id: String,
+    pub calendars: usize,
+    pub tags: usize,
+    pub tasks: u32,
+}
+
+/// small deterministic generator so the same seed always produces the same data set
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        // xorshift64*
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+/// start in demo mode when the app was started with `--demo`; must run before the database
+/// is opened
+pub fn init_from_args() {
+    if !std::env::args().any(|arg| arg == DEMO_FLAG) {
+        return;
+    }
+    // always the same directory, so a profile a crash left behind goes with the next demo
+    let dir = std::env::temp_dir().join("caldav-tasks-demo");
+    if let Err(e) = fs::remove_dir_all(&dir) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            log::warn!("Failed to remove the old demo profile: {e}");
+        }
+    }
+    let _ = PROFILE.set(dir);
+    log::info!("Started in demo mode, with a temporary database");
+}
+
+/// the temporary profile directory, in demo mode
+pub fn profile_dir() -> Option<&'static Path> {
+    PROFILE.get().map(PathBuf::as_path)
+}
+
+/// remove the demo profile when the app exits
+pub fn discard_profile() {
+    if let Some(dir) = profile_dir() {
+        // the database can still be open on windows; the next demo removes it then
+        if let Err(e) = fs::remove_dir_all(dir) {
+            log::warn!("Failed to remove the demo profile: {e}");
+        }
+    }
+}
+
+/// whether the app runs on the temporary demo profile
+#[tauri::command]
+pub fn is_demo_mode() -> bool {
+    profile_dir().is_some()
+}
+
+/// start another instance of the app in demo mode, next to this one
+#[tauri::command]
+pub fn open_demo_instance() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    std::process::Command::new(exe)
+        .arg(DEMO_FLAG)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn iso(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+// sort order is seconds since the apple epoch, like tasks created in the app
+fn apple_epoch_seconds(date: DateTime<Utc>) -> i64 {
+    let apple_epoch = Utc.with_ymd_and_hms(2001, 1, 1, 0, 0, 0).unwrap();
+    (date - apple_epoch).num_seconds()
+}
+
+async fn delete_demo_rows(tx: &mut Transaction<'_, Sqlite>) -> Result<(), String> {
+    let pattern = format!("{DEMO_PREFIX}%");
+    for query in [
+        "DELETE FROM tasks WHERE account_id = ?1 OR id LIKE ?2",
+        "DELETE FROM calendars WHERE account_id = ?1",
+        "DELETE FROM accounts WHERE id = ?1",
+        "DELETE FROM tags WHERE id LIKE ?2",
+        "UPDATE ui_state SET active_account_id = NULL, active_calendar_id = NULL,
+                active_tag_id = NULL, selected_task_id = NULL
+         WHERE active_account_id = ?1 OR active_calendar_id LIKE ?2
+            OR active_tag_id LIKE ?2 OR selected_task_id LIKE ?2",
+    ] {
+        sqlx::query(query)
+            .bind(DEMO_ACCOUNT_ID)
+            .bind(&pattern)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// fill the demo profile with a demo account holding realistic lists, tags, sub-task
+/// hierarchies and dates; any previous demo data is replaced. only in demo mode, the user's
+/// own database is never touched
+#[tauri::command]
+pub async fn generate_demo_data(
+    app_handle: tauri::AppHandle,
+    n_tasks: u32,
+    seed: Option<u64>,
+) -> Result<DemoDataSummary, String> {
+    read_only::ensure_writable()?;
+    if profile_dir().is_none() {
+        return Err(i18n::t("Demo data can only be generated in demo mode").to_string());
+    }
+    if n_tasks > MAX_TASKS {
+        return Err(format!("At most {MAX_TASKS} demo tasks can be generated"));
+    }
+
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    delete_demo_rows(&mut tx).await?;
+
+    // xorshift must not start at zero
+    let mut rng = Rng(seed.unwrap_or(0x5eed) | 1);
+    let now = Utc::now();
+
+    sqlx::query(
+        "INSERT INTO accounts (id, name, server_url, username, password, server_type, is_active)
+         VALUES (?, 'Demo', '', 'demo', '', ?, 1)",
+    )
+    .bind(DEMO_ACCOUNT_ID)
+    .bind(DEMO_SERVER_TYPE)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut calendar_ids = Vec::new();
+    for (i, (name, color, icon)) in LISTS.iter().enumerate() {
+        let id = format!("{DEMO_PREFIX}calendar-{i}");
+        sqlx::query(
+            "INSERT INTO calendars (id, account_id, display_name, url, color, icon, supported_components)
+             VALUES (?, ?, ?, '', ?, ?, '[\"VTODO\"]')",
+        )
+        .bind(&id)
+        .bind(DEMO_ACCOUNT_ID)
+        .bind(name)
+        .bind(color)
+        .bind(icon)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        calendar_ids.push(id);
+    }
+
+    let mut tag_ids = Vec::new();
+    for (i, (name, color)) in TAGS.iter().enumerate() {
+        let id = format!("{DEMO_PREFIX}tag-{i}");
+        sqlx::query("INSERT INTO tags (id, name, color) VALUES (?, ?, ?)")
+            .bind(&id)
+            .bind(name)
+            .bind(color)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        tag_ids.push(id);
+    }
+
+    // (uid, calendar index, depth) of earlier tasks, to hang sub-tasks under
+    let mut parents: Vec<(String, usize, u8)> = Vec::new();
+    for i in 0..n_tasks {
+        let id = format!("{DEMO_PREFIX}task-{i}");
+        let uid = format!("{DEMO_PREFIX}{i}@caldav-tasks");
+
+        let parent = if !parents.is_empty() && rng.chance(20) {
+            let candidate = &parents[parents.len() - 1 - rng.below(parents.len().min(20))];
+            (candidate.2 < 3).then(|| candidate.clone())
+        } else {
+            None
+        };
+        let calendar = parent
+            .as_ref()
+            .map(|(_, calendar, _)| *calendar)
+            .unwrap_or_else(|| rng.below(calendar_ids.len()));
+        let depth = parent.as_ref().map(|(_, _, depth)| depth + 1).unwrap_or(0);
+
+        let title = format!("{} {}", rng.pick(VERBS), rng.pick(OBJECTS));
+        let description = if rng.chance(30) {
+            rng.pick(DESCRIPTIONS)
+        } else {
+            ""
+        };
+        let priority = match rng.below(10) {
+            0 => "high",
+            1 | 2 => "medium",
+            3 => "low",
+            _ => "none",
+        };
+
+        let created = now - Duration::minutes(rng.below(60 * 24 * 90) as i64);
+        let modified = created + Duration::minutes(rng.below(60 * 24 * 7) as i64);
+        let completed = rng.chance(30);
+        let completed_at = completed.then(|| iso(modified.min(now)));
+
+        let due = rng
+            .chance(45)
+            .then(|| now + Duration::days(rng.below(45) as i64 - 14));
+        let due_all_day = due.is_some() && rng.chance(60);
+        let start = due
+            .filter(|_| rng.chance(30))
+            .map(|due| due - Duration::days(rng.below(5) as i64 + 1));
+        let reminders = due
+            .filter(|_| !completed && rng.chance(25))
+            .map(|due| json!([{ "id": format!("{id}-reminder"), "trigger": iso(due - Duration::hours(1)) }]).to_string());
+
+        let tags: Vec<&String> = tag_ids.iter().filter(|_| rng.chance(15)).collect();
+        let subtasks = if rng.chance(10) {
+            let count = 2 + rng.below(CHECKLIST.len() - 1);
+            json!(CHECKLIST[..count]
+                .iter()
+                .enumerate()
+                .map(|(n, title)| json!({
+                    "id": format!("{id}-item-{n}"),
+                    "title": title,
+                    "completed": completed || rng.chance(40),
+                }))
+                .collect::<Vec<_>>())
+            .to_string()
+        } else {
+            "[]".to_string()
+        };
+
+        sqlx::query(
+            "INSERT INTO tasks (
+                id, uid, title, description, completed, completed_at, tags, priority,
+                start_date, start_date_all_day, due_date, due_date_all_day,
+                created_at, modified_at, reminders, subtasks, parent_uid, is_collapsed,
+                sort_order, account_id, calendar_id, synced, local_only
+             ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, ?, ?, ?, 1, 0)",
+        )
+        .bind(&id)
+        .bind(&uid)
+        .bind(&title)
+        .bind(description)
+        .bind(completed)
+        .bind(completed_at)
+        .bind(json!(tags).to_string())
+        .bind(priority)
+        .bind(start.map(iso))
+        .bind(start.map(|_| due_all_day))
+        .bind(due.map(iso))
+        .bind(due.map(|_| due_all_day))
+        .bind(iso(created))
+        .bind(iso(modified))
+        .bind(reminders)
+        .bind(subtasks)
+        .bind(parent.as_ref().map(|(uid, _, _)| uid.clone()))
+        .bind(apple_epoch_seconds(created) + i as i64)
+        .bind(DEMO_ACCOUNT_ID)
+        .bind(&calendar_ids[calendar])
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        parents.push((uid, calendar, depth));
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    log::info!("Generated {n_tasks} demo tasks");
+    let _ = app_handle.emit("database-changed", ());
+
+    Ok(DemoDataSummary {
+        account_id: DEMO_ACCOUNT_ID.to_string(),
+        calendars: calendar_ids.len(),
+        tags: tag_ids.len(),
+        tasks: n_tasks,
+    })
+}
+
+/// remove all demo data again, leaving real accounts untouched; also cleans up demo data an
+/// older version wrote to the user's database
+#[tauri::command]
+pub async fn clear_demo_data(app_handle: tauri::AppHandle) -> Result<(), String> {
+    read_only::ensure_writable()?;
+
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    delete_demo_rows(&mut tx).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!("Removed demo data");
+    let _ = app_handle.emit("database-changed", ());
+    Ok(())
+}
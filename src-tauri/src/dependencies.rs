@@ -0,0 +1,265 @@
+use serde::Serialize;
+use sqlx::{Sqlite, Transaction};
+use tauri::Emitter;
+
+use crate::tasks::{now_iso, Task, TaskChanges, TaskRow, BLOCKED_BY};
+use crate::{db, lock, read_only};
+
+/// the task has a blocker that exists locally and isn't completed yet; blockers that aren't
+/// known here (an unsynced calendar, a deleted task) don't hold anything up
+pub const BLOCKED: &str = "EXISTS (
+        SELECT 1 FROM task_dependencies
+        JOIN tasks AS blocker ON blocker.uid = task_dependencies.depends_on_uid
+        WHERE task_dependencies.task_uid = tasks.uid AND blocker.completed = 0)";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDependencies {
+    /// tasks that have to be completed first
+    pub blocked_by: Vec<Task>,
+    /// tasks waiting for this one
+    pub blocks: Vec<Task>,
+}
+
+/// replace what a task depends on (used when saving tasks that came from the server)
+pub async fn replace(
+    tx: &mut Transaction<'_, Sqlite>,
+    uid: &str,
+    depends_on: &[String],
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM task_dependencies WHERE task_uid = ?")
+        .bind(uid)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for blocker in depends_on.iter().filter(|blocker| *blocker != uid) {
+        sqlx::query(
+            "INSERT OR IGNORE INTO task_dependencies (task_uid, depends_on_uid) VALUES (?, ?)",
+        )
+        .bind(uid)
+        .bind(blocker)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// mark a task changed so its RELATED-TO list is uploaded on the next sync
+async fn touch(tx: &mut Transaction<'_, Sqlite>, uid: &str) -> Result<Vec<String>, String> {
+    sqlx::query_scalar("UPDATE tasks SET synced = 0, modified_at = ? WHERE uid = ? RETURNING id")
+        .bind(now_iso())
+        .bind(uid)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// the tasks a task waits for and the tasks waiting for it
+#[tauri::command]
+pub async fn get_dependencies(
+    app_handle: tauri::AppHandle,
+    uid: String,
+) -> Result<TaskDependencies, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let related = |join: &str| {
+        format!(
+            "SELECT tasks.*, {BLOCKED_BY} FROM tasks
+             JOIN task_dependencies AS dependency ON {join}
+             ORDER BY tasks.sort_order"
+        )
+    };
+    let blocked_by: Vec<TaskRow> = sqlx::query_as(&related(
+        "tasks.uid = dependency.depends_on_uid AND dependency.task_uid = ?",
+    ))
+    .bind(&uid)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let blocks: Vec<TaskRow> = sqlx::query_as(&related(
+        "tasks.uid = dependency.task_uid AND dependency.depends_on_uid = ?",
+    ))
+    .bind(&uid)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(TaskDependencies {
+        blocked_by: blocked_by.into_iter().map(Task::from).collect(),
+        blocks: blocks.into_iter().map(Task::from).collect(),
+    })
+}
+
+/// make `task_uid` wait for `depends_on_uid`; refused when it would close a cycle
+#[tauri::command]
+pub async fn add_dependency(
+    app_handle: tauri::AppHandle,
+    task_uid: String,
+    depends_on_uid: String,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    if task_uid == depends_on_uid {
+        return Err("A task can't depend on itself".to_string());
+    }
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // everything the new blocker already waits for, directly or not
+    let cycle: bool = sqlx::query_scalar(
+        "WITH RECURSIVE upstream(uid) AS (
+            SELECT ?1
+            UNION SELECT task_dependencies.depends_on_uid FROM task_dependencies
+            JOIN upstream ON task_dependencies.task_uid = upstream.uid
+         )
+         SELECT EXISTS (SELECT 1 FROM upstream WHERE uid = ?2)",
+    )
+    .bind(&depends_on_uid)
+    .bind(&task_uid)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if cycle {
+        return Err("This would make the tasks wait for each other".to_string());
+    }
+
+    sqlx::query("INSERT OR IGNORE INTO task_dependencies (task_uid, depends_on_uid) VALUES (?, ?)")
+        .bind(&task_uid)
+        .bind(&depends_on_uid)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let updated = touch(&mut tx, &task_uid).await?;
+    if updated.is_empty() {
+        return Err(format!("Task {task_uid} not found"));
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_dependency(
+    app_handle: tauri::AppHandle,
+    task_uid: String,
+    depends_on_uid: String,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let removed =
+        sqlx::query("DELETE FROM task_dependencies WHERE task_uid = ? AND depends_on_uid = ?")
+            .bind(&task_uid)
+            .bind(&depends_on_uid)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected();
+    if removed == 0 {
+        return Ok(());
+    }
+    let updated = touch(&mut tx, &task_uid).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(())
+}
+
+/// open tasks that nothing is blocking, optionally in a single calendar
+#[tauri::command]
+pub async fn query_actionable_tasks(
+    app_handle: tauri::AppHandle,
+    calendar_id: Option<String>,
+) -> Result<Vec<Task>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let rows: Vec<TaskRow> = sqlx::query_as(&format!(
+        "SELECT tasks.*, {BLOCKED_BY} FROM tasks
+         WHERE completed = 0 AND archived_at IS NULL AND NOT {BLOCKED}
+            AND (?1 IS NULL OR calendar_id = ?1)
+         ORDER BY sort_order"
+    ))
+    .bind(&calendar_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(Task::from).collect())
+}
+
+/// complete or reopen a task; completing one emits `tasks-unblocked` with the tasks that
+/// no longer wait for anything. returns those tasks
+#[tauri::command]
+pub async fn complete_task(
+    app_handle: tauri::AppHandle,
+    id: String,
+    completed: bool,
+) -> Result<Vec<Task>, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let now = now_iso();
+    let uid: Option<String> = sqlx::query_scalar(
+        "UPDATE tasks SET completed = ?, completed_at = ?, modified_at = ?, synced = 0
+         WHERE id = ? AND completed != ?
+         RETURNING uid",
+    )
+    .bind(completed)
+    .bind(completed.then(|| now.clone()))
+    .bind(&now)
+    .bind(&id)
+    .bind(completed)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let Some(uid) = uid else {
+        return Ok(Vec::new());
+    };
+
+    // this task was open until now, so every open dependent without another open
+    // blocker was waiting for it alone
+    let unblocked: Vec<TaskRow> = if completed {
+        sqlx::query_as(&format!(
+            "SELECT tasks.*, {BLOCKED_BY} FROM tasks
+             JOIN task_dependencies AS dependency ON dependency.task_uid = tasks.uid
+             WHERE dependency.depends_on_uid = ? AND tasks.completed = 0 AND NOT {BLOCKED}"
+        ))
+        .bind(&uid)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+    } else {
+        Vec::new()
+    };
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    TaskChanges {
+        updated: vec![id],
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+
+    let unblocked: Vec<Task> = unblocked.into_iter().map(Task::from).collect();
+    if !unblocked.is_empty() {
+        log::debug!("Completing {uid} unblocked {} tasks", unblocked.len());
+        if let Err(e) = app_handle.emit("tasks-unblocked", &unblocked) {
+            log::warn!("Failed to emit tasks-unblocked: {e}");
+        }
+    }
+    Ok(unblocked)
+}
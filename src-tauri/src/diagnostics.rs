@@ -0,0 +1,220 @@
+use serde::Serialize;
+use serde_json::json;
+use sqlx::{FromRow, SqlitePool};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tauri::Manager;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::db;
+use crate::lock;
+use crate::redact::{self, REDACTED};
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct AccountInfo {
+    id: String,
+    name: String,
+    server_url: String,
+    server_type: Option<String>,
+    last_sync: Option<String>,
+    is_active: bool,
+    calendars: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct CalendarSyncState {
+    id: String,
+    account_id: String,
+    has_ctag: bool,
+    has_sync_token: bool,
+    supported_components: Option<String>,
+    tasks: i64,
+    unsynced_tasks: i64,
+    pending_deletions: i64,
+}
+
+// how many sync journal entries go into the bundle
+const RECENT_JOURNAL_ENTRIES: i64 = 200;
+
+/// something that happened during sync: an account's last success or failure, a change that
+/// couldn't be pushed, a conflict. task titles and contents stay out
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct SyncJournalEntry {
+    at: String,
+    event: String,
+    account_id: Option<String>,
+    item_id: Option<String>,
+    detail: Option<String>,
+}
+
+/// drop credentials from a log line: basic-auth userinfo in urls and auth header values
+fn sanitize_line(line: &str) -> String {
+    let lower = line.to_ascii_lowercase();
+    if let Some(pos) = lower.find("authorization") {
+        return format!("{}authorization: {REDACTED}", &line[..pos]);
+    }
+    if lower.contains("password") {
+        return "[line with password redacted]".to_string();
+    }
+    redact::redact(line)
+}
+
+async fn schema_version(pool: &SqlitePool) -> Result<i64, String> {
+    sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(version) FROM _sqlx_migrations")
+        .fetch_one(pool)
+        .await
+        .map(|version| version.unwrap_or(0))
+        .map_err(|e| e.to_string())
+}
+
+async fn accounts(pool: &SqlitePool) -> Result<Vec<AccountInfo>, String> {
+    let mut accounts = sqlx::query_as::<_, AccountInfo>(
+        "SELECT a.id, a.name, a.server_url, a.server_type, a.last_sync, a.is_active,
+                (SELECT COUNT(*) FROM calendars c WHERE c.account_id = a.id) AS calendars
+         FROM accounts a",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for account in &mut accounts {
+        account.server_url = sanitize_line(&account.server_url);
+    }
+    Ok(accounts)
+}
+
+async fn sync_state(pool: &SqlitePool) -> Result<Vec<CalendarSyncState>, String> {
+    sqlx::query_as::<_, CalendarSyncState>(
+        "SELECT c.id, c.account_id,
+                c.ctag IS NOT NULL AS has_ctag,
+                c.sync_token IS NOT NULL AS has_sync_token,
+                c.supported_components,
+                (SELECT COUNT(*) FROM tasks t WHERE t.calendar_id = c.id) AS tasks,
+                (SELECT COUNT(*) FROM tasks t WHERE t.calendar_id = c.id AND t.synced = 0)
+                    AS unsynced_tasks,
+                (SELECT COUNT(*) FROM pending_deletions p WHERE p.calendar_id = c.id)
+                    AS pending_deletions
+         FROM calendars c",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn sync_journal(pool: &SqlitePool) -> Result<Vec<SyncJournalEntry>, String> {
+    let mut entries = sqlx::query_as::<_, SyncJournalEntry>(
+        "SELECT at, event, account_id, item_id, detail FROM (
+            SELECT last_failure_at AS at, 'sync-failed' AS event, account_id,
+                   NULL AS item_id,
+                   state || ', ' || consecutive_failures || ' in a row: ' || last_error AS detail
+            FROM account_health WHERE last_failure_at IS NOT NULL
+            UNION ALL
+            SELECT last_success_at, 'sync-succeeded', account_id, NULL, state
+            FROM account_health WHERE last_success_at IS NOT NULL
+            UNION ALL
+            SELECT last_attempt_at, 'push-failed', NULL, kind || ':' || item_id,
+                   attempts || ' attempts: ' || error
+            FROM sync_push_failures
+            UNION ALL
+            SELECT detected_at, 'conflict', account_id, task_id,
+                   COALESCE('resolved ' || resolved_at || ' as ' || resolution, 'unresolved')
+            FROM sync_conflicts
+         )
+         ORDER BY at DESC
+         LIMIT ?",
+    )
+    .bind(RECENT_JOURNAL_ENTRIES)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // errors can quote urls and server replies
+    for entry in &mut entries {
+        entry.detail = entry.detail.as_deref().map(sanitize_line);
+    }
+    Ok(entries)
+}
+
+/// add every log file in the log dir to the archive, sanitized line by line
+fn add_logs<W: Write + std::io::Seek>(
+    zip: &mut ZipWriter<W>,
+    log_dir: &Path,
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(log_dir) else {
+        return Ok(());
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "log") {
+            continue;
+        }
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let sanitized: Vec<String> = contents.lines().map(sanitize_line).collect();
+
+        zip.start_file(
+            format!("logs/{}", entry.file_name().to_string_lossy()),
+            options,
+        )
+        .map_err(|e| e.to_string())?;
+        zip.write_all(sanitized.join("\n").as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn add_json<W: Write + std::io::Seek, T: Serialize>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    value: &T,
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    let contents = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    zip.start_file(name, options).map_err(|e| e.to_string())?;
+    zip.write_all(&contents).map_err(|e| e.to_string())
+}
+
+/// zip sanitized logs, schema version, account/server metadata (never credentials),
+/// os info, per-calendar sync state and recent sync journal entries into a single file for
+/// bug reports
+#[tauri::command]
+pub async fn export_diagnostics(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let log_dir = app_handle.path().app_log_dir().map_err(|e| e.to_string())?;
+
+    let system = json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "schemaVersion": schema_version(&pool).await?,
+        "os": tauri_plugin_os::platform(),
+        "osVersion": tauri_plugin_os::version().to_string(),
+        "osFamily": tauri_plugin_os::family(),
+        "arch": tauri_plugin_os::arch(),
+        "locale": tauri_plugin_os::locale(),
+    });
+    let accounts = accounts(&pool).await?;
+    let sync_state = sync_state(&pool).await?;
+    let sync_journal = sync_journal(&pool).await?;
+
+    let file = fs::File::create(&path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    add_json(&mut zip, "system.json", &system, options)?;
+    add_json(&mut zip, "accounts.json", &accounts, options)?;
+    add_json(&mut zip, "sync-state.json", &sync_state, options)?;
+    add_json(&mut zip, "sync-journal.json", &sync_journal, options)?;
+    add_logs(&mut zip, &log_dir, options)?;
+
+    zip.finish().map_err(|e| e.to_string())?;
+    log::info!("Exported diagnostics bundle");
+    Ok(())
+}
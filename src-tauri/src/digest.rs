@@ -0,0 +1,330 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, SecondsFormat, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::report::{self, ReportFormat, ReportRange};
+use crate::smtp::{SmtpClient, SmtpSecurity};
+use crate::tasks::now_iso;
+use crate::{credentials, db, locale, lock, read_only, settings};
+
+// a daily or weekly email of the tasks completed and still open, sent through the user's own
+// SMTP server, for whoever wants the agenda in their inbox in the morning. the app has to run
+// to send it; a digest missed while it was closed goes out when it starts
+
+const SETTINGS_KEY: &str = "email_digest";
+// credential store entry of the smtp password
+const PASSWORD_ENTRY: &str = "email_digest";
+// when the last digest went out, so a restart doesn't send it again
+const LAST_SENT_KEY: &str = "email_digest_last_sent";
+const TICK: std::time::Duration = std::time::Duration::from_secs(60);
+// a failed digest is tried again after this; a wrong password shouldn't log in every minute
+const RETRY: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+lazy_static! {
+    static ref LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+    static ref LAST_FAILURE: Mutex<Option<Instant>> = Mutex::new(None);
+    // the timer and "send now" must not send the same digest twice
+    static ref SENDING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    Daily,
+    /// on the first day of the week of the os locale
+    Weekly,
+}
+
+/// where the digest goes and when; the password lives in the os credential store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmailDigestSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: String,
+    pub from: String,
+    /// comma separated
+    pub to: String,
+    pub frequency: DigestFrequency,
+    /// local hour of the day it's sent at
+    pub hour: u32,
+}
+
+impl Default for EmailDigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: 465,
+            security: SmtpSecurity::Tls,
+            username: String::new(),
+            from: String::new(),
+            to: String::new(),
+            frequency: DigestFrequency::Daily,
+            hour: 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailDigestStatus {
+    pub last_sent_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+async fn load_settings(pool: &SqlitePool) -> Result<EmailDigestSettings, String> {
+    Ok(settings::get(pool, SETTINGS_KEY)
+        .await?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+async fn last_sent(pool: &SqlitePool) -> Result<Option<DateTime<Utc>>, String> {
+    Ok(settings::get(pool, LAST_SENT_KEY)
+        .await?
+        .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        .map(|time| time.with_timezone(&Utc)))
+}
+
+fn recipients(to: &str) -> Vec<String> {
+    to.split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn period(frequency: DigestFrequency) -> Duration {
+    match frequency {
+        DigestFrequency::Daily => Duration::days(1),
+        DigestFrequency::Weekly => Duration::weeks(1),
+    }
+}
+
+// the latest time a digest was due at, up to now
+fn last_due(settings: &EmailDigestSettings, now: DateTime<Local>) -> DateTime<Local> {
+    let time = NaiveTime::from_hms_opt(settings.hour.min(23), 0, 0).unwrap_or_default();
+    let mut day = now.date_naive();
+    if settings.frequency == DigestFrequency::Weekly {
+        let week_start = locale::current().week_start().num_days_from_monday();
+        let into_week = (day.weekday().num_days_from_monday() + 7 - week_start) % 7;
+        day -= Duration::days(i64::from(into_week));
+    }
+    let at = |day: chrono::NaiveDate| {
+        // an hour skipped by a dst change counts as the one after it
+        day.and_time(time)
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap_or_else(|| now - Duration::hours(1))
+    };
+    let due = at(day);
+    if due <= now {
+        due
+    } else {
+        at(day - period(settings.frequency))
+    }
+}
+
+// RFC 2047 encoded word, for a subject that isn't plain ascii
+fn encode_header(text: &str) -> String {
+    if text.is_ascii() {
+        text.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(text))
+    }
+}
+
+fn compose(settings: &EmailDigestSettings, to: &[String], subject: &str, html: &str) -> String {
+    let body = STANDARD.encode(html);
+    let mut message = [
+        format!("From: {}", settings.from.trim()),
+        format!("To: {}", to.join(", ")),
+        format!("Subject: {}", encode_header(subject)),
+        format!("Date: {}", Local::now().to_rfc2822()),
+        format!(
+            "Message-ID: <{}@caldav-tasks>",
+            uuid::Uuid::new_v4().simple()
+        ),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/html; charset=utf-8".to_string(),
+        "Content-Transfer-Encoding: base64".to_string(),
+        "Auto-Submitted: auto-generated".to_string(),
+        String::new(),
+    ]
+    .join("\r\n");
+    message.push_str("\r\n");
+    // base64 only has ascii, so the byte chunks are whole characters
+    for line in body.as_bytes().chunks(76) {
+        message.push_str(&String::from_utf8_lossy(line));
+        message.push_str("\r\n");
+    }
+    message
+}
+
+async fn deliver(
+    pool: &SqlitePool,
+    settings: &EmailDigestSettings,
+    until: DateTime<Utc>,
+) -> Result<(), String> {
+    let to = recipients(&settings.to);
+    if settings.host.is_empty() || settings.from.trim().is_empty() || to.is_empty() {
+        return Err("Set up the SMTP server and addresses first".to_string());
+    }
+    let password = credentials::get_password(PASSWORD_ENTRY)
+        .await?
+        .ok_or("No SMTP password saved")?;
+
+    let range = ReportRange {
+        from: (until - period(settings.frequency)).to_rfc3339_opts(SecondsFormat::Millis, true),
+        to: until.to_rfc3339_opts(SecondsFormat::Millis, true),
+    };
+    let (title, html) = report::render(pool, &range, ReportFormat::Html).await?;
+    let message = compose(settings, &to, &title, &html);
+
+    let mut client = SmtpClient::connect(
+        &settings.host,
+        settings.port,
+        settings.security,
+        &settings.username,
+        &password,
+    )
+    .await?;
+    client.send(settings.from.trim(), &to, &message).await?;
+    client.quit().await;
+    Ok(())
+}
+
+// send the digest up to `until` and remember it
+#[tracing::instrument(
+    name = "email_digest",
+    level = "debug",
+    skip_all,
+    fields(%until),
+    err(level = "debug")
+)]
+async fn send(
+    pool: &SqlitePool,
+    settings: &EmailDigestSettings,
+    until: DateTime<Utc>,
+) -> Result<(), String> {
+    let _sending = SENDING.lock().await;
+    let result = deliver(pool, settings, until).await;
+    *LAST_ERROR.lock().expect("Failed to lock LAST_ERROR") = result.as_ref().err().cloned();
+    *LAST_FAILURE.lock().expect("Failed to lock LAST_FAILURE") = result.is_err().then(Instant::now);
+    result?;
+    settings::set(pool, LAST_SENT_KEY, &now_iso()).await?;
+    log::info!("Sent the email digest");
+    Ok(())
+}
+
+/// send the digest when it's due
+pub fn start_scheduled_sends(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut tick = tokio::time::interval(TICK);
+        loop {
+            tick.tick().await;
+            // task titles stay out of mail while the app is locked
+            if lock::is_locked() {
+                continue;
+            }
+            let Ok(pool) = db::pool(&app_handle).await else {
+                continue;
+            };
+            let Ok(settings) = load_settings(&pool).await else {
+                continue;
+            };
+            if !settings.enabled {
+                continue;
+            }
+            let due = last_due(&settings, Local::now()).with_timezone(&Utc);
+            let sent = match last_sent(&pool).await {
+                Ok(sent) => sent.is_some_and(|sent| sent >= due),
+                Err(_) => continue,
+            };
+            let retry = LAST_FAILURE
+                .lock()
+                .expect("Failed to lock LAST_FAILURE")
+                .is_none_or(|failed| failed.elapsed() >= RETRY);
+            if !sent && retry {
+                if let Err(e) = send(&pool, &settings, due).await {
+                    log::warn!("Failed to send the email digest: {e}");
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_email_digest_settings(
+    app_handle: tauri::AppHandle,
+) -> Result<EmailDigestSettings, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    load_settings(&pool).await
+}
+
+/// save the settings; the password is only replaced when one is given
+#[tauri::command]
+pub async fn set_email_digest_settings(
+    app_handle: tauri::AppHandle,
+    settings: EmailDigestSettings,
+    password: Option<String>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let settings = EmailDigestSettings {
+        host: settings.host.trim().to_string(),
+        from: settings.from.trim().to_string(),
+        to: recipients(&settings.to).join(", "),
+        hour: settings.hour.min(23),
+        ..settings
+    };
+    if settings.enabled && (settings.host.is_empty() || settings.from.is_empty()) {
+        return Err("An SMTP server and sender address are needed".to_string());
+    }
+    if settings.enabled && settings.to.is_empty() {
+        return Err("At least one recipient is needed".to_string());
+    }
+    let pool = db::pool(&app_handle).await?;
+    if let Some(password) = password {
+        credentials::set_password(PASSWORD_ENTRY, password).await?;
+    }
+    // the next tick tries with the new settings
+    *LAST_FAILURE.lock().expect("Failed to lock LAST_FAILURE") = None;
+    // the first digest goes out at the next sending time, not right when it's turned on
+    if settings.enabled && last_sent(&pool).await?.is_none() {
+        settings::set(&pool, LAST_SENT_KEY, &now_iso()).await?;
+    }
+    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    settings::set(&pool, SETTINGS_KEY, &json).await
+}
+
+#[tauri::command]
+pub async fn get_email_digest_status(
+    app_handle: tauri::AppHandle,
+) -> Result<EmailDigestStatus, String> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(EmailDigestStatus {
+        last_sent_at: settings::get(&pool, LAST_SENT_KEY).await?,
+        last_error: LAST_ERROR
+            .lock()
+            .expect("Failed to lock LAST_ERROR")
+            .clone(),
+    })
+}
+
+/// send a digest of the last day or week right away, whatever the schedule
+#[tauri::command]
+pub async fn send_email_digest_now(app_handle: tauri::AppHandle) -> Result<(), String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let settings = load_settings(&pool).await?;
+    send(&pool, &settings, Utc::now()).await
+}
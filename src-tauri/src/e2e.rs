@@ -0,0 +1,259 @@
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use lazy_static::lazy_static;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::tasks::{now_iso, TaskChanges};
+use crate::{credentials, db, lock, read_only};
+
+/// format version of an encrypted SUMMARY/DESCRIPTION value; everything after it is
+/// base64(nonce || ciphertext). whether a task is encrypted is told by its
+/// X-CALDAV-TASKS-E2E property, never by this prefix, which a title may start with as well
+pub const PREFIX: &str = "e2e:v1:";
+
+// credential store kinds for the derived key, and the one it replaced: tasks on the server
+// keep the old encryption until the next sync uploads them again
+const KEY_KIND: &str = "e2e-key";
+const PREVIOUS_KEY_KIND: &str = "e2e-key-previous";
+const NONCE_LEN: usize = 24;
+
+// keys by account id and kind (None = no such key)
+type KeyCache = HashMap<(String, &'static str), Option<[u8; 32]>>;
+
+// global cache of keys read from the credential store
+lazy_static! {
+    static ref KEYS: Mutex<KeyCache> = Mutex::new(HashMap::new());
+}
+
+/// derive the account key from the passphrase; the salt comes from the account's server
+/// and username so every device that knows the passphrase ends up with the same key
+fn derive_key(passphrase: &str, server_url: &str, username: &str) -> Result<[u8; 32], String> {
+    let salt = Sha256::digest(format!(
+        "caldav-tasks-e2e\n{}\n{username}",
+        server_url.trim_end_matches('/')
+    ));
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+async fn stored_key(account_id: &str, kind: &'static str) -> Result<Option<[u8; 32]>, String> {
+    let cache_key = (account_id.to_string(), kind);
+    if let Some(key) = KEYS.lock().expect("Failed to lock KEYS").get(&cache_key) {
+        return Ok(*key);
+    }
+
+    let key = match credentials::get_account_secret(account_id, kind).await? {
+        Some(encoded) => {
+            let bytes = STANDARD.decode(encoded).map_err(|e| e.to_string())?;
+            Some(
+                <[u8; 32]>::try_from(bytes.as_slice())
+                    .map_err(|_| "Stored encryption key is corrupt".to_string())?,
+            )
+        }
+        None => None,
+    };
+    KEYS.lock()
+        .expect("Failed to lock KEYS")
+        .insert(cache_key, key);
+    Ok(key)
+}
+
+async fn store_key(
+    account_id: &str,
+    kind: &'static str,
+    key: Option<[u8; 32]>,
+) -> Result<(), String> {
+    match key {
+        Some(key) => {
+            credentials::set_account_secret(account_id, kind, STANDARD.encode(key)).await?
+        }
+        None => credentials::delete_account_secret(account_id, kind).await?,
+    }
+    KEYS.lock()
+        .expect("Failed to lock KEYS")
+        .insert((account_id.to_string(), kind), key);
+    Ok(())
+}
+
+async fn account_key(account_id: &str) -> Result<Option<[u8; 32]>, String> {
+    stored_key(account_id, KEY_KIND).await
+}
+
+fn encrypt(key: &[u8; 32], text: &str) -> Result<String, String> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, text.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!("{PREFIX}{}", STANDARD.encode(payload)))
+}
+
+fn decrypt(key: &[u8; 32], text: &str) -> Result<String, String> {
+    let encoded = text
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| "Value isn't encrypted".to_string())?;
+    let payload = STANDARD.decode(encoded).map_err(|e| e.to_string())?;
+    if payload.len() <= NONCE_LEN {
+        return Err("Encrypted value is too short".to_string());
+    }
+
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Wrong passphrase or damaged data".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
+}
+
+/// enable (or change) end-to-end encryption for an account, or disable it with `None`
+///
+/// every task of the account is marked unsynced so the next sync uploads it again with the
+/// new key, or in plain text. the old key stays around to read the tasks that haven't been
+/// uploaded yet
+#[tauri::command]
+pub async fn set_account_encryption(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    let key = match passphrase {
+        Some(passphrase) if passphrase.is_empty() => {
+            return Err("The passphrase can't be empty".to_string());
+        }
+        Some(passphrase) => {
+            let (server_url, username) = sqlx::query_as::<_, (String, String)>(
+                "SELECT server_url, username FROM accounts WHERE id = ?",
+            )
+            .bind(&account_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Account not found: {account_id}"))?;
+
+            // argon2 is deliberately slow, keep it off the async runtime
+            let key = tauri::async_runtime::spawn_blocking(move || {
+                derive_key(&passphrase, &server_url, &username)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+            Some(key)
+        }
+        None => None,
+    };
+
+    let old_key = account_key(&account_id).await?;
+    if key == old_key {
+        return Ok(());
+    }
+
+    let updated: Vec<String> = sqlx::query_scalar(
+        "UPDATE tasks SET synced = 0, modified_at = ?
+         WHERE account_id = ? AND COALESCE(local_only, 0) = 0
+         RETURNING id",
+    )
+    .bind(now_iso())
+    .bind(&account_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if old_key.is_some() {
+        store_key(&account_id, PREVIOUS_KEY_KIND, old_key).await?;
+    }
+    store_key(&account_id, KEY_KIND, key).await?;
+    log::info!(
+        "End-to-end encryption {} for account {account_id}, {} tasks to upload again",
+        if key.is_some() { "enabled" } else { "disabled" },
+        updated.len()
+    );
+
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(())
+}
+
+// decrypt `text` with the first of `keys` that fits; empty texts aren't encrypted
+fn decrypt_with(keys: &[Option<[u8; 32]>], text: &str) -> Result<String, String> {
+    if text.is_empty() {
+        return Ok(String::new());
+    }
+    let mut error = "Tasks of this account are encrypted, set its passphrase to read them";
+    for key in keys.iter().flatten() {
+        match decrypt(key, text) {
+            Ok(plaintext) => return Ok(plaintext),
+            Err(_) => {
+                error = "Can't decrypt tasks of this account: wrong passphrase or damaged data"
+            }
+        }
+    }
+    Err(error.to_string())
+}
+
+/// whether an account encrypts task content
+#[tauri::command]
+pub async fn get_account_encryption(account_id: String) -> Result<bool, String> {
+    Ok(account_key(&account_id).await?.is_some())
+}
+
+/// encrypt texts (titles, descriptions) before they are written into a VTODO, which is then
+/// marked as encrypted; None for accounts without encryption. only empty texts stay as they are
+#[tauri::command]
+pub async fn encrypt_texts(
+    account_id: String,
+    texts: Vec<String>,
+) -> Result<Option<Vec<String>>, String> {
+    let Some(key) = account_key(&account_id).await? else {
+        return Ok(None);
+    };
+    texts
+        .iter()
+        .map(|text| {
+            if text.is_empty() {
+                Ok(String::new())
+            } else {
+                encrypt(&key, text)
+            }
+        })
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// decrypt the texts of VTODOs marked as encrypted. tasks that haven't been uploaded again
+/// since the passphrase changed are read with the previous key
+#[tauri::command]
+pub async fn decrypt_texts(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    texts: Vec<String>,
+) -> Result<Vec<String>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+
+    let keys = [
+        account_key(&account_id).await?,
+        stored_key(&account_id, PREVIOUS_KEY_KIND).await?,
+    ];
+
+    texts
+        .iter()
+        .map(|text| decrypt_with(&keys, text))
+        .collect::<Result<_, _>>()
+        .inspect_err(|e| log::warn!("Failed to decrypt task content for account {account_id}: {e}"))
+}
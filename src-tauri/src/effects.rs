@@ -0,0 +1,117 @@
+use lazy_static::lazy_static;
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use tauri::{Manager, WebviewWindow};
+
+use crate::{db, read_only, settings};
+
+// the window effect picked in the settings, applied again on launch and to new windows
+const SETTINGS_KEY: &str = "window_effect";
+
+// global storage for the currently selected window effect (applied to new windows as well)
+lazy_static! {
+    static ref WINDOW_EFFECT: Mutex<String> = Mutex::new("none".to_string());
+}
+
+/// apply a window effect to a single window
+///
+/// supported values are "none", "acrylic" and "mica"; only Windows gets transparent windows
+/// for them to show through (see tauri.windows.conf.json). vibrancy on macOS would need its
+/// private API for transparent windows, which the App Store doesn't allow
+#[cfg(target_os = "windows")]
+fn apply_effect(window: &WebviewWindow, effect: &str) -> Result<(), String> {
+    use tauri::window::{Effect, EffectsBuilder};
+
+    let effect = match effect {
+        "acrylic" => Some(Effect::Acrylic),
+        "mica" => Some(Effect::Mica),
+        _ => None,
+    };
+
+    match effect {
+        Some(effect) => window
+            .set_effects(EffectsBuilder::new().effect(effect).build())
+            .map_err(|e| e.to_string()),
+        None => window.set_effects(None).map_err(|e| e.to_string()),
+    }
+}
+
+/// window effects aren't available on this platform
+#[cfg(not(target_os = "windows"))]
+fn apply_effect(_window: &WebviewWindow, _effect: &str) -> Result<(), String> {
+    Ok(())
+}
+
+fn supported_effects() -> Vec<String> {
+    let mut effects = vec!["none".to_string()];
+    if cfg!(target_os = "windows") {
+        effects.push("acrylic".to_string());
+        effects.push("mica".to_string());
+    }
+    effects
+}
+
+fn apply_to_all(app_handle: &tauri::AppHandle, effect: &str) -> Result<(), String> {
+    for window in app_handle.webview_windows().values() {
+        apply_effect(window, effect)?;
+    }
+    Ok(())
+}
+
+/// apply the currently selected effect to a newly created window
+pub fn apply_current_effect(window: &WebviewWindow) {
+    let effect = WINDOW_EFFECT
+        .lock()
+        .expect("Failed to lock WINDOW_EFFECT")
+        .clone();
+    if effect != "none" {
+        if let Err(e) = apply_effect(window, &effect) {
+            log::warn!("Failed to apply window effect: {e}");
+        }
+    }
+}
+
+/// apply the saved effect once the database is open; one saved on another platform is
+/// left alone until it's changed here
+pub async fn load(app_handle: &tauri::AppHandle, pool: &SqlitePool) -> Result<(), String> {
+    let Some(effect) = settings::get(pool, SETTINGS_KEY).await? else {
+        return Ok(());
+    };
+    if effect == "none" || !supported_effects().contains(&effect) {
+        return Ok(());
+    }
+
+    apply_to_all(app_handle, &effect)?;
+    *WINDOW_EFFECT.lock().expect("Failed to lock WINDOW_EFFECT") = effect;
+    Ok(())
+}
+
+/// get the window effect picked in the settings
+#[tauri::command]
+pub async fn get_window_effect() -> Result<String, String> {
+    Ok(WINDOW_EFFECT
+        .lock()
+        .expect("Failed to lock WINDOW_EFFECT")
+        .clone())
+}
+
+/// set the window effect for all open windows and keep it for the next launch
+#[tauri::command]
+pub async fn set_window_effect(app_handle: tauri::AppHandle, effect: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    if !supported_effects().contains(&effect) {
+        return Err(format!("Unsupported window effect: {effect}"));
+    }
+
+    let pool = db::pool(&app_handle).await?;
+    settings::set(&pool, SETTINGS_KEY, &effect).await?;
+    apply_to_all(&app_handle, &effect)?;
+    *WINDOW_EFFECT.lock().expect("Failed to lock WINDOW_EFFECT") = effect;
+    Ok(())
+}
+
+/// get the window effects supported on the current platform
+#[tauri::command]
+pub async fn get_supported_window_effects() -> Result<Vec<String>, String> {
+    Ok(supported_effects())
+}
@@ -0,0 +1,284 @@
+use lazy_static::lazy_static;
+use mail_parser::MessageParser;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::api_server::{self, NewTask};
+use crate::imap::ImapClient;
+use crate::tasks::now_iso;
+use crate::{credentials, db, lock, read_only, settings};
+
+const SETTINGS_KEY: &str = "email_to_task";
+// credential store entry of the imap password
+const PASSWORD_ENTRY: &str = "email_to_task";
+const TICK: Duration = Duration::from_secs(60);
+// messages turned into tasks per poll, the rest waits for the next one
+const MAX_PER_POLL: usize = 25;
+const MAX_DESCRIPTION: usize = 20_000;
+
+lazy_static! {
+    static ref STATUS: Mutex<EmailToTaskStatus> = Mutex::new(EmailToTaskStatus::default());
+    static ref LAST_POLL: Mutex<Option<Instant>> = Mutex::new(None);
+    // the timer and "poll now" must not import the same message twice
+    static ref POLLING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// where new mail is picked up; the password lives in the os credential store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmailToTaskSettings {
+    pub enabled: bool,
+    pub host: String,
+    /// implicit tls only
+    pub port: u16,
+    pub username: String,
+    pub folder: String,
+    /// processed messages are moved here when the server supports MOVE, else only marked read
+    pub processed_folder: Option<String>,
+    /// the first list when unset
+    pub calendar_id: Option<String>,
+    pub interval_minutes: u32,
+}
+
+impl Default for EmailToTaskSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: String::new(),
+            port: 993,
+            username: String::new(),
+            folder: "INBOX".to_string(),
+            processed_folder: None,
+            calendar_id: None,
+            interval_minutes: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailToTaskStatus {
+    pub last_poll_at: Option<String>,
+    pub last_error: Option<String>,
+    /// tasks created since the app started
+    pub imported: usize,
+}
+
+async fn load_settings(pool: &SqlitePool) -> Result<EmailToTaskSettings, String> {
+    Ok(settings::get(pool, SETTINGS_KEY)
+        .await?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+// RFC 2392 link to the message, opened by the mail client
+fn message_link(message_id: &str) -> String {
+    let mut link = String::from("mid:");
+    for byte in message_id.trim_matches(['<', '>']).bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => {
+                link.push(byte as char)
+            }
+            _ => link.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    link
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+// the task for a raw message; None when it doesn't parse
+fn to_task(raw: &[u8], calendar_id: Option<String>) -> Option<NewTask> {
+    let message = MessageParser::default().parse(raw)?;
+    let title = message
+        .subject()
+        .map(str::trim)
+        .filter(|subject| !subject.is_empty())
+        .unwrap_or("(no subject)")
+        // forwarding is how mail gets here, the prefix says nothing about the task
+        .trim_start_matches("Fwd: ")
+        .trim_start_matches("FW: ")
+        .to_string();
+    let description = message
+        .body_text(0)
+        .map(|body| truncate(body.trim(), MAX_DESCRIPTION))
+        .unwrap_or_default();
+    Some(NewTask {
+        title,
+        description,
+        calendar_id,
+        url: message.message_id().map(message_link),
+        ..NewTask::default()
+    })
+}
+
+async fn import(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    settings: &EmailToTaskSettings,
+) -> Result<usize, String> {
+    let password = credentials::get_password(PASSWORD_ENTRY)
+        .await?
+        .ok_or("No IMAP password saved")?;
+    let mut client =
+        ImapClient::connect(&settings.host, settings.port, &settings.username, &password).await?;
+    client.select(&settings.folder).await?;
+
+    let mut imported = 0;
+    for uid in client.unseen().await?.into_iter().take(MAX_PER_POLL) {
+        let raw = client.fetch(uid).await?;
+        match to_task(&raw, settings.calendar_id.clone()) {
+            Some(task) => {
+                // a message that was imported before but couldn't be flagged
+                let known = match &task.url {
+                    Some(url) => sqlx::query_scalar::<_, i64>("SELECT 1 FROM tasks WHERE url = ?")
+                        .bind(url)
+                        .fetch_optional(pool)
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .is_some(),
+                    None => false,
+                };
+                if !known {
+                    api_server::create_task(app_handle, pool, task)
+                        .await
+                        .map_err(|response| {
+                            response
+                                .body
+                                .and_then(|body| body["error"].as_str().map(str::to_string))
+                                .unwrap_or_else(|| "Failed to create the task".to_string())
+                        })?;
+                    imported += 1;
+                }
+            }
+            None => log::warn!(
+                "Skipped message {uid} in {}: not a valid email",
+                settings.folder
+            ),
+        }
+
+        client.mark_seen(uid).await?;
+        if let Some(folder) = &settings.processed_folder {
+            client.move_to(uid, folder).await?;
+        }
+    }
+    client.logout().await;
+    Ok(imported)
+}
+
+/// turn the unread messages of the folder into tasks; returns how many were created
+#[tracing::instrument(name = "email_poll", level = "trace", skip_all, err(level = "debug"))]
+pub async fn poll(app_handle: &tauri::AppHandle) -> Result<usize, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(app_handle).await?;
+    let settings = load_settings(&pool).await?;
+    if settings.host.is_empty() || settings.username.is_empty() {
+        return Err("Set up the IMAP server first".to_string());
+    }
+
+    let _polling = POLLING.lock().await;
+    *LAST_POLL.lock().expect("Failed to lock LAST_POLL") = Some(Instant::now());
+    let result = import(app_handle, &pool, &settings).await;
+
+    let mut status = STATUS.lock().expect("Failed to lock STATUS");
+    status.last_poll_at = Some(now_iso());
+    match &result {
+        Ok(imported) => {
+            status.imported += imported;
+            status.last_error = None;
+            if *imported > 0 {
+                log::info!("Created {imported} tasks from email");
+            }
+        }
+        Err(e) => status.last_error = Some(e.clone()),
+    }
+    result
+}
+
+/// poll the folder at the configured interval
+pub fn start_polling(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut tick = tokio::time::interval(TICK);
+        loop {
+            tick.tick().await;
+            let Ok(pool) = db::pool(&app_handle).await else {
+                continue;
+            };
+            let Ok(settings) = load_settings(&pool).await else {
+                continue;
+            };
+            let interval = Duration::from_secs(u64::from(settings.interval_minutes.max(1)) * 60);
+            let due = LAST_POLL
+                .lock()
+                .expect("Failed to lock LAST_POLL")
+                .is_none_or(|last| last.elapsed() >= interval);
+            if settings.enabled && due && !read_only::is_enabled() {
+                if let Err(e) = poll(&app_handle).await {
+                    log::warn!("Email to task failed: {e}");
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_email_to_task_settings(
+    app_handle: tauri::AppHandle,
+) -> Result<EmailToTaskSettings, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    load_settings(&pool).await
+}
+
+/// save the settings; the password is only replaced when one is given
+#[tauri::command]
+pub async fn set_email_to_task_settings(
+    app_handle: tauri::AppHandle,
+    settings: EmailToTaskSettings,
+    password: Option<String>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    if settings.enabled && (settings.host.trim().is_empty() || settings.username.is_empty()) {
+        return Err("An IMAP server and username are needed".to_string());
+    }
+    let pool = db::pool(&app_handle).await?;
+    if let Some(password) = password {
+        credentials::set_password(PASSWORD_ENTRY, password).await?;
+    }
+    let settings = EmailToTaskSettings {
+        host: settings.host.trim().to_string(),
+        folder: match settings.folder.trim() {
+            "" => "INBOX".to_string(),
+            folder => folder.to_string(),
+        },
+        processed_folder: settings
+            .processed_folder
+            .map(|folder| folder.trim().to_string())
+            .filter(|folder| !folder.is_empty()),
+        ..settings
+    };
+    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    settings::set(&pool, SETTINGS_KEY, &json).await?;
+    // the next tick polls with the new settings
+    *LAST_POLL.lock().expect("Failed to lock LAST_POLL") = None;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_email_to_task_status() -> EmailToTaskStatus {
+    STATUS.lock().expect("Failed to lock STATUS").clone()
+}
+
+/// poll right away, whatever the interval
+#[tauri::command]
+pub async fn poll_email_now(app_handle: tauri::AppHandle) -> Result<usize, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    poll(&app_handle).await
+}
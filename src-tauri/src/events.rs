@@ -0,0 +1,508 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::bulk::{self, BulkOp};
+use crate::caldav::{resolve_href, AccountConfig, CalDavClient};
+use crate::multistatus::MultistatusStream;
+use crate::quick_add::to_iso;
+use crate::tasks::{fetch_by_ids, new_id, now_iso, save_task, TaskChanges};
+use crate::{db, lock, read_only};
+
+// meetings next to the tasks, for the agenda; events are read and never cached. the only
+// event the app writes is a new one made from a task
+
+const EVENT_CALENDARS_PROPFIND: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav" xmlns:a="http://apple.com/ns/ical/">
+  <d:prop>
+    <d:resourcetype/>
+    <d:displayname/>
+    <c:supported-calendar-component-set/>
+    <a:calendar-color/>
+  </d:prop>
+</d:propfind>"#;
+
+lazy_static! {
+    static ref RESPONSE: Regex = Regex::new(r"(?is)<[^:>/]*:?response[\s>].*?</[^:>]*:?response>")
+        .expect("invalid response regex");
+    static ref HREF: Regex =
+        Regex::new(r"(?is)<[^:>/]*:?href[^>]*>([^<]+)<").expect("invalid href regex");
+    static ref CALENDAR: Regex =
+        Regex::new(r"(?is)<[^:>/]*:?resourcetype[^>]*>.*?<[^:>/]*:?calendar[\s/>]")
+            .expect("invalid resourcetype regex");
+    static ref DISPLAY_NAME: Regex =
+        Regex::new(r"(?is)<[^:>/]*:?displayname[^>]*>([^<]*)<").expect("invalid displayname regex");
+    static ref COLOR: Regex = Regex::new(r"(?is)<[^:>/]*:?calendar-color[^>]*>([^<]*)<")
+        .expect("invalid calendar-color regex");
+    static ref COMPONENTS: Regex = Regex::new(
+        r"(?is)<[^:>/]*:?supported-calendar-component-set[^>]*>(.*?)</[^:>]*:?supported-calendar-component-set>"
+    )
+    .expect("invalid component set regex");
+}
+
+/// one occurrence of an event; start and end are UTC timestamps, all-day events span local
+/// midnights like all-day due dates do
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub calendar_url: String,
+    pub calendar_name: String,
+    pub calendar_color: Option<String>,
+    pub title: String,
+    pub location: Option<String>,
+    pub start: String,
+    /// exclusive
+    pub end: String,
+    pub all_day: bool,
+}
+
+struct EventCalendar {
+    url: String,
+    name: String,
+    color: Option<String>,
+}
+
+// calendar collections that can hold events; a calendar without a component set takes any
+fn event_calendars(home: &str, body: &str) -> Vec<EventCalendar> {
+    RESPONSE
+        .find_iter(body)
+        .map(|m| m.as_str())
+        .filter(|response| CALENDAR.is_match(response))
+        .filter(|response| match COMPONENTS.captures(response) {
+            Some(components) => components[1].contains("\"VEVENT\""),
+            None => true,
+        })
+        .filter_map(|response| {
+            let href = HREF.captures(response)?[1].trim().to_string();
+            let url = resolve_href(home, &href)?;
+            let name = DISPLAY_NAME
+                .captures(response)
+                .map(|name| name[1].trim().to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| {
+                    href.trim_end_matches('/')
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                });
+            let color = COLOR
+                .captures(response)
+                .map(|color| color[1].trim().to_string())
+                .filter(|color| !color.is_empty());
+            Some(EventCalendar { url, name, color })
+        })
+        .collect()
+}
+
+pub fn caldav_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// VEVENTs in the range, with recurrences expanded by the server
+fn events_report(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    let (start, end) = (caldav_time(start), caldav_time(end));
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+  <d:prop>
+    <c:calendar-data>
+      <c:expand start="{start}" end="{end}"/>
+    </c:calendar-data>
+  </d:prop>
+  <c:filter>
+    <c:comp-filter name="VCALENDAR">
+      <c:comp-filter name="VEVENT">
+        <c:time-range start="{start}" end="{end}"/>
+      </c:comp-filter>
+    </c:comp-filter>
+  </c:filter>
+</c:calendar-query>"#
+    )
+}
+
+/// a content line of an ICS file (or a vCard)
+pub struct Property<'a> {
+    /// upper case, with the group of a vCard property (`item1.EMAIL`)
+    pub name: String,
+    pub params: Vec<&'a str>,
+    pub value: &'a str,
+}
+
+impl Property<'_> {
+    fn is_date(&self) -> bool {
+        self.params
+            .iter()
+            .any(|param| param.eq_ignore_ascii_case("VALUE=DATE"))
+            || self.value.len() == 8
+    }
+}
+
+pub fn parse_property(line: &str) -> Option<Property<'_>> {
+    // the first colon outside of a quoted parameter value
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let mut head = line[..colon].split(';');
+    Some(Property {
+        name: head.next()?.to_ascii_uppercase(),
+        params: head.collect(),
+        value: &line[colon + 1..],
+    })
+}
+
+/// the text of a TEXT value
+pub fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => {}
+        }
+    }
+    text
+}
+
+/// (utc time, all day); times with a TZID are taken as local time, servers that expand
+/// recurrences answer in UTC anyway
+pub fn parse_time(property: &Property) -> Option<(DateTime<Utc>, bool)> {
+    let value = property.value.trim();
+    if property.is_date() {
+        let day = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let midnight = to_iso(day.and_time(NaiveTime::MIN))?;
+        return Some((midnight.parse().ok()?, true));
+    }
+    let (time, utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(time) => (time, true),
+        None => (value, false),
+    };
+    let time = NaiveDateTime::parse_from_str(time, "%Y%m%dT%H%M%S").ok()?;
+    if utc {
+        return Some((time.and_utc(), false));
+    }
+    Some((to_iso(time)?.parse().ok()?, false))
+}
+
+// an ICS duration like P1D or PT1H30M
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = std::mem::take(&mut number).parse().ok()?;
+                duration += match unit {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    'S' => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    Some(if negative { -duration } else { duration })
+}
+
+fn iso(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// join folded content lines back into one
+pub fn unfold(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "")
+}
+
+// the VEVENTs of one calendar object (an expanded recurrence gives one per occurrence)
+fn parse_events(ics: &str, calendar: &EventCalendar) -> Vec<CalendarEvent> {
+    let unfolded = unfold(ics);
+    let mut events = Vec::new();
+    // nesting inside the current VEVENT (VALARMs have their own SUMMARY and DESCRIPTION)
+    let mut depth = 0;
+    let mut current: Option<Vec<Property>> = None;
+    for line in unfolded.lines() {
+        let Some(property) = parse_property(line) else {
+            continue;
+        };
+        match (property.name.as_str(), property.value.trim()) {
+            ("BEGIN", "VEVENT") if current.is_none() => {
+                current = Some(Vec::new());
+                depth = 0;
+            }
+            ("BEGIN", _) if current.is_some() => depth += 1,
+            ("END", "VEVENT") if depth == 0 => {
+                if let Some(event) = current.take().and_then(|properties| {
+                    let event = to_event(&properties, calendar);
+                    if event.is_none() {
+                        log::debug!("Skipped an event without a valid start");
+                    }
+                    event
+                }) {
+                    events.push(event);
+                }
+            }
+            ("END", _) if current.is_some() => depth -= 1,
+            _ => {
+                if let Some(properties) = current.as_mut().filter(|_| depth == 0) {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+    events
+}
+
+fn to_event(properties: &[Property], calendar: &EventCalendar) -> Option<CalendarEvent> {
+    let find = |name: &str| properties.iter().find(|property| property.name == name);
+    if find("STATUS").is_some_and(|status| status.value.eq_ignore_ascii_case("CANCELLED")) {
+        return None;
+    }
+
+    let (start, all_day) = parse_time(find("DTSTART")?)?;
+    let end = match (find("DTEND"), find("DURATION")) {
+        (Some(end), _) => parse_time(end).map(|(end, _)| end),
+        (None, Some(duration)) => parse_duration(duration.value).map(|duration| start + duration),
+        // RFC 5545: a date lasts the day, a date-time is a moment
+        (None, None) if all_day => Some(start + Duration::days(1)),
+        (None, None) => Some(start),
+    }
+    .filter(|end| *end >= start)
+    .unwrap_or(start);
+
+    Some(CalendarEvent {
+        uid: find("UID")
+            .map(|uid| uid.value.to_string())
+            .unwrap_or_default(),
+        calendar_url: calendar.url.clone(),
+        calendar_name: calendar.name.clone(),
+        calendar_color: calendar.color.clone(),
+        title: find("SUMMARY")
+            .map(|summary| unescape(summary.value))
+            .unwrap_or_default(),
+        location: find("LOCATION")
+            .map(|location| unescape(location.value))
+            .filter(|location| !location.is_empty()),
+        start: iso(start),
+        end: iso(end),
+        all_day,
+    })
+}
+
+fn parse_range(time: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| format!("Invalid timestamp: {time}"))
+}
+
+/// events of the account's calendars overlapping `start`..`end` (ISO timestamps), sorted by
+/// start. read-only: nothing is stored. a calendar that fails is skipped, not the whole fetch
+#[tauri::command]
+pub async fn fetch_events(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    calendar_home: String,
+    start: String,
+    end: String,
+) -> Result<Vec<CalendarEvent>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let (start, end) = (parse_range(&start)?, parse_range(&end)?);
+    if end <= start {
+        return Err("The end of the range must be after its start".to_string());
+    }
+
+    let client = CalDavClient::new(&account)?;
+    let response = client
+        .propfind(&calendar_home, "1", EVENT_CALENDARS_PROPFIND)
+        .await?;
+    if !response.is_multistatus() {
+        return Err(format!(
+            "Failed to list calendars (HTTP {})",
+            response.status
+        ));
+    }
+
+    let report = events_report(start, end);
+    let mut events = Vec::new();
+    for calendar in event_calendars(&calendar_home, &response.body) {
+        let response = match client.report(&calendar.url, "1", &report).await {
+            Ok(response) if response.is_multistatus() => response,
+            Ok(response) => {
+                log::warn!(
+                    "Failed to fetch events of {} (HTTP {})",
+                    calendar.name,
+                    response.status
+                );
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Failed to fetch events of {}: {e}", calendar.name);
+                continue;
+            }
+        };
+        let mut items = MultistatusStream::new();
+        let mut parsed = items.feed(response.body.as_bytes())?;
+        parsed.extend(items.finish()?);
+        for data in parsed.into_iter().filter_map(|item| item.calendar_data) {
+            events.extend(parse_events(&data, &calendar));
+        }
+    }
+
+    // servers without expand support send the unexpanded master event
+    let (start, end) = (iso(start), iso(end));
+    events.retain(|event| event.start < end && (event.end > start || event.start >= start));
+    // all-day events come first on their day
+    events.sort_by(|a, b| (&a.start, !a.all_day, &a.title).cmp(&(&b.start, !b.all_day, &b.title)));
+    Ok(events)
+}
+
+// TEXT value escaping (RFC 5545 3.3.11)
+pub fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// content lines are folded at 75 octets, never inside a character
+pub fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 64);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+fn to_vevent(
+    uid: &str,
+    title: &str,
+    description: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    related_to: Option<&str>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        concat!(
+            "PRODID:-//caldav-tasks//",
+            env!("CARGO_PKG_VERSION"),
+            "//EN"
+        )
+        .to_string(),
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{uid}"),
+        format!("DTSTAMP:{}", caldav_time(Utc::now())),
+        format!("DTSTART:{}", caldav_time(start)),
+        format!("DTEND:{}", caldav_time(end)),
+        format!("SUMMARY:{}", escape(title)),
+    ];
+    if !description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(description)));
+    }
+    if let Some(task_uid) = related_to {
+        lines.push(format!("RELATED-TO:{task_uid}"));
+    }
+    lines.extend(["END:VEVENT".to_string(), "END:VCALENDAR".to_string()]);
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+/// time-block a task: create an event with its title and description in the event
+/// calendar at `calendar` (a url from `fetch_events`). a linked task stays and starts with
+/// the event, which points back to it with RELATED-TO; otherwise the task is deleted.
+/// returns the url of the event
+#[tauri::command]
+pub async fn promote_to_event(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    uid: String,
+    start: String,
+    end: String,
+    calendar: String,
+    keep_linked: bool,
+) -> Result<String, String> {
+    read_only::ensure_writable()?;
+    lock::ensure_unlocked(&app_handle).await?;
+    let (start, end) = (parse_range(&start)?, parse_range(&end)?);
+    if end <= start {
+        return Err("The event must end after it starts".to_string());
+    }
+
+    let pool = db::pool(&app_handle).await?;
+    let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM tasks WHERE uid = ?")
+        .bind(&uid)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some(mut task) = fetch_by_ids(&pool, &ids).await?.into_iter().next() else {
+        return Err(format!("Task {uid} not found"));
+    };
+
+    let event_uid = new_id();
+    let url = format!("{}/{event_uid}.ics", calendar.trim_end_matches('/'));
+    let ics = to_vevent(
+        &event_uid,
+        &task.title,
+        &task.description,
+        start,
+        end,
+        keep_linked.then_some(task.uid.as_str()),
+    );
+    let response = CalDavClient::new(&account)?.put_ics(&url, &ics).await?;
+    if !matches!(response.status, 200..=299) {
+        return Err(format!(
+            "Failed to create the event (HTTP {})",
+            response.status
+        ));
+    }
+    log::info!("Created an event from a task");
+
+    if keep_linked {
+        task.start_date = Some(iso(start));
+        task.start_date_all_day = false;
+        task.modified_at = now_iso();
+        task.synced = false;
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        save_task(&mut tx, &task).await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        TaskChanges {
+            updated: vec![task.id],
+            ..TaskChanges::default()
+        }
+        .emit(&app_handle, &pool)
+        .await;
+    } else {
+        bulk::update(&app_handle, &[task.id], &BulkOp::Delete).await?;
+    }
+    Ok(url)
+}
@@ -0,0 +1,374 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::collation;
+use crate::{db, lock};
+
+// fuzzy matching of task titles and list names for a quick-open palette and command line
+// pickers: the letters of each query word in order, anywhere in a title ("bmlk" finds "Buy
+// milk"), or a word with a typo or two ("mlik"). words may come in any order. matching ignores
+// case and accents like the rest of search
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+
+// points of a match, tuned so whole words at the start of a title come first
+const CHAR: i64 = 10;
+const WORD_START: i64 = 8;
+const CONSECUTIVE: i64 = 6;
+const TITLE_START: i64 = 12;
+// per character skipped between two matched ones, up to MAX_GAP_PENALTY per word
+const GAP: i64 = 1;
+const MAX_GAP_PENALTY: i64 = 15;
+// per edit of a typo match, which always ranks below an in-order match of the same word
+const TYPO: i64 = 12;
+const COMPLETED: i64 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FuzzyKind {
+    Task,
+    List,
+}
+
+/// a task or list found by fuzzy_find
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzyMatch {
+    pub kind: FuzzyKind,
+    /// the task or calendar id
+    pub id: String,
+    pub title: String,
+    pub calendar_id: Option<String>,
+    /// the list of a task, the account of a list
+    pub list: String,
+    pub completed: bool,
+    pub score: i64,
+    /// matched characters of the title, as indices of its unicode code points, to highlight
+    pub positions: Vec<usize>,
+}
+
+// the folded characters of a text, each with the index of the character it came from
+fn fold_chars(text: &str) -> Vec<(usize, char)> {
+    text.chars()
+        .enumerate()
+        .flat_map(|(index, c)| {
+            collation::fold(c.encode_utf8(&mut [0; 4]))
+                .chars()
+                .map(move |folded| (index, folded))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn is_word_start(text: &[(usize, char)], at: usize) -> bool {
+    at == 0 || !text[at - 1].1.is_alphanumeric()
+}
+
+// the positions of `word` in order from `start`; with `word_starts`, a letter at the start of
+// a word is taken over the same letter earlier inside one
+fn walk(
+    word: &[char],
+    text: &[(usize, char)],
+    start: usize,
+    word_starts: bool,
+) -> Option<Vec<usize>> {
+    let mut matched = vec![start];
+    let mut at = start + 1;
+    for &c in &word[1..] {
+        let rest = at..text.len();
+        let preferred = if word_starts {
+            rest.clone()
+                .find(|&i| text[i].1 == c && (i == at || is_word_start(text, i)))
+        } else {
+            None
+        };
+        let i = preferred.or_else(|| rest.clone().find(|&i| text[i].1 == c))?;
+        matched.push(i);
+        at = i + 1;
+    }
+    Some(matched)
+}
+
+fn subsequence_score(text: &[(usize, char)], matched: &[usize]) -> i64 {
+    let mut score = 0;
+    let mut gaps = 0;
+    for (n, &i) in matched.iter().enumerate() {
+        score += CHAR;
+        if is_word_start(text, i) {
+            score += WORD_START;
+        }
+        if n > 0 {
+            let previous = matched[n - 1];
+            if i == previous + 1 {
+                score += CONSECUTIVE;
+            } else {
+                gaps += (i - previous - 1) as i64;
+            }
+        }
+    }
+    if matched[0] == 0 {
+        score += TITLE_START;
+    }
+    score - (gaps * GAP).min(MAX_GAP_PENALTY)
+}
+
+// the best in-order match of `word`, trying each occurrence of its first letter
+fn subsequence(word: &[char], text: &[(usize, char)]) -> Option<(i64, Vec<usize>)> {
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..text.len()).filter(|&start| text[start].1 == word[0]) {
+        // the earliest letters always fit when anything does; later starts only have less
+        // text left
+        let Some(earliest) = walk(word, text, start, false) else {
+            break;
+        };
+        for matched in [walk(word, text, start, true), Some(earliest)]
+            .into_iter()
+            .flatten()
+        {
+            let score = subsequence_score(text, &matched);
+            if best.as_ref().is_none_or(|(best, _)| score > *best) {
+                best = Some((score, matched));
+            }
+        }
+    }
+    best
+}
+
+// edits (insert, delete, replace, swap of neighbours) between two words
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+// edits a word of this length may have and still match
+fn allowed_typos(len: usize) -> usize {
+    match len {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+// the word of the text closest to `word`, compared with its start so half-typed words match
+fn typo(word: &[char], text: &[(usize, char)]) -> Option<(i64, Vec<usize>)> {
+    let allowed = allowed_typos(word.len());
+    if allowed == 0 {
+        return None;
+    }
+    let mut best: Option<(usize, Vec<usize>)> = None;
+    let mut start = 0;
+    while start < text.len() {
+        let end = (start..text.len())
+            .find(|&i| !text[i].1.is_alphanumeric())
+            .unwrap_or(text.len());
+        if end > start {
+            let candidate: Vec<char> = text[start..end].iter().map(|&(_, c)| c).collect();
+            // a prefix up to one letter longer than the word, for a missing letter
+            let compared = &candidate[..candidate.len().min(word.len() + 1)];
+            let distance = (word.len().saturating_sub(1)..=compared.len())
+                .filter(|&len| len > 0)
+                .map(|len| edit_distance(word, &compared[..len]))
+                .min()
+                .unwrap_or(usize::MAX);
+            if distance <= allowed && best.as_ref().is_none_or(|(best, _)| distance < *best) {
+                let len = compared.len().min(word.len());
+                best = Some((distance, (start..start + len).collect()));
+            }
+        }
+        start = end + 1;
+    }
+    best.map(|(distance, matched)| {
+        let score = matched.len() as i64 * CHAR + WORD_START - distance as i64 * TYPO;
+        (score, matched)
+    })
+}
+
+// the score and matched characters of the whole query; None unless every word matches
+fn score(words: &[Vec<char>], title: &str) -> Option<(i64, Vec<usize>)> {
+    let text = fold_chars(title);
+    let mut total = 0;
+    let mut positions = Vec::new();
+    for word in words {
+        let (score, matched) = subsequence(word, &text).or_else(|| typo(word, &text))?;
+        total += score;
+        positions.extend(matched.into_iter().map(|i| text[i].0));
+    }
+    positions.sort_unstable();
+    positions.dedup();
+    Some((total, positions))
+}
+
+/// the tasks and lists best matching `query`, best first
+pub async fn find(
+    pool: &SqlitePool,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<Vec<FuzzyMatch>, String> {
+    let words: Vec<Vec<char>> = collation::fold(query)
+        .split_whitespace()
+        .map(|word| word.chars().collect())
+        .collect();
+    if words.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let tasks: Vec<(String, String, Option<String>, String, bool)> = sqlx::query_as(
+        "SELECT tasks.id, tasks.title, tasks.calendar_id,
+            COALESCE(calendars.display_name, ''), tasks.completed
+         FROM tasks LEFT JOIN calendars ON calendars.id = tasks.calendar_id
+         WHERE tasks.archived_at IS NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let lists: Vec<(String, String, String)> = sqlx::query_as(
+        "SELECT calendars.id, calendars.display_name, COALESCE(accounts.name, '')
+         FROM calendars LEFT JOIN accounts ON accounts.id = calendars.account_id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let task_matches = tasks
+        .into_iter()
+        .filter_map(|(id, title, calendar_id, list, completed)| {
+            let (score, positions) = score(&words, &title)?;
+            Some(FuzzyMatch {
+                kind: FuzzyKind::Task,
+                id,
+                title,
+                calendar_id,
+                list,
+                completed,
+                score: if completed { score - COMPLETED } else { score },
+                positions,
+            })
+        });
+    let list_matches = lists.into_iter().filter_map(|(id, name, account)| {
+        let (score, positions) = score(&words, &name)?;
+        Some(FuzzyMatch {
+            kind: FuzzyKind::List,
+            calendar_id: Some(id.clone()),
+            id,
+            title: name,
+            list: account,
+            completed: false,
+            score,
+            positions,
+        })
+    });
+
+    let mut matches: Vec<FuzzyMatch> = task_matches.chain(list_matches).collect();
+    // shorter titles have less that didn't match
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.title.chars().count().cmp(&b.title.chars().count()))
+            .then_with(|| collation::natural_cmp(&a.title, &b.title))
+    });
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// fuzzy search of task titles and list names for a quick-open palette; tolerates letters
+/// left out and small typos
+#[tauri::command]
+pub async fn fuzzy_find(
+    app_handle: tauri::AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<FuzzyMatch>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    find(&pool, &query, limit).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(query: &str) -> Vec<Vec<char>> {
+        collation::fold(query)
+            .split_whitespace()
+            .map(|word| word.chars().collect())
+            .collect()
+    }
+
+    fn chars(word: &str) -> Vec<char> {
+        word.chars().collect()
+    }
+
+    #[test]
+    fn letters_in_order() {
+        let (_, positions) = score(&words("bmlk"), "Buy milk").expect("should match");
+        assert_eq!(positions, vec![0, 4, 6, 7]);
+        // the 'm' starting a word is taken over the one inside "bomb"
+        let (_, positions) = score(&words("bm"), "bomb maker").expect("should match");
+        assert_eq!(positions, vec![0, 5]);
+        assert!(score(&words("klm"), "Buy milk").is_none());
+    }
+
+    #[test]
+    fn words_in_any_order() {
+        assert!(score(&words("milk buy"), "Buy milk").is_some());
+        // every word has to match
+        assert!(score(&words("milk zzz"), "Buy milk").is_none());
+    }
+
+    #[test]
+    fn accents_and_case() {
+        let (_, positions) = score(&words("CAFE"), "Café crème").expect("should match");
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+        // positions count characters, not the bytes or the folded text
+        let (_, positions) = score(&words("creme"), "Buy crème brûlée").expect("should match");
+        assert_eq!(positions, vec![4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn better_matches_score_higher() {
+        let (start, _) = score(&words("milk"), "milk and eggs").unwrap();
+        let (middle, _) = score(&words("milk"), "buy milk").unwrap();
+        let (scattered, _) = score(&words("milk"), "mail link").unwrap();
+        assert!(start > middle);
+        assert!(middle > scattered);
+    }
+
+    #[test]
+    fn typos() {
+        let (typo, positions) = score(&words("mlik"), "Buy milk").expect("should match");
+        assert_eq!(positions, vec![4, 5, 6, 7]);
+        let (exact, _) = score(&words("milk"), "Buy milk").unwrap();
+        assert!(typo < exact);
+        // a half-typed word with its letters swapped
+        assert!(score(&words("gorc"), "Groceries").is_some());
+        // too short for a typo
+        assert!(score(&words("xy"), "my list").is_none());
+        assert!(score(&words("xilk"), "Buy milk").is_some());
+        assert!(score(&words("xylk"), "Buy milk").is_none());
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(edit_distance(&chars("mlik"), &chars("milk")), 1);
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(edit_distance(&chars(""), &chars("abc")), 3);
+        assert_eq!(allowed_typos(2), 0);
+        assert_eq!(allowed_typos(5), 1);
+        assert_eq!(allowed_typos(6), 2);
+    }
+}
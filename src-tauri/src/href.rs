@@ -0,0 +1,166 @@
+use percent_encoding::{percent_decode_str, percent_encode, AsciiSet, CONTROLS};
+use std::borrow::Cow;
+use tauri_plugin_http::reqwest::Url;
+
+// servers disagree on how they write hrefs: raw UTF-8 and spaces, lowercase or uppercase
+// escapes, escapes escaped a second time, paths or full urls. every href is brought into one
+// form before it's requested, stored or compared, so a resource always has the same href
+
+// what a path segment keeps escaped: all but the RFC 3986 unreserved characters and the
+// sub-delims, ':' and '@' that may stand in a segment as they are. non-ASCII is always escaped
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+// whether every '%' in the text starts an escape, i.e. it still reads as escaped
+fn is_escaped(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.contains(&b'%')
+        && bytes
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == b'%')
+            .all(|(i, _)| {
+                bytes
+                    .get(i + 1..i + 3)
+                    .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            })
+}
+
+fn normalize_segment(segment: &str) -> String {
+    let mut bytes: Vec<u8> = percent_decode_str(segment).collect();
+    // escaped twice, like "%2520" for a space: no server names a resource "%20" on purpose
+    if let Ok(once) = std::str::from_utf8(&bytes) {
+        if is_escaped(once) {
+            let twice: Vec<u8> = percent_decode_str(once).collect();
+            if std::str::from_utf8(&twice).is_ok() {
+                bytes = twice;
+            }
+        }
+    }
+    percent_encode(&bytes, SEGMENT).to_string()
+}
+
+/// `url` with every path segment escaped the same way
+pub fn normalize(mut url: Url) -> Url {
+    let path = url
+        .path()
+        .split('/')
+        .map(normalize_segment)
+        .collect::<Vec<_>>()
+        .join("/");
+    url.set_path(&path);
+    url
+}
+
+/// resolve an href, a path or a full url, against the url it came from and normalize it
+pub fn resolve(base: &str, href: &str) -> Option<String> {
+    let href = href.trim();
+    // hrefs taken from a body with a regex still have their xml entities
+    let href = quick_xml::escape::unescape(href).unwrap_or(Cow::Borrowed(href));
+    // DAV resources have no query or fragment, so a raw '?' or '#' is part of a name
+    let href = href.replace('?', "%3F").replace('#', "%23");
+    Url::parse(base)
+        .and_then(|base| base.join(&href))
+        .map(|url| normalize(url).to_string())
+        .ok()
+}
+
+/// the normalized form of each href, resolved against `base`; one that can't be resolved
+/// is returned as it is
+#[tauri::command]
+pub fn normalize_hrefs(base: String, hrefs: Vec<String>) -> Vec<String> {
+    hrefs
+        .into_iter()
+        .map(|href| resolve(&base, &href).unwrap_or(href))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://dav.example.com/cal/";
+
+    fn resolved(href: &str) -> String {
+        resolve(BASE, href).expect("href should resolve")
+    }
+
+    #[test]
+    fn paths_and_urls() {
+        assert_eq!(resolved("task.ics"), "https://dav.example.com/cal/task.ics");
+        assert_eq!(
+            resolved("/cal/task.ics"),
+            "https://dav.example.com/cal/task.ics"
+        );
+        assert_eq!(
+            resolved("  https://other.example.com/x/task.ics "),
+            "https://other.example.com/x/task.ics"
+        );
+    }
+
+    #[test]
+    fn escapes_are_normalized() {
+        let expected = "https://dav.example.com/cal/my%20caf%C3%A9.ics";
+        assert_eq!(resolved("my café.ics"), expected);
+        assert_eq!(resolved("my%20caf%c3%a9.ics"), expected);
+        assert_eq!(resolved("my%2520caf%25C3%25A9.ics"), expected);
+        // unreserved characters are never escaped
+        assert_eq!(
+            resolved("%7Euser.ics"),
+            "https://dav.example.com/cal/~user.ics"
+        );
+    }
+
+    #[test]
+    fn literal_percent_is_kept() {
+        // decoded once this no longer reads as escaped, so it's a real '%'
+        assert_eq!(
+            resolved("100%25done.ics"),
+            "https://dav.example.com/cal/100%25done.ics"
+        );
+    }
+
+    #[test]
+    fn reserved_characters() {
+        assert_eq!(
+            resolved("what?.ics"),
+            "https://dav.example.com/cal/what%3F.ics"
+        );
+        assert_eq!(
+            resolved("no#1.ics"),
+            "https://dav.example.com/cal/no%231.ics"
+        );
+        assert_eq!(
+            resolved("a&amp;b.ics"),
+            "https://dav.example.com/cal/a&b.ics"
+        );
+    }
+
+    #[test]
+    fn unresolvable_hrefs_are_kept() {
+        let hrefs = vec!["/cal/a b.ics".to_string()];
+        assert_eq!(
+            normalize_hrefs("not a url".to_string(), hrefs.clone()),
+            hrefs
+        );
+        assert_eq!(
+            normalize_hrefs(BASE.to_string(), hrefs),
+            vec!["https://dav.example.com/cal/a%20b.ics".to_string()]
+        );
+    }
+}
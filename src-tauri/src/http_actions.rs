@@ -0,0 +1,495 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use std::time::Duration;
+use tauri_plugin_http::reqwest::{self, header, Method, Url};
+
+use crate::tasks::{fetch_by_ids, new_id, now_iso, Task};
+use crate::webhooks::{later, TaskEvent, RETRY_DELAYS};
+use crate::{caldav, db, lock, read_only, settings};
+
+// until when completions were looked at; nothing completed before the first run is sent
+const CHECKED_KEY: &str = "http_actions_checked_at";
+const RUN_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+const LOG_DAYS: i64 = 30;
+const PENDING_RUN: &str = "SELECT http_action_runs.id, method, http_action_runs.url, content_type,
+        http_action_runs.body, attempts
+    FROM http_action_runs JOIN http_actions ON http_actions.id = http_action_runs.action_id";
+
+lazy_static! {
+    // a scheduled run and a test must not send the same pending run twice
+    static ref SENDING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// what completing sets off an action: one task (by uid) or every task of a list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionTarget {
+    Task,
+    List,
+}
+
+impl ActionTarget {
+    fn name(self) -> &'static str {
+        match self {
+            ActionTarget::Task => "task",
+            ActionTarget::List => "list",
+        }
+    }
+}
+
+/// a request sent when a task is completed; `{{placeholders}}` in the url and body are
+/// filled in from the task
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpAction {
+    /// empty for a new action
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[sqlx(try_from = "String")]
+    pub target: ActionTarget,
+    /// the task's uid or the list's id
+    pub target_id: String,
+    pub method: String,
+    pub url: String,
+    pub content_type: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+impl TryFrom<String> for ActionTarget {
+    type Error = String;
+
+    fn try_from(target: String) -> Result<Self, Self::Error> {
+        match target.as_str() {
+            "task" => Ok(ActionTarget::Task),
+            "list" => Ok(ActionTarget::List),
+            _ => Err(format!("Unknown action target \"{target}\"")),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// one entry of the run log, with the request as it was rendered
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpActionRun {
+    pub id: String,
+    pub action_id: String,
+    pub task_uid: Option<String>,
+    pub url: String,
+    pub body: String,
+    /// pending, delivered or failed
+    pub status: String,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub next_attempt_at: Option<String>,
+    pub delivered_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct PendingRun {
+    id: String,
+    method: String,
+    url: String,
+    content_type: String,
+    body: String,
+    attempts: i64,
+}
+
+// the values of the placeholders for a task
+fn fields(task: &Task, list: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("title", task.title.clone()),
+        ("description", task.description.clone()),
+        ("uid", task.uid.clone()),
+        ("list", list.to_string()),
+        ("priority", task.priority.clone()),
+        ("dueDate", task.due_date.clone().unwrap_or_default()),
+        ("completedAt", task.completed_at.clone().unwrap_or_default()),
+        ("url", task.url.clone().unwrap_or_default()),
+    ]
+}
+
+/// `value` as it goes into a url or form body
+pub fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+// fill in `{{name}}` placeholders, each value encoded to fit where it goes
+fn render(template: &str, fields: &[(&str, String)], encode: impl Fn(&str) -> String) -> String {
+    fields
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{{name}}}}}"), &encode(value))
+        })
+}
+
+// how placeholder values are written into a body of this type
+fn body_encoder(content_type: &str) -> fn(&str) -> String {
+    let content_type = content_type.to_ascii_lowercase();
+    if content_type.contains("json") {
+        // inside a json string: the template has the quotes
+        |value| {
+            let quoted = Value::String(value.to_string()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+    } else if content_type.contains("x-www-form-urlencoded") {
+        percent_encode
+    } else {
+        str::to_string
+    }
+}
+
+async fn load_actions(pool: &SqlitePool) -> Result<Vec<HttpAction>, String> {
+    sqlx::query_as("SELECT * FROM http_actions ORDER BY created_at")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// queue a run of every enabled action on the task or its list, or just of `only` (enabled
+// or not); returns the run ids
+async fn queue(pool: &SqlitePool, task: &Task, only: Option<&str>) -> Result<Vec<String>, String> {
+    let actions: Vec<HttpAction> = sqlx::query_as(
+        "SELECT * FROM http_actions
+         WHERE ((target = 'task' AND target_id = ?1) OR (target = 'list' AND target_id = ?2))
+            AND (?3 IS NULL OR id = ?3) AND (enabled = 1 OR ?3 IS NOT NULL)",
+    )
+    .bind(&task.uid)
+    .bind(&task.calendar_id)
+    .bind(only)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if actions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let list: Option<String> =
+        sqlx::query_scalar("SELECT display_name FROM calendars WHERE id = ?")
+            .bind(&task.calendar_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let fields = fields(task, list.as_deref().unwrap_or_default());
+
+    let mut ids = Vec::new();
+    for action in actions {
+        let id = new_id();
+        let now = now_iso();
+        sqlx::query(
+            "INSERT INTO http_action_runs
+                (id, action_id, task_uid, url, body, created_at, next_attempt_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&action.id)
+        .bind(&task.uid)
+        .bind(render(&action.url, &fields, percent_encode))
+        .bind(render(
+            &action.body,
+            &fields,
+            body_encoder(&action.content_type),
+        ))
+        .bind(&now)
+        .bind(&now)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        ids.push(id);
+    }
+    Ok(ids)
+}
+
+// queue runs for the tasks completed since the last run
+async fn queue_completions(pool: &SqlitePool) -> Result<(), String> {
+    let now = now_iso();
+    let Some(since) = settings::get(pool, CHECKED_KEY).await? else {
+        return settings::set(pool, CHECKED_KEY, &now).await;
+    };
+    let ids = TaskEvent::Completed.task_ids(pool, &since, &now).await?;
+    for task in fetch_by_ids(pool, &ids).await? {
+        queue(pool, &task, None).await?;
+    }
+    settings::set(pool, CHECKED_KEY, &now).await
+}
+
+async fn send(client: &reqwest::Client, pool: &SqlitePool, run: PendingRun) -> Result<(), String> {
+    let method = Method::from_bytes(run.method.as_bytes()).map_err(|e| e.to_string())?;
+    let mut request = client.request(method, &run.url);
+    if !run.body.is_empty() {
+        request = request
+            .header(header::CONTENT_TYPE, &run.content_type)
+            .body(run.body);
+    }
+
+    let (status, error) = match request.send().await {
+        Ok(response) if response.status().is_success() => {
+            sqlx::query(
+                "UPDATE http_action_runs
+                 SET status = 'delivered', attempts = attempts + 1, response_status = ?,
+                    error = NULL, next_attempt_at = NULL, delivered_at = ?
+                 WHERE id = ?",
+            )
+            .bind(response.status().as_u16())
+            .bind(now_iso())
+            .bind(&run.id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            return Ok(());
+        }
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            format!("Server answered {}", response.status()),
+        ),
+        Err(e) => (None, caldav::error_chain(&e)),
+    };
+
+    let retry = RETRY_DELAYS.get(run.attempts as usize).copied();
+    log::warn!(
+        "HTTP action run {} failed (attempt {}): {error}",
+        run.id,
+        run.attempts + 1
+    );
+    sqlx::query(
+        "UPDATE http_action_runs
+         SET status = ?, attempts = attempts + 1, response_status = ?, error = ?,
+            next_attempt_at = ?
+         WHERE id = ?",
+    )
+    .bind(if retry.is_some() { "pending" } else { "failed" })
+    .bind(status)
+    .bind(&error)
+    .bind(retry.map(later))
+    .bind(&run.id)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("caldav-tasks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+async fn send_pending(pool: &SqlitePool) -> Result<(), String> {
+    let _sending = SENDING.lock().await;
+    let pending: Vec<PendingRun> = sqlx::query_as(&format!(
+        "{PENDING_RUN}
+         WHERE status = 'pending' AND next_attempt_at <= ? AND http_actions.enabled = 1
+         ORDER BY http_action_runs.created_at"
+    ))
+    .bind(now_iso())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let client = client()?;
+    for run in pending {
+        send(&client, pool, run).await?;
+    }
+    Ok(())
+}
+
+/// queue runs for new completions, send what's due and trim the run log
+#[tracing::instrument(name = "http_actions", level = "trace", skip_all, err(level = "debug"))]
+pub async fn run(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    if read_only::is_enabled() {
+        return Ok(());
+    }
+    let pool = db::pool(app_handle).await?;
+    queue_completions(&pool).await?;
+    send_pending(&pool).await?;
+
+    sqlx::query("DELETE FROM http_action_runs WHERE status != 'pending' AND created_at < ?")
+        .bind(later(-LOG_DAYS * 24 * 60 * 60))
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// look for completed tasks and send their actions every minute
+pub fn start_scheduled_runs(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(RUN_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run(&app_handle).await {
+                log::warn!("HTTP action run failed: {e}");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_http_actions(app_handle: tauri::AppHandle) -> Result<Vec<HttpAction>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    load_actions(&pool).await
+}
+
+/// add an action (with an empty id) or change one
+#[tauri::command]
+pub async fn save_http_action(
+    app_handle: tauri::AppHandle,
+    mut action: HttpAction,
+) -> Result<HttpAction, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    action.name = action.name.trim().to_string();
+    if action.name.is_empty() {
+        return Err("Actions need a name".to_string());
+    }
+    action.method = action.method.trim().to_ascii_uppercase();
+    if !METHODS.contains(&action.method.as_str()) {
+        return Err(format!("Unsupported method {}", action.method));
+    }
+    action.url = action.url.trim().to_string();
+    // placeholders can't make an invalid url valid, so check it without them
+    let parsed = Url::parse(&render(&action.url, &[], str::to_string))
+        .map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Action URLs have to start with http:// or https://".to_string());
+    }
+    if action.target_id.is_empty() {
+        return Err(format!("Choose the {} of the action", action.target.name()));
+    }
+
+    if action.id.is_empty() {
+        action.id = new_id();
+        action.created_at = now_iso();
+    }
+    sqlx::query(
+        "INSERT INTO http_actions
+            (id, name, target, target_id, method, url, content_type, body, enabled, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name, target = excluded.target, target_id = excluded.target_id,
+            method = excluded.method, url = excluded.url, content_type = excluded.content_type,
+            body = excluded.body, enabled = excluded.enabled",
+    )
+    .bind(&action.id)
+    .bind(&action.name)
+    .bind(action.target.name())
+    .bind(&action.target_id)
+    .bind(&action.method)
+    .bind(&action.url)
+    .bind(&action.content_type)
+    .bind(&action.body)
+    .bind(action.enabled)
+    .bind(&action.created_at)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(action)
+}
+
+/// remove an action with its run log
+#[tauri::command]
+pub async fn delete_http_action(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("DELETE FROM http_actions WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// the latest runs, of one action or of all of them
+#[tauri::command]
+pub async fn get_http_action_runs(
+    app_handle: tauri::AppHandle,
+    action_id: Option<String>,
+    limit: u32,
+) -> Result<Vec<HttpActionRun>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as(
+        "SELECT id, action_id, task_uid, url, body, status, attempts, response_status, error,
+            created_at, next_attempt_at, delivered_at
+         FROM http_action_runs
+         WHERE ?1 IS NULL OR action_id = ?1
+         ORDER BY created_at DESC
+         LIMIT ?2",
+    )
+    .bind(&action_id)
+    .bind(limit)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// run an action for one of its tasks right away, even when disabled; returns the run
+#[tauri::command]
+pub async fn test_http_action(
+    app_handle: tauri::AppHandle,
+    id: String,
+    task_id: String,
+) -> Result<HttpActionRun, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let Some(task) = fetch_by_ids(&pool, std::slice::from_ref(&task_id))
+        .await?
+        .pop()
+    else {
+        return Err(format!("Task {task_id} not found"));
+    };
+    let Some(run_id) = queue(&pool, &task, Some(&id)).await?.pop() else {
+        return Err("The action doesn't apply to this task".to_string());
+    };
+
+    {
+        let _sending = SENDING.lock().await;
+        let pending: PendingRun =
+            sqlx::query_as(&format!("{PENDING_RUN} WHERE http_action_runs.id = ?"))
+                .bind(&run_id)
+                .fetch_one(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        send(&client()?, &pool, pending).await?;
+    }
+
+    sqlx::query_as(
+        "SELECT id, action_id, task_uid, url, body, status, attempts, response_status, error,
+            created_at, next_attempt_at, delivered_at
+         FROM http_action_runs WHERE id = ?",
+    )
+    .bind(&run_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,290 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::fmt::{Display, Write};
+use std::sync::Mutex;
+use tauri::Emitter;
+
+use crate::{db, read_only, settings, streaks, tray};
+
+// text the backend shows by itself (menus, the tray, notifications, reports, errors) in the
+// language picked in the settings, or the OS one. the english text doubles as the message id,
+// so anything without a translation stays english
+
+const LANGUAGE_KEY: &str = "language";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    // "de", "de-AT" or "de_DE.UTF-8"
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag.split(['-', '_', '.']).next()?.to_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    fn system() -> Self {
+        tauri_plugin_os::locale()
+            .and_then(|locale| Self::from_tag(&locale))
+            .unwrap_or(Language::English)
+    }
+}
+
+lazy_static! {
+    static ref CURRENT: Mutex<Language> = Mutex::new(Language::system());
+}
+
+const GERMAN: &[(&str, &str)] = &[
+    // app menu
+    ("File", "Ablage"),
+    ("Edit", "Bearbeiten"),
+    ("View", "Darstellung"),
+    ("Calendar", "Kalender"),
+    ("Window", "Fenster"),
+    ("Help", "Hilfe"),
+    ("New Task", "Neue Aufgabe"),
+    ("Sync", "Synchronisieren"),
+    ("Preferences...", "Einstellungen..."),
+    ("Search Tasks...", "Aufgaben suchen..."),
+    ("Import...", "Importieren..."),
+    ("Export...", "Exportieren..."),
+    ("Add Account...", "Konto hinzufügen..."),
+    ("Add Calendar...", "Kalender hinzufügen..."),
+    ("Show Completed Tasks", "Erledigte Aufgaben anzeigen"),
+    ("Keyboard Shortcuts", "Tastaturkürzel"),
+    ("Sort By", "Sortieren nach"),
+    ("Manual", "Manuell"),
+    ("Smart Sort", "Intelligent"),
+    ("Due Date", "Fälligkeit"),
+    ("Priority", "Priorität"),
+    ("Title", "Titel"),
+    ("Date Created", "Erstellungsdatum"),
+    ("Date Modified", "Änderungsdatum"),
+    ("About caldav-tasks", "Über caldav-tasks"),
+    ("Services", "Dienste"),
+    ("Hide caldav-tasks", "caldav-tasks ausblenden"),
+    ("Hide Others", "Andere ausblenden"),
+    ("Show All", "Alle einblenden"),
+    ("Quit caldav-tasks", "caldav-tasks beenden"),
+    ("Close Window", "Fenster schließen"),
+    ("Undo", "Widerrufen"),
+    ("Redo", "Wiederholen"),
+    ("Cut", "Ausschneiden"),
+    ("Copy", "Kopieren"),
+    ("Paste", "Einsetzen"),
+    ("Select All", "Alles auswählen"),
+    ("Enter Full Screen", "Vollbildmodus aktivieren"),
+    ("Minimize", "Im Dock ablegen"),
+    ("Zoom", "Zoomen"),
+    // tray
+    ("Show Window", "Fenster anzeigen"),
+    ("Sync Now", "Jetzt synchronisieren"),
+    ("Quit", "Beenden"),
+    ("Last sync: {}", "Zuletzt synchronisiert: {}"),
+    ("Last sync: Never", "Noch nie synchronisiert"),
+    ("Last sync: Syncing...", "Synchronisiert gerade..."),
+    ("Recently Completed", "Kürzlich erledigt"),
+    ("Undo \"{}\"", "„{}“ wieder öffnen"),
+    (
+        "Can't reach {}, retrying at {}",
+        "{} nicht erreichbar, neuer Versuch um {}",
+    ),
+    ("Can't reach {}", "{} nicht erreichbar"),
+    ("Can't reach {} servers", "{} Server nicht erreichbar"),
+    ("{}-day streak", "{} Tage in Folge"),
+    ("Focus {}", "Fokus {}"),
+    ("Break {}", "Pause {}"),
+    ("Focus {} (paused)", "Fokus {} (angehalten)"),
+    ("Break {} (paused)", "Pause {} (angehalten)"),
+    // notifications
+    ("Sync conflict", "Synchronisationskonflikt"),
+    (
+        "\"{}\" was also changed on the server",
+        "„{}“ wurde auch auf dem Server geändert",
+    ),
+    (
+        "\"{}\" was deleted on the server",
+        "„{}“ wurde auf dem Server gelöscht",
+    ),
+    ("Don't forget", "Nicht vergessen"),
+    ("Pomodoro finished", "Pomodoro beendet"),
+    ("Time for a {} minute break", "Zeit für {} Minuten Pause"),
+    ("Break is over", "Die Pause ist vorbei"),
+    (
+        "Ready for the next pomodoro?",
+        "Bereit für den nächsten Pomodoro?",
+    ),
+    // reports
+    ("Tasks report {} – {}", "Aufgabenbericht {} – {}"),
+    ("Completed", "Erledigt"),
+    ("Outstanding", "Offen"),
+    ("Nothing here.", "Keine Aufgaben."),
+    ("Local", "Lokal"),
+    ("Tags", "Tags"),
+    ("done {}", "erledigt am {}"),
+    ("due {}", "fällig am {}"),
+    ("overdue since {}", "überfällig seit {}"),
+    ("{} completed, {} outstanding", "{} erledigt, {} offen"),
+    // errors
+    ("App is locked", "Die App ist gesperrt"),
+    ("Wrong PIN", "Falsche PIN"),
+    ("No app PIN is set", "Es ist keine App-PIN festgelegt"),
+    (
+        "The PIN needs at least {} characters",
+        "Die PIN braucht mindestens {} Zeichen",
+    ),
+    (
+        "The app is in read-only mode",
+        "Die App ist im Nur-Lesen-Modus",
+    ),
+    (
+        "The app was started in read-only mode",
+        "Die App wurde im Nur-Lesen-Modus gestartet",
+    ),
+    (
+        "Demo data can only be generated in demo mode",
+        "Demodaten können nur im Demomodus erzeugt werden",
+    ),
+    (
+        "The database was written by a newer version of the app (schema {}, this version knows \
+         up to {}), update the app to make changes",
+        "Die Datenbank stammt von einer neueren Version der App (Schema {}, diese Version kennt \
+         bis {}), aktualisiere die App, um Änderungen vorzunehmen",
+    ),
+    (
+        "The size limit has to be at least {} KB",
+        "Die Größenbeschränkung muss mindestens {} KB betragen",
+    ),
+];
+
+/// the language backend text is shown in
+pub fn current() -> Language {
+    *CURRENT.lock().expect("Failed to lock CURRENT")
+}
+
+// the translation of `message` into the current language, if there is one
+fn lookup(message: &str) -> Option<&'static str> {
+    let catalog = match current() {
+        Language::English => return None,
+        Language::German => GERMAN,
+    };
+    catalog
+        .iter()
+        .find(|(id, _)| *id == message)
+        .map(|(_, translated)| *translated)
+}
+
+/// `message` in the current language
+pub fn t(message: &'static str) -> &'static str {
+    lookup(message).unwrap_or(message)
+}
+
+/// `message` in the current language with its `{}` placeholders filled in order
+pub fn t_with(message: &'static str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut out = String::new();
+    for (i, part) in t(message).split("{}").enumerate() {
+        if i > 0 {
+            if let Some(arg) = args.next() {
+                let _ = write!(out, "{arg}");
+            }
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+// menus and the tray are built once, so they're built again for another language
+fn apply(app_handle: &tauri::AppHandle, language: Language) {
+    let previous = std::mem::replace(
+        &mut *CURRENT.lock().expect("Failed to lock CURRENT"),
+        language,
+    );
+    if previous == language {
+        return;
+    }
+    log::info!("Backend language is now {}", language.code());
+    // the frontend builds the app menu again when it hears about it
+    tray::rebuild_tray_menu(app_handle);
+    if let Err(e) = app_handle.emit("language-changed", language) {
+        log::warn!("Failed to emit language-changed: {e}");
+    }
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = streaks::update_tray(&app_handle).await {
+            log::warn!("Failed to update the streak in the tray: {e}");
+        }
+    });
+}
+
+async fn chosen(pool: &SqlitePool) -> Result<Option<Language>, String> {
+    Ok(settings::get(pool, LANGUAGE_KEY)
+        .await?
+        .as_deref()
+        .and_then(Language::from_tag))
+}
+
+/// switch to the language picked in the settings once the database is open
+pub async fn load(app_handle: &tauri::AppHandle, pool: &SqlitePool) -> Result<(), String> {
+    let language = chosen(pool).await?.unwrap_or_else(Language::system);
+    apply(app_handle, language);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageSettings {
+    /// None to follow the OS
+    pub chosen: Option<Language>,
+    /// the language in use
+    pub active: Language,
+}
+
+#[tauri::command]
+pub async fn get_language(app_handle: tauri::AppHandle) -> Result<LanguageSettings, String> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(LanguageSettings {
+        chosen: chosen(&pool).await?,
+        active: current(),
+    })
+}
+
+/// `messages` in the current language, for the app menu the frontend builds
+#[tauri::command]
+pub fn translate(messages: Vec<String>) -> Vec<String> {
+    messages
+        .into_iter()
+        .map(|message| lookup(&message).map_or(message, str::to_string))
+        .collect()
+}
+
+/// the language of menus, the tray, notifications, reports and errors; None follows the OS
+#[tauri::command]
+pub async fn set_language(
+    app_handle: tauri::AppHandle,
+    language: Option<Language>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    match language {
+        Some(language) => settings::set(&pool, LANGUAGE_KEY, language.code()).await?,
+        None => settings::delete(&pool, LANGUAGE_KEY).await?,
+    }
+    apply(&app_handle, language.unwrap_or_else(Language::system));
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool};
+use std::collections::HashMap;
+
+use crate::{db, lock};
+
+// hrefs per IN (...) list
+const HREF_CHUNK: usize = 500;
+// rows written per transaction
+const WRITE_CHUNK: usize = 200;
+
+/// raw ICS of a calendar object as last fetched from the server
+#[derive(Debug, Clone, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedIcs {
+    pub href: String,
+    pub etag: String,
+    pub ics: String,
+}
+
+/// href -> etag of everything cached for a calendar
+pub async fn etags(
+    pool: &SqlitePool,
+    calendar_id: &str,
+) -> Result<HashMap<String, String>, String> {
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT href, etag FROM ics_cache WHERE calendar_id = ?")
+            .bind(calendar_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().collect())
+}
+
+/// store freshly fetched objects, replacing older versions of the same href
+pub async fn store(
+    pool: &SqlitePool,
+    calendar_id: &str,
+    objects: &[CachedIcs],
+) -> Result<(), String> {
+    let fetched_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    for chunk in objects.chunks(WRITE_CHUNK) {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        for object in chunk {
+            sqlx::query(
+                "INSERT INTO ics_cache (href, calendar_id, etag, ics, fetched_at)
+                 VALUES (?, ?, ?, ?, ?)
+                 ON CONFLICT(href) DO UPDATE SET
+                    calendar_id = excluded.calendar_id, etag = excluded.etag,
+                    ics = excluded.ics, fetched_at = excluded.fetched_at",
+            )
+            .bind(&object.href)
+            .bind(calendar_id)
+            .bind(&object.etag)
+            .bind(&object.ics)
+            .bind(&fetched_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// drop cached objects that are no longer on the server
+pub async fn remove(pool: &SqlitePool, hrefs: &[String]) -> Result<(), String> {
+    for chunk in hrefs.chunks(HREF_CHUNK) {
+        let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM ics_cache WHERE href IN (");
+        let mut separated = query.separated(", ");
+        for href in chunk {
+            separated.push_bind(href.clone());
+        }
+        query.push(")");
+        query
+            .build()
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// cached ICS for the given hrefs; hrefs that aren't cached are left out
+#[tauri::command]
+pub async fn get_cached_objects(
+    app_handle: tauri::AppHandle,
+    hrefs: Vec<String>,
+) -> Result<Vec<CachedIcs>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let mut objects = Vec::with_capacity(hrefs.len());
+    for chunk in hrefs.chunks(HREF_CHUNK) {
+        let mut query =
+            QueryBuilder::<Sqlite>::new("SELECT href, etag, ics FROM ics_cache WHERE href IN (");
+        let mut separated = query.separated(", ");
+        for href in chunk {
+            separated.push_bind(href.clone());
+        }
+        query.push(")");
+        let rows: Vec<CachedIcs> = query
+            .build_query_as()
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        objects.extend(rows);
+    }
+    Ok(objects)
+}
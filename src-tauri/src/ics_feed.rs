@@ -0,0 +1,171 @@
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri_plugin_http::reqwest::Url;
+
+use crate::api_server::{self, Response};
+use crate::events::{caldav_time, escape, fold};
+use crate::{credentials, db, lock, read_only, settings};
+
+// a read-only iCalendar feed of the open tasks of chosen lists, their due dates as all-day
+// events, so a calendar app can show the deadlines next to the meetings. it's served by the
+// api server; calendar apps can't send headers, so the feed has its own token in the url,
+// which only ever reads
+
+/// path of the feed on the api server
+pub const FEED_PATH: &str = "/feed.ics";
+// json list of the calendar ids in the feed; no lists means no feed
+const CALENDARS_KEY: &str = "ics_feed_calendars";
+// credential store entry of the feed token, only there while the feed is on
+const TOKEN_ENTRY: &str = "ics_feed_token";
+
+/// what's in the feed and where calendar apps subscribe to it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsFeed {
+    pub calendar_ids: Vec<String>,
+    /// the subscription url with its token; None while the feed is off
+    pub url: Option<String>,
+    /// the api server serves the feed, it's only reachable while the server runs
+    pub server_running: bool,
+}
+
+async fn calendar_ids(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    match settings::get(pool, CALENDARS_KEY).await? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn load(pool: &SqlitePool) -> Result<IcsFeed, String> {
+    let calendar_ids = calendar_ids(pool).await?;
+    let server = api_server::load_settings(pool).await?;
+    let url = match credentials::get_password(TOKEN_ENTRY).await? {
+        Some(token) if !calendar_ids.is_empty() => Some(format!(
+            "http://127.0.0.1:{}{FEED_PATH}?token={token}",
+            server.port
+        )),
+        _ => None,
+    };
+    Ok(IcsFeed {
+        calendar_ids,
+        url,
+        server_running: server.running,
+    })
+}
+
+/// let a feed request in with the token from its url; Err is the response to send instead
+pub async fn authorize(url: &Url) -> Result<(), Response> {
+    let given = url
+        .query_pairs()
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.to_string())
+        .unwrap_or_default();
+    let token = credentials::get_password(TOKEN_ENTRY)
+        .await
+        .map_err(|e| Response::error(500, e))?;
+    match token {
+        Some(token) if api_server::same_token(&given, &token) => Ok(()),
+        _ => Err(Response::error(401, "Missing or wrong feed token")),
+    }
+}
+
+/// the feed: an all-day event on the due date of every open task in the chosen lists
+pub async fn render(pool: &SqlitePool) -> Result<String, String> {
+    let calendar_ids = calendar_ids(pool).await?;
+    let mut rows: Vec<(String, String, String, String)> = Vec::new();
+    if !calendar_ids.is_empty() {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            "SELECT uid, title, strftime('%Y%m%d', due_date, 'localtime'),
+                    strftime('%Y%m%d', due_date, 'localtime', '+1 day')
+             FROM tasks
+             WHERE completed = 0 AND archived_at IS NULL AND due_date IS NOT NULL
+                AND calendar_id IN (",
+        );
+        let mut separated = query.separated(", ");
+        for id in &calendar_ids {
+            separated.push_bind(id.clone());
+        }
+        query.push(") ORDER BY julianday(due_date), sort_order");
+        rows = query
+            .build_query_as()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let stamp = caldav_time(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        concat!(
+            "PRODID:-//caldav-tasks//",
+            env!("CARGO_PKG_VERSION"),
+            "//EN"
+        )
+        .to_string(),
+        "X-WR-CALNAME:Task deadlines".to_string(),
+        // a hint for how often calendar apps check again
+        "REFRESH-INTERVAL;VALUE=DURATION:PT15M".to_string(),
+        "X-PUBLISHED-TTL:PT15M".to_string(),
+    ];
+    for (uid, title, day, next_day) in rows {
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            // not the task's own uid, a calendar app may also see the task itself
+            format!("UID:{uid}-due"),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART;VALUE=DATE:{day}"),
+            format!("DTEND;VALUE=DATE:{next_day}"),
+            format!("SUMMARY:{}", escape(&title)),
+            // deadlines don't make anyone busy
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    Ok(lines.iter().map(|line| fold(line) + "\r\n").collect())
+}
+
+/// the lists in the feed and its subscription url
+#[tauri::command]
+pub async fn get_ics_feed(app_handle: tauri::AppHandle) -> Result<IcsFeed, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    load(&pool).await
+}
+
+/// choose the lists in the feed; none turns the feed off and forgets its token, so the old
+/// url stops working
+#[tauri::command]
+pub async fn set_ics_feed_calendars(
+    app_handle: tauri::AppHandle,
+    calendar_ids: Vec<String>,
+) -> Result<IcsFeed, String> {
+    read_only::ensure_writable()?;
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    if calendar_ids.is_empty() {
+        settings::delete(&pool, CALENDARS_KEY).await?;
+        credentials::delete_password(TOKEN_ENTRY).await?;
+    } else {
+        let json = serde_json::to_string(&calendar_ids).map_err(|e| e.to_string())?;
+        settings::set(&pool, CALENDARS_KEY, &json).await?;
+        if credentials::get_password(TOKEN_ENTRY).await?.is_none() {
+            credentials::set_password(TOKEN_ENTRY, api_server::generate_token()).await?;
+        }
+    }
+    load(&pool).await
+}
+
+/// replace the feed token; calendar apps subscribed with the old url get nothing anymore
+#[tauri::command]
+pub async fn regenerate_ics_feed_token(app_handle: tauri::AppHandle) -> Result<IcsFeed, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    if calendar_ids(&pool).await?.is_empty() {
+        return Err("The feed is off".to_string());
+    }
+    credentials::set_password(TOKEN_ENTRY, api_server::generate_token()).await?;
+    load(&pool).await
+}
@@ -0,0 +1,219 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{crypto, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+// just enough IMAP4rev1 (RFC 3501) over implicit tls to read and flag messages in one folder
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+// larger messages are refused instead of being read into memory
+const MAX_LITERAL: usize = 25 << 20;
+
+/// one untagged response line, with the literals (`{n}` blocks) that came inside it
+#[derive(Debug, Default)]
+struct Untagged {
+    text: String,
+    literals: Vec<Vec<u8>>,
+}
+
+pub struct ImapClient {
+    stream: BufReader<TlsStream<TcpStream>>,
+    tag: u32,
+    capabilities: Vec<String>,
+}
+
+// an IMAP quoted string; line breaks can't be quoted at all
+fn quote(value: &str) -> Result<String, String> {
+    if value.contains(['\r', '\n']) {
+        return Err("IMAP values can't contain line breaks".to_string());
+    }
+    Ok(format!(
+        "\"{}\"",
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+impl ImapClient {
+    /// connect with tls (usually port 993) and log in
+    pub async fn connect(
+        host: &str,
+        port: u16,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, String> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(|e| e.to_string())?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        let server_name =
+            ServerName::try_from(host.to_string()).map_err(|e| format!("Invalid host: {e}"))?;
+
+        let tcp = tokio::time::timeout(TIMEOUT, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| format!("Connecting to {host}:{port} timed out"))?
+            .map_err(|e| format!("Failed to connect to {host}:{port}: {e}"))?;
+        let tls = TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| format!("TLS handshake with {host} failed: {e}"))?;
+
+        let mut client = ImapClient {
+            stream: BufReader::new(tls),
+            tag: 0,
+            capabilities: Vec::new(),
+        };
+        let greeting = client.read_line().await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(format!("Unexpected greeting: {}", greeting.trim()));
+        }
+        if greeting.starts_with("* OK") {
+            client
+                .command(&format!("LOGIN {} {}", quote(username)?, quote(password)?))
+                .await
+                .map_err(|e| format!("Login failed: {e}"))?;
+        }
+        let capabilities = client.command("CAPABILITY").await?;
+        client.capabilities = capabilities
+            .iter()
+            .filter_map(|line| line.text.strip_prefix("CAPABILITY "))
+            .flat_map(|line| line.split_whitespace().map(str::to_uppercase))
+            .collect();
+        Ok(client)
+    }
+
+    async fn read_line(&mut self) -> Result<String, String> {
+        let mut line = String::new();
+        let read = tokio::time::timeout(TIMEOUT, self.stream.read_line(&mut line))
+            .await
+            .map_err(|_| "The IMAP server stopped answering".to_string())?
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("The IMAP server closed the connection".to_string());
+        }
+        Ok(line)
+    }
+
+    // run a command and return its untagged responses, or the server's reason on NO/BAD
+    async fn command(&mut self, command: &str) -> Result<Vec<Untagged>, String> {
+        self.tag += 1;
+        let tag = format!("A{}", self.tag);
+        self.stream
+            .get_mut()
+            .write_all(format!("{tag} {command}\r\n").as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut untagged: Vec<Untagged> = Vec::new();
+        // set while a response continues after a literal
+        let mut current: Option<Untagged> = None;
+        loop {
+            let line = self.read_line().await?;
+            let line = line.trim_end_matches(['\r', '\n']);
+            let mut response = match current.take() {
+                Some(mut response) => {
+                    response.text.push_str(line);
+                    response
+                }
+                None => {
+                    if let Some(status) = line.strip_prefix(&format!("{tag} ")) {
+                        return match status.split_once(' ').map_or(status, |(word, _)| word) {
+                            "OK" => Ok(untagged),
+                            _ => Err(status.to_string()),
+                        };
+                    }
+                    Untagged {
+                        text: line.strip_prefix("* ").unwrap_or(line).to_string(),
+                        ..Untagged::default()
+                    }
+                }
+            };
+
+            // "... {123}" announces 123 bytes that follow the line break
+            let literal = response
+                .text
+                .strip_suffix('}')
+                .and_then(|text| text.rsplit_once('{'))
+                .and_then(|(_, size)| size.parse::<usize>().ok());
+            match literal {
+                Some(size) if size > MAX_LITERAL => {
+                    return Err(format!("Message too large ({size} bytes)"));
+                }
+                Some(size) => {
+                    let mut data = vec![0; size];
+                    tokio::time::timeout(TIMEOUT, self.stream.read_exact(&mut data))
+                        .await
+                        .map_err(|_| "The IMAP server stopped answering".to_string())?
+                        .map_err(|e| e.to_string())?;
+                    response.literals.push(data);
+                    current = Some(response);
+                }
+                None => untagged.push(response),
+            }
+        }
+    }
+
+    /// open a folder for reading and flagging
+    pub async fn select(&mut self, folder: &str) -> Result<(), String> {
+        self.command(&format!("SELECT {}", quote(folder)?))
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to open folder {folder}: {e}"))
+    }
+
+    /// uids of the messages nobody has read yet, oldest first
+    pub async fn unseen(&mut self) -> Result<Vec<u32>, String> {
+        let mut uids: Vec<u32> = self
+            .command("UID SEARCH UNSEEN")
+            .await?
+            .iter()
+            .filter_map(|line| line.text.strip_prefix("SEARCH"))
+            .flat_map(|uids| uids.split_whitespace().filter_map(|uid| uid.parse().ok()))
+            .collect();
+        uids.sort_unstable();
+        Ok(uids)
+    }
+
+    /// the raw message, without marking it as read
+    pub async fn fetch(&mut self, uid: u32) -> Result<Vec<u8>, String> {
+        self.command(&format!("UID FETCH {uid} BODY.PEEK[]"))
+            .await?
+            .into_iter()
+            .find(|line| line.text.contains("FETCH"))
+            .and_then(|line| line.literals.into_iter().next())
+            .ok_or_else(|| format!("Message {uid} is gone"))
+    }
+
+    pub async fn mark_seen(&mut self, uid: u32) -> Result<(), String> {
+        self.command(&format!("UID STORE {uid} +FLAGS.SILENT (\\Seen)"))
+            .await
+            .map(|_| ())
+    }
+
+    /// move a message to another folder; false when the server can't (no MOVE extension)
+    pub async fn move_to(&mut self, uid: u32, folder: &str) -> Result<bool, String> {
+        if !self
+            .capabilities
+            .iter()
+            .any(|capability| capability == "MOVE")
+        {
+            return Ok(false);
+        }
+        self.command(&format!("UID MOVE {uid} {}", quote(folder)?))
+            .await
+            .map(|_| true)
+            .map_err(|e| format!("Failed to move message to {folder}: {e}"))
+    }
+
+    pub async fn logout(mut self) {
+        let _ = self.command("LOGOUT").await;
+    }
+}
@@ -0,0 +1,205 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::tasks::{now_iso, Task, TaskChanges, TaskRow, BLOCKED_BY};
+use crate::{db, lock, read_only, settings};
+
+// open tasks without a column (STATUS:NEEDS-ACTION)
+const TODO: &str = "todo";
+// STATUS:IN-PROCESS
+const IN_PROGRESS: &str = "in-progress";
+// completed tasks, whatever column they were in before
+const DONE: &str = "done";
+
+// json array with the names of the user's own columns, in board order
+const COLUMNS_KEY: &str = "kanban_columns";
+
+/// a column of the board with its tasks in sort order
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanbanColumn {
+    pub id: String,
+    /// added by the user (or found on synced tasks) rather than built in
+    pub custom: bool,
+    pub tasks: Vec<Task>,
+}
+
+// the column a task shows up in; completing a task keeps its column, so reopening it
+// puts it back where it was
+fn column_of(task: &Task) -> &str {
+    if task.completed {
+        DONE
+    } else {
+        task.kanban_column.as_deref().unwrap_or(TODO)
+    }
+}
+
+async fn custom_columns(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    Ok(settings::get(pool, COLUMNS_KEY)
+        .await?
+        .and_then(|columns| serde_json::from_str(&columns).ok())
+        .unwrap_or_default())
+}
+
+async fn save_columns(pool: &SqlitePool, columns: &[String]) -> Result<(), String> {
+    let columns = serde_json::to_string(columns).map_err(|e| e.to_string())?;
+    settings::set(pool, COLUMNS_KEY, &columns).await
+}
+
+/// the tasks grouped per column: to do, in progress, the custom columns, then done. columns
+/// that only exist on synced tasks are shown before done as well
+#[tauri::command]
+pub async fn get_kanban_board(
+    app_handle: tauri::AppHandle,
+    calendar_id: Option<String>,
+) -> Result<Vec<KanbanColumn>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let rows: Vec<TaskRow> = sqlx::query_as(&format!(
+        "SELECT tasks.*, {BLOCKED_BY} FROM tasks
+         WHERE archived_at IS NULL AND (?1 IS NULL OR calendar_id = ?1)
+         ORDER BY sort_order"
+    ))
+    .bind(&calendar_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let column = |id: &str, custom: bool| KanbanColumn {
+        id: id.to_string(),
+        custom,
+        tasks: Vec::new(),
+    };
+    let mut board = vec![column(TODO, false), column(IN_PROGRESS, false)];
+    for id in custom_columns(&pool).await? {
+        board.push(column(&id, true));
+    }
+    let mut done = column(DONE, false);
+
+    for task in rows.into_iter().map(Task::from) {
+        let id = column_of(&task);
+        if id == DONE {
+            done.tasks.push(task);
+            continue;
+        }
+        let index = match board.iter().position(|column| column.id == id) {
+            Some(index) => index,
+            None => {
+                board.push(column(id, true));
+                board.len() - 1
+            }
+        };
+        board[index].tasks.push(task);
+    }
+    board.push(done);
+    Ok(board)
+}
+
+/// move a task to another column; moving it to done completes it and moving a completed
+/// task anywhere else reopens it
+#[tauri::command]
+pub async fn move_to_column(
+    app_handle: tauri::AppHandle,
+    id: String,
+    column: String,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    let known = [TODO, IN_PROGRESS, DONE].contains(&column.as_str())
+        || custom_columns(&pool).await?.contains(&column);
+    if !known {
+        return Err(format!("Unknown column {column}"));
+    }
+
+    let now = now_iso();
+    let query = if column == DONE {
+        sqlx::query(
+            "UPDATE tasks SET completed = 1, completed_at = COALESCE(completed_at, ?1),
+                modified_at = ?1, synced = 0
+             WHERE id = ?2 AND completed = 0",
+        )
+        .bind(&now)
+        .bind(&id)
+    } else {
+        sqlx::query(
+            "UPDATE tasks SET completed = 0, completed_at = NULL, kanban_column = ?3,
+                modified_at = ?1, synced = 0
+             WHERE id = ?2 AND (completed = 1 OR kanban_column IS NOT ?3)",
+        )
+        .bind(&now)
+        .bind(&id)
+        .bind((column != TODO).then_some(&column))
+    };
+    let updated = query
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+
+    if updated > 0 {
+        TaskChanges {
+            updated: vec![id],
+            ..TaskChanges::default()
+        }
+        .emit(&app_handle, &pool)
+        .await;
+    }
+    Ok(())
+}
+
+/// add a column after the existing custom columns; returns the custom columns
+#[tauri::command]
+pub async fn add_kanban_column(
+    app_handle: tauri::AppHandle,
+    name: String,
+) -> Result<Vec<String>, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Column name can't be empty".to_string());
+    }
+    let mut columns = custom_columns(&pool).await?;
+    if [TODO, IN_PROGRESS, DONE].contains(&name.as_str()) || columns.contains(&name) {
+        return Err(format!("Column {name} already exists"));
+    }
+    columns.push(name);
+    save_columns(&pool, &columns).await?;
+    Ok(columns)
+}
+
+/// remove a custom column; its tasks go back to the to-do column. returns the custom columns
+#[tauri::command]
+pub async fn remove_kanban_column(
+    app_handle: tauri::AppHandle,
+    name: String,
+) -> Result<Vec<String>, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    let mut columns = custom_columns(&pool).await?;
+    columns.retain(|column| *column != name);
+    save_columns(&pool, &columns).await?;
+
+    let updated: Vec<String> = sqlx::query_scalar(
+        "UPDATE tasks SET kanban_column = NULL, modified_at = ?, synced = 0
+         WHERE kanban_column = ?
+         RETURNING id",
+    )
+    .bind(now_iso())
+    .bind(&name)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(columns)
+}
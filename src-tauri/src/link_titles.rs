@@ -0,0 +1,250 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tauri_plugin_http::reqwest::{self, redirect, Url};
+
+use crate::caldav::error_chain;
+use crate::{db, read_only, settings};
+
+// the title of the page behind a task's link, so a task made from a bare url gets a readable
+// name. fetching tells the site that someone looked at the link, so it's off until turned on.
+// links come from synced and shared tasks, so only public addresses are fetched: a link must
+// not make the app talk to the local network or to its own api server
+
+const ENABLED_KEY: &str = "fetch_link_titles";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REDIRECTS: usize = 5;
+// the title is in the head, which is rarely further in than this
+const MAX_BYTES: usize = 256 * 1024;
+const MAX_TITLE_CHARS: usize = 200;
+
+lazy_static! {
+    static ref TITLE: Regex =
+        Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("invalid title regex");
+    // pages whose <title> is only the site name often have a better one for link previews
+    static ref OG_TITLE: Regex = Regex::new(
+        r#"(?is)<meta[^>]+property\s*=\s*["']og:title["'][^>]+content\s*=\s*["']([^"']*)["']"#
+    )
+    .expect("invalid og:title regex");
+    static ref ENTITY: Regex =
+        Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("invalid entity regex");
+}
+
+// the entities titles actually use, numeric ones and a few named ones
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |captures: &regex::Captures| {
+            let entity = &captures[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "ndash" => Some('–'),
+                "mdash" => Some('—'),
+                "hellip" => Some('…'),
+                _ => match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => entity
+                        .strip_prefix('#')
+                        .and_then(|number| number.parse().ok())
+                        .and_then(char::from_u32),
+                },
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .to_string()
+}
+
+fn clean(title: &str) -> Option<String> {
+    let title = decode_entities(title)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return Some(title);
+    }
+    let cut: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+    Some(format!("{}…", cut.trim_end()))
+}
+
+// the page title in an html document; og:title when there's one
+fn extract_title(html: &str) -> Option<String> {
+    [&*OG_TITLE, &*TITLE]
+        .iter()
+        .find_map(|pattern| clean(&pattern.captures(html)?[1]))
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // "this network", carrier-grade nat, benchmarking and reserved
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_public_v4(v4);
+    }
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local, link local and documentation
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => is_public_v6(ip),
+    }
+}
+
+// the addresses of the url's host, when all of them are public
+async fn public_addresses(url: &Url) -> Result<Vec<SocketAddr>, String> {
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addresses: Vec<SocketAddr> = match (url.domain(), url.host_str()) {
+        (Some(domain), _) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|e| format!("Could not resolve {domain}: {e}"))?
+            .collect(),
+        // an ipv6 host comes in brackets
+        (None, Some(host)) => host
+            .trim_matches(['[', ']'])
+            .parse::<IpAddr>()
+            .map(|ip| vec![SocketAddr::new(ip, port)])
+            .unwrap_or_default(),
+        (None, None) => Vec::new(),
+    };
+    if addresses.is_empty() {
+        return Err(format!("{url} has no addresses"));
+    }
+    if let Some(address) = addresses.iter().find(|address| !is_public(address.ip())) {
+        return Err(format!(
+            "Not fetching {url}: {} is not a public address",
+            address.ip()
+        ));
+    }
+    Ok(addresses)
+}
+
+// GET the page, following redirects by hand so every hop is checked. the client connects to
+// the addresses that were checked, a second lookup could answer differently
+async fn get_page(url: Url) -> Result<Option<reqwest::Response>, String> {
+    let mut url = url;
+    for _ in 0..=MAX_REDIRECTS {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Ok(None);
+        }
+        let addresses = public_addresses(&url).await?;
+        let mut builder = reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .timeout(FETCH_TIMEOUT)
+            .user_agent(concat!("caldav-tasks/", env!("CARGO_PKG_VERSION")));
+        if let Some(domain) = url.domain() {
+            builder = builder.resolve_to_addrs(domain, &addresses);
+        }
+        let client = builder.build().map_err(|e| error_chain(&e))?;
+        let response = client
+            .get(url.clone())
+            .header(reqwest::header::ACCEPT, "text/html")
+            .send()
+            .await
+            .map_err(|e| error_chain(&e))?;
+        if !response.status().is_redirection() {
+            return Ok(Some(response));
+        }
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|location| url.join(location).ok());
+        let Some(location) = location else {
+            return Ok(None);
+        };
+        url = location;
+    }
+    Err("Too many redirects".to_string())
+}
+
+async fn is_enabled(app_handle: &tauri::AppHandle) -> Result<bool, String> {
+    let pool = db::pool(app_handle).await?;
+    Ok(settings::get(&pool, ENABLED_KEY).await?.as_deref() == Some("true"))
+}
+
+/// whether titles of links are fetched
+#[tauri::command]
+pub async fn get_fetch_link_titles(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    is_enabled(&app_handle).await
+}
+
+#[tauri::command]
+pub async fn set_fetch_link_titles(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    settings::set(&pool, ENABLED_KEY, &enabled.to_string()).await
+}
+
+/// the title of the web page at `url`; None when fetching titles is off or the page has no
+/// title. only the first part of the page is read
+#[tauri::command]
+pub async fn fetch_page_title(
+    app_handle: tauri::AppHandle,
+    url: String,
+) -> Result<Option<String>, String> {
+    if !is_enabled(&app_handle).await? {
+        return Ok(None);
+    }
+    let url = Url::parse(url.trim()).map_err(|e| format!("Not a link: {e}"))?;
+    let Some(mut response) = get_page(url).await? else {
+        return Ok(None);
+    };
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_none_or(|value| value.contains("html"));
+    if !is_html {
+        return Ok(None);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| error_chain(&e))? {
+        // the end of the head may straddle the chunks
+        let start = body.len().saturating_sub(6);
+        body.extend_from_slice(&chunk);
+        let done = body.len() >= MAX_BYTES
+            || body[start..]
+                .windows(7)
+                .any(|window| window.eq_ignore_ascii_case(b"</head>"));
+        if done {
+            break;
+        }
+    }
+    body.truncate(MAX_BYTES);
+    Ok(extract_title(&String::from_utf8_lossy(&body)))
+}
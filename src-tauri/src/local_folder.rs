@@ -0,0 +1,456 @@
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+use tauri::Emitter;
+
+use crate::tasks::new_id;
+use crate::{db, lock, payload_limits, read_only};
+
+// accounts without a server: a folder with a subfolder per list and an .ics file per task,
+// laid out like vdirsyncer's filesystem storage (`displayname` and `color` files next to the
+// tasks), so it can be synced with Syncthing, kept in git or shared with other vdir tools.
+// the account's server url is the folder's path, a task's href the path of its file
+
+pub const BACKEND: &str = "localFolder";
+const DISPLAY_NAME_FILE: &str = "displayname";
+const COLOR_FILE: &str = "color";
+const EXTENSION: &str = "ics";
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    // what the watched folders looked like at the last look, by account id
+    static ref FINGERPRINTS: Mutex<HashMap<String, u64>> = Mutex::new(HashMap::new());
+}
+
+/// a subfolder holding a list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderCalendar {
+    pub path: String,
+    pub display_name: String,
+    pub color: Option<String>,
+}
+
+/// a task file as read from a list's folder
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderObject {
+    pub href: String,
+    /// hash of the contents; None when the file was skipped
+    pub etag: Option<String>,
+    pub data: Option<String>,
+    /// over the object size limit, so skipped like on a server
+    pub too_large: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrittenObject {
+    pub href: String,
+    pub etag: String,
+}
+
+// files change under other programs without a server to hand out etags, so the contents are
+// the etag
+fn etag(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+// hidden files are other tools' bookkeeping and our own half-written ones
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_none_or(|name| name.to_string_lossy().starts_with('.'))
+}
+
+fn is_object(path: &Path) -> bool {
+    !is_hidden(path) && path.extension().is_some_and(|ext| ext == EXTENSION)
+}
+
+fn ensure_object(path: &Path) -> Result<(), String> {
+    if is_object(path) {
+        Ok(())
+    } else {
+        Err(format!("Not a task file: {}", path.display()))
+    }
+}
+
+fn read_metadata(dir: &Path, name: &str) -> Option<String> {
+    let text = fs::read_to_string(dir.join(name)).ok()?;
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+// written next to the target and renamed over it, so a sync tool never picks up half a file
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("Not a file: {}", path.display()))?;
+    let temporary = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+    fs::write(&temporary, contents).map_err(|e| e.to_string())?;
+    fs::rename(&temporary, path).map_err(|e| {
+        let _ = fs::remove_file(&temporary);
+        e.to_string()
+    })
+}
+
+fn calendar(dir: &Path) -> FolderCalendar {
+    FolderCalendar {
+        path: dir.to_string_lossy().into_owned(),
+        display_name: read_metadata(dir, DISPLAY_NAME_FILE).unwrap_or_else(|| {
+            dir.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        }),
+        color: read_metadata(dir, COLOR_FILE),
+    }
+}
+
+fn subfolders(root: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(root).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => format!("Folder not found: {}", root.display()),
+        _ => e.to_string(),
+    })?;
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !is_hidden(path))
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn objects(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_object(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// a name that's safe on every filesystem the folder may be synced to
+fn file_name(uid: &str) -> String {
+    let name: String = uid
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-_.@".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('.');
+    if name.is_empty() {
+        format!("{}.{EXTENSION}", new_id())
+    } else {
+        format!("{name}.{EXTENSION}")
+    }
+}
+
+fn slug(display_name: &str) -> String {
+    let slug = display_name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "tasks".to_string()
+    } else {
+        slug
+    }
+}
+
+fn check_color(color: &str) -> Result<(), String> {
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid color: {color}"));
+    }
+    Ok(())
+}
+
+// whether the file still has the contents `etag` was taken from; no etag overwrites anything
+fn unchanged_since(path: &Path, expected: Option<&str>) -> Result<bool, String> {
+    let Some(expected) = expected else {
+        return Ok(true);
+    };
+    match fs::read(path) {
+        Ok(bytes) => Ok(etag(&bytes) == expected),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// the lists in a folder account
+#[tauri::command]
+pub async fn list_folder_calendars(
+    app_handle: tauri::AppHandle,
+    root: String,
+) -> Result<Vec<FolderCalendar>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    Ok(subfolders(Path::new(&root))?
+        .iter()
+        .map(|dir| calendar(dir))
+        .collect())
+}
+
+/// a new list: a subfolder named after it
+#[tauri::command]
+pub fn create_folder_calendar(
+    root: String,
+    display_name: String,
+    color: Option<String>,
+) -> Result<FolderCalendar, String> {
+    read_only::ensure_writable()?;
+    if let Some(color) = &color {
+        check_color(color)?;
+    }
+    let root = Path::new(&root);
+    if !root.is_dir() {
+        return Err(format!("Folder not found: {}", root.display()));
+    }
+    let base = slug(&display_name);
+    let dir = (1..)
+        .map(|n| match n {
+            1 => root.join(&base),
+            n => root.join(format!("{base}-{n}")),
+        })
+        .find(|dir| !dir.exists())
+        .unwrap_or_else(|| root.join(new_id()));
+    fs::create_dir(&dir).map_err(|e| e.to_string())?;
+    write_atomic(&dir.join(DISPLAY_NAME_FILE), display_name.trim().as_bytes())?;
+    if let Some(color) = &color {
+        write_atomic(&dir.join(COLOR_FILE), color.as_bytes())?;
+    }
+    log::info!("Created the list folder {}", dir.display());
+    Ok(calendar(&dir))
+}
+
+/// rename or recolor a list
+#[tauri::command]
+pub fn update_folder_calendar(
+    path: String,
+    display_name: Option<String>,
+    color: Option<String>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let dir = Path::new(&path);
+    if !dir.is_dir() {
+        return Err(format!("Folder not found: {}", dir.display()));
+    }
+    if let Some(display_name) = display_name {
+        write_atomic(&dir.join(DISPLAY_NAME_FILE), display_name.trim().as_bytes())?;
+    }
+    if let Some(color) = color {
+        check_color(&color)?;
+        write_atomic(&dir.join(COLOR_FILE), color.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// remove a list with its task files; a folder holding anything else is left alone
+#[tauri::command]
+pub fn delete_folder_calendar(path: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let dir = Path::new(&path);
+    let foreign = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            !(path.is_file()
+                && (is_object(path)
+                    || name == DISPLAY_NAME_FILE
+                    || name == COLOR_FILE
+                    || name.ends_with(".tmp")))
+        });
+    if let Some(foreign) = foreign {
+        return Err(format!(
+            "{} holds other files like {}, remove it by hand",
+            dir.display(),
+            foreign.display()
+        ));
+    }
+    fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+    log::info!("Removed the list folder {}", dir.display());
+    Ok(())
+}
+
+/// every task file in a list's folder
+#[tauri::command]
+pub async fn read_folder_objects(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<Vec<FolderObject>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let max_object = payload_limits::max_object_size(&pool).await?;
+
+    let mut read = Vec::new();
+    for file in objects(Path::new(&path))? {
+        let href = file.to_string_lossy().into_owned();
+        let size = fs::metadata(&file).map(|meta| meta.len()).unwrap_or(0);
+        if size as usize > max_object {
+            log::warn!("Skipping {href}, it's over {max_object} bytes");
+            read.push(FolderObject {
+                href,
+                etag: None,
+                data: None,
+                too_large: true,
+            });
+            continue;
+        }
+        // removed by another program since the listing
+        let Ok(bytes) = fs::read(&file) else {
+            continue;
+        };
+        read.push(FolderObject {
+            href,
+            etag: Some(etag(&bytes)),
+            data: Some(String::from_utf8_lossy(&bytes).into_owned()),
+            too_large: false,
+        });
+    }
+    Ok(read)
+}
+
+/// write a new task file named after the task's uid
+#[tauri::command]
+pub fn create_folder_object(
+    calendar: String,
+    uid: String,
+    ics: String,
+) -> Result<WrittenObject, String> {
+    read_only::ensure_writable()?;
+    let path = Path::new(&calendar).join(file_name(&uid));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    write_atomic(&path, ics.as_bytes())?;
+    Ok(WrittenObject {
+        href: path.to_string_lossy().into_owned(),
+        etag: etag(ics.as_bytes()),
+    })
+}
+
+/// replace a task file, as long as it's unchanged since `etag` was read; the new etag, or
+/// None when another program changed or removed the file in the meantime
+#[tauri::command]
+pub fn update_folder_object(
+    href: String,
+    ics: String,
+    etag: Option<String>,
+) -> Result<Option<String>, String> {
+    read_only::ensure_writable()?;
+    let path = Path::new(&href);
+    ensure_object(path)?;
+    if !unchanged_since(path, etag.as_deref())? {
+        return Ok(None);
+    }
+    write_atomic(path, ics.as_bytes())?;
+    Ok(Some(self::etag(ics.as_bytes())))
+}
+
+/// remove a task file unless it changed since `etag` was read; one that's gone already counts
+/// as removed
+#[tauri::command]
+pub fn delete_folder_object(href: String, etag: Option<String>) -> Result<bool, String> {
+    read_only::ensure_writable()?;
+    let path = Path::new(&href);
+    ensure_object(path)?;
+    if !path.exists() {
+        return Ok(true);
+    }
+    if !unchanged_since(path, etag.as_deref())? {
+        return Ok(false);
+    }
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(true),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// names, sizes and modification times of everything the account is made of
+fn fingerprint(root: &Path) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    for dir in subfolders(root).ok()? {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut files: Vec<(PathBuf, u64, u128)> = entries
+            .flatten()
+            .filter(|entry| !is_hidden(&entry.path()))
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                let modified = meta
+                    .modified()
+                    .ok()?
+                    .duration_since(UNIX_EPOCH)
+                    .ok()?
+                    .as_nanos();
+                Some((entry.path(), meta.len(), modified))
+            })
+            .collect();
+        files.sort();
+        dir.hash(&mut hasher);
+        files.hash(&mut hasher);
+    }
+    Some(hasher.finish())
+}
+
+async fn watch_once(app_handle: &tauri::AppHandle, pool: &SqlitePool) -> Result<(), String> {
+    let accounts: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, server_url FROM accounts WHERE backend = ? AND is_active = 1")
+            .bind(BACKEND)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let mut fingerprints = FINGERPRINTS.lock().expect("Failed to lock FINGERPRINTS");
+    fingerprints.retain(|id, _| accounts.iter().any(|(account_id, _)| account_id == id));
+    for (account_id, root) in accounts {
+        let Some(current) = fingerprint(Path::new(&root)) else {
+            continue;
+        };
+        let previous = fingerprints.insert(account_id.clone(), current);
+        if previous.is_some_and(|previous| previous != current) {
+            log::debug!("The folder of account {account_id} changed");
+            let _ = app_handle.emit("local-folder-changed", &account_id);
+        }
+    }
+    Ok(())
+}
+
+/// look for changes other programs make to the folders of folder accounts, and tell the
+/// frontend to sync the account when there are
+pub fn start_watching(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Ok(pool) = db::pool(&app_handle).await else {
+                continue;
+            };
+            if let Err(e) = watch_once(&app_handle, &pool).await {
+                log::warn!("Failed to check the task folders: {e}");
+            }
+        }
+    });
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Weekday};
+use serde::Serialize;
+
+// calendar conventions of the OS locale, for the dates and times the backend writes itself
+// (tray hints, reports) and the weeks it groups by
+
+// regions whose weeks start on sunday or saturday, the rest start on monday
+const SUNDAY_FIRST: &[&str] = &[
+    "AG", "AS", "BD", "BR", "BS", "BT", "BW", "BZ", "CA", "CO", "DM", "DO", "ET", "GT", "GU", "HK",
+    "HN", "ID", "IL", "IN", "JM", "JP", "KE", "KH", "KR", "LA", "MH", "MM", "MO", "MT", "MX", "MZ",
+    "NI", "NP", "PA", "PE", "PH", "PK", "PR", "PT", "PY", "SA", "SG", "SV", "TH", "TT", "TW", "US",
+    "VE", "VI", "WS", "YE", "ZA", "ZW",
+];
+const SATURDAY_FIRST: &[&str] = &[
+    "AE", "AF", "BH", "DJ", "DZ", "EG", "IQ", "IR", "JO", "KW", "LY", "OM", "QA", "SD", "SY",
+];
+// regions that write times with am/pm
+const TWELVE_HOUR: &[&str] = &[
+    "AU", "BD", "CA", "CO", "EG", "IN", "KR", "MX", "MY", "NZ", "PH", "PK", "SA", "TW", "US",
+];
+
+/// the order a locale writes day, month and year in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DateOrder {
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleSettings {
+    /// the locale tag, like "de-AT"
+    pub locale: String,
+    /// 0 for sunday through 6 for saturday, as javascript's getDay counts
+    pub first_day_of_week: u32,
+    pub date_order: DateOrder,
+    /// chrono format of a date, like "%d.%m.%Y"
+    pub date_format: String,
+    /// chrono format of a time of day, like "%H:%M"
+    pub time_format: String,
+    pub hour12: bool,
+}
+
+impl LocaleSettings {
+    /// the conventions of a locale like "en-US" or "de_AT.UTF-8"; a bare "en" is taken as
+    /// american english, anything unknown gets iso dates and 24-hour times
+    pub fn from_tag(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or("");
+        let mut parts = tag.split(['-', '_']);
+        let language = parts.next().unwrap_or("").to_lowercase();
+        // a script subtag ("zh-Hant-TW") sits before the region
+        let region = parts
+            .find(|part| part.len() == 2 || (part.len() == 3 && part.chars().all(char::is_numeric)))
+            .unwrap_or(if language == "en" { "US" } else { "" })
+            .to_uppercase();
+        let region = region.as_str();
+
+        let first_day_of_week = if SUNDAY_FIRST.contains(&region) {
+            Weekday::Sun
+        } else if SATURDAY_FIRST.contains(&region) {
+            Weekday::Sat
+        } else {
+            Weekday::Mon
+        };
+        let hour12 = TWELVE_HOUR.contains(&region) && !(region == "CA" && language == "fr");
+
+        let (date_order, date_format) = match (language.as_str(), region) {
+            (_, "US" | "PH") => (DateOrder::MonthDayYear, "%m/%d/%Y"),
+            (_, "CA" | "SE" | "LT") => (DateOrder::YearMonthDay, "%Y-%m-%d"),
+            (_, "CN" | "JP" | "TW" | "HK" | "ZA") => (DateOrder::YearMonthDay, "%Y/%m/%d"),
+            (_, "KR" | "HU") => (DateOrder::YearMonthDay, "%Y. %m. %d."),
+            (_, "NL" | "DK") => (DateOrder::DayMonthYear, "%d-%m-%Y"),
+            (
+                "de" | "ru" | "pl" | "cs" | "sk" | "fi" | "nb" | "nn" | "no" | "tr" | "uk" | "ro"
+                | "hr" | "sl" | "sr" | "bg" | "et" | "lv" | "is",
+                _,
+            ) => (DateOrder::DayMonthYear, "%d.%m.%Y"),
+            (_, "") => (DateOrder::YearMonthDay, "%Y-%m-%d"),
+            _ => (DateOrder::DayMonthYear, "%d/%m/%Y"),
+        };
+
+        Self {
+            locale: tag.to_string(),
+            first_day_of_week: first_day_of_week.num_days_from_sunday(),
+            date_order,
+            date_format: date_format.to_string(),
+            time_format: if hour12 { "%-I:%M %p" } else { "%H:%M" }.to_string(),
+            hour12,
+        }
+    }
+
+    pub fn week_start(&self) -> Weekday {
+        // num_days_from_monday of the day before, so 0 (sunday) wraps to 6
+        Weekday::try_from(((self.first_day_of_week + 6) % 7) as u8).unwrap_or(Weekday::Mon)
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(&self.date_format).to_string()
+    }
+
+    pub fn format_time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        time.format(&self.time_format).to_string()
+    }
+
+    /// sqlite date modifiers that move a local date back to the first day of its week, e.g.
+    /// 'weekday 0', '-6 days' for weeks starting on monday
+    pub fn week_start_modifiers(&self) -> String {
+        // 'weekday N' moves forward to the last day of the week (or stays on it)
+        format!("'weekday {}', '-6 days'", (self.first_day_of_week + 6) % 7)
+    }
+}
+
+/// the conventions of the OS locale
+pub fn current() -> LocaleSettings {
+    LocaleSettings::from_tag(&tauri_plugin_os::locale().unwrap_or_else(|| "en-US".to_string()))
+}
+
+/// first day of the week, date and time formats and 12/24-hour preference of the OS locale
+#[tauri::command]
+pub fn get_locale_settings() -> LocaleSettings {
+    current()
+}
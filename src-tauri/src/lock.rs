@@ -0,0 +1,299 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::{db, i18n, read_only, settings};
+
+// app_settings keys
+const PIN_HASH_KEY: &str = "app_lock_pin_hash";
+const IDLE_TIMEOUT_KEY: &str = "app_lock_idle_timeout";
+
+const MIN_PIN_LEN: usize = 4;
+// how often the idle timer checks whether the app should lock itself
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+// wrong pins are answered slower and slower (doubling), up to this delay
+const MAX_FAILURE_DELAY: u64 = 60;
+
+struct LockState {
+    pin_hash: Option<String>,
+    // 0 means the app only locks on launch
+    idle_timeout: u64,
+    locked: bool,
+    last_activity: Instant,
+    failures: u32,
+}
+
+impl LockState {
+    fn idle_expired(&self) -> bool {
+        self.pin_hash.is_some()
+            && self.idle_timeout > 0
+            && self.last_activity.elapsed() >= Duration::from_secs(self.idle_timeout)
+    }
+}
+
+// global storage for the lock state, loaded from the database when it opens
+lazy_static! {
+    static ref LOCK: Mutex<LockState> = Mutex::new(LockState {
+        pin_hash: None,
+        idle_timeout: 0,
+        locked: false,
+        last_activity: Instant::now(),
+        failures: 0,
+    });
+    // pin checks run one at a time, so parallel attempts can't skip the failure delay
+    static ref PIN_ATTEMPT: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub idle_timeout: u64,
+}
+
+fn status() -> AppLockStatus {
+    let state = LOCK.lock().expect("Failed to lock LOCK");
+    AppLockStatus {
+        enabled: state.pin_hash.is_some(),
+        locked: state.locked,
+        idle_timeout: state.idle_timeout,
+    }
+}
+
+/// whether the app is locked right now; unlike ensure_unlocked this doesn't count as activity
+pub fn is_locked() -> bool {
+    status().locked
+}
+
+// argon2 is deliberately slow, so hashing and verifying stay off the async runtime
+async fn hash_pin(pin: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(pin.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+async fn verify_pin(pin: String, hash: String) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        PasswordHash::new(&hash)
+            .map(|hash| {
+                Argon2::default()
+                    .verify_password(pin.as_bytes(), &hash)
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// check a pin against the stored hash, after the delay earned by earlier wrong pins
+///
+/// the attempt counts as a failure until the pin is verified, so one that's abandoned
+/// halfway still slows down the next
+async fn check_pin(pin: String, hash: String) -> Result<bool, String> {
+    let _attempt = PIN_ATTEMPT.lock().await;
+
+    let failures = {
+        let mut state = LOCK.lock().expect("Failed to lock LOCK");
+        let failures = state.failures;
+        state.failures += 1;
+        failures
+    };
+    if failures > 0 {
+        let delay = (1u64 << (failures - 1).min(16)).min(MAX_FAILURE_DELAY);
+        tokio::time::sleep(Duration::from_secs(delay)).await;
+    }
+
+    let valid = verify_pin(pin, hash).await?;
+    let mut state = LOCK.lock().expect("Failed to lock LOCK");
+    if valid {
+        state.failures = 0;
+    } else {
+        log::warn!("Wrong app pin ({} failed attempts)", state.failures);
+    }
+    Ok(valid)
+}
+
+/// load the lock settings; called once when the database is opened, so the app
+/// starts locked whenever a pin is set
+pub async fn load(pool: &SqlitePool) -> Result<(), String> {
+    let pin_hash = settings::get(pool, PIN_HASH_KEY).await?;
+    let idle_timeout = settings::get_u64(pool, IDLE_TIMEOUT_KEY, 0).await?;
+
+    let mut state = LOCK.lock().expect("Failed to lock LOCK");
+    state.locked = pin_hash.is_some();
+    state.pin_hash = pin_hash;
+    state.idle_timeout = idle_timeout;
+    state.last_activity = Instant::now();
+    Ok(())
+}
+
+/// fail with an error while the app is locked; data-returning commands call this first
+pub async fn ensure_unlocked(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    // the lock state is loaded together with the database
+    db::pool(app_handle).await?;
+
+    let mut state = LOCK.lock().expect("Failed to lock LOCK");
+    if state.idle_expired() {
+        state.locked = true;
+    }
+    if state.locked {
+        return Err(i18n::t("App is locked").to_string());
+    }
+    state.last_activity = Instant::now();
+    Ok(())
+}
+
+/// lock the app once it was idle for the configured time
+pub fn start_idle_timer(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let newly_locked = {
+                let mut state = LOCK.lock().expect("Failed to lock LOCK");
+                let expired = !state.locked && state.idle_expired();
+                if expired {
+                    state.locked = true;
+                }
+                expired
+            };
+            if newly_locked {
+                log::info!("App locked after being idle");
+                let _ = app_handle.emit("app-locked", ());
+            }
+        }
+    });
+}
+
+/// get whether an app pin is set and whether the app is currently locked; the frontend
+/// doesn't load the database until it's unlocked, and reloads when it locks
+#[tauri::command]
+pub async fn get_app_lock_status(app_handle: tauri::AppHandle) -> Result<AppLockStatus, String> {
+    db::pool(&app_handle).await?;
+    Ok(status())
+}
+
+/// unlock the app with its pin
+#[tauri::command]
+pub async fn unlock(app_handle: tauri::AppHandle, pin: String) -> Result<(), String> {
+    db::pool(&app_handle).await?;
+
+    let hash = LOCK.lock().expect("Failed to lock LOCK").pin_hash.clone();
+    let Some(hash) = hash else {
+        return Ok(());
+    };
+
+    if !check_pin(pin, hash).await? {
+        return Err(i18n::t("Wrong PIN").to_string());
+    }
+    {
+        let mut state = LOCK.lock().expect("Failed to lock LOCK");
+        state.locked = false;
+        state.last_activity = Instant::now();
+    }
+
+    log::info!("App unlocked");
+    let _ = app_handle.emit("app-unlocked", ());
+    Ok(())
+}
+
+/// lock the app right away
+#[tauri::command]
+pub async fn lock_app(app_handle: tauri::AppHandle) -> Result<(), String> {
+    db::pool(&app_handle).await?;
+
+    let mut state = LOCK.lock().expect("Failed to lock LOCK");
+    if state.pin_hash.is_none() {
+        return Err(i18n::t("No app PIN is set").to_string());
+    }
+    state.locked = true;
+    drop(state);
+
+    let _ = app_handle.emit("app-locked", ());
+    Ok(())
+}
+
+/// reset the idle timer (frontend calls this on user input)
+#[tauri::command]
+pub async fn report_activity() -> Result<(), String> {
+    let mut state = LOCK.lock().expect("Failed to lock LOCK");
+    if !state.locked {
+        state.last_activity = Instant::now();
+    }
+    Ok(())
+}
+
+/// set, change or remove (pin = None) the app pin; an existing pin has to be confirmed
+#[tauri::command]
+pub async fn set_app_lock(
+    app_handle: tauri::AppHandle,
+    pin: Option<String>,
+    current_pin: Option<String>,
+    idle_timeout: u64,
+) -> Result<AppLockStatus, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    let existing = LOCK.lock().expect("Failed to lock LOCK").pin_hash.clone();
+    if let Some(existing) = existing {
+        let confirmed = match current_pin {
+            Some(current) => check_pin(current, existing).await?,
+            None => false,
+        };
+        if !confirmed {
+            return Err(i18n::t("Wrong PIN").to_string());
+        }
+    }
+
+    let pin_hash = match pin {
+        Some(pin) if pin.chars().count() < MIN_PIN_LEN => {
+            return Err(i18n::t_with(
+                "The PIN needs at least {} characters",
+                &[&MIN_PIN_LEN],
+            ));
+        }
+        Some(pin) => {
+            let hash = hash_pin(pin).await?;
+            settings::set(&pool, PIN_HASH_KEY, &hash).await?;
+            Some(hash)
+        }
+        None => {
+            settings::delete(&pool, PIN_HASH_KEY).await?;
+            None
+        }
+    };
+    settings::set(&pool, IDLE_TIMEOUT_KEY, &idle_timeout.to_string()).await?;
+
+    {
+        let mut state = LOCK.lock().expect("Failed to lock LOCK");
+        state.pin_hash = pin_hash;
+        state.idle_timeout = idle_timeout;
+        state.locked = false;
+        state.last_activity = Instant::now();
+    }
+
+    let status = status();
+    log::info!(
+        "App lock {} (idle timeout {idle_timeout}s)",
+        if status.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    Ok(status)
+}
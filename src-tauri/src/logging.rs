@@ -0,0 +1,84 @@
+use log::LevelFilter;
+use tauri::{Manager, Runtime};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::redact;
+
+// the backend logs with `log`, and traces sync, database and scheduler work with `tracing`
+// spans. there's no tracing subscriber: with its log feature, events and the opening of
+// spans (with their fields) are logged through the plugin like everything else, and entering
+// and leaving spans at trace level
+
+/// log file name (without extension) inside the app log dir
+pub const LOG_FILE_NAME: &str = "caldav-tasks";
+
+// level used until the frontend applies the user's setting
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Debug;
+
+// rotate the log file at this size and keep this many old files around
+const MAX_FILE_SIZE: u128 = 1_000_000;
+const KEEP_FILES: usize = 5;
+
+// every target scrubs credentials from the already formatted line (frontend logs included)
+fn redacted(kind: TargetKind) -> Target {
+    Target::new(kind).format(|out, message, _record| {
+        out.finish(format_args!("{}", redact::redact(&message.to_string())))
+    })
+}
+
+/// build the log plugin: stdout, a rotating file in the app log dir and the webview console
+///
+/// the plugin itself lets everything through, the effective level is the global
+/// max level so it can be changed at runtime with `set_log_level`
+pub fn plugin<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    tauri_plugin_log::Builder::new()
+        .targets([
+            redacted(TargetKind::Stdout),
+            redacted(TargetKind::LogDir {
+                file_name: Some(LOG_FILE_NAME.to_string()),
+            }),
+            redacted(TargetKind::Webview),
+        ])
+        .level(LevelFilter::Trace)
+        .max_file_size(MAX_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(KEEP_FILES))
+        .build()
+}
+
+/// apply the default level once the plugin has installed the logger
+pub fn init() {
+    log::set_max_level(DEFAULT_LEVEL);
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("Unknown log level: {level}"))
+}
+
+/// change the log level at runtime ("off", "error", "warn", "info", "debug" or "trace")
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<(), String> {
+    let level = parse_level(&level)?;
+    log::set_max_level(level);
+    log::info!("Log level set to {level}");
+    Ok(())
+}
+
+/// get the current log level
+#[tauri::command]
+pub async fn get_log_level() -> Result<String, String> {
+    Ok(log::max_level().to_string().to_lowercase())
+}
+
+/// open the folder containing the log files in the system file manager
+#[tauri::command]
+pub async fn open_log_folder(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let dir = app_handle.path().app_log_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    app_handle
+        .opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
@@ -3,33 +3,101 @@
     windows_subsystem = "windows"
 )]
 
+mod account_config;
+mod account_health;
+mod accounts;
+mod agenda;
+mod api_server;
+mod appearance;
+mod archive;
+mod attachments;
+mod auth;
+mod benchmark;
+mod bulk;
+mod caldav;
+mod collation;
+mod conflicts;
+mod contacts;
+mod contexts;
+mod crash;
+mod credentials;
+mod db;
+mod db_watch;
+mod demo;
+mod dependencies;
+mod diagnostics;
+mod digest;
+mod e2e;
+mod effects;
+mod email_tasks;
+mod events;
+mod fuzzy;
+mod href;
+mod http_actions;
+mod i18n;
+mod ics_cache;
+mod ics_feed;
+mod imap;
+mod kanban;
+mod link_titles;
+mod local_folder;
+mod locale;
+mod lock;
+mod logging;
+mod maintenance;
+mod mcp;
+mod merge;
 mod migrations;
+#[cfg(feature = "mock-caldav")]
+mod mock_caldav;
+mod multistatus;
+mod nag;
+mod oauth;
+mod payload_limits;
+mod pending_changes;
+mod planning;
+mod pomodoro;
+mod providers;
+mod quick_add;
+mod read_only;
+mod recent_completions;
+mod recovery;
+mod recurrence;
+mod redact;
+mod report;
+mod roaming;
+mod rules;
+mod scripts;
+mod search;
+mod settings;
+mod shortcuts;
+mod smtp;
+mod snapshots;
+mod stats;
+mod streaks;
+mod sync_schedule;
+mod tasks;
+mod time_tracking;
+mod tls;
+mod transfer;
 mod tray;
+mod updater;
+mod webhooks;
+mod windows;
+mod wire_trace;
 
 use tauri::{Manager, RunEvent, WindowEvent};
-use tauri_plugin_log::{Target, TargetKind};
 use tauri_plugin_sql::Builder;
 
 fn main() {
+    let context = tauri::generate_context!();
+    crash::install_panic_hook(&context.config().identifier);
     let db_migrations = migrations::get_migrations();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
-        .plugin(
-            tauri_plugin_log::Builder::new()
-                .targets([
-                    Target::new(TargetKind::Stdout),
-                    Target::new(TargetKind::LogDir {
-                        file_name: Some("caldav-tasks".to_string()),
-                    }),
-                    Target::new(TargetKind::Webview),
-                ])
-                .level(log::LevelFilter::Debug)
-                .max_file_size(50_000)
-                .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
-                .build(),
-        )
+        .plugin(logging::plugin())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_shell::init())
@@ -39,7 +107,7 @@ fn main() {
         .plugin(tauri_plugin_notification::init())
         .plugin(
             Builder::default()
-                .add_migrations("sqlite:caldav-tasks.db", db_migrations)
+                .add_migrations(db::DB_URL, db_migrations)
                 .build(),
         )
         .invoke_handler(tauri::generate_handler![
@@ -47,13 +115,279 @@ fn main() {
             tray::update_tray_sync_enabled,
             tray::set_tray_visible,
             tray::get_tray_enabled,
-            tray::initialize_tray
+            tray::initialize_tray,
+            db::wait_for_database,
+            db::get_schema_compatibility,
+            db::get_database_url,
+            logging::set_log_level,
+            logging::get_log_level,
+            logging::open_log_folder,
+            crash::get_last_crash_report,
+            crash::dismiss_crash_report,
+            diagnostics::export_diagnostics,
+            benchmark::run_benchmark,
+            maintenance::repair_orphans,
+            maintenance::cleanup_database,
+            quick_add::parse_quick_add,
+            tray::is_tray_supported,
+            windows::open_calendar_window,
+            windows::get_open_calendar_windows,
+            effects::get_window_effect,
+            effects::set_window_effect,
+            effects::get_supported_window_effects,
+            updater::get_update_settings,
+            updater::set_update_settings,
+            updater::get_update_mode,
+            updater::check_for_updates,
+            updater::install_update,
+            wire_trace::set_wire_trace_enabled,
+            wire_trace::get_wire_trace_enabled,
+            wire_trace::record_wire_trace,
+            wire_trace::get_wire_trace,
+            wire_trace::clear_wire_trace,
+            caldav::test_connection,
+            caldav::fetch_calendar_objects,
+            caldav::dav_request,
+            ics_cache::get_cached_objects,
+            credentials::get_account_password,
+            credentials::set_account_password,
+            credentials::delete_account_password,
+            credentials::get_credential_migration_report,
+            tls::get_account_tls,
+            tls::set_account_tls,
+            lock::get_app_lock_status,
+            lock::unlock,
+            lock::lock_app,
+            lock::report_activity,
+            lock::set_app_lock,
+            e2e::set_account_encryption,
+            e2e::get_account_encryption,
+            e2e::encrypt_texts,
+            e2e::decrypt_texts,
+            read_only::set_read_only,
+            read_only::get_read_only,
+            demo::generate_demo_data,
+            demo::clear_demo_data,
+            demo::is_demo_mode,
+            demo::open_demo_instance,
+            tasks::query_visible_tasks,
+            tasks::reorder_tasks,
+            tasks::upsert_tasks,
+            tasks::duplicate_task,
+            bulk::bulk_update,
+            transfer::move_tasks,
+            dependencies::get_dependencies,
+            dependencies::add_dependency,
+            dependencies::remove_dependency,
+            dependencies::query_actionable_tasks,
+            dependencies::complete_task,
+            time_tracking::start_timer,
+            time_tracking::stop_timer,
+            time_tracking::get_running_timer,
+            time_tracking::get_time_entries,
+            time_tracking::delete_time_entry,
+            time_tracking::get_time_totals,
+            planning::get_planned_time,
+            kanban::get_kanban_board,
+            kanban::move_to_column,
+            kanban::add_kanban_column,
+            kanban::remove_kanban_column,
+            contexts::get_contexts,
+            contexts::save_context,
+            contexts::rename_context,
+            contexts::delete_context,
+            contexts::query_next_actions,
+            archive::get_archive_policy,
+            archive::set_archive_policy,
+            archive::query_archived_tasks,
+            archive::restore_archived_tasks,
+            archive::get_archived_uids,
+            merge::merge_tasks,
+            stats::get_completion_stats,
+            stats::get_overdue_trend,
+            report::generate_report,
+            streaks::get_streaks,
+            streaks::get_streak_in_tray,
+            streaks::set_streak_in_tray,
+            search::validate_search_query,
+            search::global_search,
+            webhooks::get_webhooks,
+            webhooks::save_webhook,
+            webhooks::delete_webhook,
+            webhooks::get_webhook_secret,
+            webhooks::get_webhook_deliveries,
+            webhooks::test_webhook,
+            scripts::get_scripts,
+            scripts::save_script,
+            scripts::delete_script,
+            scripts::run_script,
+            scripts::check_script,
+            rules::get_rules,
+            rules::save_rule,
+            rules::delete_rule,
+            rules::apply_rules,
+            rules::test_rule,
+            api_server::get_api_server_settings,
+            api_server::set_api_server_settings,
+            api_server::get_api_server_token,
+            api_server::regenerate_api_server_token,
+            mcp::get_mcp_enabled,
+            mcp::set_mcp_enabled,
+            email_tasks::get_email_to_task_settings,
+            email_tasks::set_email_to_task_settings,
+            email_tasks::get_email_to_task_status,
+            email_tasks::poll_email_now,
+            events::fetch_events,
+            events::promote_to_event,
+            http_actions::get_http_actions,
+            http_actions::save_http_action,
+            http_actions::delete_http_action,
+            http_actions::get_http_action_runs,
+            http_actions::test_http_action,
+            contacts::search_contacts,
+            account_health::get_account_health,
+            account_health::get_unreachable_servers,
+            account_health::report_sync_result,
+            account_health::revalidate_credentials,
+            oauth::set_oauth_tokens,
+            oauth::delete_oauth_tokens,
+            oauth::get_oauth_access_token,
+            oauth::refresh_oauth_tokens,
+            account_config::export_account_config,
+            account_config::import_account_config,
+            sync_schedule::get_sync_schedules,
+            sync_schedule::set_sync_schedule,
+            sync_schedule::get_due_accounts,
+            accounts::set_account_active,
+            accounts::set_calendar_archived,
+            accounts::reorder_accounts,
+            accounts::reorder_calendars,
+            accounts::set_account_color,
+            accounts::set_account_collapsed,
+            roaming::push_roaming_settings,
+            roaming::pull_roaming_settings,
+            roaming::merge_roaming_settings,
+            auth::negotiate_auth,
+            auth::digest_auth_header,
+            auth::set_account_auth_method,
+            providers::get_provider_presets,
+            providers::detect_provider,
+            conflicts::record_sync_conflict,
+            conflicts::get_sync_conflicts,
+            conflicts::resolve_sync_conflict,
+            pending_changes::get_pending_changes,
+            pending_changes::report_push_result,
+            pending_changes::discard_pending_change,
+            snapshots::get_db_snapshots,
+            snapshots::rollback_to_snapshot,
+            payload_limits::get_payload_limits,
+            payload_limits::set_payload_limits,
+            locale::get_locale_settings,
+            i18n::get_language,
+            i18n::set_language,
+            i18n::translate,
+            href::normalize_hrefs,
+            #[cfg(feature = "mock-caldav")]
+            mock_caldav::start_mock_caldav_server,
+            #[cfg(feature = "mock-caldav")]
+            mock_caldav::set_mock_caldav_fault,
+            #[cfg(feature = "mock-caldav")]
+            mock_caldav::stop_mock_caldav_server,
+            local_folder::list_folder_calendars,
+            local_folder::create_folder_calendar,
+            local_folder::update_folder_calendar,
+            local_folder::delete_folder_calendar,
+            local_folder::read_folder_objects,
+            local_folder::create_folder_object,
+            local_folder::update_folder_object,
+            local_folder::delete_folder_object,
+            recurrence::preview_occurrences,
+            nag::get_task_nag,
+            nag::set_task_nag,
+            nag::dismiss_task_nag,
+            agenda::agenda,
+            recovery::recover_calendar_tasks,
+            recovery::recover_account_tasks,
+            shortcuts::get_shortcuts,
+            shortcuts::check_shortcuts,
+            shortcuts::set_shortcuts,
+            shortcuts::export_shortcuts,
+            shortcuts::import_shortcuts,
+            appearance::get_system_appearance,
+            attachments::fetch_attachment,
+            attachments::get_attachment_cache_usage,
+            attachments::set_attachment_cache_limit,
+            attachments::clear_attachment_cache,
+            ics_feed::get_ics_feed,
+            ics_feed::set_ics_feed_calendars,
+            ics_feed::regenerate_ics_feed_token,
+            link_titles::get_fetch_link_titles,
+            link_titles::set_fetch_link_titles,
+            link_titles::fetch_page_title,
+            digest::get_email_digest_settings,
+            digest::set_email_digest_settings,
+            digest::get_email_digest_status,
+            digest::send_email_digest_now,
+            fuzzy::fuzzy_find,
+            db_watch::note_local_write,
+            pomodoro::start_pomodoro,
+            pomodoro::pause_pomodoro,
+            pomodoro::skip_pomodoro,
+            pomodoro::stop_pomodoro,
+            pomodoro::get_pomodoro_status,
+            pomodoro::get_pomodoro_settings,
+            pomodoro::set_pomodoro_settings
         ])
-        .setup(|_app| {
+        .setup(|app| {
+            logging::init();
+            read_only::init_from_args();
+            demo::init_from_args();
+
+            // show the window right away; the database is opened and migrated in the
+            // background and the frontend waits for it via `wait_for_database`
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+            }
+            db::init_in_background(app.handle().clone());
+            updater::start_scheduled_checks(app.handle().clone());
+            lock::start_idle_timer(app.handle().clone());
+            pomodoro::start_ticker(app.handle().clone());
+            archive::start_scheduled_runs(app.handle().clone());
+            streaks::start_tray_updates(app.handle().clone());
+            recent_completions::start_tray_updates(app.handle().clone());
+            db_watch::start_watching(app.handle().clone());
+            webhooks::start_scheduled_runs(app.handle().clone());
+            scripts::start_scheduled_runs(app.handle().clone());
+            api_server::start_if_enabled(app.handle().clone());
+            email_tasks::start_polling(app.handle().clone());
+            digest::start_scheduled_sends(app.handle().clone());
+            local_folder::start_watching(app.handle().clone());
+            nag::start_nagging(app.handle().clone());
+            http_actions::start_scheduled_runs(app.handle().clone());
+            appearance::watch_appearance(app.handle().clone());
+
             // tray will be initialized from frontend after reading settings
             Ok(())
         })
         .on_window_event(|window, event| {
+            // per-calendar windows close normally and drop their state
+            if windows::is_calendar_window(window.label()) {
+                if let WindowEvent::Destroyed = event {
+                    windows::forget_window(window.label());
+                }
+                return;
+            }
+
+            // keep the tray icon crisp when the main window moves to a display with another scale
+            if let WindowEvent::ScaleFactorChanged { .. } = event {
+                tray::refresh_tray_icon(window.app_handle());
+            }
+
+            // the system theme or accent color may have changed, in settings or while away
+            if let WindowEvent::ThemeChanged(_) | WindowEvent::Focused(true) = event {
+                appearance::refresh_appearance(window.app_handle());
+            }
+
             // hide window instead of closing when X is clicked, but only if tray is enabled
             if let WindowEvent::CloseRequested { api, .. } = event {
                 // check if tray is enabled
@@ -68,13 +402,21 @@ fn main() {
                             .app_handle()
                             .set_activation_policy(tauri::ActivationPolicy::Accessory);
                     }
+                } else if tray::is_tray_fallback() {
+                    // tray was requested but the desktop can't show it, keep the app running
+                    let _ = window.minimize();
+                    api.prevent_close();
                 }
                 // if tray is disabled, let the window close normally
             }
         })
-        .build(tauri::generate_context!())
+        .build(context)
         .expect("error while running tauri application")
         .run(|app_handle, event| {
+            if let RunEvent::Exit = event {
+                demo::discard_profile();
+            }
+
             // handle app reactivation (e.g., from Spotlight, Dock, Cmd+Tab)
             #[cfg(target_os = "macos")]
             {
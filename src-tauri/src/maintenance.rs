@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Sqlite, Transaction};
+use tauri::Emitter;
+
+use crate::{db, lock, read_only};
+
+// tasks whose calendar or account no longer exists (a calendar of a removed account
+// counts as gone too)
+const ORPHANED_TASKS: &str = "(calendar_id IS NOT NULL AND calendar_id NOT IN (
+        SELECT id FROM calendars WHERE account_id IN (SELECT id FROM accounts)))
+     OR (account_id IS NOT NULL AND account_id NOT IN (SELECT id FROM accounts))";
+const ORPHANED_CALENDARS: &str = "account_id NOT IN (SELECT id FROM accounts)";
+const ORPHANED_DELETIONS: &str = "account_id NOT IN (SELECT id FROM accounts)";
+const UNUSED_TAGS: &str = "NOT EXISTS (
+        SELECT 1 FROM tasks, json_each(tasks.tags) WHERE json_each.value = tags.id)";
+// cached ics of calendars that are gone
+const ORPHANED_CACHE: &str = "calendar_id NOT IN (SELECT id FROM calendars)";
+
+/// what to do with tasks whose calendar or account is gone
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrphanRepair {
+    /// keep them as local tasks
+    #[default]
+    Reassign,
+    Delete,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanReport {
+    pub tasks_reassigned: u64,
+    pub tasks_deleted: u64,
+    pub calendars_removed: u64,
+}
+
+async fn execute(tx: &mut Transaction<'_, Sqlite>, query: &str) -> Result<u64, String> {
+    sqlx::query(query)
+        .execute(&mut **tx)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| e.to_string())
+}
+
+/// fix tasks and calendars that point at rows which no longer exist
+#[tauri::command]
+pub async fn repair_orphans(
+    app_handle: tauri::AppHandle,
+    mode: Option<OrphanRepair>,
+) -> Result<OrphanReport, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut report = OrphanReport::default();
+    match mode.unwrap_or_default() {
+        OrphanRepair::Reassign => {
+            report.tasks_reassigned = execute(
+                &mut tx,
+                &format!(
+                    "UPDATE tasks
+                     SET account_id = NULL, calendar_id = NULL, local_only = 1, synced = 0,
+                         etag = NULL, href = NULL
+                     WHERE {ORPHANED_TASKS}"
+                ),
+            )
+            .await?
+        }
+        OrphanRepair::Delete => {
+            report.tasks_deleted = execute(
+                &mut tx,
+                &format!("DELETE FROM tasks WHERE {ORPHANED_TASKS}"),
+            )
+            .await?
+        }
+    }
+    report.calendars_removed = execute(
+        &mut tx,
+        "DELETE FROM calendars WHERE account_id NOT IN (SELECT id FROM accounts)",
+    )
+    .await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let changed = report.tasks_reassigned + report.tasks_deleted + report.calendars_removed;
+    if changed > 0 {
+        log::info!("Repaired orphans: {report:?}");
+        let _ = app_handle.emit("database-changed", ());
+    }
+    Ok(report)
+}
+
+/// a row cleanup_database removes
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DanglingRow {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    /// tasks whose calendar or account was deleted
+    pub tasks: Vec<DanglingRow>,
+    /// calendars of deleted accounts
+    pub calendars: Vec<DanglingRow>,
+    /// server deletions still queued for deleted accounts
+    pub pending_deletions: Vec<DanglingRow>,
+    /// tags no task uses anymore
+    pub tags: Vec<DanglingRow>,
+    /// cached ics of deleted calendars
+    pub cached_objects: i64,
+    /// whether the rows were removed (false for a dry run)
+    pub applied: bool,
+}
+
+impl CleanupReport {
+    fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+            && self.calendars.is_empty()
+            && self.pending_deletions.is_empty()
+            && self.tags.is_empty()
+            && self.cached_objects == 0
+    }
+}
+
+async fn dangling(
+    tx: &mut Transaction<'_, Sqlite>,
+    query: &str,
+) -> Result<Vec<DanglingRow>, String> {
+    sqlx::query_as(query)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// find rows that point at deleted accounts or calendars and tags nothing uses anymore.
+/// without `apply` this is a dry run that only reports what would be removed
+#[tauri::command]
+pub async fn cleanup_database(
+    app_handle: tauri::AppHandle,
+    apply: bool,
+) -> Result<CleanupReport, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    if apply {
+        read_only::ensure_writable()?;
+    }
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut report = CleanupReport {
+        tasks: dangling(
+            &mut tx,
+            &format!("SELECT id, title AS name FROM tasks WHERE {ORPHANED_TASKS}"),
+        )
+        .await?,
+        calendars: dangling(
+            &mut tx,
+            &format!("SELECT id, display_name AS name FROM calendars WHERE {ORPHANED_CALENDARS}"),
+        )
+        .await?,
+        pending_deletions: dangling(
+            &mut tx,
+            &format!(
+                "SELECT uid AS id, href AS name FROM pending_deletions WHERE {ORPHANED_DELETIONS}"
+            ),
+        )
+        .await?,
+        tags: dangling(
+            &mut tx,
+            &format!("SELECT id, name FROM tags WHERE {UNUSED_TAGS}"),
+        )
+        .await?,
+        cached_objects: sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM ics_cache WHERE {ORPHANED_CACHE}"
+        ))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?,
+        applied: false,
+    };
+    if !apply || report.is_empty() {
+        return Ok(report);
+    }
+
+    // tasks first, so none of them go through the calendar cascade unaccounted for
+    for query in [
+        format!("DELETE FROM tasks WHERE {ORPHANED_TASKS}"),
+        format!("DELETE FROM calendars WHERE {ORPHANED_CALENDARS}"),
+        format!("DELETE FROM pending_deletions WHERE {ORPHANED_DELETIONS}"),
+        format!("DELETE FROM tags WHERE {UNUSED_TAGS}"),
+        format!("DELETE FROM ics_cache WHERE {ORPHANED_CACHE}"),
+        "UPDATE ui_state SET active_tag_id = NULL
+         WHERE active_tag_id IS NOT NULL AND active_tag_id NOT IN (SELECT id FROM tags)"
+            .to_string(),
+        "UPDATE ui_state SET active_calendar_id = NULL
+         WHERE active_calendar_id IS NOT NULL
+           AND active_calendar_id NOT IN (SELECT id FROM calendars)"
+            .to_string(),
+        "UPDATE ui_state SET selected_task_id = NULL
+         WHERE selected_task_id IS NOT NULL AND selected_task_id NOT IN (SELECT id FROM tasks)"
+            .to_string(),
+    ] {
+        execute(&mut tx, &query).await?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    report.applied = true;
+
+    log::info!(
+        "Database cleanup removed {} tasks, {} calendars, {} pending deletions, {} tags and {} cached objects",
+        report.tasks.len(),
+        report.calendars.len(),
+        report.pending_deletions.len(),
+        report.tags.len(),
+        report.cached_objects
+    );
+    let _ = app_handle.emit("database-changed", ());
+    Ok(report)
+}
@@ -0,0 +1,296 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+
+use crate::api_server::{self, NewTask, Response, TaskPatch};
+use crate::quick_add::to_iso;
+use crate::tasks::TaskFilter;
+use crate::{db, read_only, settings};
+
+// the Model Context Protocol endpoint on the api server (POST /mcp, plain json responses);
+// assistants get the app's tasks through it, never the account credentials
+const ENABLED_KEY: &str = "mcp_enabled";
+// newest first; a client asking for another version gets the newest
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+const DEFAULT_LIMIT: u32 = 50;
+// task fields an assistant gets to see
+const TASK_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "description",
+    "completed",
+    "dueDate",
+    "dueDateAllDay",
+    "priority",
+    "calendarId",
+    "parentUid",
+];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+pub async fn is_enabled(pool: &SqlitePool) -> Result<bool, String> {
+    Ok(settings::get(pool, ENABLED_KEY).await?.as_deref() == Some("true"))
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "list_lists",
+            "description": "List the task lists (CalDAV calendars) with their ids and accounts.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "list_tasks",
+            "description": "List tasks in manual order. `search` takes plain text or the app's \
+                search syntax, e.g. `due<today tag:work`.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "search": { "type": "string" },
+                    "listId": { "type": "string", "description": "id from list_lists" },
+                    "includeCompleted": { "type": "boolean", "default": false },
+                    "limit": { "type": "integer", "minimum": 1, "default": DEFAULT_LIMIT }
+                }
+            }
+        },
+        {
+            "name": "create_task",
+            "description": "Create a task. Without a listId it goes to the first list.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "title": { "type": "string" },
+                    "description": { "type": "string" },
+                    "listId": { "type": "string", "description": "id from list_lists" },
+                    "dueDate": {
+                        "type": "string",
+                        "description": "YYYY-MM-DD for an all-day date, or an ISO 8601 timestamp"
+                    },
+                    "priority": { "type": "string", "enum": ["high", "medium", "low", "none"] }
+                },
+                "required": ["title"]
+            }
+        },
+        {
+            "name": "complete_task",
+            "description": "Mark a task as done, or as not done with `completed: false`.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "id from list_tasks" },
+                    "completed": { "type": "boolean", "default": true }
+                },
+                "required": ["id"]
+            }
+        }
+    ])
+}
+
+// (due date, all day)
+fn parse_due(date: &str) -> Result<(String, bool), String> {
+    if let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        let due = to_iso(day.and_time(NaiveTime::MIN))
+            .ok_or_else(|| format!("{date} doesn't exist in the local time zone"))?;
+        return Ok((due, true));
+    }
+    DateTime::parse_from_rfc3339(date)
+        .map(|due| {
+            let due = due.with_timezone(&Utc);
+            (due.to_rfc3339_opts(SecondsFormat::Millis, true), false)
+        })
+        .map_err(|_| format!("Expected YYYY-MM-DD or an ISO 8601 timestamp, got \"{date}\""))
+}
+
+fn summarize(task: &Value) -> Value {
+    TASK_FIELDS
+        .iter()
+        .filter_map(|field| Some((field.to_string(), task.get(*field)?.clone())))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn string(arguments: &Value, name: &str) -> Option<String> {
+    arguments.get(name)?.as_str().map(str::to_string)
+}
+
+// the answer to tools/call; failures of the tool itself are results with isError set
+async fn call(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    params: &Value,
+) -> Result<Value, (i64, String)> {
+    let name = params["name"].as_str().unwrap_or_default();
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+    if matches!(name, "create_task" | "complete_task") {
+        if let Err(e) = read_only::ensure_writable() {
+            return Ok(tool_result(Response::error(403, e)));
+        }
+    }
+
+    let response = match name {
+        "list_lists" => api_server::list_lists(pool).await,
+        "list_tasks" => {
+            let filter = TaskFilter {
+                search: string(&arguments, "search"),
+                calendar_id: string(&arguments, "listId"),
+                completed: match arguments["includeCompleted"].as_bool() {
+                    Some(true) => None,
+                    _ => Some(false),
+                },
+                ..TaskFilter::default()
+            };
+            let limit = arguments["limit"]
+                .as_u64()
+                .map_or(DEFAULT_LIMIT, |limit| limit.min(u32::MAX.into()) as u32);
+            api_server::list_tasks(pool, &filter, 0, limit)
+                .await
+                .map(|mut response| {
+                    if let Some(tasks) = response
+                        .body
+                        .as_mut()
+                        .and_then(|body| body.get_mut("tasks"))
+                        .and_then(Value::as_array_mut)
+                    {
+                        for task in tasks.iter_mut() {
+                            *task = summarize(task);
+                        }
+                    }
+                    response
+                })
+        }
+        "create_task" => {
+            let Some(title) = string(&arguments, "title") else {
+                return Err((INVALID_PARAMS, "create_task needs a title".to_string()));
+            };
+            let due = match string(&arguments, "dueDate").as_deref().map(parse_due) {
+                Some(Err(e)) => return Ok(tool_result(Response::error(400, e))),
+                due => due.and_then(Result::ok),
+            };
+            let new = NewTask {
+                title,
+                description: string(&arguments, "description").unwrap_or_default(),
+                calendar_id: string(&arguments, "listId"),
+                priority: string(&arguments, "priority"),
+                due_date_all_day: due.as_ref().is_some_and(|(_, all_day)| *all_day),
+                due_date: due.map(|(due, _)| due),
+                ..NewTask::default()
+            };
+            api_server::create_task(app_handle, pool, new).await
+        }
+        "complete_task" => {
+            let Some(id) = string(&arguments, "id") else {
+                return Err((INVALID_PARAMS, "complete_task needs an id".to_string()));
+            };
+            let patch = TaskPatch {
+                completed: Some(arguments["completed"].as_bool().unwrap_or(true)),
+                ..TaskPatch::default()
+            };
+            api_server::update_task(app_handle, pool, &id, patch).await
+        }
+        _ => return Err((INVALID_PARAMS, format!("Unknown tool \"{name}\""))),
+    };
+
+    let mut response = response.unwrap_or_else(|response| response);
+    if matches!(name, "create_task" | "complete_task") && response.status < 400 {
+        response.body = response.body.as_ref().map(summarize);
+    }
+    Ok(tool_result(response))
+}
+
+fn tool_result(response: Response) -> Value {
+    let text = response
+        .body
+        .map(|body| serde_json::to_string_pretty(&body).unwrap_or_default())
+        .unwrap_or_default();
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": response.status >= 400,
+    })
+}
+
+// the answer to one message, None for notifications and for responses from the client
+async fn respond(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    message: Value,
+) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return match message.get("result").or(message.get("error")) {
+            Some(_) => None,
+            None => Some(error(Value::Null, INVALID_REQUEST, "Invalid request")),
+        };
+    };
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+
+    let result = match method {
+        "initialize" => {
+            let requested = params["protocolVersion"].as_str().unwrap_or_default();
+            let version = PROTOCOL_VERSIONS
+                .iter()
+                .find(|version| **version == requested)
+                .unwrap_or(&PROTOCOL_VERSIONS[0]);
+            Ok(json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "caldav-tasks", "version": env!("CARGO_PKG_VERSION") },
+                "instructions": "Tasks from the user's CalDAV task lists. Changes show up in \
+                    the app right away and are uploaded with its next sync."
+            }))
+        }
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => call(app_handle, pool, &params).await,
+        _ => Err((METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+    };
+
+    // notifications (no id) never get an answer
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error(id, code, &message),
+    })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// answer a POST /mcp body, a single json-rpc message or a batch
+pub async fn handle(app_handle: &tauri::AppHandle, pool: &SqlitePool, body: &[u8]) -> Response {
+    let message: Value = match serde_json::from_slice(body) {
+        Ok(message) => message,
+        Err(e) => return Response::json(200, error(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    let answer = match message {
+        Value::Array(messages) => {
+            let mut answers = Vec::new();
+            for message in messages {
+                answers.extend(respond(app_handle, pool, message).await);
+            }
+            (!answers.is_empty()).then_some(Value::Array(answers))
+        }
+        message => respond(app_handle, pool, message).await,
+    };
+    match answer {
+        Some(answer) => Response::json(200, answer),
+        None => Response::empty(202),
+    }
+}
+
+#[tauri::command]
+pub async fn get_mcp_enabled(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let pool = db::pool(&app_handle).await?;
+    is_enabled(&pool).await
+}
+
+/// serve MCP at /mcp of the api server, with the same token
+#[tauri::command]
+pub async fn set_mcp_enabled(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    settings::set(&pool, ENABLED_KEY, &enabled.to_string()).await
+}
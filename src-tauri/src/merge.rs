@@ -0,0 +1,201 @@
+use serde_json::Value;
+use sqlx::{Sqlite, Transaction};
+use std::collections::HashSet;
+use tauri::Emitter;
+
+use crate::tasks::{fetch_by_ids, now_iso, save_task, Task, TaskRow};
+use crate::{bulk, db, read_only, transfer};
+
+async fn load(tx: &mut Transaction<'_, Sqlite>, id: &str) -> Result<Task, String> {
+    sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(Task::from)
+        .ok_or_else(|| format!("Task {id} not found"))
+}
+
+// add the items of `other` that aren't in `items` yet; two items are the same when their
+// `key` field matches
+fn union_items(items: Option<Value>, other: Option<Value>, key: &str) -> Option<Value> {
+    let Some(Value::Array(other)) = other else {
+        return items;
+    };
+    let mut items = match items {
+        Some(Value::Array(items)) => items,
+        _ => Vec::new(),
+    };
+    for item in other {
+        let known = items
+            .iter()
+            .any(|existing| existing.get(key).is_some() && existing.get(key) == item.get(key));
+        if !known {
+            items.push(item);
+        }
+    }
+    (!items.is_empty()).then_some(Value::Array(items))
+}
+
+// what the duplicate adds to the primary task
+fn absorb(merged: &mut Task, duplicate: &Task) {
+    for tag in &duplicate.tags {
+        if !merged.tags.contains(tag) {
+            merged.tags.push(tag.clone());
+        }
+    }
+    for context in &duplicate.contexts {
+        if !merged.contexts.contains(context) {
+            merged.contexts.push(context.clone());
+        }
+    }
+
+    let description = duplicate.description.trim();
+    if !description.is_empty() && !merged.description.contains(description) {
+        if !merged.description.is_empty() {
+            merged.description.push_str("\n\n");
+        }
+        merged.description.push_str(description);
+    }
+
+    merged.subtasks = union_items(
+        Some(merged.subtasks.take()),
+        Some(duplicate.subtasks.clone()),
+        "title",
+    )
+    .unwrap_or(Value::Array(Vec::new()));
+    merged.reminders = union_items(
+        merged.reminders.take(),
+        duplicate.reminders.clone(),
+        "trigger",
+    );
+}
+
+/// fold duplicates into one task: tags, contexts, checklist items, descriptions and reminders
+/// are combined, sub-tasks, dependencies and tracked time move over, and the duplicates are
+/// deleted, on the server with the next sync. returns the merged task
+#[tauri::command]
+pub async fn merge_tasks(
+    app_handle: tauri::AppHandle,
+    primary: String,
+    duplicates: Vec<String>,
+) -> Result<Task, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut merged = load(&mut tx, &primary).await?;
+    let mut removed: Vec<Task> = Vec::with_capacity(duplicates.len());
+    for id in &duplicates {
+        if *id != primary && !removed.iter().any(|task| task.id == *id) {
+            removed.push(load(&mut tx, id).await?);
+        }
+    }
+    if removed.is_empty() {
+        return Ok(merged);
+    }
+    let uids: HashSet<&str> = removed.iter().map(|task| task.uid.as_str()).collect();
+
+    for duplicate in &removed {
+        absorb(&mut merged, duplicate);
+    }
+    // a primary below one of its duplicates takes that duplicate's place in the tree
+    let mut passed = HashSet::new();
+    while let Some(parent) = merged
+        .parent_uid
+        .clone()
+        .filter(|parent| uids.contains(parent.as_str()) && passed.insert(parent.clone()))
+    {
+        merged.parent_uid = removed
+            .iter()
+            .find(|task| task.uid == parent)
+            .and_then(|task| task.parent_uid.clone());
+    }
+    if merged
+        .parent_uid
+        .as_deref()
+        .is_some_and(|parent| uids.contains(parent))
+    {
+        merged.parent_uid = None;
+    }
+    let now = now_iso();
+    merged.modified_at = now.clone();
+    merged.synced = false;
+    save_task(&mut tx, &merged).await?;
+
+    // sub-tasks of the duplicates hang under the primary, in its calendar
+    let mut children: Vec<(String, String, Option<String>)> = Vec::new();
+    for uid in &uids {
+        let rows: Vec<(String, String, Option<String>)> =
+            sqlx::query_as("SELECT id, uid, calendar_id FROM tasks WHERE parent_uid = ?")
+                .bind(uid)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        children.extend(
+            rows.into_iter()
+                .filter(|(_, uid, _)| *uid != merged.uid && !uids.contains(uid.as_str())),
+        );
+    }
+    if !merged.calendar_id.is_empty() {
+        let elsewhere: Vec<String> = children
+            .iter()
+            .filter(|(_, _, calendar_id)| calendar_id.as_deref() != Some(&merged.calendar_id))
+            .map(|(id, _, _)| id.clone())
+            .collect();
+        if !elsewhere.is_empty() {
+            transfer::move_to(&mut tx, &elsewhere, &merged.calendar_id).await?;
+        }
+    }
+    for (id, _, _) in &children {
+        sqlx::query("UPDATE tasks SET parent_uid = ?, modified_at = ?, synced = 0 WHERE id = ?")
+            .bind(&merged.uid)
+            .bind(&now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    for uid in &uids {
+        for statement in [
+            "INSERT OR IGNORE INTO task_dependencies (task_uid, depends_on_uid)
+             SELECT ?1, depends_on_uid FROM task_dependencies
+             WHERE task_uid = ?2 AND depends_on_uid != ?1",
+            "INSERT OR IGNORE INTO task_dependencies (task_uid, depends_on_uid)
+             SELECT task_uid, ?1 FROM task_dependencies
+             WHERE depends_on_uid = ?2 AND task_uid != ?1",
+            "DELETE FROM task_dependencies WHERE depends_on_uid = ?2",
+            "UPDATE time_entries SET task_uid = ?1 WHERE task_uid = ?2",
+            "UPDATE pomodoros SET task_uid = ?1 WHERE task_uid = ?2",
+        ] {
+            sqlx::query(statement)
+                .bind(&merged.uid)
+                .bind(uid)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    bulk::delete(&mut tx, &removed).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!(
+        "Merged {} duplicates ({}) into task {}",
+        removed.len(),
+        removed
+            .iter()
+            .map(|task| task.uid.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+        merged.uid
+    );
+    // the duplicates left pending deletions behind, so the frontend reloads everything
+    let _ = app_handle.emit("database-changed", ());
+
+    Ok(fetch_by_ids(&pool, &[merged.id.clone()])
+        .await?
+        .pop()
+        .unwrap_or(merged))
+}
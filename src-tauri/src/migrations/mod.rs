@@ -1,14 +1,102 @@
 mod v001_initial_tables;
 mod v002_nullable_account_calendar;
 mod v003_add_url_field;
+mod v004_account_tls_settings;
+mod v005_app_settings;
+mod v006_task_query_indexes;
+mod v007_ics_cache;
+mod v008_repair_orphans;
+mod v009_task_dependencies;
+mod v010_time_entries;
+mod v011_pomodoros;
+mod v012_estimated_duration;
+mod v013_calendar_checklist_items;
+mod v014_kanban_column;
+mod v015_contexts;
+mod v016_archived_tasks;
+mod v017_webhooks;
+mod v018_scripts;
+mod v019_rules;
+mod v020_http_actions;
+mod v021_account_health;
+mod v022_account_sync_schedule;
+mod v023_account_ordering;
+mod v024_account_auth_method;
+mod v025_sync_conflicts;
+mod v026_sync_push_failures;
+mod v027_account_sync_backend;
+mod v028_task_nags;
+mod v029_calendar_archived;
+mod v030_attachment_cache;
+mod v031_task_change_id;
 
 use tauri_plugin_sql::Migration;
 
 pub use v001_initial_tables::migration as migration_v001;
 pub use v002_nullable_account_calendar::migration as migration_v002;
 pub use v003_add_url_field::migration as migration_v003;
+pub use v004_account_tls_settings::migration as migration_v004;
+pub use v005_app_settings::migration as migration_v005;
+pub use v006_task_query_indexes::migration as migration_v006;
+pub use v007_ics_cache::migration as migration_v007;
+pub use v008_repair_orphans::migration as migration_v008;
+pub use v009_task_dependencies::migration as migration_v009;
+pub use v010_time_entries::migration as migration_v010;
+pub use v011_pomodoros::migration as migration_v011;
+pub use v012_estimated_duration::migration as migration_v012;
+pub use v013_calendar_checklist_items::migration as migration_v013;
+pub use v014_kanban_column::migration as migration_v014;
+pub use v015_contexts::migration as migration_v015;
+pub use v016_archived_tasks::migration as migration_v016;
+pub use v017_webhooks::migration as migration_v017;
+pub use v018_scripts::migration as migration_v018;
+pub use v019_rules::migration as migration_v019;
+pub use v020_http_actions::migration as migration_v020;
+pub use v021_account_health::migration as migration_v021;
+pub use v022_account_sync_schedule::migration as migration_v022;
+pub use v023_account_ordering::migration as migration_v023;
+pub use v024_account_auth_method::migration as migration_v024;
+pub use v025_sync_conflicts::migration as migration_v025;
+pub use v026_sync_push_failures::migration as migration_v026;
+pub use v027_account_sync_backend::migration as migration_v027;
+pub use v028_task_nags::migration as migration_v028;
+pub use v029_calendar_archived::migration as migration_v029;
+pub use v030_attachment_cache::migration as migration_v030;
+pub use v031_task_change_id::migration as migration_v031;
 
 /// Returns all database migrations for the application
 pub fn get_migrations() -> Vec<Migration> {
-    vec![migration_v001(), migration_v002(), migration_v003()]
+    vec![
+        migration_v001(),
+        migration_v002(),
+        migration_v003(),
+        migration_v004(),
+        migration_v005(),
+        migration_v006(),
+        migration_v007(),
+        migration_v008(),
+        migration_v009(),
+        migration_v010(),
+        migration_v011(),
+        migration_v012(),
+        migration_v013(),
+        migration_v014(),
+        migration_v015(),
+        migration_v016(),
+        migration_v017(),
+        migration_v018(),
+        migration_v019(),
+        migration_v020(),
+        migration_v021(),
+        migration_v022(),
+        migration_v023(),
+        migration_v024(),
+        migration_v025(),
+        migration_v026(),
+        migration_v027(),
+        migration_v028(),
+        migration_v029(),
+        migration_v030(),
+        migration_v031(),
+    ]
 }
@@ -0,0 +1,18 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds per-account TLS settings
+/// Invalid hostnames are only ever accepted together with a pinned certificate
+pub fn migration() -> Migration {
+    Migration {
+        version: 4,
+        description: "add_account_tls_settings",
+        sql: r#"
+            -- Minimum TLS version ("1.2" or "1.3")
+            ALTER TABLE accounts ADD COLUMN tls_min_version TEXT NOT NULL DEFAULT '1.2';
+            -- PEM certificate the server must present instead of a publicly trusted one
+            ALTER TABLE accounts ADD COLUMN tls_pinned_certificate TEXT;
+            ALTER TABLE accounts ADD COLUMN tls_allow_invalid_hostnames INTEGER NOT NULL DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
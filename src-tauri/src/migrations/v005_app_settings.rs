@@ -0,0 +1,17 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds a key/value table for settings owned by the backend
+/// (the frontend keeps its own preferences in local storage)
+pub fn migration() -> Migration {
+    Migration {
+        version: 5,
+        description: "add_app_settings",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS app_settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
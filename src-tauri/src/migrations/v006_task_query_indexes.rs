@@ -0,0 +1,18 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds indexes for the columns the backend task queries filter and sort on
+pub fn migration() -> Migration {
+    Migration {
+        version: 6,
+        description: "add_task_query_indexes",
+        sql: r#"
+            CREATE INDEX IF NOT EXISTS idx_tasks_completed_sort_order ON tasks(completed, sort_order);
+            CREATE INDEX IF NOT EXISTS idx_tasks_calendar_sort_order ON tasks(calendar_id, sort_order);
+            CREATE INDEX IF NOT EXISTS idx_tasks_due_date ON tasks(due_date);
+            CREATE INDEX IF NOT EXISTS idx_tasks_start_date ON tasks(start_date);
+            CREATE INDEX IF NOT EXISTS idx_tasks_modified_at ON tasks(modified_at);
+            CREATE INDEX IF NOT EXISTS idx_tasks_created_at ON tasks(created_at);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,20 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds a cache of the raw ICS of every fetched calendar object, keyed by href and etag
+pub fn migration() -> Migration {
+    Migration {
+        version: 7,
+        description: "add_ics_cache",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS ics_cache (
+                href TEXT PRIMARY KEY,
+                calendar_id TEXT NOT NULL,
+                etag TEXT NOT NULL,
+                ics TEXT NOT NULL,
+                fetched_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_ics_cache_calendar ON ics_cache(calendar_id);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,21 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Repairs rows left behind while foreign keys weren't enforced: tasks whose calendar or
+/// account is gone become local tasks, calendars of removed accounts are dropped
+pub fn migration() -> Migration {
+    Migration {
+        version: 8,
+        description: "repair_orphans",
+        sql: r#"
+            UPDATE tasks
+            SET account_id = NULL, calendar_id = NULL, local_only = 1, synced = 0,
+                etag = NULL, href = NULL
+            WHERE (calendar_id IS NOT NULL AND calendar_id NOT IN (
+                    SELECT id FROM calendars WHERE account_id IN (SELECT id FROM accounts)))
+               OR (account_id IS NOT NULL AND account_id NOT IN (SELECT id FROM accounts));
+
+            DELETE FROM calendars WHERE account_id NOT IN (SELECT id FROM accounts);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,22 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Tasks that must be completed before another one can start (RELATED-TO;RELTYPE=DEPENDS-ON);
+/// the blocker is kept by uid since it may live in a calendar that hasn't been synced yet
+pub fn migration() -> Migration {
+    Migration {
+        version: 9,
+        description: "task_dependencies",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS task_dependencies (
+                task_uid TEXT NOT NULL,
+                depends_on_uid TEXT NOT NULL,
+                PRIMARY KEY (task_uid, depends_on_uid),
+                FOREIGN KEY (task_uid) REFERENCES tasks(uid) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_task_dependencies_depends_on
+                ON task_dependencies(depends_on_uid);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,22 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds tracked time per task; a running timer is the entry without an end
+pub fn migration() -> Migration {
+    Migration {
+        version: 10,
+        description: "time_entries",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS time_entries (
+                id TEXT PRIMARY KEY NOT NULL,
+                task_uid TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                ended_at TEXT,
+                FOREIGN KEY (task_uid) REFERENCES tasks(uid) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_time_entries_task_uid ON time_entries(task_uid);
+            CREATE INDEX IF NOT EXISTS idx_time_entries_started_at ON time_entries(started_at);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
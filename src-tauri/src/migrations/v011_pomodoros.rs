@@ -0,0 +1,21 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds a log of finished pomodoros and the task each one was spent on
+pub fn migration() -> Migration {
+    Migration {
+        version: 11,
+        description: "pomodoros",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS pomodoros (
+                id TEXT PRIMARY KEY NOT NULL,
+                task_uid TEXT,
+                started_at TEXT NOT NULL,
+                ended_at TEXT NOT NULL,
+                FOREIGN KEY (task_uid) REFERENCES tasks(uid) ON DELETE SET NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_pomodoros_task_uid ON pomodoros(task_uid);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the estimated duration of a task in seconds (X-ESTIMATED-DURATION, as Tasks.org writes it)
+pub fn migration() -> Migration {
+    Migration {
+        version: 12,
+        description: "add_estimated_duration",
+        sql: r#"
+            ALTER TABLE tasks ADD COLUMN estimated_duration INTEGER;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
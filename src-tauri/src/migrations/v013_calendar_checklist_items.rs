@@ -0,0 +1,14 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds a per-calendar option to upload checklist items as child VTODOs
+/// instead of the app-specific X-CALDAV-TASKS-SUBTASKS property
+pub fn migration() -> Migration {
+    Migration {
+        version: 13,
+        description: "add_calendar_checklist_items",
+        sql: r#"
+            ALTER TABLE calendars ADD COLUMN checklist_as_tasks INTEGER DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
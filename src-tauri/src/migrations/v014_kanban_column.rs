@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the board column of open tasks (NULL = to do; completed tasks are always done)
+pub fn migration() -> Migration {
+    Migration {
+        version: 14,
+        description: "add_kanban_column",
+        sql: r#"
+            ALTER TABLE tasks ADD COLUMN kanban_column TEXT;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
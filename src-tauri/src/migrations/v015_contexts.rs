@@ -0,0 +1,19 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds GTD contexts (@home, @errands, ...), kept apart from tags; tasks list the names of
+/// their contexts, the contexts table keeps them (and their color) while no task uses them
+pub fn migration() -> Migration {
+    Migration {
+        version: 15,
+        description: "add_contexts",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS contexts (
+                name TEXT PRIMARY KEY NOT NULL COLLATE NOCASE,
+                color TEXT
+            );
+
+            ALTER TABLE tasks ADD COLUMN contexts TEXT;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
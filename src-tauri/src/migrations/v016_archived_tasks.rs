@@ -0,0 +1,16 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the time a completed task was archived; archived tasks stay out of the task list
+/// and aren't downloaded again by sync
+pub fn migration() -> Migration {
+    Migration {
+        version: 16,
+        description: "add_archived_tasks",
+        sql: r#"
+            ALTER TABLE tasks ADD COLUMN archived_at TEXT;
+
+            CREATE INDEX IF NOT EXISTS idx_tasks_archived_at ON tasks(archived_at);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,41 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds outgoing webhooks and their delivery log; the signing secrets live in the os
+/// credential store
+pub fn migration() -> Migration {
+    Migration {
+        version: 17,
+        description: "add_webhooks",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS webhooks (
+                id TEXT PRIMARY KEY NOT NULL,
+                url TEXT NOT NULL,
+                events TEXT NOT NULL DEFAULT '[]',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                id TEXT PRIMARY KEY NOT NULL,
+                webhook_id TEXT NOT NULL,
+                event TEXT NOT NULL,
+                task_uid TEXT,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                response_status INTEGER,
+                error TEXT,
+                created_at TEXT NOT NULL,
+                next_attempt_at TEXT,
+                delivered_at TEXT,
+                FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_status
+                ON webhook_deliveries(status, next_attempt_at);
+            CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook_id
+                ON webhook_deliveries(webhook_id);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,24 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds user automation scripts; `checked_at` is where the scheduler last looked for the
+/// script's trigger
+pub fn migration() -> Migration {
+    Migration {
+        version: 18,
+        description: "add_scripts",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS scripts (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                source TEXT NOT NULL,
+                trigger TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                checked_at TEXT NOT NULL,
+                last_run_at TEXT,
+                last_error TEXT,
+                created_at TEXT NOT NULL
+            );
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
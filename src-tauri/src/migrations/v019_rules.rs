@@ -0,0 +1,22 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds declarative task rules; events, conditions and actions are stored as json
+pub fn migration() -> Migration {
+    Migration {
+        version: 19,
+        description: "add_rules",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS rules (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                events TEXT NOT NULL DEFAULT '[]',
+                conditions TEXT NOT NULL DEFAULT '[]',
+                actions TEXT NOT NULL DEFAULT '[]',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                sort_order INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            );
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
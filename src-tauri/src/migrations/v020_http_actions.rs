@@ -0,0 +1,47 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds http actions run when a task, or any task of a list, is completed, and their log
+pub fn migration() -> Migration {
+    Migration {
+        version: 20,
+        description: "add_http_actions",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS http_actions (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                target TEXT NOT NULL,
+                target_id TEXT NOT NULL,
+                method TEXT NOT NULL DEFAULT 'POST',
+                url TEXT NOT NULL,
+                content_type TEXT NOT NULL DEFAULT 'application/json',
+                body TEXT NOT NULL DEFAULT '',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_http_actions_target ON http_actions(target, target_id);
+
+            CREATE TABLE IF NOT EXISTS http_action_runs (
+                id TEXT PRIMARY KEY NOT NULL,
+                action_id TEXT NOT NULL,
+                task_uid TEXT,
+                url TEXT NOT NULL,
+                body TEXT NOT NULL DEFAULT '',
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                response_status INTEGER,
+                error TEXT,
+                created_at TEXT NOT NULL,
+                next_attempt_at TEXT,
+                delivered_at TEXT,
+                FOREIGN KEY (action_id) REFERENCES http_actions(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_http_action_runs_status
+                ON http_action_runs(status, next_attempt_at);
+            CREATE INDEX IF NOT EXISTS idx_http_action_runs_action_id
+                ON http_action_runs(action_id);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,21 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the sync health of each account: the last failure and how many syncs failed in a row
+pub fn migration() -> Migration {
+    Migration {
+        version: 21,
+        description: "add_account_health",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS account_health (
+                account_id TEXT PRIMARY KEY NOT NULL,
+                state TEXT NOT NULL DEFAULT 'healthy',
+                consecutive_failures INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                last_failure_at TEXT,
+                last_success_at TEXT,
+                FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+            );
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
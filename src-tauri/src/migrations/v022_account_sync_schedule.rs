@@ -0,0 +1,15 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds a per-account sync interval and quiet hours; NULL keeps the app-wide interval
+pub fn migration() -> Migration {
+    Migration {
+        version: 22,
+        description: "add_account_sync_schedule",
+        sql: r#"
+            ALTER TABLE accounts ADD COLUMN sync_interval INTEGER;
+            ALTER TABLE accounts ADD COLUMN sync_quiet_start TEXT;
+            ALTER TABLE accounts ADD COLUMN sync_quiet_end TEXT;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
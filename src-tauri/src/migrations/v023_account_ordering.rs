@@ -0,0 +1,20 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds sidebar ordering for accounts and calendars, an account color and whether an
+/// account is collapsed; existing rows keep the order they were added in
+pub fn migration() -> Migration {
+    Migration {
+        version: 23,
+        description: "add_account_ordering",
+        sql: r#"
+            ALTER TABLE accounts ADD COLUMN color TEXT;
+            ALTER TABLE accounts ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE accounts ADD COLUMN collapsed INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE calendars ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+
+            UPDATE accounts SET sort_order = rowid;
+            UPDATE calendars SET sort_order = rowid;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the authentication method the account's server last accepted
+pub fn migration() -> Migration {
+    Migration {
+        version: 24,
+        description: "add_account_auth_method",
+        sql: r#"
+            ALTER TABLE accounts ADD COLUMN auth_method TEXT;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,29 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the sync conflicts waiting for review: a local edit and the server's version of the
+/// same task, kept until the user picks one
+pub fn migration() -> Migration {
+    Migration {
+        version: 25,
+        description: "add_sync_conflicts",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS sync_conflicts (
+                id TEXT PRIMARY KEY NOT NULL,
+                task_id TEXT NOT NULL,
+                task_uid TEXT NOT NULL,
+                account_id TEXT NOT NULL,
+                calendar_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                local_data TEXT NOT NULL,
+                remote_data TEXT,
+                detected_at TEXT NOT NULL,
+                resolved_at TEXT,
+                resolution TEXT,
+                FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_sync_conflicts_task ON sync_conflicts(task_id);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
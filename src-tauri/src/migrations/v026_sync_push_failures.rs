@@ -0,0 +1,21 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds why the last upload of a pending change (an unsynced task or a queued deletion)
+/// failed, and how often it failed in a row
+pub fn migration() -> Migration {
+    Migration {
+        version: 26,
+        description: "add_sync_push_failures",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS sync_push_failures (
+                kind TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                error TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 1,
+                last_attempt_at TEXT NOT NULL,
+                PRIMARY KEY (kind, item_id)
+            );
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the backend an account syncs through, CalDAV for every account so far
+pub fn migration() -> Migration {
+    Migration {
+        version: 27,
+        description: "add_account_sync_backend",
+        sql: r#"
+            ALTER TABLE accounts ADD COLUMN backend TEXT NOT NULL DEFAULT 'caldav';
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,20 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds nagging: tasks whose reminder fires again every few minutes until the task is done or
+/// the reminder is dismissed
+pub fn migration() -> Migration {
+    Migration {
+        version: 28,
+        description: "add_task_nags",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS task_nags (
+                task_uid TEXT PRIMARY KEY NOT NULL,
+                interval_minutes INTEGER NOT NULL,
+                last_nagged_at TEXT,
+                dismissed_at TEXT,
+                FOREIGN KEY (task_uid) REFERENCES tasks(uid) ON DELETE CASCADE
+            );
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,13 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the archived flag of calendars, which hides a finished list without unsubscribing
+pub fn migration() -> Migration {
+    Migration {
+        version: 29,
+        description: "add_calendar_archived",
+        sql: r#"
+            ALTER TABLE calendars ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
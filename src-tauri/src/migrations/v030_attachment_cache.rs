@@ -0,0 +1,23 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the index of attachments downloaded to the cache directory, with when each was last
+/// opened so the least recently used ones can be evicted
+pub fn migration() -> Migration {
+    Migration {
+        version: 30,
+        description: "add_attachment_cache",
+        sql: r#"
+            CREATE TABLE IF NOT EXISTS attachment_cache (
+                url TEXT PRIMARY KEY NOT NULL,
+                file_name TEXT NOT NULL,
+                content_type TEXT,
+                size INTEGER NOT NULL,
+                fetched_at TEXT NOT NULL,
+                last_used_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_attachment_cache_last_used
+                ON attachment_cache(last_used_at);
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
@@ -0,0 +1,14 @@
+use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Adds the id of the last change uploaded from here, which marks the task's own upload when
+/// it comes back with a later sync
+pub fn migration() -> Migration {
+    Migration {
+        version: 31,
+        description: "add_task_change_id",
+        sql: r#"
+            ALTER TABLE tasks ADD COLUMN change_id TEXT;
+        "#,
+        kind: MigrationKind::Up,
+    }
+}
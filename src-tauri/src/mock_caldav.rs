@@ -0,0 +1,1256 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use lazy_static::lazy_static;
+use quick_xml::escape::escape;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri_plugin_http::reqwest::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::caldav::AccountConfig;
+use crate::tls::TlsSettings;
+
+// an in-process CalDAV server with one task calendar, for checking the sync protocol without a
+// live server: discovery, PROPFIND, REPORT (calendar-query, calendar-multiget,
+// sync-collection), GET, PUT and DELETE with etags and preconditions, plus faults injected on
+// demand. only built with the mock-caldav feature
+
+pub const USERNAME: &str = "test";
+pub const PASSWORD: &str = "test";
+const PRINCIPAL: &str = "/principals/test/";
+const HOME: &str = "/calendars/test/";
+const CALENDAR: &str = "/calendars/test/tasks/";
+const SERVER_TYPE: &str = "generic";
+const SYNC_TOKEN_PREFIX: &str = "http://mock-caldav/sync/";
+const MAX_REQUEST_SIZE: usize = 4 << 20;
+const MAX_HEADERS: usize = 64;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    // the server started from the app, for pointing an account at
+    static ref SERVER: Mutex<Option<MockServer>> = Mutex::new(None);
+    static ref HREF: Regex =
+        Regex::new(r"(?is)<[^:>/]*:?href[^>]*>([^<]+)<").expect("invalid href regex");
+    static ref SYNC_TOKEN: Regex = Regex::new(r"(?is)<[^:>/]*:?sync-token[^>]*>([^<]*)<")
+        .expect("invalid sync-token regex");
+}
+
+/// a failure the server answers requests with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MockFault {
+    /// 503 Service Unavailable
+    ServerError,
+    /// 401, whatever the credentials
+    Unauthorized,
+    /// the connection is closed without an answer
+    Disconnect,
+    /// the body breaks off halfway
+    MalformedBody,
+}
+
+struct MockObject {
+    ics: String,
+    etag: String,
+    /// the revision of the collection the object was last written at
+    revision: u64,
+}
+
+#[derive(Default)]
+struct MockState {
+    /// by resource name inside the calendar
+    objects: BTreeMap<String, MockObject>,
+    /// names of deleted objects and the revision they were deleted at
+    tombstones: BTreeMap<String, u64>,
+    /// bumped by every change, serves as ctag and sync token
+    revision: u64,
+    fault: Option<MockFault>,
+    /// requests the fault still applies to; None until it's cleared
+    fault_remaining: Option<u32>,
+    requests: usize,
+}
+
+impl MockState {
+    fn sync_token(&self) -> String {
+        format!("{SYNC_TOKEN_PREFIX}{}", self.revision)
+    }
+
+    // the fault for the next request, used up one request at a time
+    fn take_fault(&mut self) -> Option<MockFault> {
+        let fault = self.fault?;
+        match &mut self.fault_remaining {
+            Some(0) => {
+                self.fault = None;
+                return None;
+            }
+            Some(remaining) => *remaining -= 1,
+            None => {}
+        }
+        Some(fault)
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+struct Reply {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl Reply {
+    fn empty(status: u16) -> Self {
+        Reply {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    fn multistatus(responses: &[String], extra: &str) -> Self {
+        Reply {
+            status: 207,
+            headers: vec![("Content-Type", "application/xml; charset=utf-8".to_string())],
+            body: format!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<d:multistatus xmlns:d=\"DAV:\" \
+                 xmlns:c=\"urn:ietf:params:xml:ns:caldav\" \
+                 xmlns:cs=\"http://calendarserver.org/ns/\">{}{extra}</d:multistatus>",
+                responses.concat()
+            ),
+        }
+    }
+
+    fn with_etag(mut self, etag: &str) -> Self {
+        self.headers.push(("ETag", format!("\"{etag}\"")));
+        self
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        207 => "Multi-Status",
+        301 => "Moved Permanently",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        412 => "Precondition Failed",
+        413 => "Payload Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+fn response(href: &str, props: &str) -> String {
+    format!(
+        "<d:response><d:href>{}</d:href><d:propstat><d:prop>{props}</d:prop>\
+         <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        escape(href)
+    )
+}
+
+fn missing(href: &str) -> String {
+    format!(
+        "<d:response><d:href>{}</d:href><d:status>HTTP/1.1 404 Not Found</d:status></d:response>",
+        escape(href)
+    )
+}
+
+fn collection_props(state: &MockState) -> String {
+    format!(
+        "<d:resourcetype><d:collection/><c:calendar/></d:resourcetype>\
+         <d:displayname>Tasks</d:displayname>\
+         <c:supported-calendar-component-set><c:comp name=\"VTODO\"/>\
+         </c:supported-calendar-component-set>\
+         <cs:getctag>{}</cs:getctag><d:sync-token>{}</d:sync-token>",
+        state.revision,
+        state.sync_token()
+    )
+}
+
+fn object_props(object: &MockObject, with_data: bool) -> String {
+    let data = if with_data {
+        format!("<c:calendar-data>{}</c:calendar-data>", escape(&object.ics))
+    } else {
+        String::new()
+    };
+    format!(
+        "<d:resourcetype/><d:getcontenttype>text/calendar; charset=utf-8</d:getcontenttype>\
+         <d:getetag>\"{}\"</d:getetag>{data}",
+        object.etag
+    )
+}
+
+// etags as sent in If-Match, quoted or weak
+fn same_etag(given: &str, etag: &str) -> bool {
+    given.split(',').any(|given| {
+        let given = given.trim();
+        given == "*" || given.trim_start_matches("W/").trim_matches('"') == etag
+    })
+}
+
+fn authorized(request: &Request) -> bool {
+    let expected = STANDARD.encode(format!("{USERNAME}:{PASSWORD}"));
+    request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Basic "))
+        .is_some_and(|credentials| credentials.trim() == expected)
+}
+
+fn propfind(state: &MockState, request: &Request) -> Reply {
+    let depth_one = request.header("depth") == Some("1");
+    let principal = format!(
+        "<d:current-user-principal><d:href>{PRINCIPAL}</d:href></d:current-user-principal>"
+    );
+    match request.path.as_str() {
+        "/" => Reply::multistatus(&[response("/", &principal)], ""),
+        PRINCIPAL => Reply::multistatus(
+            &[response(
+                PRINCIPAL,
+                &format!(
+                    "{principal}<d:resourcetype><d:principal/></d:resourcetype>\
+                     <c:calendar-home-set><d:href>{HOME}</d:href></c:calendar-home-set>"
+                ),
+            )],
+            "",
+        ),
+        HOME => {
+            let mut responses = vec![response(
+                HOME,
+                "<d:resourcetype><d:collection/></d:resourcetype>",
+            )];
+            if depth_one {
+                responses.push(response(CALENDAR, &collection_props(state)));
+            }
+            Reply::multistatus(&responses, "")
+        }
+        CALENDAR => {
+            let mut responses = vec![response(CALENDAR, &collection_props(state))];
+            if depth_one {
+                responses.extend(state.objects.iter().map(|(name, object)| {
+                    response(&format!("{CALENDAR}{name}"), &object_props(object, false))
+                }));
+            }
+            Reply::multistatus(&responses, "")
+        }
+        path => match object_name(path).and_then(|name| state.objects.get(name)) {
+            Some(object) => Reply::multistatus(&[response(path, &object_props(object, false))], ""),
+            None => Reply::empty(404),
+        },
+    }
+}
+
+fn report(state: &MockState, request: &Request) -> Reply {
+    if request.path != CALENDAR {
+        return Reply::empty(405);
+    }
+    let with_data = request.body.contains("calendar-data");
+
+    if request.body.contains("sync-collection") {
+        let given = SYNC_TOKEN
+            .captures(&request.body)
+            .map(|captures| captures[1].trim().to_string())
+            .unwrap_or_default();
+        // an empty token asks for everything
+        let since = if given.is_empty() {
+            Some(0)
+        } else {
+            given
+                .strip_prefix(SYNC_TOKEN_PREFIX)
+                .and_then(|revision| revision.parse::<u64>().ok())
+                .filter(|revision| *revision <= state.revision)
+        };
+        let Some(since) = since else {
+            return Reply {
+                status: 403,
+                headers: vec![("Content-Type", "application/xml; charset=utf-8".to_string())],
+                body: "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+                       <d:error xmlns:d=\"DAV:\"><d:valid-sync-token/></d:error>"
+                    .to_string(),
+            };
+        };
+        let mut responses: Vec<String> = state
+            .objects
+            .iter()
+            .filter(|(_, object)| object.revision > since)
+            .map(|(name, object)| {
+                response(
+                    &format!("{CALENDAR}{name}"),
+                    &object_props(object, with_data),
+                )
+            })
+            .collect();
+        if since > 0 {
+            responses.extend(
+                state
+                    .tombstones
+                    .iter()
+                    .filter(|(_, revision)| **revision > since)
+                    .map(|(name, _)| missing(&format!("{CALENDAR}{name}"))),
+            );
+        }
+        let token = format!("<d:sync-token>{}</d:sync-token>", state.sync_token());
+        return Reply::multistatus(&responses, &token);
+    }
+
+    if request.body.contains("calendar-multiget") {
+        let responses: Vec<String> = HREF
+            .captures_iter(&request.body)
+            .map(|captures| captures[1].trim().to_string())
+            .map(|href| {
+                let path = Url::parse("http://localhost")
+                    .and_then(|base| base.join(&href))
+                    .map(|url| url.path().to_string())
+                    .unwrap_or(href);
+                match object_name(&path).and_then(|name| state.objects.get(name)) {
+                    Some(object) => response(&path, &object_props(object, with_data)),
+                    None => missing(&path),
+                }
+            })
+            .collect();
+        return Reply::multistatus(&responses, "");
+    }
+
+    // calendar-query: the calendar only holds VTODOs, so every object matches
+    let responses: Vec<String> = state
+        .objects
+        .iter()
+        .map(|(name, object)| {
+            response(
+                &format!("{CALENDAR}{name}"),
+                &object_props(object, with_data),
+            )
+        })
+        .collect();
+    Reply::multistatus(&responses, "")
+}
+
+// the resource name of an object path inside the calendar
+fn object_name(path: &str) -> Option<&str> {
+    path.strip_prefix(CALENDAR)
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+fn put(state: &mut MockState, request: &Request) -> Reply {
+    let Some(name) = object_name(&request.path) else {
+        return Reply::empty(405);
+    };
+    let existing = state.objects.get(name);
+    if request.header("if-none-match") == Some("*") && existing.is_some() {
+        return Reply::empty(412);
+    }
+    if let Some(given) = request.header("if-match") {
+        if !existing.is_some_and(|object| same_etag(given, &object.etag)) {
+            return Reply::empty(412);
+        }
+    }
+    if !request.body.contains("BEGIN:VCALENDAR") {
+        return Reply::empty(400);
+    }
+
+    let status = if existing.is_some() { 204 } else { 201 };
+    state.revision += 1;
+    let etag = format!("{}-{}", state.revision, request.body.len());
+    state.tombstones.remove(name);
+    state.objects.insert(
+        name.to_string(),
+        MockObject {
+            ics: request.body.clone(),
+            etag: etag.clone(),
+            revision: state.revision,
+        },
+    );
+    Reply::empty(status).with_etag(&etag)
+}
+
+fn delete(state: &mut MockState, request: &Request) -> Reply {
+    let Some(name) = object_name(&request.path) else {
+        return Reply::empty(405);
+    };
+    let Some(object) = state.objects.get(name) else {
+        return Reply::empty(404);
+    };
+    if let Some(given) = request.header("if-match") {
+        if !same_etag(given, &object.etag) {
+            return Reply::empty(412);
+        }
+    }
+    state.revision += 1;
+    state.objects.remove(name);
+    state.tombstones.insert(name.to_string(), state.revision);
+    Reply::empty(204)
+}
+
+fn route(state: &mut MockState, request: &Request) -> Reply {
+    // probes go unauthenticated, like on most servers
+    if request.method == "OPTIONS" {
+        let mut reply = Reply::empty(200);
+        reply
+            .headers
+            .push(("DAV", "1, 3, calendar-access".to_string()));
+        reply.headers.push((
+            "Allow",
+            "OPTIONS, GET, PUT, DELETE, PROPFIND, REPORT".to_string(),
+        ));
+        return reply;
+    }
+    if !authorized(request) {
+        let mut reply = Reply::empty(401);
+        reply.headers.push((
+            "WWW-Authenticate",
+            "Basic realm=\"mock-caldav\"".to_string(),
+        ));
+        return reply;
+    }
+    if request.path == "/.well-known/caldav" {
+        let mut reply = Reply::empty(301);
+        reply.headers.push(("Location", "/".to_string()));
+        return reply;
+    }
+
+    match request.method.as_str() {
+        "PROPFIND" => propfind(state, request),
+        "REPORT" => report(state, request),
+        "GET" => match object_name(&request.path).and_then(|name| state.objects.get(name)) {
+            Some(object) => Reply {
+                status: 200,
+                headers: vec![("Content-Type", "text/calendar; charset=utf-8".to_string())],
+                body: object.ics.clone(),
+            }
+            .with_etag(&object.etag),
+            None => Reply::empty(404),
+        },
+        "PUT" => put(state, request),
+        "DELETE" => delete(state, request),
+        _ => Reply::empty(405),
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, String> {
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = stream.read(&mut buffer).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("Incomplete request".to_string());
+        }
+        data.extend_from_slice(&buffer[..read]);
+        if data.len() > MAX_REQUEST_SIZE {
+            return Err("Request too large".to_string());
+        }
+
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut parsed = httparse::Request::new(&mut headers);
+        let httparse::Status::Complete(head) = parsed.parse(&data).map_err(|e| e.to_string())?
+        else {
+            continue;
+        };
+        let headers: BTreeMap<String, String> = parsed
+            .headers
+            .iter()
+            .map(|header| {
+                (
+                    header.name.to_lowercase(),
+                    String::from_utf8_lossy(header.value).trim().to_string(),
+                )
+            })
+            .collect();
+        let length = match headers.get("content-length") {
+            Some(length) => length
+                .parse::<usize>()
+                .map_err(|_| "Invalid Content-Length".to_string())?,
+            None => 0,
+        };
+        if data.len() < head + length {
+            continue;
+        }
+
+        let path = parsed.path.unwrap_or("/");
+        return Ok(Request {
+            method: parsed.method.unwrap_or("GET").to_string(),
+            // no query strings on DAV resources
+            path: path.split('?').next().unwrap_or(path).to_string(),
+            headers,
+            body: String::from_utf8_lossy(&data[head..head + length]).into_owned(),
+        });
+    }
+}
+
+async fn handle(state: Arc<Mutex<MockState>>, mut stream: TcpStream) {
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => request,
+        Ok(Err(e)) => {
+            log::debug!("Mock CalDAV server got a bad request: {e}");
+            return;
+        }
+        Err(_) => return,
+    };
+
+    let (fault, mut reply) = {
+        let mut state = state.lock().expect("Failed to lock mock CalDAV state");
+        state.requests += 1;
+        let fault = state.take_fault();
+        let reply = match fault {
+            Some(MockFault::Disconnect) => return,
+            Some(MockFault::ServerError) => Reply::empty(503),
+            Some(MockFault::Unauthorized) => {
+                let mut reply = Reply::empty(401);
+                reply.headers.push((
+                    "WWW-Authenticate",
+                    "Basic realm=\"mock-caldav\"".to_string(),
+                ));
+                reply
+            }
+            _ => route(&mut state, &request),
+        };
+        (fault, reply)
+    };
+    log::debug!(
+        "Mock CalDAV server: {} {} -> {}",
+        request.method,
+        request.path,
+        reply.status
+    );
+
+    let length = reply.body.len();
+    if fault == Some(MockFault::MalformedBody) {
+        let mut cut = length / 2;
+        while !reply.body.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        reply.body.truncate(cut);
+    }
+    let mut head = format!("HTTP/1.1 {} {}\r\n", reply.status, reason(reply.status));
+    for (name, value) in &reply.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    // a cut body still announces its full length, so the client sees the connection drop
+    head.push_str(&format!(
+        "Content-Length: {length}\r\nConnection: close\r\n\r\n"
+    ));
+    let result = async {
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(reply.body.as_bytes()).await?;
+        stream.shutdown().await
+    }
+    .await;
+    if let Err(e) = result {
+        log::debug!("Mock CalDAV server failed to answer: {e}");
+    }
+}
+
+/// a running mock server; it stops when dropped
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    server: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl MockServer {
+    /// listen on a free localhost port with an empty calendar
+    pub async fn start() -> Result<Self, String> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| e.to_string())?;
+        let address = listener.local_addr().map_err(|e| e.to_string())?;
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let accepting = state.clone();
+        let server = tauri::async_runtime::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tauri::async_runtime::spawn(handle(accepting.clone(), stream));
+                    }
+                    Err(e) => log::warn!("Mock CalDAV server failed to accept a connection: {e}"),
+                }
+            }
+        });
+        Ok(Self {
+            address,
+            state,
+            server,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.address)
+    }
+
+    /// connection details of an account on this server
+    pub fn account(&self) -> AccountConfig {
+        AccountConfig {
+            server_url: self.url(),
+            username: USERNAME.to_string(),
+            password: PASSWORD.to_string(),
+            // found through .well-known, like any server without a known layout
+            server_type: Some(SERVER_TYPE.to_string()),
+            tls: TlsSettings::default(),
+            bearer_token: None,
+            auth_method: None,
+        }
+    }
+
+    /// answer the next `count` requests with `fault`, or all of them until cleared when
+    /// `count` is None; a None fault clears it
+    pub fn set_fault(&self, fault: Option<MockFault>, count: Option<u32>) {
+        let mut state = self.state.lock().expect("Failed to lock mock CalDAV state");
+        state.fault = fault;
+        state.fault_remaining = count;
+    }
+
+    /// requests answered so far
+    pub fn requests(&self) -> usize {
+        self.state
+            .lock()
+            .expect("Failed to lock mock CalDAV state")
+            .requests
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockServerInfo {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    /// the server type to add the account as
+    pub server_type: String,
+}
+
+/// start the mock server (again, with an empty calendar) for an account to sync against
+#[tauri::command]
+pub async fn start_mock_caldav_server() -> Result<MockServerInfo, String> {
+    let server = MockServer::start().await?;
+    let account = server.account();
+    let info = MockServerInfo {
+        url: account.server_url,
+        username: account.username,
+        password: account.password,
+        server_type: SERVER_TYPE.to_string(),
+    };
+    log::info!("Mock CalDAV server listening on {}", info.url);
+    *SERVER.lock().expect("Failed to lock SERVER") = Some(server);
+    Ok(info)
+}
+
+/// answer the next `count` requests to the mock server with `fault` (all of them when unset);
+/// None clears it
+#[tauri::command]
+pub fn set_mock_caldav_fault(fault: Option<MockFault>, count: Option<u32>) -> Result<(), String> {
+    match SERVER.lock().expect("Failed to lock SERVER").as_ref() {
+        Some(server) => {
+            server.set_fault(fault, count);
+            Ok(())
+        }
+        None => Err("The mock CalDAV server isn't running".to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn stop_mock_caldav_server() -> Result<(), String> {
+    if let Some(server) = SERVER.lock().expect("Failed to lock SERVER").take() {
+        log::info!(
+            "Mock CalDAV server stopped after {} requests",
+            server.requests()
+        );
+    }
+    Ok(())
+}
+
+// the sync protocol, step by step against a fresh server; `cargo test --features mock-caldav`
+// runs it
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+    use tauri_plugin_http::reqwest::{self, Method};
+
+    use super::*;
+    use crate::caldav::{
+        test_connection, CalDavClient, ConnectionStage, DavResponse, VTODO_DATA_REPORT,
+    };
+    use crate::multistatus::{DavItem, MultistatusStream};
+
+    struct SyncCheck {
+        name: &'static str,
+        passed: bool,
+        /// what was seen, or why it failed
+        detail: String,
+    }
+
+    const SYNC_COLLECTION: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+    <d:sync-collection xmlns:d="DAV:">
+      <d:sync-token>{token}</d:sync-token>
+      <d:sync-level>1</d:sync-level>
+      <d:prop>
+        <d:getetag/>
+      </d:prop>
+    </d:sync-collection>"#;
+
+    const MULTIGET: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+    <c:calendar-multiget xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
+      <d:prop>
+        <d:getetag/>
+        <c:calendar-data/>
+      </d:prop>
+      {hrefs}
+    </c:calendar-multiget>"#;
+
+    fn vtodo(uid: &str, summary: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//caldav-tasks//mock//EN\r\nBEGIN:VTODO\r\n\
+             UID:{uid}\r\nDTSTAMP:20240101T000000Z\r\nSUMMARY:{summary}\r\nEND:VTODO\r\n\
+             END:VCALENDAR\r\n"
+        )
+    }
+
+    fn items(response: &DavResponse) -> Result<Vec<DavItem>, String> {
+        if !response.is_multistatus() {
+            return Err(format!("Expected 207, got {}", response.status));
+        }
+        let mut parser = MultistatusStream::new();
+        let mut items = parser.feed(response.body.as_bytes())?;
+        items.extend(parser.finish()?);
+        Ok(items)
+    }
+
+    fn expect_status(response: &DavResponse, expected: u16) -> Result<(), String> {
+        if response.status == expected {
+            Ok(())
+        } else {
+            Err(format!("Expected {expected}, got {}", response.status))
+        }
+    }
+
+    fn etag(response: &DavResponse) -> Result<String, String> {
+        response
+            .headers
+            .get("etag")
+            .map(|etag| etag.trim_matches('"').to_string())
+            .ok_or_else(|| "No ETag in the response".to_string())
+    }
+
+    async fn sync_token(client: &CalDavClient, calendar: &str) -> Result<String, String> {
+        let response = client
+            .propfind(
+                calendar,
+                "0",
+                r#"<d:propfind xmlns:d="DAV:"><d:prop><d:sync-token/></d:prop></d:propfind>"#,
+            )
+            .await?;
+        SYNC_TOKEN
+            .captures(&response.body)
+            .map(|captures| captures[1].trim().to_string())
+            .ok_or_else(|| "No sync-token on the calendar".to_string())
+    }
+
+    async fn sync_collection(
+        client: &CalDavClient,
+        calendar: &str,
+        token: &str,
+    ) -> Result<DavResponse, String> {
+        client
+            .report(
+                calendar,
+                "1",
+                &SYNC_COLLECTION.replace("{token}", &escape(token)),
+            )
+            .await
+    }
+
+    // PUT or DELETE with an If-Match or If-None-Match precondition, which CalDavClient doesn't
+    // send; the frontend's sync sends them the same way
+    async fn conditional(
+        method: Method,
+        url: &str,
+        precondition: (&str, &str),
+        ics: Option<&str>,
+    ) -> Result<DavResponse, String> {
+        let mut request = reqwest::Client::new()
+            .request(method, url)
+            .basic_auth(USERNAME, Some(PASSWORD))
+            .header(precondition.0, precondition.1);
+        if let Some(ics) = ics {
+            request = request
+                .header("Content-Type", "text/calendar; charset=utf-8")
+                .body(ics.to_string());
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    value.to_str().unwrap_or_default().to_string(),
+                )
+            })
+            .collect();
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        Ok(DavResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    async fn check(
+        checks: &mut Vec<SyncCheck>,
+        name: &'static str,
+        run: impl Future<Output = Result<String, String>>,
+    ) {
+        let result = run.await;
+        if let Err(e) = &result {
+            log::warn!("Sync check {name} failed: {e}");
+        }
+        checks.push(SyncCheck {
+            name,
+            passed: result.is_ok(),
+            detail: result.unwrap_or_else(|e| e),
+        });
+    }
+
+    // the protocol a sync goes through, step by step against a fresh server
+    async fn run_checks(server: &MockServer) -> Result<Vec<SyncCheck>, String> {
+        let account = server.account();
+        let client = CalDavClient::new(&account)?;
+        let calendar = format!("{}{}", server.url().trim_end_matches('/'), CALENDAR);
+        let first = format!("{calendar}first.ics");
+        let second = format!("{calendar}second.ics");
+        let mut checks = Vec::new();
+
+        check(&mut checks, "discovery", async {
+            let result = test_connection(account.clone()).await?;
+            if !result.success {
+                return Err(format!(
+                    "Failed at {:?}: {}",
+                    result.failed_stage,
+                    result.message.unwrap_or_default()
+                ));
+            }
+            let home = result.calendar_home.unwrap_or_default();
+            if !home.ends_with(HOME) {
+                return Err(format!("Found the calendar home at {home}"));
+            }
+            Ok(format!("Principal {PRINCIPAL}, calendar home {HOME}"))
+        })
+        .await;
+
+        let mut first_etag = String::new();
+        check(&mut checks, "create", async {
+            let response = client.put_ics(&first, &vtodo("first", "First")).await?;
+            expect_status(&response, 201)?;
+            first_etag = etag(&response)?;
+            Ok(format!("Created with ETag {first_etag}"))
+        })
+        .await;
+
+        check(&mut checks, "fetch", async {
+            let mut fetched = Vec::new();
+            client
+                .report_stream(&calendar, "1", VTODO_DATA_REPORT, None, |items| {
+                    fetched.extend(items);
+                    Ok(())
+                })
+                .await?;
+            let [item] = fetched.as_slice() else {
+                return Err(format!("Expected 1 object, got {}", fetched.len()));
+            };
+            if item.etag.as_deref() != Some(first_etag.as_str()) {
+                return Err(format!("Expected ETag {first_etag}, got {:?}", item.etag));
+            }
+            if !item
+                .calendar_data
+                .as_deref()
+                .is_some_and(|data| data.contains("SUMMARY:First"))
+            {
+                return Err("The object came back without its data".to_string());
+            }
+            Ok(format!("{} with matching ETag and data", item.href))
+        })
+        .await;
+
+        check(&mut checks, "create over existing", async {
+            let response = conditional(
+                Method::PUT,
+                &first,
+                ("If-None-Match", "*"),
+                Some(&vtodo("first", "Again")),
+            )
+            .await?;
+            expect_status(&response, 412)?;
+            Ok("A second create of the same href was refused".to_string())
+        })
+        .await;
+
+        check(&mut checks, "conditional update", async {
+            let stale = conditional(
+                Method::PUT,
+                &first,
+                ("If-Match", "\"stale\""),
+                Some(&vtodo("first", "Stale")),
+            )
+            .await?;
+            expect_status(&stale, 412)?;
+            let current = conditional(
+                Method::PUT,
+                &first,
+                ("If-Match", &format!("\"{first_etag}\"")),
+                Some(&vtodo("first", "Updated")),
+            )
+            .await?;
+            expect_status(&current, 204)?;
+            let updated = etag(&current)?;
+            if updated == first_etag {
+                return Err("The ETag didn't change with the update".to_string());
+            }
+            first_etag = updated;
+            Ok(format!(
+                "Stale ETag refused, current one accepted, now {first_etag}"
+            ))
+        })
+        .await;
+
+        check(&mut checks, "sync-collection", async {
+            let token = sync_token(&client, &calendar).await?;
+            expect_status(
+                &client.put_ics(&second, &vtodo("second", "Second")).await?,
+                201,
+            )?;
+            let changed = items(&sync_collection(&client, &calendar, &token).await?)?;
+            let hrefs: Vec<&str> = changed.iter().map(|item| item.href.as_str()).collect();
+            if hrefs != [format!("{CALENDAR}second.ics")] {
+                return Err(format!("Expected only second.ics to change, got {hrefs:?}"));
+            }
+
+            let token = sync_token(&client, &calendar).await?;
+            let deleted = conditional(
+                Method::DELETE,
+                &first,
+                ("If-Match", &format!("\"{first_etag}\"")),
+                None,
+            )
+            .await?;
+            expect_status(&deleted, 204)?;
+            let changed = items(&sync_collection(&client, &calendar, &token).await?)?;
+            match changed.as_slice() {
+                [item] if item.status == Some(404) && item.href.ends_with("first.ics") => {}
+                _ => return Err(format!("Expected first.ics as removed, got {changed:?}")),
+            }
+
+            let token = sync_token(&client, &calendar).await?;
+            let unchanged = items(&sync_collection(&client, &calendar, &token).await?)?;
+            if !unchanged.is_empty() {
+                return Err(format!("Expected no changes, got {}", unchanged.len()));
+            }
+            Ok("Additions and deletions since a token, nothing for the newest".to_string())
+        })
+        .await;
+
+        check(&mut checks, "invalid sync token", async {
+            let response = sync_collection(&client, &calendar, "http://elsewhere/sync/1").await?;
+            expect_status(&response, 403)?;
+            if !response.body.contains("valid-sync-token") {
+                return Err("The error doesn't name valid-sync-token".to_string());
+            }
+            Ok("Refused with valid-sync-token, a full fetch follows".to_string())
+        })
+        .await;
+
+        check(&mut checks, "multiget", async {
+            let hrefs = [
+                format!("{CALENDAR}second.ics"),
+                format!("{CALENDAR}first.ics"),
+            ]
+            .iter()
+            .map(|href| format!("<d:href>{href}</d:href>"))
+            .collect::<String>();
+            let response = client
+                .report(&calendar, "1", &MULTIGET.replace("{hrefs}", &hrefs))
+                .await?;
+            let fetched = items(&response)?;
+            let found = fetched
+                .iter()
+                .filter(|item| item.calendar_data.is_some())
+                .count();
+            let removed = fetched
+                .iter()
+                .filter(|item| item.status == Some(404))
+                .count();
+            if (found, removed) != (1, 1) {
+                return Err(format!(
+                    "Expected 1 found and 1 removed, got {found} and {removed}"
+                ));
+            }
+            Ok("The remaining object with data, the deleted one as 404".to_string())
+        })
+        .await;
+
+        check(&mut checks, "server error", async {
+            server.set_fault(Some(MockFault::ServerError), Some(1));
+            let result = client
+                .report_stream(&calendar, "1", VTODO_DATA_REPORT, None, |_| Ok(()))
+                .await;
+            server.set_fault(None, None);
+            match result {
+                Err(e) => Ok(format!("Reported as: {e}")),
+                Ok(()) => Err("A 503 passed as a successful fetch".to_string()),
+            }
+        })
+        .await;
+
+        check(&mut checks, "unauthorized", async {
+            server.set_fault(Some(MockFault::Unauthorized), None);
+            let result = test_connection(account.clone()).await;
+            server.set_fault(None, None);
+            let result = result?;
+            match result.failed_stage {
+                Some(ConnectionStage::Auth) => Ok(format!(
+                    "Stopped at the auth stage: {}",
+                    result.message.unwrap_or_default()
+                )),
+                stage => Err(format!("Expected to fail at auth, failed at {stage:?}")),
+            }
+        })
+        .await;
+
+        check(&mut checks, "disconnect", async {
+            server.set_fault(Some(MockFault::Disconnect), Some(1));
+            let result = client.propfind(&calendar, "0", "").await;
+            server.set_fault(None, None);
+            match result {
+                Err(e) => Ok(format!("Reported as: {e}")),
+                Ok(response) => Err(format!("Got {} from a closed connection", response.status)),
+            }
+        })
+        .await;
+
+        check(&mut checks, "malformed body", async {
+            server.set_fault(Some(MockFault::MalformedBody), Some(1));
+            let mut fetched = Vec::new();
+            let result = client
+                .report_stream(&calendar, "1", VTODO_DATA_REPORT, None, |items| {
+                    fetched.extend(items);
+                    Ok(())
+                })
+                .await;
+            server.set_fault(None, None);
+            match result {
+                Err(e) => Ok(format!("Reported as: {e}")),
+                Ok(()) => Err(format!(
+                    "A cut off body passed as a successful fetch of {} objects",
+                    fetched.len()
+                )),
+            }
+        })
+        .await;
+
+        Ok(checks)
+    }
+
+    // start a fresh server and fail the test with the error `run` returns
+    fn with_server<F, Fut>(run: F)
+    where
+        F: FnOnce(MockServer) -> Fut,
+        Fut: Future<Output = Result<(), String>>,
+    {
+        tauri::async_runtime::block_on(async {
+            let server = MockServer::start()
+                .await
+                .expect("Failed to start the mock CalDAV server");
+            if let Err(e) = run(server).await {
+                panic!("{e}");
+            }
+        });
+    }
+
+    fn calendar_url(server: &MockServer) -> String {
+        format!("{}{}", server.url().trim_end_matches('/'), CALENDAR)
+    }
+
+    // upload `ics` over the object with `etag`, the way a sync uploads a change
+    async fn update(href: &str, etag: &str, ics: &str) -> Result<DavResponse, String> {
+        conditional(
+            Method::PUT,
+            href,
+            ("If-Match", &format!("\"{etag}\"")),
+            Some(ics),
+        )
+        .await
+    }
+
+    async fn create(href: &str, ics: &str) -> Result<String, String> {
+        let response = conditional(Method::PUT, href, ("If-None-Match", "*"), Some(ics)).await?;
+        expect_status(&response, 201)?;
+        etag(&response)
+    }
+
+    // the object as the server has it now, with its ETag
+    async fn fetch(client: &CalDavClient, href: &str) -> Result<(String, String), String> {
+        let response = client.request("GET", href, None, None).await?;
+        expect_status(&response, 200)?;
+        Ok((etag(&response)?, response.body))
+    }
+
+    // two devices change a task from the same ETag: the second upload is refused, and goes
+    // through once it's based on what the first one wrote
+    #[test]
+    fn conflicting_updates() {
+        with_server(|server| async move {
+            let client = CalDavClient::new(&server.account())?;
+            let href = format!("{}task.ics", calendar_url(&server));
+            let base = create(&href, &vtodo("task", "Original")).await?;
+
+            let first = update(&href, &base, &vtodo("task", "First device")).await?;
+            expect_status(&first, 204)?;
+            let second = update(&href, &base, &vtodo("task", "Second device")).await?;
+            expect_status(&second, 412)?;
+
+            // the second device takes the server's version and uploads its merge on top
+            let (current, ics) = fetch(&client, &href).await?;
+            if current != etag(&first)? || !ics.contains("SUMMARY:First device") {
+                return Err(format!("Expected the first device's version, got {ics}"));
+            }
+            expect_status(
+                &update(&href, &current, &vtodo("task", "Merged")).await?,
+                204,
+            )?;
+            let (_, ics) = fetch(&client, &href).await?;
+            if !ics.contains("SUMMARY:Merged") {
+                return Err(format!("Expected the merged version, got {ics}"));
+            }
+            Ok(())
+        });
+    }
+
+    // a task deleted on one device while another changed it: the change is refused, the next
+    // sync sees the deletion, and the task can be uploaded again as new to keep the change
+    #[test]
+    fn deleted_while_changed() {
+        with_server(|server| async move {
+            let client = CalDavClient::new(&server.account())?;
+            let calendar = calendar_url(&server);
+            let href = format!("{calendar}task.ics");
+            let base = create(&href, &vtodo("task", "Original")).await?;
+            let token = sync_token(&client, &calendar).await?;
+
+            let deleted = conditional(
+                Method::DELETE,
+                &href,
+                ("If-Match", &format!("\"{base}\"")),
+                None,
+            )
+            .await?;
+            expect_status(&deleted, 204)?;
+            expect_status(&update(&href, &base, &vtodo("task", "Changed")).await?, 412)?;
+            let again = conditional(Method::DELETE, &href, ("If-Match", "\"stale\""), None).await?;
+            expect_status(&again, 404)?;
+
+            let changed = items(&sync_collection(&client, &calendar, &token).await?)?;
+            match changed.as_slice() {
+                [item] if item.status == Some(404) && item.href.ends_with("task.ics") => {}
+                _ => return Err(format!("Expected task.ics as removed, got {changed:?}")),
+            }
+            expect_status(&client.request("GET", &href, None, None).await?, 404)?;
+
+            let token = sync_token(&client, &calendar).await?;
+            let restored = create(&href, &vtodo("task", "Changed")).await?;
+            let changed = items(&sync_collection(&client, &calendar, &token).await?)?;
+            match changed.as_slice() {
+                [item] if item.etag.as_deref() == Some(restored.as_str()) => {}
+                _ => return Err(format!("Expected task.ics as added again, got {changed:?}")),
+            }
+            Ok(())
+        });
+    }
+
+    // a task changed twice in a row is uploaded over its newest ETag each time; a device that
+    // synced before sees one change with the last version, an upload over an older ETag fails
+    #[test]
+    fn reupload_after_change() {
+        with_server(|server| async move {
+            let client = CalDavClient::new(&server.account())?;
+            let calendar = calendar_url(&server);
+            let href = format!("{calendar}task.ics");
+            let mut current = create(&href, &vtodo("task", "Draft")).await?;
+            let token = sync_token(&client, &calendar).await?;
+            let original = current.clone();
+
+            for summary in ["Edited", "Edited again"] {
+                let response = update(&href, &current, &vtodo("task", summary)).await?;
+                expect_status(&response, 204)?;
+                let uploaded = etag(&response)?;
+                if uploaded == current {
+                    return Err(format!("The ETag stayed {current} after the upload"));
+                }
+                current = uploaded;
+            }
+            expect_status(&update(&href, &original, &vtodo("task", "Old")).await?, 412)?;
+
+            let changed = items(&sync_collection(&client, &calendar, &token).await?)?;
+            match changed.as_slice() {
+                [item] if item.etag.as_deref() == Some(current.as_str()) => {}
+                _ => {
+                    return Err(format!(
+                        "Expected one change with ETag {current}, got {changed:?}"
+                    ))
+                }
+            }
+            let (etag, ics) = fetch(&client, &href).await?;
+            if etag != current || !ics.contains("SUMMARY:Edited again") {
+                return Err(format!("Expected the last upload, got {ics}"));
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn sync_protocol() {
+        tauri::async_runtime::block_on(async {
+            let server = MockServer::start()
+                .await
+                .expect("Failed to start the mock CalDAV server");
+            let checks = run_checks(&server)
+                .await
+                .expect("Failed to run the sync checks");
+            let failed: Vec<String> = checks
+                .iter()
+                .filter(|check| !check.passed)
+                .map(|check| format!("{}: {}", check.name, check.detail))
+                .collect();
+            assert!(
+                failed.is_empty(),
+                "{} of {} sync checks failed:\n{}",
+                failed.len(),
+                checks.len(),
+                failed.join("\n")
+            );
+        });
+    }
+}
@@ -0,0 +1,206 @@
+use lazy_static::lazy_static;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::bytes::Regex;
+
+lazy_static! {
+    // closing tag of a <response> element, whatever prefix the server uses
+    static ref RESPONSE_END: Regex =
+        Regex::new(r"(?i)</(?:[\w.-]+:)?response\s*>").expect("invalid response end regex");
+    // the first href of a response, to name one that is too large to parse
+    static ref FIRST_HREF: Regex =
+        Regex::new(r"(?i)<(?:[\w.-]+:)?href\s*>\s*([^<\s]*)").expect("invalid href regex");
+}
+
+// longest possible closing tag, so a tag split across two chunks is still found
+const MAX_END_TAG: usize = 64;
+
+/// one <response> of a multistatus body
+#[derive(Debug, Clone, Default)]
+pub struct DavItem {
+    pub href: String,
+    /// etag without the surrounding quotes
+    pub etag: Option<String>,
+    /// calendar-data, or address-data in CardDAV responses
+    pub calendar_data: Option<String>,
+    /// status of the response itself (not of a propstat), e.g. 404 for removed items
+    pub status: Option<u16>,
+    /// the response went over the size limit, so only its href is known
+    pub oversized: bool,
+}
+
+/// incremental multistatus parser: bytes go in as they arrive from the network and every
+/// complete <response> comes out right away, so only one response is buffered at a time
+#[derive(Debug, Default)]
+pub struct MultistatusStream {
+    buffer: Vec<u8>,
+    scanned: usize,
+    /// largest <response> to parse, in bytes
+    max_response: Option<usize>,
+    /// href of the oversized response being skipped
+    skipping: Option<String>,
+}
+
+impl MultistatusStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// a parser that skips responses larger than `max_response` bytes instead of buffering
+    /// them, handing them out as `oversized` items
+    pub fn with_limit(max_response: usize) -> Self {
+        Self {
+            max_response: Some(max_response),
+            ..Self::default()
+        }
+    }
+
+    /// add a chunk of the body and return the responses it completed
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<DavItem>, String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut items = Vec::new();
+
+        if let Some(href) = self.skipping.take() {
+            let from = self.scanned.saturating_sub(MAX_END_TAG);
+            let Some(end) = RESPONSE_END
+                .find(&self.buffer[from..])
+                .map(|m| from + m.end())
+            else {
+                // only the tail could still hold the start of the closing tag
+                let tail = self.buffer.len().saturating_sub(MAX_END_TAG);
+                self.buffer.drain(..tail);
+                self.scanned = self.buffer.len();
+                self.skipping = Some(href);
+                return Ok(items);
+            };
+            self.buffer.drain(..end);
+            self.scanned = 0;
+            items.push(oversized(href));
+        }
+
+        let from = self.scanned.saturating_sub(MAX_END_TAG);
+        if let Some(end) = RESPONSE_END
+            .find_iter(&self.buffer[from..])
+            .last()
+            .map(|m| from + m.end())
+        {
+            items.extend(parse_items(&self.buffer[..end])?);
+            self.buffer.drain(..end);
+        }
+        self.scanned = self.buffer.len();
+
+        if let Some(max) = self.max_response {
+            // what's left is the start of a response that hasn't ended yet
+            if self.buffer.len() > max {
+                let href = FIRST_HREF
+                    .captures(&self.buffer)
+                    .map(|captures| String::from_utf8_lossy(&captures[1]).into_owned())
+                    .unwrap_or_default();
+                log::warn!("Skipping a response over {max} bytes: {href}");
+                let tail = self.buffer.len().saturating_sub(MAX_END_TAG);
+                self.buffer.drain(..tail);
+                self.scanned = self.buffer.len();
+                self.skipping = Some(href);
+            }
+            for item in &mut items {
+                if item
+                    .calendar_data
+                    .as_ref()
+                    .is_some_and(|data| data.len() > max)
+                {
+                    log::warn!("Skipping a response over {max} bytes: {}", item.href);
+                    *item = oversized(std::mem::take(&mut item.href));
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    /// parse whatever is left once the body is complete
+    pub fn finish(self) -> Result<Vec<DavItem>, String> {
+        match self.skipping {
+            Some(href) => Ok(vec![oversized(href)]),
+            None => parse_items(&self.buffer),
+        }
+    }
+}
+
+fn oversized(href: String) -> DavItem {
+    DavItem {
+        href,
+        oversized: true,
+        ..DavItem::default()
+    }
+}
+
+fn status_code(line: &str) -> Option<u16> {
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+// parses a run of <response> elements; namespaces are ignored and elements are matched by
+// local name, since the enclosing <multistatus> (and its xmlns) may be in an earlier chunk
+fn parse_items(xml: &[u8]) -> Result<Vec<DavItem>, String> {
+    let mut reader = Reader::from_reader(xml);
+    let config = reader.config_mut();
+    config.trim_text(true);
+    config.check_end_names = false;
+    config.allow_unmatched_ends = true;
+
+    let mut items = Vec::new();
+    let mut current: Option<DavItem> = None;
+    // local name of the innermost element whose text we care about
+    let mut field: Option<Vec<u8>> = None;
+    let mut in_propstat = false;
+    let mut text = String::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Invalid multistatus response: {e}"))?;
+        match event {
+            Event::Start(start) => match start.local_name().as_ref() {
+                b"response" => current = Some(DavItem::default()),
+                b"propstat" => in_propstat = true,
+                name @ (b"href" | b"getetag" | b"calendar-data" | b"address-data" | b"status") => {
+                    field = Some(name.to_vec());
+                    text.clear();
+                }
+                _ => {}
+            },
+            Event::Text(content) if field.is_some() => {
+                let content = content
+                    .unescape()
+                    .map_err(|e| format!("Invalid multistatus response: {e}"))?;
+                text.push_str(&content);
+            }
+            Event::CData(content) if field.is_some() => {
+                text.push_str(&String::from_utf8_lossy(&content.into_inner()));
+            }
+            Event::End(end) => match end.local_name().as_ref() {
+                b"response" => items.extend(current.take()),
+                b"propstat" => in_propstat = false,
+                name if field.as_deref() == Some(name) => {
+                    field = None;
+                    let value = std::mem::take(&mut text);
+                    let Some(item) = current.as_mut() else {
+                        continue;
+                    };
+                    match name {
+                        // only the response's own href, not hrefs inside properties
+                        b"href" if item.href.is_empty() && !in_propstat => {
+                            item.href = value.trim().to_string()
+                        }
+                        b"getetag" => item.etag = Some(value.trim().trim_matches('"').to_string()),
+                        b"calendar-data" | b"address-data" => item.calendar_data = Some(value),
+                        b"status" if !in_propstat => item.status = status_code(&value),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(items)
+}
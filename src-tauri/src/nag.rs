@@ -0,0 +1,234 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::tasks::now_iso;
+use crate::{db, i18n, lock, read_only};
+
+// a nagging task fires its reminder again every few minutes until it's completed or the
+// reminder is dismissed. the first notification is the reminder itself, nagging starts after
+// it: at the task's latest reminder that has passed, or its due date without reminders
+
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+const MAX_INTERVAL_MINUTES: u32 = 24 * 60;
+
+/// how a task nags
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskNag {
+    pub task_uid: String,
+    pub interval_minutes: i64,
+    pub last_nagged_at: Option<String>,
+    pub dismissed_at: Option<String>,
+    /// the reminder is due and not dismissed, so the task nags right now
+    #[sqlx(skip)]
+    pub active: bool,
+}
+
+#[derive(FromRow)]
+struct NagRow {
+    task_uid: String,
+    interval_minutes: i64,
+    last_nagged_at: Option<String>,
+    dismissed_at: Option<String>,
+    title: String,
+    reminders: Option<String>,
+    due_date: Option<String>,
+}
+
+fn parse(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+// when the reminder that nagging follows up on went off; None while it's still ahead
+fn reminded_at(
+    reminders: Option<&str>,
+    due_date: Option<&str>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let triggers: Vec<DateTime<Utc>> = reminders
+        .and_then(|reminders| serde_json::from_str::<Vec<Value>>(reminders).ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|reminder| reminder.get("trigger")?.as_str().and_then(parse))
+        .collect();
+    let at = if triggers.is_empty() {
+        due_date.and_then(parse)?
+    } else {
+        triggers
+            .into_iter()
+            .filter(|trigger| *trigger <= now)
+            .max()?
+    };
+    (at <= now).then_some(at)
+}
+
+// nagging since the reminder went off, unless it was dismissed after that
+fn nagging_since(row: &NagRow, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let reminded = reminded_at(row.reminders.as_deref(), row.due_date.as_deref(), now)?;
+    let dismissed = row.dismissed_at.as_deref().and_then(parse);
+    dismissed
+        .is_none_or(|dismissed| dismissed < reminded)
+        .then_some(reminded)
+}
+
+async fn nag_rows(pool: &SqlitePool, uid: Option<&str>) -> Result<Vec<NagRow>, String> {
+    sqlx::query_as(
+        "SELECT n.task_uid, n.interval_minutes, n.last_nagged_at, n.dismissed_at, t.title,
+                t.reminders, t.due_date
+         FROM task_nags n JOIN tasks t ON t.uid = n.task_uid
+         WHERE t.completed = 0 AND (?1 IS NULL OR n.task_uid = ?1)",
+    )
+    .bind(uid)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn notify(app_handle: &tauri::AppHandle, title: &str) {
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(i18n::t("Don't forget"))
+        .body(title)
+        .show()
+    {
+        log::warn!("Failed to show nag notification: {e}");
+    }
+}
+
+#[tracing::instrument(name = "nag", level = "trace", skip_all, err(level = "debug"))]
+async fn nag_once(app_handle: &tauri::AppHandle, pool: &SqlitePool) -> Result<(), String> {
+    let now = Utc::now();
+    for row in nag_rows(pool, None).await? {
+        let Some(since) = nagging_since(&row, now) else {
+            continue;
+        };
+        // the reminder itself was the first notification
+        let last = row
+            .last_nagged_at
+            .as_deref()
+            .and_then(parse)
+            .filter(|last| *last > since)
+            .unwrap_or(since);
+        if now < last + Duration::minutes(row.interval_minutes) {
+            continue;
+        }
+        notify(app_handle, &row.title);
+        sqlx::query("UPDATE task_nags SET last_nagged_at = ? WHERE task_uid = ?")
+            .bind(now_iso())
+            .bind(&row.task_uid)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// nag about due tasks in the background; a locked app keeps quiet, its task titles are
+/// nobody's business
+pub fn start_nagging(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if lock::is_locked() {
+                continue;
+            }
+            let Ok(pool) = db::pool(&app_handle).await else {
+                continue;
+            };
+            if let Err(e) = nag_once(&app_handle, &pool).await {
+                log::warn!("Failed to check nagging tasks: {e}");
+            }
+        }
+    });
+}
+
+fn emit_changed(app_handle: &tauri::AppHandle, uid: &str) {
+    if let Err(e) = app_handle.emit("nag-changed", uid) {
+        log::warn!("Failed to emit nag-changed: {e}");
+    }
+}
+
+/// how the task nags; None when it doesn't
+#[tauri::command]
+pub async fn get_task_nag(
+    app_handle: tauri::AppHandle,
+    uid: String,
+) -> Result<Option<TaskNag>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let nag: Option<TaskNag> = sqlx::query_as(
+        "SELECT task_uid, interval_minutes, last_nagged_at, dismissed_at FROM task_nags
+         WHERE task_uid = ?",
+    )
+    .bind(&uid)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let active = nag_rows(&pool, Some(&uid))
+        .await?
+        .first()
+        .is_some_and(|row| nagging_since(row, Utc::now()).is_some());
+    Ok(nag.map(|nag| TaskNag { active, ..nag }))
+}
+
+/// make the task's reminder fire again every `interval_minutes` until the task is completed
+/// or the reminder dismissed; None stops nagging
+#[tauri::command]
+pub async fn set_task_nag(
+    app_handle: tauri::AppHandle,
+    uid: String,
+    interval_minutes: Option<u32>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    match interval_minutes {
+        Some(minutes) => {
+            if !(1..=MAX_INTERVAL_MINUTES).contains(&minutes) {
+                return Err(format!(
+                    "Nag interval must be between 1 and {MAX_INTERVAL_MINUTES} minutes"
+                ));
+            }
+            sqlx::query(
+                "INSERT INTO task_nags (task_uid, interval_minutes) VALUES (?, ?)
+                 ON CONFLICT(task_uid) DO UPDATE SET interval_minutes = excluded.interval_minutes",
+            )
+            .bind(&uid)
+            .bind(minutes)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        None => {
+            sqlx::query("DELETE FROM task_nags WHERE task_uid = ?")
+                .bind(&uid)
+                .execute(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    emit_changed(&app_handle, &uid);
+    Ok(())
+}
+
+/// stop nagging about the reminder that went off; the task nags again at its next reminder
+#[tauri::command]
+pub async fn dismiss_task_nag(app_handle: tauri::AppHandle, uid: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("UPDATE task_nags SET dismissed_at = ? WHERE task_uid = ?")
+        .bind(now_iso())
+        .bind(&uid)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    emit_changed(&app_handle, &uid);
+    Ok(())
+}
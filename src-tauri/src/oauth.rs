@@ -0,0 +1,279 @@
+use chrono::{DateTime, Duration as ChronoDuration, SecondsFormat, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::Emitter;
+use tauri_plugin_http::reqwest::{self, header};
+
+use crate::http_actions::percent_encode;
+use crate::{caldav, credentials, db, lock, read_only};
+
+// OAuth tokens of an account are one json secret in the credential store, next to its
+// password; only the authorization itself happens elsewhere
+const SECRET_KIND: &str = "oauth";
+// access tokens are refreshed this long before they expire, so a sync never starts with
+// one that runs out halfway
+const REFRESH_MARGIN: i64 = 5 * 60;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// token endpoint errors that no retry can fix (RFC 6749 5.2)
+const FATAL_ERRORS: &[&str] = &["invalid_grant", "invalid_client", "unauthorized_client"];
+
+lazy_static! {
+    // accounts the ui was already told to re-authorize, so it prompts once
+    static ref REAUTHORIZE: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    // refresh tokens can rotate, two refreshes at once would lose one of them
+    static ref REFRESHING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthTokens {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// None for tokens that don't expire
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+impl OAuthTokens {
+    fn expires_within(&self, seconds: i64) -> bool {
+        self.expires_at
+            .as_deref()
+            .and_then(|expires_at| DateTime::parse_from_rfc3339(expires_at).ok())
+            .is_some_and(|expires_at| expires_at <= Utc::now() + ChronoDuration::seconds(seconds))
+    }
+}
+
+/// what the ui gets to know about an account's tokens
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthStatus {
+    pub account_id: String,
+    pub expires_at: Option<String>,
+    pub can_refresh: bool,
+    pub reauthorization_required: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReauthorizationRequired {
+    account_id: String,
+    error: String,
+}
+
+async fn load(account_id: &str) -> Result<Option<OAuthTokens>, String> {
+    credentials::get_account_secret(account_id, SECRET_KIND)
+        .await?
+        .map(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+async fn store(account_id: &str, tokens: &OAuthTokens) -> Result<(), String> {
+    let json = serde_json::to_string(tokens).map_err(|e| e.to_string())?;
+    credentials::set_account_secret(account_id, SECRET_KIND, json).await
+}
+
+fn status(account_id: &str, tokens: &OAuthTokens) -> OAuthStatus {
+    OAuthStatus {
+        account_id: account_id.to_string(),
+        expires_at: tokens.expires_at.clone(),
+        can_refresh: tokens.refresh_token.is_some(),
+        reauthorization_required: REAUTHORIZE
+            .lock()
+            .expect("Failed to lock REAUTHORIZE")
+            .contains(account_id),
+    }
+}
+
+// tell the ui once that only a new authorization helps
+fn require_reauthorization(app_handle: &tauri::AppHandle, account_id: &str, error: &str) {
+    let first = REAUTHORIZE
+        .lock()
+        .expect("Failed to lock REAUTHORIZE")
+        .insert(account_id.to_string());
+    if !first {
+        return;
+    }
+    log::warn!("Account {account_id} has to be authorized again: {error}");
+    let payload = ReauthorizationRequired {
+        account_id: account_id.to_string(),
+        error: error.to_string(),
+    };
+    if let Err(e) = app_handle.emit("oauth-reauthorization-required", payload) {
+        log::warn!("Failed to emit oauth-reauthorization-required: {e}");
+    }
+}
+
+// Err(Some(error)) when the refresh token is no good anymore, Err(None) with a logged
+// error when the endpoint just couldn't be reached
+async fn refresh(tokens: &OAuthTokens) -> Result<OAuthTokens, Option<String>> {
+    let Some(refresh_token) = &tokens.refresh_token else {
+        return Err(Some(
+            "The access token expired and can't be refreshed".to_string(),
+        ));
+    };
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+        ("client_id", tokens.client_id.as_str()),
+    ];
+    if let Some(secret) = &tokens.client_secret {
+        form.push(("client_secret", secret));
+    }
+    if let Some(scope) = &tokens.scope {
+        form.push(("scope", scope));
+    }
+    let body = form
+        .iter()
+        .map(|(name, value)| format!("{name}={}", percent_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("caldav-tasks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| {
+            log::warn!("Token refresh failed: {e}");
+            None
+        })?;
+    let response = client
+        .post(&tokens.token_url)
+        .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+        .header(header::ACCEPT, "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| {
+            log::warn!("Token refresh failed: {}", caldav::error_chain(&e));
+            None
+        })?;
+    let status = response.status();
+    let answer: Value = response.json().await.unwrap_or(json!({}));
+
+    if !status.is_success() {
+        let error = answer["error"].as_str().unwrap_or_default();
+        if FATAL_ERRORS.contains(&error) || status.as_u16() == 401 {
+            let description = answer["error_description"].as_str().unwrap_or(error);
+            return Err(Some(format!(
+                "The authorization was revoked ({description})"
+            )));
+        }
+        log::warn!("Token refresh failed: the server answered {status}");
+        return Err(None);
+    }
+    let Some(access_token) = answer["access_token"].as_str() else {
+        log::warn!("Token refresh failed: no access token in the answer");
+        return Err(None);
+    };
+    Ok(OAuthTokens {
+        access_token: access_token.to_string(),
+        // servers that rotate refresh tokens send a new one, the others keep the old one valid
+        refresh_token: answer["refresh_token"]
+            .as_str()
+            .map(str::to_string)
+            .or_else(|| tokens.refresh_token.clone()),
+        expires_at: answer["expires_in"].as_i64().map(|seconds| {
+            (Utc::now() + ChronoDuration::seconds(seconds))
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+        }),
+        ..tokens.clone()
+    })
+}
+
+/// a usable access token for the account, refreshed first when it's about to expire;
+/// None for accounts without OAuth
+pub async fn access_token(
+    app_handle: &tauri::AppHandle,
+    account_id: &str,
+) -> Result<Option<String>, String> {
+    let _refreshing = REFRESHING.lock().await;
+    let Some(tokens) = load(account_id).await? else {
+        return Ok(None);
+    };
+    if !tokens.expires_within(REFRESH_MARGIN) {
+        return Ok(Some(tokens.access_token));
+    }
+
+    match refresh(&tokens).await {
+        Ok(refreshed) => {
+            store(account_id, &refreshed).await?;
+            log::debug!("Refreshed the access token of account {account_id}");
+            Ok(Some(refreshed.access_token))
+        }
+        Err(Some(error)) => {
+            require_reauthorization(app_handle, account_id, &error);
+            Err(error)
+        }
+        // the current token still works for a while, the next sync tries again
+        Err(None) if !tokens.expires_within(0) => Ok(Some(tokens.access_token)),
+        Err(None) => Err("The access token expired and couldn't be refreshed".to_string()),
+    }
+}
+
+/// save the tokens of a (new) authorization
+#[tauri::command]
+pub async fn set_oauth_tokens(account_id: String, tokens: OAuthTokens) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    store(&account_id, &tokens).await?;
+    REAUTHORIZE
+        .lock()
+        .expect("Failed to lock REAUTHORIZE")
+        .remove(&account_id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_oauth_tokens(account_id: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    REAUTHORIZE
+        .lock()
+        .expect("Failed to lock REAUTHORIZE")
+        .remove(&account_id);
+    credentials::delete_account_secret(&account_id, SECRET_KIND).await
+}
+
+/// the access token to send, see `access_token`
+#[tauri::command]
+pub async fn get_oauth_access_token(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+) -> Result<Option<String>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    access_token(&app_handle, &account_id).await
+}
+
+/// refresh the tokens of every OAuth account that are about to expire, before a sync;
+/// returns the state of each of them
+#[tauri::command]
+pub async fn refresh_oauth_tokens(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<OAuthStatus>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let account_ids: Vec<String> = sqlx::query_scalar("SELECT id FROM accounts")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut statuses = Vec::new();
+    for account_id in account_ids {
+        if let Err(e) = access_token(&app_handle, &account_id).await {
+            log::warn!("Failed to refresh the tokens of account {account_id}: {e}");
+        }
+        if let Some(tokens) = load(&account_id).await? {
+            statuses.push(status(&account_id, &tokens));
+        }
+    }
+    Ok(statuses)
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::{db, i18n, read_only, settings};
+
+// how large a single task may get on the wire. a pathological object (a description with a
+// pasted log, thousands of inline attachments) is skipped with a warning instead of stalling
+// the whole sync, and an oversized local task fails its upload with a clear error
+
+const MAX_OBJECT_KB_KEY: &str = "max_object_kb";
+const DEFAULT_MAX_OBJECT_KB: u64 = 2048;
+// below this even ordinary tasks with long notes wouldn't fit
+const MIN_MAX_OBJECT_KB: u64 = 64;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadLimits {
+    /// largest task (its whole VTODO) that is fetched or uploaded, in KB
+    pub max_object_kb: u64,
+}
+
+async fn load(pool: &SqlitePool) -> Result<PayloadLimits, String> {
+    Ok(PayloadLimits {
+        max_object_kb: settings::get_u64(pool, MAX_OBJECT_KB_KEY, DEFAULT_MAX_OBJECT_KB).await?,
+    })
+}
+
+/// the object size limit in bytes
+pub async fn max_object_size(pool: &SqlitePool) -> Result<usize, String> {
+    Ok(load(pool).await?.max_object_kb as usize * 1024)
+}
+
+#[tauri::command]
+pub async fn get_payload_limits(app_handle: tauri::AppHandle) -> Result<PayloadLimits, String> {
+    let pool = db::pool(&app_handle).await?;
+    load(&pool).await
+}
+
+#[tauri::command]
+pub async fn set_payload_limits(
+    app_handle: tauri::AppHandle,
+    limits: PayloadLimits,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    if limits.max_object_kb < MIN_MAX_OBJECT_KB {
+        return Err(i18n::t_with(
+            "The size limit has to be at least {} KB",
+            &[&MIN_MAX_OBJECT_KB],
+        ));
+    }
+    let pool = db::pool(&app_handle).await?;
+    settings::set(&pool, MAX_OBJECT_KB_KEY, &limits.max_object_kb.to_string()).await
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use tauri::Emitter;
+
+use crate::tasks::now_iso;
+use crate::{db, lock, read_only};
+
+// the local changes that haven't reached the server yet: unsynced tasks and queued deletions,
+// with why they're held back and how their last upload failed, so a sync that "finished"
+// without them can be explained and the stuck ones retried or dropped
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PendingKind {
+    /// an unsynced task, by its id
+    Task,
+    /// a queued deletion, by the uid of the deleted task
+    Deletion,
+}
+
+impl PendingKind {
+    fn name(self) -> &'static str {
+        match self {
+            PendingKind::Task => "task",
+            PendingKind::Deletion => "deletion",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PendingOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+/// why a sync skips the change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockedReason {
+    /// its account is paused
+    AccountPaused,
+    /// it waits in the conflict review queue
+    Conflict,
+    /// the server rejects the account's credentials
+    AuthFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChange {
+    pub kind: PendingKind,
+    pub id: String,
+    pub task_uid: String,
+    /// None for deletions, whose task is gone
+    pub title: Option<String>,
+    pub account_id: String,
+    pub account_name: Option<String>,
+    pub calendar_id: String,
+    pub calendar_name: Option<String>,
+    pub operation: PendingOperation,
+    pub modified_at: Option<String>,
+    /// None when the next sync tries it
+    pub blocked: Option<BlockedReason>,
+    pub last_error: Option<String>,
+    /// failed uploads in a row
+    pub attempts: i64,
+    pub last_attempt_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct PendingRow {
+    id: String,
+    task_uid: String,
+    title: Option<String>,
+    account_id: String,
+    account_name: Option<String>,
+    calendar_id: String,
+    calendar_name: Option<String>,
+    has_href: bool,
+    modified_at: Option<String>,
+    account_paused: bool,
+    in_review: bool,
+    auth_failed: bool,
+    last_error: Option<String>,
+    attempts: i64,
+    last_attempt_at: Option<String>,
+}
+
+impl PendingRow {
+    fn into_change(self, kind: PendingKind) -> PendingChange {
+        let operation = match kind {
+            PendingKind::Deletion => PendingOperation::Delete,
+            PendingKind::Task if self.has_href => PendingOperation::Update,
+            PendingKind::Task => PendingOperation::Create,
+        };
+        let blocked = if self.account_paused {
+            Some(BlockedReason::AccountPaused)
+        } else if self.in_review {
+            Some(BlockedReason::Conflict)
+        } else if self.auth_failed {
+            Some(BlockedReason::AuthFailed)
+        } else {
+            None
+        };
+        PendingChange {
+            kind,
+            id: self.id,
+            task_uid: self.task_uid,
+            title: self.title,
+            account_id: self.account_id,
+            account_name: self.account_name,
+            calendar_id: self.calendar_id,
+            calendar_name: self.calendar_name,
+            operation,
+            modified_at: self.modified_at,
+            blocked,
+            last_error: self.last_error,
+            attempts: self.attempts,
+            last_attempt_at: self.last_attempt_at,
+        }
+    }
+}
+
+/// every change waiting for the server, oldest first
+#[tauri::command]
+pub async fn get_pending_changes(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<PendingChange>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let tasks: Vec<PendingRow> = sqlx::query_as(
+        "SELECT t.id, t.uid AS task_uid, t.title, t.account_id, a.name AS account_name,
+                t.calendar_id, c.display_name AS calendar_name, t.href IS NOT NULL AS has_href,
+                t.modified_at, a.is_active = 0 AS account_paused,
+                EXISTS (SELECT 1 FROM sync_conflicts sc
+                        WHERE sc.task_id = t.id AND sc.resolved_at IS NULL) AS in_review,
+                COALESCE(h.state = 'authFailed', 0) AS auth_failed,
+                f.error AS last_error, COALESCE(f.attempts, 0) AS attempts,
+                f.last_attempt_at
+         FROM tasks t
+         JOIN accounts a ON a.id = t.account_id
+         LEFT JOIN calendars c ON c.id = t.calendar_id
+         LEFT JOIN account_health h ON h.account_id = t.account_id
+         LEFT JOIN sync_push_failures f ON f.kind = 'task' AND f.item_id = t.id
+         WHERE t.synced = 0 AND COALESCE(t.local_only, 0) = 0
+             AND COALESCE(a.server_type, '') NOT IN ('demo', 'local')
+         ORDER BY t.modified_at",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let deletions: Vec<PendingRow> = sqlx::query_as(
+        "SELECT p.uid AS id, p.uid AS task_uid, NULL AS title, p.account_id,
+                a.name AS account_name, p.calendar_id, c.display_name AS calendar_name,
+                1 AS has_href, NULL AS modified_at, COALESCE(a.is_active = 0, 0) AS account_paused,
+                0 AS in_review, COALESCE(h.state = 'authFailed', 0) AS auth_failed,
+                f.error AS last_error, COALESCE(f.attempts, 0) AS attempts,
+                f.last_attempt_at
+         FROM pending_deletions p
+         LEFT JOIN accounts a ON a.id = p.account_id
+         LEFT JOIN calendars c ON c.id = p.calendar_id
+         LEFT JOIN account_health h ON h.account_id = p.account_id
+         LEFT JOIN sync_push_failures f ON f.kind = 'deletion' AND f.item_id = p.uid",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(deletions
+        .into_iter()
+        .map(|row| row.into_change(PendingKind::Deletion))
+        .chain(
+            tasks
+                .into_iter()
+                .map(|row| row.into_change(PendingKind::Task)),
+        )
+        .collect())
+}
+
+/// the outcome of uploading a change during a sync; None when it went through
+#[tauri::command]
+pub async fn report_push_result(
+    app_handle: tauri::AppHandle,
+    kind: PendingKind,
+    id: String,
+    error: Option<String>,
+) -> Result<(), String> {
+    let pool = db::pool(&app_handle).await?;
+    if let Some(error) = &error {
+        tracing::warn!(kind = kind.name(), %id, "Failed to upload a change: {error}");
+    }
+    let query = match &error {
+        None => sqlx::query("DELETE FROM sync_push_failures WHERE kind = ? AND item_id = ?")
+            .bind(kind.name())
+            .bind(&id),
+        Some(error) => sqlx::query(
+            "INSERT INTO sync_push_failures (kind, item_id, error, attempts, last_attempt_at)
+             VALUES (?1, ?2, ?3, 1, ?4)
+             ON CONFLICT(kind, item_id) DO UPDATE SET
+                error = ?3, attempts = attempts + 1, last_attempt_at = ?4",
+        )
+        .bind(kind.name())
+        .bind(&id)
+        .bind(error)
+        .bind(now_iso()),
+    };
+    query.execute(&pool).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// give up on a change: a new task is removed, an edit is replaced by the server's version on
+/// the next sync, and a deletion is dropped so the task comes back from the server
+#[tauri::command]
+pub async fn discard_pending_change(
+    app_handle: tauri::AppHandle,
+    kind: PendingKind,
+    id: String,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let statement = match kind {
+        PendingKind::Deletion => "DELETE FROM pending_deletions WHERE uid = ?",
+        PendingKind::Task => {
+            let href: Option<Option<String>> =
+                sqlx::query_scalar("SELECT href FROM tasks WHERE id = ? AND synced = 0")
+                    .bind(&id)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            match href {
+                None => return Err(format!("No pending change for task {id}")),
+                Some(None) => "DELETE FROM tasks WHERE id = ?",
+                // a changed etag makes the next sync take the server's copy
+                Some(Some(_)) => "UPDATE tasks SET synced = 1, etag = '' WHERE id = ?",
+            }
+        }
+    };
+    sqlx::query(statement)
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM sync_push_failures WHERE kind = ? AND item_id = ?")
+        .bind(kind.name())
+        .bind(&id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!("Discarded the pending {} {id}", kind.name());
+    let _ = app_handle.emit("database-changed", ());
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{db, locale, lock};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanGrouping {
+    /// local calendar day the task is due
+    Day,
+    /// local week (starting on the locale's first day) the task is due
+    Week,
+}
+
+/// estimated work for the tasks due in one day or week
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTime {
+    /// yyyy-mm-dd of the day, or of the first day of the week
+    pub key: String,
+    /// estimates of every task due then, completed or not
+    pub seconds: i64,
+    /// estimates of the tasks still open
+    pub remaining_seconds: i64,
+    pub tasks: i64,
+    /// open tasks without an estimate, which the totals leave out
+    pub unestimated: i64,
+}
+
+/// estimated durations summed per day or week for tasks due in [from, to), in order
+#[tauri::command]
+pub async fn get_planned_time(
+    app_handle: tauri::AppHandle,
+    group_by: PlanGrouping,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<PlannedTime>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let key = match group_by {
+        PlanGrouping::Day => "date(due_date, 'localtime')".to_string(),
+        PlanGrouping::Week => format!(
+            "date(due_date, 'localtime', {})",
+            locale::current().week_start_modifiers()
+        ),
+    };
+    let query = format!(
+        "SELECT {key} AS key,
+            COALESCE(SUM(estimated_duration), 0) AS seconds,
+            COALESCE(SUM(CASE WHEN completed = 0 THEN estimated_duration END), 0)
+                AS remaining_seconds,
+            COUNT(*) AS tasks,
+            COUNT(CASE WHEN completed = 0 AND estimated_duration IS NULL THEN 1 END)
+                AS unestimated
+         FROM tasks
+         WHERE due_date IS NOT NULL AND archived_at IS NULL
+            AND (?1 IS NULL OR due_date >= ?1) AND (?2 IS NULL OR due_date < ?2)
+         GROUP BY key
+         ORDER BY key"
+    );
+    sqlx::query_as(&query)
+        .bind(&from)
+        .bind(&to)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,409 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::tasks::{new_id, now_iso};
+use crate::{db, i18n, read_only, settings, tray};
+
+// app_settings keys
+const WORK_MINUTES_KEY: &str = "pomodoro_work_minutes";
+const SHORT_BREAK_MINUTES_KEY: &str = "pomodoro_short_break_minutes";
+const LONG_BREAK_MINUTES_KEY: &str = "pomodoro_long_break_minutes";
+const LONG_BREAK_EVERY_KEY: &str = "pomodoro_long_break_every";
+
+// the tray countdown only shows whole seconds
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+    Idle,
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PomodoroSettings {
+    pub work_minutes: u64,
+    pub short_break_minutes: u64,
+    pub long_break_minutes: u64,
+    /// a long break follows every n-th pomodoro
+    pub long_break_every: u64,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PomodoroStatus {
+    pub phase: Phase,
+    pub running: bool,
+    pub remaining_secs: u64,
+    /// pomodoros finished since the last long break
+    pub completed: u64,
+    pub task_uid: Option<String>,
+}
+
+struct Engine {
+    phase: Phase,
+    // time left while paused; while running it is counted down to `ends_at`
+    remaining: Duration,
+    ends_at: Option<Instant>,
+    // when the current pomodoro first started running, for the log
+    started_at: Option<String>,
+    completed: u64,
+    task_uid: Option<String>,
+    settings: PomodoroSettings,
+}
+
+impl Engine {
+    fn remaining(&self) -> Duration {
+        match self.ends_at {
+            Some(ends_at) => ends_at.saturating_duration_since(Instant::now()),
+            None => self.remaining,
+        }
+    }
+
+    fn status(&self) -> PomodoroStatus {
+        PomodoroStatus {
+            phase: self.phase,
+            running: self.ends_at.is_some(),
+            remaining_secs: self.remaining().as_secs_f64().round() as u64,
+            completed: self.completed,
+            task_uid: self.task_uid.clone(),
+        }
+    }
+
+    fn length(&self, phase: Phase) -> Duration {
+        let minutes = match phase {
+            Phase::Idle => 0,
+            Phase::Work => self.settings.work_minutes,
+            Phase::ShortBreak => self.settings.short_break_minutes,
+            Phase::LongBreak => self.settings.long_break_minutes,
+        };
+        Duration::from_secs(minutes.max(1) * 60)
+    }
+
+    fn begin(&mut self, phase: Phase, running: bool) {
+        self.phase = phase;
+        self.remaining = self.length(phase);
+        self.ends_at = None;
+        self.started_at = None;
+        if running {
+            self.resume();
+        }
+    }
+
+    fn resume(&mut self) {
+        if self.ends_at.is_none() {
+            self.ends_at = Some(Instant::now() + self.remaining);
+        }
+        if self.phase == Phase::Work && self.started_at.is_none() {
+            self.started_at = Some(now_iso());
+        }
+    }
+
+    fn pause(&mut self) {
+        self.remaining = self.remaining();
+        self.ends_at = None;
+    }
+
+    // the phase after the current one; finished pomodoros count towards the long break
+    fn next(&self, counted: bool) -> Phase {
+        match self.phase {
+            Phase::Work => {
+                let every = self.settings.long_break_every.max(1);
+                if counted && self.completed.is_multiple_of(every) {
+                    Phase::LongBreak
+                } else {
+                    Phase::ShortBreak
+                }
+            }
+            _ => Phase::Work,
+        }
+    }
+}
+
+// global storage for the pomodoro engine, driven by the ticker started in setup
+lazy_static! {
+    static ref ENGINE: Mutex<Engine> = Mutex::new(Engine {
+        phase: Phase::Idle,
+        remaining: Duration::ZERO,
+        ends_at: None,
+        started_at: None,
+        completed: 0,
+        task_uid: None,
+        settings: PomodoroSettings::default(),
+    });
+}
+
+fn status() -> PomodoroStatus {
+    ENGINE.lock().expect("Failed to lock ENGINE").status()
+}
+
+fn format_remaining(secs: u64) -> String {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+// countdown in the tray title and tooltip, cleared while idle
+fn update_tray(app_handle: &tauri::AppHandle, status: &PomodoroStatus) {
+    let text = match status.phase {
+        Phase::Idle => None,
+        phase => {
+            let message = match (phase == Phase::Work, status.running) {
+                (true, true) => "Focus {}",
+                (true, false) => "Focus {} (paused)",
+                (false, true) => "Break {}",
+                (false, false) => "Break {} (paused)",
+            };
+            Some(i18n::t_with(
+                message,
+                &[&format_remaining(status.remaining_secs)],
+            ))
+        }
+    };
+    tray::set_tray_status(app_handle, text.as_deref());
+}
+
+fn changed(app_handle: &tauri::AppHandle, status: PomodoroStatus) -> PomodoroStatus {
+    update_tray(app_handle, &status);
+    if let Err(e) = app_handle.emit("pomodoro-changed", &status) {
+        log::warn!("Failed to emit pomodoro-changed: {e}");
+    }
+    status
+}
+
+fn notify(app_handle: &tauri::AppHandle, title: &str, body: &str) {
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show pomodoro notification: {e}");
+    }
+}
+
+async fn load_settings(app_handle: &tauri::AppHandle) -> Result<PomodoroSettings, String> {
+    let pool = db::pool(app_handle).await?;
+    let defaults = PomodoroSettings::default();
+    Ok(PomodoroSettings {
+        work_minutes: settings::get_u64(&pool, WORK_MINUTES_KEY, defaults.work_minutes).await?,
+        short_break_minutes: settings::get_u64(
+            &pool,
+            SHORT_BREAK_MINUTES_KEY,
+            defaults.short_break_minutes,
+        )
+        .await?,
+        long_break_minutes: settings::get_u64(
+            &pool,
+            LONG_BREAK_MINUTES_KEY,
+            defaults.long_break_minutes,
+        )
+        .await?,
+        long_break_every: settings::get_u64(&pool, LONG_BREAK_EVERY_KEY, defaults.long_break_every)
+            .await?,
+    })
+}
+
+async fn log_pomodoro(
+    app_handle: &tauri::AppHandle,
+    task_uid: Option<String>,
+    started_at: String,
+) -> Result<(), String> {
+    let pool = db::pool(app_handle).await?;
+    // a task deleted in the meantime leaves an unassigned pomodoro
+    sqlx::query(
+        "INSERT INTO pomodoros (id, task_uid, started_at, ended_at)
+         VALUES (?, (SELECT uid FROM tasks WHERE uid = ?), ?, ?)",
+    )
+    .bind(new_id())
+    .bind(&task_uid)
+    .bind(&started_at)
+    .bind(now_iso())
+    .execute(&pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+// move on once the running phase is over: a finished pomodoro starts its break right away,
+// a finished break waits for the next pomodoro to be started
+async fn finish_phase(app_handle: &tauri::AppHandle) {
+    let finished = {
+        let mut engine = ENGINE.lock().expect("Failed to lock ENGINE");
+        if engine.ends_at.is_none() || !engine.remaining().is_zero() {
+            return;
+        }
+        let phase = engine.phase;
+        let log = if phase == Phase::Work {
+            engine.completed += 1;
+            engine
+                .started_at
+                .take()
+                .map(|started_at| (engine.task_uid.clone(), started_at))
+        } else {
+            None
+        };
+        let next = engine.next(true);
+        if next == Phase::Work && phase == Phase::LongBreak {
+            engine.completed = 0;
+        }
+        engine.begin(next, phase == Phase::Work);
+        (phase, log, engine.status())
+    };
+
+    let (phase, log, status) = finished;
+    if let Some((task_uid, started_at)) = log.filter(|_| !read_only::is_enabled()) {
+        if let Err(e) = log_pomodoro(app_handle, task_uid, started_at).await {
+            log::warn!("Failed to log pomodoro: {e}");
+        }
+    }
+    match phase {
+        Phase::Work => notify(
+            app_handle,
+            i18n::t("Pomodoro finished"),
+            &i18n::t_with(
+                "Time for a {} minute break",
+                &[&status.remaining_secs.div_ceil(60)],
+            ),
+        ),
+        _ => notify(
+            app_handle,
+            i18n::t("Break is over"),
+            i18n::t("Ready for the next pomodoro?"),
+        ),
+    }
+    changed(app_handle, status);
+}
+
+/// count down the running phase in the background, so it keeps going while the window
+/// is hidden or reloading
+pub fn start_ticker(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let status = status();
+            if !status.running {
+                continue;
+            }
+            if status.remaining_secs == 0 {
+                finish_phase(&app_handle).await;
+            } else {
+                update_tray(&app_handle, &status);
+            }
+        }
+    });
+}
+
+/// start a pomodoro, or resume the paused phase; a task given here is what finished
+/// pomodoros are logged against
+#[tauri::command]
+pub async fn start_pomodoro(
+    app_handle: tauri::AppHandle,
+    task_uid: Option<String>,
+) -> Result<PomodoroStatus, String> {
+    let settings = load_settings(&app_handle).await?;
+    let status = {
+        let mut engine = ENGINE.lock().expect("Failed to lock ENGINE");
+        engine.settings = settings;
+        if task_uid.is_some() {
+            engine.task_uid = task_uid;
+        }
+        if engine.phase == Phase::Idle {
+            engine.begin(Phase::Work, true);
+        } else {
+            engine.resume();
+        }
+        engine.status()
+    };
+    Ok(changed(&app_handle, status))
+}
+
+#[tauri::command]
+pub async fn pause_pomodoro(app_handle: tauri::AppHandle) -> Result<PomodoroStatus, String> {
+    let status = {
+        let mut engine = ENGINE.lock().expect("Failed to lock ENGINE");
+        engine.pause();
+        engine.status()
+    };
+    Ok(changed(&app_handle, status))
+}
+
+/// jump to the next phase; a skipped pomodoro isn't logged
+#[tauri::command]
+pub async fn skip_pomodoro(app_handle: tauri::AppHandle) -> Result<PomodoroStatus, String> {
+    let status = {
+        let mut engine = ENGINE.lock().expect("Failed to lock ENGINE");
+        if engine.phase != Phase::Idle {
+            let running = engine.ends_at.is_some();
+            let next = engine.next(false);
+            engine.begin(next, running);
+        }
+        engine.status()
+    };
+    Ok(changed(&app_handle, status))
+}
+
+/// stop and reset the cycle
+#[tauri::command]
+pub async fn stop_pomodoro(app_handle: tauri::AppHandle) -> Result<PomodoroStatus, String> {
+    let status = {
+        let mut engine = ENGINE.lock().expect("Failed to lock ENGINE");
+        engine.phase = Phase::Idle;
+        engine.remaining = Duration::ZERO;
+        engine.ends_at = None;
+        engine.started_at = None;
+        engine.completed = 0;
+        engine.status()
+    };
+    Ok(changed(&app_handle, status))
+}
+
+#[tauri::command]
+pub async fn get_pomodoro_status() -> Result<PomodoroStatus, String> {
+    Ok(status())
+}
+
+#[tauri::command]
+pub async fn get_pomodoro_settings(
+    app_handle: tauri::AppHandle,
+) -> Result<PomodoroSettings, String> {
+    load_settings(&app_handle).await
+}
+
+/// save new durations; they apply from the next phase on
+#[tauri::command]
+pub async fn set_pomodoro_settings(
+    app_handle: tauri::AppHandle,
+    settings: PomodoroSettings,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    for (key, value) in [
+        (WORK_MINUTES_KEY, settings.work_minutes),
+        (SHORT_BREAK_MINUTES_KEY, settings.short_break_minutes),
+        (LONG_BREAK_MINUTES_KEY, settings.long_break_minutes),
+        (LONG_BREAK_EVERY_KEY, settings.long_break_every),
+    ] {
+        settings::set(&pool, key, &value.max(1).to_string()).await?;
+    }
+    ENGINE.lock().expect("Failed to lock ENGINE").settings = settings;
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use serde::Serialize;
+use tauri_plugin_http::reqwest::Url;
+
+use crate::auth::AuthMethod;
+use crate::caldav::{AccountConfig, CalDavClient};
+use crate::tls::TlsSettings;
+
+// the CalDAV providers most people use, so adding an account is picking one and entering the
+// credentials. hosted services have a fixed server; self-hosted ones only need their address
+
+/// what the account dialog needs to set up an account with a provider
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// None for self-hosted servers, where the user enters the address
+    pub server_url: Option<&'static str>,
+    pub server_type: &'static str,
+    pub auth_method: AuthMethod,
+    /// the username is the full email address
+    pub email_username: bool,
+    /// how to get the password to enter, for providers that want an app password
+    pub password_hint: Option<&'static str>,
+    pub password_help_url: Option<&'static str>,
+    // email domains and server hosts that belong to the provider
+    #[serde(skip)]
+    domains: &'static [&'static str],
+}
+
+const PRESETS: &[ProviderPreset] = &[
+    ProviderPreset {
+        id: "nextcloud",
+        name: "Nextcloud",
+        server_url: None,
+        server_type: "nextcloud",
+        auth_method: AuthMethod::Basic,
+        email_username: false,
+        password_hint: Some(
+            "With two-factor authentication, create an app password under Settings → Security",
+        ),
+        password_help_url: None,
+        domains: &[],
+    },
+    ProviderPreset {
+        id: "fastmail",
+        name: "Fastmail",
+        server_url: Some("https://caldav.fastmail.com"),
+        server_type: "generic",
+        auth_method: AuthMethod::Basic,
+        email_username: true,
+        password_hint: Some(
+            "Fastmail needs an app password with CalDAV access, created under Settings → Privacy & Security",
+        ),
+        password_help_url: Some("https://app.fastmail.com/settings/security/apps"),
+        domains: &["fastmail.com", "fastmail.fm", "fastmail.net", "messagingengine.com"],
+    },
+    ProviderPreset {
+        id: "icloud",
+        name: "iCloud",
+        server_url: Some("https://caldav.icloud.com"),
+        server_type: "generic",
+        auth_method: AuthMethod::Basic,
+        email_username: true,
+        password_hint: Some(
+            "iCloud needs an app-specific password, created under Sign-In and Security on your Apple Account page",
+        ),
+        password_help_url: Some("https://account.apple.com/account/manage"),
+        domains: &["icloud.com", "me.com", "mac.com"],
+    },
+    ProviderPreset {
+        id: "mailbox-org",
+        name: "mailbox.org",
+        server_url: Some("https://dav.mailbox.org"),
+        server_type: "generic",
+        auth_method: AuthMethod::Basic,
+        email_username: true,
+        password_hint: Some(
+            "With two-factor authentication, create an app password under Settings → Security",
+        ),
+        password_help_url: None,
+        domains: &["mailbox.org"],
+    },
+    ProviderPreset {
+        id: "radicale",
+        name: "Radicale",
+        server_url: None,
+        server_type: "radicale",
+        auth_method: AuthMethod::Basic,
+        email_username: false,
+        password_hint: None,
+        password_help_url: None,
+        domains: &[],
+    },
+    ProviderPreset {
+        id: "baikal",
+        name: "Baïkal",
+        server_url: None,
+        server_type: "baikal",
+        // Baïkal's default, though it can be switched to Basic
+        auth_method: AuthMethod::Digest,
+        email_username: false,
+        password_hint: None,
+        password_help_url: None,
+        domains: &[],
+    },
+];
+
+/// what `detect_provider` made of the input
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedProvider {
+    pub provider: ProviderPreset,
+    /// the server to connect to: the preset's, or the one typed in
+    pub server_url: Option<String>,
+    /// the email address that was typed in, for providers that use it as the username
+    pub username: Option<String>,
+}
+
+fn preset(id: &str) -> ProviderPreset {
+    PRESETS
+        .iter()
+        .find(|preset| preset.id == id)
+        .cloned()
+        .expect("unknown provider preset")
+}
+
+// the preset whose domain is `host` or one of its parents
+fn by_domain(host: &str) -> Option<ProviderPreset> {
+    let host = host.to_ascii_lowercase();
+    PRESETS
+        .iter()
+        .find(|preset| {
+            preset
+                .domains
+                .iter()
+                .any(|domain| host == *domain || host.ends_with(&format!(".{domain}")))
+        })
+        .cloned()
+}
+
+// ask a self-hosted server what it is; any failure just means "don't know"
+async fn probe(base: &str) -> Option<&'static str> {
+    let client = CalDavClient::new(&AccountConfig {
+        server_url: base.to_string(),
+        username: String::new(),
+        password: String::new(),
+        server_type: None,
+        tls: TlsSettings::default(),
+        bearer_token: None,
+        auth_method: None,
+    })
+    .ok()?;
+
+    let status = client
+        .request_anonymous("GET", &format!("{base}/status.php"))
+        .await
+        .ok()?;
+    if status.status == 200 && status.body.contains("\"productname\"") {
+        return Some("nextcloud");
+    }
+    let baikal = client
+        .request_anonymous("GET", &format!("{base}/dav.php/"))
+        .await
+        .ok()?;
+    if baikal.status != 404 && baikal.body.to_ascii_lowercase().contains("baikal") {
+        return Some("baikal");
+    }
+    let root = client.request_anonymous("GET", base).await.ok()?;
+    let server = root.headers.get("server").cloned().unwrap_or_default();
+    if server.contains("Radicale") || root.body.contains("Radicale") {
+        return Some("radicale");
+    }
+    None
+}
+
+/// the providers the account dialog offers
+#[tauri::command]
+pub fn get_provider_presets() -> Vec<ProviderPreset> {
+    PRESETS.to_vec()
+}
+
+/// the provider behind an email address or a server url: hosted providers by their domain,
+/// self-hosted servers by their url layout or by asking them. None when nothing matched,
+/// which leaves the generic setup
+#[tauri::command]
+pub async fn detect_provider(input: String) -> Result<Option<DetectedProvider>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    // an email address
+    if !input.contains("://") {
+        if let Some((_, domain)) = input.rsplit_once('@') {
+            return Ok(by_domain(domain).map(|provider| DetectedProvider {
+                server_url: provider.server_url.map(str::to_string),
+                username: provider.email_username.then(|| input.to_string()),
+                provider,
+            }));
+        }
+    }
+
+    let with_scheme = if input.contains("://") {
+        input.to_string()
+    } else {
+        format!("https://{input}")
+    };
+    let url = Url::parse(&with_scheme).map_err(|e| format!("Invalid server URL: {e}"))?;
+    let Some(host) = url.host_str() else {
+        return Err(format!("Invalid server URL: {input}"));
+    };
+    if let Some(provider) = by_domain(host) {
+        return Ok(Some(DetectedProvider {
+            server_url: provider.server_url.map(str::to_string),
+            username: None,
+            provider,
+        }));
+    }
+
+    // a pasted dav url gives the server away, and the part before it is the address
+    let path = url.path();
+    let known_path = [("/remote.php/", "nextcloud"), ("/dav.php", "baikal")]
+        .into_iter()
+        .find_map(|(marker, id)| path.find(marker).map(|at| (at, id)));
+    let origin = url.origin().ascii_serialization();
+    let (base, id) = match known_path {
+        Some((at, id)) => (format!("{origin}{}", &path[..at]), Some(id)),
+        None => {
+            let base = format!("{origin}{}", path.trim_end_matches('/'));
+            let id = probe(&base).await;
+            (base, id)
+        }
+    };
+    log::info!("Detected provider {id:?} for {base}");
+    Ok(id.map(|id| DetectedProvider {
+        provider: preset(id),
+        server_url: Some(base),
+        username: None,
+    }))
+}
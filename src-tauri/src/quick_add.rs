@@ -0,0 +1,1173 @@
+use chrono::{
+    Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat,
+    TimeZone, Utc, Weekday,
+};
+use serde::Serialize;
+use sqlx::FromRow;
+
+use crate::locale::{DateOrder, LocaleSettings};
+use crate::recurrence::{Frequency, Recurrence};
+use crate::{collation, db, lock};
+
+// time used for "tonight" when no time is given
+const EVENING_HOUR: u32 = 20;
+// occurrences of a recurring task returned for confirmation
+const PREVIEW_OCCURRENCES: usize = 3;
+const WORKDAYS: [Weekday; 5] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Unit {
+    fn frequency(self) -> Frequency {
+        match self {
+            Self::Day => Frequency::Daily,
+            Self::Week => Frequency::Weekly,
+            Self::Month => Frequency::Monthly,
+            Self::Year => Frequency::Yearly,
+        }
+    }
+}
+
+/// words the parser understands, per language
+struct Language {
+    today: &'static [&'static str],
+    tonight: &'static [&'static str],
+    tomorrow: &'static [&'static str],
+    day_after_tomorrow: &'static [&'static str],
+    next: &'static [&'static str],
+    week: &'static [&'static str],
+    month: &'static [&'static str],
+    within: &'static [&'static str],
+    one: &'static [&'static str],
+    units: &'static [(&'static str, Unit)],
+    /// words before a date that are dropped together with it ("on friday", "by monday")
+    on: &'static [&'static str],
+    /// words before a time; after these a bare hour counts as a time ("at 5")
+    at: &'static [&'static str],
+    noon: &'static [&'static str],
+    oclock: &'static [&'static str],
+    weekdays: [&'static [&'static str]; 7],
+    months: [&'static [&'static str]; 12],
+    priorities: &'static [(&'static str, &'static str)],
+    // recurrence: "every 2 weeks", "every mon and thu", "monthly on the 1st"
+    every: &'static [&'static str],
+    other: &'static [&'static str],
+    frequencies: &'static [(&'static str, Frequency)],
+    workday: &'static [&'static str],
+    /// "werktags" on its own
+    workdays: &'static [&'static str],
+    weekend: &'static [&'static str],
+    and: &'static [&'static str],
+    the: &'static [&'static str],
+    last_day: &'static [&'static str],
+}
+
+const ENGLISH: Language = Language {
+    today: &["today"],
+    tonight: &["tonight"],
+    tomorrow: &["tomorrow", "tmrw", "tmr"],
+    day_after_tomorrow: &["day after tomorrow"],
+    next: &["next"],
+    week: &["week"],
+    month: &["month"],
+    within: &["in"],
+    one: &["a", "an", "one"],
+    units: &[
+        ("day", Unit::Day),
+        ("days", Unit::Day),
+        ("week", Unit::Week),
+        ("weeks", Unit::Week),
+        ("month", Unit::Month),
+        ("months", Unit::Month),
+        ("year", Unit::Year),
+        ("years", Unit::Year),
+    ],
+    on: &["on", "by", "due"],
+    at: &["at", "@"],
+    noon: &["noon", "midday"],
+    oclock: &["o'clock", "oclock"],
+    weekdays: [
+        &["monday", "mon"],
+        &["tuesday", "tue", "tues"],
+        &["wednesday", "wed"],
+        &["thursday", "thu", "thurs"],
+        &["friday", "fri"],
+        &["saturday", "sat"],
+        &["sunday"],
+    ],
+    months: [
+        &["january", "jan"],
+        &["february", "feb"],
+        &["march", "mar"],
+        &["april", "apr"],
+        &["may"],
+        &["june", "jun"],
+        &["july", "jul"],
+        &["august", "aug"],
+        &["september", "sep", "sept"],
+        &["october", "oct"],
+        &["november", "nov"],
+        &["december", "dec"],
+    ],
+    priorities: &[
+        ("high", "high"),
+        ("h", "high"),
+        ("medium", "medium"),
+        ("med", "medium"),
+        ("m", "medium"),
+        ("low", "low"),
+        ("l", "low"),
+        ("none", "none"),
+    ],
+    every: &["every", "each"],
+    other: &["other"],
+    frequencies: &[
+        ("daily", Frequency::Daily),
+        ("weekly", Frequency::Weekly),
+        ("monthly", Frequency::Monthly),
+        ("yearly", Frequency::Yearly),
+        ("annually", Frequency::Yearly),
+    ],
+    workday: &["weekday", "workday"],
+    workdays: &[],
+    weekend: &["weekend"],
+    and: &["and", "&"],
+    the: &["the"],
+    last_day: &["last day"],
+};
+
+const GERMAN: Language = Language {
+    today: &["heute"],
+    tonight: &["heute abend", "heute nacht"],
+    tomorrow: &["morgen"],
+    day_after_tomorrow: &["übermorgen"],
+    next: &[
+        "nächste",
+        "nächsten",
+        "nächster",
+        "nächstes",
+        "kommende",
+        "kommenden",
+    ],
+    week: &["woche"],
+    month: &["monat"],
+    within: &["in"],
+    one: &["einem", "einer", "ein", "eine"],
+    units: &[
+        ("tag", Unit::Day),
+        ("tagen", Unit::Day),
+        ("tage", Unit::Day),
+        ("woche", Unit::Week),
+        ("wochen", Unit::Week),
+        ("monat", Unit::Month),
+        ("monaten", Unit::Month),
+        ("monate", Unit::Month),
+        ("jahr", Unit::Year),
+        ("jahren", Unit::Year),
+        ("jahre", Unit::Year),
+    ],
+    on: &["am", "bis", "fällig"],
+    at: &["um"],
+    noon: &["mittag", "mittags"],
+    oclock: &["uhr"],
+    weekdays: [
+        &["montag"],
+        &["dienstag"],
+        &["mittwoch"],
+        &["donnerstag"],
+        &["freitag"],
+        &["samstag", "sonnabend"],
+        &["sonntag"],
+    ],
+    months: [
+        &["januar", "jan", "jänner"],
+        &["februar", "feb"],
+        &["märz", "mär"],
+        &["april", "apr"],
+        &["mai"],
+        &["juni", "jun"],
+        &["juli", "jul"],
+        &["august", "aug"],
+        &["september", "sep", "sept"],
+        &["oktober", "okt"],
+        &["november", "nov"],
+        &["dezember", "dez"],
+    ],
+    priorities: &[
+        ("hoch", "high"),
+        ("mittel", "medium"),
+        ("niedrig", "low"),
+        ("keine", "none"),
+    ],
+    every: &["jeden", "jede", "jedes", "alle"],
+    other: &["zweiten", "zweite"],
+    frequencies: &[
+        ("täglich", Frequency::Daily),
+        ("wöchentlich", Frequency::Weekly),
+        ("monatlich", Frequency::Monthly),
+        ("jährlich", Frequency::Yearly),
+    ],
+    workday: &["werktag"],
+    workdays: &["werktags"],
+    weekend: &["wochenende"],
+    and: &["und"],
+    the: &["den"],
+    last_day: &["letzten tag", "letzten"],
+};
+
+/// language and regional conventions taken from a locale like "en-US" or "de_AT"
+struct Locale {
+    languages: Vec<&'static Language>,
+    /// "5/1" is may 1st rather than january 5th
+    month_first: bool,
+    /// a bare "at 5" means 5pm
+    twelve_hour: bool,
+    week_start: Weekday,
+}
+
+impl Locale {
+    fn from_tag(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        let settings = LocaleSettings::from_tag(tag);
+
+        // english keywords always work, the locale's own language is tried first
+        let languages = match language.as_str() {
+            "de" => vec![&GERMAN, &ENGLISH],
+            _ => vec![&ENGLISH],
+        };
+        Self {
+            languages,
+            month_first: settings.date_order == DateOrder::MonthDayYear,
+            twelve_hour: language == "en",
+            week_start: settings.week_start(),
+        }
+    }
+}
+
+/// a calendar the `@list` token can refer to
+#[derive(Debug, Clone, FromRow)]
+pub struct ListTarget {
+    pub id: String,
+    pub account_id: String,
+    pub display_name: String,
+}
+
+/// a task as typed into quick add, before it's created
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickAddDraft {
+    pub title: String,
+    pub due_date: Option<String>,
+    pub due_date_all_day: bool,
+    /// tag names without the #
+    pub tags: Vec<String>,
+    pub priority: Option<String>,
+    /// list name as typed after the @
+    pub list: Option<String>,
+    /// the calendar the list name matched, if any
+    pub calendar_id: Option<String>,
+    pub account_id: Option<String>,
+    /// RRULE value for phrases like "every 2 weeks"
+    pub rrule: Option<String>,
+    /// the next few occurrences of the rule, so the user can check it was understood
+    pub occurrences: Vec<String>,
+    /// the first link in the text, taken out of the title
+    pub url: Option<String>,
+}
+
+fn normalize(word: &str) -> String {
+    word.trim_end_matches([',', ';']).to_lowercase()
+}
+
+fn number(word: &str) -> Option<u32> {
+    word.parse()
+        .ok()
+        .filter(|_| word.chars().all(|c| c.is_ascii_digit()))
+}
+
+// "1st", "2nd", "3." -> day of month
+fn day_of_month(word: &str) -> Option<u32> {
+    let digits = word
+        .trim_end_matches('.')
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    number(digits).filter(|day| (1..=31).contains(day))
+}
+
+pub fn to_iso(date: NaiveDateTime) -> Option<String> {
+    Local.from_local_datetime(&date).earliest().map(|date| {
+        date.with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Millis, true)
+    })
+}
+
+fn add_units(date: NaiveDate, count: u32, unit: Unit) -> Option<NaiveDate> {
+    match unit {
+        Unit::Day => date.checked_add_signed(Duration::days(count.into())),
+        Unit::Week => date.checked_add_signed(Duration::weeks(count.into())),
+        Unit::Month => date.checked_add_months(Months::new(count)),
+        Unit::Year => date.checked_add_months(Months::new(count * 12)),
+    }
+}
+
+// two digit years are in this century
+fn full_year(year: u32) -> i32 {
+    if year < 100 {
+        2000 + year as i32
+    } else {
+        year as i32
+    }
+}
+
+/// a date or time phrase found in the text
+#[derive(Debug, Clone, Copy, Default)]
+struct Match {
+    words: usize,
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+}
+
+struct Parser<'a> {
+    words: Vec<&'a str>,
+    lower: Vec<String>,
+    used: Vec<bool>,
+    locale: Locale,
+    now: NaiveDateTime,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str, locale: Locale, now: NaiveDateTime) -> Self {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let lower = words.iter().map(|word| normalize(word)).collect();
+        let used = vec![false; words.len()];
+        Self {
+            words,
+            lower,
+            used,
+            locale,
+            now,
+        }
+    }
+
+    fn today(&self) -> NaiveDate {
+        self.now.date()
+    }
+
+    fn word(&self, at: usize) -> Option<&str> {
+        self.lower.get(at).map(String::as_str)
+    }
+
+    /// number of words of the longest phrase that starts at `at`
+    fn phrase(&self, at: usize, phrases: &[&str]) -> Option<usize> {
+        phrases
+            .iter()
+            .filter(|phrase| {
+                phrase
+                    .split(' ')
+                    .enumerate()
+                    .all(|(offset, part)| self.word(at + offset) == Some(part))
+            })
+            .map(|phrase| phrase.split(' ').count())
+            .max()
+    }
+
+    fn weekday(&self, at: usize, language: &Language) -> Option<Weekday> {
+        let word = self.word(at)?;
+        let index = language
+            .weekdays
+            .iter()
+            .position(|names| names.contains(&word))?;
+        Weekday::try_from(index as u8).ok()
+    }
+
+    fn month(&self, at: usize, language: &Language) -> Option<u32> {
+        let word = self.word(at)?.trim_end_matches('.');
+        let index = language
+            .months
+            .iter()
+            .position(|names| names.contains(&word))?;
+        Some(index as u32 + 1)
+    }
+
+    // the next date with this weekday, never today
+    fn upcoming(&self, weekday: Weekday) -> NaiveDate {
+        let today = self.today();
+        let ahead =
+            (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+        today + Duration::days(if ahead == 0 { 7 } else { ahead.into() })
+    }
+
+    fn start_of_next_week(&self) -> NaiveDate {
+        let today = self.today();
+        let into_week = (7 + today.weekday().num_days_from_monday()
+            - self.locale.week_start.num_days_from_monday())
+            % 7;
+        today + Duration::days(7 - i64::from(into_week))
+    }
+
+    // a date without a year is the next time that day comes around
+    fn with_year(&self, year: Option<i32>, month: u32, day: u32) -> Option<NaiveDate> {
+        match year {
+            Some(year) => NaiveDate::from_ymd_opt(year, month, day),
+            None => {
+                let today = self.today();
+                let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+                if date < today {
+                    NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+                } else {
+                    Some(date)
+                }
+            }
+        }
+    }
+
+    // short forms like "5/1" only count after "on", so "1/2 cup" stays in the title
+    fn numeric_date(&self, word: &str, after_on: bool) -> Option<NaiveDate> {
+        // 2025-05-01
+        if let [year, month, day] = word.split('-').collect::<Vec<_>>()[..] {
+            if year.len() == 4 {
+                return NaiveDate::from_ymd_opt(number(year)? as i32, number(month)?, number(day)?);
+            }
+        }
+        // 1.5. or 1.5.2025 is always day first ("1.5" without the dot is a version number)
+        if word.contains('.') {
+            let parts: Vec<&str> = word.trim_end_matches('.').split('.').collect();
+            return match parts[..] {
+                [day, month] if word.ends_with('.') => {
+                    self.with_year(None, number(month)?, number(day)?)
+                }
+                [day, month, year] => {
+                    self.with_year(Some(full_year(number(year)?)), number(month)?, number(day)?)
+                }
+                _ => None,
+            };
+        }
+        // 5/1 or 5/1/2025 in the locale's order
+        let parts: Vec<&str> = word.split('/').collect();
+        let (first, second, year) = match parts[..] {
+            [first, second] if after_on => (number(first)?, number(second)?, None),
+            [first, second, year] => (
+                number(first)?,
+                number(second)?,
+                Some(full_year(number(year)?)),
+            ),
+            _ => return None,
+        };
+        let (month, day) = if self.locale.month_first {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        self.with_year(year, month, day)
+    }
+
+    fn date_at(&self, at: usize, after_on: bool) -> Option<Match> {
+        let today = self.today();
+        let on = |words, date| {
+            Some(Match {
+                words,
+                date: Some(date),
+                time: None,
+            })
+        };
+
+        for language in &self.locale.languages {
+            if let Some(words) = self.phrase(at, language.day_after_tomorrow) {
+                return on(words, today + Duration::days(2));
+            }
+            if let Some(words) = self.phrase(at, language.tonight) {
+                return Some(Match {
+                    words,
+                    date: Some(today),
+                    time: NaiveTime::from_hms_opt(EVENING_HOUR, 0, 0),
+                });
+            }
+            if let Some(words) = self.phrase(at, language.tomorrow) {
+                return on(words, today + Duration::days(1));
+            }
+            if let Some(words) = self.phrase(at, language.today) {
+                return on(words, today);
+            }
+            if self.phrase(at, language.next).is_some() {
+                if self.phrase(at + 1, language.week).is_some() {
+                    return on(2, self.start_of_next_week());
+                }
+                if self.phrase(at + 1, language.month).is_some() {
+                    let next_month = today.checked_add_months(Months::new(1))?;
+                    return on(2, next_month.with_day(1)?);
+                }
+                if let Some(weekday) = self.weekday(at + 1, language) {
+                    let week = self.start_of_next_week();
+                    let offset = (7 + weekday.num_days_from_monday()
+                        - week.weekday().num_days_from_monday())
+                        % 7;
+                    return on(2, week + Duration::days(offset.into()));
+                }
+            }
+            if let Some(weekday) = self.weekday(at, language) {
+                return on(1, self.upcoming(weekday));
+            }
+            if self.phrase(at, language.within).is_some() {
+                let count = self.word(at + 1).and_then(|word| {
+                    number(word).or_else(|| language.one.contains(&word).then_some(1))
+                });
+                let unit = self.word(at + 2).and_then(|word| {
+                    language
+                        .units
+                        .iter()
+                        .find(|(name, _)| *name == word)
+                        .map(|(_, unit)| *unit)
+                });
+                if let (Some(count), Some(unit)) = (count, unit) {
+                    return on(3, add_units(today, count, unit)?);
+                }
+            }
+            // "may 1st", "may 1 2026"
+            if let Some(month) = self.month(at, language) {
+                if let Some(day) = self.word(at + 1).and_then(day_of_month) {
+                    let year = self
+                        .word(at + 2)
+                        .and_then(number)
+                        .filter(|year| *year >= 1000);
+                    return on(
+                        if year.is_some() { 3 } else { 2 },
+                        self.with_year(year.map(|year| year as i32), month, day)?,
+                    );
+                }
+            }
+            // "1 may", "1. mai"
+            if let Some(day) = self.word(at).and_then(day_of_month) {
+                if let Some(month) = self.month(at + 1, language) {
+                    return on(2, self.with_year(None, month, day)?);
+                }
+            }
+        }
+
+        let word = self.word(at)?;
+        if word.chars().next()?.is_ascii_digit() {
+            return on(1, self.numeric_date(word, after_on)?);
+        }
+        None
+    }
+
+    // "5pm", "5:30 pm", "17:00", "17 uhr"; a bare hour only counts after "at"
+    fn time_at(&self, at: usize, after_at: bool) -> Option<Match> {
+        let time = |words, time| {
+            Some(Match {
+                words,
+                date: None,
+                time: Some(time),
+            })
+        };
+        for language in &self.locale.languages {
+            if let Some(words) = self.phrase(at, language.noon) {
+                return time(words, NaiveTime::from_hms_opt(12, 0, 0)?);
+            }
+        }
+
+        let word = self.word(at)?;
+        let (clock, suffix, mut words) = match word.find(['a', 'p']) {
+            Some(index) => (&word[..index], Some(&word[index..]), 1),
+            None => match self.word(at + 1) {
+                Some(next @ ("am" | "pm" | "a.m." | "p.m.")) => (word, Some(next), 2),
+                _ => (word, None, 1),
+            },
+        };
+        let (hour, minute) = match clock.split_once(':') {
+            Some((hour, minute)) => (number(hour)?, number(minute)?),
+            None => (number(clock)?, 0),
+        };
+
+        let hour = match suffix {
+            Some("am" | "a" | "a.m.") => match hour {
+                12 => 0,
+                1..=11 => hour,
+                _ => return None,
+            },
+            Some("pm" | "p" | "p.m.") => match hour {
+                12 => 12,
+                1..=11 => hour + 12,
+                _ => return None,
+            },
+            Some(_) => return None,
+            None => {
+                let oclock = self
+                    .locale
+                    .languages
+                    .iter()
+                    .find_map(|language| self.phrase(at + 1, language.oclock));
+                if let Some(extra) = oclock {
+                    words += extra;
+                } else if !clock.contains(':') {
+                    if !after_at {
+                        return None;
+                    }
+                    // nobody means 3am when they write "at 3"
+                    if self.locale.twelve_hour && (1..=7).contains(&hour) {
+                        return time(words, NaiveTime::from_hms_opt(hour + 12, minute, 0)?);
+                    }
+                }
+                hour
+            }
+        };
+        time(words, NaiveTime::from_hms_opt(hour, minute, 0)?)
+    }
+
+    fn unit(&self, at: usize, language: &Language) -> Option<Unit> {
+        let word = self.word(at)?;
+        language
+            .units
+            .iter()
+            .find(|(name, _)| *name == word)
+            .map(|(_, unit)| *unit)
+    }
+
+    // "every mon, wed and fri"
+    fn weekday_list(&self, mut at: usize, language: &Language) -> (usize, Vec<Weekday>) {
+        let start = at;
+        let mut days = Vec::new();
+        while let Some(day) = self.weekday(at, language) {
+            if !days.contains(&day) {
+                days.push(day);
+            }
+            at += 1;
+            if self.weekday(at, language).is_none() {
+                match self.phrase(at, language.and) {
+                    Some(words) if self.weekday(at + words, language).is_some() => at += words,
+                    _ => break,
+                }
+            }
+        }
+        (at - start, days)
+    }
+
+    // "on", "by" and the like in any of the locale's languages
+    fn is_on(&self, at: usize) -> bool {
+        self.locale
+            .languages
+            .iter()
+            .any(|language| self.phrase(at, language.on).is_some())
+    }
+
+    // "on the 1st", "on the last day" after a monthly rule
+    fn month_day(&self, mut at: usize, language: &Language) -> Option<(usize, i32)> {
+        let start = at;
+        at += usize::from(self.is_on(at));
+        at += self.phrase(at, language.the).unwrap_or(0);
+        if let Some(words) = self.phrase(at, language.last_day) {
+            return Some((at + words - start, -1));
+        }
+        let day = self.word(at).and_then(day_of_month)?;
+        Some((at + 1 - start, day as i32))
+    }
+
+    fn recurrence_at(&self, at: usize) -> Option<(usize, Recurrence)> {
+        for language in &self.locale.languages {
+            let (mut end, mut rule) = if let Some((_, frequency)) = language
+                .frequencies
+                .iter()
+                .find(|(name, _)| self.word(at) == Some(name))
+            {
+                (at + 1, Recurrence::new(*frequency, 1))
+            } else if let Some(words) = self.phrase(at, language.workdays) {
+                let mut rule = Recurrence::new(Frequency::Weekly, 1);
+                rule.by_day = WORKDAYS.to_vec();
+                (at + words, rule)
+            } else if let Some(words) = self.phrase(at, language.every) {
+                let mut end = at + words;
+                let mut interval = 1;
+                if let Some(count) = self.word(end).and_then(number) {
+                    interval = count;
+                    end += 1;
+                } else if let Some(words) = self.phrase(end, language.other) {
+                    interval = 2;
+                    end += words;
+                }
+
+                let mut rule;
+                if let Some(unit) = self.unit(end, language) {
+                    rule = Recurrence::new(unit.frequency(), interval);
+                    end += 1;
+                } else if let Some(words) = self.phrase(end, language.workday) {
+                    rule = Recurrence::new(Frequency::Weekly, interval);
+                    rule.by_day = WORKDAYS.to_vec();
+                    end += words;
+                } else if let Some(words) = self.phrase(end, language.weekend) {
+                    rule = Recurrence::new(Frequency::Weekly, interval);
+                    rule.by_day = vec![Weekday::Sat, Weekday::Sun];
+                    end += words;
+                } else {
+                    let (words, days) = self.weekday_list(end, language);
+                    if days.is_empty() {
+                        continue;
+                    }
+                    rule = Recurrence::new(Frequency::Weekly, interval);
+                    rule.by_day = days;
+                    end += words;
+                }
+                (end, rule)
+            } else {
+                continue;
+            };
+
+            // "every other week on fri"
+            if rule.frequency == Frequency::Weekly && rule.by_day.is_empty() {
+                let lead = usize::from(self.is_on(end));
+                let (words, days) = self.weekday_list(end + lead, language);
+                if !days.is_empty() {
+                    rule.by_day = days;
+                    end += lead + words;
+                }
+            }
+            if rule.frequency == Frequency::Monthly {
+                if let Some((words, day)) = self.month_day(end, language) {
+                    rule.by_month_day = Some(day);
+                    end += words;
+                }
+            }
+            return Some((end - at, rule));
+        }
+        None
+    }
+
+    fn consume(&mut self, at: usize, words: usize) {
+        for used in &mut self.used[at..at + words] {
+            *used = true;
+        }
+    }
+
+    fn list_at(&self, at: usize, lists: &[ListTarget]) -> (usize, String, Option<ListTarget>) {
+        let first = self.words[at].trim_start_matches('@');
+        // longest list name that the following words spell out
+        for count in (1..=(self.words.len() - at)).rev() {
+            let name = std::iter::once(first)
+                .chain(self.words[at + 1..at + count].iter().copied())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if let Some(list) = lists
+                .iter()
+                .find(|list| collation::fold(&list.display_name) == collation::fold(&name))
+            {
+                return (count, list.display_name.clone(), Some(list.clone()));
+            }
+        }
+        (1, first.to_string(), None)
+    }
+
+    fn priority(&self, word: &str) -> Option<&'static str> {
+        match word {
+            "!!!" | "!1" => return Some("high"),
+            "!!" | "!2" => return Some("medium"),
+            "!3" => return Some("low"),
+            _ => {}
+        }
+        let name = word.strip_prefix('!')?;
+        self.locale.languages.iter().find_map(|language| {
+            language
+                .priorities
+                .iter()
+                .find(|(word, _)| *word == name)
+                .map(|(_, priority)| *priority)
+        })
+    }
+
+    fn parse(mut self, lists: &[ListTarget]) -> QuickAddDraft {
+        let mut draft = QuickAddDraft::default();
+        let mut date = None;
+        let mut time = None;
+        let mut recurrence: Option<Recurrence> = None;
+
+        let mut at = 0;
+        while at < self.words.len() {
+            let word = self.lower[at].clone();
+
+            if draft.url.is_none() && (word.starts_with("https://") || word.starts_with("http://"))
+            {
+                draft.url = Some(self.words[at].trim_end_matches([',', ';']).to_string());
+                self.consume(at, 1);
+                at += 1;
+                continue;
+            }
+            if let Some(tag) = self.words[at].strip_prefix('#') {
+                let tag = tag.trim_end_matches([',', ';', '.']);
+                if !tag.is_empty() {
+                    if !draft
+                        .tags
+                        .iter()
+                        .any(|known| collation::fold(known) == collation::fold(tag))
+                    {
+                        draft.tags.push(tag.to_string());
+                    }
+                    self.consume(at, 1);
+                    at += 1;
+                    continue;
+                }
+            }
+            if let Some(priority) = draft
+                .priority
+                .is_none()
+                .then(|| self.priority(&word))
+                .flatten()
+            {
+                draft.priority = Some(priority.to_string());
+                self.consume(at, 1);
+                at += 1;
+                continue;
+            }
+            if word.len() > 1 && word.starts_with('@') && draft.list.is_none() {
+                let (words, name, list) = self.list_at(at, lists);
+                draft.list = Some(name);
+                if let Some(list) = list {
+                    draft.calendar_id = Some(list.id);
+                    draft.account_id = Some(list.account_id);
+                }
+                self.consume(at, words);
+                at += words;
+                continue;
+            }
+
+            if recurrence.is_none() {
+                if let Some((words, rule)) = self.recurrence_at(at) {
+                    recurrence = Some(rule);
+                    self.consume(at, words);
+                    at += words;
+                    continue;
+                }
+            }
+
+            // "on friday", "at 5pm": the leading word goes with the phrase
+            let is_on = self.is_on(at);
+            let is_at = self
+                .locale
+                .languages
+                .iter()
+                .any(|language| language.at.contains(&word.as_str()));
+            let lead = usize::from(is_on || is_at);
+
+            let found = date
+                .is_none()
+                .then(|| self.date_at(at + lead, is_on))
+                .flatten()
+                .or_else(|| {
+                    time.is_none()
+                        .then(|| self.time_at(at + lead, is_at))
+                        .flatten()
+                });
+            match found {
+                Some(found) => {
+                    date = date.or(found.date);
+                    time = time.or(found.time);
+                    self.consume(at, lead + found.words);
+                    at += lead + found.words;
+                }
+                None => at += 1,
+            }
+        }
+
+        if let Some(rule) = &recurrence {
+            let first = date.unwrap_or_else(|| self.today());
+            let occurrences: Vec<NaiveDateTime> = rule
+                .occurrences(first, PREVIEW_OCCURRENCES + 1)
+                .into_iter()
+                .map(|day| day.and_time(time.unwrap_or(NaiveTime::MIN)))
+                // without a date, an occurrence earlier today is already over
+                .filter(|occurrence| date.is_some() || time.is_none() || *occurrence > self.now)
+                .take(PREVIEW_OCCURRENCES)
+                .collect();
+            // "water plants every monday" is due on the first monday
+            if date.is_none() {
+                date = occurrences.first().map(NaiveDateTime::date);
+            }
+            draft.rrule = Some(rule.to_rrule());
+            draft.occurrences = occurrences.into_iter().filter_map(to_iso).collect();
+        }
+
+        let (due, all_day) = match (date, time) {
+            (Some(date), Some(time)) => (Some(date.and_time(time)), false),
+            (Some(date), None) => (Some(date.and_time(NaiveTime::MIN)), true),
+            // a time on its own is today, or tomorrow once it has passed
+            (None, Some(time)) => {
+                let today = self.today().and_time(time);
+                let due = if today > self.now {
+                    today
+                } else {
+                    today + Duration::days(1)
+                };
+                (Some(due), false)
+            }
+            (None, None) => (None, false),
+        };
+        draft.due_date = due.and_then(to_iso);
+        draft.due_date_all_day = all_day;
+
+        draft.title = self
+            .words
+            .iter()
+            .zip(&self.used)
+            .filter(|(_, used)| !**used)
+            .map(|(word, _)| *word)
+            .collect::<Vec<_>>()
+            .join(" ");
+        draft
+    }
+}
+
+/// parse quick-add text against a fixed "now", for callers that already have the lists
+pub fn parse(text: &str, locale: &str, now: NaiveDateTime, lists: &[ListTarget]) -> QuickAddDraft {
+    Parser::new(text, Locale::from_tag(locale), now).parse(lists)
+}
+
+/// turn quick-add text like "call mom tomorrow 5pm #family !high @Home" or
+/// "water plants every monday" into a task draft; the locale defaults to the system one
+#[tauri::command]
+pub async fn parse_quick_add(
+    app_handle: tauri::AppHandle,
+    text: String,
+    locale: Option<String>,
+) -> Result<QuickAddDraft, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let lists: Vec<ListTarget> =
+        sqlx::query_as("SELECT id, account_id, display_name FROM calendars")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let locale = locale
+        .or_else(tauri_plugin_os::locale)
+        .unwrap_or_else(|| "en-US".to_string());
+    Ok(parse(&text, &locale, Local::now().naive_local(), &lists))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a wednesday morning
+    fn now() -> NaiveDateTime {
+        at(2025, 1, 15, 10, 0)
+    }
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .expect("valid date")
+    }
+
+    fn iso(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Option<String> {
+        to_iso(at(year, month, day, hour, minute))
+    }
+
+    fn lists() -> Vec<ListTarget> {
+        ["Home", "Groceries List"]
+            .iter()
+            .enumerate()
+            .map(|(index, name)| ListTarget {
+                id: format!("calendar-{index}"),
+                account_id: "account".to_string(),
+                display_name: name.to_string(),
+            })
+            .collect()
+    }
+
+    fn english(text: &str) -> QuickAddDraft {
+        parse(text, "en-US", now(), &lists())
+    }
+
+    fn german(text: &str) -> QuickAddDraft {
+        parse(text, "de-DE", now(), &lists())
+    }
+
+    #[test]
+    fn everything_at_once() {
+        let draft = english("call mom tomorrow 5pm #family !high @home");
+        assert_eq!(draft.title, "call mom");
+        assert_eq!(draft.due_date, iso(2025, 1, 16, 17, 0));
+        assert!(!draft.due_date_all_day);
+        assert_eq!(draft.tags, vec!["family".to_string()]);
+        assert_eq!(draft.priority.as_deref(), Some("high"));
+        assert_eq!(draft.list.as_deref(), Some("Home"));
+        assert_eq!(draft.calendar_id.as_deref(), Some("calendar-0"));
+        assert_eq!(draft.account_id.as_deref(), Some("account"));
+        assert!(draft.rrule.is_none());
+    }
+
+    #[test]
+    fn plain_text_is_left_alone() {
+        for text in [
+            "add 1/2 cup sugar",
+            "version 1.5 release",
+            "meet 13pm",
+            "call 3 people",
+        ] {
+            let draft = english(text);
+            assert_eq!(draft.title, text);
+            assert!(draft.due_date.is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn weekdays_are_never_today() {
+        let draft = english("pay rent on wednesday");
+        assert_eq!(draft.title, "pay rent");
+        assert_eq!(draft.due_date, iso(2025, 1, 22, 0, 0));
+        assert!(draft.due_date_all_day);
+        assert_eq!(english("pay rent thu").due_date, iso(2025, 1, 16, 0, 0));
+    }
+
+    #[test]
+    fn numeric_dates_follow_the_locale() {
+        assert_eq!(english("dentist on 5/1").due_date, iso(2025, 5, 1, 0, 0));
+        // day first, and january 5th is already over this year
+        let draft = parse("dentist on 5/1", "en-GB", now(), &[]);
+        assert_eq!(draft.due_date, iso(2026, 1, 5, 0, 0));
+        let draft = german("Zahnarzt am 1.5.");
+        assert_eq!(draft.title, "Zahnarzt");
+        assert_eq!(draft.due_date, iso(2025, 5, 1, 0, 0));
+        assert_eq!(english("launch 2025-03-02").due_date, iso(2025, 3, 2, 0, 0));
+    }
+
+    #[test]
+    fn month_names_and_relative_dates() {
+        assert_eq!(english("taxes jan 10").due_date, iso(2026, 1, 10, 0, 0));
+        assert_eq!(english("taxes 10 march").due_date, iso(2025, 3, 10, 0, 0));
+        let draft = english("renew passport in 2 weeks");
+        assert_eq!(draft.title, "renew passport");
+        assert_eq!(draft.due_date, iso(2025, 1, 29, 0, 0));
+        assert_eq!(
+            english("backup in a month").due_date,
+            iso(2025, 2, 15, 0, 0)
+        );
+        assert_eq!(english("report next month").due_date, iso(2025, 2, 1, 0, 0));
+        let draft = english("movie tonight");
+        assert_eq!(draft.due_date, iso(2025, 1, 15, 20, 0));
+        assert!(!draft.due_date_all_day);
+    }
+
+    #[test]
+    fn next_week_starts_with_the_locale_week() {
+        // sunday in the US, monday in germany
+        assert_eq!(english("plan next week").due_date, iso(2025, 1, 19, 0, 0));
+        assert_eq!(
+            german("planen nächste woche").due_date,
+            iso(2025, 1, 20, 0, 0)
+        );
+        assert_eq!(english("plan next friday").due_date, iso(2025, 1, 24, 0, 0));
+    }
+
+    #[test]
+    fn times() {
+        // "at 3" is the afternoon in english, the night in german
+        let draft = english("call bob at 3");
+        assert_eq!(draft.title, "call bob");
+        assert_eq!(draft.due_date, iso(2025, 1, 15, 15, 0));
+        let draft = german("Bob anrufen um 3");
+        assert_eq!(draft.title, "Bob anrufen");
+        assert_eq!(draft.due_date, iso(2025, 1, 16, 3, 0));
+
+        assert_eq!(english("lunch at noon").due_date, iso(2025, 1, 15, 12, 0));
+        assert_eq!(english("call 5:30 pm").due_date, iso(2025, 1, 15, 17, 30));
+        assert_eq!(english("call 12am").due_date, iso(2025, 1, 16, 0, 0));
+        assert_eq!(german("Termin 17 uhr").due_date, iso(2025, 1, 15, 17, 0));
+        // a time that has passed today is tomorrow
+        assert_eq!(english("standup 9:00").due_date, iso(2025, 1, 16, 9, 0));
+    }
+
+    #[test]
+    fn recurrence() {
+        let draft = english("water plants every monday");
+        assert_eq!(draft.title, "water plants");
+        assert_eq!(draft.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO"));
+        assert_eq!(draft.due_date, iso(2025, 1, 20, 0, 0));
+        assert_eq!(
+            draft.occurrences,
+            [
+                iso(2025, 1, 20, 0, 0),
+                iso(2025, 1, 27, 0, 0),
+                iso(2025, 2, 3, 0, 0),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+        );
+
+        let draft = english("review every other week on fri");
+        assert_eq!(draft.title, "review");
+        assert_eq!(
+            draft.rrule.as_deref(),
+            Some("FREQ=WEEKLY;INTERVAL=2;BYDAY=FR")
+        );
+        assert_eq!(draft.due_date, iso(2025, 1, 17, 0, 0));
+
+        let draft = english("pay rent monthly on the last day");
+        assert_eq!(draft.title, "pay rent");
+        assert_eq!(draft.rrule.as_deref(), Some("FREQ=MONTHLY;BYMONTHDAY=-1"));
+        assert_eq!(draft.due_date, iso(2025, 1, 31, 0, 0));
+
+        let draft = english("gym every mon, wed and fri");
+        assert_eq!(draft.title, "gym");
+        assert_eq!(draft.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=MO,WE,FR"));
+        assert_eq!(draft.due_date, iso(2025, 1, 15, 0, 0));
+
+        let draft = german("Müll rausbringen jeden zweiten freitag");
+        assert_eq!(
+            draft.rrule.as_deref(),
+            Some("FREQ=WEEKLY;INTERVAL=2;BYDAY=FR")
+        );
+    }
+
+    #[test]
+    fn occurrences_earlier_today_are_over() {
+        let draft = english("standup every weekday at 9am");
+        assert_eq!(draft.title, "standup");
+        assert_eq!(
+            draft.rrule.as_deref(),
+            Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")
+        );
+        assert_eq!(draft.due_date, iso(2025, 1, 16, 9, 0));
+        assert_eq!(draft.occurrences.len(), PREVIEW_OCCURRENCES);
+        assert_eq!(draft.occurrences.last().cloned(), iso(2025, 1, 20, 9, 0));
+    }
+
+    #[test]
+    fn tags_priority_and_url() {
+        let draft = english("read https://example.com/post, later #Work #work, #");
+        assert_eq!(draft.url.as_deref(), Some("https://example.com/post"));
+        assert_eq!(draft.tags, vec!["Work".to_string()]);
+        assert_eq!(draft.title, "read later #");
+
+        // only the first priority counts
+        let draft = english("!low fix !high");
+        assert_eq!(draft.priority.as_deref(), Some("low"));
+        assert_eq!(draft.title, "fix !high");
+        assert_eq!(english("fix !!!").priority.as_deref(), Some("high"));
+        assert_eq!(german("fix !niedrig").priority.as_deref(), Some("low"));
+    }
+
+    #[test]
+    fn list_names() {
+        let draft = english("@groceries list buy eggs");
+        assert_eq!(draft.list.as_deref(), Some("Groceries List"));
+        assert_eq!(draft.calendar_id.as_deref(), Some("calendar-1"));
+        assert_eq!(draft.title, "buy eggs");
+
+        let draft = english("buy eggs @Nowhere");
+        assert_eq!(draft.list.as_deref(), Some("Nowhere"));
+        assert!(draft.calendar_id.is_none());
+        assert_eq!(draft.title, "buy eggs");
+
+        // a lone @ is "at"
+        assert_eq!(english("lunch @ 1pm").due_date, iso(2025, 1, 15, 13, 0));
+    }
+}
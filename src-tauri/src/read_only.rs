@@ -0,0 +1,81 @@
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::Emitter;
+
+use crate::i18n;
+
+/// command line flag that starts the app in read-only mode
+pub const READ_ONLY_FLAG: &str = "--read-only";
+
+// global storage for the read-only toggle
+lazy_static! {
+    static ref READ_ONLY: Mutex<bool> = Mutex::new(false);
+    // why read-only mode can't be turned off, see `force`
+    static ref FORCED: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// switch read-only mode on when the app was started with `--read-only`; the webview can't
+/// turn it off again
+pub fn init_from_args() {
+    if std::env::args().any(|arg| arg == READ_ONLY_FLAG) {
+        force(i18n::t("The app was started in read-only mode"));
+    }
+}
+
+/// switch read-only mode on for good, e.g. for a database this version can't safely write
+pub fn force(reason: &str) {
+    *READ_ONLY.lock().expect("Failed to lock READ_ONLY") = true;
+    *FORCED.lock().expect("Failed to lock FORCED") = Some(reason.to_string());
+    log::warn!("Read-only mode forced: {reason}");
+}
+
+/// whether the app is in read-only mode
+pub fn is_enabled() -> bool {
+    *READ_ONLY.lock().expect("Failed to lock READ_ONLY")
+}
+
+/// fail while read-only mode is on; every mutating command calls this first
+pub fn ensure_writable() -> Result<(), String> {
+    if let Some(reason) = FORCED.lock().expect("Failed to lock FORCED").as_ref() {
+        return Err(reason.clone());
+    }
+    if is_enabled() {
+        return Err(i18n::t("The app is in read-only mode").to_string());
+    }
+    Ok(())
+}
+
+/// turn read-only mode on or off
+#[tauri::command]
+pub async fn set_read_only(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    if !enabled {
+        if let Some(reason) = FORCED.lock().expect("Failed to lock FORCED").as_ref() {
+            return Err(reason.clone());
+        }
+    }
+    *READ_ONLY.lock().expect("Failed to lock READ_ONLY") = enabled;
+    log::info!(
+        "Read-only mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    let _ = app_handle.emit("read-only-changed", enabled);
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadOnlyStatus {
+    pub enabled: bool,
+    /// why it can't be turned off, if it can't
+    pub forced: Option<String>,
+}
+
+/// get the read-only state (for frontend to read on startup)
+#[tauri::command]
+pub async fn get_read_only() -> Result<ReadOnlyStatus, String> {
+    Ok(ReadOnlyStatus {
+        enabled: is_enabled(),
+        forced: FORCED.lock().expect("Failed to lock FORCED").clone(),
+    })
+}
@@ -0,0 +1,84 @@
+use chrono::{Duration, SecondsFormat, Utc};
+use tauri::Listener;
+
+use crate::{db, dependencies, lock, tray};
+
+// the last few completed tasks in a tray submenu, each item reopening its task: for the
+// checkbox hit by mistake, from a notification or the wrong row. a locked app keeps the titles
+// out of the tray
+
+const LIMIT: i64 = 5;
+// older completions were hardly a slip of the mouse
+const MAX_AGE: Duration = Duration::hours(24);
+// longer titles are cut so the menu stays narrow
+const MAX_TITLE_CHARS: usize = 40;
+
+// events after which the list may have changed
+const REFRESH_EVENTS: [&str; 4] = [
+    "tasks-changed",
+    "database-changed",
+    "app-locked",
+    "app-unlocked",
+];
+
+fn short_title(title: &str) -> String {
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    let cut: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+async fn refresh(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let tasks = if lock::is_locked() {
+        Vec::new()
+    } else {
+        let pool = db::pool(app_handle).await?;
+        let since = (Utc::now() - MAX_AGE).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, title FROM tasks
+             WHERE completed = 1 AND completed_at >= ? AND archived_at IS NULL
+             ORDER BY completed_at DESC LIMIT ?",
+        )
+        .bind(since)
+        .bind(LIMIT)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        rows.into_iter()
+            .map(|(id, title)| (id, short_title(&title)))
+            .collect()
+    };
+    tray::set_recently_completed(app_handle, tasks);
+    Ok(())
+}
+
+fn schedule_refresh(app_handle: &tauri::AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = refresh(&app_handle).await {
+            log::warn!("Failed to update the recently completed tasks: {e}");
+        }
+    });
+}
+
+/// keep the recently completed tasks in the tray up to date
+pub fn start_tray_updates(app_handle: tauri::AppHandle) {
+    for event in REFRESH_EVENTS {
+        let handle = app_handle.clone();
+        app_handle.listen_any(event, move |_| schedule_refresh(&handle));
+    }
+    schedule_refresh(&app_handle);
+}
+
+/// reopen a task picked in the tray
+pub fn undo(app_handle: &tauri::AppHandle, id: &str) {
+    let app_handle = app_handle.clone();
+    let id = id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match dependencies::complete_task(app_handle, id, false).await {
+            Ok(_) => log::info!("Reopened a task from the tray"),
+            Err(e) => log::warn!("Failed to reopen a task from the tray: {e}"),
+        }
+    });
+}
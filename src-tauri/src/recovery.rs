@@ -0,0 +1,119 @@
+use sqlx::{Sqlite, Transaction};
+use tauri::Emitter;
+
+use crate::{db, read_only};
+
+// tasks of a calendar that disappeared from the server, or of an account that is removed, can
+// be kept instead of deleted with it: they become local tasks in the Recovered list of a local
+// account that never syncs
+
+/// id of the local account that holds recovered tasks
+pub const LOCAL_ACCOUNT_ID: &str = "local-account";
+/// server type of the local account; sync skips it
+pub const LOCAL_SERVER_TYPE: &str = "local";
+const RECOVERED_CALENDAR_ID: &str = "local-recovered";
+
+async fn ensure_recovered_list(tx: &mut Transaction<'_, Sqlite>) -> Result<(), String> {
+    sqlx::query(
+        "INSERT OR IGNORE INTO accounts (id, name, server_url, username, password, server_type,
+                                         is_active)
+         VALUES (?, 'Local', '', '', '', ?, 1)",
+    )
+    .bind(LOCAL_ACCOUNT_ID)
+    .bind(LOCAL_SERVER_TYPE)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT OR IGNORE INTO calendars (id, account_id, display_name, url, icon,
+                                          supported_components)
+         VALUES (?, ?, 'Recovered', '', 'life-buoy', '[\"VTODO\"]')",
+    )
+    .bind(RECOVERED_CALENDAR_ID)
+    .bind(LOCAL_ACCOUNT_ID)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// moves the tasks of the calendars to the Recovered list and removes the calendars, the tasks
+// would go with them otherwise
+async fn recover(app_handle: &tauri::AppHandle, calendars: &str, id: &str) -> Result<u64, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    ensure_recovered_list(&mut tx).await?;
+    let recovered = sqlx::query(&format!(
+        "UPDATE tasks
+         SET account_id = ?, calendar_id = ?, local_only = 1, synced = 0, etag = NULL,
+             href = NULL
+         WHERE calendar_id IN ({calendars})"
+    ))
+    .bind(LOCAL_ACCOUNT_ID)
+    .bind(RECOVERED_CALENDAR_ID)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    // there's nothing left to delete on the server
+    sqlx::query(&format!(
+        "DELETE FROM pending_deletions WHERE calendar_id IN ({calendars})"
+    ))
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(&format!(
+        "DELETE FROM ics_cache WHERE calendar_id IN ({calendars})"
+    ))
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(&format!("DELETE FROM calendars WHERE id IN ({calendars})"))
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!("Recovered {recovered} tasks to the local Recovered list");
+    if let Err(e) = app_handle.emit("database-changed", ()) {
+        log::warn!("Failed to emit database-changed: {e}");
+    }
+    Ok(recovered)
+}
+
+/// keep the tasks of a calendar that's gone from the server as local tasks in the Recovered
+/// list and remove the calendar. returns the number of tasks kept
+#[tauri::command]
+pub async fn recover_calendar_tasks(
+    app_handle: tauri::AppHandle,
+    calendar_id: String,
+) -> Result<u64, String> {
+    if calendar_id == RECOVERED_CALENDAR_ID {
+        return Err("The Recovered list can't be recovered into itself".to_string());
+    }
+    recover(&app_handle, "?", &calendar_id).await
+}
+
+/// keep the tasks of an account that's about to be removed as local tasks in the Recovered
+/// list; its calendars are removed, the account itself stays for the caller to delete
+#[tauri::command]
+pub async fn recover_account_tasks(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+) -> Result<u64, String> {
+    if account_id == LOCAL_ACCOUNT_ID {
+        return Err("The local account can't be recovered into itself".to_string());
+    }
+    recover(
+        &app_handle,
+        "SELECT id FROM calendars WHERE account_id = ?",
+        &account_id,
+    )
+    .await
+}
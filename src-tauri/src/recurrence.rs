@@ -0,0 +1,456 @@
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveTime, Weekday};
+
+use crate::events::{parse_property, parse_time, unfold, Property};
+use crate::quick_add::to_iso;
+use crate::{db, lock};
+
+// give up on rules that never produce a date (every 12 months on the 31st, starting in june)
+const MAX_PERIODS: u32 = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "DAILY" => Some(Self::Daily),
+            "WEEKLY" => Some(Self::Weekly),
+            "MONTHLY" => Some(Self::Monthly),
+            "YEARLY" => Some(Self::Yearly),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+            Self::Yearly => "YEARLY",
+        }
+    }
+}
+
+/// the subset of RFC 5545 recurrence rules quick add can express
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// weekdays of a weekly rule, empty for the start date's weekday
+    pub by_day: Vec<Weekday>,
+    /// day of a monthly rule, -1 for the last day; None for the start date's day
+    pub by_month_day: Option<i32>,
+    /// how many occurrences there are in all, counting the start
+    pub count: Option<u32>,
+    /// the last day an occurrence can fall on
+    pub until: Option<NaiveDate>,
+}
+
+fn day_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_day_code(code: &str) -> Option<Weekday> {
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    let first = date.with_day(1).expect("every month has a first day");
+    first + Months::new(1) - Duration::days(1)
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency, interval: u32) -> Self {
+        Self {
+            frequency,
+            interval: interval.max(1),
+            by_day: Vec::new(),
+            by_month_day: None,
+            count: None,
+            until: None,
+        }
+    }
+
+    /// read an RRULE value (with or without the "RRULE:" name); rules outside the subset,
+    /// like "every 2nd tuesday" or several month days, are refused
+    pub fn parse(rrule: &str) -> Result<Self, String> {
+        let unsupported = || format!("Unsupported recurrence rule: {rrule}");
+        let value = rrule.trim();
+        let value = value
+            .get(..6)
+            .filter(|name| name.eq_ignore_ascii_case("RRULE:"))
+            .map_or(value, |_| &value[6..]);
+
+        let mut frequency = None;
+        let mut rule = Self::new(Frequency::Daily, 1);
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=').ok_or_else(unsupported)?;
+            let value = value.to_ascii_uppercase();
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => frequency = Some(Frequency::parse(&value).ok_or_else(unsupported)?),
+                "INTERVAL" => rule.interval = value.parse().map_err(|_| unsupported())?,
+                "BYDAY" => {
+                    rule.by_day = value
+                        .split(',')
+                        .map(parse_day_code)
+                        .collect::<Option<_>>()
+                        .ok_or_else(unsupported)?;
+                }
+                "BYMONTHDAY" => match value.parse() {
+                    Ok(day @ (1..=31 | -1)) => rule.by_month_day = Some(day),
+                    _ => return Err(unsupported()),
+                },
+                "COUNT" => rule.count = Some(value.parse().map_err(|_| unsupported())?),
+                "UNTIL" => {
+                    rule.until = Some(
+                        value
+                            .get(..8)
+                            .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+                            .ok_or_else(unsupported)?,
+                    );
+                }
+                // weeks always start on monday here
+                "WKST" => {}
+                _ => return Err(unsupported()),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(unsupported)?;
+        rule.interval = rule.interval.max(1);
+        let by_day_fits = rule.by_day.is_empty() || rule.frequency == Frequency::Weekly;
+        let by_month_day_fits = rule.by_month_day.is_none() || rule.frequency == Frequency::Monthly;
+        if !by_day_fits || !by_month_day_fits {
+            return Err(unsupported());
+        }
+        Ok(rule)
+    }
+
+    /// the rule as an RRULE value, e.g. "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH"
+    pub fn to_rrule(&self) -> String {
+        let mut rule = format!("FREQ={}", self.frequency.name());
+        if self.interval > 1 {
+            rule.push_str(&format!(";INTERVAL={}", self.interval));
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|day| day_code(*day)).collect();
+            rule.push_str(&format!(";BYDAY={}", days.join(",")));
+        }
+        if let Some(day) = self.by_month_day {
+            rule.push_str(&format!(";BYMONTHDAY={day}"));
+        }
+        if let Some(count) = self.count {
+            rule.push_str(&format!(";COUNT={count}"));
+        }
+        if let Some(until) = self.until {
+            rule.push_str(&format!(";UNTIL={}", until.format("%Y%m%d")));
+        }
+        rule
+    }
+
+    // the dates of one period (a day, week, month or year) of the rule, in order
+    fn period(&self, start: NaiveDate, index: u32) -> Vec<NaiveDate> {
+        let step = index * self.interval;
+        match self.frequency {
+            Frequency::Daily => vec![start + Duration::days(step.into())],
+            Frequency::Weekly if self.by_day.is_empty() => {
+                vec![start + Duration::weeks(step.into())]
+            }
+            Frequency::Weekly => {
+                // weeks start on monday, the RRULE default
+                let monday = start - Duration::days(start.weekday().num_days_from_monday().into())
+                    + Duration::weeks(step.into());
+                let mut days: Vec<NaiveDate> = self
+                    .by_day
+                    .iter()
+                    .map(|day| monday + Duration::days(day.num_days_from_monday().into()))
+                    .collect();
+                days.sort();
+                days
+            }
+            Frequency::Monthly => {
+                let Some(month) = start
+                    .with_day(1)
+                    .and_then(|first| first.checked_add_months(Months::new(step)))
+                else {
+                    return Vec::new();
+                };
+                let day = match self.by_month_day {
+                    Some(-1) => return vec![last_day_of_month(month)],
+                    Some(day) => day as u32,
+                    None => start.day(),
+                };
+                // months without that day are skipped, like RFC 5545 says
+                month.with_day(day).into_iter().collect()
+            }
+            Frequency::Yearly => {
+                NaiveDate::from_ymd_opt(start.year() + step as i32, start.month(), start.day())
+                    .into_iter()
+                    .collect()
+            }
+        }
+    }
+
+    // every date of the rule on or after `start`, up to its COUNT and UNTIL
+    fn dates(&self, start: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        (0..MAX_PERIODS)
+            .flat_map(move |index| self.period(start, index))
+            .filter(move |date| *date >= start)
+            .take_while(|date| self.until.is_none_or(|until| *date <= until))
+            .take(self.count.map_or(usize::MAX, |count| count as usize))
+    }
+
+    /// the first `count` dates of the rule on or after `start`
+    pub fn occurrences(&self, start: NaiveDate, count: usize) -> Vec<NaiveDate> {
+        self.dates(start).take(count).collect()
+    }
+}
+
+// the recurrence of a synced task, from the VTODO as last fetched
+#[derive(Default)]
+struct StoredRule {
+    rrule: Option<String>,
+    exdates: Vec<NaiveDate>,
+}
+
+// the first VTODO without a RECURRENCE-ID is the series, the others override occurrences
+fn stored_rule(ics: &str) -> StoredRule {
+    let mut current: Option<(StoredRule, bool)> = None;
+    for line in unfold(ics).lines() {
+        if line.eq_ignore_ascii_case("BEGIN:VTODO") {
+            current = Some((StoredRule::default(), false));
+            continue;
+        }
+        if line.eq_ignore_ascii_case("END:VTODO") {
+            match current.take() {
+                Some((rule, false)) => return rule,
+                _ => continue,
+            }
+        }
+        let (Some((rule, is_override)), Some(property)) = (&mut current, parse_property(line))
+        else {
+            continue;
+        };
+        match property.name.as_str() {
+            "RRULE" => rule.rrule = Some(property.value.trim().to_string()),
+            "RECURRENCE-ID" => *is_override = true,
+            "EXDATE" => {
+                // one property can list several dates
+                for value in property.value.split(',') {
+                    let exdate = Property {
+                        name: property.name.clone(),
+                        params: property.params.clone(),
+                        value,
+                    };
+                    rule.exdates.extend(
+                        parse_time(&exdate)
+                            .map(|(time, _)| time.with_timezone(&Local).date_naive()),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    StoredRule::default()
+}
+
+/// the next `count` occurrences of a recurring task from today on, as UTC timestamps, with its
+/// EXDATEs left out. `rrule` previews a rule that isn't saved yet in place of the task's own.
+/// the series starts at the task's start date, or its due date
+#[tauri::command]
+pub async fn preview_occurrences(
+    app_handle: tauri::AppHandle,
+    uid: String,
+    count: usize,
+    rrule: Option<String>,
+) -> Result<Vec<String>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let (href, start_date, due_date): (Option<String>, Option<String>, Option<String>) =
+        sqlx::query_as("SELECT href, start_date, due_date FROM tasks WHERE uid = ?")
+            .bind(&uid)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Task not found: {uid}"))?;
+    let ics: Option<String> = match &href {
+        Some(href) => sqlx::query_scalar("SELECT ics FROM ics_cache WHERE href = ?")
+            .bind(href)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let stored = ics.as_deref().map(stored_rule).unwrap_or_default();
+    let rrule = rrule
+        .or(stored.rrule)
+        .ok_or_else(|| format!("Task {uid} doesn't repeat"))?;
+    let rule = Recurrence::parse(&rrule)?;
+
+    let start = start_date
+        .or(due_date)
+        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+        .map(|date| date.with_timezone(&Local).naive_local());
+    let today = Local::now().date_naive();
+    let time = start.map_or(NaiveTime::MIN, |start| start.time());
+    Ok(rule
+        .dates(start.map_or(today, |start| start.date()))
+        .filter(|date| *date >= today && !stored.exdates.contains(date))
+        .take(count)
+        .filter_map(|date| to_iso(date.and_time(time)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("valid date")
+    }
+
+    fn occurrences(rrule: &str, start: NaiveDate, count: usize) -> Vec<NaiveDate> {
+        Recurrence::parse(rrule)
+            .expect("rule should parse")
+            .occurrences(start, count)
+    }
+
+    #[test]
+    fn round_trip() {
+        let rule = Recurrence::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH").unwrap();
+        assert_eq!(rule.frequency, Frequency::Weekly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.by_day, vec![Weekday::Mon, Weekday::Thu]);
+        assert_eq!(rule.to_rrule(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH");
+
+        let rule = "FREQ=MONTHLY;BYMONTHDAY=-1;COUNT=6";
+        assert_eq!(Recurrence::parse(rule).unwrap().to_rrule(), rule);
+        let rule = "FREQ=DAILY;UNTIL=20250301";
+        assert_eq!(Recurrence::parse(rule).unwrap().to_rrule(), rule);
+    }
+
+    #[test]
+    fn lenient_input() {
+        let rule = Recurrence::parse(" rrule:freq=daily;interval=0;wkst=su; ").unwrap();
+        assert_eq!(rule, Recurrence::new(Frequency::Daily, 1));
+        let rule = Recurrence::parse("FREQ=DAILY;UNTIL=20250301T120000Z").unwrap();
+        assert_eq!(rule.until, Some(day(2025, 3, 1)));
+    }
+
+    #[test]
+    fn unsupported_rules() {
+        for rrule in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=WEEKLY;BYDAY=2TU",
+            "FREQ=MONTHLY;BYDAY=MO",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYMONTHDAY=1,15",
+            "FREQ=MONTHLY;BYMONTHDAY=0",
+            "FREQ=MONTHLY;BYDAY=MO;BYSETPOS=-1",
+            "FREQ=DAILY;COUNT=many",
+            "FREQ=DAILY;UNTIL=soon",
+            "FREQ",
+        ] {
+            let error = Recurrence::parse(rrule).expect_err(rrule);
+            assert!(error.starts_with("Unsupported recurrence rule"), "{error}");
+        }
+    }
+
+    #[test]
+    fn months_without_the_day_are_skipped() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY", day(2025, 1, 31), 3),
+            vec![day(2025, 1, 31), day(2025, 3, 31), day(2025, 5, 31)]
+        );
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYMONTHDAY=-1", day(2024, 1, 15), 3),
+            vec![day(2024, 1, 31), day(2024, 2, 29), day(2024, 3, 31)]
+        );
+        assert_eq!(
+            occurrences("FREQ=YEARLY", day(2024, 2, 29), 2),
+            vec![day(2024, 2, 29), day(2028, 2, 29)]
+        );
+        // never on a 31st, which gives up instead of looping forever
+        assert!(
+            occurrences("FREQ=MONTHLY;INTERVAL=12;BYMONTHDAY=31", day(2025, 6, 1), 1).is_empty()
+        );
+    }
+
+    #[test]
+    fn weekdays() {
+        // starting on a wednesday, the monday of its week is already over
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;BYDAY=TH,MO", day(2025, 1, 1), 3),
+            vec![day(2025, 1, 2), day(2025, 1, 6), day(2025, 1, 9)]
+        );
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;INTERVAL=2", day(2025, 1, 1), 2),
+            vec![day(2025, 1, 1), day(2025, 1, 15)]
+        );
+    }
+
+    #[test]
+    fn count_and_until() {
+        assert_eq!(
+            occurrences("FREQ=DAILY;COUNT=3", day(2025, 1, 1), 10),
+            vec![day(2025, 1, 1), day(2025, 1, 2), day(2025, 1, 3)]
+        );
+        // days of the first week before the start don't count
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;BYDAY=MO,TH;COUNT=2", day(2025, 1, 1), 10),
+            vec![day(2025, 1, 2), day(2025, 1, 6)]
+        );
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;UNTIL=20250115", day(2025, 1, 1), 10),
+            vec![day(2025, 1, 1), day(2025, 1, 8), day(2025, 1, 15)]
+        );
+    }
+
+    #[test]
+    fn rule_of_the_series() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VTODO\r\n\
+            UID:1\r\n\
+            RECURRENCE-ID:20250108T090000Z\r\n\
+            RRULE:FREQ=YEARLY\r\n\
+            END:VTODO\r\n\
+            BEGIN:VTODO\r\n\
+            UID:1\r\n\
+            RRULE:FREQ=WEEKLY;\r\n \
+            BYDAY=WE\r\n\
+            EXDATE;VALUE=DATE:20250115,20250122\r\n\
+            END:VTODO\r\n\
+            END:VCALENDAR\r\n";
+        let stored = stored_rule(ics);
+        assert_eq!(stored.rrule.as_deref(), Some("FREQ=WEEKLY;BYDAY=WE"));
+        assert_eq!(stored.exdates, vec![day(2025, 1, 15), day(2025, 1, 22)]);
+
+        let stored = stored_rule("BEGIN:VTODO\r\nUID:2\r\nEND:VTODO\r\n");
+        assert!(stored.rrule.is_none());
+        assert!(stored.exdates.is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use chrono::{DateTime, Duration, Local};
+use serde::Deserialize;
+use sqlx::{FromRow, SqlitePool};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::locale::{self, LocaleSettings};
+use crate::tasks::now_iso;
+use crate::{db, i18n, lock};
+
+// names of a task's tags, as a json array
+const TAG_NAMES: &str = "(SELECT json_group_array(tags.name) FROM json_each(tasks.tags)
+        JOIN tags ON tags.id = json_each.value) AS tag_names";
+
+/// the period a report covers, as iso strings; `to` is exclusive
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRange {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+#[derive(Debug, FromRow)]
+struct ReportTask {
+    title: String,
+    list: String,
+    tag_names: Option<String>,
+    due_date: Option<String>,
+    completed_at: Option<String>,
+}
+
+impl ReportTask {
+    fn tags(&self) -> Vec<String> {
+        self.tag_names
+            .as_deref()
+            .and_then(|tags| serde_json::from_str(tags).ok())
+            .unwrap_or_default()
+    }
+}
+
+// the local day in the locale's format, or the stored value when it doesn't parse
+fn local_date(date: &str, locale: &LocaleSettings) -> String {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| locale.format_date(date.with_timezone(&Local).date_naive()))
+        .unwrap_or_else(|_| date.to_string())
+}
+
+// writes the same structure as html or markdown
+struct Document {
+    format: ReportFormat,
+    out: String,
+}
+
+impl Document {
+    fn escape(&self, text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match (self.format, c) {
+                (ReportFormat::Html, '&') => escaped.push_str("&amp;"),
+                (ReportFormat::Html, '<') => escaped.push_str("&lt;"),
+                (ReportFormat::Html, '>') => escaped.push_str("&gt;"),
+                (ReportFormat::Html, '"') => escaped.push_str("&quot;"),
+                (ReportFormat::Markdown, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                (_, '\n') => escaped.push(' '),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    fn heading(&mut self, level: usize, text: &str) {
+        let text = self.escape(text);
+        let _ = match self.format {
+            ReportFormat::Html => writeln!(self.out, "<h{level}>{text}</h{level}>"),
+            ReportFormat::Markdown => writeln!(self.out, "\n{} {text}\n", "#".repeat(level)),
+        };
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        let text = self.escape(text);
+        let _ = match self.format {
+            ReportFormat::Html => writeln!(self.out, "<p>{text}</p>"),
+            ReportFormat::Markdown => writeln!(self.out, "{text}"),
+        };
+    }
+
+    // a list item with an optional note after the text
+    fn items(&mut self, items: &[(String, String)]) {
+        if matches!(self.format, ReportFormat::Html) {
+            self.out.push_str("<ul>\n");
+        }
+        for (text, note) in items {
+            let text = self.escape(text);
+            let note = self.escape(note);
+            let _ = match (self.format, note.is_empty()) {
+                (ReportFormat::Html, true) => writeln!(self.out, "<li>{text}</li>"),
+                (ReportFormat::Html, false) => {
+                    writeln!(self.out, "<li>{text} <small>{note}</small></li>")
+                }
+                (ReportFormat::Markdown, true) => writeln!(self.out, "- {text}"),
+                (ReportFormat::Markdown, false) => writeln!(self.out, "- {text} _{note}_"),
+            };
+        }
+        if matches!(self.format, ReportFormat::Html) {
+            self.out.push_str("</ul>\n");
+        }
+    }
+
+    fn finish(self, title: &str) -> String {
+        match self.format {
+            ReportFormat::Html => format!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+                 </head>\n<body>\n{}</body>\n</html>\n",
+                self.escape(title),
+                self.out
+            ),
+            ReportFormat::Markdown => format!("{}\n", self.out.trim()),
+        }
+    }
+}
+
+// one section with the tasks grouped per list, in the order the rows came in
+fn write_section(
+    document: &mut Document,
+    title: &str,
+    tasks: &[ReportTask],
+    note: impl Fn(&ReportTask) -> String,
+) {
+    document.heading(2, &format!("{title} ({})", tasks.len()));
+    if tasks.is_empty() {
+        document.paragraph(i18n::t("Nothing here."));
+        return;
+    }
+    let mut lists: Vec<(&str, Vec<(String, String)>)> = Vec::new();
+    for task in tasks {
+        let mut parts = Vec::new();
+        let tags = task.tags();
+        if !tags.is_empty() {
+            parts.push(tags.join(", "));
+        }
+        let note = note(task);
+        if !note.is_empty() {
+            parts.push(note);
+        }
+        let item = (task.title.clone(), parts.join(" · "));
+        match lists.iter_mut().find(|(list, _)| *list == task.list) {
+            Some((_, items)) => items.push(item),
+            None => lists.push((&task.list, vec![item])),
+        }
+    }
+    for (list, items) in lists {
+        document.heading(3, &format!("{list} ({})", items.len()));
+        document.items(&items);
+    }
+}
+
+/// the report and its title, for callers that send it somewhere else
+pub async fn render(
+    pool: &SqlitePool,
+    range: &ReportRange,
+    format: ReportFormat,
+) -> Result<(String, String), String> {
+    let completed: Vec<ReportTask> = sqlx::query_as(&format!(
+        "SELECT tasks.title, COALESCE(calendars.display_name, ?3) AS list, {TAG_NAMES},
+            tasks.due_date, tasks.completed_at
+         FROM tasks LEFT JOIN calendars ON calendars.id = tasks.calendar_id
+         WHERE tasks.completed = 1 AND tasks.completed_at >= ?1 AND tasks.completed_at < ?2
+         ORDER BY list COLLATE NOCASE, tasks.completed_at"
+    ))
+    .bind(&range.from)
+    .bind(&range.to)
+    .bind(i18n::t("Local"))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let outstanding: Vec<ReportTask> = sqlx::query_as(&format!(
+        "SELECT tasks.title, COALESCE(calendars.display_name, ?2) AS list, {TAG_NAMES},
+            tasks.due_date, tasks.completed_at
+         FROM tasks LEFT JOIN calendars ON calendars.id = tasks.calendar_id
+         WHERE tasks.completed = 0 AND tasks.archived_at IS NULL AND tasks.created_at < ?1
+         ORDER BY list COLLATE NOCASE, tasks.due_date IS NULL, tasks.due_date, tasks.sort_order"
+    ))
+    .bind(&range.to)
+    .bind(i18n::t("Local"))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let locale = locale::current();
+    let title = i18n::t_with(
+        "Tasks report {} – {}",
+        &[
+            &local_date(&range.from, &locale),
+            // the last day that's still in the range
+            &DateTime::parse_from_rfc3339(&range.to)
+                .map(|to| (to - Duration::milliseconds(1)).to_rfc3339())
+                .map_or_else(|_| range.to.clone(), |to| local_date(&to, &locale)),
+        ],
+    );
+    let mut document = Document {
+        format,
+        out: String::new(),
+    };
+    document.heading(1, &title);
+
+    write_section(&mut document, i18n::t("Completed"), &completed, |task| {
+        task.completed_at
+            .as_deref()
+            .map(|date| i18n::t_with("done {}", &[&local_date(date, &locale)]))
+            .unwrap_or_default()
+    });
+    let now = now_iso();
+    write_section(
+        &mut document,
+        i18n::t("Outstanding"),
+        &outstanding,
+        |task| match task.due_date.as_deref() {
+            Some(due) if due < now.as_str() => {
+                i18n::t_with("overdue since {}", &[&local_date(due, &locale)])
+            }
+            Some(due) => i18n::t_with("due {}", &[&local_date(due, &locale)]),
+            None => String::new(),
+        },
+    );
+
+    // completed and outstanding per tag
+    let mut tags: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for task in &completed {
+        for tag in task.tags() {
+            tags.entry(tag).or_default().0 += 1;
+        }
+    }
+    for task in &outstanding {
+        for tag in task.tags() {
+            tags.entry(tag).or_default().1 += 1;
+        }
+    }
+    if !tags.is_empty() {
+        document.heading(2, i18n::t("Tags"));
+        let items: Vec<(String, String)> = tags
+            .into_iter()
+            .map(|(tag, (completed, outstanding))| {
+                (
+                    tag,
+                    i18n::t_with("{} completed, {} outstanding", &[&completed, &outstanding]),
+                )
+            })
+            .collect();
+        document.items(&items);
+    }
+
+    log::info!(
+        "Generated a report with {} completed and {} outstanding tasks",
+        completed.len(),
+        outstanding.len()
+    );
+    let report = document.finish(&title);
+    Ok((title, report))
+}
+
+/// a summary of the tasks completed in the range and of the tasks still open, grouped by
+/// list, with completed and open counts per tag
+#[tauri::command]
+pub async fn generate_report(
+    app_handle: tauri::AppHandle,
+    range: ReportRange,
+    format: ReportFormat,
+) -> Result<String, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let (_, report) = render(&pool, &range, format).await?;
+    Ok(report)
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+use crate::caldav::{AccountConfig, CalDavClient};
+use crate::tasks::now_iso;
+use crate::{db, lock, read_only, settings};
+
+// app settings that follow the user between machines, as one json document in their WebDAV
+// space. every setting carries when it was last changed, so a merge keeps the newer one per
+// setting instead of one machine's whole copy
+
+// the last document this machine pushed or pulled, with the change times
+const LOCAL_KEY: &str = "roaming_settings";
+const FILE_NAME: &str = "caldav-tasks-settings.json";
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoamingEntry {
+    value: Value,
+    modified_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoamingDocument {
+    version: u32,
+    updated_at: String,
+    entries: BTreeMap<String, RoamingEntry>,
+}
+
+impl Default for RoamingDocument {
+    fn default() -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            updated_at: now_iso(),
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+impl RoamingDocument {
+    // stamp the settings that differ from the last known copy as changed now; settings this
+    // version doesn't know (from a newer one) stay as they are
+    fn record(&mut self, settings: BTreeMap<String, Value>) {
+        let now = now_iso();
+        for (key, value) in settings {
+            if self
+                .entries
+                .get(&key)
+                .is_some_and(|entry| entry.value == value)
+            {
+                continue;
+            }
+            self.entries.insert(
+                key,
+                RoamingEntry {
+                    value,
+                    modified_at: now.clone(),
+                },
+            );
+        }
+        self.updated_at = now;
+    }
+
+    // the newer entry of each setting wins
+    fn merge(mut self, other: RoamingDocument) -> Self {
+        for (key, entry) in other.entries {
+            let newer = self
+                .entries
+                .get(&key)
+                .is_none_or(|current| entry.modified_at > current.modified_at);
+            if newer {
+                self.entries.insert(key, entry);
+            }
+        }
+        self.updated_at = now_iso();
+        self
+    }
+
+    fn values(&self) -> BTreeMap<String, Value> {
+        self.entries
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.value.clone()))
+            .collect()
+    }
+}
+
+// where the document lives: the given url, or the files space of servers that have one
+fn document_url(account: &AccountConfig, url: Option<String>) -> Result<String, String> {
+    if let Some(url) = url.filter(|url| !url.trim().is_empty()) {
+        return Ok(url.trim().to_string());
+    }
+    match account.server_type() {
+        "nextcloud" => Ok(format!(
+            "{}/remote.php/dav/files/{}/{FILE_NAME}",
+            account.base_url(),
+            account.username
+        )),
+        _ => Err("Choose a WebDAV location for the settings of this server".to_string()),
+    }
+}
+
+async fn load_local(pool: &SqlitePool) -> Result<RoamingDocument, String> {
+    match settings::get(pool, LOCAL_KEY).await? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(RoamingDocument::default()),
+    }
+}
+
+async fn store_local(pool: &SqlitePool, document: &RoamingDocument) -> Result<(), String> {
+    let json = serde_json::to_string(document).map_err(|e| e.to_string())?;
+    settings::set(pool, LOCAL_KEY, &json).await
+}
+
+async fn download(client: &CalDavClient, url: &str) -> Result<RoamingDocument, String> {
+    let response = client.request("GET", url, None, None).await?;
+    match response.status {
+        // nothing roamed yet
+        404 => Ok(RoamingDocument::default()),
+        200..=299 => {
+            let document: RoamingDocument = serde_json::from_str(&response.body)
+                .map_err(|e| format!("The settings on the server can't be read: {e}"))?;
+            if document.version > FORMAT_VERSION {
+                return Err("The settings on the server are from a newer version".to_string());
+            }
+            Ok(document)
+        }
+        status => Err(format!("Failed to download the settings (HTTP {status})")),
+    }
+}
+
+async fn upload(
+    client: &CalDavClient,
+    url: &str,
+    document: &RoamingDocument,
+) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(document).map_err(|e| e.to_string())?;
+    let response = client.put_json(url, &json).await?;
+    if !(200..300).contains(&response.status) {
+        return Err(format!(
+            "Failed to upload the settings (HTTP {})",
+            response.status
+        ));
+    }
+    Ok(())
+}
+
+/// replace the settings on the server with this machine's `settings`
+#[tauri::command]
+pub async fn push_roaming_settings(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    url: Option<String>,
+    settings: BTreeMap<String, Value>,
+) -> Result<(), String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    read_only::ensure_writable()?;
+    let url = document_url(&account, url)?;
+    let pool = db::pool(&app_handle).await?;
+    let client = CalDavClient::new(&account)?;
+
+    let mut document = load_local(&pool).await?;
+    document.record(settings);
+    upload(&client, &url, &document).await?;
+    store_local(&pool, &document).await?;
+    log::info!("Pushed {} roaming settings", document.entries.len());
+    Ok(())
+}
+
+/// the settings on the server, replacing this machine's copy; the frontend applies them
+#[tauri::command]
+pub async fn pull_roaming_settings(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    url: Option<String>,
+) -> Result<BTreeMap<String, Value>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    read_only::ensure_writable()?;
+    let url = document_url(&account, url)?;
+    let pool = db::pool(&app_handle).await?;
+    let client = CalDavClient::new(&account)?;
+
+    let document = download(&client, &url).await?;
+    store_local(&pool, &document).await?;
+    log::info!("Pulled {} roaming settings", document.entries.len());
+    Ok(document.values())
+}
+
+/// merge this machine's `settings` with the ones on the server, the newer change of each
+/// setting winning, and upload the result. returns the merged settings to apply
+#[tauri::command]
+pub async fn merge_roaming_settings(
+    app_handle: tauri::AppHandle,
+    account: AccountConfig,
+    url: Option<String>,
+    settings: BTreeMap<String, Value>,
+) -> Result<BTreeMap<String, Value>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    read_only::ensure_writable()?;
+    let url = document_url(&account, url)?;
+    let pool = db::pool(&app_handle).await?;
+    let client = CalDavClient::new(&account)?;
+
+    let mut local = load_local(&pool).await?;
+    local.record(settings);
+    let remote = download(&client, &url).await?;
+    let merged = local.merge(remote);
+    upload(&client, &url, &merged).await?;
+    store_local(&pool, &merged).await?;
+    log::info!("Merged {} roaming settings", merged.entries.len());
+    Ok(merged.values())
+}
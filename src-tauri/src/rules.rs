@@ -0,0 +1,404 @@
+use chrono::{Duration, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::quick_add::to_iso;
+use crate::tasks::{fetch_by_ids, new_id, now_iso, save_task, Task, TaskChanges};
+use crate::{contexts, db, lock, read_only};
+
+const PRIORITIES: &[&str] = &["high", "medium", "low", "none"];
+
+/// when rules are evaluated for a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleEvent {
+    Created,
+    /// edited in the app
+    Updated,
+    /// new or changed on the server
+    Synced,
+}
+
+/// every condition of a rule has to hold; text matches ignore case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RuleCondition {
+    InList {
+        calendar_id: String,
+    },
+    TitleContains {
+        text: String,
+    },
+    /// in the title or the description
+    TextContains {
+        text: String,
+    },
+    HasTag {
+        tag_id: String,
+    },
+    LacksTag {
+        tag_id: String,
+    },
+    PriorityIs {
+        priority: String,
+    },
+    NoDueDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RuleAction {
+    AddTag {
+        tag_id: String,
+    },
+    RemoveTag {
+        tag_id: String,
+    },
+    SetPriority {
+        priority: String,
+    },
+    AddContext {
+        name: String,
+    },
+    /// all-day, `days` from the day the rule runs; only for tasks without a due date
+    SetDueInDays {
+        days: i64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    /// empty for a new rule
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub events: Vec<RuleEvent>,
+    pub conditions: Vec<RuleCondition>,
+    pub actions: Vec<RuleAction>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub sort_order: i64,
+    #[serde(default)]
+    pub created_at: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, FromRow)]
+struct RuleRow {
+    id: String,
+    name: String,
+    events: String,
+    conditions: String,
+    actions: String,
+    enabled: bool,
+    sort_order: i64,
+    created_at: String,
+}
+
+impl From<RuleRow> for Rule {
+    fn from(row: RuleRow) -> Self {
+        Rule {
+            id: row.id,
+            name: row.name,
+            events: serde_json::from_str(&row.events).unwrap_or_default(),
+            conditions: serde_json::from_str(&row.conditions).unwrap_or_default(),
+            actions: serde_json::from_str(&row.actions).unwrap_or_default(),
+            enabled: row.enabled,
+            sort_order: row.sort_order,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// a task a rule would change, and how
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTestMatch {
+    pub task_id: String,
+    pub title: String,
+    /// the actions that would change something
+    pub changes: Vec<RuleAction>,
+}
+
+fn contains(text: &str, part: &str) -> bool {
+    text.to_lowercase().contains(&part.to_lowercase())
+}
+
+impl RuleCondition {
+    fn holds(&self, task: &Task) -> bool {
+        match self {
+            RuleCondition::InList { calendar_id } => task.calendar_id == *calendar_id,
+            RuleCondition::TitleContains { text } => contains(&task.title, text),
+            RuleCondition::TextContains { text } => {
+                contains(&task.title, text) || contains(&task.description, text)
+            }
+            RuleCondition::HasTag { tag_id } => task.tags.contains(tag_id),
+            RuleCondition::LacksTag { tag_id } => !task.tags.contains(tag_id),
+            RuleCondition::PriorityIs { priority } => task.priority == *priority,
+            RuleCondition::NoDueDate => task.due_date.is_none(),
+        }
+    }
+}
+
+impl RuleAction {
+    // false when the task is already that way
+    fn apply(&self, task: &mut Task) -> bool {
+        match self {
+            RuleAction::AddTag { tag_id } => {
+                if task.tags.contains(tag_id) {
+                    return false;
+                }
+                task.tags.push(tag_id.clone());
+            }
+            RuleAction::RemoveTag { tag_id } => {
+                let before = task.tags.len();
+                task.tags.retain(|tag| tag != tag_id);
+                return task.tags.len() != before;
+            }
+            RuleAction::SetPriority { priority } => {
+                if task.priority == *priority {
+                    return false;
+                }
+                task.priority = priority.clone();
+            }
+            RuleAction::AddContext { name } => {
+                if task.contexts.contains(name) {
+                    return false;
+                }
+                task.contexts.push(name.clone());
+            }
+            RuleAction::SetDueInDays { days } => {
+                if task.due_date.is_some() {
+                    return false;
+                }
+                let day = Local::now().date_naive() + Duration::days(*days);
+                task.due_date = to_iso(day.and_time(NaiveTime::MIN));
+                task.due_date_all_day = true;
+            }
+        }
+        true
+    }
+}
+
+impl Rule {
+    fn matches(&self, task: &Task) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.holds(task))
+    }
+
+    // the actions that changed the task
+    fn apply(&self, task: &mut Task) -> Vec<RuleAction> {
+        if !self.matches(task) {
+            return Vec::new();
+        }
+        self.actions
+            .iter()
+            .filter(|action| action.apply(task))
+            .cloned()
+            .collect()
+    }
+}
+
+/// the enabled rules for an event, in the order they run
+pub async fn load(pool: &SqlitePool, event: RuleEvent) -> Result<Vec<Rule>, String> {
+    let rows: Vec<RuleRow> =
+        sqlx::query_as("SELECT * FROM rules WHERE enabled = 1 ORDER BY sort_order, created_at")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(Rule::from)
+        .filter(|rule| rule.events.contains(&event))
+        .collect())
+}
+
+/// the task after running the rules on it, marked for upload, or None when no rule changed
+/// anything; later rules see what earlier ones did
+pub fn apply(rules: &[Rule], task: &Task) -> Option<Task> {
+    let mut changed = task.clone();
+    let mut fired = Vec::new();
+    for rule in rules {
+        if !rule.apply(&mut changed).is_empty() {
+            fired.push(rule.name.as_str());
+        }
+    }
+    if fired.is_empty() {
+        return None;
+    }
+    log::debug!("Rules {fired:?} changed task {}", task.id);
+    changed.modified_at = now_iso();
+    changed.synced = false;
+    Some(changed)
+}
+
+fn validate(rule: &mut Rule) -> Result<(), String> {
+    rule.name = rule.name.trim().to_string();
+    if rule.name.is_empty() {
+        return Err("Rules need a name".to_string());
+    }
+    if rule.events.is_empty() {
+        return Err("Rules need at least one event".to_string());
+    }
+    if rule.actions.is_empty() {
+        return Err("Rules need at least one action".to_string());
+    }
+    for condition in &rule.conditions {
+        if let RuleCondition::PriorityIs { priority } = condition {
+            if !PRIORITIES.contains(&priority.as_str()) {
+                return Err(format!("Unknown priority \"{priority}\""));
+            }
+        }
+    }
+    for action in &mut rule.actions {
+        match action {
+            RuleAction::SetPriority { priority } if !PRIORITIES.contains(&priority.as_str()) => {
+                return Err(format!("Unknown priority \"{priority}\""));
+            }
+            RuleAction::AddContext { name } => *name = contexts::normalize(name)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_rules(app_handle: tauri::AppHandle) -> Result<Vec<Rule>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let rows: Vec<RuleRow> = sqlx::query_as("SELECT * FROM rules ORDER BY sort_order, created_at")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(Rule::from).collect())
+}
+
+/// add a rule (with an empty id, at the end) or change one
+#[tauri::command]
+pub async fn save_rule(app_handle: tauri::AppHandle, mut rule: Rule) -> Result<Rule, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    validate(&mut rule)?;
+
+    if rule.id.is_empty() {
+        rule.id = new_id();
+        rule.created_at = now_iso();
+        rule.sort_order = sqlx::query_scalar("SELECT COALESCE(MAX(sort_order), -1) + 1 FROM rules")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let events = serde_json::to_string(&rule.events).map_err(|e| e.to_string())?;
+    let conditions = serde_json::to_string(&rule.conditions).map_err(|e| e.to_string())?;
+    let actions = serde_json::to_string(&rule.actions).map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO rules (id, name, events, conditions, actions, enabled, sort_order, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name, events = excluded.events, conditions = excluded.conditions,
+            actions = excluded.actions, enabled = excluded.enabled, sort_order = excluded.sort_order",
+    )
+    .bind(&rule.id)
+    .bind(&rule.name)
+    .bind(events)
+    .bind(conditions)
+    .bind(actions)
+    .bind(rule.enabled)
+    .bind(rule.sort_order)
+    .bind(&rule.created_at)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rule)
+}
+
+#[tauri::command]
+pub async fn delete_rule(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("DELETE FROM rules WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// run the rules for an event on tasks the frontend just created or edited; returns how many
+/// tasks changed, those arrive through the tasks-changed event
+#[tauri::command]
+pub async fn apply_rules(
+    app_handle: tauri::AppHandle,
+    ids: Vec<String>,
+    event: RuleEvent,
+) -> Result<usize, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let rules = load(&pool, event).await?;
+    if rules.is_empty() {
+        return Ok(0);
+    }
+
+    let tasks = fetch_by_ids(&pool, &ids).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut updated = Vec::new();
+    for task in tasks {
+        if let Some(changed) = apply(&rules, &task) {
+            save_task(&mut tx, &changed).await?;
+            updated.push(changed.id);
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let count = updated.len();
+    TaskChanges {
+        updated,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(count)
+}
+
+/// dry run: the tasks a rule would change right now and how, whatever its events; the rule
+/// doesn't have to be saved
+#[tauri::command]
+pub async fn test_rule(
+    app_handle: tauri::AppHandle,
+    mut rule: Rule,
+) -> Result<Vec<RuleTestMatch>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    validate(&mut rule)?;
+    let ids: Vec<String> =
+        sqlx::query_scalar("SELECT id FROM tasks WHERE archived_at IS NULL ORDER BY sort_order")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(fetch_by_ids(&pool, &ids)
+        .await?
+        .into_iter()
+        .filter_map(|mut task| {
+            let changes = rule.apply(&mut task);
+            (!changes.is_empty()).then_some(RuleTestMatch {
+                task_id: task.id,
+                title: task.title,
+                changes,
+            })
+        })
+        .collect())
+}
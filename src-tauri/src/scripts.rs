@@ -0,0 +1,623 @@
+use chrono::{
+    DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, SecondsFormat, Utc,
+};
+use lazy_static::lazy_static;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Map, Scope};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::bulk::{self, BulkOp};
+use crate::quick_add::to_iso;
+use crate::tasks::{fetch_by_ids, new_id, now_iso, Task};
+use crate::webhooks::TaskEvent;
+use crate::{collation, db, lock, read_only};
+
+const RUN_INTERVAL: Duration = Duration::from_secs(60);
+// a script that runs longer than this is stopped, its changes are dropped
+const TIME_LIMIT: Duration = Duration::from_secs(5);
+const MAX_OPERATIONS: u64 = 5_000_000;
+// printed lines kept per run
+const MAX_OUTPUT: usize = 200;
+
+lazy_static! {
+    // scheduled and manual runs must not race on checked_at
+    static ref RUNNING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+/// when a script runs by itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ScriptTrigger {
+    /// only from the scripts settings
+    Manual,
+    /// once a day at a local "HH:MM"; a run missed while the app was closed happens on start
+    /// if it's still the same day
+    Daily { at: String },
+    /// once per task the event happened to, with the task in `task`
+    Event { event: TaskEvent },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Script {
+    pub id: String,
+    pub name: String,
+    pub source: String,
+    pub trigger: ScriptTrigger,
+    pub enabled: bool,
+    pub last_run_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, FromRow)]
+struct ScriptRow {
+    id: String,
+    name: String,
+    source: String,
+    trigger: String,
+    enabled: bool,
+    checked_at: String,
+    last_run_at: Option<String>,
+    last_error: Option<String>,
+    created_at: String,
+}
+
+impl ScriptRow {
+    fn trigger(&self) -> ScriptTrigger {
+        serde_json::from_str(&self.trigger).unwrap_or(ScriptTrigger::Manual)
+    }
+}
+
+impl From<ScriptRow> for Script {
+    fn from(row: ScriptRow) -> Self {
+        Script {
+            trigger: row.trigger(),
+            id: row.id,
+            name: row.name,
+            source: row.source,
+            enabled: row.enabled,
+            last_run_at: row.last_run_at,
+            last_error: row.last_error,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// what a run did
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRun {
+    /// tasks changed by the script's calls
+    pub changed: usize,
+    /// lines the script printed
+    pub output: Vec<String>,
+}
+
+// everything a script can see, loaded before it runs
+struct Context {
+    tasks: Vec<Task>,
+    // (id, name, account name)
+    lists: Vec<(String, String, String)>,
+    // (id, name)
+    tags: Vec<(String, String)>,
+}
+
+impl Context {
+    async fn load(pool: &SqlitePool) -> Result<Self, String> {
+        let ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM tasks WHERE archived_at IS NULL ORDER BY sort_order",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        let lists = sqlx::query_as(
+            "SELECT calendars.id, calendars.display_name, accounts.name FROM calendars
+             JOIN accounts ON accounts.id = calendars.account_id
+             ORDER BY accounts.name COLLATE NOCASE, calendars.display_name COLLATE NOCASE",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        let tags = sqlx::query_as("SELECT id, name FROM tags ORDER BY name COLLATE NOCASE")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Context {
+            tasks: fetch_by_ids(pool, &ids).await?,
+            lists,
+            tags,
+        })
+    }
+
+    fn task_map(&self, task: &Task) -> Map {
+        let optional = |value: &Option<String>| value.clone().map_or(Dynamic::UNIT, Dynamic::from);
+        let tags: Array = task
+            .tags
+            .iter()
+            .filter_map(|id| self.tags.iter().find(|(tag_id, _)| tag_id == id))
+            .map(|(_, name)| Dynamic::from(name.clone()))
+            .collect();
+        let contexts: Array = task.contexts.iter().cloned().map(Dynamic::from).collect();
+        let list = self
+            .lists
+            .iter()
+            .find(|(id, ..)| *id == task.calendar_id)
+            .map_or_else(|| "Local".to_string(), |(_, name, _)| name.clone());
+
+        let mut map = Map::new();
+        map.insert("id".into(), task.id.clone().into());
+        map.insert("uid".into(), task.uid.clone().into());
+        map.insert("title".into(), task.title.clone().into());
+        map.insert("description".into(), task.description.clone().into());
+        map.insert("completed".into(), task.completed.into());
+        map.insert("priority".into(), task.priority.clone().into());
+        map.insert("start".into(), optional(&task.start_date));
+        map.insert("due".into(), optional(&task.due_date));
+        map.insert("due_all_day".into(), task.due_date_all_day.into());
+        map.insert("created".into(), task.created_at.clone().into());
+        map.insert("tags".into(), tags.into());
+        map.insert("contexts".into(), contexts.into());
+        map.insert("list".into(), list.into());
+        map.insert("list_id".into(), task.calendar_id.clone().into());
+        map.insert("parent_uid".into(), optional(&task.parent_uid));
+        map
+    }
+}
+
+// "YYYY-MM-DD" is an all-day date, anything else has to be a full iso timestamp
+fn parse_due(date: &str) -> Result<(String, bool), Box<EvalAltResult>> {
+    if let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        let due = to_iso(day.and_time(NaiveTime::MIN))
+            .ok_or_else(|| format!("{date} doesn't exist in the local time zone"))?;
+        return Ok((due, true));
+    }
+    DateTime::parse_from_rfc3339(date)
+        .map(|due| {
+            let due = due.with_timezone(&Utc);
+            (due.to_rfc3339_opts(SecondsFormat::Millis, true), false)
+        })
+        .map_err(|_| format!("Expected YYYY-MM-DD or an iso timestamp, got \"{date}\"").into())
+}
+
+fn local_day(days_from_today: i64) -> String {
+    (Local::now().date_naive() + ChronoDuration::days(days_from_today))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+// a sandboxed engine: no modules, no eval, bounded time and memory
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_modules(0)
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(1 << 20)
+        .set_max_array_size(100_000)
+        .set_max_map_size(10_000)
+        .disable_symbol("eval");
+
+    engine.register_fn("today", || local_day(0));
+    engine.register_fn("days_from_today", local_day);
+    // the local date of a timestamp, to compare with today()
+    engine.register_fn("local_date", |date: &str| -> Dynamic {
+        DateTime::parse_from_rfc3339(date)
+            .map(|date| date.with_timezone(&Local).format("%Y-%m-%d").to_string())
+            .map_or(Dynamic::UNIT, Dynamic::from)
+    });
+    engine.register_fn("local_date", |_: ()| Dynamic::UNIT);
+    engine
+}
+
+/// check that a script compiles, for the editor
+pub fn check(source: &str) -> Result<(), String> {
+    engine()
+        .compile(source)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// what a script asked for: changes by task id, and the lines it printed
+struct Evaluation {
+    ops: Vec<(String, BulkOp)>,
+    output: Vec<String>,
+}
+
+// run the script against the context; the changes it asks for are only collected
+fn evaluate(source: &str, context: Context, task: Option<Task>) -> Result<Evaluation, String> {
+    let context = Rc::new(context);
+    let ops: Rc<RefCell<Vec<(String, BulkOp)>>> = Rc::default();
+    let output: Rc<RefCell<Vec<String>>> = Rc::default();
+    let mut engine = engine();
+
+    let printed = output.clone();
+    engine.on_print(move |line| {
+        let mut output = printed.borrow_mut();
+        if output.len() < MAX_OUTPUT {
+            output.push(line.to_string());
+        }
+    });
+    let started = Instant::now();
+    engine.on_progress(move |_| {
+        (started.elapsed() > TIME_LIMIT).then(|| Dynamic::from("time limit exceeded"))
+    });
+
+    let known = {
+        let context = context.clone();
+        move |id: &str| -> Result<String, Box<EvalAltResult>> {
+            match context.tasks.iter().any(|task| task.id == id) {
+                true => Ok(id.to_string()),
+                false => Err(format!("Unknown task id \"{id}\"").into()),
+            }
+        }
+    };
+    let queue = {
+        let ops = ops.clone();
+        move |id: String, op: BulkOp| ops.borrow_mut().push((id, op))
+    };
+
+    let (known_, queue_) = (known.clone(), queue.clone());
+    engine.register_fn(
+        "complete",
+        move |id: &str| -> Result<(), Box<EvalAltResult>> {
+            queue_(known_(id)?, BulkOp::Complete { completed: true });
+            Ok(())
+        },
+    );
+    let (known_, queue_) = (known.clone(), queue.clone());
+    engine.register_fn(
+        "reopen",
+        move |id: &str| -> Result<(), Box<EvalAltResult>> {
+            queue_(known_(id)?, BulkOp::Complete { completed: false });
+            Ok(())
+        },
+    );
+    let (known_, queue_) = (known.clone(), queue.clone());
+    engine.register_fn(
+        "set_due",
+        move |id: &str, date: &str| -> Result<(), Box<EvalAltResult>> {
+            let (due_date, all_day) = parse_due(date)?;
+            let op = BulkOp::SetDue {
+                due_date: Some(due_date),
+                all_day,
+            };
+            queue_(known_(id)?, op);
+            Ok(())
+        },
+    );
+    let (known_, queue_) = (known.clone(), queue.clone());
+    engine.register_fn(
+        "clear_due",
+        move |id: &str| -> Result<(), Box<EvalAltResult>> {
+            let op = BulkOp::SetDue {
+                due_date: None,
+                all_day: false,
+            };
+            queue_(known_(id)?, op);
+            Ok(())
+        },
+    );
+    let (known_, queue_, context_) = (known.clone(), queue.clone(), context.clone());
+    engine.register_fn(
+        "add_tag",
+        move |id: &str, name: &str| -> Result<(), Box<EvalAltResult>> {
+            let (tag_id, _) = context_
+                .tags
+                .iter()
+                .find(|(_, tag)| collation::fold(tag) == collation::fold(name))
+                .ok_or_else(|| format!("Unknown tag \"{name}\""))?;
+            queue_(
+                known_(id)?,
+                BulkOp::AddTag {
+                    tag_id: tag_id.clone(),
+                },
+            );
+            Ok(())
+        },
+    );
+    let (known_, queue_, context_) = (known, queue, context.clone());
+    // by list id, or by name when that's unique
+    engine.register_fn(
+        "move_to_list",
+        move |id: &str, list: &str| -> Result<(), Box<EvalAltResult>> {
+            let matching: Vec<&String> = context_
+                .lists
+                .iter()
+                .filter(|(list_id, name, _)| {
+                    list_id == list || collation::fold(name) == collation::fold(list)
+                })
+                .map(|(list_id, ..)| list_id)
+                .collect();
+            let calendar_id = match matching[..] {
+                [calendar_id] => calendar_id.clone(),
+                [] => return Err(format!("Unknown list \"{list}\"").into()),
+                _ => return Err(format!("More than one list is called \"{list}\"").into()),
+            };
+            queue_(known_(id)?, BulkOp::MoveToList { calendar_id });
+            Ok(())
+        },
+    );
+
+    let mut scope = Scope::new();
+    let tasks: Array = context
+        .tasks
+        .iter()
+        .map(|task| context.task_map(task).into())
+        .collect();
+    let lists: Array = context
+        .lists
+        .iter()
+        .map(|(id, name, account)| {
+            let mut map = Map::new();
+            map.insert("id".into(), id.clone().into());
+            map.insert("name".into(), name.clone().into());
+            map.insert("account".into(), account.clone().into());
+            map.into()
+        })
+        .collect();
+    let tags: Array = context
+        .tags
+        .iter()
+        .map(|(_, name)| Dynamic::from(name.clone()))
+        .collect();
+    scope.push("tasks", tasks);
+    scope.push("lists", lists);
+    scope.push("tags", tags);
+    scope.push(
+        "task",
+        task.map_or(Dynamic::UNIT, |task| context.task_map(&task).into()),
+    );
+
+    let ast = engine.compile(source).map_err(|e| e.to_string())?;
+    engine
+        .run_ast_with_scope(&mut scope, &ast)
+        .map_err(|e| e.to_string())?;
+    drop(engine);
+
+    Ok(Evaluation {
+        ops: ops.take(),
+        output: output.take(),
+    })
+}
+
+// run a script and apply what it asked for; same changes are applied together
+async fn execute(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    source: &str,
+    task: Option<Task>,
+) -> Result<ScriptRun, String> {
+    let context = Context::load(pool).await?;
+    let source = source.to_string();
+    let Evaluation { ops, output } =
+        tauri::async_runtime::spawn_blocking(move || evaluate(&source, context, task))
+            .await
+            .map_err(|e| e.to_string())??;
+
+    let mut grouped: Vec<(BulkOp, Vec<String>)> = Vec::new();
+    for (id, op) in ops {
+        match grouped.iter_mut().find(|(grouped, _)| *grouped == op) {
+            Some((_, ids)) => ids.push(id),
+            None => grouped.push((op, vec![id])),
+        }
+    }
+    let mut run = ScriptRun {
+        output,
+        ..ScriptRun::default()
+    };
+    for (op, ids) in grouped {
+        run.changed += bulk::update(app_handle, &ids, &op).await?;
+    }
+    Ok(run)
+}
+
+// run a stored script and remember how it went
+async fn run_row(
+    app_handle: &tauri::AppHandle,
+    pool: &SqlitePool,
+    script: &ScriptRow,
+    task: Option<Task>,
+) -> Result<ScriptRun, String> {
+    let result = execute(app_handle, pool, &script.source, task).await;
+    match &result {
+        Ok(run) => log::info!(
+            "Script \"{}\" ran and changed {} tasks",
+            script.name,
+            run.changed
+        ),
+        Err(e) => log::warn!("Script \"{}\" failed: {e}", script.name),
+    }
+    sqlx::query("UPDATE scripts SET last_run_at = ?, last_error = ? WHERE id = ?")
+        .bind(now_iso())
+        .bind(result.as_ref().err())
+        .bind(&script.id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    result
+}
+
+// the daily run that's due today, if the script hasn't had it yet
+fn daily_due(at: &str, checked_at: &str) -> bool {
+    let Ok(at) = NaiveTime::parse_from_str(at, "%H:%M") else {
+        return false;
+    };
+    let now = Local::now();
+    let Some(scheduled) = now
+        .date_naive()
+        .and_time(at)
+        .and_local_timezone(Local)
+        .earliest()
+    else {
+        return false;
+    };
+    let checked_at =
+        DateTime::parse_from_rfc3339(checked_at).map(|date| date.with_timezone(&Local));
+    scheduled <= now && checked_at.is_ok_and(|checked_at| checked_at < scheduled)
+}
+
+/// run the enabled scripts whose trigger fired since the last look
+#[tracing::instrument(
+    name = "scheduled_scripts",
+    level = "trace",
+    skip_all,
+    err(level = "debug")
+)]
+pub async fn run_scheduled(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    if read_only::is_enabled() {
+        return Ok(());
+    }
+    let pool = db::pool(app_handle).await?;
+    let _running = RUNNING.lock().await;
+    let scripts: Vec<ScriptRow> =
+        sqlx::query_as("SELECT * FROM scripts WHERE enabled = 1 ORDER BY created_at")
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    for script in scripts {
+        let now = now_iso();
+        match script.trigger() {
+            ScriptTrigger::Manual => continue,
+            ScriptTrigger::Daily { at } => {
+                if daily_due(&at, &script.checked_at) {
+                    let _ = run_row(app_handle, &pool, &script, None).await;
+                }
+            }
+            ScriptTrigger::Event { event } => {
+                let ids = event.task_ids(&pool, &script.checked_at, &now).await?;
+                for task in fetch_by_ids(&pool, &ids).await? {
+                    let _ = run_row(app_handle, &pool, &script, Some(task)).await;
+                }
+            }
+        }
+        sqlx::query("UPDATE scripts SET checked_at = ? WHERE id = ?")
+            .bind(&now)
+            .bind(&script.id)
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// check the script triggers every minute
+pub fn start_scheduled_runs(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(RUN_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_scheduled(&app_handle).await {
+                log::warn!("Scheduled scripts failed: {e}");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_scripts(app_handle: tauri::AppHandle) -> Result<Vec<Script>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let rows: Vec<ScriptRow> = sqlx::query_as("SELECT * FROM scripts ORDER BY created_at")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(Script::from).collect())
+}
+
+/// add a script (without an id) or change one; it has to compile. triggers only fire for
+/// what happens after saving
+#[tauri::command]
+pub async fn save_script(
+    app_handle: tauri::AppHandle,
+    id: Option<String>,
+    name: String,
+    source: String,
+    trigger: ScriptTrigger,
+    enabled: bool,
+) -> Result<Script, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Scripts need a name".to_string());
+    }
+    if let ScriptTrigger::Daily { at } = &trigger {
+        NaiveTime::parse_from_str(at, "%H:%M")
+            .map_err(|_| format!("Expected a time like 08:00, got \"{at}\""))?;
+    }
+    check(&source)?;
+    let trigger = serde_json::to_string(&trigger).map_err(|e| e.to_string())?;
+
+    let _running = RUNNING.lock().await;
+    let now = now_iso();
+    let id = id.unwrap_or_else(new_id);
+    sqlx::query(
+        "INSERT INTO scripts (id, name, source, trigger, enabled, checked_at, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+         ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name, source = excluded.source, trigger = excluded.trigger,
+            enabled = excluded.enabled, checked_at = excluded.checked_at",
+    )
+    .bind(&id)
+    .bind(&name)
+    .bind(&source)
+    .bind(&trigger)
+    .bind(enabled)
+    .bind(&now)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let row: ScriptRow = sqlx::query_as("SELECT * FROM scripts WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(row.into())
+}
+
+#[tauri::command]
+pub async fn delete_script(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("DELETE FROM scripts WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// run a script now, whatever its trigger; `task` is unset
+#[tauri::command]
+pub async fn run_script(app_handle: tauri::AppHandle, id: String) -> Result<ScriptRun, String> {
+    read_only::ensure_writable()?;
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    let script: ScriptRow = sqlx::query_as("SELECT * FROM scripts WHERE id = ?")
+        .bind(&id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Script {id} not found"))?;
+    let _running = RUNNING.lock().await;
+    run_row(&app_handle, &pool, &script, None).await
+}
+
+/// compile errors of a script, for the editor
+#[tauri::command]
+pub fn check_script(source: String) -> Result<(), String> {
+    check(&source)
+}
@@ -0,0 +1,658 @@
+use chrono::{Duration, Local, NaiveDate};
+use serde::Serialize;
+use sqlx::{FromRow, QueryBuilder, Sqlite};
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::collation::{self, FOLDED};
+use crate::dependencies::BLOCKED;
+use crate::{contexts, db, lock};
+
+// most hits global_search returns
+const GLOBAL_SEARCH_LIMIT: i64 = 50;
+
+// one of the task's tags has a name matching ?1
+const TAG_MATCH: &str = "EXISTS (SELECT 1 FROM json_each(tasks.tags)
+        JOIN tags ON tags.id = json_each.value WHERE tags.name REGEXP ?1)";
+
+// open and past its due date; all-day tasks are due until their day is over
+const OVERDUE: &str = "completed = 0 AND due_date IS NOT NULL
+        AND CASE WHEN due_date_all_day = 1
+            THEN date(due_date, 'localtime') < date('now', 'localtime')
+            ELSE julianday(due_date) < julianday('now') END";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn sql(self) -> &'static str {
+        match self {
+            Op::Eq => " = ",
+            Op::Lt => " < ",
+            Op::Le => " <= ",
+            Op::Gt => " > ",
+            Op::Ge => " >= ",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    /// a word or a quoted phrase
+    Text(String),
+    /// `key:value`, `key<value` and the like
+    Field(String, Op, String),
+}
+
+#[derive(Debug, Clone)]
+enum Part {
+    Sql(String),
+    Bind(String),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Text(String),
+    /// compiled when the query is parsed, so mistakes show up right away
+    Filter(Vec<Part>),
+}
+
+/// a parsed search query; free text matches title, description and checklist items
+#[derive(Debug, Clone)]
+pub struct Query(Expr);
+
+fn quoted(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    let mut text = String::new();
+    for c in chars.by_ref() {
+        if c == '"' {
+            return Ok(text);
+        }
+        text.push(c);
+    }
+    Err("Missing closing quote".to_string())
+}
+
+fn word(chars: &mut Peekable<Chars>) -> String {
+    let mut word = String::new();
+    while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | '"')) {
+        word.push(c);
+    }
+    word
+}
+
+// `key:value` when the word starts with letters followed by an operator; a quoted value
+// right after the operator belongs to the field
+fn field(word: &str, chars: &mut Peekable<Chars>) -> Result<Option<Token>, String> {
+    let key_len = word
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(word.len());
+    let (key, rest) = word.split_at(key_len);
+    let (op, value) = if let Some(value) = rest.strip_prefix("<=") {
+        (Op::Le, value)
+    } else if let Some(value) = rest.strip_prefix(">=") {
+        (Op::Ge, value)
+    } else if let Some(value) = rest.strip_prefix('<') {
+        (Op::Lt, value)
+    } else if let Some(value) = rest.strip_prefix('>') {
+        (Op::Gt, value)
+    } else if let Some(value) = rest.strip_prefix(':').or_else(|| rest.strip_prefix('=')) {
+        (Op::Eq, value)
+    } else {
+        return Ok(None);
+    };
+    if key.is_empty() {
+        return Ok(None);
+    }
+    let value = if value.is_empty() && chars.next_if_eq(&'"').is_some() {
+        quoted(chars)?
+    } else {
+        value.to_string()
+    };
+    Ok(Some(Token::Field(key.to_lowercase(), op, value)))
+}
+
+fn lex(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let token = match c {
+            '(' => {
+                chars.next();
+                Token::Open
+            }
+            ')' => {
+                chars.next();
+                Token::Close
+            }
+            '"' => {
+                chars.next();
+                Token::Text(quoted(&mut chars)?)
+            }
+            // `-tag:work` leaves out what follows
+            '-' => {
+                chars.next();
+                Token::Not
+            }
+            _ => {
+                let word = word(&mut chars);
+                match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => field(&word, &mut chars)?.unwrap_or(Token::Text(word)),
+                }
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+// yyyy-mm-dd, today, tomorrow or yesterday, as a local date
+fn date(value: &str) -> Result<String, String> {
+    let today = Local::now().date_naive();
+    let date = match value.to_lowercase().as_str() {
+        "today" => today,
+        "tomorrow" => today + Duration::days(1),
+        "yesterday" => today - Duration::days(1),
+        _ => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| format!("Unknown date {value}, use yyyy-mm-dd or today"))?,
+    };
+    Ok(date.format("%Y-%m-%d").to_string())
+}
+
+fn sql(sql: &str) -> Vec<Part> {
+    vec![Part::Sql(sql.to_string())]
+}
+
+// the sql for one `key:value` filter
+fn filter(key: &str, op: Op, value: &str) -> Result<Vec<Part>, String> {
+    let equals_only = || {
+        if op == Op::Eq {
+            Ok(())
+        } else {
+            Err(format!("{key}: only supports {key}:value"))
+        }
+    };
+    let column = match key {
+        "due" => Some("due_date"),
+        "start" => Some("start_date"),
+        "created" => Some("created_at"),
+        "modified" => Some("modified_at"),
+        "completed" | "done" => Some("completed_at"),
+        _ => None,
+    };
+    if let Some(column) = column {
+        if value.eq_ignore_ascii_case("none") {
+            equals_only()?;
+            return Ok(sql(&format!("{column} IS NULL")));
+        }
+        return Ok(vec![
+            Part::Sql(format!("date({column}, 'localtime'){}", op.sql())),
+            Part::Bind(date(value)?),
+        ]);
+    }
+
+    equals_only()?;
+    let lower = value.to_lowercase();
+    let parts = match key {
+        "tag" => vec![
+            Part::Sql(
+                "EXISTS (SELECT 1 FROM json_each(tasks.tags) JOIN tags ON tags.id = json_each.value
+                 WHERE tags.name = "
+                    .to_string(),
+            ),
+            Part::Bind(value.to_string()),
+            Part::Sql(format!(" COLLATE {FOLDED})")),
+        ],
+        "list" => vec![
+            Part::Sql("calendar_id IN (SELECT id FROM calendars WHERE display_name = ".to_string()),
+            Part::Bind(value.to_string()),
+            Part::Sql(format!(" COLLATE {FOLDED})")),
+        ],
+        "context" => vec![
+            Part::Sql(
+                "EXISTS (SELECT 1 FROM json_each(tasks.contexts) WHERE json_each.value = "
+                    .to_string(),
+            ),
+            Part::Bind(contexts::normalize(value)?),
+            Part::Sql(format!(" COLLATE {FOLDED})")),
+        ],
+        "priority" => match lower.as_str() {
+            "high" | "medium" | "low" | "none" => {
+                vec![Part::Sql("priority = ".to_string()), Part::Bind(lower)]
+            }
+            _ => return Err(format!("Unknown priority {value}, use high, medium, low or none")),
+        },
+        "is" => match lower.as_str() {
+            "open" => sql("completed = 0"),
+            "done" | "completed" => sql("completed = 1"),
+            "overdue" => sql(OVERDUE),
+            "blocked" => sql(BLOCKED),
+            "unsynced" => sql("synced = 0"),
+            _ => {
+                return Err(format!(
+                    "Unknown is:{value}, use open, done, overdue, blocked or unsynced"
+                ))
+            }
+        },
+        "has" => match lower.as_str() {
+            "subtasks" => sql("EXISTS (SELECT 1 FROM tasks AS child WHERE child.parent_uid = tasks.uid)"),
+            "checklist" => sql("json_array_length(COALESCE(subtasks, '[]')) > 0"),
+            "parent" => sql("parent_uid IS NOT NULL"),
+            "due" => sql("due_date IS NOT NULL"),
+            "start" => sql("start_date IS NOT NULL"),
+            "tags" => sql("json_array_length(COALESCE(tags, '[]')) > 0"),
+            "description" => sql("description != ''"),
+            "reminders" => sql("json_array_length(COALESCE(reminders, '[]')) > 0"),
+            "url" => sql("COALESCE(url, '') != ''"),
+            "estimate" => sql("estimated_duration IS NOT NULL"),
+            "dependencies" => sql(
+                "EXISTS (SELECT 1 FROM task_dependencies WHERE task_dependencies.task_uid = tasks.uid)",
+            ),
+            _ => {
+                return Err(format!(
+                    "Unknown has:{value}, use subtasks, checklist, parent, due, start, tags, \
+                     description, reminders, url, estimate or dependencies"
+                ))
+            }
+        },
+        _ => return Err(format!("Unknown filter {key}:")),
+    };
+    Ok(parts)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    // AND binds tighter than OR
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut query = self.and()?;
+        while self.eat(&Token::Or) {
+            query = Expr::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    // terms next to each other are AND-ed as well
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut query = self.unary()?;
+        loop {
+            let next = self.eat(&Token::And)
+                || matches!(
+                    self.peek(),
+                    Some(Token::Open | Token::Not | Token::Text(_) | Token::Field(..))
+                );
+            if !next {
+                return Ok(query);
+            }
+            query = Expr::And(Box::new(query), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        let token = self.peek().cloned();
+        self.position += 1;
+        match token {
+            Some(Token::Open) => {
+                let query = self.or()?;
+                if !self.eat(&Token::Close) {
+                    return Err("Missing closing parenthesis".to_string());
+                }
+                Ok(query)
+            }
+            Some(Token::Text(text)) => Ok(Expr::Text(text)),
+            Some(Token::Field(key, op, value)) => Ok(Expr::Filter(filter(&key, op, &value)?)),
+            Some(Token::Close) => Err("Unexpected closing parenthesis".to_string()),
+            Some(Token::And | Token::Or | Token::Not) | None => {
+                Err("AND, OR and NOT need a term to apply to".to_string())
+            }
+        }
+    }
+}
+
+/// parse a search query; plain text without filters or operators gives None, so it keeps
+/// matching as one phrase
+pub fn parse(text: &str) -> Result<Option<Query>, String> {
+    let tokens = lex(text)?;
+    if tokens.iter().all(|token| matches!(token, Token::Text(_))) {
+        return Ok(None);
+    }
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let expr = parser.or()?;
+    if parser.position < parser.tokens.len() {
+        return Err("Unexpected closing parenthesis".to_string());
+    }
+    Ok(Some(Query(expr)))
+}
+
+/// append the condition for free text: title, description or a checklist item contains it,
+/// ignoring case and accents
+pub fn push_text(query: &mut QueryBuilder<'_, Sqlite>, text: &str) {
+    let pattern = collation::contains_pattern(text);
+    query
+        .push("(title REGEXP ")
+        .push_bind(pattern.clone())
+        .push(" OR description REGEXP ")
+        .push_bind(pattern.clone())
+        .push(" OR EXISTS (SELECT 1 FROM json_each(tasks.subtasks) WHERE json_extract(json_each.value, '$.title') REGEXP ")
+        .push_bind(pattern)
+        .push("))");
+}
+
+impl Expr {
+    fn push_sql(&self, query: &mut QueryBuilder<'_, Sqlite>) {
+        match self {
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                query.push("(");
+                left.push_sql(query);
+                query.push(if matches!(self, Expr::And(..)) {
+                    " AND "
+                } else {
+                    " OR "
+                });
+                right.push_sql(query);
+                query.push(")");
+            }
+            // conditions on empty columns are NULL, which NOT would leave out as well
+            Expr::Not(inner) => {
+                query.push("((");
+                inner.push_sql(query);
+                query.push(") IS NOT 1)");
+            }
+            Expr::Text(text) => push_text(query, text),
+            Expr::Filter(parts) => {
+                query.push("(");
+                for part in parts {
+                    match part {
+                        Part::Sql(sql) => {
+                            query.push(sql);
+                        }
+                        Part::Bind(value) => {
+                            query.push_bind(value.clone());
+                        }
+                    }
+                }
+                query.push(")");
+            }
+        }
+    }
+}
+
+impl Query {
+    /// append the query as a condition on `tasks`
+    pub fn push_sql(&self, query: &mut QueryBuilder<'_, Sqlite>) {
+        self.0.push_sql(query);
+    }
+}
+
+/// check a search query, so the search box and saved filters can point out mistakes
+#[tauri::command]
+pub fn validate_search_query(query: String) -> Result<(), String> {
+    parse(query.trim()).map(|_| ())
+}
+
+/// a task found by global_search
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub id: String,
+    pub uid: String,
+    pub title: String,
+    pub completed: bool,
+    pub due_date: Option<String>,
+    /// where the query matched first: title, tag or description
+    pub matched: String,
+    pub score: f64,
+    #[serde(skip)]
+    calendar_id: Option<String>,
+    #[serde(skip)]
+    list: String,
+    #[serde(skip)]
+    account: String,
+}
+
+/// the hits in one list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchGroup {
+    pub calendar_id: Option<String>,
+    pub list: String,
+    pub account: String,
+    pub hits: Vec<SearchHit>,
+}
+
+/// search every account and list for a jump-to-task palette: title matches rank above tag
+/// matches above description matches, exact and prefix title matches first. recently
+/// changed tasks get a boost and completed ones sink. hits are grouped per list, the list
+/// with the best hit first
+#[tauri::command]
+pub async fn global_search(
+    app_handle: tauri::AppHandle,
+    query: String,
+) -> Result<Vec<SearchGroup>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let contains = collation::contains_pattern(query);
+    let exact = format!("^{contains}$");
+    let prefix = format!("^{contains}");
+
+    let hits: Vec<SearchHit> = sqlx::query_as(&format!(
+        "SELECT tasks.id, tasks.uid, tasks.title, tasks.completed, tasks.due_date,
+            tasks.calendar_id, COALESCE(calendars.display_name, '') AS list,
+            COALESCE(accounts.name, '') AS account,
+            CASE WHEN tasks.title REGEXP ?1 THEN 'title'
+                WHEN {TAG_MATCH} THEN 'tag' ELSE 'description' END AS matched,
+            CASE WHEN tasks.title REGEXP ?2 THEN 100
+                WHEN tasks.title REGEXP ?3 THEN 80
+                WHEN tasks.title REGEXP ?1 THEN 60 ELSE 0 END
+            + CASE WHEN {TAG_MATCH} THEN 30 ELSE 0 END
+            + CASE WHEN tasks.description REGEXP ?1 THEN 10 ELSE 0 END
+            -- up to 10 points for tasks changed in the last month
+            + MAX(0.0, 10 - (julianday('now') - julianday(tasks.modified_at)) / 3)
+            - CASE WHEN tasks.completed = 1 THEN 15 ELSE 0 END AS score
+         FROM tasks
+         LEFT JOIN calendars ON calendars.id = tasks.calendar_id
+         LEFT JOIN accounts ON accounts.id = tasks.account_id
+         WHERE tasks.archived_at IS NULL
+            AND (tasks.title REGEXP ?1 OR tasks.description REGEXP ?1 OR {TAG_MATCH})
+         ORDER BY score DESC, tasks.title COLLATE NOCASE
+         LIMIT ?4"
+    ))
+    .bind(&contains)
+    .bind(&exact)
+    .bind(&prefix)
+    .bind(GLOBAL_SEARCH_LIMIT)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut groups: Vec<SearchGroup> = Vec::new();
+    for hit in hits {
+        match groups
+            .iter_mut()
+            .find(|group| group.calendar_id == hit.calendar_id)
+        {
+            Some(group) => group.hits.push(hit),
+            None => groups.push(SearchGroup {
+                calendar_id: hit.calendar_id.clone(),
+                list: hit.list.clone(),
+                account: hit.account.clone(),
+                hits: vec![hit],
+            }),
+        }
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compiled(text: &str) -> String {
+        let parsed = parse(text)
+            .expect("query should parse")
+            .expect("query should have filters");
+        let mut query = QueryBuilder::<Sqlite>::new("");
+        parsed.push_sql(&mut query);
+        query.sql().to_string()
+    }
+
+    fn error(text: &str) -> String {
+        parse(text).expect_err("query should be refused")
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            lex(r#"Tag:"Home Office" -is:done (a OR b)"#).unwrap(),
+            vec![
+                Token::Field("tag".to_string(), Op::Eq, "Home Office".to_string()),
+                Token::Not,
+                Token::Field("is".to_string(), Op::Eq, "done".to_string()),
+                Token::Open,
+                Token::Text("a".to_string()),
+                Token::Or,
+                Token::Text("b".to_string()),
+                Token::Close,
+            ]
+        );
+        assert_eq!(
+            lex("due>=today start=2025-01-01").unwrap(),
+            vec![
+                Token::Field("due".to_string(), Op::Ge, "today".to_string()),
+                Token::Field("start".to_string(), Op::Eq, "2025-01-01".to_string()),
+            ]
+        );
+        // no key, so not a field
+        assert_eq!(
+            lex("12:30").unwrap(),
+            vec![Token::Text("12:30".to_string())]
+        );
+    }
+
+    #[test]
+    fn plain_text_is_a_phrase() {
+        assert!(parse("buy milk and eggs").unwrap().is_none());
+        assert!(parse(r#""buy milk""#).unwrap().is_none());
+        assert!(parse("").unwrap().is_none());
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            compiled("has:due OR has:start has:url"),
+            "((due_date IS NOT NULL) OR ((start_date IS NOT NULL) AND (COALESCE(url, '') != '')))"
+        );
+        assert_eq!(
+            compiled("(has:due OR has:start) AND has:url"),
+            "(((due_date IS NOT NULL) OR (start_date IS NOT NULL)) AND (COALESCE(url, '') != ''))"
+        );
+    }
+
+    #[test]
+    fn negation_keeps_empty_columns() {
+        assert_eq!(compiled("-is:done"), "(((completed = 1)) IS NOT 1)");
+        assert_eq!(
+            compiled("NOT (is:open OR is:unsynced)"),
+            "((((completed = 0) OR (synced = 0))) IS NOT 1)"
+        );
+        assert_eq!(
+            compiled("NOT NOT has:parent"),
+            "(((((parent_uid IS NOT NULL)) IS NOT 1)) IS NOT 1)"
+        );
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(
+            compiled("due<2025-01-01"),
+            "(date(due_date, 'localtime') < ?)"
+        );
+        assert_eq!(compiled("done:NONE"), "(completed_at IS NULL)");
+        assert_eq!(
+            date("TODAY").unwrap(),
+            Local::now().date_naive().format("%Y-%m-%d").to_string()
+        );
+        assert!(date("2025-02-30").is_err());
+    }
+
+    #[test]
+    fn text_next_to_filters() {
+        let sql = compiled("milk is:open");
+        assert!(sql.starts_with("((title REGEXP ? OR description REGEXP ?"));
+        assert!(sql.ends_with(" AND (completed = 0))"));
+    }
+
+    #[test]
+    fn mistakes() {
+        assert_eq!(error(r#"is:open "buy milk"#), "Missing closing quote");
+        assert_eq!(error("(is:open"), "Missing closing parenthesis");
+        assert_eq!(error("is:open)"), "Unexpected closing parenthesis");
+        assert_eq!(error(") is:open"), "Unexpected closing parenthesis");
+        assert_eq!(
+            error("is:open AND"),
+            "AND, OR and NOT need a term to apply to"
+        );
+        assert_eq!(
+            error("OR is:open"),
+            "AND, OR and NOT need a term to apply to"
+        );
+        assert_eq!(
+            error("is:open -"),
+            "AND, OR and NOT need a term to apply to"
+        );
+        assert!(error("is:later").starts_with("Unknown is:later"));
+        assert!(error("has:everything").starts_with("Unknown has:everything"));
+        assert!(error("priority:urgent").starts_with("Unknown priority urgent"));
+        assert!(error("due:someday").starts_with("Unknown date someday"));
+        assert_eq!(error("tag<work"), "tag: only supports tag:value");
+        assert_eq!(error("due<none"), "due: only supports due:value");
+        assert_eq!(error("color:red"), "Unknown filter color:");
+        assert!(validate_search_query("  is:open  ".to_string()).is_ok());
+    }
+}
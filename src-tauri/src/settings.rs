@@ -0,0 +1,42 @@
+use sqlx::SqlitePool;
+
+/// read a backend setting (None if it was never set)
+pub async fn get(pool: &SqlitePool, key: &str) -> Result<Option<String>, String> {
+    sqlx::query_scalar::<_, String>("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// write a backend setting
+pub async fn set(pool: &SqlitePool, key: &str, value: &str) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO app_settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+/// remove a backend setting
+pub async fn delete(pool: &SqlitePool, key: &str) -> Result<(), String> {
+    sqlx::query("DELETE FROM app_settings WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// read a numeric setting, falling back to a default when missing or unparsable
+pub async fn get_u64(pool: &SqlitePool, key: &str, default: u64) -> Result<u64, String> {
+    Ok(get(pool, key)
+        .await?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default))
+}
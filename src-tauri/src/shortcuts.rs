@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::tasks::now_iso;
+use crate::{db, read_only, settings};
+
+// custom keyboard shortcuts are kept in the backend settings so they can be checked against
+// each other and the app menu before they're saved, and shared between machines as a profile
+// file. without custom ones the frontend uses its defaults
+
+const SHORTCUTS_KEY: &str = "keyboard_shortcuts";
+// bumped when the profile layout changes in a way older versions can't read
+const FORMAT_VERSION: u32 = 1;
+
+// accelerators of app menu items that have no keyboard shortcut setting (see utils/menu.ts),
+// with the item's name; the menu gets these keys before any shortcut does
+const MENU_ACCELERATORS: &[(&str, &str)] = &[
+    ("CmdOrCtrl+I", "Import..."),
+    ("CmdOrCtrl+E", "Export..."),
+    ("CmdOrCtrl+/", "Keyboard Shortcuts"),
+];
+
+// keys of the edit menu and quit, the system handles them before the app sees them
+const SYSTEM_ACCELERATORS: &[(&str, &str)] = &[
+    ("CmdOrCtrl+Z", "Undo"),
+    ("CmdOrCtrl+Shift+Z", "Redo"),
+    ("CmdOrCtrl+X", "Cut"),
+    ("CmdOrCtrl+C", "Copy"),
+    ("CmdOrCtrl+V", "Paste"),
+    ("CmdOrCtrl+A", "Select All"),
+    ("CmdOrCtrl+Q", "Quit"),
+];
+
+/// a key binding, in the frontend's shape
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shortcut {
+    pub id: String,
+    pub key: String,
+    /// Cmd on macOS, Ctrl elsewhere
+    #[serde(default)]
+    pub meta: bool,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub description: String,
+}
+
+// the keys a binding reacts to; meta also matches ctrl, so they count as one modifier
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct Keys {
+    command: bool,
+    shift: bool,
+    alt: bool,
+    key: String,
+}
+
+impl Shortcut {
+    fn keys(&self) -> Keys {
+        Keys {
+            command: self.meta || self.ctrl,
+            shift: self.shift,
+            alt: self.alt,
+            key: self.key.to_lowercase(),
+        }
+    }
+}
+
+fn accelerator_keys(accelerator: &str) -> Keys {
+    let mut keys = Keys {
+        command: false,
+        shift: false,
+        alt: false,
+        key: String::new(),
+    };
+    for part in accelerator.split('+') {
+        match part {
+            "CmdOrCtrl" | "Cmd" | "Ctrl" => keys.command = true,
+            "Shift" => keys.shift = true,
+            "Alt" => keys.alt = true,
+            key => keys.key = key.to_lowercase(),
+        }
+    }
+    keys
+}
+
+/// a shortcut whose keys are taken
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutConflict {
+    pub id: String,
+    /// the shortcut that has the keys first, or the menu item that owns them
+    pub conflicts_with: String,
+    /// the conflict is with the app menu or the system rather than another shortcut
+    pub reserved: bool,
+}
+
+fn find_conflicts(shortcuts: &[Shortcut]) -> Vec<ShortcutConflict> {
+    let reserved: HashMap<Keys, &str> = MENU_ACCELERATORS
+        .iter()
+        .chain(SYSTEM_ACCELERATORS)
+        .map(|(accelerator, name)| (accelerator_keys(accelerator), *name))
+        .collect();
+    let mut taken: HashMap<Keys, &str> = HashMap::new();
+    let mut conflicts = Vec::new();
+    for shortcut in shortcuts.iter().filter(|shortcut| !shortcut.key.is_empty()) {
+        let keys = shortcut.keys();
+        if let Some(name) = reserved.get(&keys) {
+            conflicts.push(ShortcutConflict {
+                id: shortcut.id.clone(),
+                conflicts_with: name.to_string(),
+                reserved: true,
+            });
+        } else if let Some(other) = taken.get(&keys) {
+            conflicts.push(ShortcutConflict {
+                id: shortcut.id.clone(),
+                conflicts_with: other.to_string(),
+                reserved: false,
+            });
+        } else {
+            taken.insert(keys, &shortcut.id);
+        }
+    }
+    conflicts
+}
+
+fn ensure_no_conflicts(shortcuts: &[Shortcut]) -> Result<(), String> {
+    match find_conflicts(shortcuts).first() {
+        Some(conflict) => Err(format!(
+            "The keys of {} are already used by {}",
+            conflict.id, conflict.conflicts_with
+        )),
+        None => Ok(()),
+    }
+}
+
+/// the custom shortcuts; None while the defaults are in use
+#[tauri::command]
+pub async fn get_shortcuts(app_handle: tauri::AppHandle) -> Result<Option<Vec<Shortcut>>, String> {
+    let pool = db::pool(&app_handle).await?;
+    settings::get(&pool, SHORTCUTS_KEY)
+        .await?
+        .map(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+/// shortcuts that share their keys with an earlier one, the app menu or the system
+#[tauri::command]
+pub fn check_shortcuts(shortcuts: Vec<Shortcut>) -> Vec<ShortcutConflict> {
+    find_conflicts(&shortcuts)
+}
+
+/// save the custom shortcuts, refused while any of them conflict; None goes back to the
+/// defaults
+#[tauri::command]
+pub async fn set_shortcuts(
+    app_handle: tauri::AppHandle,
+    shortcuts: Option<Vec<Shortcut>>,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    match shortcuts {
+        Some(shortcuts) => {
+            ensure_no_conflicts(&shortcuts)?;
+            let json = serde_json::to_string(&shortcuts).map_err(|e| e.to_string())?;
+            settings::set(&pool, SHORTCUTS_KEY, &json).await
+        }
+        None => settings::delete(&pool, SHORTCUTS_KEY).await,
+    }
+}
+
+/// a shortcuts profile as shared between machines
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShortcutsProfile {
+    version: u32,
+    exported_at: String,
+    shortcuts: Vec<Shortcut>,
+}
+
+/// write the shortcuts to a profile file
+#[tauri::command]
+pub fn export_shortcuts(path: String, shortcuts: Vec<Shortcut>) -> Result<(), String> {
+    let profile = ShortcutsProfile {
+        version: FORMAT_VERSION,
+        exported_at: now_iso(),
+        shortcuts,
+    };
+    let json = serde_json::to_string_pretty(&profile).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+    log::info!("Exported {} shortcuts", profile.shortcuts.len());
+    Ok(())
+}
+
+/// read a profile file and save its shortcuts, unless they conflict; returns them for the
+/// frontend to use
+#[tauri::command]
+pub async fn import_shortcuts(
+    app_handle: tauri::AppHandle,
+    path: String,
+) -> Result<Vec<Shortcut>, String> {
+    read_only::ensure_writable()?;
+    let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let profile: ShortcutsProfile =
+        serde_json::from_str(&json).map_err(|e| format!("Not a shortcuts profile: {e}"))?;
+    if profile.version > FORMAT_VERSION {
+        return Err(format!(
+            "The file was exported by a newer version (format {})",
+            profile.version
+        ));
+    }
+    set_shortcuts(app_handle, Some(profile.shortcuts.clone())).await?;
+    log::info!("Imported {} shortcuts", profile.shortcuts.len());
+    Ok(profile.shortcuts)
+}
@@ -0,0 +1,194 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{crypto, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+// just enough SMTP (RFC 5321) over tls with AUTH PLAIN to hand one message to the user's own
+// mail server. tls either from the start or after STARTTLS (RFC 3207); nothing is ever sent
+// in the clear after the greeting
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// how the connection to the server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// tls from the start, usually port 465
+    Tls,
+    /// a plain connection upgraded with STARTTLS, usually port 587
+    Starttls,
+}
+
+pub struct SmtpClient<S = TlsStream<TcpStream>> {
+    stream: BufReader<S>,
+}
+
+// an address inside <...>; anything that could end the command early is refused
+fn address(value: &str) -> Result<&str, String> {
+    let value = value.trim();
+    if value.is_empty() || value.contains(['\r', '\n', '<', '>']) || !value.contains('@') {
+        return Err(format!("Not an email address: {value}"));
+    }
+    Ok(value)
+}
+
+impl SmtpClient {
+    /// connect, secure the connection and log in
+    pub async fn connect(
+        host: &str,
+        port: u16,
+        security: SmtpSecurity,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, String> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let config =
+            ClientConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .map_err(|e| e.to_string())?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        let server_name =
+            ServerName::try_from(host.to_string()).map_err(|e| format!("Invalid host: {e}"))?;
+
+        let tcp = tokio::time::timeout(TIMEOUT, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| format!("Connecting to {host}:{port} timed out"))?
+            .map_err(|e| format!("Failed to connect to {host}:{port}: {e}"))?;
+        let tcp = match security {
+            SmtpSecurity::Tls => tcp,
+            SmtpSecurity::Starttls => {
+                let mut plain = SmtpClient {
+                    stream: BufReader::new(tcp),
+                };
+                plain.expect(220).await?;
+                let extensions = plain.command("EHLO caldav-tasks", 250).await?;
+                if !extensions
+                    .split_whitespace()
+                    .any(|extension| extension.eq_ignore_ascii_case("STARTTLS"))
+                {
+                    return Err(format!("{host} doesn't offer STARTTLS"));
+                }
+                plain.command("STARTTLS", 220).await?;
+                // anything already sent would be taken as coming over tls (CVE-2011-0411)
+                if !plain.stream.buffer().is_empty() {
+                    return Err(format!("{host} sent data before the TLS handshake"));
+                }
+                plain.stream.into_inner()
+            }
+        };
+        let tls = TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| format!("TLS handshake with {host} failed: {e}"))?;
+
+        let mut client = SmtpClient {
+            stream: BufReader::new(tls),
+        };
+        // the greeting came before the upgrade, and the session starts over after it
+        if security == SmtpSecurity::Tls {
+            client.expect(220).await?;
+        }
+        client.command("EHLO caldav-tasks", 250).await?;
+        let credentials = STANDARD.encode(format!("\0{username}\0{password}"));
+        client
+            .command(&format!("AUTH PLAIN {credentials}"), 235)
+            .await
+            .map_err(|e| format!("Login failed: {e}"))?;
+        Ok(client)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> SmtpClient<S> {
+    // the code and text of the next reply; multi-line replies ("250-...") are joined
+    async fn reply(&mut self) -> Result<(u16, String), String> {
+        let mut text = Vec::new();
+        loop {
+            let mut line = String::new();
+            let read = tokio::time::timeout(TIMEOUT, self.stream.read_line(&mut line))
+                .await
+                .map_err(|_| "The SMTP server stopped answering".to_string())?
+                .map_err(|e| e.to_string())?;
+            if read == 0 {
+                return Err("The SMTP server closed the connection".to_string());
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            let code = line
+                .get(..3)
+                .and_then(|code| code.parse::<u16>().ok())
+                .ok_or_else(|| format!("Unexpected reply: {line}"))?;
+            text.push(line.get(4..).unwrap_or_default().to_string());
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok((code, text.join(" ")));
+            }
+        }
+    }
+
+    async fn expect(&mut self, expected: u16) -> Result<String, String> {
+        let (code, text) = self.reply().await?;
+        if code != expected {
+            return Err(format!("{code} {text}"));
+        }
+        Ok(text)
+    }
+
+    async fn command(&mut self, command: &str, expected: u16) -> Result<String, String> {
+        self.stream
+            .get_mut()
+            .write_all(format!("{command}\r\n").as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        self.expect(expected).await
+    }
+
+    /// send a complete message (headers and body, CRLF line ends) to the recipients
+    pub async fn send(&mut self, from: &str, to: &[String], message: &str) -> Result<(), String> {
+        self.command(&format!("MAIL FROM:<{}>", address(from)?), 250)
+            .await?;
+        for recipient in to {
+            let command = format!("RCPT TO:<{}>", address(recipient)?);
+            self.stream
+                .get_mut()
+                .write_all(format!("{command}\r\n").as_bytes())
+                .await
+                .map_err(|e| e.to_string())?;
+            // 251: accepted and forwarded elsewhere
+            match self.reply().await? {
+                (250 | 251, _) => {}
+                (code, text) => return Err(format!("{recipient} was refused: {code} {text}")),
+            }
+        }
+        self.command("DATA", 354).await?;
+        // a line starting with a dot would end the message early (RFC 5321 4.5.2)
+        let mut data = String::with_capacity(message.len() + 5);
+        for line in message.split("\r\n") {
+            if line.starts_with('.') {
+                data.push('.');
+            }
+            data.push_str(line);
+            data.push_str("\r\n");
+        }
+        data.push_str(".\r\n");
+        self.stream
+            .get_mut()
+            .write_all(data.as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        self.expect(250).await?;
+        Ok(())
+    }
+
+    /// end the session; errors don't matter anymore at this point
+    pub async fn quit(mut self) {
+        let _ = self.command("QUIT", 221).await;
+    }
+}
@@ -0,0 +1,163 @@
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{db, lock, read_only};
+
+// copies of the database taken right before new migrations run, so a release whose migration
+// goes wrong can't take the task history with it
+
+// how many snapshots to keep, oldest are removed first
+const KEEP_SNAPSHOTS: usize = 3;
+const PREFIX: &str = "caldav-tasks-v";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbSnapshot {
+    pub file_name: String,
+    /// the schema version the database had
+    pub version: i64,
+    /// unix seconds
+    pub created_at: u64,
+    pub size: u64,
+}
+
+fn snapshot_dir(db_path: &Path) -> PathBuf {
+    db_path.with_file_name("snapshots")
+}
+
+// "caldav-tasks-v025-1712345678.db" -> (25, 1712345678)
+fn parse_name(file_name: &str) -> Option<(i64, u64)> {
+    let rest = file_name.strip_prefix(PREFIX)?.strip_suffix(".db")?;
+    let (version, created_at) = rest.split_once('-')?;
+    Some((version.parse().ok()?, created_at.parse().ok()?))
+}
+
+/// the snapshots on disk, newest first
+fn list(db_path: &Path) -> Vec<DbSnapshot> {
+    let Ok(entries) = fs::read_dir(snapshot_dir(db_path)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<DbSnapshot> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let (version, created_at) = parse_name(&file_name)?;
+            Some(DbSnapshot {
+                size: entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+                file_name,
+                version,
+                created_at,
+            })
+        })
+        .collect();
+    snapshots.sort_by_key(|snapshot| Reverse(snapshot.created_at));
+    snapshots
+}
+
+/// snapshot the database, at schema version `current`, when some of `versions` haven't been
+/// applied to it yet; a new database has nothing to lose and isn't copied
+pub async fn before_migrations(
+    pool: &SqlitePool,
+    db_path: &Path,
+    current: i64,
+    versions: &[i64],
+) -> Result<(), String> {
+    if current == 0 || versions.iter().all(|version| *version <= current) {
+        return Ok(());
+    }
+
+    let dir = snapshot_dir(db_path);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let target = dir.join(format!("{PREFIX}{current:03}-{timestamp}.db"));
+    // a consistent copy even with the database open, unlike copying the file
+    sqlx::query("VACUUM INTO ?")
+        .bind(target.to_string_lossy().into_owned())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to snapshot the database: {e}"))?;
+    log::info!(
+        "Snapshotted the database at version {current} to {}",
+        target.display()
+    );
+
+    for old in list(db_path).into_iter().skip(KEEP_SNAPSHOTS) {
+        if let Err(e) = fs::remove_file(dir.join(&old.file_name)) {
+            log::warn!("Failed to remove old snapshot {}: {e}", old.file_name);
+        }
+    }
+    Ok(())
+}
+
+/// the pre-migration snapshots, newest first
+#[tauri::command]
+pub async fn get_db_snapshots(app_handle: tauri::AppHandle) -> Result<Vec<DbSnapshot>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    Ok(list(&db::db_path(&app_handle)?))
+}
+
+/// put a snapshot back in place of the database and restart. the migrations it predates run
+/// again on start, so this is meant for going back to the release that wrote it or forward to
+/// one that fixed the migration
+///
+/// the database stays open in several places (this pool, the sql plugin, the change watcher),
+/// so the snapshot is only staged next to it here and swapped in by `apply_staged_rollback`
+/// on the next start, before anything opens it
+#[tauri::command]
+pub async fn rollback_to_snapshot(
+    app_handle: tauri::AppHandle,
+    file_name: String,
+) -> Result<(), String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    // a database from a newer release forces read-only mode, and that's when this is needed
+    if !db::has_newer_schema() {
+        read_only::ensure_writable()?;
+    }
+    let path = db::db_path(&app_handle)?;
+    // only names from the list, never a path
+    if !list(&path)
+        .iter()
+        .any(|snapshot| snapshot.file_name == file_name)
+    {
+        return Err(format!("Snapshot not found: {file_name}"));
+    }
+
+    fs::copy(snapshot_dir(&path).join(&file_name), staged_path(&path))
+        .map_err(|e| e.to_string())?;
+    log::warn!("Staged a rollback of the database to {file_name}, restarting");
+    app_handle.restart();
+}
+
+fn staged_path(db_path: &Path) -> PathBuf {
+    let mut staged = db_path.to_path_buf().into_os_string();
+    staged.push(".rollback");
+    PathBuf::from(staged)
+}
+
+/// swap a snapshot staged by `rollback_to_snapshot` in for the database; only called while
+/// nothing has the database open
+pub fn apply_staged_rollback(db_path: &Path) -> Result<(), String> {
+    let staged = staged_path(db_path);
+    if !staged.exists() {
+        return Ok(());
+    }
+
+    for suffix in ["-wal", "-shm"] {
+        let mut journal = db_path.to_path_buf().into_os_string();
+        journal.push(suffix);
+        let journal = PathBuf::from(journal);
+        if journal.exists() {
+            fs::remove_file(&journal).map_err(|e| e.to_string())?;
+        }
+    }
+    fs::rename(&staged, db_path).map_err(|e| e.to_string())?;
+    log::warn!("Rolled the database back to the staged snapshot");
+    Ok(())
+}
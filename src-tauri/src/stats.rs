@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::{db, locale, lock};
+
+// upper bound for the days of a trend, about ten years
+const MAX_TREND_DAYS: u32 = 3660;
+
+// the moment local day `day` ends, for comparing against stored timestamps
+const DAY_END: &str = "julianday(day, '+1 day', 'utc')";
+
+// completed after it was due; an all-day task is late from the day after its due date
+const COMPLETED_LATE: &str = "CASE WHEN tasks.due_date_all_day = 1
+        THEN date(tasks.due_date, 'localtime') < date(tasks.completed_at, 'localtime')
+        ELSE julianday(tasks.completed_at) > julianday(tasks.due_date) END";
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsGrouping {
+    /// local calendar day the task was completed
+    Day,
+    /// local week (starting on the locale's first day) the task was completed
+    Week,
+    /// calendar the task is in
+    List,
+    Tag,
+}
+
+/// completed tasks in one group
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionStats {
+    /// yyyy-mm-dd (the first day for weeks), calendar id or tag id
+    pub key: String,
+    pub label: String,
+    pub completed: i64,
+    /// the ones completed after they were due
+    pub completed_late: i64,
+    /// days from creation to completion, on average
+    pub average_age_days: Option<f64>,
+}
+
+/// how many tasks were overdue at the end of a day
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct OverdueDay {
+    /// yyyy-mm-dd
+    pub day: String,
+    pub overdue: i64,
+    /// tasks completed that day after they were due
+    pub completed_late: i64,
+}
+
+/// completed tasks per day, week, list or tag for tasks completed in [from, to); days and weeks
+/// in order, lists and tags with the most completions first. archived tasks count too
+#[tauri::command]
+pub async fn get_completion_stats(
+    app_handle: tauri::AppHandle,
+    group_by: StatsGrouping,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<CompletionStats>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let week = format!(
+        "date(tasks.completed_at, 'localtime', {})",
+        locale::current().week_start_modifiers()
+    );
+    let (select, join, order) = match group_by {
+        StatsGrouping::Day => (
+            "date(tasks.completed_at, 'localtime') AS key,
+             date(tasks.completed_at, 'localtime') AS label"
+                .to_string(),
+            "",
+            "key",
+        ),
+        StatsGrouping::Week => (format!("{week} AS key, {week} AS label"), "", "key"),
+        StatsGrouping::List => (
+            "COALESCE(tasks.calendar_id, '') AS key,
+             COALESCE(MAX(calendars.display_name), '') AS label"
+                .to_string(),
+            "LEFT JOIN calendars ON calendars.id = tasks.calendar_id",
+            "completed DESC",
+        ),
+        StatsGrouping::Tag => (
+            "tags.id AS key, MAX(tags.name) AS label".to_string(),
+            "JOIN json_each(tasks.tags) AS tag
+             JOIN tags ON tags.id = tag.value",
+            "completed DESC",
+        ),
+    };
+    let query = format!(
+        "SELECT {select},
+            COUNT(*) AS completed,
+            COUNT(CASE WHEN {COMPLETED_LATE} THEN 1 END) AS completed_late,
+            AVG(julianday(tasks.completed_at) - julianday(tasks.created_at)) AS average_age_days
+         FROM tasks {join}
+         WHERE tasks.completed = 1 AND tasks.completed_at IS NOT NULL
+            AND (?1 IS NULL OR tasks.completed_at >= ?1) AND (?2 IS NULL OR tasks.completed_at < ?2)
+         GROUP BY key
+         ORDER BY {order}"
+    );
+    sqlx::query_as(&query)
+        .bind(&from)
+        .bind(&to)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// overdue tasks at the end of every local day from `from` up to (not including) the day of
+/// `to`, in order; all-day tasks become overdue once their day is over
+pub async fn overdue_trend(
+    pool: &SqlitePool,
+    from: &str,
+    to: &str,
+) -> Result<Vec<OverdueDay>, String> {
+    let query = format!(
+        "WITH RECURSIVE days(day) AS (
+            SELECT date(?1, 'localtime') WHERE date(?1, 'localtime') < date(?2, 'localtime')
+            UNION ALL SELECT date(day, '+1 day') FROM days
+            WHERE date(day, '+1 day') < date(?2, 'localtime')
+            LIMIT {MAX_TREND_DAYS}
+         )
+         SELECT day,
+            (SELECT COUNT(*) FROM tasks
+             WHERE due_date IS NOT NULL AND julianday(created_at) < {DAY_END}
+                AND CASE WHEN due_date_all_day = 1 THEN date(due_date, 'localtime') < day
+                    ELSE julianday(due_date) < {DAY_END} END
+                AND (completed = 0 OR julianday(completed_at) >= {DAY_END})
+            ) AS overdue,
+            (SELECT COUNT(*) FROM tasks
+             WHERE completed = 1 AND date(completed_at, 'localtime') = day AND {COMPLETED_LATE}
+            ) AS completed_late
+         FROM days
+         ORDER BY day"
+    );
+    sqlx::query_as(&query)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// see `overdue_trend`
+#[tauri::command]
+pub async fn get_overdue_trend(
+    app_handle: tauri::AppHandle,
+    from: String,
+    to: String,
+) -> Result<Vec<OverdueDay>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    overdue_trend(&pool, &from, &to).await
+}
@@ -0,0 +1,149 @@
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, SecondsFormat, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::time::Duration;
+
+use crate::stats::overdue_trend;
+use crate::tasks::now_iso;
+use crate::{db, i18n, lock, read_only, settings, tray};
+
+const IN_TRAY_KEY: &str = "streak_in_tray";
+const TRAY_INTERVAL: Duration = Duration::from_secs(60);
+// zero-overdue days are looked for this far back, the check runs per day over every task
+const NO_OVERDUE_LOOKBACK_DAYS: i64 = 365;
+
+/// days in a row; a streak stays current until a whole day passes without keeping it
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Streak {
+    pub current: u32,
+    pub longest: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Streaks {
+    /// days with at least one completed task
+    pub completion: Streak,
+    /// days that ended without overdue tasks; today counts while nothing is overdue
+    pub no_overdue: Streak,
+}
+
+// streaks over the days that qualified, in ascending order
+fn streak(days: &[NaiveDate], today: NaiveDate) -> Streak {
+    let mut result = Streak::default();
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days {
+        run = match previous {
+            Some(previous) if day - previous == ChronoDuration::days(1) => run + 1,
+            _ => 1,
+        };
+        result.longest = result.longest.max(run);
+        previous = Some(day);
+    }
+    if previous.is_some_and(|last| today - last <= ChronoDuration::days(1)) {
+        result.current = run;
+    }
+    result
+}
+
+fn parse_days(days: Vec<String>) -> Vec<NaiveDate> {
+    days.iter()
+        .filter_map(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+        .collect()
+}
+
+async fn completion_streak(pool: &SqlitePool) -> Result<Streak, String> {
+    let days: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT date(completed_at, 'localtime') AS day FROM tasks
+         WHERE completed = 1 AND completed_at IS NOT NULL
+         ORDER BY day",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(streak(&parse_days(days), Local::now().date_naive()))
+}
+
+async fn no_overdue_streak(pool: &SqlitePool) -> Result<Streak, String> {
+    let now = now_iso();
+    let from = (Utc::now() - ChronoDuration::days(NO_OVERDUE_LOOKBACK_DAYS))
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+    // every day before today
+    let mut days: Vec<String> = overdue_trend(pool, &from, &now)
+        .await?
+        .into_iter()
+        .filter(|day| day.overdue == 0)
+        .map(|day| day.day)
+        .collect();
+
+    let overdue_now: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM tasks
+         WHERE completed = 0 AND archived_at IS NULL AND due_date IS NOT NULL
+            AND CASE WHEN due_date_all_day = 1
+                THEN date(due_date, 'localtime') < date('now', 'localtime')
+                ELSE julianday(due_date) < julianday(?) END",
+    )
+    .bind(&now)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let today = Local::now().date_naive();
+    if overdue_now == 0 {
+        days.push(today.format("%Y-%m-%d").to_string());
+    }
+    Ok(streak(&parse_days(days), today))
+}
+
+/// put the completion streak in the tray tooltip, or take it out when that's turned off
+pub async fn update_tray(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let pool = db::pool(app_handle).await?;
+    let enabled = settings::get(&pool, IN_TRAY_KEY).await?.as_deref() == Some("true");
+    let note = if enabled && !lock::is_locked() {
+        let streak = completion_streak(&pool).await?;
+        (streak.current > 0).then(|| i18n::t_with("{}-day streak", &[&streak.current]))
+    } else {
+        None
+    };
+    tray::set_tray_note(app_handle, note.as_deref());
+    Ok(())
+}
+
+/// keep the streak in the tray tooltip up to date
+pub fn start_tray_updates(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TRAY_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = update_tray(&app_handle).await {
+                log::warn!("Failed to update the streak in the tray: {e}");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_streaks(app_handle: tauri::AppHandle) -> Result<Streaks, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    Ok(Streaks {
+        completion: completion_streak(&pool).await?,
+        no_overdue: no_overdue_streak(&pool).await?,
+    })
+}
+
+#[tauri::command]
+pub async fn get_streak_in_tray(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    let pool = db::pool(&app_handle).await?;
+    Ok(settings::get(&pool, IN_TRAY_KEY).await?.as_deref() == Some("true"))
+}
+
+/// show the completion streak in the tray tooltip
+#[tauri::command]
+pub async fn set_streak_in_tray(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    settings::set(&pool, IN_TRAY_KEY, &enabled.to_string()).await?;
+    update_tray(&app_handle).await
+}
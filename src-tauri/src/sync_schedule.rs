@@ -0,0 +1,155 @@
+use chrono::{DateTime, Duration, Local, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{account_health, db, lock, read_only};
+
+// the shortest interval an account can have, in minutes
+const MIN_INTERVAL: i64 = 1;
+
+/// when an account syncs on its own
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSchedule {
+    pub account_id: String,
+    /// minutes between syncs: None for the app-wide interval, 0 to only sync by hand
+    #[sqlx(rename = "sync_interval")]
+    pub interval: Option<i64>,
+    /// local "HH:MM" from which the account doesn't sync; the quiet hours can span midnight
+    #[sqlx(rename = "sync_quiet_start")]
+    pub quiet_start: Option<String>,
+    #[sqlx(rename = "sync_quiet_end")]
+    pub quiet_end: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct ScheduleRow {
+    #[sqlx(flatten)]
+    schedule: SyncSchedule,
+    last_attempt: Option<String>,
+    health_state: Option<String>,
+    consecutive_failures: Option<i64>,
+    last_failure_at: Option<String>,
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| format!("Invalid time: {time}"))
+}
+
+impl SyncSchedule {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(interval) = self.interval {
+            if interval != 0 && interval < MIN_INTERVAL {
+                return Err(format!(
+                    "The interval has to be at least {MIN_INTERVAL} minute"
+                ));
+            }
+        }
+        match (&self.quiet_start, &self.quiet_end) {
+            (Some(start), Some(end)) => {
+                parse_time(start)?;
+                parse_time(end)?;
+                Ok(())
+            }
+            (None, None) => Ok(()),
+            _ => Err("Quiet hours need both a start and an end".to_string()),
+        }
+    }
+
+    fn is_quiet(&self, now: NaiveTime) -> bool {
+        let (Some(start), Some(end)) = (&self.quiet_start, &self.quiet_end) else {
+            return false;
+        };
+        let (Ok(start), Ok(end)) = (parse_time(start), parse_time(end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_sync_schedules(app_handle: tauri::AppHandle) -> Result<Vec<SyncSchedule>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as(
+        "SELECT id AS account_id, sync_interval, sync_quiet_start, sync_quiet_end FROM accounts",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_sync_schedule(
+    app_handle: tauri::AppHandle,
+    schedule: SyncSchedule,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    schedule.validate()?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query(
+        "UPDATE accounts SET sync_interval = ?, sync_quiet_start = ?, sync_quiet_end = ?
+         WHERE id = ?",
+    )
+    .bind(schedule.interval)
+    .bind(&schedule.quiet_start)
+    .bind(&schedule.quiet_end)
+    .bind(&schedule.account_id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// the accounts whose interval ran out since they last synced (successfully or not) and that
+/// aren't in their quiet hours or backing off from an unreachable server; `default_interval`
+/// is the app-wide one, in minutes
+#[tauri::command]
+pub async fn get_due_accounts(
+    app_handle: tauri::AppHandle,
+    default_interval: i64,
+) -> Result<Vec<String>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    // the account health has when each account last finished a sync
+    let rows: Vec<ScheduleRow> = sqlx::query_as(
+        "SELECT a.id AS account_id, a.sync_interval, a.sync_quiet_start, a.sync_quiet_end,
+                MAX(COALESCE(h.last_success_at, ''), COALESCE(h.last_failure_at, ''))
+                    AS last_attempt,
+                h.state AS health_state, h.consecutive_failures, h.last_failure_at
+         FROM accounts a LEFT JOIN account_health h ON h.account_id = a.id
+         WHERE a.is_active = 1",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let now = Utc::now();
+    let local_time = Local::now().time();
+    Ok(rows
+        .into_iter()
+        .filter(|row| {
+            let interval = row.schedule.interval.unwrap_or(default_interval);
+            if interval <= 0 || row.schedule.is_quiet(local_time) {
+                return false;
+            }
+            let retry_at = account_health::retry_at(
+                row.health_state.as_deref().unwrap_or_default(),
+                row.consecutive_failures.unwrap_or(0),
+                row.last_failure_at.as_deref(),
+            );
+            if retry_at.is_some_and(|at| at > now) {
+                return false;
+            }
+            row.last_attempt
+                .as_deref()
+                .and_then(|last| DateTime::parse_from_rfc3339(last).ok())
+                .is_none_or(|last| last + Duration::minutes(interval) <= now)
+        })
+        .map(|row| row.schedule.account_id)
+        .collect())
+}
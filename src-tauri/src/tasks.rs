@@ -0,0 +1,1118 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{FromRow, QueryBuilder, Sqlite, SqlitePool, Transaction};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use tauri::Emitter;
+
+use crate::rules::{self, RuleEvent};
+use crate::time_tracking::{self, TimeEntry};
+use crate::{accounts, collation, db, dependencies, lock, read_only, search, transfer};
+
+// upper bound for a single page, so a bad call can't pull the whole table again
+const MAX_PAGE_SIZE: usize = 1000;
+// spacing between sort orders when a list of siblings has to be renumbered
+const SORT_GAP: i64 = 1024;
+// ids per IN (...) query, well below sqlite's variable limit
+const ID_CHUNK: usize = 500;
+// tasks written per transaction by upsert_tasks
+const UPSERT_CHUNK: usize = 200;
+
+/// extra column with the uids a task depends on, as a json array
+pub const BLOCKED_BY: &str = "(SELECT json_group_array(depends_on_uid) FROM task_dependencies
+        WHERE task_dependencies.task_uid = tasks.uid) AS blocked_by";
+
+/// leaves out tasks of archived calendars, which only show in the calendar's own view
+pub const NOT_ARCHIVED_CALENDAR: &str = "NOT EXISTS (SELECT 1 FROM calendars
+        WHERE calendars.id = tasks.calendar_id AND calendars.archived = 1)";
+
+/// a task row as stored by the frontend
+#[derive(Debug, Clone, FromRow)]
+pub struct TaskRow {
+    pub id: String,
+    pub uid: String,
+    pub etag: Option<String>,
+    pub href: Option<String>,
+    pub title: String,
+    pub description: String,
+    pub completed: bool,
+    pub completed_at: Option<String>,
+    pub tags: Option<String>,
+    pub category_id: Option<String>,
+    pub priority: String,
+    pub start_date: Option<String>,
+    pub start_date_all_day: Option<bool>,
+    pub due_date: Option<String>,
+    pub due_date_all_day: Option<bool>,
+    pub created_at: String,
+    pub modified_at: String,
+    pub reminders: Option<String>,
+    pub subtasks: String,
+    pub parent_uid: Option<String>,
+    pub is_collapsed: Option<bool>,
+    pub sort_order: i64,
+    pub url: Option<String>,
+    pub account_id: Option<String>,
+    pub calendar_id: Option<String>,
+    pub synced: bool,
+    pub local_only: Option<bool>,
+    pub estimated_duration: Option<i64>,
+    pub kanban_column: Option<String>,
+    pub contexts: Option<String>,
+    pub change_id: Option<String>,
+    /// json array of blocker uids, only filled by queries that select `BLOCKED_BY`
+    #[sqlx(default)]
+    pub blocked_by: Option<String>,
+}
+
+/// a task in the shape the frontend uses (dates stay iso strings)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub uid: String,
+    pub etag: Option<String>,
+    pub href: Option<String>,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub completed: bool,
+    pub completed_at: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub category_id: Option<String>,
+    pub priority: String,
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub start_date_all_day: bool,
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub due_date_all_day: bool,
+    pub created_at: String,
+    pub modified_at: String,
+    pub reminders: Option<Value>,
+    #[serde(default = "empty_array")]
+    pub subtasks: Value,
+    pub parent_uid: Option<String>,
+    #[serde(default)]
+    pub is_collapsed: bool,
+    pub sort_order: i64,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub account_id: String,
+    #[serde(default)]
+    pub calendar_id: String,
+    #[serde(default)]
+    pub synced: bool,
+    #[serde(default)]
+    pub local_only: bool,
+    /// planned effort in seconds
+    #[serde(default)]
+    pub estimated_duration: Option<i64>,
+    /// board column of the open task; None is the to-do column
+    #[serde(default)]
+    pub kanban_column: Option<String>,
+    /// names of the task's GTD contexts
+    #[serde(default)]
+    pub contexts: Vec<String>,
+    /// id of the last change uploaded from here (X-CALDAV-TASKS-CHANGE), so the sync can tell
+    /// its own upload from an edit made elsewhere
+    #[serde(default)]
+    pub change_id: Option<String>,
+    /// uids of the tasks this one waits for; None leaves the stored dependencies alone
+    #[serde(default)]
+    pub blocked_by: Option<Vec<String>>,
+    /// tracked time that came with the task from the server, merged into time_entries
+    #[serde(default)]
+    pub time_entries: Option<Vec<TimeEntry>>,
+}
+
+fn empty_array() -> Value {
+    Value::Array(Vec::new())
+}
+
+impl From<TaskRow> for Task {
+    fn from(row: TaskRow) -> Self {
+        Self {
+            tags: row
+                .tags
+                .as_deref()
+                .and_then(|tags| serde_json::from_str(tags).ok())
+                .unwrap_or_default(),
+            reminders: row
+                .reminders
+                .as_deref()
+                .and_then(|reminders| serde_json::from_str(reminders).ok()),
+            subtasks: serde_json::from_str(&row.subtasks).unwrap_or_else(|_| empty_array()),
+            start_date_all_day: row.start_date_all_day.unwrap_or(false),
+            due_date_all_day: row.due_date_all_day.unwrap_or(false),
+            is_collapsed: row.is_collapsed.unwrap_or(false),
+            account_id: row.account_id.unwrap_or_default(),
+            calendar_id: row.calendar_id.unwrap_or_default(),
+            local_only: row.local_only.unwrap_or(false),
+            contexts: row
+                .contexts
+                .as_deref()
+                .and_then(|contexts| serde_json::from_str(contexts).ok())
+                .unwrap_or_default(),
+            blocked_by: row
+                .blocked_by
+                .as_deref()
+                .and_then(|uids| serde_json::from_str(uids).ok()),
+            time_entries: None,
+            id: row.id,
+            uid: row.uid,
+            etag: row.etag,
+            href: row.href,
+            title: row.title,
+            description: row.description,
+            completed: row.completed,
+            completed_at: row.completed_at,
+            category_id: row.category_id,
+            priority: row.priority,
+            start_date: row.start_date,
+            due_date: row.due_date,
+            created_at: row.created_at,
+            modified_at: row.modified_at,
+            parent_uid: row.parent_uid,
+            sort_order: row.sort_order,
+            url: row.url,
+            synced: row.synced,
+            estimated_duration: row.estimated_duration,
+            kanban_column: row.kanban_column,
+            change_id: row.change_id,
+        }
+    }
+}
+
+/// which tasks to return; unset fields don't filter
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskFilter {
+    pub account_id: Option<String>,
+    pub calendar_id: Option<String>,
+    pub tag_id: Option<String>,
+    pub search: Option<String>,
+    /// None = all tasks, Some(false) = only open ones
+    pub completed: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    #[default]
+    Manual,
+    DueDate,
+    StartDate,
+    Priority,
+    Title,
+    Modified,
+    Created,
+    Smart,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct SortConfig {
+    pub mode: SortMode,
+    pub direction: SortDirection,
+}
+
+impl SortConfig {
+    /// ORDER BY clause matching the frontend's sort modes; ties fall back to the
+    /// manual order and the id so pages never overlap
+    pub fn order_by(&self) -> String {
+        let (asc, desc) = match self.direction {
+            SortDirection::Asc => ("ASC", "DESC"),
+            SortDirection::Desc => ("DESC", "ASC"),
+        };
+        let primary = match self.mode {
+            SortMode::Manual | SortMode::Smart => format!("sort_order {asc}"),
+            // tasks without a date always go last
+            SortMode::DueDate => format!("due_date IS NULL, due_date {asc}"),
+            SortMode::StartDate => format!("start_date IS NULL, start_date {asc}"),
+            SortMode::Priority => format!(
+                "CASE priority WHEN 'high' THEN 0 WHEN 'medium' THEN 1 WHEN 'low' THEN 2 ELSE 3 END {asc}"
+            ),
+            SortMode::Title => format!("title COLLATE {} {asc}", collation::NATURAL),
+            // newest first in ascending order, like the task list always did
+            SortMode::Modified => format!("modified_at {desc}"),
+            SortMode::Created => format!("created_at {desc}"),
+        };
+        format!("{primary}, sort_order ASC, id ASC")
+    }
+
+    /// the same order as `order_by`, for sorting tasks that are already loaded
+    pub fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        // tasks without a date go last in both directions
+        fn dates(a: &Option<String>, b: &Option<String>) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        }
+        fn priority(task: &Task) -> u8 {
+            match task.priority.as_str() {
+                "high" => 0,
+                "medium" => 1,
+                "low" => 2,
+                _ => 3,
+            }
+        }
+        let directed = |ordering: Ordering| match self.direction {
+            SortDirection::Asc => ordering,
+            SortDirection::Desc => ordering.reverse(),
+        };
+
+        let primary = match self.mode {
+            SortMode::Manual | SortMode::Smart => directed(a.sort_order.cmp(&b.sort_order)),
+            SortMode::DueDate => match (&a.due_date, &b.due_date) {
+                (Some(x), Some(y)) => directed(x.cmp(y)),
+                _ => dates(&a.due_date, &b.due_date),
+            },
+            SortMode::StartDate => match (&a.start_date, &b.start_date) {
+                (Some(x), Some(y)) => directed(x.cmp(y)),
+                _ => dates(&a.start_date, &b.start_date),
+            },
+            SortMode::Priority => directed(priority(a).cmp(&priority(b))),
+            SortMode::Title => directed(collation::natural_cmp(&a.title, &b.title)),
+            SortMode::Modified => directed(b.modified_at.cmp(&a.modified_at)),
+            SortMode::Created => directed(b.created_at.cmp(&a.created_at)),
+        };
+        primary
+            .then_with(|| a.sort_order.cmp(&b.sort_order))
+            .then_with(|| a.id.cmp(&b.id))
+    }
+}
+
+/// the task list as the user currently sees it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TaskView {
+    pub calendar_id: Option<String>,
+    pub tag_id: Option<String>,
+    pub search: String,
+    pub show_completed: bool,
+    pub sort: SortConfig,
+}
+
+impl Default for TaskView {
+    fn default() -> Self {
+        Self {
+            calendar_id: None,
+            tag_id: None,
+            search: String::new(),
+            show_completed: true,
+            sort: SortConfig::default(),
+        }
+    }
+}
+
+/// a task in display order, with its place in the hierarchy
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VisibleTask {
+    #[serde(flatten)]
+    pub task: Task,
+    pub depth: usize,
+    pub ancestor_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPage {
+    pub tasks: Vec<Task>,
+    /// number of matching tasks over all pages
+    pub total: i64,
+    pub offset: u32,
+}
+
+/// one page of the task list as shown
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VisibleTaskPage {
+    pub tasks: Vec<VisibleTask>,
+    /// number of shown tasks over all pages
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// append the WHERE clause for a filter (archived tasks never match)
+pub fn push_filter(query: &mut QueryBuilder<'_, Sqlite>, filter: &TaskFilter) {
+    query.push(" WHERE archived_at IS NULL");
+    if let Some(account_id) = &filter.account_id {
+        query
+            .push(" AND account_id = ")
+            .push_bind(account_id.clone());
+    }
+    // a tag view spans all calendars, same as in the sidebar
+    if let Some(tag_id) = &filter.tag_id {
+        query
+            .push(" AND EXISTS (SELECT 1 FROM json_each(tasks.tags) WHERE json_each.value = ")
+            .push_bind(tag_id.clone())
+            .push(")");
+    } else if let Some(calendar_id) = &filter.calendar_id {
+        query
+            .push(" AND calendar_id = ")
+            .push_bind(calendar_id.clone());
+    }
+    if filter.calendar_id.is_none() || filter.tag_id.is_some() {
+        query.push(" AND ").push(NOT_ARCHIVED_CALENDAR);
+    }
+    if let Some(completed) = filter.completed {
+        query.push(" AND completed = ").push_bind(completed);
+    }
+    if let Some(search) = filter.search.as_deref().map(str::trim) {
+        if !search.is_empty() {
+            query.push(" AND ");
+            match search::parse(search) {
+                Ok(Some(parsed)) => parsed.push_sql(query),
+                // plain text, and queries that don't parse while they're being typed, match
+                // as one phrase
+                _ => search::push_text(query, search),
+            }
+        }
+    }
+}
+
+// `query` is folded already; both sides ignore case, accents and NFC/NFD differences
+fn matches_search(task: &Task, query: &str) -> bool {
+    let contains = |text: &str| collation::fold(text).contains(query);
+    contains(&task.title)
+        || contains(&task.description)
+        || task.subtasks.as_array().is_some_and(|subtasks| {
+            subtasks.iter().any(|subtask| {
+                subtask
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .is_some_and(contains)
+            })
+        })
+}
+
+struct VisibleTree<'a> {
+    tasks: &'a [Task],
+    view: &'a TaskView,
+    query: String,
+    // ids matching a search query with filters, which the database evaluated
+    matching: Option<&'a HashSet<String>>,
+    children: HashMap<&'a str, Vec<usize>>,
+    visible: Vec<bool>,
+}
+
+impl<'a> VisibleTree<'a> {
+    /// mark which tasks are shown: a task is shown when it matches the view itself or when
+    /// one of its sub-tasks does, so matches never lose their parents; in a tag view
+    /// sub-tasks inherit the tag of their parent
+    fn mark(&mut self, index: usize, inherits_tag: bool, seen: &mut HashSet<usize>) -> bool {
+        if !seen.insert(index) {
+            return false;
+        }
+        let task = &self.tasks[index];
+        let has_tag = match &self.view.tag_id {
+            Some(tag_id) => inherits_tag || task.tags.contains(tag_id),
+            None => true,
+        };
+        let matches = has_tag
+            && (self.view.show_completed || !task.completed)
+            && match self.matching {
+                Some(matching) => matching.contains(&task.id),
+                None => self.query.is_empty() || matches_search(task, &self.query),
+            };
+
+        let mut visible = matches;
+        for child in self
+            .children
+            .get(task.uid.as_str())
+            .cloned()
+            .unwrap_or_default()
+        {
+            visible |= self.mark(child, has_tag, seen);
+        }
+        self.visible[index] = visible;
+        visible
+    }
+
+    fn emit(&self, mut indices: Vec<usize>, ancestor_ids: &[String], out: &mut Vec<VisibleTask>) {
+        indices.retain(|&index| self.visible[index]);
+        indices.sort_by(|&a, &b| self.view.sort.compare(&self.tasks[a], &self.tasks[b]));
+
+        for index in indices {
+            let task = &self.tasks[index];
+            out.push(VisibleTask {
+                task: task.clone(),
+                depth: ancestor_ids.len(),
+                ancestor_ids: ancestor_ids.to_vec(),
+            });
+            // search results are always expanded so matches don't hide in collapsed parents
+            if task.is_collapsed && self.query.is_empty() {
+                continue;
+            }
+            if let Some(children) = self.children.get(task.uid.as_str()) {
+                let mut ancestors = ancestor_ids.to_vec();
+                ancestors.push(task.id.clone());
+                self.emit(children.clone(), &ancestors, out);
+            }
+        }
+    }
+}
+
+/// filter, sort and flatten tasks for a view; sub-tasks whose parent isn't loaded are
+/// shown at the top level. `matching` replaces the text search with the ids that matched
+/// a search query
+pub fn visible_tasks(
+    tasks: &[Task],
+    view: &TaskView,
+    matching: Option<&HashSet<String>>,
+) -> Vec<VisibleTask> {
+    let uids: HashSet<&str> = tasks.iter().map(|task| task.uid.as_str()).collect();
+    let mut roots = Vec::new();
+    let mut children: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, task) in tasks.iter().enumerate() {
+        match task.parent_uid.as_deref() {
+            Some(parent) if uids.contains(parent) && parent != task.uid => {
+                children.entry(parent).or_default().push(index)
+            }
+            _ => roots.push(index),
+        }
+    }
+
+    let mut tree = VisibleTree {
+        tasks,
+        view,
+        query: collation::fold(view.search.trim()),
+        matching,
+        children,
+        visible: vec![false; tasks.len()],
+    };
+    let mut seen = HashSet::new();
+    for &root in &roots {
+        tree.mark(root, false, &mut seen);
+    }
+
+    let mut out = Vec::new();
+    tree.emit(roots, &[], &mut out);
+    out
+}
+
+/// get one page of exactly the tasks the task list shows for a view, in display order; the
+/// tree is built here so the webview only ever holds the pages it has scrolled to
+#[tauri::command]
+pub async fn query_visible_tasks(
+    app_handle: tauri::AppHandle,
+    view: TaskView,
+    offset: usize,
+    limit: usize,
+) -> Result<VisibleTaskPage, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    // tag views span all calendars
+    let filter = TaskFilter {
+        calendar_id: view.calendar_id.clone().filter(|_| view.tag_id.is_none()),
+        ..TaskFilter::default()
+    };
+    let mut query = QueryBuilder::<Sqlite>::new("SELECT * FROM tasks");
+    push_filter(&mut query, &filter);
+    let rows: Vec<TaskRow> = query
+        .build_query_as()
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let tasks: Vec<Task> = rows.into_iter().map(Task::from).collect();
+
+    let matching: Option<HashSet<String>> = match search::parse(view.search.trim()) {
+        Ok(Some(parsed)) => {
+            let mut query = QueryBuilder::<Sqlite>::new("SELECT id FROM tasks WHERE ");
+            parsed.push_sql(&mut query);
+            Some(
+                query
+                    .build_query_scalar()
+                    .fetch_all(&pool)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .collect(),
+            )
+        }
+        _ => None,
+    };
+    let visible = visible_tasks(&tasks, &view, matching.as_ref());
+    Ok(VisibleTaskPage {
+        total: visible.len(),
+        tasks: visible
+            .into_iter()
+            .skip(offset)
+            .take(limit.min(MAX_PAGE_SIZE))
+            .collect(),
+        offset,
+    })
+}
+
+/// load tasks by id (missing ids are skipped)
+pub async fn fetch_by_ids(pool: &SqlitePool, ids: &[String]) -> Result<Vec<Task>, String> {
+    let mut tasks = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(ID_CHUNK) {
+        let mut query =
+            QueryBuilder::<Sqlite>::new(format!("SELECT *, {BLOCKED_BY} FROM tasks WHERE id IN ("));
+        let mut separated = query.separated(", ");
+        for id in chunk {
+            separated.push_bind(id.clone());
+        }
+        query.push(")");
+        let rows: Vec<TaskRow> = query
+            .build_query_as()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        tasks.extend(rows.into_iter().map(Task::from));
+    }
+    Ok(tasks)
+}
+
+/// payload of the `tasks-changed` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TasksChangedEvent {
+    pub created: Vec<Task>,
+    pub updated: Vec<Task>,
+    pub deleted: Vec<String>,
+}
+
+/// ids of tasks a backend command created, updated or deleted; emitted as one
+/// `tasks-changed` event so the frontend can patch its cache instead of reloading
+#[derive(Debug, Default)]
+pub struct TaskChanges {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl TaskChanges {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+
+    /// load the changed tasks and emit the event; the changes are already saved, so
+    /// failures are only logged
+    pub async fn emit(self, app_handle: &tauri::AppHandle, pool: &SqlitePool) {
+        if self.is_empty() {
+            return;
+        }
+        let payload = async {
+            Ok::<_, String>(TasksChangedEvent {
+                created: fetch_by_ids(pool, &self.created).await?,
+                updated: fetch_by_ids(pool, &self.updated).await?,
+                deleted: self.deleted,
+            })
+        }
+        .await;
+
+        match payload {
+            Ok(payload) => {
+                if let Err(e) = app_handle.emit("tasks-changed", payload) {
+                    log::warn!("Failed to emit tasks-changed: {e}");
+                }
+            }
+            Err(e) => log::warn!("Failed to load changed tasks: {e}"),
+        }
+    }
+}
+
+/// current time the way the frontend stores dates
+pub fn now_iso() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// sort orders for siblings in their new order: the longest run of tasks that is already
+/// in order keeps its values and the others are placed in the gaps around it, so a single
+/// drag usually rewrites a single row; the list is only renumbered when a gap is too small
+pub fn gap_positions(current: &[i64]) -> Vec<i64> {
+    // longest strictly increasing subsequence (patience sorting)
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; current.len()];
+    for (index, &value) in current.iter().enumerate() {
+        let at = tails.partition_point(|&tail| current[tail] < value);
+        previous[index] = at.checked_sub(1).map(|at| tails[at]);
+        if at == tails.len() {
+            tails.push(index);
+        } else {
+            tails[at] = index;
+        }
+    }
+    let mut keep = vec![false; current.len()];
+    let mut next = tails.last().copied();
+    while let Some(index) = next {
+        keep[index] = true;
+        next = previous[index];
+    }
+
+    let mut positions = current.to_vec();
+    let mut start = 0;
+    while start < current.len() {
+        if keep[start] {
+            start += 1;
+            continue;
+        }
+        let end = (start..current.len())
+            .find(|&index| keep[index])
+            .unwrap_or(current.len());
+        let count = (end - start) as i64;
+        let low = start.checked_sub(1).map(|index| current[index]);
+        let high = (end < current.len()).then(|| current[end]);
+
+        match (low, high) {
+            (Some(low), Some(high)) => {
+                let step = (high - low) / (count + 1);
+                if step == 0 {
+                    return (1..=current.len() as i64).map(|n| n * SORT_GAP).collect();
+                }
+                for (n, index) in (start..end).enumerate() {
+                    positions[index] = low + step * (n as i64 + 1);
+                }
+            }
+            (Some(low), None) => {
+                for (n, index) in (start..end).enumerate() {
+                    positions[index] = low + SORT_GAP * (n as i64 + 1);
+                }
+            }
+            (None, Some(high)) => {
+                for (n, index) in (start..end).enumerate() {
+                    positions[index] = high - SORT_GAP * (count - n as i64);
+                }
+            }
+            (None, None) => unreachable!("a non-empty list keeps at least one task"),
+        }
+        start = end;
+    }
+    positions
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPosition {
+    pub id: String,
+    pub uid: String,
+    pub sort_order: i64,
+    pub parent_uid: Option<String>,
+    pub calendar_id: Option<String>,
+    pub account_id: Option<String>,
+}
+
+/// persist a new order of sibling tasks (ordered by ascending sort order) under a parent
+/// in one transaction; tasks that get a new parent move into its calendar together with
+/// their sub-tasks, and are queued for deletion from the old one. returns every task whose
+/// position changed
+#[tauri::command]
+pub async fn reorder_tasks(
+    app_handle: tauri::AppHandle,
+    ordered_ids: Vec<String>,
+    parent_uid: Option<String>,
+) -> Result<Vec<TaskPosition>, String> {
+    read_only::ensure_writable()?;
+    if ordered_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pool = db::pool(&app_handle).await?;
+    accounts::ensure_tasks_editable(&pool, "id", &ordered_ids).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let mut query = QueryBuilder::<Sqlite>::new(
+        "SELECT id, uid, sort_order, parent_uid, calendar_id, account_id FROM tasks WHERE id IN (",
+    );
+    let mut ids = query.separated(", ");
+    for id in &ordered_ids {
+        ids.push_bind(id.clone());
+    }
+    query.push(")");
+    let rows: Vec<TaskPosition> = query
+        .build_query_as()
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut by_id: HashMap<&str, &TaskPosition> =
+        rows.iter().map(|row| (row.id.as_str(), row)).collect();
+    let tasks = ordered_ids
+        .iter()
+        .map(|id| {
+            by_id
+                .remove(id.as_str())
+                .ok_or_else(|| format!("Task not found: {id}"))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // the new parent decides the calendar, and must not be one of the moved subtrees
+    let parent = match &parent_uid {
+        Some(uid) => {
+            let (calendar_id, account_id) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+                "SELECT calendar_id, account_id FROM tasks WHERE uid = ?",
+            )
+            .bind(uid)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Parent task not found: {uid}"))?;
+
+            let ancestors: Vec<String> = sqlx::query_scalar(
+                "WITH RECURSIVE ancestors(uid) AS (
+                    SELECT ?
+                    UNION SELECT tasks.parent_uid FROM tasks
+                    JOIN ancestors ON tasks.uid = ancestors.uid
+                    WHERE tasks.parent_uid IS NOT NULL
+                 )
+                 SELECT uid FROM ancestors",
+            )
+            .bind(uid)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            if tasks.iter().any(|task| ancestors.contains(&task.uid)) {
+                return Err("A task can't be moved into its own sub-tasks".to_string());
+            }
+            Some((calendar_id, account_id))
+        }
+        None => None,
+    };
+
+    let positions = gap_positions(&tasks.iter().map(|task| task.sort_order).collect::<Vec<_>>());
+    let modified_at = now_iso();
+    let mut changed = Vec::new();
+    let mut released = false;
+    for (task, sort_order) in tasks.into_iter().zip(positions) {
+        let reparented = task.parent_uid != parent_uid;
+        if !reparented && task.sort_order == sort_order {
+            continue;
+        }
+        let (calendar_id, account_id) = match &parent {
+            Some((calendar_id, account_id)) if reparented && *calendar_id != task.calendar_id => {
+                (calendar_id.clone(), account_id.clone())
+            }
+            _ => (task.calendar_id.clone(), task.account_id.clone()),
+        };
+
+        if calendar_id != task.calendar_id {
+            transfer::release(&mut tx, &task.uid).await?;
+            released = true;
+        }
+        let updated: Vec<TaskPosition> = sqlx::query_as(
+            "UPDATE tasks
+             SET sort_order = ?, parent_uid = ?, calendar_id = ?, account_id = ?,
+                 synced = 0, modified_at = ?
+             WHERE id = ?
+             RETURNING id, uid, sort_order, parent_uid, calendar_id, account_id",
+        )
+        .bind(sort_order)
+        .bind(&parent_uid)
+        .bind(&calendar_id)
+        .bind(&account_id)
+        .bind(&modified_at)
+        .bind(&task.id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        changed.extend(updated);
+
+        if calendar_id != task.calendar_id {
+            let descendants: Vec<TaskPosition> = sqlx::query_as(
+                "WITH RECURSIVE subtree(uid) AS (
+                    SELECT uid FROM tasks WHERE parent_uid = ?1
+                    UNION SELECT tasks.uid FROM tasks JOIN subtree ON tasks.parent_uid = subtree.uid
+                 )
+                 UPDATE tasks
+                 SET calendar_id = ?2, account_id = ?3, synced = 0, modified_at = ?4
+                 WHERE uid IN subtree
+                 RETURNING id, uid, sort_order, parent_uid, calendar_id, account_id",
+            )
+            .bind(&task.uid)
+            .bind(&calendar_id)
+            .bind(&account_id)
+            .bind(&modified_at)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            changed.extend(descendants);
+        }
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    log::debug!(
+        "Reordered {} tasks, {} rows changed",
+        ordered_ids.len(),
+        changed.len()
+    );
+
+    if released {
+        // tasks moved to another calendar left pending deletions behind, reload everything
+        let _ = app_handle.emit("database-changed", ());
+    } else {
+        TaskChanges {
+            updated: changed.iter().map(|task| task.id.clone()).collect(),
+            ..TaskChanges::default()
+        }
+        .emit(&app_handle, &pool)
+        .await;
+    }
+    Ok(changed)
+}
+
+/// insert a task, or replace the row with the same id; its dependencies are replaced and its
+/// tracked time merged when the task carries them
+pub async fn save_task(tx: &mut Transaction<'_, Sqlite>, task: &Task) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO tasks (
+            id, uid, etag, href, title, description, completed, completed_at, tags,
+            category_id, priority, start_date, start_date_all_day, due_date,
+            due_date_all_day, created_at, modified_at, reminders, subtasks, parent_uid,
+            is_collapsed, sort_order, url, account_id, calendar_id, synced, local_only,
+            estimated_duration, kanban_column, contexts, change_id
+         ) VALUES (
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+            ?
+         )
+         ON CONFLICT(id) DO UPDATE SET
+            uid = excluded.uid, etag = excluded.etag, href = excluded.href,
+            title = excluded.title, description = excluded.description,
+            completed = excluded.completed, completed_at = excluded.completed_at,
+            tags = excluded.tags, category_id = excluded.category_id,
+            priority = excluded.priority, start_date = excluded.start_date,
+            start_date_all_day = excluded.start_date_all_day, due_date = excluded.due_date,
+            due_date_all_day = excluded.due_date_all_day, created_at = excluded.created_at,
+            modified_at = excluded.modified_at, reminders = excluded.reminders,
+            subtasks = excluded.subtasks, parent_uid = excluded.parent_uid,
+            is_collapsed = excluded.is_collapsed, sort_order = excluded.sort_order,
+            url = excluded.url, account_id = excluded.account_id,
+            calendar_id = excluded.calendar_id, synced = excluded.synced,
+            local_only = excluded.local_only, estimated_duration = excluded.estimated_duration,
+            kanban_column = excluded.kanban_column, contexts = excluded.contexts,
+            change_id = excluded.change_id",
+    )
+    .bind(&task.id)
+    .bind(&task.uid)
+    .bind(&task.etag)
+    .bind(&task.href)
+    .bind(&task.title)
+    .bind(&task.description)
+    .bind(task.completed)
+    .bind(&task.completed_at)
+    .bind((!task.tags.is_empty()).then(|| json!(task.tags).to_string()))
+    .bind(&task.category_id)
+    .bind(&task.priority)
+    .bind(&task.start_date)
+    .bind(task.start_date_all_day)
+    .bind(&task.due_date)
+    .bind(task.due_date_all_day)
+    .bind(&task.created_at)
+    .bind(&task.modified_at)
+    .bind(
+        task.reminders
+            .as_ref()
+            .filter(|reminders| reminders.as_array().is_some_and(|r| !r.is_empty()))
+            .map(Value::to_string),
+    )
+    .bind(task.subtasks.to_string())
+    .bind(&task.parent_uid)
+    .bind(task.is_collapsed)
+    .bind(task.sort_order)
+    .bind(&task.url)
+    .bind(Some(&task.account_id).filter(|id| !id.is_empty()))
+    .bind(Some(&task.calendar_id).filter(|id| !id.is_empty()))
+    .bind(task.synced)
+    .bind(task.local_only)
+    .bind(task.estimated_duration)
+    .bind(&task.kanban_column)
+    .bind((!task.contexts.is_empty()).then(|| json!(task.contexts).to_string()))
+    .bind(&task.change_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Some(blocked_by) = &task.blocked_by {
+        dependencies::replace(tx, &task.uid, blocked_by).await?;
+    }
+    if let Some(entries) = &task.time_entries {
+        time_tracking::merge(tx, &task.uid, entries).await?;
+    }
+    Ok(())
+}
+
+/// insert or replace tasks in chunked transactions (one write per chunk instead of one per
+/// task) after running the sync rules on them; the caller keeps its own cache up to date, so
+/// only tasks the rules changed are reported
+#[tauri::command]
+pub async fn upsert_tasks(app_handle: tauri::AppHandle, tasks: Vec<Task>) -> Result<usize, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    let rules = rules::load(&pool, RuleEvent::Synced).await?;
+    let mut ruled = Vec::new();
+    for chunk in tasks.chunks(UPSERT_CHUNK) {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        for task in chunk {
+            match rules::apply(&rules, task) {
+                Some(changed) => {
+                    save_task(&mut tx, &changed).await?;
+                    ruled.push(changed.id);
+                }
+                None => save_task(&mut tx, task).await?,
+            }
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+    }
+
+    log::debug!("Saved {} tasks", tasks.len());
+    // the caller's cache has the tasks as they were before the rules ran
+    TaskChanges {
+        updated: ruled,
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(tasks.len())
+}
+
+/// ids for rows created in the backend, in the same format as the frontend's uuidv4()
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+fn shift_date(date: Option<String>, days: i64) -> Option<String> {
+    let date = date?;
+    match DateTime::parse_from_rfc3339(&date) {
+        Ok(parsed) => Some(
+            (parsed.with_timezone(&Utc) + Duration::days(days))
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+        ),
+        Err(_) => Some(date),
+    }
+}
+
+// checklist items and reminders get new ids; checklist items are unchecked again and
+// reminder triggers move with the dates
+fn copy_items(items: Value, reset: impl Fn(&mut serde_json::Map<String, Value>)) -> Value {
+    match items {
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|mut item| {
+                    if let Value::Object(fields) = &mut item {
+                        fields.insert("id".to_string(), Value::String(new_id()));
+                        reset(fields);
+                    }
+                    item
+                })
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// copy a task, and optionally all of its sub-tasks, as new unsynced tasks with fresh uids;
+/// completion is cleared and dates move by `offset_days`. returns the copies, the top one first
+#[tauri::command]
+pub async fn duplicate_task(
+    app_handle: tauri::AppHandle,
+    uid: String,
+    include_subtasks: bool,
+    offset_days: Option<i64>,
+) -> Result<Vec<Task>, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    accounts::ensure_tasks_editable(&pool, "uid", std::slice::from_ref(&uid)).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let query = if include_subtasks {
+        // UNION stops at uids it has already seen, so a parent cycle can't loop forever
+        "WITH RECURSIVE tree(uid) AS (
+            SELECT uid FROM tasks WHERE uid = ?
+            UNION
+            SELECT tasks.uid FROM tasks JOIN tree ON tasks.parent_uid = tree.uid
+         )
+         SELECT tasks.* FROM tasks JOIN tree ON tasks.uid = tree.uid"
+    } else {
+        "SELECT * FROM tasks WHERE uid = ?"
+    };
+    let mut rows: Vec<TaskRow> = sqlx::query_as(query)
+        .bind(&uid)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if rows.is_empty() {
+        return Err(format!("Task {uid} not found"));
+    }
+    rows.sort_by_key(|row| row.uid != uid);
+
+    let offset = offset_days.unwrap_or(0);
+    let now = now_iso();
+    let new_uids: HashMap<String, String> =
+        rows.iter().map(|row| (row.uid.clone(), new_id())).collect();
+
+    let mut copies = Vec::with_capacity(rows.len());
+    for task in rows.into_iter().map(Task::from) {
+        let is_top = task.uid == uid;
+        let copy = Task {
+            id: new_id(),
+            uid: new_uids[&task.uid].clone(),
+            etag: None,
+            href: None,
+            change_id: None,
+            completed: false,
+            completed_at: None,
+            start_date: shift_date(task.start_date, offset),
+            due_date: shift_date(task.due_date, offset),
+            created_at: now.clone(),
+            modified_at: now.clone(),
+            reminders: task.reminders.map(|reminders| {
+                copy_items(reminders, |reminder| {
+                    let trigger = reminder
+                        .get("trigger")
+                        .and_then(Value::as_str)
+                        .map(str::to_string);
+                    if let Some(trigger) = shift_date(trigger, offset) {
+                        reminder.insert("trigger".to_string(), Value::String(trigger));
+                    }
+                })
+            }),
+            subtasks: copy_items(task.subtasks, |item| {
+                item.insert("completed".to_string(), Value::Bool(false));
+            }),
+            // the copy sits right below the original; its sub-tasks hang under the copy
+            parent_uid: if is_top {
+                task.parent_uid
+            } else {
+                task.parent_uid
+                    .map(|parent| new_uids.get(&parent).cloned().unwrap_or(parent))
+            },
+            sort_order: if is_top {
+                task.sort_order + 1
+            } else {
+                task.sort_order
+            },
+            synced: false,
+            ..task
+        };
+        save_task(&mut tx, &copy).await?;
+        copies.push(copy);
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    TaskChanges {
+        created: copies.iter().map(|task| task.id.clone()).collect(),
+        ..TaskChanges::default()
+    }
+    .emit(&app_handle, &pool)
+    .await;
+    Ok(copies)
+}
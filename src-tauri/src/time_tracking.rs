@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Sqlite, Transaction};
+use tauri::Emitter;
+
+use crate::tasks::{new_id, now_iso};
+use crate::{db, lock, read_only};
+
+// seconds of the matching entries; a running timer counts up to ?1
+const SECONDS: &str = "CAST(ROUND(SUM(
+        (julianday(COALESCE(entry.ended_at, ?1)) - julianday(entry.started_at)) * 86400
+    )) AS INTEGER)";
+
+/// a stretch of time spent on a task; the running timer has no end yet
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeEntry {
+    pub id: String,
+    /// left out when the entry travels inside its task
+    #[serde(default)]
+    pub task_uid: String,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeGrouping {
+    Task,
+    Tag,
+    /// local calendar day the entry started on
+    Day,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeTotal {
+    /// task uid, tag id or yyyy-mm-dd
+    pub key: String,
+    pub label: String,
+    pub seconds: i64,
+}
+
+/// add entries that came with a task from the server; known entries only pick up an end
+pub async fn merge(
+    tx: &mut Transaction<'_, Sqlite>,
+    uid: &str,
+    entries: &[TimeEntry],
+) -> Result<(), String> {
+    for entry in entries {
+        sqlx::query(
+            "INSERT INTO time_entries (id, task_uid, started_at, ended_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET ended_at = COALESCE(ended_at, excluded.ended_at)",
+        )
+        .bind(&entry.id)
+        .bind(uid)
+        .bind(&entry.started_at)
+        .bind(&entry.ended_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+async fn stop_running(
+    tx: &mut Transaction<'_, Sqlite>,
+    now: &str,
+) -> Result<Option<TimeEntry>, String> {
+    sqlx::query_as("UPDATE time_entries SET ended_at = ? WHERE ended_at IS NULL RETURNING *")
+        .bind(now)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn emit_timer(app_handle: &tauri::AppHandle, running: Option<&TimeEntry>) {
+    if let Err(e) = app_handle.emit("timer-changed", running) {
+        log::warn!("Failed to emit timer-changed: {e}");
+    }
+}
+
+/// start timing a task; a timer that is already running is stopped first
+#[tauri::command]
+pub async fn start_timer(
+    app_handle: tauri::AppHandle,
+    task_uid: String,
+) -> Result<TimeEntry, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM tasks WHERE uid = ?)")
+        .bind(&task_uid)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Err(format!("Task {task_uid} not found"));
+    }
+
+    let now = now_iso();
+    stop_running(&mut tx, &now).await?;
+    let entry = TimeEntry {
+        id: new_id(),
+        task_uid,
+        started_at: now,
+        ended_at: None,
+    };
+    sqlx::query("INSERT INTO time_entries (id, task_uid, started_at) VALUES (?, ?, ?)")
+        .bind(&entry.id)
+        .bind(&entry.task_uid)
+        .bind(&entry.started_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::debug!("Started timer for {}", entry.task_uid);
+    emit_timer(&app_handle, Some(&entry));
+    Ok(entry)
+}
+
+/// stop the running timer; returns the finished entry, if there was one
+#[tauri::command]
+pub async fn stop_timer(app_handle: tauri::AppHandle) -> Result<Option<TimeEntry>, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let stopped = stop_running(&mut tx, &now_iso()).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if stopped.is_some() {
+        emit_timer(&app_handle, None);
+    }
+    Ok(stopped)
+}
+
+#[tauri::command]
+pub async fn get_running_timer(app_handle: tauri::AppHandle) -> Result<Option<TimeEntry>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as("SELECT * FROM time_entries WHERE ended_at IS NULL")
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// every entry of a task, oldest first
+#[tauri::command]
+pub async fn get_time_entries(
+    app_handle: tauri::AppHandle,
+    task_uid: String,
+) -> Result<Vec<TimeEntry>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as("SELECT * FROM time_entries WHERE task_uid = ? ORDER BY started_at")
+        .bind(&task_uid)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_time_entry(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let running: Option<bool> =
+        sqlx::query_scalar("DELETE FROM time_entries WHERE id = ? RETURNING ended_at IS NULL")
+            .bind(&id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if running == Some(true) {
+        emit_timer(&app_handle, None);
+    }
+    Ok(())
+}
+
+/// tracked time per task, tag or day for entries that started in [from, to), largest first
+/// (days in order); a task with several tags counts towards each of them
+#[tauri::command]
+pub async fn get_time_totals(
+    app_handle: tauri::AppHandle,
+    group_by: TimeGrouping,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<TimeTotal>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+
+    let (select, join, order) = match group_by {
+        TimeGrouping::Task => (
+            "entry.task_uid AS key, COALESCE(MAX(tasks.title), '') AS label",
+            "LEFT JOIN tasks ON tasks.uid = entry.task_uid",
+            "seconds DESC",
+        ),
+        TimeGrouping::Tag => (
+            "tags.id AS key, MAX(tags.name) AS label",
+            "JOIN tasks ON tasks.uid = entry.task_uid
+             JOIN json_each(tasks.tags) AS tag
+             JOIN tags ON tags.id = tag.value",
+            "seconds DESC",
+        ),
+        TimeGrouping::Day => (
+            "date(entry.started_at, 'localtime') AS key,
+             date(entry.started_at, 'localtime') AS label",
+            "",
+            "key",
+        ),
+    };
+    let query = format!(
+        "SELECT {select}, {SECONDS} AS seconds
+         FROM time_entries AS entry {join}
+         WHERE (?2 IS NULL OR entry.started_at >= ?2) AND (?3 IS NULL OR entry.started_at < ?3)
+         GROUP BY key
+         ORDER BY {order}"
+    );
+    sqlx::query_as(&query)
+        .bind(now_iso())
+        .bind(&from)
+        .bind(&to)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use tauri::Emitter;
+use tauri_plugin_http::reqwest::{tls, Certificate, ClientBuilder};
+
+use crate::{db, lock, read_only};
+
+// used when an account has no explicit setting
+const DEFAULT_MIN_VERSION: &str = "1.2";
+
+/// per-account tls settings enforced by the backend http client
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsSettings {
+    /// minimum tls version, "1.2" or "1.3"
+    #[serde(default = "default_min_version")]
+    #[sqlx(rename = "tls_min_version")]
+    pub min_version: String,
+    /// pem certificate the server has to present; replaces the system trust store
+    #[serde(default)]
+    #[sqlx(rename = "tls_pinned_certificate")]
+    pub pinned_certificate: Option<String>,
+    /// skip hostname checks (only allowed together with a pinned certificate)
+    #[serde(default)]
+    #[sqlx(rename = "tls_allow_invalid_hostnames")]
+    pub allow_invalid_hostnames: bool,
+}
+
+fn default_min_version() -> String {
+    DEFAULT_MIN_VERSION.to_string()
+}
+
+impl Default for TlsSettings {
+    fn default() -> Self {
+        Self {
+            min_version: default_min_version(),
+            pinned_certificate: None,
+            allow_invalid_hostnames: false,
+        }
+    }
+}
+
+impl TlsSettings {
+    fn pinned(&self) -> Option<&str> {
+        self.pinned_certificate
+            .as_deref()
+            .map(str::trim)
+            .filter(|pem| !pem.is_empty())
+    }
+
+    /// check the settings without building a client
+    pub fn validate(&self) -> Result<(), String> {
+        self.min_tls_version()?;
+        if let Some(pem) = self.pinned() {
+            Certificate::from_pem(pem.as_bytes())
+                .map_err(|e| format!("Invalid pinned certificate: {e}"))?;
+        }
+        if self.allow_invalid_hostnames && self.pinned().is_none() {
+            return Err("Invalid hostnames can only be allowed for a pinned certificate".into());
+        }
+        Ok(())
+    }
+
+    fn min_tls_version(&self) -> Result<tls::Version, String> {
+        match self.min_version.as_str() {
+            "1.2" => Ok(tls::Version::TLS_1_2),
+            "1.3" => Ok(tls::Version::TLS_1_3),
+            // the tls backend has no support for anything older
+            "1.0" | "1.1" => Err(format!(
+                "TLS {} is not supported, the minimum is TLS 1.2",
+                self.min_version
+            )),
+            other => Err(format!("Unknown TLS version: {other}")),
+        }
+    }
+
+    /// apply the settings to a client builder
+    pub fn apply(&self, builder: ClientBuilder) -> Result<ClientBuilder, String> {
+        self.validate()?;
+
+        let mut builder = builder.min_tls_version(self.min_tls_version()?);
+        if let Some(pem) = self.pinned() {
+            let certificate = Certificate::from_pem(pem.as_bytes())
+                .map_err(|e| format!("Invalid pinned certificate: {e}"))?;
+            builder = builder
+                .tls_built_in_root_certs(false)
+                .add_root_certificate(certificate)
+                .danger_accept_invalid_hostnames(self.allow_invalid_hostnames);
+        }
+        Ok(builder)
+    }
+}
+
+/// get the tls settings of an account
+#[tauri::command]
+pub async fn get_account_tls(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+) -> Result<TlsSettings, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as::<_, TlsSettings>(
+        "SELECT tls_min_version, tls_pinned_certificate, tls_allow_invalid_hostnames
+         FROM accounts WHERE id = ?",
+    )
+    .bind(&account_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Account not found: {account_id}"))
+}
+
+/// validate and save the tls settings of an account
+#[tauri::command]
+pub async fn set_account_tls(
+    app_handle: tauri::AppHandle,
+    account_id: String,
+    tls: TlsSettings,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    tls.validate()?;
+
+    let pool = db::pool(&app_handle).await?;
+    let result = sqlx::query(
+        "UPDATE accounts
+         SET tls_min_version = ?, tls_pinned_certificate = ?, tls_allow_invalid_hostnames = ?
+         WHERE id = ?",
+    )
+    .bind(&tls.min_version)
+    .bind(tls.pinned().map(str::to_string))
+    .bind(tls.allow_invalid_hostnames)
+    .bind(&account_id)
+    .execute(&pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err(format!("Account not found: {account_id}"));
+    }
+    log::info!(
+        "TLS settings for account {account_id}: min {}, pinned {}, invalid hostnames {}",
+        tls.min_version,
+        tls.pinned().is_some(),
+        tls.allow_invalid_hostnames
+    );
+    // the frontend reloads its accounts, and connects with the new settings
+    let _ = app_handle.emit("database-changed", ());
+    Ok(())
+}
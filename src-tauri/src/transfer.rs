@@ -0,0 +1,134 @@
+use sqlx::{Sqlite, Transaction};
+use std::collections::HashSet;
+use tauri::Emitter;
+
+use crate::tasks::now_iso;
+use crate::{accounts, db, read_only};
+
+// a task and everything below it, as a CTE over ?1
+const SUBTREE: &str = "WITH RECURSIVE subtree(uid) AS (
+        SELECT ?1
+        UNION SELECT tasks.uid FROM tasks JOIN subtree ON tasks.parent_uid = subtree.uid
+     )";
+
+/// queue the server copies of a task and its sub-tasks for deletion and drop their hrefs, so
+/// the next sync creates them in the collection they moved to. call it before the calendar
+/// changes: the pending deletions keep the calendar and account they were synced to
+pub async fn release(tx: &mut Transaction<'_, Sqlite>, uid: &str) -> Result<(), String> {
+    for statement in [
+        "INSERT OR REPLACE INTO pending_deletions (uid, href, account_id, calendar_id)
+         SELECT uid, href, account_id, calendar_id FROM tasks
+         WHERE uid IN subtree AND href IS NOT NULL",
+        "DELETE FROM ics_cache WHERE href IN (SELECT href FROM tasks WHERE uid IN subtree)",
+        "UPDATE tasks SET href = NULL, etag = NULL WHERE uid IN subtree",
+    ] {
+        sqlx::query(&format!("{SUBTREE} {statement}"))
+            .bind(uid)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// move tasks with their sub-tasks into another calendar, possibly of another account; a
+/// moved task whose parent stays behind becomes a top-level task there. returns the ids of
+/// every task that moved
+pub async fn move_to(
+    tx: &mut Transaction<'_, Sqlite>,
+    ids: &[String],
+    calendar_id: &str,
+) -> Result<Vec<String>, String> {
+    let account_id: String = sqlx::query_scalar("SELECT account_id FROM calendars WHERE id = ?")
+        .bind(calendar_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Calendar {calendar_id} not found"))?;
+
+    let mut selected = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(task) = sqlx::query_as::<_, (String, Option<String>)>(
+            "SELECT uid, calendar_id FROM tasks WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?
+        {
+            selected.push(task);
+        }
+    }
+    let uids: HashSet<&str> = selected.iter().map(|(uid, _)| uid.as_str()).collect();
+
+    let modified_at = now_iso();
+    let mut moved = Vec::new();
+    for (uid, current) in &selected {
+        if current.as_deref() == Some(calendar_id) {
+            continue;
+        }
+        // sub-tasks of another selected task move with it
+        let ancestors: Vec<String> = sqlx::query_scalar(
+            "WITH RECURSIVE ancestors(uid) AS (
+                SELECT parent_uid FROM tasks WHERE uid = ?
+                UNION SELECT tasks.parent_uid FROM tasks
+                JOIN ancestors ON tasks.uid = ancestors.uid
+                WHERE tasks.parent_uid IS NOT NULL
+             )
+             SELECT uid FROM ancestors WHERE uid IS NOT NULL",
+        )
+        .bind(uid)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        if ancestors
+            .iter()
+            .any(|ancestor| uids.contains(ancestor.as_str()))
+        {
+            continue;
+        }
+
+        release(tx, uid).await?;
+        let subtree: Vec<String> = sqlx::query_scalar(&format!(
+            "{SUBTREE}
+             UPDATE tasks
+             SET calendar_id = ?2, account_id = ?3, synced = 0, modified_at = ?4,
+                 parent_uid = CASE WHEN uid = ?1 THEN NULL ELSE parent_uid END
+             WHERE uid IN subtree
+             RETURNING id"
+        ))
+        .bind(uid)
+        .bind(calendar_id)
+        .bind(&account_id)
+        .bind(&modified_at)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        moved.extend(subtree);
+    }
+    Ok(moved)
+}
+
+/// move tasks into another calendar in one transaction; the next sync deletes them from
+/// the old collection and uploads them to the new one. returns how many tasks moved
+#[tauri::command]
+pub async fn move_tasks(
+    app_handle: tauri::AppHandle,
+    ids: Vec<String>,
+    calendar_id: String,
+) -> Result<usize, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    accounts::ensure_tasks_editable(&pool, "id", &ids).await?;
+    accounts::ensure_calendar_editable(&pool, &calendar_id).await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let moved = move_to(&mut tx, &ids, &calendar_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!("Moved {} tasks to calendar {calendar_id}", moved.len());
+    if !moved.is_empty() {
+        // pending deletions changed too, reload everything
+        let _ = app_handle.emit("database-changed", ());
+    }
+    Ok(moved.len())
+}
@@ -1,17 +1,53 @@
 use lazy_static::lazy_static;
 use std::sync::Mutex;
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    image::Image,
+    menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{TrayIconBuilder, TrayIconEvent, TrayIconId},
     Emitter, Manager, Wry,
 };
 
+use crate::{i18n, recent_completions};
+
+// bundled tray icon sizes (in physical pixels), smallest first
+const TRAY_ICONS: &[(u32, &[u8])] = &[
+    (16, include_bytes!("../icons/tray/16x16.png")),
+    (20, include_bytes!("../icons/tray/20x20.png")),
+    (24, include_bytes!("../icons/tray/24x24.png")),
+    (32, include_bytes!("../icons/tray/32x32.png")),
+    (40, include_bytes!("../icons/tray/40x40.png")),
+    (44, include_bytes!("../icons/tray/44x44.png")),
+    (48, include_bytes!("../icons/tray/48x48.png")),
+    (64, include_bytes!("../icons/tray/64x64.png")),
+];
+
+// id prefix of the menu items that reopen a recently completed task
+const UNDO_COMPLETE_PREFIX: &str = "undo_complete:";
+
+// logical size of a tray icon on each platform
+#[cfg(target_os = "macos")]
+const TRAY_ICON_LOGICAL_SIZE: f64 = 22.0;
+#[cfg(target_os = "windows")]
+const TRAY_ICON_LOGICAL_SIZE: f64 = 16.0;
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const TRAY_ICON_LOGICAL_SIZE: f64 = 22.0;
+
 // global storage for the last sync menu item updater function
 lazy_static! {
     static ref MENU_UPDATER: Mutex<Option<Box<dyn Fn(String) + Send>>> = Mutex::new(None);
     static ref SYNC_ITEM: Mutex<Option<MenuItem<Wry>>> = Mutex::new(None);
+    // the last sync time shown in the menu, None while syncing; None until the first update
+    static ref LAST_SYNC: Mutex<Option<Option<String>>> = Mutex::new(None);
     static ref TRAY_VISIBLE: Mutex<bool> = Mutex::new(true);
     static ref TRAY_ENABLED: Mutex<bool> = Mutex::new(true);
+    static ref TRAY_FALLBACK: Mutex<bool> = Mutex::new(false);
+    // short status next to the icon and extra tooltip lines, see set_tray_status/set_tray_note/
+    // set_tray_warning
+    static ref TRAY_STATUS: Mutex<Option<String>> = Mutex::new(None);
+    static ref TRAY_NOTE: Mutex<Option<String>> = Mutex::new(None);
+    static ref TRAY_WARNING: Mutex<Option<String>> = Mutex::new(None);
+    // (id, title) of the tasks in the "Recently Completed" submenu, newest first
+    static ref RECENTLY_COMPLETED: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
 }
 
 /// check if the system tray is currently enabled
@@ -19,34 +55,189 @@ pub fn is_tray_enabled() -> bool {
     *TRAY_ENABLED.lock().expect("Failed to lock TRAY_ENABLED")
 }
 
-/// initialize the system tray (called from frontend after reading settings)
-#[tauri::command]
-pub async fn initialize_tray(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
-    // update the global state
-    *TRAY_VISIBLE.lock().expect("Failed to lock TRAY_VISIBLE") = enabled;
-    *TRAY_ENABLED.lock().expect("Failed to lock TRAY_ENABLED") = enabled;
+/// whether closing the main window should minimize it because the tray is unavailable
+pub fn is_tray_fallback() -> bool {
+    *TRAY_FALLBACK.lock().expect("Failed to lock TRAY_FALLBACK")
+}
 
-    // if tray is disabled, don't create it at all
-    if !enabled {
-        return Ok(());
+/// check whether the desktop can actually show tray icons
+///
+/// on Linux the tray relies on a StatusNotifier host (stock GNOME and some Wayland
+/// setups don't have one), so ask the session bus whether the watcher is running
+#[cfg(target_os = "linux")]
+async fn tray_host_available() -> bool {
+    let check = async {
+        let connection = zbus::Connection::session().await?;
+        let proxy = zbus::fdo::DBusProxy::new(&connection).await?;
+        let name = zbus::names::BusName::try_from("org.kde.StatusNotifierWatcher")?;
+        Ok::<bool, zbus::Error>(proxy.name_has_owner(name).await?)
+    };
+
+    match check.await {
+        Ok(available) => available,
+        Err(e) => {
+            log::warn!("Failed to query StatusNotifierWatcher: {e}");
+            false
+        }
+    }
+}
+
+/// macOS and Windows always provide a tray/menu bar area
+#[cfg(not(target_os = "linux"))]
+async fn tray_host_available() -> bool {
+    true
+}
+
+/// pick the bundled tray icon matching the current display scale
+fn tray_icon(app_handle: &tauri::AppHandle) -> Result<Image<'static>, String> {
+    let scale_factor = app_handle
+        .get_webview_window("main")
+        .and_then(|window| window.scale_factor().ok())
+        .or_else(|| {
+            app_handle
+                .primary_monitor()
+                .ok()
+                .flatten()
+                .map(|monitor| monitor.scale_factor())
+        })
+        .unwrap_or(1.0);
+    let wanted = (TRAY_ICON_LOGICAL_SIZE * scale_factor).round() as u32;
+
+    // use the smallest icon that is at least as big as needed, otherwise the biggest one
+    let (_, bytes) = TRAY_ICONS
+        .iter()
+        .find(|(size, _)| *size >= wanted)
+        .unwrap_or(&TRAY_ICONS[TRAY_ICONS.len() - 1]);
+
+    Image::from_bytes(bytes).map_err(|e| e.to_string())
+}
+
+/// swap the tray icon after the display scale changed (e.g. window moved to a 4K screen)
+pub fn refresh_tray_icon(app_handle: &tauri::AppHandle) {
+    let tray_id = TrayIconId::new("main");
+    if let Some(tray) = app_handle.tray_by_id(&tray_id) {
+        match tray_icon(app_handle) {
+            Ok(icon) => {
+                let _ = tray.set_icon(Some(icon));
+            }
+            Err(e) => log::warn!("Failed to load tray icon: {e}"),
+        }
+    }
+}
+
+fn apply_tray_text(app_handle: &tauri::AppHandle) {
+    let status = TRAY_STATUS
+        .lock()
+        .expect("Failed to lock TRAY_STATUS")
+        .clone();
+    let note = TRAY_NOTE.lock().expect("Failed to lock TRAY_NOTE").clone();
+    let warning = TRAY_WARNING
+        .lock()
+        .expect("Failed to lock TRAY_WARNING")
+        .clone();
+    let tray_id = TrayIconId::new("main");
+    if let Some(tray) = app_handle.tray_by_id(&tray_id) {
+        let mut tooltip = match &status {
+            Some(status) => format!("caldav-tasks - {status}"),
+            None => "caldav-tasks".to_string(),
+        };
+        for line in [warning, note].into_iter().flatten() {
+            tooltip.push('\n');
+            tooltip.push_str(&line);
+        }
+        let _ = tray.set_tooltip(Some(tooltip));
+        let _ = tray.set_title(status.as_deref());
+    }
+}
+
+/// show a short status (e.g. a pomodoro countdown) next to the tray icon and in its tooltip;
+/// None restores the plain tray
+pub fn set_tray_status(app_handle: &tauri::AppHandle, status: Option<&str>) {
+    *TRAY_STATUS.lock().expect("Failed to lock TRAY_STATUS") = status.map(str::to_string);
+    apply_tray_text(app_handle);
+}
+
+/// an extra line below the tray tooltip (e.g. the completion streak); None removes it
+pub fn set_tray_note(app_handle: &tauri::AppHandle, note: Option<&str>) {
+    *TRAY_NOTE.lock().expect("Failed to lock TRAY_NOTE") = note.map(str::to_string);
+    apply_tray_text(app_handle);
+}
+
+/// a problem to point out in the tray tooltip (e.g. an unreachable server); None removes it
+pub fn set_tray_warning(app_handle: &tauri::AppHandle, warning: Option<&str>) {
+    *TRAY_WARNING.lock().expect("Failed to lock TRAY_WARNING") = warning.map(str::to_string);
+    apply_tray_text(app_handle);
+}
+
+// "Last sync: 14:05", or syncing while `time` is None
+fn last_sync_text(time: Option<&str>) -> String {
+    match time {
+        Some(time) => i18n::t_with("Last sync: {}", &[&time]),
+        None => i18n::t("Last sync: Syncing...").to_string(),
     }
+}
+
+// the tray menu in the current language, keeping the last sync time and whether syncing is
+// possible from the menu it replaces
+fn build_tray_menu(app_handle: &tauri::AppHandle) -> Result<Menu<Wry>, String> {
+    let show_item = MenuItem::with_id(
+        app_handle,
+        "show",
+        i18n::t("Show Window"),
+        true,
+        None::<&str>,
+    )
+    .map_err(|e| e.to_string())?;
 
-    let show_item = MenuItem::with_id(&app_handle, "show", "Show Window", true, None::<&str>)
+    let recent_items = RECENTLY_COMPLETED
+        .lock()
+        .expect("Failed to lock RECENTLY_COMPLETED")
+        .iter()
+        .map(|(id, title)| {
+            MenuItem::with_id(
+                app_handle,
+                format!("{UNDO_COMPLETE_PREFIX}{id}"),
+                i18n::t_with("Undo \"{}\"", &[title]),
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
+    let recent_refs: Vec<&dyn IsMenuItem<Wry>> = recent_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let recent_menu = Submenu::with_items(
+        app_handle,
+        i18n::t("Recently Completed"),
+        !recent_items.is_empty(),
+        &recent_refs,
+    )
+    .map_err(|e| e.to_string())?;
 
-    let separator_item1 = PredefinedMenuItem::separator(&app_handle).map_err(|e| e.to_string())?;
+    let separator_item1 = PredefinedMenuItem::separator(app_handle).map_err(|e| e.to_string())?;
 
-    let last_sync_item = MenuItem::with_id(
-        &app_handle,
-        "last_sync",
-        "Last sync: Never",
-        false,
+    let last_sync = match LAST_SYNC.lock().expect("Failed to lock LAST_SYNC").as_ref() {
+        Some(time) => last_sync_text(time.as_deref()),
+        None => i18n::t("Last sync: Never").to_string(),
+    };
+    let last_sync_item = MenuItem::with_id(app_handle, "last_sync", last_sync, false, None::<&str>)
+        .map_err(|e| e.to_string())?;
+    let sync_enabled = SYNC_ITEM
+        .lock()
+        .expect("Failed to lock SYNC_ITEM")
+        .as_ref()
+        .and_then(|item| item.is_enabled().ok())
+        .unwrap_or(true);
+    let sync_item = MenuItem::with_id(
+        app_handle,
+        "sync",
+        i18n::t("Sync Now"),
+        sync_enabled,
         None::<&str>,
     )
     .map_err(|e| e.to_string())?;
-    let sync_item =
-        MenuItem::with_id(&app_handle, "sync", "Sync Now", true, None::<&str>)
-            .map_err(|e| e.to_string())?;
 
     // Store a closure that can update the last sync item text
     let item_clone = last_sync_item.clone();
@@ -58,15 +249,15 @@ pub async fn initialize_tray(app_handle: tauri::AppHandle, enabled: bool) -> Res
     // Store the sync item for enable/disable updates
     *SYNC_ITEM.lock().expect("Failed to lock SYNC_ITEM") = Some(sync_item.clone());
 
-    let separator_item2 = PredefinedMenuItem::separator(&app_handle).map_err(|e| e.to_string())?;
-    let quit_item =
-        MenuItem::with_id(&app_handle, "quit", "Quit", true, None::<&str>)
-            .map_err(|e| e.to_string())?;
+    let separator_item2 = PredefinedMenuItem::separator(app_handle).map_err(|e| e.to_string())?;
+    let quit_item = MenuItem::with_id(app_handle, "quit", i18n::t("Quit"), true, None::<&str>)
+        .map_err(|e| e.to_string())?;
 
-    let menu = Menu::with_items(
-        &app_handle,
+    Menu::with_items(
+        app_handle,
         &[
             &show_item,
+            &recent_menu,
             &separator_item1,
             &last_sync_item,
             &sync_item,
@@ -74,13 +265,65 @@ pub async fn initialize_tray(app_handle: tauri::AppHandle, enabled: bool) -> Res
             &quit_item,
         ],
     )
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| e.to_string())
+}
 
-    // get the default window icon
-    let icon = app_handle
-        .default_window_icon()
-        .ok_or_else(|| "No default window icon found".to_string())?
-        .clone();
+/// build the tray menu again in the current language
+pub fn rebuild_tray_menu(app_handle: &tauri::AppHandle) {
+    let tray_id = TrayIconId::new("main");
+    let Some(tray) = app_handle.tray_by_id(&tray_id) else {
+        return;
+    };
+    match build_tray_menu(app_handle) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::warn!("Failed to rebuild the tray menu: {e}"),
+    }
+}
+
+/// the tasks to offer undoing the completion of, as (id, title) newest first; the menu is
+/// only rebuilt when they changed
+pub fn set_recently_completed(app_handle: &tauri::AppHandle, tasks: Vec<(String, String)>) {
+    {
+        let mut current = RECENTLY_COMPLETED
+            .lock()
+            .expect("Failed to lock RECENTLY_COMPLETED");
+        if *current == tasks {
+            return;
+        }
+        *current = tasks;
+    }
+    rebuild_tray_menu(app_handle);
+}
+
+/// initialize the system tray (called from frontend after reading settings)
+#[tauri::command]
+pub async fn initialize_tray(app_handle: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    // update the global state
+    *TRAY_VISIBLE.lock().expect("Failed to lock TRAY_VISIBLE") = enabled;
+    *TRAY_ENABLED.lock().expect("Failed to lock TRAY_ENABLED") = enabled;
+
+    *TRAY_FALLBACK.lock().expect("Failed to lock TRAY_FALLBACK") = false;
+
+    // if tray is disabled, don't create it at all
+    if !enabled {
+        return Ok(());
+    }
+
+    // without a tray host the icon would silently vanish; minimize on close instead
+    // so the app (and its background sync/notifications) stays reachable
+    if !tray_host_available().await {
+        log::warn!("No system tray available, falling back to minimize on close");
+        *TRAY_VISIBLE.lock().expect("Failed to lock TRAY_VISIBLE") = false;
+        *TRAY_ENABLED.lock().expect("Failed to lock TRAY_ENABLED") = false;
+        *TRAY_FALLBACK.lock().expect("Failed to lock TRAY_FALLBACK") = true;
+        let _ = app_handle.emit("tray-unavailable", ());
+        return Ok(());
+    }
+
+    let menu = build_tray_menu(&app_handle)?;
+    let icon = tray_icon(&app_handle)?;
 
     let _tray = TrayIconBuilder::with_id("main")
         .icon(icon)
@@ -108,7 +351,11 @@ pub async fn initialize_tray(app_handle: tauri::AppHandle, enabled: bool) -> Res
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                if let Some(task_id) = id.strip_prefix(UNDO_COMPLETE_PREFIX) {
+                    recent_completions::undo(app, task_id);
+                }
+            }
         })
         .on_tray_icon_event(|_tray, event| {
             // on macOS, clicking the tray icon shows the menu (handled automatically)
@@ -120,26 +367,39 @@ pub async fn initialize_tray(app_handle: tauri::AppHandle, enabled: bool) -> Res
         .build(&app_handle)
         .map_err(|e| e.to_string())?;
 
+    // status, note or warning set before the tray existed
+    apply_tray_text(&app_handle);
+
+    log::info!("System tray initialized");
     Ok(())
 }
 
+/// check whether the system tray can be shown on this desktop
+#[tauri::command]
+pub async fn is_tray_supported() -> Result<bool, String> {
+    Ok(tray_host_available().await)
+}
+
 /// get the current tray enabled state (for frontend to read on startup)
 #[tauri::command]
 pub async fn get_tray_enabled() -> Result<bool, String> {
     Ok(is_tray_enabled())
 }
 
+/// show the last sync time in the tray menu; None while a sync runs
 #[tauri::command]
 pub async fn update_tray_sync_time(
     _app_handle: tauri::AppHandle,
-    time_str: String,
+    time_str: Option<String>,
 ) -> Result<(), String> {
+    let text = last_sync_text(time_str.as_deref());
+    *LAST_SYNC.lock().expect("Failed to lock LAST_SYNC") = Some(time_str);
     if let Some(updater) = MENU_UPDATER
         .lock()
         .expect("Failed to lock MENU_UPDATER")
         .as_ref()
     {
-        updater(time_str);
+        updater(text);
     }
     Ok(())
 }
@@ -0,0 +1,302 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::{db, read_only, settings};
+
+// release manifests per channel
+const STABLE_ENDPOINT: &str =
+    "https://github.com/sapphies/caldav-tasks/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/sapphies/caldav-tasks/releases/download/beta/latest.json";
+
+// where users of packaged builds are sent to get the new release
+const RELEASES_URL: &str = "https://github.com/sapphies/caldav-tasks/releases";
+
+// env var and marker file (next to the executable) that turn off self-updating
+const NO_AUTO_UPDATE_ENV: &str = "CALDAV_TASKS_NO_AUTO_UPDATE";
+const PORTABLE_MARKER: &str = "portable";
+
+// the release channel and whether to check on a schedule
+const SETTINGS_KEY: &str = "updates";
+
+// delay before the first scheduled check, and the interval between checks
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+// global storage for the update found by the last check
+lazy_static! {
+    static ref PENDING_UPDATE: Mutex<Option<Update>> = Mutex::new(None);
+    static ref DOWNLOADED_UPDATE: Mutex<Option<(String, Vec<u8>)>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub notes: Option<String>,
+    pub date: Option<String>,
+    pub channel: String,
+    /// false for packaged/portable builds, which should link to `release_url` instead
+    pub can_install: bool,
+    pub release_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSettings {
+    /// "stable" or "beta"
+    pub channel: String,
+    /// check on a schedule and download new releases in the background
+    pub auto_check: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: "stable".to_string(),
+            auto_check: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMode {
+    /// "auto" or "manual"
+    pub mode: String,
+    /// why self-updating is disabled ("packaged", "flatpak", "portable" or "disabled")
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadProgress {
+    downloaded: usize,
+    total: Option<u64>,
+}
+
+/// why this build must not update itself, if it mustn't
+///
+/// distro packages (AUR, Flatpak, ...) are updated by the package manager and portable
+/// installs shouldn't rewrite themselves, so those only get a manual availability check
+fn self_update_blocked_reason() -> Option<&'static str> {
+    if cfg!(feature = "no-auto-update") {
+        return Some("packaged");
+    }
+    if std::env::var_os("FLATPAK_ID").is_some() || std::path::Path::new("/.flatpak-info").exists() {
+        return Some("flatpak");
+    }
+    if std::env::var_os(NO_AUTO_UPDATE_ENV).is_some() {
+        return Some("disabled");
+    }
+    let portable = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(PORTABLE_MARKER).exists()))
+        .unwrap_or(false);
+    if portable {
+        return Some("portable");
+    }
+    None
+}
+
+async fn load_settings(app_handle: &tauri::AppHandle) -> Result<UpdateSettings, String> {
+    let pool = db::pool(app_handle).await?;
+    Ok(settings::get(&pool, SETTINGS_KEY)
+        .await?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+fn endpoint_for(channel: &str) -> &'static str {
+    match channel {
+        "beta" => BETA_ENDPOINT,
+        _ => STABLE_ENDPOINT,
+    }
+}
+
+/// query the release manifest of the selected channel and remember what was found; the
+/// frontend hears about a new release through `update-available`
+async fn check(app_handle: &tauri::AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let channel = load_settings(app_handle).await?.channel;
+    let endpoint = Url::parse(endpoint_for(&channel)).map_err(|e| e.to_string())?;
+
+    let update = app_handle
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .map_err(|e| e.to_string())?
+        .build()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+        channel: channel.clone(),
+        can_install: self_update_blocked_reason().is_none(),
+        release_url: RELEASES_URL.to_string(),
+    });
+
+    // drop a previously downloaded package if it's not the version we'd install now
+    {
+        let mut downloaded = DOWNLOADED_UPDATE
+            .lock()
+            .expect("Failed to lock DOWNLOADED_UPDATE");
+        let still_valid = matches!(
+            (&*downloaded, &info),
+            (Some((version, _)), Some(info)) if *version == info.version
+        );
+        if !still_valid {
+            *downloaded = None;
+        }
+    }
+    *PENDING_UPDATE
+        .lock()
+        .expect("Failed to lock PENDING_UPDATE") = update;
+
+    if let Some(info) = &info {
+        log::info!("Update available: {}", info.version);
+        let _ = app_handle.emit("update-available", info);
+    }
+    Ok(info)
+}
+
+/// download the pending update, emitting progress events to the frontend
+async fn download(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let update = PENDING_UPDATE
+        .lock()
+        .expect("Failed to lock PENDING_UPDATE")
+        .clone()
+        .ok_or_else(|| "No update available".to_string())?;
+
+    let already_downloaded = DOWNLOADED_UPDATE
+        .lock()
+        .expect("Failed to lock DOWNLOADED_UPDATE")
+        .as_ref()
+        .is_some_and(|(version, _)| *version == update.version);
+    if already_downloaded {
+        return Ok(());
+    }
+
+    let mut downloaded = 0;
+    let bytes = update
+        .download(
+            |chunk_length, total| {
+                downloaded += chunk_length;
+                let _ = app_handle.emit(
+                    "update-download-progress",
+                    DownloadProgress { downloaded, total },
+                );
+            },
+            || {},
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    *DOWNLOADED_UPDATE
+        .lock()
+        .expect("Failed to lock DOWNLOADED_UPDATE") = Some((update.version.clone(), bytes));
+    let _ = app_handle.emit("update-downloaded", update.version.clone());
+
+    Ok(())
+}
+
+/// periodically check for updates and download them in the background
+pub fn start_scheduled_checks(app_handle: tauri::AppHandle) {
+    if let Some(reason) = self_update_blocked_reason() {
+        log::info!("Automatic updates disabled ({reason}), only manual checks are available");
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            match load_settings(&app_handle).await {
+                Ok(settings) if settings.auto_check => match check(&app_handle).await {
+                    Ok(Some(_)) => {
+                        if let Err(e) = download(&app_handle).await {
+                            log::warn!("Failed to download update: {e}");
+                        }
+                    }
+                    Ok(None) => log::debug!("No update available"),
+                    Err(e) => log::warn!("Scheduled update check failed: {e}"),
+                },
+                Ok(_) => log::debug!("Scheduled update checks are off"),
+                Err(e) => log::warn!("Failed to load the update settings: {e}"),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_update_settings(app_handle: tauri::AppHandle) -> Result<UpdateSettings, String> {
+    load_settings(&app_handle).await
+}
+
+/// set the release channel and whether to check on a schedule; the next scheduled check
+/// picks them up
+#[tauri::command]
+pub async fn set_update_settings(
+    app_handle: tauri::AppHandle,
+    settings: UpdateSettings,
+) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    if !matches!(settings.channel.as_str(), "stable" | "beta") {
+        return Err(format!("Unknown update channel: {}", settings.channel));
+    }
+
+    let pool = db::pool(&app_handle).await?;
+    let json = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    settings::set(&pool, SETTINGS_KEY, &json).await
+}
+
+/// report whether this build updates itself or only supports manual checks
+#[tauri::command]
+pub async fn get_update_mode() -> Result<UpdateMode, String> {
+    let reason = self_update_blocked_reason();
+    Ok(UpdateMode {
+        mode: if reason.is_some() { "manual" } else { "auto" }.to_string(),
+        reason: reason.map(str::to_string),
+    })
+}
+
+/// check the selected channel for a newer release
+#[tauri::command]
+pub async fn check_for_updates(app_handle: tauri::AppHandle) -> Result<Option<UpdateInfo>, String> {
+    check(&app_handle).await
+}
+
+/// download (if not done in the background already), install and restart
+#[tauri::command]
+pub async fn install_update(app_handle: tauri::AppHandle) -> Result<(), String> {
+    if let Some(reason) = self_update_blocked_reason() {
+        return Err(format!(
+            "Updates for this build ({reason}) are managed outside the app, see {RELEASES_URL}"
+        ));
+    }
+
+    download(&app_handle).await?;
+
+    let update = PENDING_UPDATE
+        .lock()
+        .expect("Failed to lock PENDING_UPDATE")
+        .clone()
+        .ok_or_else(|| "No update available".to_string())?;
+    let (_, bytes) = DOWNLOADED_UPDATE
+        .lock()
+        .expect("Failed to lock DOWNLOADED_UPDATE")
+        .take()
+        .ok_or_else(|| "Update has not been downloaded".to_string())?;
+
+    update.install(bytes).map_err(|e| e.to_string())?;
+    app_handle.restart();
+}
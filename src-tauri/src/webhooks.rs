@@ -0,0 +1,498 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration as ChronoDuration, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::{FromRow, SqlitePool};
+use std::time::Duration;
+use tauri_plugin_http::reqwest::{self, header, Url};
+
+use crate::tasks::{fetch_by_ids, new_id, now_iso};
+use crate::{caldav, credentials, db, lock, read_only, settings};
+
+// until when task events were looked at; nothing before the first run is sent
+const CHECKED_KEY: &str = "webhooks_checked_at";
+const RUN_INTERVAL: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// waits before the 2nd, 3rd, ... attempt; a delivery fails for good after the last one
+pub const RETRY_DELAYS: &[i64] = &[60, 5 * 60, 30 * 60, 2 * 60 * 60];
+// delivered and failed deliveries are kept this long
+const LOG_DAYS: i64 = 30;
+
+lazy_static! {
+    // a run and a test delivery must not send the same pending delivery twice
+    static ref DELIVERING: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskEvent {
+    Created,
+    Completed,
+    /// an open task passed its due date (for all-day tasks, once the day is over)
+    Overdue,
+}
+
+impl TaskEvent {
+    fn name(self) -> &'static str {
+        match self {
+            TaskEvent::Created => "task.created",
+            TaskEvent::Completed => "task.completed",
+            TaskEvent::Overdue => "task.overdue",
+        }
+    }
+
+    // tasks the event happened to in (?1, ?2]
+    fn condition(self) -> &'static str {
+        match self {
+            TaskEvent::Created => "created_at > ?1 AND created_at <= ?2",
+            TaskEvent::Completed => {
+                "completed = 1 AND completed_at > ?1 AND completed_at <= ?2"
+            }
+            TaskEvent::Overdue => {
+                "completed = 0 AND due_date IS NOT NULL
+                 AND julianday(due_date, CASE WHEN due_date_all_day = 1 THEN '+1 day' ELSE '+0 days' END)
+                    BETWEEN julianday(?1) AND julianday(?2)"
+            }
+        }
+    }
+
+    /// ids of the unarchived tasks the event happened to after `since`, up to `until`
+    pub async fn task_ids(
+        self,
+        pool: &SqlitePool,
+        since: &str,
+        until: &str,
+    ) -> Result<Vec<String>, String> {
+        sqlx::query_scalar(&format!(
+            "SELECT id FROM tasks WHERE archived_at IS NULL AND {}",
+            self.condition()
+        ))
+        .bind(since)
+        .bind(until)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<TaskEvent>,
+    pub enabled: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, FromRow)]
+struct WebhookRow {
+    id: String,
+    url: String,
+    events: String,
+    enabled: bool,
+    created_at: String,
+}
+
+impl From<WebhookRow> for Webhook {
+    fn from(row: WebhookRow) -> Self {
+        Webhook {
+            id: row.id,
+            url: row.url,
+            events: serde_json::from_str(&row.events).unwrap_or_default(),
+            enabled: row.enabled,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// one entry of the delivery log
+#[derive(Debug, Clone, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: String,
+    pub task_uid: Option<String>,
+    /// pending, delivered or failed
+    pub status: String,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub next_attempt_at: Option<String>,
+    pub delivered_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct PendingDelivery {
+    id: String,
+    webhook_id: String,
+    url: String,
+    event: String,
+    payload: String,
+    attempts: i64,
+}
+
+// the credential store entry of a webhook's signing secret
+fn secret_entry(webhook_id: &str) -> String {
+    format!("webhook:{webhook_id}")
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// hex HMAC-SHA256 of the body, sent as `X-Caldav-Tasks-Signature: sha256=<hex>`
+fn sign(secret: &str, body: &str) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| e.to_string())?;
+    mac.update(body.as_bytes());
+    Ok(mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// the iso time `seconds` from now
+pub fn later(seconds: i64) -> String {
+    (Utc::now() + ChronoDuration::seconds(seconds)).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+async fn load_webhooks(pool: &SqlitePool) -> Result<Vec<Webhook>, String> {
+    let rows: Vec<WebhookRow> = sqlx::query_as("SELECT * FROM webhooks ORDER BY created_at")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(Webhook::from).collect())
+}
+
+async fn queue(
+    pool: &SqlitePool,
+    webhook_id: &str,
+    event: &str,
+    task_uid: Option<&str>,
+    payload: &serde_json::Value,
+) -> Result<String, String> {
+    let id = new_id();
+    let now = now_iso();
+    sqlx::query(
+        "INSERT INTO webhook_deliveries
+            (id, webhook_id, event, task_uid, payload, created_at, next_attempt_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(webhook_id)
+    .bind(event)
+    .bind(task_uid)
+    .bind(payload.to_string())
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+// queue a delivery per subscribed webhook for everything that happened since the last run
+async fn queue_events(pool: &SqlitePool) -> Result<(), String> {
+    let now = now_iso();
+    let Some(since) = settings::get(pool, CHECKED_KEY).await? else {
+        return settings::set(pool, CHECKED_KEY, &now).await;
+    };
+
+    let webhooks: Vec<Webhook> = load_webhooks(pool)
+        .await?
+        .into_iter()
+        .filter(|webhook| webhook.enabled)
+        .collect();
+    for event in [TaskEvent::Created, TaskEvent::Completed, TaskEvent::Overdue] {
+        let subscribed: Vec<&Webhook> = webhooks
+            .iter()
+            .filter(|webhook| webhook.events.contains(&event))
+            .collect();
+        if subscribed.is_empty() {
+            continue;
+        }
+        let ids = event.task_ids(pool, &since, &now).await?;
+        for task in fetch_by_ids(pool, &ids).await? {
+            let payload = json!({ "event": event.name(), "timestamp": now, "task": task });
+            for webhook in &subscribed {
+                queue(pool, &webhook.id, event.name(), Some(&task.uid), &payload).await?;
+            }
+        }
+    }
+    settings::set(pool, CHECKED_KEY, &now).await
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    pool: &SqlitePool,
+    delivery: PendingDelivery,
+) -> Result<(), String> {
+    let secret = credentials::get_password(&secret_entry(&delivery.webhook_id))
+        .await?
+        .unwrap_or_default();
+    let signature = sign(&secret, &delivery.payload)?;
+    let response = client
+        .post(&delivery.url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("X-Caldav-Tasks-Event", &delivery.event)
+        .header("X-Caldav-Tasks-Delivery", &delivery.id)
+        .header("X-Caldav-Tasks-Signature", format!("sha256={signature}"))
+        .body(delivery.payload)
+        .send()
+        .await;
+
+    let (status, error) = match response {
+        Ok(response) if response.status().is_success() => {
+            sqlx::query(
+                "UPDATE webhook_deliveries
+                 SET status = 'delivered', attempts = attempts + 1, response_status = ?,
+                    error = NULL, next_attempt_at = NULL, delivered_at = ?
+                 WHERE id = ?",
+            )
+            .bind(response.status().as_u16())
+            .bind(now_iso())
+            .bind(&delivery.id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            return Ok(());
+        }
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            format!("Server answered {}", response.status()),
+        ),
+        Err(e) => (None, caldav::error_chain(&e)),
+    };
+
+    let retry = RETRY_DELAYS.get(delivery.attempts as usize).copied();
+    log::warn!(
+        "Webhook delivery {} failed (attempt {}): {error}",
+        delivery.id,
+        delivery.attempts + 1
+    );
+    sqlx::query(
+        "UPDATE webhook_deliveries
+         SET status = ?, attempts = attempts + 1, response_status = ?, error = ?,
+            next_attempt_at = ?
+         WHERE id = ?",
+    )
+    .bind(if retry.is_some() { "pending" } else { "failed" })
+    .bind(status)
+    .bind(&error)
+    .bind(retry.map(later))
+    .bind(&delivery.id)
+    .execute(pool)
+    .await
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+async fn deliver_pending(pool: &SqlitePool) -> Result<(), String> {
+    let _delivering = DELIVERING.lock().await;
+    let pending: Vec<PendingDelivery> = sqlx::query_as(
+        "SELECT webhook_deliveries.id, webhook_id, url, event, payload, attempts
+         FROM webhook_deliveries JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+         WHERE status = 'pending' AND next_attempt_at <= ? AND webhooks.enabled = 1
+         ORDER BY webhook_deliveries.created_at",
+    )
+    .bind(now_iso())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("caldav-tasks/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())?;
+    for delivery in pending {
+        deliver(&client, pool, delivery).await?;
+    }
+    Ok(())
+}
+
+/// queue deliveries for new task events, send what's due and trim the delivery log
+#[tracing::instrument(name = "webhooks", level = "trace", skip_all, err(level = "debug"))]
+pub async fn run(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    if read_only::is_enabled() {
+        return Ok(());
+    }
+    let pool = db::pool(app_handle).await?;
+    queue_events(&pool).await?;
+    deliver_pending(&pool).await?;
+
+    sqlx::query("DELETE FROM webhook_deliveries WHERE status != 'pending' AND created_at < ?")
+        .bind(later(-LOG_DAYS * 24 * 60 * 60))
+        .execute(&pool)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// look for task events and send webhooks every minute
+pub fn start_scheduled_runs(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(RUN_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run(&app_handle).await {
+                log::warn!("Webhook run failed: {e}");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_webhooks(app_handle: tauri::AppHandle) -> Result<Vec<Webhook>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    load_webhooks(&pool).await
+}
+
+/// add a webhook (without an id) or change one; a new webhook gets a signing secret
+#[tauri::command]
+pub async fn save_webhook(
+    app_handle: tauri::AppHandle,
+    id: Option<String>,
+    url: String,
+    events: Vec<TaskEvent>,
+    enabled: bool,
+) -> Result<Webhook, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+
+    let url = url.trim().to_string();
+    let parsed = Url::parse(&url).map_err(|e| format!("Invalid webhook URL: {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Webhook URLs have to start with http:// or https://".to_string());
+    }
+    let events = serde_json::to_string(&events).map_err(|e| e.to_string())?;
+
+    let id = match id {
+        Some(id) => {
+            let updated =
+                sqlx::query("UPDATE webhooks SET url = ?, events = ?, enabled = ? WHERE id = ?")
+                    .bind(&url)
+                    .bind(&events)
+                    .bind(enabled)
+                    .bind(&id)
+                    .execute(&pool)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .rows_affected();
+            if updated == 0 {
+                return Err(format!("Webhook {id} not found"));
+            }
+            id
+        }
+        None => {
+            let id = new_id();
+            credentials::set_password(&secret_entry(&id), generate_secret()).await?;
+            sqlx::query(
+                "INSERT INTO webhooks (id, url, events, enabled, created_at)
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(&id)
+            .bind(&url)
+            .bind(&events)
+            .bind(enabled)
+            .bind(now_iso())
+            .execute(&pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            id
+        }
+    };
+
+    load_webhooks(&pool)
+        .await?
+        .into_iter()
+        .find(|webhook| webhook.id == id)
+        .ok_or_else(|| format!("Webhook {id} not found"))
+}
+
+/// remove a webhook with its delivery log and signing secret
+#[tauri::command]
+pub async fn delete_webhook(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query("DELETE FROM webhooks WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    credentials::delete_password(&secret_entry(&id)).await
+}
+
+/// the secret the payloads of a webhook are signed with, for setting up the receiver
+#[tauri::command]
+pub async fn get_webhook_secret(
+    app_handle: tauri::AppHandle,
+    id: String,
+) -> Result<String, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    credentials::get_password(&secret_entry(&id))
+        .await?
+        .ok_or_else(|| format!("Webhook {id} has no secret"))
+}
+
+/// the latest deliveries, of one webhook or of all of them
+#[tauri::command]
+pub async fn get_webhook_deliveries(
+    app_handle: tauri::AppHandle,
+    webhook_id: Option<String>,
+    limit: u32,
+) -> Result<Vec<WebhookDelivery>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    let pool = db::pool(&app_handle).await?;
+    sqlx::query_as(
+        "SELECT id, webhook_id, event, task_uid, status, attempts, response_status, error,
+            created_at, next_attempt_at, delivered_at
+         FROM webhook_deliveries
+         WHERE ?1 IS NULL OR webhook_id = ?1
+         ORDER BY created_at DESC
+         LIMIT ?2",
+    )
+    .bind(&webhook_id)
+    .bind(limit)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// send a `ping` event to a webhook right away; returns the delivery as logged
+#[tauri::command]
+pub async fn test_webhook(
+    app_handle: tauri::AppHandle,
+    id: String,
+) -> Result<WebhookDelivery, String> {
+    read_only::ensure_writable()?;
+    let pool = db::pool(&app_handle).await?;
+    let payload = json!({ "event": "ping", "timestamp": now_iso() });
+    let delivery_id = queue(&pool, &id, "ping", None, &payload).await?;
+    deliver_pending(&pool).await?;
+
+    sqlx::query_as(
+        "SELECT id, webhook_id, event, task_uid, status, attempts, response_status, error,
+            created_at, next_attempt_at, delivered_at
+         FROM webhook_deliveries WHERE id = ?",
+    )
+    .bind(&delivery_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| e.to_string())
+}
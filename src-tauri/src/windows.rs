@@ -0,0 +1,102 @@
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::http_actions;
+
+// window label prefix for lists opened in their own window
+const CALENDAR_WINDOW_PREFIX: &str = "calendar-";
+
+// global storage for which calendar each secondary window is showing (label -> calendar id)
+lazy_static! {
+    static ref WINDOW_CALENDARS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// window labels only allow alphanumerics, `-`, `/`, `:` and `_`
+fn window_label(calendar_id: &str) -> String {
+    let sanitized: String = calendar_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{CALENDAR_WINDOW_PREFIX}{sanitized}")
+}
+
+/// whether a window label belongs to a per-calendar window
+pub fn is_calendar_window(label: &str) -> bool {
+    label.starts_with(CALENDAR_WINDOW_PREFIX)
+}
+
+/// drop the per-window state once a calendar window is destroyed
+pub fn forget_window(label: &str) {
+    WINDOW_CALENDARS
+        .lock()
+        .expect("Failed to lock WINDOW_CALENDARS")
+        .remove(label);
+}
+
+/// open a calendar/list in its own window, or focus it if it's already open
+#[tauri::command]
+pub async fn open_calendar_window(
+    app_handle: tauri::AppHandle,
+    calendar_id: String,
+    title: Option<String>,
+) -> Result<(), String> {
+    let label = window_label(&calendar_id);
+
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    WINDOW_CALENDARS
+        .lock()
+        .expect("Failed to lock WINDOW_CALENDARS")
+        .insert(label.clone(), calendar_id.clone());
+
+    // the frontend starts a light view of just this calendar for the parameter
+    let url = format!(
+        "index.html?calendar={}",
+        http_actions::percent_encode(&calendar_id)
+    );
+    let url = WebviewUrl::App(url.into());
+    let builder = WebviewWindowBuilder::new(&app_handle, &label, url)
+        .title(title.unwrap_or_else(|| "caldav-tasks".to_string()))
+        .inner_size(480.0, 700.0)
+        .min_inner_size(360.0, 400.0)
+        .resizable(true);
+    // transparent like the main window (see tauri.windows.conf.json) for the window effect
+    #[cfg(target_os = "windows")]
+    let builder = builder.transparent(true);
+    let result = builder.build();
+
+    match result {
+        Ok(window) => {
+            log::debug!("Opened window for calendar {calendar_id}");
+            crate::effects::apply_current_effect(&window);
+            Ok(())
+        }
+        Err(e) => {
+            forget_window(&label);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// list the calendars currently open in their own windows
+#[tauri::command]
+pub async fn get_open_calendar_windows() -> Result<Vec<String>, String> {
+    Ok(WINDOW_CALENDARS
+        .lock()
+        .expect("Failed to lock WINDOW_CALENDARS")
+        .values()
+        .cloned()
+        .collect())
+}
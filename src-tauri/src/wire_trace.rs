@@ -0,0 +1,153 @@
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Emitter;
+
+use crate::lock;
+use crate::redact::{self, REDACTED};
+
+// size caps so a long debugging session can't eat memory
+const MAX_ENTRIES: usize = 200;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+// global storage for the trace toggle and the recorded exchanges (oldest first)
+lazy_static! {
+    static ref TRACE_ENABLED: Mutex<bool> = Mutex::new(false);
+    static ref TRACE: Mutex<VecDeque<WireTraceEntry>> = Mutex::new(VecDeque::new());
+}
+
+/// one recorded request/response pair of a sync operation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WireTraceEntry {
+    #[serde(default)]
+    pub timestamp: u64,
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub request_headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub request_body: Option<String>,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub response_headers: BTreeMap<String, String>,
+    #[serde(default)]
+    pub response_body: Option<String>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// whether sync operations should currently be traced
+pub fn is_enabled() -> bool {
+    *TRACE_ENABLED.lock().expect("Failed to lock TRACE_ENABLED")
+}
+
+fn sanitize_headers(headers: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    headers
+        .into_iter()
+        .map(|(name, value)| {
+            if redact::is_sensitive_header(&name) {
+                (name, REDACTED.to_string())
+            } else {
+                (name, redact::strip_userinfo(&value))
+            }
+        })
+        .collect()
+}
+
+fn sanitize_body(body: Option<String>) -> Option<String> {
+    body.map(|body| {
+        let mut body = redact::strip_userinfo(&body);
+        if body.len() > MAX_BODY_BYTES {
+            let mut cut = MAX_BODY_BYTES;
+            while !body.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            let total = body.len();
+            body.truncate(cut);
+            body.push_str(&format!("\n[truncated, {total} bytes total]"));
+        }
+        body
+    })
+}
+
+/// record an exchange with credentials removed; a no-op while tracing is off
+pub fn record(mut entry: WireTraceEntry) {
+    if !is_enabled() {
+        return;
+    }
+
+    if entry.timestamp == 0 {
+        entry.timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+    }
+    entry.url = redact::redact(&entry.url);
+    entry.request_headers = sanitize_headers(entry.request_headers);
+    entry.response_headers = sanitize_headers(entry.response_headers);
+    entry.request_body = sanitize_body(entry.request_body);
+    entry.response_body = sanitize_body(entry.response_body);
+    entry.error = entry.error.map(|error| redact::redact(&error));
+
+    let mut trace = TRACE.lock().expect("Failed to lock TRACE");
+    if trace.len() >= MAX_ENTRIES {
+        trace.pop_front();
+    }
+    trace.push_back(entry);
+}
+
+/// turn wire tracing on or off (clears the recorded trace when turned off)
+#[tauri::command]
+pub async fn set_wire_trace_enabled(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    *TRACE_ENABLED.lock().expect("Failed to lock TRACE_ENABLED") = enabled;
+    if !enabled {
+        TRACE.lock().expect("Failed to lock TRACE").clear();
+    }
+    log::info!(
+        "CalDAV wire trace {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    let _ = app_handle.emit("wire-trace-changed", enabled);
+    Ok(())
+}
+
+/// get the wire trace state (for frontend to read on startup)
+#[tauri::command]
+pub async fn get_wire_trace_enabled() -> Result<bool, String> {
+    Ok(is_enabled())
+}
+
+/// record an exchange made by the frontend http client
+#[tauri::command]
+pub async fn record_wire_trace(entry: WireTraceEntry) -> Result<(), String> {
+    record(entry);
+    Ok(())
+}
+
+/// get all recorded exchanges, oldest first
+#[tauri::command]
+pub async fn get_wire_trace(app_handle: tauri::AppHandle) -> Result<Vec<WireTraceEntry>, String> {
+    lock::ensure_unlocked(&app_handle).await?;
+    Ok(TRACE
+        .lock()
+        .expect("Failed to lock TRACE")
+        .iter()
+        .cloned()
+        .collect())
+}
+
+/// drop all recorded exchanges
+#[tauri::command]
+pub async fn clear_wire_trace() -> Result<(), String> {
+    TRACE.lock().expect("Failed to lock TRACE").clear();
+    Ok(())
+}
@@ -1,26 +1,46 @@
 import { useCallback, useEffect, useState } from 'react';
+import { ActionableView } from '@/components/ActionableView';
+import { AgendaView } from '@/components/AgendaView';
+import { ArchiveView } from '@/components/ArchiveView';
 import { DragOverlay } from '@/components/DragOverlay';
 import { Header } from '@/components/Header';
+import { KanbanView } from '@/components/KanbanView';
 import { AccountModal } from '@/components/modals/AccountModal';
 import { CreateCalendarModal } from '@/components/modals/CreateCalendarModal';
 import { ExportModal } from '@/components/modals/ExportModal';
 import { ImportModal } from '@/components/modals/ImportModal';
 import { OnboardingModal } from '@/components/modals/OnboardingModal';
+import { QuickOpenModal } from '@/components/modals/QuickOpenModal';
 import { SettingsModal, type SettingsSubtab } from '@/components/modals/SettingsModal';
 import { UpdateModal } from '@/components/modals/UpdateModal';
+import { NextActionsView } from '@/components/NextActionsView';
+import { PlannedView } from '@/components/PlannedView';
 import { Sidebar } from '@/components/Sidebar';
+import { StatsView } from '@/components/StatsView';
 import { TaskEditor } from '@/components/TaskEditor';
 import { TaskList } from '@/components/TaskList';
-import { useAccounts, useSyncQuery, useTasks, useUIState } from '@/hooks/queries';
+import { TimeView } from '@/components/TimeView';
+import { UnreachableBanner } from '@/components/UnreachableBanner';
+import { useAccounts, useReadOnly, useSyncQuery, useTasks, useUIState } from '@/hooks/queries';
+import { useAppLock } from '@/hooks/useAppLock';
 import { useAppMenu } from '@/hooks/useAppMenu';
+import { useCrashReport } from '@/hooks/useCrashReport';
+import { useCredentialMigration } from '@/hooks/useCredentialMigration';
 import { useFileDrop } from '@/hooks/useFileDrop';
 import { useKeyboardShortcuts } from '@/hooks/useKeyboardShortcuts';
 import { useMenuHandlers } from '@/hooks/useMenuHandlers';
 import { useNotifications } from '@/hooks/useNotifications';
+import { useOAuthReauthorization } from '@/hooks/useOAuthReauthorization';
 import { useTheme } from '@/hooks/useTheme';
 import { useTray } from '@/hooks/useTray';
 import { useUpdateChecker } from '@/hooks/useUpdateChecker';
+import {
+  getLocaleSettings,
+  getSchemaCompatibility,
+  type SchemaCompatibility,
+} from '@/lib/database';
 import { useSettingsStore } from '@/store/settingsStore';
+import type { MainView } from '@/types';
 import { initWebKitDragFix } from './utils/webkit';
 
 function App() {
@@ -34,7 +54,11 @@ function App() {
   );
   const [showOnboarding, setShowOnboarding] = useState(false);
   const [showUpdateModal, setShowUpdateModal] = useState(false);
+  const [schema, setSchema] = useState<SchemaCompatibility | null>(null);
+  const [view, setView] = useState<MainView>('tasks');
+  const [showQuickOpen, setShowQuickOpen] = useState(false);
   const { isSyncing, isOffline, lastSyncTime, syncAll } = useSyncQuery();
+  const { data: readOnly } = useReadOnly();
   const { data: accounts = [] } = useAccounts();
   const {
     sidebarCollapsed,
@@ -42,14 +66,23 @@ function App() {
     toggleSidebarCollapsed,
     setSidebarWidth,
     onboardingCompleted,
+    setStartOfWeek,
   } = useSettingsStore();
 
-  // show onboarding modal on first launch
+  // a database from a newer release is only read, say so
+  useEffect(() => {
+    getSchemaCompatibility().then(setSchema).catch(() => {});
+  }, []);
+
+  // show onboarding modal on first launch, weeks start like in the OS locale until changed
   useEffect(() => {
     if (!onboardingCompleted) {
       setShowOnboarding(true);
+      getLocaleSettings()
+        .then((locale) => setStartOfWeek(locale.firstDayOfWeek === 1 ? 'monday' : 'sunday'))
+        .catch(() => {});
     }
-  }, [onboardingCompleted]);
+  }, [onboardingCompleted, setStartOfWeek]);
 
   // system tray integration (sync button, status updates)
   useTray({
@@ -65,9 +98,24 @@ function App() {
   // app menu state synchronization
   useAppMenu();
 
+  // back to the lock screen when the app locks
+  useAppLock();
+
   // menu handlers and modal state
   const menuHandlers = useMenuHandlers();
 
+  // an account that has to be authorized again is pointed out once
+  useOAuthReauthorization(() => {
+    menuHandlers.setSettingsInitialTab({ category: 'account', subtab: 'connections' });
+    menuHandlers.setShowSettings(true);
+  });
+
+  // passwords the startup audit moved into the credential store are pointed out once
+  useCredentialMigration();
+
+  // a crash report of the last run is offered for sharing
+  useCrashReport();
+
   // file drop handling via hook
   const {
     isDragOver,
@@ -92,6 +140,7 @@ function App() {
       menuHandlers.setShowSettings((prev: boolean) => !prev);
     },
     onSync: syncAll,
+    onQuickOpen: () => setShowQuickOpen(true),
   });
 
   const { data: uiState } = useUIState();
@@ -127,6 +176,8 @@ function App() {
       {isDragOver && <DragOverlay isUnsupportedFile={isUnsupportedFile} />}
 
       <Sidebar
+        view={view}
+        onViewChange={setView}
         onOpenSettings={menuHandlers.handleOpenSettings}
         onOpenImport={() => menuHandlers.setShowImport(true)}
         isCollapsed={sidebarCollapsed}
@@ -138,11 +189,34 @@ function App() {
       />
 
       <main className="flex-1 flex flex-col min-w-0">
+        {schema && !schema.compatible && (
+          <div className="flex items-center justify-center gap-2 bg-red-600 text-white py-1 px-4 text-sm font-medium">
+            <span>
+              These tasks were saved by a newer version of the app and are read-only. Update the
+              app to make changes.
+            </span>
+            <button
+              type="button"
+              onClick={() => menuHandlers.setShowExport(true)}
+              className="flex-shrink-0 underline hover:no-underline"
+            >
+              Export
+            </button>
+          </div>
+        )}
+        {schema?.compatible && readOnly?.enabled && (
+          <div className="bg-surface-600 text-white text-center py-1 text-sm font-medium">
+            Read-only mode. Tasks can't be changed here, changes on the server still come in.
+          </div>
+        )}
         {isOffline && (
           <div className="bg-amber-500 text-white text-center py-1 text-sm font-medium">
             You're offline. Changes will sync when you reconnect.
           </div>
         )}
+        {!isOffline && (
+          <UnreachableBanner onRetry={isSyncing || accounts.length === 0 ? undefined : syncAll} />
+        )}
 
         <Header
           isSyncing={isSyncing}
@@ -156,7 +230,15 @@ function App() {
           <div
             className={`flex-1 flex flex-col min-w-0 min-h-0 ${isEditorOpen && selectedTask ? 'hidden lg:flex' : ''}`}
           >
-            <TaskList />
+            {view === 'tasks' && <TaskList />}
+            {view === 'agenda' && <AgendaView />}
+            {view === 'actionable' && <ActionableView />}
+            {view === 'next' && <NextActionsView />}
+            {view === 'board' && <KanbanView />}
+            {view === 'time' && <TimeView />}
+            {view === 'planned' && <PlannedView />}
+            {view === 'stats' && <StatsView />}
+            {view === 'archive' && <ArchiveView />}
           </div>
 
           {isEditorOpen && selectedTask && (
@@ -180,6 +262,13 @@ function App() {
         />
       )}
 
+      {showQuickOpen && (
+        <QuickOpenModal
+          onClose={() => setShowQuickOpen(false)}
+          onOpenList={() => setView('tasks')}
+        />
+      )}
+
       <ImportModal
         isOpen={menuHandlers.showImport}
         onClose={handleImportClose}
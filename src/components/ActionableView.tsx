@@ -0,0 +1,36 @@
+import ListChecks from 'lucide-react/icons/list-checks';
+import { useActionableTasks } from '@/hooks/queries';
+import { TaskRow } from './TaskRow';
+
+/**
+ * open tasks of every list that nothing is blocking
+ */
+export function ActionableView() {
+  const { data: tasks = [], isLoading } = useActionableTasks();
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 overflow-y-auto p-4 overscroll-contain">
+      <div className="flex items-center gap-2 mb-1">
+        <ListChecks className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Actionable Now
+        </h2>
+      </div>
+      <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+        Open tasks that aren't waiting for another task.
+      </p>
+
+      {!isLoading && tasks.length === 0 ? (
+        <p className="py-8 text-center text-sm text-surface-500 dark:text-surface-400">
+          Nothing to do right now.
+        </p>
+      ) : (
+        <div className="space-y-1.5">
+          {tasks.map((task) => (
+            <TaskRow key={task.id} task={task} />
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
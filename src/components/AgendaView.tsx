@@ -0,0 +1,106 @@
+import { format, parseISO } from 'date-fns';
+import CalendarDays from 'lucide-react/icons/calendar-days';
+import { useState } from 'react';
+import { useAgenda } from '@/hooks/queries';
+import type { AgendaBucket, AgendaGroup, AgendaRange } from '@/lib/database';
+import { TaskRow } from './TaskRow';
+
+const RANGES: { value: AgendaRange; label: string }[] = [
+  { value: 'today', label: 'Today' },
+  { value: 'week', label: 'This Week' },
+  { value: 'all', label: 'All' },
+];
+
+const bucketLabels: Record<AgendaBucket, string> = {
+  overdue: 'Overdue',
+  today: 'Today',
+  tomorrow: 'Tomorrow',
+  thisWeek: 'This Week',
+  later: 'Later',
+};
+
+// the days of a bucket that spans several, in the order the backend sorted them
+function byDay(group: AgendaGroup) {
+  const days: { day: string; tasks: AgendaGroup['tasks'] }[] = [];
+  for (const task of group.tasks) {
+    const last = days[days.length - 1];
+    if (last?.day === task.day) last.tasks.push(task);
+    else days.push({ day: task.day, tasks: [task] });
+  }
+  return days;
+}
+
+/**
+ * open tasks by when they're due; the backend works out the buckets in local time
+ */
+export function AgendaView() {
+  const [range, setRange] = useState<AgendaRange>('week');
+  const { data: groups = [], isLoading } = useAgenda(range);
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 overflow-y-auto p-4 overscroll-contain">
+      <div className="flex items-center gap-2 mb-4">
+        <CalendarDays className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="flex-1 text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Agenda
+        </h2>
+        <div className="flex rounded-lg border border-surface-200 dark:border-surface-600 overflow-hidden">
+          {RANGES.map(({ value, label }) => (
+            <button
+              type="button"
+              key={value}
+              onClick={() => setRange(value)}
+              className={`px-3 py-1.5 text-sm transition-colors ${
+                range === value
+                  ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
+                  : 'text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700'
+              }`}
+            >
+              {label}
+            </button>
+          ))}
+        </div>
+      </div>
+
+      {!isLoading && groups.length === 0 ? (
+        <p className="py-8 text-center text-sm text-surface-500 dark:text-surface-400">
+          Nothing due.
+        </p>
+      ) : (
+        <div className="space-y-4">
+          {groups.map((group) => (
+            <div key={group.bucket}>
+              <h3
+                className={`mb-1.5 text-sm font-semibold ${group.bucket === 'overdue' ? 'text-red-600 dark:text-red-400' : 'text-surface-700 dark:text-surface-300'}`}
+              >
+                {bucketLabels[group.bucket]}
+              </h3>
+              {group.bucket === 'thisWeek' || group.bucket === 'later' ? (
+                <div className="space-y-3">
+                  {byDay(group).map(({ day, tasks }) => (
+                    <div key={day}>
+                      <div className="mb-1 text-xs text-surface-500 dark:text-surface-400">
+                        {format(parseISO(day), 'EEEE, MMM d')}
+                      </div>
+                      <div className="space-y-1.5">
+                        {tasks.map((task) => (
+                          <TaskRow key={task.id} task={task} />
+                        ))}
+                      </div>
+                    </div>
+                  ))}
+                </div>
+              ) : (
+                <div className="space-y-1.5">
+                  {group.tasks.map((task) => (
+                    <TaskRow key={task.id} task={task} />
+                  ))}
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,122 @@
+import { format } from 'date-fns';
+import Archive from 'lucide-react/icons/archive';
+import ArchiveRestore from 'lucide-react/icons/archive-restore';
+import Search from 'lucide-react/icons/search';
+import { useState } from 'react';
+import { useAccounts, useArchivedTasks, useRestoreArchivedTasks } from '@/hooks/queries';
+import { useLoadMore } from '@/hooks/useLoadMore';
+
+/**
+ * tasks the archive policy moved out of the lists, to look something up or restore it
+ */
+export function ArchiveView() {
+  const [search, setSearch] = useState('');
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [error, setError] = useState<string | null>(null);
+  const { data: accounts = [] } = useAccounts();
+  const { data, isLoading, hasNextPage, isFetchingNextPage, fetchNextPage } = useArchivedTasks(
+    search.trim(),
+  );
+  const restoreArchivedTasksMutation = useRestoreArchivedTasks();
+  const loadMoreRef = useLoadMore(hasNextPage, isFetchingNextPage, fetchNextPage);
+
+  const tasks = data?.pages.flatMap((page) => page.tasks) ?? [];
+  const total = data?.pages[0]?.total ?? 0;
+  const calendars = accounts.flatMap((a) => a.calendars);
+
+  const toggle = (id: string) => {
+    const next = new Set(selected);
+    if (next.has(id)) next.delete(id);
+    else next.add(id);
+    setSelected(next);
+  };
+
+  const restore = (ids: string[]) => {
+    setError(null);
+    restoreArchivedTasksMutation.mutate(ids, {
+      onSuccess: () => setSelected(new Set()),
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 overflow-y-auto p-4 overscroll-contain">
+      <div className="flex items-center gap-2 mb-1">
+        <Archive className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="flex-1 text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Archive
+        </h2>
+        {selected.size > 0 && (
+          <button
+            type="button"
+            onClick={() => restore([...selected])}
+            className="flex items-center gap-2 px-3 py-1.5 text-sm text-white bg-primary-600 hover:bg-primary-700 rounded-lg transition-colors"
+          >
+            <ArchiveRestore className="w-4 h-4" />
+            Restore {selected.size}
+          </button>
+        )}
+      </div>
+      <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+        {total} archived {total === 1 ? 'task' : 'tasks'}. Completed tasks are archived after the
+        number of days set in Settings → Data.
+      </p>
+
+      <div className="flex items-center gap-2 px-3 py-2 mb-4 border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-800 rounded-lg">
+        <Search className="w-4 h-4 text-surface-400" />
+        <input
+          type="text"
+          value={search}
+          onChange={(e) => setSearch(e.target.value)}
+          placeholder="Search the archive..."
+          className="flex-1 text-sm text-surface-700 dark:text-surface-300 bg-transparent border-0 focus:outline-none focus:ring-0 placeholder:text-surface-400"
+        />
+      </div>
+      {error && <p className="mb-2 text-xs text-red-600 dark:text-red-400">{error}</p>}
+
+      {!isLoading && tasks.length === 0 ? (
+        <p className="py-8 text-center text-sm text-surface-500 dark:text-surface-400">
+          {search.trim() ? 'No archived tasks match.' : 'The archive is empty.'}
+        </p>
+      ) : (
+        <div className="space-y-1.5">
+          {tasks.map((task) => (
+            <div
+              key={task.id}
+              className="group flex items-center gap-3 px-3 py-2 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700"
+            >
+              <input
+                type="checkbox"
+                checked={selected.has(task.id)}
+                onChange={() => toggle(task.id)}
+                className="w-4 h-4 rounded border-surface-300 dark:border-surface-600 text-primary-600 focus:ring-primary-500"
+              />
+              <div className="flex-1 min-w-0">
+                <div className="text-sm text-surface-800 dark:text-surface-200 truncate">
+                  {task.title || 'Untitled task'}
+                </div>
+                <div className="text-xs text-surface-500 dark:text-surface-400 truncate">
+                  {[
+                    task.completedAt && `Completed ${format(task.completedAt, 'MMM d, yyyy')}`,
+                    calendars.find((c) => c.id === task.calendarId)?.displayName,
+                  ]
+                    .filter(Boolean)
+                    .join(' · ')}
+                </div>
+              </div>
+              <button
+                type="button"
+                onClick={() => restore([task.id])}
+                className="flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg opacity-0 group-hover:opacity-100 transition-all"
+              >
+                <ArchiveRestore className="w-3.5 h-3.5" />
+                Restore
+              </button>
+            </div>
+          ))}
+          <div ref={loadMoreRef} />
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,104 @@
+import { useState } from 'react';
+import {
+  useAccounts,
+  useCalendarView,
+  useCreateTask,
+  useToggleTaskComplete,
+} from '@/hooks/queries';
+import { useAppLock } from '@/hooks/useAppLock';
+import { useLoadMore } from '@/hooks/useLoadMore';
+import { useTheme } from '@/hooks/useTheme';
+
+interface CalendarWindowProps {
+  calendarId: string;
+}
+
+/**
+ * a calendar opened in its own window: its open tasks and a field to add one. the main
+ * window keeps syncing, this one reloads whenever another window writes
+ */
+export function CalendarWindow({ calendarId }: CalendarWindowProps) {
+  useTheme();
+  useAppLock();
+
+  const { data: accounts = [] } = useAccounts();
+  const {
+    data: tasks = [],
+    hasNextPage,
+    isFetchingNextPage,
+    fetchNextPage,
+  } = useCalendarView(calendarId);
+  const loadMoreRef = useLoadMore(hasNextPage, isFetchingNextPage, fetchNextPage);
+  const createTaskMutation = useCreateTask();
+  const toggleTaskCompleteMutation = useToggleTaskComplete();
+  const [title, setTitle] = useState('');
+
+  const calendar = accounts.flatMap((a) => a.calendars).find((c) => c.id === calendarId);
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    const trimmed = title.trim();
+    if (!trimmed || !calendar) return;
+    createTaskMutation.mutate({ title: trimmed, calendarId, accountId: calendar.accountId });
+    setTitle('');
+  };
+
+  if (!calendar) {
+    return (
+      <div className="flex h-screen items-center justify-center bg-surface-50 dark:bg-surface-900 text-sm text-surface-500 dark:text-surface-400">
+        This calendar no longer exists.
+      </div>
+    );
+  }
+
+  return (
+    <div className="flex h-screen flex-col bg-surface-50 dark:bg-surface-900">
+      <header className="flex items-center gap-2 px-4 py-3 border-b border-surface-200 dark:border-surface-700">
+        <span
+          className="w-3 h-3 rounded-full flex-shrink-0"
+          style={{ backgroundColor: calendar.color ?? '#3b82f6' }}
+        />
+        <h1 className="text-sm font-semibold text-surface-800 dark:text-surface-200 truncate">
+          {calendar.displayName}
+        </h1>
+      </header>
+
+      <form onSubmit={handleSubmit} className="px-4 py-2">
+        <input
+          type="text"
+          value={title}
+          onChange={(e) => setTitle(e.target.value)}
+          placeholder="Add a task"
+          className="w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300"
+        />
+      </form>
+
+      <ul className="flex-1 overflow-y-auto px-2 pb-2">
+        {tasks.map((task) => (
+          <li
+            key={task.id}
+            className="flex items-center gap-2 px-2 py-1.5 rounded-lg hover:bg-surface-100 dark:hover:bg-surface-800"
+            style={{ paddingLeft: `${0.5 + task.depth * 1.25}rem` }}
+          >
+            <input
+              type="checkbox"
+              checked={task.completed}
+              onChange={() => toggleTaskCompleteMutation.mutate(task.id)}
+              aria-label={`Complete ${task.title}`}
+              className="rounded border-surface-300"
+            />
+            <span className="text-sm text-surface-700 dark:text-surface-300 truncate">
+              {task.title}
+            </span>
+          </li>
+        ))}
+        <li ref={loadMoreRef} aria-hidden />
+        {tasks.length === 0 && (
+          <li className="px-2 py-6 text-center text-sm text-surface-500 dark:text-surface-400">
+            Nothing left to do here.
+          </li>
+        )}
+      </ul>
+    </div>
+  );
+}
@@ -3,19 +3,26 @@ import SortDesc from 'lucide-react/icons/arrow-down-wide-narrow';
 import SortAsc from 'lucide-react/icons/arrow-up-narrow-wide';
 import Eye from 'lucide-react/icons/eye';
 import EyeOff from 'lucide-react/icons/eye-off';
+import GitCompare from 'lucide-react/icons/git-compare';
 import Plus from 'lucide-react/icons/plus';
 import RefreshCw from 'lucide-react/icons/refresh-cw';
 import Search from 'lucide-react/icons/search';
+import Upload from 'lucide-react/icons/upload';
 import WifiOff from 'lucide-react/icons/wifi-off';
 import { useEffect, useState } from 'react';
 import { ComposedInput } from '@/components/ComposedInput';
+import { ConflictsModal } from '@/components/modals/ConflictsModal';
+import { PendingChangesModal } from '@/components/modals/PendingChangesModal';
 import { useModalState } from '@/context/modalStateContext';
 import {
   useCreateTask,
+  usePendingChanges,
+  useSearchQueryError,
   useSetSearchQuery,
   useSetSelectedTask,
   useSetShowCompletedTasks,
   useSetSortConfig,
+  useSyncConflicts,
   useUIState,
 } from '@/hooks/queries';
 import type { SortMode } from '@/types';
@@ -54,8 +61,17 @@ export function Header({
   const setShowCompletedTasksMutation = useSetShowCompletedTasks();
   const createTaskMutation = useCreateTask();
   const setSelectedTaskMutation = useSetSelectedTask();
+  const { data: conflicts = [] } = useSyncConflicts();
+  const [showConflicts, setShowConflicts] = useState(false);
+  const { data: pendingChanges = [] } = usePendingChanges();
+  const [showPendingChanges, setShowPendingChanges] = useState(false);
+  // changes the last sync couldn't upload, or that syncing skips
+  const stuckChanges = pendingChanges.filter((change) => change.blocked || change.lastError);
 
   const searchQuery = uiState?.searchQuery ?? '';
+  const { data: searchError } = useSearchQueryError(searchQuery);
+  // an emptied search box has nothing to complain about
+  const searchQueryError = searchQuery.trim() ? searchError : null;
   const sortConfig = uiState?.sortConfig ?? {
     mode: 'manual' as SortMode,
     direction: 'asc' as const,
@@ -114,8 +130,14 @@ export function Header({
             placeholder={`Search tasks... (${searchShortcut})`}
             value={searchQuery}
             onChange={(value) => setSearchQueryMutation.mutate(value)}
-            className="w-full pl-9 pr-4 py-2 bg-surface-100 dark:bg-surface-700 border border-transparent rounded-lg text-sm text-surface-800 dark:text-surface-200 placeholder:text-surface-400 focus:outline-none focus:border-primary-300 focus:bg-white dark:focus:bg-surface-600 transition-colors"
+            title={searchQueryError ?? undefined}
+            className={`w-full pl-9 pr-4 py-2 bg-surface-100 dark:bg-surface-700 border rounded-lg text-sm text-surface-800 dark:text-surface-200 placeholder:text-surface-400 focus:outline-none focus:bg-white dark:focus:bg-surface-600 transition-colors ${searchQueryError ? 'border-amber-400 dark:border-amber-500' : 'border-transparent focus:border-primary-300'}`}
           />
+          {searchQueryError && (
+            <p className="absolute left-0 top-full mt-1 z-20 px-2 py-1 text-xs text-amber-700 dark:text-amber-300 bg-amber-50 dark:bg-amber-900/80 border border-amber-200 dark:border-amber-700 rounded-md shadow-sm">
+              {searchQueryError}. Searching for the text instead.
+            </p>
+          )}
         </div>
 
         <div className="flex items-center gap-2 flex-shrink-0">
@@ -126,6 +148,38 @@ export function Header({
             </div>
           )}
 
+          {conflicts.length > 0 && (
+            <Tooltip
+              content={`${conflicts.length} sync ${conflicts.length === 1 ? 'conflict' : 'conflicts'} to review`}
+              position="bottom"
+            >
+              <button
+                type="button"
+                onClick={() => setShowConflicts(true)}
+                className="flex items-center gap-1 px-2 py-1 text-red-600 dark:text-red-400 bg-red-50 dark:bg-red-900/30 hover:bg-red-100 dark:hover:bg-red-900/50 rounded-lg text-sm transition-colors"
+              >
+                <GitCompare className="w-4 h-4" />
+                <span>{conflicts.length}</span>
+              </button>
+            </Tooltip>
+          )}
+
+          {stuckChanges.length > 0 && (
+            <Tooltip
+              content={`${stuckChanges.length} ${stuckChanges.length === 1 ? 'change' : 'changes'} not on the server yet`}
+              position="bottom"
+            >
+              <button
+                type="button"
+                onClick={() => setShowPendingChanges(true)}
+                className="flex items-center gap-1 px-2 py-1 text-amber-600 dark:text-amber-400 bg-amber-50 dark:bg-amber-900/30 hover:bg-amber-100 dark:hover:bg-amber-900/50 rounded-lg text-sm transition-colors"
+              >
+                <Upload className="w-4 h-4" />
+                <span>{stuckChanges.length}</span>
+              </button>
+            </Tooltip>
+          )}
+
           {onSync && (
             <Tooltip
               content={
@@ -247,6 +301,14 @@ export function Header({
           </button>
         </div>
       </div>
+
+      {showConflicts && <ConflictsModal onClose={() => setShowConflicts(false)} />}
+      {showPendingChanges && (
+        <PendingChangesModal
+          onClose={() => setShowPendingChanges(false)}
+          onRetry={isSyncing || isOffline || disableSync ? undefined : onSync}
+        />
+      )}
     </header>
   );
 }
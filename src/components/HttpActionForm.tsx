@@ -0,0 +1,304 @@
+import { formatDistanceToNow } from 'date-fns';
+import ChevronDown from 'lucide-react/icons/chevron-down';
+import Pencil from 'lucide-react/icons/pencil';
+import Send from 'lucide-react/icons/send';
+import Trash2 from 'lucide-react/icons/trash-2';
+import { useState } from 'react';
+import {
+  useAccounts,
+  useDeleteHttpAction,
+  useHttpActionRuns,
+  useSaveHttpAction,
+  useTestHttpAction,
+} from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import type { HttpAction } from '@/lib/database';
+
+const METHODS: HttpAction['method'][] = ['GET', 'POST', 'PUT', 'PATCH', 'DELETE'];
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-1.5 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+const smallButtonClass =
+  'flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors disabled:opacity-50';
+
+// an action of a task or a list that isn't saved yet
+export function newHttpAction(target: HttpAction['target'], targetId: string): HttpAction {
+  return {
+    id: '',
+    name: '',
+    target,
+    targetId,
+    method: 'POST',
+    url: '',
+    contentType: 'application/json',
+    body: '{"title": "{{title}}", "completedAt": "{{completedAt}}"}',
+    enabled: true,
+    createdAt: '',
+  };
+}
+
+interface HttpActionFormProps {
+  draft: HttpAction;
+  onDone: () => void;
+}
+
+/**
+ * the request an action sends when its task, or a task of its list, is completed
+ */
+export function HttpActionForm({ draft, onDone }: HttpActionFormProps) {
+  const { data: accounts = [] } = useAccounts();
+  const saveHttpActionMutation = useSaveHttpAction();
+  const [action, setAction] = useState(draft);
+  const [error, setError] = useState<string | null>(null);
+
+  const update = (changes: Partial<HttpAction>) => setAction({ ...action, ...changes });
+
+  const hasBody = action.method !== 'GET' && action.method !== 'DELETE';
+
+  const handleSave = () => {
+    setError(null);
+    saveHttpActionMutation.mutate(action, {
+      onSuccess: onDone,
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  return (
+    <div className="space-y-2 rounded-lg border border-primary-200 dark:border-primary-800 p-3">
+      <input
+        type="text"
+        value={action.name}
+        onChange={(e) => update({ name: e.target.value })}
+        placeholder="Name"
+        className={inputClass}
+      />
+      {action.target === 'list' && (
+        <select
+          value={action.targetId}
+          onChange={(e) => update({ targetId: e.target.value })}
+          className={inputClass}
+        >
+          <option value="">Choose a list</option>
+          {accounts.map((account) => (
+            <optgroup key={account.id} label={account.name}>
+              {account.calendars.map((calendar) => (
+                <option key={calendar.id} value={calendar.id}>
+                  {calendar.displayName}
+                </option>
+              ))}
+            </optgroup>
+          ))}
+        </select>
+      )}
+      <div className="flex gap-2">
+        <select
+          value={action.method}
+          onChange={(e) => update({ method: e.target.value as HttpAction['method'] })}
+          className={`${inputClass} w-28`}
+        >
+          {METHODS.map((method) => (
+            <option key={method} value={method}>
+              {method}
+            </option>
+          ))}
+        </select>
+        <input
+          type="url"
+          value={action.url}
+          onChange={(e) => update({ url: e.target.value })}
+          placeholder="https://example.com/hook?task={{uid}}"
+          className={inputClass}
+        />
+      </div>
+      {hasBody && (
+        <>
+          <input
+            type="text"
+            value={action.contentType}
+            onChange={(e) => update({ contentType: e.target.value })}
+            placeholder="Content type"
+            className={inputClass}
+          />
+          <textarea
+            value={action.body}
+            onChange={(e) => update({ body: e.target.value })}
+            rows={4}
+            spellCheck={false}
+            className={`${inputClass} font-mono text-xs`}
+          />
+        </>
+      )}
+      <p className="text-xs text-surface-500 dark:text-surface-400">
+        {'{{title}}'}, {'{{description}}'}, {'{{uid}}'}, {'{{list}}'}, {'{{priority}}'},{' '}
+        {'{{dueDate}}'}, {'{{completedAt}}'} and {'{{url}}'} are filled in from the task.
+      </p>
+      <div className="flex gap-2">
+        <button
+          type="button"
+          disabled={!action.name.trim() || !action.url.trim() || saveHttpActionMutation.isPending}
+          onClick={handleSave}
+          className={buttonClass}
+        >
+          Save
+        </button>
+        <button type="button" onClick={onDone} className={buttonClass}>
+          Cancel
+        </button>
+      </div>
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  );
+}
+
+/**
+ * the latest runs of an action, with retries still to come
+ */
+function HttpActionRuns({ actionId }: { actionId: string }) {
+  const { data: runs = [] } = useHttpActionRuns(actionId);
+
+  if (runs.length === 0) {
+    return <p className="text-xs text-surface-500 dark:text-surface-400">Not run yet.</p>;
+  }
+  return (
+    <ul className="space-y-1 text-xs">
+      {runs.map((run) => (
+        <li key={run.id} className="flex items-baseline gap-2">
+          <span
+            className={
+              run.status === 'delivered'
+                ? 'text-green-600 dark:text-green-400'
+                : run.status === 'failed'
+                  ? 'text-red-600 dark:text-red-400'
+                  : 'text-amber-600 dark:text-amber-400'
+            }
+          >
+            {run.status}
+          </span>
+          <span className="flex-1 truncate text-surface-500 dark:text-surface-400">
+            {run.responseStatus !== null && `HTTP ${run.responseStatus} `}
+            {run.error}
+            {run.status === 'pending' &&
+              run.nextAttemptAt &&
+              `retry ${formatDistanceToNow(new Date(run.nextAttemptAt), { addSuffix: true })}`}
+          </span>
+          <span className="text-surface-400 flex-shrink-0">
+            {formatDistanceToNow(new Date(run.createdAt), { addSuffix: true })}
+          </span>
+        </li>
+      ))}
+    </ul>
+  );
+}
+
+interface HttpActionItemProps {
+  action: HttpAction;
+  // what the action is attached to, when that isn't obvious from where it's shown
+  label?: string;
+  // the task a test run is sent for
+  testTaskId?: string;
+}
+
+/**
+ * a saved action with its runs; it can be tested, edited, turned off and deleted
+ */
+export function HttpActionItem({ action, label, testTaskId }: HttpActionItemProps) {
+  const saveHttpActionMutation = useSaveHttpAction();
+  const deleteHttpActionMutation = useDeleteHttpAction();
+  const testHttpActionMutation = useTestHttpAction();
+  const { confirm } = useConfirmDialog();
+  const [editing, setEditing] = useState(false);
+  const [showRuns, setShowRuns] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  if (editing) return <HttpActionForm draft={action} onDone={() => setEditing(false)} />;
+
+  const handleTest = () => {
+    if (!testTaskId) return;
+    testHttpActionMutation.mutate(
+      { id: action.id, taskId: testTaskId },
+      {
+        onSuccess: (run) => {
+          setShowRuns(true);
+          setMessage(
+            run.status === 'delivered'
+              ? `Sent (HTTP ${run.responseStatus})`
+              : `Not sent: ${run.error ?? `HTTP ${run.responseStatus}`}`,
+          );
+        },
+        onError: (err) => setMessage(String(err)),
+      },
+    );
+  };
+
+  const handleDelete = async () => {
+    const confirmed = await confirm({
+      title: 'Delete action',
+      subtitle: action.name,
+      message: 'The action and its runs are removed.',
+      confirmLabel: 'Delete',
+      destructive: true,
+    });
+    if (confirmed) deleteHttpActionMutation.mutate(action.id);
+  };
+
+  return (
+    <div className="space-y-2 rounded-lg border border-surface-200 dark:border-surface-700 p-3">
+      <div className="flex items-center gap-2">
+        <div className="flex-1 min-w-0">
+          <div className="text-sm font-medium text-surface-800 dark:text-surface-200 truncate">
+            {action.name}
+            {label && <span className="ml-2 text-xs font-normal text-surface-400">{label}</span>}
+          </div>
+          <div className="text-xs text-surface-500 dark:text-surface-400 truncate">
+            {action.method} {action.url}
+          </div>
+        </div>
+        <label className="flex items-center gap-1.5 text-xs text-surface-600 dark:text-surface-400">
+          <input
+            type="checkbox"
+            checked={action.enabled}
+            onChange={(e) =>
+              saveHttpActionMutation.mutate({ ...action, enabled: e.target.checked })
+            }
+            className="rounded border-surface-300"
+          />
+          Enabled
+        </label>
+      </div>
+      <div className="flex flex-wrap gap-1">
+        <button
+          type="button"
+          disabled={!testTaskId || testHttpActionMutation.isPending}
+          onClick={handleTest}
+          title={testTaskId ? undefined : 'The list has no task to test with'}
+          className={smallButtonClass}
+        >
+          <Send className="w-3.5 h-3.5" />
+          Send test
+        </button>
+        <button type="button" onClick={() => setShowRuns(!showRuns)} className={smallButtonClass}>
+          <ChevronDown
+            className={`w-3.5 h-3.5 transition-transform ${showRuns ? 'rotate-180' : ''}`}
+          />
+          Runs
+        </button>
+        <button type="button" onClick={() => setEditing(true)} className={smallButtonClass}>
+          <Pencil className="w-3.5 h-3.5" />
+          Edit
+        </button>
+        <button
+          type="button"
+          onClick={handleDelete}
+          className="flex items-center gap-1 px-2 py-1 text-xs text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 rounded-lg transition-colors"
+        >
+          <Trash2 className="w-3.5 h-3.5" />
+          Delete
+        </button>
+      </div>
+      {message && <p className="text-xs text-surface-500 dark:text-surface-400">{message}</p>}
+      {showRuns && <HttpActionRuns actionId={action.id} />}
+    </div>
+  );
+}
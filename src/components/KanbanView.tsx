@@ -0,0 +1,199 @@
+import {
+  DndContext,
+  type DragEndEvent,
+  PointerSensor,
+  useDraggable,
+  useDroppable,
+  useSensor,
+  useSensors,
+} from '@dnd-kit/core';
+import Columns3 from 'lucide-react/icons/columns-3';
+import Plus from 'lucide-react/icons/plus';
+import X from 'lucide-react/icons/x';
+import { useState } from 'react';
+import {
+  useAccounts,
+  useAddKanbanColumn,
+  useKanbanBoard,
+  useMoveToColumn,
+  useRemoveKanbanColumn,
+} from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import type { KanbanColumn } from '@/lib/database';
+import type { Task } from '@/types';
+import { TaskRow } from './TaskRow';
+
+// headings of the built-in columns; custom ones show their name
+const columnLabels: Record<string, string> = {
+  todo: 'To Do',
+  'in-progress': 'In Progress',
+  done: 'Done',
+};
+
+function DraggableTask({ task }: { task: Task }) {
+  const { attributes, listeners, setNodeRef, transform, isDragging } = useDraggable({
+    id: task.id,
+  });
+
+  return (
+    <div
+      ref={setNodeRef}
+      {...attributes}
+      {...listeners}
+      className={isDragging ? 'relative z-10 opacity-80' : undefined}
+      style={transform ? { transform: `translate(${transform.x}px, ${transform.y}px)` } : undefined}
+    >
+      <TaskRow task={task} />
+    </div>
+  );
+}
+
+function BoardColumn({ column, onRemove }: { column: KanbanColumn; onRemove: () => void }) {
+  const { setNodeRef, isOver } = useDroppable({ id: column.id });
+
+  return (
+    <div
+      ref={setNodeRef}
+      className={`flex flex-col w-72 flex-shrink-0 rounded-xl p-2 transition-colors ${
+        isOver ? 'bg-primary-50 dark:bg-primary-900/30' : 'bg-surface-100 dark:bg-surface-800/50'
+      }`}
+    >
+      <div className="flex items-center gap-2 px-1 pb-2 group">
+        <span className="flex-1 text-sm font-semibold text-surface-700 dark:text-surface-300 truncate">
+          {columnLabels[column.id] ?? column.id}
+        </span>
+        <span className="text-xs text-surface-500 dark:text-surface-400">{column.tasks.length}</span>
+        {column.custom && (
+          <button
+            type="button"
+            onClick={onRemove}
+            className="p-0.5 text-surface-400 hover:text-red-500 dark:hover:text-red-400 opacity-0 group-hover:opacity-100 transition-all"
+            title="Remove column"
+          >
+            <X className="w-4 h-4" />
+          </button>
+        )}
+      </div>
+      <div className="flex-1 space-y-1.5 overflow-y-auto min-h-16">
+        {column.tasks.map((task) => (
+          <DraggableTask key={task.id} task={task} />
+        ))}
+      </div>
+    </div>
+  );
+}
+
+/**
+ * a board of to do, in progress, custom and done columns; dragging a task to another column
+ * updates its status, so the board survives sync
+ */
+export function KanbanView() {
+  const { data: accounts = [] } = useAccounts();
+  const [calendarId, setCalendarId] = useState<string | undefined>();
+  const { data: board = [] } = useKanbanBoard(calendarId);
+  const moveToColumnMutation = useMoveToColumn();
+  const addKanbanColumnMutation = useAddKanbanColumn();
+  const removeKanbanColumnMutation = useRemoveKanbanColumn();
+  const { confirm } = useConfirmDialog();
+  const [newColumn, setNewColumn] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const sensors = useSensors(
+    useSensor(PointerSensor, {
+      activationConstraint: {
+        distance: 8,
+      },
+    }),
+  );
+
+  const handleDragEnd = ({ active, over }: DragEndEvent) => {
+    if (!over) return;
+    const from = board.find((column) => column.tasks.some((task) => task.id === active.id));
+    if (from?.id === over.id) return;
+    moveToColumnMutation.mutate(
+      { id: String(active.id), column: String(over.id) },
+      { onError: (err) => setError(String(err)) },
+    );
+  };
+
+  const handleAddColumn = () => {
+    const name = newColumn.trim();
+    if (!name) return;
+    setError(null);
+    addKanbanColumnMutation.mutate(name, {
+      onSuccess: () => setNewColumn(''),
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  const handleRemoveColumn = async (column: KanbanColumn) => {
+    const confirmed = await confirm({
+      title: 'Remove column',
+      subtitle: column.id,
+      message:
+        column.tasks.length > 0
+          ? `The ${column.tasks.length} tasks in this column go back to To Do.`
+          : 'Remove this column from the board?',
+      confirmLabel: 'Remove',
+      destructive: true,
+    });
+    if (confirmed) {
+      removeKanbanColumnMutation.mutate(column.id, {
+        onError: (err) => setError(String(err)),
+      });
+    }
+  };
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 p-4">
+      <div className="flex items-center gap-2 mb-4">
+        <Columns3 className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="flex-1 text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Board
+        </h2>
+        <select
+          value={calendarId ?? ''}
+          onChange={(e) => setCalendarId(e.target.value || undefined)}
+          className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+        >
+          <option value="">All calendars</option>
+          {accounts.map((account) => (
+            <optgroup key={account.id} label={account.name}>
+              {account.calendars.map((cal) => (
+                <option key={cal.id} value={cal.id}>
+                  {cal.displayName}
+                </option>
+              ))}
+            </optgroup>
+          ))}
+        </select>
+      </div>
+      {error && <p className="mb-2 text-xs text-red-600 dark:text-red-400">{error}</p>}
+
+      <DndContext sensors={sensors} onDragEnd={handleDragEnd}>
+        <div className="flex-1 flex gap-3 min-h-0 overflow-x-auto pb-2">
+          {board.map((column) => (
+            <BoardColumn
+              key={column.id}
+              column={column}
+              onRemove={() => handleRemoveColumn(column)}
+            />
+          ))}
+          <div className="w-60 flex-shrink-0">
+            <div className="flex items-center gap-2 px-2 py-1.5 rounded-xl border border-dashed border-surface-300 dark:border-surface-600">
+              <Plus className="w-4 h-4 text-surface-400" />
+              <input
+                type="text"
+                value={newColumn}
+                onChange={(e) => setNewColumn(e.target.value)}
+                onKeyDown={(e) => e.key === 'Enter' && handleAddColumn()}
+                placeholder="Add column..."
+                className="flex-1 min-w-0 text-sm text-surface-700 dark:text-surface-300 bg-transparent border-0 focus:outline-none focus:ring-0 placeholder:text-surface-400"
+              />
+            </div>
+          </div>
+        </div>
+      </DndContext>
+    </div>
+  );
+}
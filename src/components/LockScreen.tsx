@@ -0,0 +1,68 @@
+import Lock from 'lucide-react/icons/lock';
+import { useState } from 'react';
+import { unlockApp } from '@/lib/database';
+
+interface LockScreenProps {
+  onUnlock: () => void;
+}
+
+/**
+ * shown instead of the app while it's locked; nothing of the database is loaded until the
+ * PIN is entered
+ */
+export function LockScreen({ onUnlock }: LockScreenProps) {
+  const [pin, setPin] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!pin || isChecking) return;
+    setIsChecking(true);
+    setError(null);
+    try {
+      await unlockApp(pin);
+      onUnlock();
+    } catch (err) {
+      // wrong PINs are answered slower each time, the backend waits before replying
+      setError(String(err));
+      setPin('');
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
+  return (
+    <div className="flex h-screen items-center justify-center bg-surface-50 dark:bg-surface-900">
+      <form onSubmit={handleSubmit} className="flex w-72 flex-col items-center gap-4">
+        <div className="w-16 h-16 rounded-2xl bg-primary-100 dark:bg-primary-900/30 flex items-center justify-center">
+          <Lock className="w-8 h-8 text-primary-600 dark:text-primary-400" />
+        </div>
+        <h1 className="text-lg font-semibold text-surface-800 dark:text-surface-200">
+          caldav-tasks is locked
+        </h1>
+        <input
+          type="password"
+          inputMode="numeric"
+          autoComplete="off"
+          // biome-ignore lint/a11y/noAutofocus: the PIN is the only thing to enter here
+          autoFocus
+          value={pin}
+          onChange={(e) => setPin(e.target.value)}
+          placeholder="PIN"
+          aria-label="PIN"
+          disabled={isChecking}
+          className="w-full px-3 py-2 text-center text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+        />
+        {error && <p className="text-sm text-red-600 dark:text-red-400">{error}</p>}
+        <button
+          type="submit"
+          disabled={!pin || isChecking}
+          className="w-full px-4 py-2 text-sm font-medium text-white bg-primary-600 hover:bg-primary-700 rounded-lg transition-colors disabled:opacity-50"
+        >
+          {isChecking ? 'Checking…' : 'Unlock'}
+        </button>
+      </form>
+    </div>
+  );
+}
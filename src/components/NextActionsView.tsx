@@ -0,0 +1,181 @@
+import AtSign from 'lucide-react/icons/at-sign';
+import Pencil from 'lucide-react/icons/pencil';
+import Trash2 from 'lucide-react/icons/trash-2';
+import { useState } from 'react';
+import {
+  useContexts,
+  useDeleteContext,
+  useNextActions,
+  useRenameContext,
+  useSaveContext,
+} from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import type { Context } from '@/types';
+import { TaskRow } from './TaskRow';
+
+/**
+ * GTD next actions: open, unblocked tasks that have started, grouped per context
+ */
+export function NextActionsView() {
+  const { data: contexts = [] } = useContexts();
+  const [selected, setSelected] = useState<string | undefined>();
+  const { data: groups = [], isLoading } = useNextActions(selected);
+  const saveContextMutation = useSaveContext();
+  const renameContextMutation = useRenameContext();
+  const deleteContextMutation = useDeleteContext();
+  const { confirm } = useConfirmDialog();
+  const [renaming, setRenaming] = useState<string | null>(null);
+  const [newName, setNewName] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const selectedContext = contexts.find((c) => c.name === selected);
+
+  const handleRename = (context: Context) => {
+    const name = newName.trim();
+    setRenaming(null);
+    if (!name || name === context.name) return;
+    setError(null);
+    renameContextMutation.mutate(
+      { name: context.name, newName: name },
+      {
+        onSuccess: (stored) => setSelected(stored),
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  const handleDelete = async (context: Context) => {
+    const confirmed = await confirm({
+      title: 'Delete context',
+      subtitle: context.name,
+      message: 'The context is removed from every task that has it. The tasks stay.',
+      confirmLabel: 'Delete',
+      destructive: true,
+    });
+    if (confirmed) {
+      setSelected(undefined);
+      deleteContextMutation.mutate(context.name, {
+        onError: (err) => setError(String(err)),
+      });
+    }
+  };
+
+  const chipClass = (active: boolean) =>
+    `inline-flex items-center gap-1.5 px-3 py-1 text-sm rounded-full border transition-colors ${
+      active
+        ? 'border-primary-300 bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
+        : 'border-surface-200 dark:border-surface-600 text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700'
+    }`;
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 overflow-y-auto p-4 overscroll-contain">
+      <div className="flex items-center gap-2 mb-1">
+        <AtSign className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Next Actions
+        </h2>
+      </div>
+      <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+        Open tasks that have started and aren't waiting for another task, per context. Add
+        contexts to a task in its editor.
+      </p>
+
+      <div className="flex flex-wrap items-center gap-2 mb-4">
+        <button
+          type="button"
+          onClick={() => setSelected(undefined)}
+          className={chipClass(!selected)}
+        >
+          All contexts
+        </button>
+        {contexts.map((context) => (
+          <button
+            type="button"
+            key={context.name}
+            onClick={() => setSelected(context.name)}
+            className={chipClass(selected === context.name)}
+          >
+            {context.color && (
+              <span className="w-2 h-2 rounded-full" style={{ backgroundColor: context.color }} />
+            )}
+            {context.name}
+            <span className="text-xs text-surface-400">{context.openTasks}</span>
+          </button>
+        ))}
+      </div>
+
+      {selectedContext && (
+        <div className="flex items-center gap-2 mb-4">
+          {renaming === selectedContext.name ? (
+            <input
+              type="text"
+              value={newName}
+              onChange={(e) => setNewName(e.target.value)}
+              onBlur={() => handleRename(selectedContext)}
+              onKeyDown={(e) => {
+                if (e.key === 'Enter') handleRename(selectedContext);
+                if (e.key === 'Escape') setRenaming(null);
+              }}
+              // biome-ignore lint/a11y/noAutofocus: the field replaces the rename button
+              autoFocus
+              className="px-2 py-1 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+            />
+          ) : (
+            <button
+              type="button"
+              onClick={() => {
+                setNewName(selectedContext.name);
+                setRenaming(selectedContext.name);
+              }}
+              className="inline-flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors"
+            >
+              <Pencil className="w-3.5 h-3.5" />
+              Rename
+            </button>
+          )}
+          <label className="inline-flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors cursor-pointer">
+            <input
+              type="color"
+              value={selectedContext.color ?? '#3b82f6'}
+              onChange={(e) =>
+                saveContextMutation.mutate({ name: selectedContext.name, color: e.target.value })
+              }
+              className="w-4 h-4 p-0 border-0 bg-transparent cursor-pointer"
+            />
+            Color
+          </label>
+          <button
+            type="button"
+            onClick={() => handleDelete(selectedContext)}
+            className="inline-flex items-center gap-1 px-2 py-1 text-xs text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 rounded-lg transition-colors"
+          >
+            <Trash2 className="w-3.5 h-3.5" />
+            Delete
+          </button>
+        </div>
+      )}
+      {error && <p className="mb-2 text-xs text-red-600 dark:text-red-400">{error}</p>}
+
+      {!isLoading && groups.length === 0 ? (
+        <p className="py-8 text-center text-sm text-surface-500 dark:text-surface-400">
+          No next actions{selected ? ` for ${selected}` : ''}.
+        </p>
+      ) : (
+        <div className="space-y-4">
+          {groups.map((group) => (
+            <div key={group.context}>
+              <h3 className="mb-1.5 text-sm font-semibold text-surface-700 dark:text-surface-300">
+                {group.context}
+              </h3>
+              <div className="space-y-1.5">
+                {group.tasks.map((task) => (
+                  <TaskRow key={task.id} task={task} />
+                ))}
+              </div>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,94 @@
+import { addDays, addWeeks, type Day, format, startOfDay, startOfWeek } from 'date-fns';
+import CalendarClock from 'lucide-react/icons/calendar-clock';
+import { useState } from 'react';
+import { useLocaleSettings, usePlannedTime } from '@/hooks/queries';
+import type { PlanGrouping } from '@/lib/database';
+import { formatDuration } from '@/utils/format';
+
+// days or weeks shown ahead
+const DAYS_AHEAD = 14;
+const WEEKS_AHEAD = 8;
+
+/**
+ * the estimated work of the tasks due in the coming days or weeks,
+ * e.g. "9h planned for Tuesday"
+ */
+export function PlannedView() {
+  const { data: locale } = useLocaleSettings();
+  const [groupBy, setGroupBy] = useState<PlanGrouping>('day');
+
+  // the backend keys weeks by their first day in the OS locale
+  const weekStartsOn = (locale?.firstDayOfWeek ?? 0) as Day;
+  const first =
+    groupBy === 'day' ? startOfDay(new Date()) : startOfWeek(new Date(), { weekStartsOn });
+  const periods = Array.from({ length: groupBy === 'day' ? DAYS_AHEAD : WEEKS_AHEAD }, (_, i) =>
+    groupBy === 'day' ? addDays(first, i) : addWeeks(first, i),
+  );
+  const end = groupBy === 'day' ? addDays(first, DAYS_AHEAD) : addWeeks(first, WEEKS_AHEAD);
+  const { data: planned = [] } = usePlannedTime(groupBy, first.toISOString(), end.toISOString());
+
+  const largest = Math.max(1, ...planned.map((p) => p.seconds));
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 overflow-y-auto p-4 overscroll-contain">
+      <div className="flex items-center gap-2 mb-4">
+        <CalendarClock className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="flex-1 text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Planned Time
+        </h2>
+        <div className="flex rounded-lg border border-surface-200 dark:border-surface-600 overflow-hidden">
+          {(['day', 'week'] as const).map((value) => (
+            <button
+              type="button"
+              key={value}
+              onClick={() => setGroupBy(value)}
+              className={`px-3 py-1.5 text-sm transition-colors ${
+                groupBy === value
+                  ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
+                  : 'text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700'
+              }`}
+            >
+              {value === 'day' ? 'Days' : 'Weeks'}
+            </button>
+          ))}
+        </div>
+      </div>
+
+      <div className="space-y-1.5">
+        {periods.map((period) => {
+          const key = format(period, 'yyyy-MM-dd');
+          const entry = planned.find((p) => p.key === key);
+          const label =
+            groupBy === 'day'
+              ? format(period, 'EEEE, MMM d')
+              : `Week of ${format(period, 'MMM d')}`;
+
+          return (
+            <div
+              key={key}
+              className="relative flex items-center gap-3 px-3 py-2 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700 overflow-hidden"
+            >
+              <div
+                className="absolute inset-y-0 left-0 bg-primary-50 dark:bg-primary-900/30"
+                style={{ width: `${((entry?.seconds ?? 0) / largest) * 100}%` }}
+              />
+              <div className="relative flex-1 min-w-0">
+                <div className="text-sm text-surface-800 dark:text-surface-200">{label}</div>
+                {entry && (
+                  <div className="text-xs text-surface-500 dark:text-surface-400 truncate">
+                    {entry.tasks} {entry.tasks === 1 ? 'task' : 'tasks'}
+                    {entry.seconds > 0 && `, ${formatDuration(entry.remainingSeconds)} remaining`}
+                    {entry.unestimated > 0 && `, ${entry.unestimated} without an estimate`}
+                  </div>
+                )}
+              </div>
+              <span className="relative text-sm tabular-nums text-surface-600 dark:text-surface-400">
+                {entry?.seconds ? `${formatDuration(entry.seconds)} planned` : 'Nothing planned'}
+              </span>
+            </div>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
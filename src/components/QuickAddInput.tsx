@@ -0,0 +1,131 @@
+import { format } from 'date-fns';
+import Plus from 'lucide-react/icons/plus';
+import { type FormEvent, useState } from 'react';
+import { useCreateTag, useCreateTask, useQuickAddDraft, useTags } from '@/hooks/queries';
+import { parseQuickAdd, type QuickAddDraft } from '@/lib/database';
+import type { Task } from '@/types';
+import { formatDueDate } from '@/utils/date';
+
+const chipClass =
+  'px-1.5 py-0.5 rounded text-xs bg-surface-100 dark:bg-surface-700 text-surface-600 dark:text-surface-400';
+
+// whether the text said anything besides the title
+const understood = (draft: QuickAddDraft) =>
+  !!(draft.dueDate || draft.tags.length || draft.priority || draft.list || draft.url);
+
+interface QuickAddInputProps {
+  /** called for an empty line, to add a blank task and open it */
+  onAddBlank: () => void;
+}
+
+/**
+ * adds a task from one line of text; the backend picks the due date, tags, priority and list
+ * out of it, e.g. "call mom tomorrow 5pm #family !high @Home"
+ */
+export function QuickAddInput({ onAddBlank }: QuickAddInputProps) {
+  const [text, setText] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const { data: draft } = useQuickAddDraft(text);
+  const { data: tags = [] } = useTags();
+  const createTaskMutation = useCreateTask();
+  const createTagMutation = useCreateTag();
+
+  // tags are matched by name, the ones that don't exist yet are created
+  const tagIds = async (names: string[]) => {
+    const ids: string[] = [];
+    for (const name of names) {
+      const existing = tags.find(
+        (tag) => tag.name.localeCompare(name, undefined, { sensitivity: 'base' }) === 0,
+      );
+      ids.push(existing?.id ?? (await createTagMutation.mutateAsync({ name })).id);
+    }
+    return ids;
+  };
+
+  const handleSubmit = async (e: FormEvent) => {
+    e.preventDefault();
+    setError(null);
+    if (!text.trim()) {
+      onAddBlank();
+      return;
+    }
+    try {
+      // parsed again, the preview can be a keystroke behind
+      const parsed = await parseQuickAdd(text);
+      // only what was typed, so the task defaults fill in the rest
+      const input: Partial<Task> = { title: parsed.title };
+      if (parsed.dueDate) {
+        input.dueDate = new Date(parsed.dueDate);
+        input.dueDateAllDay = parsed.dueDateAllDay;
+      }
+      if (parsed.tags.length > 0) input.tags = await tagIds(parsed.tags);
+      if (parsed.priority) input.priority = parsed.priority;
+      if (parsed.calendarId && parsed.accountId) {
+        input.calendarId = parsed.calendarId;
+        input.accountId = parsed.accountId;
+      }
+      if (parsed.url) input.url = parsed.url;
+      createTaskMutation.mutate(input, {
+        onSuccess: () => setText(''),
+        onError: (err) => setError(String(err)),
+      });
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const due = draft?.dueDate ? new Date(draft.dueDate) : null;
+
+  return (
+    <form onSubmit={handleSubmit} className="mt-4">
+      <div className="flex items-center gap-3 p-3 rounded-lg border-2 border-dashed border-surface-200 dark:border-surface-600 focus-within:border-primary-300 dark:focus-within:border-primary-600 transition-colors">
+        <button
+          type="submit"
+          aria-label="Add task"
+          className="text-surface-500 dark:text-surface-400 hover:text-surface-700 dark:hover:text-surface-300"
+        >
+          <Plus className="w-5 h-5" />
+        </button>
+        <input
+          type="text"
+          value={text}
+          onChange={(e) => setText(e.target.value)}
+          placeholder="Add a task... e.g. call mom tomorrow 5pm #family !high"
+          className="flex-1 bg-transparent text-surface-800 dark:text-surface-200 placeholder:text-surface-500 dark:placeholder:text-surface-400 focus:outline-none"
+        />
+      </div>
+      {text.trim() && draft && understood(draft) && (
+        <div className="mt-2 flex flex-wrap items-center gap-1.5 px-1">
+          <span className="text-xs text-surface-700 dark:text-surface-300">{draft.title}</span>
+          {due && (
+            <span className={chipClass}>
+              {draft.dueDateAllDay ? formatDueDate(due).text : format(due, 'PP p')}
+            </span>
+          )}
+          {draft.priority && <span className={chipClass}>!{draft.priority}</span>}
+          {draft.tags.map((tag) => (
+            <span key={tag} className={chipClass}>
+              #{tag}
+            </span>
+          ))}
+          {draft.list && (
+            <span
+              title={draft.calendarId ? undefined : 'No list has this name'}
+              className={draft.calendarId ? chipClass : `${chipClass} line-through`}
+            >
+              @{draft.list}
+            </span>
+          )}
+          {draft.occurrences.length > 0 && (
+            <span className="text-xs text-surface-500 dark:text-surface-400">
+              Only the first of{' '}
+              {draft.occurrences.map((date) => format(new Date(date), 'MMM d')).join(', ')} is
+              kept, tasks don't repeat yet
+            </span>
+          )}
+        </div>
+      )}
+      {error && <p className="mt-2 px-1 text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </form>
+  );
+}
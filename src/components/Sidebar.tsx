@@ -1,45 +1,70 @@
 import { useQueryClient } from '@tanstack/react-query';
+import AlertCircle from 'lucide-react/icons/alert-circle';
+import Archive from 'lucide-react/icons/archive';
+import ArchiveRestore from 'lucide-react/icons/archive-restore';
+import ArrowDown from 'lucide-react/icons/arrow-down';
+import ArrowUp from 'lucide-react/icons/arrow-up';
+import AtSign from 'lucide-react/icons/at-sign';
+import CalendarClock from 'lucide-react/icons/calendar-clock';
+import CalendarDays from 'lucide-react/icons/calendar-days';
+import ChartColumn from 'lucide-react/icons/chart-column';
 import ChevronDown from 'lucide-react/icons/chevron-down';
 import ChevronRight from 'lucide-react/icons/chevron-right';
+import Columns3 from 'lucide-react/icons/columns-3';
 import Download from 'lucide-react/icons/download';
 import Edit2 from 'lucide-react/icons/edit-2';
+import ExternalLink from 'lucide-react/icons/external-link';
+import Eye from 'lucide-react/icons/eye';
+import EyeOff from 'lucide-react/icons/eye-off';
 import FolderKanban from 'lucide-react/icons/folder-kanban';
 import Import from 'lucide-react/icons/import';
 import Inbox from 'lucide-react/icons/inbox';
+import ListChecks from 'lucide-react/icons/list-checks';
 import MoreVertical from 'lucide-react/icons/more-vertical';
 import PanelLeftClose from 'lucide-react/icons/panel-left-close';
 import PanelLeftOpen from 'lucide-react/icons/panel-left-open';
+import Pause from 'lucide-react/icons/pause';
+import Play from 'lucide-react/icons/play';
 import Plus from 'lucide-react/icons/plus';
 import RefreshCw from 'lucide-react/icons/refresh-cw';
 import Settings from 'lucide-react/icons/settings';
 import Share2 from 'lucide-react/icons/share-2';
+import Timer from 'lucide-react/icons/timer';
 import Trash2 from 'lucide-react/icons/trash-2';
 import User from 'lucide-react/icons/user';
 import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
 import {
+  useAccountHealth,
   useAccounts,
   useDeleteAccount,
   useDeleteTag,
+  useReorderAccounts,
+  useReorderCalendars,
+  useSetAccountActive,
+  useSetAccountColor,
   useSetActiveAccount,
   useSetActiveCalendar,
   useSetActiveTag,
   useSetAllTasksView,
+  useSetCalendarArchived,
   useTags,
   useTasks,
   useUIState,
 } from '@/hooks/queries';
 import { useGlobalContextMenuClose } from '@/hooks/useGlobalContextMenu';
+import { openCalendarWindow } from '@/lib/database';
 import { createLogger } from '@/lib/logger';
+import { syncBackendFor } from '@/lib/syncBackend';
 import * as taskData from '@/lib/taskData';
 
 const log = createLogger('Sidebar', '#ec4899');
 
 import { useModalState } from '@/context/modalStateContext';
 import { useConfirmDialog } from '@/hooks/useConfirmDialog';
-import { caldavService } from '@/lib/caldav';
 import { useSettingsStore } from '@/store/settingsStore';
-import type { Account, Calendar as CalendarType } from '@/types';
+import type { Account, Calendar as CalendarType, MainView } from '@/types';
 import { getContrastTextColor } from '../utils/color';
+import { COLOR_PRESETS } from '../utils/constants';
 import { getMetaKeyLabel, getModifierJoiner } from '../utils/keyboard';
 import { clampToViewport } from '../utils/position';
 import { getIconByName } from './IconPicker';
@@ -51,6 +76,8 @@ import { TagModal } from './modals/TagModal';
 import { Tooltip } from './Tooltip';
 
 interface SidebarProps {
+  view: MainView;
+  onViewChange: (view: MainView) => void;
   onOpenSettings?: () => void;
   onOpenImport?: () => void;
   isCollapsed: boolean;
@@ -61,10 +88,24 @@ interface SidebarProps {
   onUpdateClick?: () => void;
 }
 
+// the views listed below All Tasks
+const views: { id: Exclude<MainView, 'tasks'>; label: string; icon: typeof Inbox }[] = [
+  { id: 'agenda', label: 'Agenda', icon: CalendarDays },
+  { id: 'actionable', label: 'Actionable Now', icon: ListChecks },
+  { id: 'next', label: 'Next Actions', icon: AtSign },
+  { id: 'board', label: 'Board', icon: Columns3 },
+  { id: 'time', label: 'Tracked Time', icon: Timer },
+  { id: 'planned', label: 'Planned Time', icon: CalendarClock },
+  { id: 'stats', label: 'Statistics', icon: ChartColumn },
+  { id: 'archive', label: 'Archive', icon: Archive },
+];
+
 const MIN_SIDEBAR_WIDTH = 200;
 const MAX_SIDEBAR_WIDTH = 400;
 
 export function Sidebar({
+  view,
+  onViewChange,
   onOpenSettings,
   onOpenImport,
   isCollapsed,
@@ -76,10 +117,16 @@ export function Sidebar({
 }: SidebarProps) {
   const queryClient = useQueryClient();
   const { data: accounts = [] } = useAccounts();
+  const { data: accountHealth = [] } = useAccountHealth();
   const { data: tags = [] } = useTags();
   const { data: uiState } = useUIState();
   const { data: tasks = [] } = useTasks();
 
+  const setAccountActiveMutation = useSetAccountActive();
+  const setCalendarArchivedMutation = useSetCalendarArchived();
+  const reorderCalendarsMutation = useReorderCalendars();
+  const reorderAccountsMutation = useReorderAccounts();
+  const setAccountColorMutation = useSetAccountColor();
   const setActiveAccountMutation = useSetActiveAccount();
   const setActiveCalendarMutation = useSetActiveCalendar();
   const setActiveTagMutation = useSetActiveTag();
@@ -87,44 +134,32 @@ export function Sidebar({
   const deleteAccountMutation = useDeleteAccount();
   const deleteTagMutation = useDeleteTag();
 
-  const activeCalendarId = uiState?.activeCalendarId ?? null;
-  const activeTagId = uiState?.activeTagId ?? null;
+  // nothing in the sidebar's lists is highlighted while a view is shown
+  const activeCalendarId = view === 'tasks' ? (uiState?.activeCalendarId ?? null) : undefined;
+  const activeTagId = view === 'tasks' ? (uiState?.activeTagId ?? null) : undefined;
 
   const { isAnyModalOpen } = useModalState();
-  const { confirm } = useConfirmDialog();
-  const {
-    confirmBeforeDeleteCalendar,
-    confirmBeforeDeleteAccount,
-    confirmBeforeDeleteTag,
-    expandedAccountIds,
-    defaultAccountsExpanded,
-    toggleAccountExpanded,
-    setExpandedAccountIds,
-  } = useSettingsStore();
-
-  // Track which account IDs we've already initialized (to avoid re-processing)
-  const initializedAccountIdsRef = useRef<Set<string>>(new Set(expandedAccountIds));
-
-  // Initialize expanded accounts: new accounts should follow defaultAccountsExpanded setting
-  useEffect(() => {
-    const currentAccountIds = accounts.map((a) => a.id);
-    const newAccountIds = currentAccountIds.filter(
-      (id) => !initializedAccountIdsRef.current.has(id),
-    );
-
-    if (newAccountIds.length > 0) {
-      // Mark these accounts as initialized
-      newAccountIds.forEach((id) => initializedAccountIdsRef.current.add(id));
-
-      // If they should be expanded by default, add them to the expanded list
-      if (defaultAccountsExpanded) {
-        setExpandedAccountIds([...expandedAccountIds, ...newAccountIds]);
-      }
-    }
-  }, [accounts, defaultAccountsExpanded, setExpandedAccountIds, expandedAccountIds]);
+  const { confirm, confirmWithAlternate } = useConfirmDialog();
+  const { confirmBeforeDeleteCalendar, confirmBeforeDeleteAccount, confirmBeforeDeleteTag } =
+    useSettingsStore();
 
-  // Convert expandedAccountIds array to a Set for efficient lookups
-  const expandedAccounts = useMemo(() => new Set(expandedAccountIds), [expandedAccountIds]);
+  // the collapse state is kept with the accounts, new ones follow defaultAccountsExpanded
+  const expandedAccounts = useMemo(
+    () => new Set(accounts.filter((a) => !a.collapsed).map((a) => a.id)),
+    [accounts],
+  );
+  // archived calendars stay hidden unless they're shown for their account
+  const archivedCalendarIds = useMemo(
+    () =>
+      new Set(accounts.flatMap((a) => a.calendars.filter((c) => c.archived).map((c) => c.id))),
+    [accounts],
+  );
+  // accounts whose syncs keep failing get a warning next to their name
+  const failingAccountIds = useMemo(
+    () => new Set(accountHealth.filter((h) => h.attentionNeeded).map((h) => h.accountId)),
+    [accountHealth],
+  );
+  const [showArchivedFor, setShowArchivedFor] = useState<Set<string>>(new Set());
   const [showAccountModal, setShowAccountModal] = useState(false);
   const [showTagModal, setShowTagModal] = useState(false);
   const [showCalendarModal, setShowCalendarModal] = useState(false);
@@ -214,7 +249,7 @@ export function Sidebar({
   };
 
   const toggleAccount = (id: string) => {
-    toggleAccountExpanded(id);
+    taskData.setAccountCollapsed(id, expandedAccounts.has(id));
   };
 
   const handleContextMenu = (
@@ -242,11 +277,42 @@ export function Sidebar({
   };
 
   const getTotalActiveTaskCount = () => {
-    return tasks.filter((t) => !t.completed).length;
+    return tasks.filter((t) => !t.completed && !archivedCalendarIds.has(t.calendarId)).length;
   };
 
   const getTagTaskCount = (tagId: string) => {
-    return tasks.filter((t) => (t.tags || []).includes(tagId) && !t.completed).length;
+    return tasks.filter(
+      (t) =>
+        (t.tags || []).includes(tagId) && !t.completed && !archivedCalendarIds.has(t.calendarId),
+    ).length;
+  };
+
+  // swap a calendar with its neighbour in the sidebar, `offset` -1 for up and 1 for down
+  const moveCalendar = (accountId: string, calendarId: string, offset: number) => {
+    const ids = accounts.find((a) => a.id === accountId)?.calendars.map((c) => c.id) ?? [];
+    const from = ids.indexOf(calendarId);
+    const to = from + offset;
+    if (from < 0 || to < 0 || to >= ids.length) return;
+    [ids[from], ids[to]] = [ids[to], ids[from]];
+    reorderCalendarsMutation.mutate({ accountId, orderedIds: ids });
+  };
+
+  // the same for an account among the others
+  const moveAccount = (accountId: string, offset: number) => {
+    const ids = accounts.map((a) => a.id);
+    const from = ids.indexOf(accountId);
+    const to = from + offset;
+    if (from < 0 || to < 0 || to >= ids.length) return;
+    [ids[from], ids[to]] = [ids[to], ids[from]];
+    reorderAccountsMutation.mutate(ids);
+  };
+
+  const toggleArchivedFor = (accountId: string) => {
+    setShowArchivedFor((shown) => {
+      const next = new Set(shown);
+      if (!next.delete(accountId)) next.add(accountId);
+      return next;
+    });
   };
 
   return (
@@ -303,6 +369,7 @@ export function Sidebar({
               <button
                 type="button"
                 onClick={() => {
+                  onViewChange('tasks');
                   setAllTasksViewMutation.mutate();
                   setActiveAccountMutation.mutate(null);
                 }}
@@ -317,6 +384,24 @@ export function Sidebar({
                 <span className="text-xs">{getTotalActiveTaskCount()}</span>
               </button>
 
+              <div className="mb-4 -mt-2">
+                {views.map(({ id, label, icon: ViewIcon }) => (
+                  <button
+                    type="button"
+                    key={id}
+                    onClick={() => onViewChange(id)}
+                    className={`w-full flex items-center gap-2 px-4 py-2 text-sm transition-colors ${
+                      view === id
+                        ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
+                        : `text-surface-600 dark:text-surface-400 ${!isAnyModalOpen ? 'hover:bg-surface-200 dark:hover:bg-surface-700' : ''}`
+                    }`}
+                  >
+                    <ViewIcon className="w-4 h-4" />
+                    <span className="flex-1 text-left">{label}</span>
+                  </button>
+                ))}
+              </div>
+
               <div className="mb-4">
                 <div className="flex items-center justify-between px-4 py-2">
                   <span className="text-xs font-semibold text-surface-500 dark:text-surface-400 uppercase tracking-wider">
@@ -353,7 +438,11 @@ export function Sidebar({
                   </div>
                 ) : (
                   accounts.map((account) => (
-                    <div key={account.id} data-context-menu>
+                    <div
+                      key={account.id}
+                      data-context-menu
+                      className={account.isActive ? undefined : 'opacity-50'}
+                    >
                       <div
                         onClick={() => toggleAccount(account.id)}
                         onContextMenu={(e) => handleContextMenu(e, 'account', account.id)}
@@ -364,10 +453,21 @@ export function Sidebar({
                         ) : (
                           <ChevronRight className="w-4 h-4 text-surface-400 flex-shrink-0" />
                         )}
-                        <User className="w-4 h-4 text-surface-500 dark:text-surface-400 flex-shrink-0" />
+                        <User
+                          className="w-4 h-4 text-surface-500 dark:text-surface-400 flex-shrink-0"
+                          style={account.color ? { color: account.color } : undefined}
+                        />
                         <span className="flex-1 text-left truncate text-surface-700 dark:text-surface-300">
                           {account.name}
+                          {!account.isActive && (
+                            <span className="ml-1 text-xs text-surface-400">(paused)</span>
+                          )}
                         </span>
+                        {failingAccountIds.has(account.id) && (
+                          <Tooltip content="Syncing keeps failing" position="top">
+                            <AlertCircle className="w-3.5 h-3.5 text-red-500 flex-shrink-0" />
+                          </Tooltip>
+                        )}
                         <Tooltip content="Add a new calendar" position="top">
                           <button
                             type="button"
@@ -409,47 +509,53 @@ export function Sidebar({
                               No calendars yet.
                             </div>
                           ) : (
-                            account.calendars.map((calendar) => {
-                              const CalendarIcon = getIconByName(calendar.icon || 'calendar');
-                              const isActive = activeCalendarId === calendar.id;
-                              const calendarColor = calendar.color ?? '#3b82f6';
-                              const textColor = isActive
-                                ? getContrastTextColor(calendarColor)
-                                : undefined;
-                              return (
-                                <button
-                                  type="button"
-                                  key={calendar.id}
-                                  data-context-menu
-                                  onClick={() => {
-                                    setActiveAccountMutation.mutate(account.id);
-                                    setActiveCalendarMutation.mutate(calendar.id);
-                                  }}
-                                  onContextMenu={(e) =>
-                                    handleContextMenu(e, 'calendar', calendar.id, account.id)
-                                  }
-                                  className={`w-full flex items-center gap-2 px-4 py-2 text-sm transition-colors ${
-                                    isActive
-                                      ? ''
-                                      : `text-surface-600 dark:text-surface-400 ${!isAnyModalOpen ? 'hover:bg-surface-200 dark:hover:bg-surface-700' : ''}`
-                                  }`}
-                                  style={
-                                    isActive
-                                      ? { backgroundColor: calendarColor, color: textColor }
-                                      : undefined
-                                  }
-                                >
-                                  <CalendarIcon
-                                    className="w-4 h-4"
-                                    style={{ color: isActive ? textColor : calendarColor }}
-                                  />
-                                  <span className="flex-1 text-left truncate">
-                                    {calendar.displayName}
-                                  </span>
-                                  <span className="text-xs">{getTaskCount(calendar.id)}</span>
-                                </button>
-                              );
-                            })
+                            account.calendars
+                              .filter((c) => !c.archived || showArchivedFor.has(account.id))
+                              .map((calendar) => {
+                                const CalendarIcon = getIconByName(calendar.icon || 'calendar');
+                                const isActive = activeCalendarId === calendar.id;
+                                const calendarColor = calendar.color ?? '#3b82f6';
+                                const textColor = isActive
+                                  ? getContrastTextColor(calendarColor)
+                                  : undefined;
+                                return (
+                                  <button
+                                    type="button"
+                                    key={calendar.id}
+                                    data-context-menu
+                                    onClick={() => {
+                                      onViewChange('tasks');
+                                      setActiveAccountMutation.mutate(account.id);
+                                      setActiveCalendarMutation.mutate(calendar.id);
+                                    }}
+                                    onContextMenu={(e) =>
+                                      handleContextMenu(e, 'calendar', calendar.id, account.id)
+                                    }
+                                    className={`w-full flex items-center gap-2 px-4 py-2 text-sm transition-colors ${calendar.archived ? 'opacity-50' : ''} ${
+                                      isActive
+                                        ? ''
+                                        : `text-surface-600 dark:text-surface-400 ${!isAnyModalOpen ? 'hover:bg-surface-200 dark:hover:bg-surface-700' : ''}`
+                                    }`}
+                                    style={
+                                      isActive
+                                        ? { backgroundColor: calendarColor, color: textColor }
+                                        : undefined
+                                    }
+                                  >
+                                    <CalendarIcon
+                                      className="w-4 h-4"
+                                      style={{ color: isActive ? textColor : calendarColor }}
+                                    />
+                                    <span className="flex-1 text-left truncate">
+                                      {calendar.displayName}
+                                      {calendar.archived && (
+                                        <span className="ml-1 text-xs">(archived)</span>
+                                      )}
+                                    </span>
+                                    <span className="text-xs">{getTaskCount(calendar.id)}</span>
+                                  </button>
+                                );
+                              })
                           )}
                         </div>
                       )}
@@ -490,7 +596,10 @@ export function Sidebar({
                         type="button"
                         key={tag.id}
                         data-context-menu
-                        onClick={() => setActiveTagMutation.mutate(tag.id)}
+                        onClick={() => {
+                          onViewChange('tasks');
+                          setActiveTagMutation.mutate(tag.id);
+                        }}
                         onContextMenu={(e) => handleContextMenu(e, 'tag', tag.id)}
                         className={`w-full flex items-center gap-2 px-4 py-2 text-sm transition-colors ${
                           isActive
@@ -550,6 +659,7 @@ export function Sidebar({
               <button
                 type="button"
                 onClick={() => {
+                  onViewChange('tasks');
                   setAllTasksViewMutation.mutate();
                   setActiveAccountMutation.mutate(null);
                 }}
@@ -563,12 +673,28 @@ export function Sidebar({
               </button>
             </Tooltip>
 
+            {views.map(({ id, label, icon: ViewIcon }) => (
+              <Tooltip key={id} content={label} position="right">
+                <button
+                  type="button"
+                  onClick={() => onViewChange(id)}
+                  className={`p-2 rounded-lg transition-colors ${
+                    view === id
+                      ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-600 dark:text-primary-400'
+                      : 'text-surface-500 dark:text-surface-400 hover:bg-surface-200 dark:hover:bg-surface-700'
+                  }`}
+                >
+                  <ViewIcon className="w-5 h-5" />
+                </button>
+              </Tooltip>
+            ))}
+
             {/* Separator */}
             <div className="w-6 h-px bg-surface-200 dark:bg-surface-700 my-1" />
 
             {/* Calendars */}
             {accounts.flatMap((account) =>
-              account.calendars.map((calendar) => {
+              account.calendars.filter((c) => !c.archived).map((calendar) => {
                 const CalendarIcon = getIconByName(calendar.icon || 'calendar');
                 const isActive = activeCalendarId === calendar.id;
                 const calendarColor = calendar.color ?? '#3b82f6';
@@ -578,6 +704,7 @@ export function Sidebar({
                       type="button"
                       data-context-menu
                       onClick={() => {
+                        onViewChange('tasks');
                         setActiveAccountMutation.mutate(account.id);
                         setActiveCalendarMutation.mutate(calendar.id);
                       }}
@@ -625,6 +752,7 @@ export function Sidebar({
                     type="button"
                     data-context-menu
                     onClick={() => {
+                      onViewChange('tasks');
                       setActiveTagMutation.mutate(tag.id);
                     }}
                     onContextMenu={(e) => handleContextMenu(e, 'tag', tag.id)}
@@ -711,6 +839,107 @@ export function Sidebar({
             </button>
           )}
 
+          {contextMenu.type === 'account' &&
+            (() => {
+              const paused = accounts.find((a) => a.id === contextMenu.id)?.isActive === false;
+              return (
+                <button
+                  type="button"
+                  onClick={() => {
+                    setAccountActiveMutation.mutate({ id: contextMenu.id, active: paused });
+                    handleCloseContextMenu();
+                  }}
+                  className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+                >
+                  {paused ? <Play className="w-4 h-4" /> : <Pause className="w-4 h-4" />}
+                  {paused ? 'Resume Account' : 'Pause Account'}
+                </button>
+              );
+            })()}
+
+          {contextMenu.type === 'account' &&
+            (() => {
+              const index = accounts.findIndex((a) => a.id === contextMenu.id);
+              const color = accounts[index]?.color ?? null;
+              return (
+                <>
+                  {index > 0 && (
+                    <button
+                      type="button"
+                      onClick={() => {
+                        moveAccount(contextMenu.id, -1);
+                        handleCloseContextMenu();
+                      }}
+                      className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+                    >
+                      <ArrowUp className="w-4 h-4" />
+                      Move Up
+                    </button>
+                  )}
+                  {index >= 0 && index < accounts.length - 1 && (
+                    <button
+                      type="button"
+                      onClick={() => {
+                        moveAccount(contextMenu.id, 1);
+                        handleCloseContextMenu();
+                      }}
+                      className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+                    >
+                      <ArrowDown className="w-4 h-4" />
+                      Move Down
+                    </button>
+                  )}
+                  <div className="flex items-center gap-1.5 px-3 py-2">
+                    {COLOR_PRESETS.map((preset) => (
+                      <button
+                        key={preset}
+                        type="button"
+                        title="Account color"
+                        onClick={() => {
+                          setAccountColorMutation.mutate({ id: contextMenu.id, color: preset });
+                          handleCloseContextMenu();
+                        }}
+                        className={`w-4 h-4 rounded-full border-2 ${color === preset ? 'border-surface-800 dark:border-white' : 'border-transparent'}`}
+                        style={{ backgroundColor: preset }}
+                      />
+                    ))}
+                    {color && (
+                      <button
+                        type="button"
+                        title="Default color"
+                        onClick={() => {
+                          setAccountColorMutation.mutate({ id: contextMenu.id, color: null });
+                          handleCloseContextMenu();
+                        }}
+                        className="w-4 h-4 rounded-full border border-surface-300 dark:border-surface-600"
+                      />
+                    )}
+                  </div>
+                </>
+              );
+            })()}
+
+          {contextMenu.type === 'account' &&
+            accounts.find((a) => a.id === contextMenu.id)?.calendars.some((c) => c.archived) && (
+              <button
+                type="button"
+                onClick={() => {
+                  toggleArchivedFor(contextMenu.id);
+                  handleCloseContextMenu();
+                }}
+                className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+              >
+                {showArchivedFor.has(contextMenu.id) ? (
+                  <EyeOff className="w-4 h-4" />
+                ) : (
+                  <Eye className="w-4 h-4" />
+                )}
+                {showArchivedFor.has(contextMenu.id)
+                  ? 'Hide Archived Calendars'
+                  : 'Show Archived Calendars'}
+              </button>
+            )}
+
           {contextMenu.type === 'calendar' && (
             <button
               type="button"
@@ -744,6 +973,85 @@ export function Sidebar({
             </button>
           )}
 
+          {contextMenu.type === 'calendar' && (
+            <button
+              type="button"
+              onClick={() => {
+                const calendar = accounts
+                  .flatMap((a) => a.calendars)
+                  .find((c) => c.id === contextMenu.id);
+                openCalendarWindow(contextMenu.id, calendar?.displayName).catch((error) =>
+                  log.error('Failed to open the calendar window:', error),
+                );
+                handleCloseContextMenu();
+              }}
+              className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+            >
+              <ExternalLink className="w-4 h-4" />
+              Open in New Window
+            </button>
+          )}
+
+          {contextMenu.type === 'calendar' &&
+            (() => {
+              const archived = archivedCalendarIds.has(contextMenu.id);
+              return (
+                <button
+                  type="button"
+                  onClick={() => {
+                    setCalendarArchivedMutation.mutate({ id: contextMenu.id, archived: !archived });
+                    handleCloseContextMenu();
+                  }}
+                  className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+                >
+                  {archived ? (
+                    <ArchiveRestore className="w-4 h-4" />
+                  ) : (
+                    <Archive className="w-4 h-4" />
+                  )}
+                  {archived ? 'Restore Calendar' : 'Archive Calendar'}
+                </button>
+              );
+            })()}
+
+          {contextMenu.type === 'calendar' &&
+            contextMenu.accountId &&
+            (() => {
+              const accountId = contextMenu.accountId;
+              const ids = accounts.find((a) => a.id === accountId)?.calendars.map((c) => c.id);
+              const index = ids?.indexOf(contextMenu.id) ?? -1;
+              return (
+                <>
+                  {index > 0 && (
+                    <button
+                      type="button"
+                      onClick={() => {
+                        moveCalendar(accountId, contextMenu.id, -1);
+                        handleCloseContextMenu();
+                      }}
+                      className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+                    >
+                      <ArrowUp className="w-4 h-4" />
+                      Move Up
+                    </button>
+                  )}
+                  {ids && index >= 0 && index < ids.length - 1 && (
+                    <button
+                      type="button"
+                      onClick={() => {
+                        moveCalendar(accountId, contextMenu.id, 1);
+                        handleCloseContextMenu();
+                      }}
+                      className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+                    >
+                      <ArrowDown className="w-4 h-4" />
+                      Move Down
+                    </button>
+                  )}
+                </>
+              );
+            })()}
+
           <button
             type="button"
             onClick={async () => {
@@ -781,20 +1089,23 @@ export function Sidebar({
 
               if (contextMenu.type === 'account') {
                 const account = accounts.find((a) => a.id === contextMenu.id);
+                let keepTasks = false;
                 if (confirmBeforeDeleteAccount) {
-                  const confirmed = await confirm({
+                  const result = await confirmWithAlternate({
                     title: 'Remove account',
                     subtitle: account?.name,
                     message:
-                      'Are you sure? All tasks from this account will be removed from the app. They will remain on the server.',
+                      'Are you sure? All tasks from this account will be removed from the app. They will remain on the server, or can be kept here as local tasks in the Recovered list.',
                     confirmLabel: 'Remove',
+                    alternateLabel: 'Keep Tasks',
                     destructive: true,
                   });
-                  if (!confirmed) {
+                  if (result === 'cancel') {
                     return;
                   }
+                  keepTasks = result === 'alternate';
                 }
-                deleteAccountMutation.mutate(contextMenu.id);
+                deleteAccountMutation.mutate({ id: contextMenu.id, keepTasks });
               } else if (contextMenu.type === 'tag') {
                 const tag = tags.find((t) => t.id === contextMenu.id);
                 if (confirmBeforeDeleteTag) {
@@ -831,7 +1142,8 @@ export function Sidebar({
 
                 // delete calendar from server
                 try {
-                  await caldavService.deleteCalendar(contextMenu.accountId, contextMenu.id);
+                  const backend = syncBackendFor(taskData.getAccountById(contextMenu.accountId));
+                  await backend.deleteCalendar(contextMenu.accountId, contextMenu.id);
                   // delete calendar and its tasks from local state
                   taskData.deleteCalendar(contextMenu.accountId, contextMenu.id);
 
@@ -0,0 +1,208 @@
+import { save } from '@tauri-apps/plugin-dialog';
+import { writeTextFile } from '@tauri-apps/plugin-fs';
+import { addDays, format, parseISO, startOfDay } from 'date-fns';
+import ChartColumn from 'lucide-react/icons/chart-column';
+import FileText from 'lucide-react/icons/file-text';
+import { useState } from 'react';
+import { useCompletionStats, useOverdueTrend, useStreaks } from '@/hooks/queries';
+import { generateReport, type ReportFormat, type StatsGrouping, type Streak } from '@/lib/database';
+import { downloadFile } from '@/utils/file';
+
+// ranges ending today, in days
+const ranges = [7, 30, 90, 365];
+
+const groupings: { value: StatsGrouping; label: string }[] = [
+  { value: 'day', label: 'Day' },
+  { value: 'week', label: 'Week' },
+  { value: 'list', label: 'List' },
+  { value: 'tag', label: 'Tag' },
+];
+
+// 'yyyy-mm-dd' keys of the day and week groupings are shown as dates
+function statsLabel(groupBy: StatsGrouping, key: string, label: string): string {
+  if (groupBy === 'day') return format(parseISO(key), 'EEE, MMM d');
+  if (groupBy === 'week') return `Week of ${format(parseISO(key), 'MMM d')}`;
+  return label;
+}
+
+function StreakCard({ label, streak }: { label: string; streak?: Streak }) {
+  return (
+    <div className="px-4 py-3 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700">
+      <div className="text-2xl font-semibold text-surface-800 dark:text-surface-200">
+        {streak?.current ?? 0} {streak?.current === 1 ? 'day' : 'days'}
+      </div>
+      <div className="text-xs text-surface-500 dark:text-surface-400">
+        {label}, longest {streak?.longest ?? 0}
+      </div>
+    </div>
+  );
+}
+
+/**
+ * what got done: completions per day, week, list or tag and the overdue trend
+ */
+export function StatsView() {
+  const [days, setDays] = useState(30);
+  const [groupBy, setGroupBy] = useState<StatsGrouping>('day');
+  const [reportFormat, setReportFormat] = useState<ReportFormat>('markdown');
+  const [reportError, setReportError] = useState<string | null>(null);
+
+  const to = addDays(startOfDay(new Date()), 1);
+  const from = addDays(to, -days).toISOString();
+  const { data: stats = [], isLoading } = useCompletionStats(groupBy, from, to.toISOString());
+  const { data: trend = [] } = useOverdueTrend(from, to.toISOString());
+  const { data: streaks } = useStreaks();
+
+  const completed = stats.reduce((sum, s) => sum + s.completed, 0);
+  const completedLate = stats.reduce((sum, s) => sum + s.completedLate, 0);
+  const largest = Math.max(1, ...stats.map((s) => s.completed));
+  const mostOverdue = Math.max(1, ...trend.map((d) => d.overdue));
+
+  const handleExportReport = async () => {
+    setReportError(null);
+    const extension = reportFormat === 'html' ? 'html' : 'md';
+    const formatName = reportFormat === 'html' ? 'HTML' : 'Markdown';
+    const fileName = `report-${format(new Date(), 'yyyy-MM-dd')}.${extension}`;
+    try {
+      const report = await generateReport(from, to.toISOString(), reportFormat);
+      try {
+        const path = await save({
+          defaultPath: fileName,
+          filters: [{ name: formatName, extensions: [extension] }],
+        });
+        if (path) {
+          await writeTextFile(path, report);
+        }
+      } catch (_e) {
+        // fallback to browser download
+        downloadFile(report, fileName, reportFormat === 'html' ? 'text/html' : 'text/markdown');
+      }
+    } catch (err) {
+      setReportError(String(err));
+    }
+  };
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 overflow-y-auto p-4 overscroll-contain">
+      <div className="flex items-center gap-2 mb-4">
+        <ChartColumn className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="flex-1 text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Statistics
+        </h2>
+        <select
+          value={days}
+          onChange={(e) => setDays(Number(e.target.value))}
+          className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+        >
+          {ranges.map((range) => (
+            <option key={range} value={range}>
+              Last {range} days
+            </option>
+          ))}
+        </select>
+        <select
+          value={reportFormat}
+          onChange={(e) => setReportFormat(e.target.value as ReportFormat)}
+          className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+        >
+          <option value="markdown">Markdown</option>
+          <option value="html">HTML</option>
+        </select>
+        <button
+          type="button"
+          onClick={handleExportReport}
+          title="A summary of the tasks completed in this range and of the open ones"
+          className="flex items-center gap-2 px-3 py-1.5 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors"
+        >
+          <FileText className="w-4 h-4" />
+          Export Report
+        </button>
+      </div>
+      {reportError && <p className="mb-2 text-xs text-red-600 dark:text-red-400">{reportError}</p>}
+
+      <div className="grid grid-cols-2 gap-3 mb-3">
+        <StreakCard label="Completion streak" streak={streaks?.completion} />
+        <StreakCard label="Without overdue tasks" streak={streaks?.noOverdue} />
+      </div>
+      <div className="grid grid-cols-2 gap-3 mb-6">
+        <div className="px-4 py-3 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700">
+          <div className="text-2xl font-semibold text-surface-800 dark:text-surface-200">
+            {completed}
+          </div>
+          <div className="text-xs text-surface-500 dark:text-surface-400">Tasks completed</div>
+        </div>
+        <div className="px-4 py-3 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700">
+          <div className="text-2xl font-semibold text-surface-800 dark:text-surface-200">
+            {completedLate}
+          </div>
+          <div className="text-xs text-surface-500 dark:text-surface-400">Completed after due</div>
+        </div>
+      </div>
+
+      <div className="flex items-center gap-2 mb-2">
+        <h3 className="flex-1 text-sm font-semibold text-surface-700 dark:text-surface-300">
+          Completed
+        </h3>
+        <div className="flex rounded-lg border border-surface-200 dark:border-surface-600 overflow-hidden">
+          {groupings.map((g) => (
+            <button
+              type="button"
+              key={g.value}
+              onClick={() => setGroupBy(g.value)}
+              className={`px-3 py-1 text-xs transition-colors ${
+                groupBy === g.value
+                  ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
+                  : 'text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700'
+              }`}
+            >
+              {g.label}
+            </button>
+          ))}
+        </div>
+      </div>
+      {!isLoading && stats.length === 0 ? (
+        <p className="py-6 text-center text-sm text-surface-500 dark:text-surface-400">
+          No tasks completed in this range.
+        </p>
+      ) : (
+        <div className="space-y-1.5 mb-6">
+          {stats.map((s) => (
+            <div
+              key={s.key}
+              className="relative flex items-center gap-3 px-3 py-2 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700 overflow-hidden"
+            >
+              <div
+                className="absolute inset-y-0 left-0 bg-primary-50 dark:bg-primary-900/30"
+                style={{ width: `${(s.completed / largest) * 100}%` }}
+              />
+              <span className="relative flex-1 text-sm text-surface-800 dark:text-surface-200 truncate">
+                {statsLabel(groupBy, s.key, s.label)}
+              </span>
+              <span className="relative text-xs text-surface-500 dark:text-surface-400">
+                {s.completedLate > 0 && `${s.completedLate} late · `}
+                {s.averageAgeDays !== null && `${s.averageAgeDays.toFixed(1)} days old on average`}
+              </span>
+              <span className="relative w-10 text-right text-sm font-medium tabular-nums text-surface-700 dark:text-surface-300">
+                {s.completed}
+              </span>
+            </div>
+          ))}
+        </div>
+      )}
+
+      <h3 className="mb-2 text-sm font-semibold text-surface-700 dark:text-surface-300">
+        Overdue at the end of the day
+      </h3>
+      <div className="flex items-end gap-px h-32 px-3 py-2 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700">
+        {trend.map((d) => (
+          <div
+            key={d.day}
+            className="flex-1 min-w-px bg-red-300 dark:bg-red-500/60 rounded-t-sm"
+            style={{ height: `${(d.overdue / mostOverdue) * 100}%` }}
+            title={`${format(parseISO(d.day), 'MMM d')}: ${d.overdue} overdue, ${d.completedLate} completed late`}
+          />
+        ))}
+      </div>
+    </div>
+  );
+}
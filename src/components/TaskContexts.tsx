@@ -0,0 +1,101 @@
+import AtSign from 'lucide-react/icons/at-sign';
+import Plus from 'lucide-react/icons/plus';
+import X from 'lucide-react/icons/x';
+import { useState } from 'react';
+import { useContexts, useSaveContext, useUpdateTask } from '@/hooks/queries';
+import type { Task } from '@/types';
+
+interface TaskContextsProps {
+  task: Task;
+}
+
+/**
+ * the GTD contexts of a task (@home, @errands); unlike tags they only feed the next actions view
+ */
+export function TaskContexts({ task }: TaskContextsProps) {
+  const { data: contexts = [] } = useContexts();
+  const saveContextMutation = useSaveContext();
+  const updateTaskMutation = useUpdateTask();
+  const [input, setInput] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const taskContexts = task.contexts ?? [];
+  const has = (name: string) => taskContexts.some((c) => c.toLowerCase() === name.toLowerCase());
+
+  const query = input.trim().replace(/^@/, '').toLowerCase();
+  const suggestions = contexts.filter(
+    (c) => !has(c.name) && (!query || c.name.slice(1).toLowerCase().includes(query)),
+  );
+
+  const setContexts = (next: string[]) => {
+    updateTaskMutation.mutate({ id: task.id, updates: { contexts: next } });
+  };
+
+  const handleAdd = (name: string) => {
+    setError(null);
+    // new contexts are stored first so they get their canonical '@name'
+    saveContextMutation.mutate(
+      { name, color: contexts.find((c) => c.name.toLowerCase() === name.toLowerCase())?.color },
+      {
+        onSuccess: (stored) => {
+          if (!has(stored)) setContexts([...taskContexts, stored]);
+          setInput('');
+        },
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  return (
+    <div>
+      <label className="flex items-center gap-2 text-sm font-medium text-surface-600 dark:text-surface-400 mb-2">
+        <AtSign className="w-4 h-4" />
+        Contexts
+      </label>
+      <div className="flex flex-wrap items-center gap-2">
+        {taskContexts.map((name) => {
+          const color = contexts.find((c) => c.name.toLowerCase() === name.toLowerCase())?.color;
+          return (
+            <span
+              key={name}
+              className="inline-flex items-center gap-1 px-2 py-1 text-xs rounded-full border border-surface-200 dark:border-surface-600 text-surface-700 dark:text-surface-300 group"
+            >
+              {color && (
+                <span className="w-2 h-2 rounded-full" style={{ backgroundColor: color }} />
+              )}
+              {name}
+              <button
+                type="button"
+                onClick={() => setContexts(taskContexts.filter((c) => c !== name))}
+                className="text-surface-400 hover:text-red-500 dark:hover:text-red-400 transition-colors"
+                title="Remove context"
+              >
+                <X className="w-3 h-3" />
+              </button>
+            </span>
+          );
+        })}
+        <div className="relative flex items-center gap-1">
+          <Plus className="w-4 h-4 text-surface-400" />
+          <input
+            type="text"
+            value={input}
+            onChange={(e) => setInput(e.target.value)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter' && query) handleAdd(input.trim());
+            }}
+            placeholder="@context"
+            list={`contexts-${task.id}`}
+            className="w-28 px-1 py-1 text-sm text-surface-700 dark:text-surface-300 bg-transparent border-0 focus:outline-none focus:ring-0 placeholder:text-surface-400"
+          />
+          <datalist id={`contexts-${task.id}`}>
+            {suggestions.map((c) => (
+              <option key={c.name} value={c.name} />
+            ))}
+          </datalist>
+        </div>
+      </div>
+      {error && <p className="mt-1 text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  );
+}
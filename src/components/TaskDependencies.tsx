@@ -0,0 +1,137 @@
+import Link2 from 'lucide-react/icons/link-2';
+import Plus from 'lucide-react/icons/plus';
+import X from 'lucide-react/icons/x';
+import { useState } from 'react';
+import {
+  useAddDependency,
+  useRemoveDependency,
+  useSetSelectedTask,
+  useTaskDependencies,
+  useTasks,
+} from '@/hooks/queries';
+import type { Task } from '@/types';
+
+// suggestions shown while picking a task to wait for
+const MAX_SUGGESTIONS = 5;
+
+interface TaskDependenciesProps {
+  task: Task;
+}
+
+/**
+ * the tasks a task waits for (blocked by) and the ones waiting for it (blocks)
+ */
+export function TaskDependencies({ task }: TaskDependenciesProps) {
+  const { data: dependencies } = useTaskDependencies(task.uid);
+  const { data: tasks = [] } = useTasks();
+  const addDependencyMutation = useAddDependency();
+  const removeDependencyMutation = useRemoveDependency();
+  const setSelectedTaskMutation = useSetSelectedTask();
+  const [search, setSearch] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const blockedBy = dependencies?.blockedBy ?? [];
+  const blocks = dependencies?.blocks ?? [];
+
+  const query = search.trim().toLowerCase();
+  const suggestions = query
+    ? tasks
+        .filter(
+          (t) =>
+            t.uid !== task.uid &&
+            !t.completed &&
+            !blockedBy.some((b) => b.uid === t.uid) &&
+            t.title.toLowerCase().includes(query),
+        )
+        .slice(0, MAX_SUGGESTIONS)
+    : [];
+
+  const handleAdd = (dependsOn: Task) => {
+    setError(null);
+    addDependencyMutation.mutate(
+      { taskUid: task.uid, dependsOnUid: dependsOn.uid },
+      {
+        onSuccess: () => setSearch(''),
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  const renderTask = (other: Task, onRemove?: () => void) => (
+    <div
+      key={other.uid}
+      className="flex items-center gap-2 px-3 py-2 bg-surface-50 dark:bg-surface-700 rounded-lg group"
+    >
+      <button
+        type="button"
+        onClick={() => setSelectedTaskMutation.mutate(other.id)}
+        className={`flex-1 text-left text-sm truncate ${other.completed ? 'line-through text-surface-400' : 'text-surface-700 dark:text-surface-300'}`}
+      >
+        {other.title || 'Untitled task'}
+      </button>
+      {onRemove && (
+        <button
+          type="button"
+          onClick={onRemove}
+          className="p-1 text-surface-400 hover:text-red-500 dark:hover:text-red-400 opacity-0 group-hover:opacity-100 transition-all"
+          title="Stop waiting for this task"
+        >
+          <X className="w-4 h-4" />
+        </button>
+      )}
+    </div>
+  );
+
+  return (
+    <div>
+      <label className="flex items-center gap-2 text-sm font-medium text-surface-600 dark:text-surface-400 mb-2">
+        <Link2 className="w-4 h-4" />
+        Blocked by {blockedBy.length > 0 && `(${blockedBy.length})`}
+      </label>
+      <div className="space-y-2">
+        {blockedBy.map((other) =>
+          renderTask(other, () =>
+            removeDependencyMutation.mutate({ taskUid: task.uid, dependsOnUid: other.uid }),
+          ),
+        )}
+
+        <div className="relative">
+          <div className="flex items-center gap-2">
+            <Plus className="w-5 h-5 text-surface-400" />
+            <input
+              type="text"
+              value={search}
+              onChange={(e) => setSearch(e.target.value)}
+              placeholder="Wait for another task..."
+              className="flex-1 px-2 py-1 text-sm text-surface-700 dark:text-surface-300 bg-transparent border-0 focus:outline-none focus:ring-0 placeholder:text-surface-400"
+            />
+          </div>
+          {suggestions.length > 0 && (
+            <div className="mt-1 rounded-lg border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-800 shadow-sm py-1">
+              {suggestions.map((suggestion) => (
+                <button
+                  type="button"
+                  key={suggestion.id}
+                  onClick={() => handleAdd(suggestion)}
+                  className="w-full px-3 py-1.5 text-left text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 truncate"
+                >
+                  {suggestion.title}
+                </button>
+              ))}
+            </div>
+          )}
+        </div>
+        {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+      </div>
+
+      {blocks.length > 0 && (
+        <>
+          <p className="mt-3 mb-2 text-xs font-medium text-surface-500 dark:text-surface-400">
+            Waiting for this task
+          </p>
+          <div className="space-y-2">{blocks.map((other) => renderTask(other))}</div>
+        </>
+      )}
+    </div>
+  );
+}
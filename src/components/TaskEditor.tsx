@@ -6,9 +6,11 @@ import CheckCircle2 from 'lucide-react/icons/check-circle-2';
 import Clock from 'lucide-react/icons/clock';
 import Flag from 'lucide-react/icons/flag';
 import FolderSync from 'lucide-react/icons/folder-sync';
+import Hourglass from 'lucide-react/icons/hourglass';
 import Link from 'lucide-react/icons/link';
 import Pencil from 'lucide-react/icons/pencil';
 import Plus from 'lucide-react/icons/plus';
+import Repeat from 'lucide-react/icons/repeat';
 import Tag from 'lucide-react/icons/tag';
 import Trash2 from 'lucide-react/icons/trash-2';
 import X from 'lucide-react/icons/x';
@@ -21,10 +23,14 @@ import {
   useAddTagToTask,
   useCreateTask,
   useDeleteSubtask,
+  useDismissTaskNag,
   useRemoveReminder,
   useRemoveTagFromTask,
   useSetEditorOpen,
+  useSetTaskNag,
   useTags,
+  useTaskNag,
+  useTaskOccurrences,
   useToggleSubtaskComplete,
   useUpdateReminder,
   useUpdateSubtask,
@@ -33,16 +39,26 @@ import {
 import { useConfirmTaskDelete } from '@/hooks/useConfirmTaskDelete';
 import { useDebouncedTaskUpdate } from '@/hooks/useDebouncedTaskUpdate';
 import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
+import { fetchPageTitle } from '@/lib/database';
+import { createLogger } from '@/lib/logger';
 import * as taskData from '@/lib/taskData';
 import { useSettingsStore } from '@/store/settingsStore';
-import type { Priority, Task } from '@/types';
+import type { Priority, RelativeTrigger, Reminder, Task } from '@/types';
+import { reminderTrigger } from '@/utils/date';
+import { formatDuration } from '@/utils/format';
 import { filterCalDavDescription } from '@/utils/ical';
 import { getContrastTextColor } from '../utils/color';
 import { getIconByName } from './IconPicker';
 import { DatePickerModal } from './modals/DatePickerModal';
-import { ReminderPickerModal } from './modals/ReminderPickerModal';
+import { describeRelativeTrigger, ReminderPickerModal } from './modals/ReminderPickerModal';
 import { TagPickerModal } from './modals/TagPickerModal';
 import { SubtaskTreeItem } from './SubtaskTreeItem';
+import { TaskContexts } from './TaskContexts';
+import { TaskDependencies } from './TaskDependencies';
+import { TaskHttpActions } from './TaskHttpActions';
+import { TaskTimeTracking } from './TaskTimeTracking';
+
+const log = createLogger('TaskEditor', '#8b5cf6');
 
 interface TaskEditorProps {
   task: Task;
@@ -85,6 +101,12 @@ const priorities: {
   },
 ];
 
+// estimate presets, in seconds
+const estimates = [900, 1800, 3600, 7200, 14400, 28800];
+
+// how often a nagging reminder fires again, in minutes
+const NAG_INTERVALS = [5, 10, 15, 30, 60];
+
 export function TaskEditor({ task }: TaskEditorProps) {
   const updateTaskMutation = useUpdateTask();
   const setEditorOpenMutation = useSetEditorOpen();
@@ -94,6 +116,9 @@ export function TaskEditor({ task }: TaskEditorProps) {
   const addReminderMutation = useAddReminder();
   const removeReminderMutation = useRemoveReminder();
   const updateReminderMutation = useUpdateReminder();
+  const { data: nag } = useTaskNag(task.uid);
+  const setTaskNagMutation = useSetTaskNag();
+  const dismissTaskNagMutation = useDismissTaskNag();
   const updateSubtaskMutation = useUpdateSubtask();
   const deleteSubtaskMutation = useDeleteSubtask();
   const toggleSubtaskCompleteMutation = useToggleSubtaskComplete();
@@ -111,8 +136,10 @@ export function TaskEditor({ task }: TaskEditorProps) {
   const [showReminderPicker, setShowReminderPicker] = useState(false);
   const [editingReminderId, setEditingReminderId] = useState<string | null>(null);
   const [editReminderDate, setEditReminderDate] = useState<Date | undefined>(undefined);
+  const [editReminderRelative, setEditReminderRelative] = useState<RelativeTrigger>();
   const [showStartDatePicker, setShowStartDatePicker] = useState(false);
   const [showDueDatePicker, setShowDueDatePicker] = useState(false);
+  const { data: occurrences = [] } = useTaskOccurrences(task);
 
   // Debounced field updates
   const [pendingTitle, updatePendingTitle] = useDebouncedTaskUpdate(task.id, 'title', task.title);
@@ -211,24 +238,36 @@ export function TaskEditor({ task }: TaskEditorProps) {
     });
   };
 
+  // name an untitled task, or one titled with its own link, after the page it points to
+  const handleUrlBlur = () => {
+    const url = pendingUrl.trim();
+    const title = pendingTitle.trim();
+    if (!url || (title && title !== url)) return;
+    fetchPageTitle(url)
+      .then((pageTitle) => {
+        if (pageTitle) updatePendingTitle(pageTitle);
+      })
+      .catch((e) => log.debug('Failed to fetch the link title:', e));
+  };
+
   const handlePriorityChange = (priority: Priority) => {
     updateTaskMutation.mutate({ id: task.id, updates: { priority } });
   };
 
+  const handleEstimateChange = (value: string) => {
+    updateTaskMutation.mutate({
+      id: task.id,
+      updates: { estimatedDuration: value ? Number(value) : undefined },
+    });
+  };
+
   const handleCalendarChange = (calendarId: string) => {
     const targetCalendar = allCalendars.find((c) => c.id === calendarId);
     if (targetCalendar) {
-      // If this is a subtask and calendar is being changed, convert it to a regular task
-      const updates: any = {
-        calendarId: targetCalendar.id,
-        accountId: targetCalendar.accountId,
-      };
-
-      if (task.parentUid) {
-        updates.parentUid = undefined;
-      }
-
-      updateTaskMutation.mutate({ id: task.id, updates });
+      // the backend moves sub-tasks along and turns a moved subtask into a regular task
+      taskData.moveTask(task.id, targetCalendar.id).catch((error) => {
+        log.error('Failed to move task:', error);
+      });
     }
   };
 
@@ -251,28 +290,35 @@ export function TaskEditor({ task }: TaskEditorProps) {
     updateTaskMutation.mutate({ id: task.id, updates: { dueDateAllDay: allDay } });
   };
 
-  const handleAddReminder = (date: Date) => {
-    addReminderMutation.mutate({ taskId: task.id, trigger: date });
+  const handleAddReminder = (date: Date, relative?: RelativeTrigger) => {
+    addReminderMutation.mutate({ taskId: task.id, trigger: date, relative });
   };
 
   const handleRemoveReminder = (reminderId: string) => {
     removeReminderMutation.mutate({ taskId: task.id, reminderId });
   };
 
-  const handleUpdateReminder = (reminderId: string, trigger: Date) => {
-    updateReminderMutation.mutate({ taskId: task.id, reminderId, trigger });
+  const handleUpdateReminder = (reminderId: string, trigger: Date, relative?: RelativeTrigger) => {
+    updateReminderMutation.mutate({ taskId: task.id, reminderId, trigger, relative });
     setEditingReminderId(null);
     setEditReminderDate(undefined);
+    setEditReminderRelative(undefined);
   };
 
-  const handleStartEditReminder = (reminder: { id: string; trigger: Date }) => {
+  const handleStartEditReminder = (reminder: Reminder) => {
     setEditingReminderId(reminder.id);
-    setEditReminderDate(new Date(reminder.trigger));
+    setEditReminderDate(reminderTrigger(task, reminder));
+    setEditReminderRelative(
+      reminder.related && reminder.offset !== undefined
+        ? { related: reminder.related, offset: reminder.offset }
+        : undefined,
+    );
   };
 
   const handleCancelEditReminder = () => {
     setEditingReminderId(null);
     setEditReminderDate(undefined);
+    setEditReminderRelative(undefined);
   };
 
   const handleAddChildTask = () => {
@@ -367,6 +413,7 @@ export function TaskEditor({ task }: TaskEditorProps) {
               type="url"
               value={pendingUrl}
               onChange={handleUrlChange}
+              onBlur={handleUrlBlur}
               placeholder="https://example.com"
               className="flex-1 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 bg-surface-50 dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
             />
@@ -432,6 +479,17 @@ export function TaskEditor({ task }: TaskEditorProps) {
                   : 'Set due date...'}
               </span>
             </button>
+            {occurrences.length > 0 && (
+              <p className="mt-1.5 flex items-center gap-1.5 text-xs text-surface-500 dark:text-surface-400">
+                <Repeat className="w-3 h-3 flex-shrink-0" />
+                Repeats, next on{' '}
+                {occurrences
+                  .map((date) =>
+                    format(new Date(date), task.dueDateAllDay ? 'MMM d' : 'MMM d, h:mm a'),
+                  )
+                  .join(', ')}
+              </p>
+            )}
           </div>
         </div>
 
@@ -461,6 +519,30 @@ export function TaskEditor({ task }: TaskEditorProps) {
           </div>
         </div>
 
+        <div>
+          <label className="flex items-center gap-2 text-sm font-medium text-surface-600 dark:text-surface-400 mb-2">
+            <Hourglass className="w-4 h-4" />
+            Estimate
+          </label>
+          <select
+            value={task.estimatedDuration ?? ''}
+            onChange={(e) => handleEstimateChange(e.target.value)}
+            className="w-full px-3 py-2 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+          >
+            <option value="">No estimate</option>
+            {task.estimatedDuration && !estimates.includes(task.estimatedDuration) && (
+              <option value={task.estimatedDuration}>
+                {formatDuration(task.estimatedDuration)}
+              </option>
+            )}
+            {estimates.map((seconds) => (
+              <option key={seconds} value={seconds}>
+                {formatDuration(seconds)}
+              </option>
+            ))}
+          </select>
+        </div>
+
         <div>
           <label className="flex items-center gap-2 text-sm font-medium text-surface-600 dark:text-surface-400 mb-2">
             <FolderSync className="w-4 h-4" />
@@ -560,7 +642,12 @@ export function TaskEditor({ task }: TaskEditorProps) {
                 <div className="flex items-center gap-2 px-3 py-2 bg-surface-50 dark:bg-surface-700 rounded-lg group">
                   <Bell className="w-4 h-4 text-surface-400 flex-shrink-0" />
                   <span className="flex-1 text-sm text-surface-700 dark:text-surface-300">
-                    {format(new Date(reminder.trigger), 'MMM d, yyyy h:mm a')}
+                    {format(reminderTrigger(task, reminder), 'MMM d, yyyy h:mm a')}
+                    {reminder.related && (
+                      <span className="ml-1 text-xs text-surface-500 dark:text-surface-400">
+                        ({describeRelativeTrigger(reminder)})
+                      </span>
+                    )}
                   </span>
                   <button
                     type="button"
@@ -590,9 +677,47 @@ export function TaskEditor({ task }: TaskEditorProps) {
               <Plus className="w-3 h-3" />
               Add reminder
             </button>
+
+            <div className="flex items-center gap-2">
+              <select
+                value={nag?.intervalMinutes ?? 0}
+                onChange={(e) =>
+                  setTaskNagMutation.mutate({
+                    uid: task.uid,
+                    intervalMinutes: parseInt(e.target.value, 10) || null,
+                  })
+                }
+                title="Fire the reminder again until the task is completed"
+                className="px-2 py-1 text-xs bg-surface-100 dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded text-surface-700 dark:text-surface-300 focus:outline-none focus:border-primary-300"
+              >
+                <option value={0}>Remind once</option>
+                {NAG_INTERVALS.map((minutes) => (
+                  <option key={minutes} value={minutes}>
+                    Nag every {minutes} minutes
+                  </option>
+                ))}
+              </select>
+              {nag?.active && (
+                <button
+                  type="button"
+                  onClick={() => dismissTaskNagMutation.mutate(task.uid)}
+                  className="px-2 py-1 text-xs font-medium text-primary-600 dark:text-primary-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded transition-colors"
+                >
+                  Dismiss
+                </button>
+              )}
+            </div>
           </div>
         </div>
 
+        <TaskContexts task={task} />
+
+        <TaskTimeTracking task={task} />
+
+        <TaskDependencies task={task} />
+
+        <TaskHttpActions task={task} />
+
         <div>
           <div className="flex items-center justify-between mb-2">
             <label className="flex items-center gap-2 text-sm font-medium text-surface-600 dark:text-surface-400">
@@ -753,6 +878,8 @@ export function TaskEditor({ task }: TaskEditorProps) {
         <ReminderPickerModal
           isOpen={showReminderPicker}
           onClose={() => setShowReminderPicker(false)}
+          startDate={task.startDate}
+          dueDate={task.dueDate}
           onSave={handleAddReminder}
           title="Add Reminder"
         />
@@ -764,9 +891,12 @@ export function TaskEditor({ task }: TaskEditorProps) {
           isOpen={editingReminderId !== null}
           onClose={handleCancelEditReminder}
           value={editReminderDate}
-          onSave={(date) => {
+          relative={editReminderRelative}
+          startDate={task.startDate}
+          dueDate={task.dueDate}
+          onSave={(date, relative) => {
             if (editingReminderId) {
-              handleUpdateReminder(editingReminderId, date);
+              handleUpdateReminder(editingReminderId, date, relative);
             }
           }}
           title="Edit Reminder"
@@ -0,0 +1,50 @@
+import Plus from 'lucide-react/icons/plus';
+import Zap from 'lucide-react/icons/zap';
+import { useState } from 'react';
+import { useHttpActions } from '@/hooks/queries';
+import type { Task } from '@/types';
+import { HttpActionForm, HttpActionItem, newHttpAction } from './HttpActionForm';
+
+interface TaskHttpActionsProps {
+  task: Task;
+}
+
+/**
+ * requests sent when the task is completed, its own and those of its list
+ */
+export function TaskHttpActions({ task }: TaskHttpActionsProps) {
+  const { data: actions = [] } = useHttpActions();
+  const [adding, setAdding] = useState(false);
+
+  const own = actions.filter((a) => a.target === 'task' && a.targetId === task.uid);
+  const fromList = actions.filter((a) => a.target === 'list' && a.targetId === task.calendarId);
+
+  return (
+    <div>
+      <label className="flex items-center gap-2 text-sm font-medium text-surface-600 dark:text-surface-400 mb-2">
+        <Zap className="w-4 h-4" />
+        On Completion
+      </label>
+      <div className="space-y-2">
+        {own.map((action) => (
+          <HttpActionItem key={action.id} action={action} testTaskId={task.id} />
+        ))}
+        {fromList.map((action) => (
+          <HttpActionItem key={action.id} action={action} label="list" testTaskId={task.id} />
+        ))}
+        {adding ? (
+          <HttpActionForm draft={newHttpAction('task', task.uid)} onDone={() => setAdding(false)} />
+        ) : (
+          <button
+            type="button"
+            onClick={() => setAdding(true)}
+            className="flex items-center gap-2 px-3 py-2 text-sm text-surface-500 dark:text-surface-400 hover:text-surface-700 dark:hover:text-surface-200 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors"
+          >
+            <Plus className="w-4 h-4" />
+            Call a URL when completed
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -6,6 +6,7 @@ import CheckCircle2 from 'lucide-react/icons/check-circle-2';
 import ChevronDown from 'lucide-react/icons/chevron-down';
 import ChevronRight from 'lucide-react/icons/chevron-right';
 import Clock from 'lucide-react/icons/clock';
+import Copy from 'lucide-react/icons/copy';
 import Edit2 from 'lucide-react/icons/edit-2';
 import Link from 'lucide-react/icons/link';
 import Share2 from 'lucide-react/icons/share-2';
@@ -22,6 +23,7 @@ import {
 } from '@/hooks/queries';
 import { useConfirmTaskDelete } from '@/hooks/useConfirmTaskDelete';
 import { useContextMenu } from '@/hooks/useContextMenu';
+import { createLogger } from '@/lib/logger';
 import * as taskData from '@/lib/taskData';
 import { useSettingsStore } from '@/store/settingsStore';
 import type { Priority, Task } from '@/types';
@@ -32,6 +34,8 @@ import { pluralize } from '../utils/format';
 import { getIconByName } from './IconPicker';
 import { ExportModal } from './modals/ExportModal';
 
+const log = createLogger('TaskItem', '#f97316');
+
 interface TaskItemProps {
   task: Task;
   depth: number;
@@ -155,7 +159,17 @@ export function TaskItem({ task, depth, ancestorIds, isDragEnabled, isOverlay }:
     taskData.toggleTaskCollapsed(task.id);
   };
 
-  // todo: implement duplicate functionality at some point
+  const handleDuplicate = async () => {
+    setContextMenu(null);
+    try {
+      const copy = await taskData.duplicateTask(task.id);
+      if (copy) {
+        setSelectedTaskMutation.mutate(copy.id);
+      }
+    } catch (error) {
+      log.error('Failed to duplicate task:', error);
+    }
+  };
 
   // calculate left margin based on depth
   const marginLeft = depth * 24; // 24px per level
@@ -366,7 +380,14 @@ export function TaskItem({ task, depth, ancestorIds, isDragEnabled, isOverlay }:
               <Edit2 className="w-4 h-4" />
               Edit
             </button>
-            {/* todo: implement duplicate func. low priority rn */}
+            <button
+              type="button"
+              onClick={handleDuplicate}
+              className="w-full flex items-center gap-2 px-3 py-2 text-sm text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700"
+            >
+              <Copy className="w-4 h-4" />
+              Duplicate
+            </button>
             <button
               type="button"
               onClick={() => {
@@ -12,18 +12,19 @@ import {
 import { SortableContext, verticalListSortingStrategy } from '@dnd-kit/sortable';
 import ListTodo from 'lucide-react/icons/list-todo';
 import Plus from 'lucide-react/icons/plus';
-import { useCallback, useEffect, useMemo, useRef, useState } from 'react';
+import { useCallback, useEffect, useRef, useState } from 'react';
 import {
   useCreateTask,
-  useFilteredTasks,
   useReorderTasks,
   useSetSelectedTask,
   useUIState,
+  useVisibleTasks,
 } from '@/hooks/queries';
+import { useLoadMore } from '@/hooks/useLoadMore';
 import { createLogger } from '@/lib/logger';
-import * as taskData from '@/lib/taskData';
 import { getMetaKeyLabel, getModifierJoiner } from '../utils/keyboard';
-import { type FlattenedTask, flattenTasks } from '../utils/tree';
+import type { FlattenedTask } from '../utils/tree';
+import { QuickAddInput } from './QuickAddInput';
 import { TaskItem } from './TaskItem';
 
 const log = createLogger('TaskList', '#14b8a6');
@@ -39,14 +40,20 @@ const dropAnimation = null;
 
 export function TaskList() {
   const { data: uiState } = useUIState();
-  const { data: filteredTasksData = [] } = useFilteredTasks();
+  // the backend returns exactly the visible tasks, already sorted and flattened with depth info
+  const {
+    data: flattenedTasks = [],
+    hasNextPage,
+    isFetchingNextPage,
+    fetchNextPage,
+  } = useVisibleTasks();
+  const loadMoreRef = useLoadMore(hasNextPage, isFetchingNextPage, fetchNextPage);
   const createTaskMutation = useCreateTask();
   const setSelectedTaskMutation = useSetSelectedTask();
   const reorderTasksMutation = useReorderTasks();
 
   const sortConfig = uiState?.sortConfig ?? { mode: 'manual' as const, direction: 'asc' as const };
   const searchQuery = uiState?.searchQuery ?? '';
-  const showCompletedTasks = uiState?.showCompletedTasks ?? true;
 
   const [activeTask, setActiveTask] = useState<FlattenedTask | null>(null);
   const [targetIndent, setTargetIndent] = useState<number>(0);
@@ -56,39 +63,6 @@ export function TaskList() {
   const dragStartXRef = useRef<number>(0);
   const originalIndentRef = useRef<number>(0);
 
-  const filteredTasks = filteredTasksData;
-
-  // filter out child tasks - top level only for the root
-  const topLevelTasks = useMemo(
-    () => filteredTasks.filter((task) => !task.parentUid),
-    [filteredTasks],
-  );
-
-  const sortedTasks = useMemo(
-    () => taskData.getSortedTasks(topLevelTasks, sortConfig),
-    [topLevelTasks, sortConfig],
-  );
-
-  const getFilteredChildTasks = useCallback(
-    (parentUid: string) => {
-      const children = taskData.getChildTasks(parentUid);
-      if (!showCompletedTasks) {
-        return children.filter((task) => !task.completed);
-      }
-      return children;
-    },
-    [showCompletedTasks],
-  );
-
-  // flatten the tree into a single list with depth info
-  const flattenedTasks = useMemo(
-    () =>
-      flattenTasks(sortedTasks, getFilteredChildTasks, (tasks) =>
-        taskData.getSortedTasks(tasks, sortConfig),
-      ),
-    [sortedTasks, getFilteredChildTasks, sortConfig],
-  );
-
   // Clear active task if it no longer exists (e.g., was deleted during drag)
   useEffect(() => {
     if (activeTask && !flattenedTasks.find((t) => t.id === activeTask.id)) {
@@ -377,6 +351,7 @@ export function TaskList() {
               />
             ))}
           </div>
+          <div ref={loadMoreRef} />
         </SortableContext>
 
         <DragOverlay dropAnimation={dropAnimation}>
@@ -403,14 +378,7 @@ export function TaskList() {
         </DragOverlay>
       </DndContext>
 
-      <button
-        type="button"
-        onClick={handleQuickAdd}
-        className="mt-4 w-full flex items-center gap-3 p-3 text-surface-500 dark:text-surface-400 hover:text-surface-700 dark:hover:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors border-2 border-dashed border-surface-200 dark:border-surface-600 hover:border-surface-300 dark:hover:border-surface-500"
-      >
-        <Plus className="w-5 h-5" />
-        <span>Add a task...</span>
-      </button>
+      <QuickAddInput onAddBlank={handleQuickAdd} />
     </div>
   );
 }
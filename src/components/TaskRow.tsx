@@ -0,0 +1,85 @@
+import Check from 'lucide-react/icons/check';
+import Clock from 'lucide-react/icons/clock';
+import {
+  useAccounts,
+  useSetSelectedTask,
+  useToggleTaskComplete,
+  useUIState,
+} from '@/hooks/queries';
+import { useSettingsStore } from '@/store/settingsStore';
+import type { Task } from '@/types';
+import { formatDueDate } from '@/utils/date';
+import { getContrastTextColor } from '../utils/color';
+
+interface TaskRowProps {
+  task: Task;
+  // shown before the list name, e.g. what a task is waiting for
+  note?: string;
+}
+
+/**
+ * a compact, flat task row for the views outside the task list (agenda, board, next actions);
+ * clicking it opens the task in the editor
+ */
+export function TaskRow({ task, note }: TaskRowProps) {
+  const { data: uiState } = useUIState();
+  const { data: accounts = [] } = useAccounts();
+  const toggleTaskCompleteMutation = useToggleTaskComplete();
+  const setSelectedTaskMutation = useSetSelectedTask();
+  const { accentColor } = useSettingsStore();
+
+  const calendar = accounts.flatMap((a) => a.calendars).find((c) => c.id === task.calendarId);
+  const isSelected = uiState?.selectedTaskId === task.id;
+  const dueDateDisplay = task.dueDate ? formatDueDate(new Date(task.dueDate)) : null;
+
+  return (
+    <div
+      onClick={() => setSelectedTaskMutation.mutate(task.id)}
+      className={`group flex items-center gap-3 px-3 py-2 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700 shadow-sm hover:shadow-md cursor-pointer transition-all ${isSelected ? 'ring-2 ring-primary-100 dark:ring-primary-500/50' : ''} ${task.completed ? 'opacity-60' : ''}`}
+    >
+      <button
+        type="button"
+        onClick={(e) => {
+          e.stopPropagation();
+          toggleTaskCompleteMutation.mutate(task.id);
+        }}
+        className={`w-5 h-5 rounded border-2 flex items-center justify-center flex-shrink-0 transition-all ${
+          task.completed
+            ? 'bg-primary-500 border-primary-500'
+            : 'border-surface-300 dark:border-surface-600 hover:border-primary-400 hover:bg-primary-50 dark:hover:bg-primary-900/30'
+        }`}
+      >
+        {task.completed && (
+          <Check
+            className="w-4 h-4"
+            style={{ color: getContrastTextColor(accentColor) }}
+            strokeWidth={3}
+          />
+        )}
+      </button>
+
+      <div className="flex-1 min-w-0">
+        <div
+          className={`text-sm font-medium truncate ${task.completed ? 'line-through text-surface-400' : 'text-surface-800 dark:text-surface-200'}`}
+        >
+          {task.title || <span className="text-surface-400 italic">Untitled task</span>}
+        </div>
+        {(note || calendar) && (
+          <div className="text-xs text-surface-500 dark:text-surface-400 truncate">
+            {[note, calendar?.displayName].filter(Boolean).join(' · ')}
+          </div>
+        )}
+      </div>
+
+      {dueDateDisplay && (
+        <span
+          className="inline-flex items-center gap-1 px-2 py-0.5 rounded text-xs font-medium flex-shrink-0"
+          style={{ backgroundColor: dueDateDisplay.bgColor, color: dueDateDisplay.textColor }}
+        >
+          <Clock className="w-3 h-3" />
+          {dueDateDisplay.text}
+        </span>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,96 @@
+import { format } from 'date-fns';
+import Play from 'lucide-react/icons/play';
+import Square from 'lucide-react/icons/square';
+import Timer from 'lucide-react/icons/timer';
+import X from 'lucide-react/icons/x';
+import { useEffect, useState } from 'react';
+import {
+  useDeleteTimeEntry,
+  useRunningTimer,
+  useStartTimer,
+  useStopTimer,
+  useTimeEntries,
+} from '@/hooks/queries';
+import type { Task, TimeEntry } from '@/types';
+import { formatDuration } from '@/utils/format';
+
+interface TaskTimeTrackingProps {
+  task: Task;
+}
+
+// seconds of an entry; a running one counts up to now
+function entrySeconds(entry: TimeEntry, now: number): number {
+  const end = entry.endedAt ? new Date(entry.endedAt).getTime() : now;
+  return Math.max(0, (end - new Date(entry.startedAt).getTime()) / 1000);
+}
+
+/**
+ * the timer of a task and the time tracked for it so far
+ */
+export function TaskTimeTracking({ task }: TaskTimeTrackingProps) {
+  const { data: running } = useRunningTimer();
+  const { data: entries = [] } = useTimeEntries(task.uid);
+  const startTimerMutation = useStartTimer();
+  const stopTimerMutation = useStopTimer();
+  const deleteTimeEntryMutation = useDeleteTimeEntry();
+  const [now, setNow] = useState(Date.now());
+
+  const isRunning = running?.taskUid === task.uid;
+
+  // count up while this task's timer runs
+  useEffect(() => {
+    if (!isRunning) return;
+    const interval = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(interval);
+  }, [isRunning]);
+
+  const total = entries.reduce((sum, entry) => sum + entrySeconds(entry, now), 0);
+
+  return (
+    <div>
+      <label className="flex items-center gap-2 text-sm font-medium text-surface-600 dark:text-surface-400 mb-2">
+        <Timer className="w-4 h-4" />
+        Time Tracked {entries.length > 0 && `(${formatDuration(total)})`}
+      </label>
+      <div className="space-y-2">
+        {entries.map((entry) => (
+          <div
+            key={entry.id}
+            className="flex items-center gap-2 px-3 py-2 bg-surface-50 dark:bg-surface-700 rounded-lg group"
+          >
+            <span className="flex-1 text-sm text-surface-700 dark:text-surface-300">
+              {format(new Date(entry.startedAt), 'MMM d, h:mm a')}
+              {!entry.endedAt && (
+                <span className="ml-1 text-xs text-primary-600 dark:text-primary-400">
+                  (running)
+                </span>
+              )}
+            </span>
+            <span className="text-sm tabular-nums text-surface-500 dark:text-surface-400">
+              {formatDuration(entrySeconds(entry, now))}
+            </span>
+            <button
+              type="button"
+              onClick={() => deleteTimeEntryMutation.mutate(entry.id)}
+              className="p-1 text-surface-400 hover:text-red-500 dark:hover:text-red-400 opacity-0 group-hover:opacity-100 transition-all"
+              title="Delete entry"
+            >
+              <X className="w-4 h-4" />
+            </button>
+          </div>
+        ))}
+
+        <button
+          type="button"
+          onClick={() =>
+            isRunning ? stopTimerMutation.mutate() : startTimerMutation.mutate(task.uid)
+          }
+          className="inline-flex items-center gap-1 px-2 py-1 text-xs text-surface-500 dark:text-surface-400 border border-dashed border-surface-300 dark:border-surface-600 rounded-full hover:border-surface-400 dark:hover:border-surface-500 transition-colors"
+        >
+          {isRunning ? <Square className="w-3 h-3" /> : <Play className="w-3 h-3" />}
+          {isRunning ? 'Stop timer' : 'Start timer'}
+        </button>
+      </div>
+    </div>
+  );
+}
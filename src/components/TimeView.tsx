@@ -0,0 +1,122 @@
+import { addMonths, addWeeks, startOfMonth, startOfWeek } from 'date-fns';
+import Timer from 'lucide-react/icons/timer';
+import { useState } from 'react';
+import { useTimeTotals } from '@/hooks/queries';
+import type { TimeGrouping } from '@/lib/database';
+import { useSettingsStore } from '@/store/settingsStore';
+import { formatDuration } from '@/utils/format';
+
+type TimeRange = 'thisWeek' | 'lastWeek' | 'thisMonth' | 'all';
+
+const ranges: { value: TimeRange; label: string }[] = [
+  { value: 'thisWeek', label: 'This week' },
+  { value: 'lastWeek', label: 'Last week' },
+  { value: 'thisMonth', label: 'This month' },
+  { value: 'all', label: 'All time' },
+];
+
+const groupings: { value: TimeGrouping; label: string }[] = [
+  { value: 'task', label: 'Task' },
+  { value: 'tag', label: 'Tag' },
+  { value: 'day', label: 'Day' },
+];
+
+// [from, to) of a range as iso strings, unbounded for all time
+function rangeBounds(range: TimeRange, weekStartsOn: 0 | 1): { from?: string; to?: string } {
+  const thisWeek = startOfWeek(new Date(), { weekStartsOn });
+  switch (range) {
+    case 'thisWeek':
+      return { from: thisWeek.toISOString(), to: addWeeks(thisWeek, 1).toISOString() };
+    case 'lastWeek':
+      return { from: addWeeks(thisWeek, -1).toISOString(), to: thisWeek.toISOString() };
+    case 'thisMonth': {
+      const month = startOfMonth(new Date());
+      return { from: month.toISOString(), to: addMonths(month, 1).toISOString() };
+    }
+    case 'all':
+      return {};
+  }
+}
+
+/**
+ * tracked time per task, tag or day, e.g. for billing a week's work
+ */
+export function TimeView() {
+  const { startOfWeek: weekStart } = useSettingsStore();
+  const [range, setRange] = useState<TimeRange>('thisWeek');
+  const [groupBy, setGroupBy] = useState<TimeGrouping>('task');
+  const { from, to } = rangeBounds(range, weekStart === 'monday' ? 1 : 0);
+  const { data: totals = [], isLoading } = useTimeTotals(groupBy, from, to);
+
+  const total = totals.reduce((sum, t) => sum + t.seconds, 0);
+  const largest = Math.max(1, ...totals.map((t) => t.seconds));
+
+  return (
+    <div className="flex-1 flex flex-col min-h-0 overflow-y-auto p-4 overscroll-contain">
+      <div className="flex items-center gap-2 mb-4">
+        <Timer className="w-5 h-5 text-primary-600 dark:text-primary-400" />
+        <h2 className="flex-1 text-lg font-semibold text-surface-800 dark:text-surface-200">
+          Tracked Time
+        </h2>
+        <select
+          value={range}
+          onChange={(e) => setRange(e.target.value as TimeRange)}
+          className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+        >
+          {ranges.map((r) => (
+            <option key={r.value} value={r.value}>
+              {r.label}
+            </option>
+          ))}
+        </select>
+        <div className="flex rounded-lg border border-surface-200 dark:border-surface-600 overflow-hidden">
+          {groupings.map((g) => (
+            <button
+              type="button"
+              key={g.value}
+              onClick={() => setGroupBy(g.value)}
+              className={`px-3 py-1.5 text-sm transition-colors ${
+                groupBy === g.value
+                  ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300'
+                  : 'text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700'
+              }`}
+            >
+              {g.label}
+            </button>
+          ))}
+        </div>
+      </div>
+
+      {!isLoading && totals.length === 0 ? (
+        <p className="py-8 text-center text-sm text-surface-500 dark:text-surface-400">
+          No time tracked in this range. Start a timer from a task.
+        </p>
+      ) : (
+        <div className="space-y-1.5">
+          {totals.map((t) => (
+            <div
+              key={t.key}
+              className="relative flex items-center gap-3 px-3 py-2 bg-white dark:bg-surface-800 rounded-lg border border-surface-200 dark:border-surface-700 overflow-hidden"
+            >
+              <div
+                className="absolute inset-y-0 left-0 bg-primary-50 dark:bg-primary-900/30"
+                style={{ width: `${(t.seconds / largest) * 100}%` }}
+              />
+              <span className="relative flex-1 text-sm text-surface-800 dark:text-surface-200 truncate">
+                {t.label || 'Untitled task'}
+              </span>
+              <span className="relative text-sm tabular-nums text-surface-600 dark:text-surface-400">
+                {formatDuration(t.seconds)}
+              </span>
+            </div>
+          ))}
+          {totals.length > 0 && (
+            <div className="flex justify-end px-3 pt-2 text-sm font-medium text-surface-700 dark:text-surface-300">
+              {groupBy === 'tag' ? 'Tagged' : 'Total'}: {formatDuration(total)}
+            </div>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
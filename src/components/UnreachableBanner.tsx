@@ -0,0 +1,45 @@
+import { useUnreachableServers } from '@/hooks/queries';
+import type { UnreachableServer } from '@/lib/database';
+
+interface UnreachableBannerProps {
+  /** syncs right away instead of waiting for the backoff; absent while syncing isn't possible */
+  onRetry?: () => void;
+}
+
+function describe(servers: UnreachableServer[]) {
+  const [first] = servers;
+  if (servers.length > 1) {
+    return `Can't reach ${servers.length} servers. Scheduled syncs are retried less often.`;
+  }
+  const reason = first.reason === 'networkError' ? "Can't reach" : 'Server errors from';
+  if (!first.nextRetryAt) return `${reason} ${first.accountName}.`;
+  const retryAt = new Date(first.nextRetryAt).toLocaleTimeString([], {
+    hour: '2-digit',
+    minute: '2-digit',
+  });
+  return `${reason} ${first.accountName}. Retrying at ${retryAt}.`;
+}
+
+export function UnreachableBanner({ onRetry }: UnreachableBannerProps) {
+  const { data: servers = [] } = useUnreachableServers();
+
+  if (servers.length === 0) return null;
+
+  return (
+    <div
+      className="flex items-center justify-center gap-2 py-1 px-4 text-xs text-surface-600 dark:text-surface-300 bg-surface-100 dark:bg-surface-800 border-b border-surface-200 dark:border-surface-700"
+      title={servers.map((server) => `${server.accountName}: ${server.lastError ?? ''}`).join('\n')}
+    >
+      <span className="truncate">{describe(servers)}</span>
+      {onRetry && (
+        <button
+          type="button"
+          onClick={onRetry}
+          className="flex-shrink-0 font-medium text-primary-600 dark:text-primary-400 hover:underline"
+        >
+          Retry now
+        </button>
+      )}
+    </div>
+  );
+}
@@ -1,4 +1,8 @@
 import { useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+import { open } from '@tauri-apps/plugin-dialog';
+import { openUrl } from '@tauri-apps/plugin-opener';
+import ExternalLink from 'lucide-react/icons/external-link';
 import Info from 'lucide-react/icons/info';
 import Loader2 from 'lucide-react/icons/loader-2';
 import X from 'lucide-react/icons/x';
@@ -7,13 +11,41 @@ import { ComposedInput } from '@/components/ComposedInput';
 import { useAddCalendar, useCreateAccount, useUpdateAccount } from '@/hooks/queries';
 import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
 import { caldavService } from '@/lib/caldav';
+import { setAccountAuthMethod } from '@/lib/database';
+import { localFolderBackend } from '@/lib/localFolder';
 import { createLogger } from '@/lib/logger';
+import { syncBackendFor } from '@/lib/syncBackend';
 import * as taskData from '@/lib/taskData';
-import type { Account, Calendar, ServerType } from '@/types';
+import type { Account, Calendar, ServerType, SyncBackendType } from '@/types';
 import { generateTagColor } from '@/utils/color';
+import { foldText } from '@/utils/misc';
 
 const log = createLogger('Account', '#f97316');
 
+interface ConnectionTestResult {
+  success: boolean;
+  failedStage?: 'dns' | 'tls' | 'auth' | 'principal' | 'homeSet' | 'report';
+  status?: number;
+  message?: string;
+}
+
+interface ProviderPreset {
+  id: string;
+  name: string;
+  serverUrl: string | null; // null for self-hosted servers
+  serverType: ServerType;
+  authMethod: 'basic' | 'digest' | 'bearer';
+  emailUsername: boolean;
+  passwordHint: string | null;
+  passwordHelpUrl: string | null;
+}
+
+interface DetectedProvider {
+  provider: ProviderPreset;
+  serverUrl: string | null;
+  username: string | null;
+}
+
 interface AccountModalProps {
   account: Account | null;
   onClose: () => void;
@@ -25,14 +57,19 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
   const updateAccountMutation = useUpdateAccount();
   const addCalendarMutation = useAddCalendar();
 
+  const [backend, setBackend] = useState<SyncBackendType>(account?.backend ?? 'caldav');
   const [name, setName] = useState(account?.name || '');
   const [serverUrl, setServerUrl] = useState(account?.serverUrl || '');
   const [username, setUsername] = useState(account?.username || '');
   const [password, setPassword] = useState('');
   const [serverType, setServerType] = useState<ServerType>(account?.serverType ?? 'generic');
+  const [providers, setProviders] = useState<ProviderPreset[]>([]);
+  const [provider, setProvider] = useState<ProviderPreset | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState('');
   const nameInputRef = useRef<HTMLInputElement>(null);
+  // a folder of .ics files instead of a server; its path is kept as the server url
+  const isFolder = backend === 'localFolder';
 
   // handle ESC key to close modal
   useModalEscapeKey(onClose);
@@ -46,12 +83,54 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
     return () => clearTimeout(timer);
   }, []);
 
+  useEffect(() => {
+    if (account) return;
+    invoke<ProviderPreset[]>('get_provider_presets')
+      .then(setProviders)
+      .catch((error) => log.warn('Failed to load the provider presets:', error));
+  }, [account]);
+
+  const applyProvider = (preset: ProviderPreset | null) => {
+    setProvider(preset);
+    if (!preset) {
+      setServerType('generic');
+      return;
+    }
+    setServerType(preset.serverType);
+    if (preset.serverUrl) setServerUrl(preset.serverUrl);
+    if (!name.trim()) setName(preset.name);
+  };
+
+  /**
+   * pick the provider from what was typed so far: an email address or a server address
+   */
+  const detectProvider = async (input: string) => {
+    if (account || provider || !input.trim()) return;
+    try {
+      const detected = await invoke<DetectedProvider | null>('detect_provider', { input });
+      if (!detected) return;
+      log.info(`Detected ${detected.provider.name}`);
+      applyProvider(detected.provider);
+      if (detected.serverUrl) setServerUrl(detected.serverUrl);
+      if (detected.username && !username.trim()) setUsername(detected.username);
+    } catch (error) {
+      log.debug('Provider detection failed:', error);
+    }
+  };
+
+  const chooseFolder = async () => {
+    const folder = await open({ directory: true, multiple: false });
+    if (typeof folder !== 'string') return;
+    setServerUrl(folder);
+    if (!name.trim()) setName(folder.split(/[\\/]/).filter(Boolean).pop() ?? folder);
+  };
+
   /**
    * ensure a tag exists by name, returns the tag ID
    */
   const ensureTagExists = (tagName: string): string => {
     const currentTags = taskData.getAllTags();
-    const existing = currentTags.find((t) => t.name.toLowerCase() === tagName.toLowerCase());
+    const existing = currentTags.find((t) => foldText(t.name) === foldText(tagName));
 
     if (existing) {
       return existing.id;
@@ -69,7 +148,7 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
    */
   const fetchTasksForCalendar = async (accountId: string, calendar: Calendar) => {
     try {
-      const remoteTasks = await caldavService.fetchTasks(accountId, calendar);
+      const remoteTasks = await syncBackendFor({ backend }).fetchTasks(accountId, calendar);
       log.info(`Fetched ${remoteTasks.length} tasks from ${calendar.displayName}`);
 
       for (const remoteTask of remoteTasks) {
@@ -94,6 +173,39 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
     }
   };
 
+  /**
+   * add or change a local folder account: the folder has to be readable, and a new account
+   * starts out with the lists and tasks already in it
+   */
+  const submitFolder = async () => {
+    const id = account?.id ?? crypto.randomUUID();
+    log.debug(`Opening ${serverUrl}...`);
+    await localFolderBackend.connect(id, serverUrl);
+
+    if (account) {
+      updateAccountMutation.mutate({ id: account.id, updates: { name, serverUrl } });
+      return;
+    }
+
+    const calendars = await localFolderBackend.fetchCalendars(id);
+    log.info(`Found ${calendars.length} lists:`, calendars);
+    createAccountMutation.mutate(
+      { id, name, serverUrl, username: '', password: '', backend: 'localFolder' },
+      {
+        onSuccess: async (newAccount) => {
+          for (const calendar of calendars) {
+            addCalendarMutation.mutate({ accountId: newAccount.id, calendarData: calendar });
+          }
+          for (const calendar of calendars) {
+            await fetchTasksForCalendar(newAccount.id, calendar);
+          }
+          queryClient.invalidateQueries({ queryKey: ['tasks'] });
+          queryClient.invalidateQueries({ queryKey: ['tags'] });
+        },
+      },
+    );
+  };
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
     setError('');
@@ -102,7 +214,9 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
     try {
       const effectivePassword = password || account?.password;
 
-      if (account) {
+      if (isFolder) {
+        await submitFolder();
+      } else if (account) {
         // update existing account
         if (effectivePassword) {
           // test connection with new credentials before saving
@@ -113,6 +227,7 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
             username,
             effectivePassword,
             serverType,
+            account.tls,
           );
         }
 
@@ -136,7 +251,16 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
         const tempId = crypto.randomUUID();
 
         log.debug(`Connecting to ${serverUrl}...`);
-        await caldavService.connect(tempId, serverUrl, username, effectivePassword, serverType);
+        await caldavService.connect(
+          tempId,
+          serverUrl,
+          username,
+          effectivePassword,
+          serverType,
+          undefined,
+          undefined,
+          provider?.authMethod,
+        );
 
         log.debug(`Fetching calendars...`);
         const calendars = await caldavService.fetchCalendars(tempId);
@@ -154,6 +278,14 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
           },
           {
             onSuccess: async (newAccount) => {
+              // the connection may have negotiated the method before the account existed
+              const authMethod = caldavService.getAuthMethod(newAccount.id);
+              if (authMethod) {
+                setAccountAuthMethod(newAccount.id, authMethod).catch((error) =>
+                  log.warn('Failed to remember the auth method:', error),
+                );
+              }
+
               // add the fetched calendars
               for (const calendar of calendars) {
                 addCalendarMutation.mutate({ accountId: newAccount.id, calendarData: calendar });
@@ -175,8 +307,31 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
 
       onClose();
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to connect to CalDAV server');
       log.error('Failed to connect:', err);
+
+      // run the step-by-step test to tell the user which stage actually failed
+      const effectivePassword = password || account?.password;
+      const diagnosis =
+        effectivePassword && !isFolder
+          ? await invoke<ConnectionTestResult>('test_connection', {
+              account: {
+                serverUrl,
+                username,
+                password: effectivePassword,
+                serverType,
+                tls: account?.tls,
+              },
+            }).catch(() => null)
+          : null;
+
+      if (diagnosis && !diagnosis.success && diagnosis.message) {
+        setError(diagnosis.message);
+      } else if (isFolder) {
+        // the backend's errors arrive as plain strings
+        setError(String(err instanceof Error ? err.message : err));
+      } else {
+        setError(err instanceof Error ? err.message : 'Failed to connect to CalDAV server');
+      }
     } finally {
       setIsLoading(false);
     }
@@ -190,7 +345,7 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
       >
         <div className="flex items-center justify-between p-4 border-b border-surface-200 dark:border-surface-700">
           <h2 className="text-lg font-semibold text-surface-800 dark:text-surface-200">
-            {account ? 'Edit Account' : 'Add CalDAV Account'}
+            {account ? 'Edit Account' : isFolder ? 'Add Local Folder' : 'Add CalDAV Account'}
           </h2>
           <button
             type="button"
@@ -202,6 +357,53 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
         </div>
 
         <form onSubmit={handleSubmit} className="p-4 space-y-4">
+          {!account && (
+            <div>
+              <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                Sync With
+              </label>
+              <select
+                value={backend}
+                onChange={(e) => {
+                  setBackend(e.target.value as SyncBackendType);
+                  setServerUrl('');
+                }}
+                className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+              >
+                <option value="caldav">CalDAV server</option>
+                <option value="localFolder">Local folder</option>
+              </select>
+              {isFolder && (
+                <p className="mt-1 text-xs text-surface-500 dark:text-surface-400">
+                  One .ics file per task, in a subfolder per list. Sync the folder with
+                  Syncthing, git or any other tool.
+                </p>
+              )}
+            </div>
+          )}
+
+          {!account && !isFolder && providers.length > 0 && (
+            <div>
+              <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                Provider
+              </label>
+              <select
+                value={provider?.id ?? ''}
+                onChange={(e) =>
+                  applyProvider(providers.find((p) => p.id === e.target.value) ?? null)
+                }
+                className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+              >
+                <option value="">Other CalDAV server</option>
+                {providers.map((preset) => (
+                  <option key={preset.id} value={preset.id}>
+                    {preset.name}
+                  </option>
+                ))}
+              </select>
+            </div>
+          )}
+
           <div>
             <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
               Account Display Name
@@ -211,83 +413,137 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
               type="text"
               value={name}
               onChange={setName}
-              placeholder="My CalDAV Account"
+              placeholder={isFolder ? 'My Tasks' : 'My CalDAV Account'}
               required
               className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
             />
           </div>
 
-          <div>
-            <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
-              Server Type
-            </label>
-            <select
-              value={serverType}
-              onChange={(e) => setServerType(e.target.value as ServerType)}
-              className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
-            >
-              <option value="generic">Generic (auto-detect)</option>
-              <option value="nextcloud">Nextcloud</option>
-              <option value="rustical">RustiCal</option>
-              <option value="radicale">Radicale</option>
-              <option value="baikal">Baikal</option>
-            </select>
-            <p className="mt-1 text-xs text-surface-500 dark:text-surface-400">
-              {serverType === 'rustical' && 'Uses /caldav/principal/{username}/ path structure'}
-              {serverType === 'radicale' && 'Uses /{username}/ path structure'}
-              {serverType === 'baikal' && 'Uses /dav.php/principals/{username}/ path structure'}
-              {serverType === 'nextcloud' && 'Uses /remote.php/dav/ path structure'}
-              {serverType === 'generic' && 'Uses .well-known/caldav. Good enough for most servers.'}
-            </p>
-          </div>
-
-          <div>
-            <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
-              Server URL
-            </label>
-            <ComposedInput
-              type="url"
-              value={serverUrl}
-              onChange={setServerUrl}
-              placeholder="https://caldav.example.com"
-              required
-              className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
-            />
-            {serverType === 'generic' && (
-              <p className="mt-2 text-xs flex flex-row text-surface-500 dark:text-surface-400">
-                <Info className="inline w-3.5 h-3.5 mr-1 text-surface-400" />
-                The app will attempt to auto-discover for base URLs.
-              </p>
+          {isFolder ? (
+            <div>
+              <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                Folder
+              </label>
+              <div className="flex gap-2">
+                <ComposedInput
+                  type="text"
+                  value={serverUrl}
+                  onChange={setServerUrl}
+                  placeholder="/home/me/Sync/tasks"
+                  required
+                  className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+                />
+                <button
+                  type="button"
+                  onClick={() =>
+                    chooseFolder().catch((error) => log.error('Failed to pick a folder:', error))
+                  }
+                  className="px-3 py-2 text-sm font-medium text-surface-600 dark:text-surface-400 hover:text-surface-800 dark:hover:text-surface-200 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors shrink-0"
+                >
+                  Choose...
+                </button>
+              </div>
+            </div>
+          ) : (
+            <>
+            {!provider && (
+              <div>
+                <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                  Server Type
+                </label>
+                <select
+                  value={serverType}
+                  onChange={(e) => setServerType(e.target.value as ServerType)}
+                  className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+                >
+                  <option value="generic">Generic (auto-detect)</option>
+                  <option value="nextcloud">Nextcloud</option>
+                  <option value="rustical">RustiCal</option>
+                  <option value="radicale">Radicale</option>
+                  <option value="baikal">Baikal</option>
+                </select>
+                <p className="mt-1 text-xs text-surface-500 dark:text-surface-400">
+                  {serverType === 'rustical' && 'Uses /caldav/principal/{username}/ path structure'}
+                  {serverType === 'radicale' && 'Uses /{username}/ path structure'}
+                  {serverType === 'baikal' && 'Uses /dav.php/principals/{username}/ path structure'}
+                  {serverType === 'nextcloud' && 'Uses /remote.php/dav/ path structure'}
+                  {serverType === 'generic' &&
+                    'Uses .well-known/caldav. Good enough for most servers.'}
+                </p>
+              </div>
             )}
-          </div>
 
-          <div>
-            <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
-              Username
-            </label>
-            <ComposedInput
-              type="text"
-              value={username}
-              onChange={setUsername}
-              placeholder="user@example.com"
-              required
-              className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
-            />
-          </div>
+            <div>
+              <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                Server URL
+              </label>
+              <ComposedInput
+                type="url"
+                value={serverUrl}
+                onChange={setServerUrl}
+                onBlur={() => detectProvider(serverUrl)}
+                placeholder="https://caldav.example.com"
+                required
+                className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+              />
+              {serverType === 'generic' && (
+                <p className="mt-2 text-xs flex flex-row text-surface-500 dark:text-surface-400">
+                  <Info className="inline w-3.5 h-3.5 mr-1 text-surface-400" />
+                  The app will attempt to auto-discover for base URLs.
+                </p>
+              )}
+            </div>
 
-          <div>
-            <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
-              Password
-            </label>
-            <ComposedInput
-              type="password"
-              value={password}
-              onChange={setPassword}
-              placeholder={account ? '(unchanged)' : 'Enter password'}
-              required={!account}
-              className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
-            />
-          </div>
+            <div>
+              <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                Username
+              </label>
+              <ComposedInput
+                type="text"
+                value={username}
+                onChange={setUsername}
+                onBlur={() => detectProvider(username)}
+                placeholder="user@example.com"
+                required
+                className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+              />
+            </div>
+
+            <div>
+              <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                Password
+              </label>
+              <ComposedInput
+                type="password"
+                value={password}
+                onChange={setPassword}
+                placeholder={account ? '(unchanged)' : 'Enter password'}
+                required={!account}
+                className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
+              />
+              {provider?.passwordHint && (
+                <p className="mt-2 text-xs flex flex-row text-surface-500 dark:text-surface-400">
+                  <Info className="inline shrink-0 w-3.5 h-3.5 mr-1 text-surface-400" />
+                  <span>
+                    {provider.passwordHint}
+                    {provider.passwordHelpUrl && (
+                      <button
+                        type="button"
+                        onClick={() =>
+                          provider.passwordHelpUrl && openUrl(provider.passwordHelpUrl)
+                        }
+                        className="ml-1 inline-flex items-center gap-0.5 text-primary-600 dark:text-primary-400 hover:underline"
+                      >
+                        Open
+                        <ExternalLink className="w-3 h-3" />
+                      </button>
+                    )}
+                  </span>
+                </p>
+              )}
+            </div>
+            </>
+          )}
 
           {error && (
             <div className="p-3 text-sm text-red-600 dark:text-red-400 bg-red-50 dark:bg-red-900/30 border border-red-200 dark:border-red-800 rounded-lg">
@@ -309,8 +565,7 @@ export function AccountModal({ account, onClose }: AccountModalProps) {
                 isLoading ||
                 !name.trim() ||
                 !serverUrl.trim() ||
-                !username.trim() ||
-                (!account && !password.trim())
+                (!isFolder && (!username.trim() || (!account && !password.trim())))
               }
               className="px-4 py-2 text-sm font-medium text-white bg-primary-600 hover:bg-primary-700 rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2"
             >
@@ -4,7 +4,8 @@ import { useState } from 'react';
 import { ComposedInput } from '@/components/ComposedInput';
 import { useAccounts, useUpdateAccount } from '@/hooks/queries';
 import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
-import { caldavService } from '@/lib/caldav';
+import { syncBackendFor } from '@/lib/syncBackend';
+import * as taskData from '@/lib/taskData';
 import type { Calendar } from '@/types';
 import { COLOR_PRESETS } from '@/utils/constants';
 import { IconPicker } from '../IconPicker';
@@ -22,6 +23,7 @@ export function CalendarModal({ calendar, accountId, onClose }: CalendarModalPro
   const [displayName, setDisplayName] = useState(calendar.displayName);
   const [color, setColor] = useState(calendar.color || '#3b82f6');
   const [icon, setIcon] = useState(calendar.icon || 'calendar');
+  const [checklistAsTasks, setChecklistAsTasks] = useState(calendar.checklistAsTasks ?? false);
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState('');
   const [warning, setWarning] = useState('');
@@ -35,6 +37,8 @@ export function CalendarModal({ calendar, accountId, onClose }: CalendarModalPro
     setIsLoading(true);
 
     try {
+      const backend = syncBackendFor(accounts.find((a) => a.id === accountId));
+
       // only send properties that have actually changed (to server)
       const serverUpdates: { displayName?: string; color?: string } = {};
 
@@ -46,11 +50,12 @@ export function CalendarModal({ calendar, accountId, onClose }: CalendarModalPro
         serverUpdates.color = color;
       }
 
-      // track local-only changes (icon is stored locally only)
+      // track local-only changes (icon and checklist option are stored locally only)
       const iconChanged = icon !== calendar.icon;
+      const checklistChanged = checklistAsTasks !== (calendar.checklistAsTasks ?? false);
 
       // if nothing changed at all, just close the modal
-      if (Object.keys(serverUpdates).length === 0 && !iconChanged) {
+      if (Object.keys(serverUpdates).length === 0 && !iconChanged && !checklistChanged) {
         onClose();
         return;
       }
@@ -60,7 +65,7 @@ export function CalendarModal({ calendar, accountId, onClose }: CalendarModalPro
       // only call server if server properties changed
       if (Object.keys(serverUpdates).length > 0) {
         // update calendar on server via PROPPATCH
-        result = await caldavService.updateCalendar(accountId, calendar.url, serverUpdates);
+        result = await backend.updateCalendar(accountId, calendar.url, serverUpdates);
       }
 
       // update local state (only update what succeeded + local-only fields)
@@ -77,8 +82,9 @@ export function CalendarModal({ calendar, accountId, onClose }: CalendarModalPro
             if (!result.failedProperties.includes('calendar-color')) {
               updates.color = color;
             }
-            // icon is always updated locally (not stored on server)
+            // icon and checklist option are always updated locally (not stored on server)
             updates.icon = icon;
+            updates.checklistAsTasks = checklistAsTasks;
             return { ...c, ...updates };
           }
           return c;
@@ -86,6 +92,23 @@ export function CalendarModal({ calendar, accountId, onClose }: CalendarModalPro
         updateAccountMutation.mutate({ id: accountId, updates: { calendars: updatedCalendars } });
       }
 
+      if (checklistChanged) {
+        // upload the checklists again in their new form; items that were tasks are removed
+        // so they don't show up twice
+        for (const task of taskData.getTasksByCalendar(calendar.id)) {
+          if (task.subtasks.length === 0) continue;
+          taskData.updateTask(task.id, { synced: false });
+          if (!checklistAsTasks && task.href) {
+            await backend.deleteChecklistItems(
+              accountId,
+              calendar,
+              task.uid,
+              task.subtasks.map((item) => item.id),
+            );
+          }
+        }
+      }
+
       // show warning if some properties failed
       if (result.failedProperties.length > 0) {
         const failedNames = result.failedProperties
@@ -168,6 +191,23 @@ export function CalendarModal({ calendar, accountId, onClose }: CalendarModalPro
             </div>
           </div>
 
+          <label className="flex items-center justify-between gap-4">
+            <div>
+              <span className="text-sm text-surface-700 dark:text-surface-300">
+                Sync checklist items as tasks
+              </span>
+              <p className="text-xs text-surface-500 dark:text-surface-400">
+                Upload each checklist item as a sub-task so other apps can show it
+              </p>
+            </div>
+            <input
+              type="checkbox"
+              checked={checklistAsTasks}
+              onChange={(e) => setChecklistAsTasks(e.target.checked)}
+              className="rounded border-surface-300 dark:border-surface-600"
+            />
+          </label>
+
           {warning && (
             <div className="p-3 text-sm text-amber-600 dark:text-amber-400 bg-amber-50 dark:bg-amber-900/30 border border-amber-200 dark:border-amber-800 rounded-lg">
               {warning}
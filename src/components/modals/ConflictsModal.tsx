@@ -0,0 +1,134 @@
+import { formatDistanceToNow } from 'date-fns';
+import X from 'lucide-react/icons/x';
+import { useResolveSyncConflict, useSyncConflicts } from '@/hooks/queries';
+import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
+import type { SyncConflict } from '@/lib/database';
+import type { Task } from '@/types';
+
+interface ConflictsModalProps {
+  onClose: () => void;
+}
+
+// the fields worth comparing, with how to show them
+const fields: { label: string; value: (task: Task) => string }[] = [
+  { label: 'Title', value: (task) => task.title },
+  { label: 'Notes', value: (task) => task.description },
+  { label: 'Status', value: (task) => (task.completed ? 'Completed' : 'Open') },
+  { label: 'Priority', value: (task) => task.priority },
+  {
+    label: 'Due',
+    value: (task) => (task.dueDate ? new Date(task.dueDate).toLocaleString() : ''),
+  },
+];
+
+function Differences({ conflict }: { conflict: SyncConflict }) {
+  const { local, remote } = conflict;
+  if (!remote) {
+    return (
+      <p className="text-xs text-surface-500 dark:text-surface-400">
+        Deleted on the server after it was last synced.
+      </p>
+    );
+  }
+
+  const changed = fields.filter((field) => field.value(local) !== field.value(remote));
+  if (changed.length === 0) {
+    return (
+      <p className="text-xs text-surface-500 dark:text-surface-400">
+        Both versions differ only in details like tags or checklist items.
+      </p>
+    );
+  }
+  return (
+    <table className="w-full text-xs">
+      <thead>
+        <tr className="text-left text-surface-500 dark:text-surface-400">
+          <th className="font-medium pr-2" />
+          <th className="font-medium pr-2">Here</th>
+          <th className="font-medium">On the server</th>
+        </tr>
+      </thead>
+      <tbody className="text-surface-700 dark:text-surface-300">
+        {changed.map((field) => (
+          <tr key={field.label} className="align-top">
+            <td className="pr-2 text-surface-500 dark:text-surface-400">{field.label}</td>
+            <td className="pr-2 break-words">{field.value(local) || '—'}</td>
+            <td className="break-words">{field.value(remote) || '—'}</td>
+          </tr>
+        ))}
+      </tbody>
+    </table>
+  );
+}
+
+export function ConflictsModal({ onClose }: ConflictsModalProps) {
+  const { data: conflicts = [] } = useSyncConflicts();
+  const resolveMutation = useResolveSyncConflict();
+
+  useModalEscapeKey(onClose);
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 animate-fade-in">
+      <div
+        className="bg-white dark:bg-surface-800 rounded-xl shadow-xl w-full max-w-lg animate-scale-in"
+        onClick={(e) => e.stopPropagation()}
+      >
+        <div className="flex items-center justify-between p-4 border-b border-surface-200 dark:border-surface-700">
+          <h2 className="text-lg font-semibold text-surface-800 dark:text-surface-200">
+            Sync Conflicts
+          </h2>
+          <button
+            type="button"
+            onClick={onClose}
+            className="p-2 text-surface-500 hover:text-surface-700 dark:hover:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors"
+          >
+            <X className="w-5 h-5" />
+          </button>
+        </div>
+
+        <div className="p-4 max-h-[60vh] overflow-y-auto space-y-3">
+          {conflicts.length === 0 ? (
+            <div className="p-4 text-center text-sm text-surface-500 dark:text-surface-400">
+              No conflicts to review.
+            </div>
+          ) : (
+            conflicts.map((conflict) => (
+              <div
+                key={conflict.id}
+                className="p-3 space-y-2 border border-surface-200 dark:border-surface-700 rounded-lg"
+              >
+                <div className="flex items-baseline justify-between gap-2">
+                  <span className="text-sm font-medium text-surface-800 dark:text-surface-200 truncate">
+                    {conflict.title}
+                  </span>
+                  <span className="text-xs text-surface-400 flex-shrink-0">
+                    {formatDistanceToNow(new Date(conflict.detectedAt), { addSuffix: true })}
+                  </span>
+                </div>
+                <Differences conflict={conflict} />
+                <div className="flex justify-end gap-2">
+                  <button
+                    type="button"
+                    disabled={resolveMutation.isPending}
+                    onClick={() => resolveMutation.mutate({ conflict, resolution: 'keepRemote' })}
+                    className="px-3 py-1.5 text-xs font-medium text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors disabled:opacity-50"
+                  >
+                    {conflict.remote ? 'Keep server version' : 'Delete here too'}
+                  </button>
+                  <button
+                    type="button"
+                    disabled={resolveMutation.isPending}
+                    onClick={() => resolveMutation.mutate({ conflict, resolution: 'keepLocal' })}
+                    className="px-3 py-1.5 text-xs font-medium text-white bg-primary-600 hover:bg-primary-700 rounded-lg transition-colors disabled:opacity-50"
+                  >
+                    Keep my version
+                  </button>
+                </div>
+              </div>
+            ))
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
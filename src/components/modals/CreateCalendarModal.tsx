@@ -4,7 +4,8 @@ import { useEffect, useRef, useState } from 'react';
 import { ComposedInput } from '@/components/ComposedInput';
 import { useAddCalendar } from '@/hooks/queries';
 import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
-import { caldavService } from '@/lib/caldav';
+import { syncBackendFor } from '@/lib/syncBackend';
+import * as taskData from '@/lib/taskData';
 import { useSettingsStore } from '@/store/settingsStore';
 import { COLOR_PRESETS } from '@/utils/constants';
 import { IconPicker } from '../IconPicker';
@@ -44,7 +45,8 @@ export function CreateCalendarModal({ accountId, onClose }: CreateCalendarModalP
 
     try {
       // create calendar on server
-      const calendar = await caldavService.createCalendar(accountId, displayName, color);
+      const backend = syncBackendFor(taskData.getAccountById(accountId));
+      const calendar = await backend.createCalendar(accountId, displayName, color);
 
       // add to local store with icon
       addCalendarMutation.mutate({ accountId, calendarData: { ...calendar, icon } });
@@ -0,0 +1,128 @@
+import { formatDistanceToNow } from 'date-fns';
+import X from 'lucide-react/icons/x';
+import { useDiscardPendingChange, usePendingChanges } from '@/hooks/queries';
+import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
+import type { PendingChange } from '@/lib/database';
+
+interface PendingChangesModalProps {
+  onClose: () => void;
+  /** starts a sync, absent when syncing isn't possible right now */
+  onRetry?: () => void;
+}
+
+const operationLabels: Record<PendingChange['operation'], string> = {
+  create: 'New',
+  update: 'Edited',
+  delete: 'Deleted',
+};
+
+const blockedLabels: Record<NonNullable<PendingChange['blocked']>, string> = {
+  accountPaused: 'Waiting: the account is paused',
+  conflict: 'Waiting: in the conflict review',
+  authFailed: 'Waiting: the server rejects the sign-in',
+};
+
+function discardLabel(change: PendingChange) {
+  switch (change.operation) {
+    case 'create':
+      return 'Delete';
+    case 'update':
+      return 'Use server version';
+    case 'delete':
+      return 'Restore';
+  }
+}
+
+export function PendingChangesModal({ onClose, onRetry }: PendingChangesModalProps) {
+  const { data: changes = [] } = usePendingChanges();
+  const discardMutation = useDiscardPendingChange();
+
+  useModalEscapeKey(onClose);
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 animate-fade-in">
+      <div
+        className="bg-white dark:bg-surface-800 rounded-xl shadow-xl w-full max-w-lg animate-scale-in"
+        onClick={(e) => e.stopPropagation()}
+      >
+        <div className="flex items-center justify-between p-4 border-b border-surface-200 dark:border-surface-700">
+          <h2 className="text-lg font-semibold text-surface-800 dark:text-surface-200">
+            Unsynced Changes
+          </h2>
+          <button
+            type="button"
+            onClick={onClose}
+            className="p-2 text-surface-500 hover:text-surface-700 dark:hover:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors"
+          >
+            <X className="w-5 h-5" />
+          </button>
+        </div>
+
+        <div className="p-4 max-h-[60vh] overflow-y-auto space-y-3">
+          {changes.length === 0 ? (
+            <div className="p-4 text-center text-sm text-surface-500 dark:text-surface-400">
+              Everything is on the server.
+            </div>
+          ) : (
+            changes.map((change) => (
+              <div
+                key={`${change.kind}-${change.id}`}
+                className="p-3 space-y-1 border border-surface-200 dark:border-surface-700 rounded-lg"
+              >
+                <div className="flex items-baseline justify-between gap-2">
+                  <span className="text-sm font-medium text-surface-800 dark:text-surface-200 truncate">
+                    <span className="text-surface-500 dark:text-surface-400">
+                      {operationLabels[change.operation]}:{' '}
+                    </span>
+                    {change.title ?? change.taskUid}
+                  </span>
+                  {change.modifiedAt && (
+                    <span className="text-xs text-surface-400 flex-shrink-0">
+                      {formatDistanceToNow(new Date(change.modifiedAt), { addSuffix: true })}
+                    </span>
+                  )}
+                </div>
+                <p className="text-xs text-surface-500 dark:text-surface-400 truncate">
+                  {[change.accountName, change.calendarName].filter(Boolean).join(' · ')}
+                </p>
+                {change.blocked && (
+                  <p className="text-xs text-amber-600 dark:text-amber-400">
+                    {blockedLabels[change.blocked]}
+                  </p>
+                )}
+                {change.lastError && (
+                  <p className="text-xs text-red-600 dark:text-red-400 break-words">
+                    {change.lastError}
+                    {change.attempts > 1 && ` (${change.attempts} attempts)`}
+                  </p>
+                )}
+                <div className="flex justify-end gap-2 pt-1">
+                  <button
+                    type="button"
+                    disabled={discardMutation.isPending}
+                    onClick={() => discardMutation.mutate(change)}
+                    className="px-3 py-1.5 text-xs font-medium text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors disabled:opacity-50"
+                  >
+                    {discardLabel(change)}
+                  </button>
+                </div>
+              </div>
+            ))
+          )}
+        </div>
+
+        {onRetry && changes.length > 0 && (
+          <div className="flex justify-end p-4 border-t border-surface-200 dark:border-surface-700">
+            <button
+              type="button"
+              onClick={onRetry}
+              className="px-4 py-2 text-sm font-medium text-white bg-primary-600 hover:bg-primary-700 rounded-lg transition-colors"
+            >
+              Retry now
+            </button>
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,234 @@
+import Search from 'lucide-react/icons/search';
+import { useEffect, useRef, useState } from 'react';
+import {
+  useAccounts,
+  useFuzzyFind,
+  useGlobalSearch,
+  useSetActiveAccount,
+  useSetActiveCalendar,
+  useSetSelectedTask,
+} from '@/hooks/queries';
+import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
+import type { FuzzyMatch, SearchHit } from '@/lib/database';
+import { formatDueDate } from '@/utils/date';
+
+// typo-tolerant matches asked for next to the ranked search
+const FUZZY_LIMIT = 8;
+
+interface QuickOpenModalProps {
+  onClose: () => void;
+  // a list was picked; the task list should show it
+  onOpenList: () => void;
+}
+
+// where a hit matched, when it isn't the title
+const matchedLabels: Record<SearchHit['matched'], string | null> = {
+  title: null,
+  tag: 'tag',
+  description: 'notes',
+};
+
+// the title with the characters the fuzzy matcher picked in bold
+function Highlighted({ match }: { match: FuzzyMatch }) {
+  const positions = new Set(match.positions);
+  return (
+    <>
+      {Array.from(match.title).map((char, i) => (
+        // biome-ignore lint/suspicious/noArrayIndexKey: characters repeat, their position doesn't
+        <span key={i} className={positions.has(i) ? 'font-semibold text-primary-600' : ''}>
+          {char}
+        </span>
+      ))}
+    </>
+  );
+}
+
+/**
+ * command-palette style search across every account; picking a hit opens the task, picking
+ * a list shows it. lists and close (misspelled) matches come from the fuzzy matcher
+ */
+export function QuickOpenModal({ onClose, onOpenList }: QuickOpenModalProps) {
+  const [query, setQuery] = useState('');
+  const [active, setActive] = useState(0);
+  const inputRef = useRef<HTMLInputElement>(null);
+  const trimmed = query.trim();
+  const { data: groups = [] } = useGlobalSearch(trimmed);
+  const { data: fuzzy = [] } = useFuzzyFind(trimmed, FUZZY_LIMIT);
+  const { data: accounts = [] } = useAccounts();
+  const setSelectedTaskMutation = useSetSelectedTask();
+  const setActiveAccountMutation = useSetActiveAccount();
+  const setActiveCalendarMutation = useSetActiveCalendar();
+
+  useModalEscapeKey(onClose);
+
+  // focus after the modal state provider has blurred the previous element
+  useEffect(() => {
+    const timeout = setTimeout(() => inputRef.current?.focus(), 100);
+    return () => clearTimeout(timeout);
+  }, []);
+
+  const hits = trimmed ? groups.flatMap((group) => group.hits) : [];
+  const lists = trimmed ? fuzzy.filter((match) => match.kind === 'list') : [];
+  // only when the search itself found nothing, e.g. for a typo
+  const closeMatches =
+    trimmed && hits.length === 0 ? fuzzy.filter((match) => match.kind === 'task') : [];
+
+  const openTask = (id: string) => {
+    setSelectedTaskMutation.mutate(id);
+    onClose();
+  };
+
+  const openList = (calendarId: string) => {
+    const account = accounts.find((a) => a.calendars.some((c) => c.id === calendarId));
+    setActiveAccountMutation.mutate(account?.id ?? null);
+    setActiveCalendarMutation.mutate(calendarId);
+    onOpenList();
+    onClose();
+  };
+
+  // keyboard order: lists, then search hits, then close matches
+  const items = [
+    ...lists.map((match) => () => openList(match.id)),
+    ...hits.map((hit) => () => openTask(hit.id)),
+    ...closeMatches.map((match) => () => openTask(match.id)),
+  ];
+
+  const handleKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'ArrowDown') {
+      e.preventDefault();
+      setActive((i) => Math.min(i + 1, items.length - 1));
+    } else if (e.key === 'ArrowUp') {
+      e.preventDefault();
+      setActive((i) => Math.max(i - 1, 0));
+    } else if (e.key === 'Enter' && items[active]) {
+      e.preventDefault();
+      items[active]();
+    }
+  };
+
+  const rowClass = (index: number) =>
+    `w-full flex items-center gap-2 px-4 py-2 text-left text-sm ${
+      index === active ? 'bg-primary-50 dark:bg-primary-900/30' : ''
+    }`;
+  const headingClass =
+    'px-4 pt-2 pb-1 text-xs font-medium text-surface-500 dark:text-surface-400 truncate';
+
+  return (
+    <div
+      className="fixed inset-0 z-50 flex items-start justify-center pt-[15vh] bg-black/50 animate-fade-in"
+      onClick={onClose}
+    >
+      <div
+        className="bg-white dark:bg-surface-800 rounded-xl shadow-xl w-full max-w-lg animate-scale-in overflow-hidden"
+        onClick={(e) => e.stopPropagation()}
+      >
+        <div className="flex items-center gap-2 px-4 border-b border-surface-200 dark:border-surface-700">
+          <Search className="w-4 h-4 text-surface-400" />
+          <input
+            ref={inputRef}
+            type="text"
+            value={query}
+            onChange={(e) => {
+              setQuery(e.target.value);
+              setActive(0);
+            }}
+            onKeyDown={handleKeyDown}
+            placeholder="Jump to a task or list..."
+            className="flex-1 py-3 text-sm text-surface-800 dark:text-surface-200 bg-transparent border-0 focus:outline-none focus:ring-0 placeholder:text-surface-400"
+          />
+        </div>
+
+        <div className="max-h-[50vh] overflow-y-auto py-1">
+          {trimmed && items.length === 0 && (
+            <p className="px-4 py-6 text-center text-sm text-surface-500 dark:text-surface-400">
+              No tasks or lists found.
+            </p>
+          )}
+
+          {lists.length > 0 && (
+            <div>
+              <div className={headingClass}>Lists</div>
+              {lists.map((match, i) => (
+                <button
+                  type="button"
+                  key={match.id}
+                  onClick={() => openList(match.id)}
+                  onMouseMove={() => setActive(i)}
+                  className={rowClass(i)}
+                >
+                  <span className="flex-1 truncate text-surface-800 dark:text-surface-200">
+                    <Highlighted match={match} />
+                  </span>
+                  <span className="text-xs text-surface-400 truncate">{match.list}</span>
+                </button>
+              ))}
+            </div>
+          )}
+
+          {hits.length > 0 &&
+            groups.map((group) => (
+              <div key={group.calendarId ?? group.list}>
+                <div className={headingClass}>
+                  {group.list} · {group.account}
+                </div>
+                {group.hits.map((hit) => {
+                  const index = lists.length + hits.indexOf(hit);
+                  const due = hit.dueDate ? formatDueDate(new Date(hit.dueDate)) : null;
+                  return (
+                    <button
+                      type="button"
+                      key={hit.id}
+                      onClick={() => openTask(hit.id)}
+                      onMouseMove={() => setActive(index)}
+                      className={rowClass(index)}
+                    >
+                      <span
+                        className={`flex-1 truncate ${hit.completed ? 'line-through text-surface-400' : 'text-surface-800 dark:text-surface-200'}`}
+                      >
+                        {hit.title || 'Untitled task'}
+                      </span>
+                      {matchedLabels[hit.matched] && (
+                        <span className="text-xs text-surface-400">
+                          in {matchedLabels[hit.matched]}
+                        </span>
+                      )}
+                      {due && (
+                        <span className="text-xs" style={{ color: due.textColor }}>
+                          {due.text}
+                        </span>
+                      )}
+                    </button>
+                  );
+                })}
+              </div>
+            ))}
+
+          {closeMatches.length > 0 && (
+            <div>
+              <div className={headingClass}>Close matches</div>
+              {closeMatches.map((match, i) => {
+                const index = lists.length + i;
+                return (
+                  <button
+                    type="button"
+                    key={match.id}
+                    onClick={() => openTask(match.id)}
+                    onMouseMove={() => setActive(index)}
+                    className={rowClass(index)}
+                  >
+                    <span
+                      className={`flex-1 truncate ${match.completed ? 'line-through text-surface-400' : 'text-surface-800 dark:text-surface-200'}`}
+                    >
+                      <Highlighted match={match} />
+                    </span>
+                    <span className="text-xs text-surface-400 truncate">{match.list}</span>
+                  </button>
+                );
+              })}
+            </div>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -17,12 +17,43 @@ import X from 'lucide-react/icons/x';
 import { useEffect, useState } from 'react';
 import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
 import { useSettingsStore } from '@/store/settingsStore';
+import type { RelativeTrigger } from '@/types';
+
+// offsets a reminder can have from the start or due date, in seconds
+const OFFSETS: { seconds: number; label: string }[] = [
+  { seconds: 0, label: 'At the time' },
+  { seconds: -5 * 60, label: '5 minutes before' },
+  { seconds: -15 * 60, label: '15 minutes before' },
+  { seconds: -30 * 60, label: '30 minutes before' },
+  { seconds: -60 * 60, label: '1 hour before' },
+  { seconds: -2 * 60 * 60, label: '2 hours before' },
+  { seconds: -24 * 60 * 60, label: '1 day before' },
+  { seconds: -2 * 24 * 60 * 60, label: '2 days before' },
+  { seconds: -7 * 24 * 60 * 60, label: '1 week before' },
+];
+
+/**
+ * when a relative reminder fires, e.g. "15 minutes before the due date"
+ */
+export function describeRelativeTrigger({ related, offset = 0 }: Partial<RelativeTrigger>): string {
+  const date = related === 'start' ? 'the start date' : 'the due date';
+  if (offset === 0) return `at ${date}`;
+  const known = OFFSETS.find((o) => o.seconds === offset);
+  if (known) return `${known.label.toLowerCase()} ${date}`;
+  const minutes = Math.round(Math.abs(offset) / 60);
+  return `${minutes} minutes ${offset < 0 ? 'before' : 'after'} ${date}`;
+}
 
 interface ReminderPickerModalProps {
   isOpen: boolean;
   onClose: () => void;
   value?: Date;
-  onSave: (date: Date) => void;
+  // set when the reminder being edited moves with the start or due date
+  relative?: RelativeTrigger;
+  // the task's dates, which reminders can be relative to
+  startDate?: Date;
+  dueDate?: Date;
+  onSave: (date: Date, relative?: RelativeTrigger) => void;
   title?: string;
 }
 
@@ -30,9 +61,16 @@ export function ReminderPickerModal({
   isOpen,
   onClose,
   value,
+  relative,
+  startDate,
+  dueDate,
   onSave,
   title = 'Add Reminder',
 }: ReminderPickerModalProps) {
+  const [related, setRelated] = useState<RelativeTrigger['related'] | null>(
+    relative?.related ?? null,
+  );
+  const [offset, setOffset] = useState(relative?.offset ?? 0);
   const [currentMonth, setCurrentMonth] = useState(value ? new Date(value) : new Date());
   const [selectedDate, setSelectedDate] = useState<Date | undefined>(value);
   const [selectedTime, setSelectedTime] = useState(() => {
@@ -51,6 +89,8 @@ export function ReminderPickerModal({
   // Reset state when modal opens
   useEffect(() => {
     if (isOpen) {
+      setRelated(relative?.related ?? null);
+      setOffset(relative?.offset ?? 0);
       if (value) {
         setSelectedDate(value);
         setCurrentMonth(new Date(value));
@@ -64,7 +104,7 @@ export function ReminderPickerModal({
         setSelectedTime({ hours: 9, minutes: 0 });
       }
     }
-  }, [isOpen, value]);
+  }, [isOpen, value, relative]);
 
   if (!isOpen) return null;
 
@@ -109,8 +149,20 @@ export function ReminderPickerModal({
     setSelectedDate(newDate);
   };
 
+  const relatedDate = related === 'start' ? startDate : related === 'due' ? dueDate : undefined;
+  const relativeTrigger = relatedDate
+    ? new Date(new Date(relatedDate).getTime() + offset * 1000)
+    : undefined;
+
+  const relativeHint = relativeTrigger
+    ? `${format(relativeTrigger, 'MMM d, yyyy h:mm a')}, moves with the ${related} date`
+    : 'The task has no such date';
+
   const handleSave = () => {
-    if (selectedDate) {
+    if (related && relativeTrigger) {
+      onSave(relativeTrigger, { related, offset });
+      onClose();
+    } else if (selectedDate) {
       onSave(selectedDate);
       onClose();
     }
@@ -134,129 +186,169 @@ export function ReminderPickerModal({
         </div>
 
         <div className="p-4">
-          {/* Quick select buttons */}
-          <div className="flex gap-2 mb-4">
-            <button
-              type="button"
-              onClick={() => handleQuickSelect(new Date())}
-              className="flex-1 px-3 py-2 text-sm font-medium text-surface-600 dark:text-surface-400 bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 rounded-lg transition-colors"
-            >
-              Today
-            </button>
-            <button
-              type="button"
-              onClick={() => handleQuickSelect(addDays(new Date(), 1))}
-              className="flex-1 px-3 py-2 text-sm font-medium text-surface-600 dark:text-surface-400 bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 rounded-lg transition-colors"
-            >
-              Tomorrow
-            </button>
-            <button
-              type="button"
-              onClick={() => handleQuickSelect(addDays(new Date(), 7))}
-              className="flex-1 px-3 py-2 text-sm font-medium text-surface-600 dark:text-surface-400 bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 rounded-lg transition-colors"
-            >
-              Next week
-            </button>
-          </div>
-
-          {/* Month navigation */}
-          <div className="flex items-center justify-between mb-3">
-            <button
-              type="button"
-              onClick={() => setCurrentMonth(subMonths(currentMonth, 1))}
-              className="p-1 rounded hover:bg-surface-100 dark:hover:bg-surface-700 text-surface-600 dark:text-surface-400 transition-colors"
-            >
-              <ChevronLeft className="w-5 h-5" />
-            </button>
-            <span className="text-sm font-medium text-surface-800 dark:text-surface-200">
-              {format(currentMonth, 'MMMM yyyy')}
-            </span>
-            <button
-              type="button"
-              onClick={() => setCurrentMonth(addMonths(currentMonth, 1))}
-              className="p-1 rounded hover:bg-surface-100 dark:hover:bg-surface-700 text-surface-600 dark:text-surface-400 transition-colors"
-            >
-              <ChevronRight className="w-5 h-5" />
-            </button>
-          </div>
-
-          {/* Days of week header */}
-          <div className="grid grid-cols-7 gap-1 mb-2">
-            {daysOfWeek.map((day) => (
-              <div
-                key={day}
-                className="text-center text-xs font-medium text-surface-500 dark:text-surface-400"
-              >
-                {day}
-              </div>
-            ))}
-          </div>
-
-          {/* Calendar days */}
-          <div className="grid grid-cols-7 gap-1 mb-4">
-            {paddedDays.map((day, index) => {
-              if (!day) {
-                return <div key={`empty-${index}`} />;
-              }
-
-              const isSelected = selectedDate && isSameDay(day, selectedDate);
-              const isCurrentMonth = isSameMonth(day, currentMonth);
-              const isTodayDate = isToday(day);
-
-              return (
-                <button
-                  key={day.toISOString()}
-                  type="button"
-                  onClick={() => handleDayClick(day)}
-                  className={`
-                    w-8 h-8 rounded-full text-sm flex items-center justify-center transition-colors
-                    ${
-                      isSelected
-                        ? 'bg-primary-600 text-white'
-                        : isTodayDate
-                          ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300 font-medium'
-                          : isCurrentMonth
-                            ? 'text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700'
-                            : 'text-surface-400 dark:text-surface-600'
-                    }
-                  `}
-                >
-                  {format(day, 'd')}
-                </button>
-              );
-            })}
-          </div>
-
-          {/* Time Picker */}
-          <div className="flex items-center gap-2 py-3 border-t border-surface-200 dark:border-surface-700">
-            <Clock className="w-4 h-4 text-surface-400" />
-            <span className="text-sm text-surface-600 dark:text-surface-400">Time</span>
-            <div className="flex-1 flex items-center justify-end gap-1">
+          {(startDate || dueDate) && (
+            <div className="mb-4">
+              <label className="block text-sm font-medium text-surface-700 dark:text-surface-300 mb-1">
+                Remind me
+              </label>
               <select
-                value={selectedTime.hours}
-                onChange={(e) => handleTimeChange('hours', parseInt(e.target.value, 10))}
-                className="px-2 py-1 text-sm bg-surface-100 dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded text-surface-700 dark:text-surface-300 focus:outline-none focus:border-primary-300"
+                value={related ?? ''}
+                onChange={(e) =>
+                  setRelated((e.target.value || null) as RelativeTrigger['related'] | null)
+                }
+                className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
               >
-                {Array.from({ length: 24 }, (_, i) => (
-                  <option key={i} value={i}>
-                    {i.toString().padStart(2, '0')}
-                  </option>
-                ))}
+                <option value="">At a date and time</option>
+                {startDate && <option value="start">Relative to the start date</option>}
+                {dueDate && <option value="due">Relative to the due date</option>}
               </select>
-              <span className="text-surface-500">:</span>
+            </div>
+          )}
+
+          {related ? (
+            <div>
               <select
-                value={selectedTime.minutes}
-                onChange={(e) => handleTimeChange('minutes', parseInt(e.target.value, 10))}
-                className="px-2 py-1 text-sm bg-surface-100 dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded text-surface-700 dark:text-surface-300 focus:outline-none focus:border-primary-300"
+                value={offset}
+                onChange={(e) => setOffset(parseInt(e.target.value, 10))}
+                className="w-full px-3 py-2 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300 focus:ring-2 focus:ring-primary-100 dark:focus:ring-primary-900/50"
               >
-                {Array.from({ length: 60 }, (_, i) => (
-                  <option key={i} value={i}>
-                    {i.toString().padStart(2, '0')}
+                {OFFSETS.map(({ seconds, label }) => (
+                  <option key={seconds} value={seconds}>
+                    {label}
                   </option>
                 ))}
               </select>
+              <p className="mt-2 text-xs text-surface-500 dark:text-surface-400">
+                {relativeHint}
+              </p>
+            </div>
+          ) : (
+            <>
+            {/* Quick select buttons */}
+            <div className="flex gap-2 mb-4">
+              <button
+                type="button"
+                onClick={() => handleQuickSelect(new Date())}
+                className="flex-1 px-3 py-2 text-sm font-medium text-surface-600 dark:text-surface-400 bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 rounded-lg transition-colors"
+              >
+                Today
+              </button>
+              <button
+                type="button"
+                onClick={() => handleQuickSelect(addDays(new Date(), 1))}
+                className="flex-1 px-3 py-2 text-sm font-medium text-surface-600 dark:text-surface-400 bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 rounded-lg transition-colors"
+              >
+                Tomorrow
+              </button>
+              <button
+                type="button"
+                onClick={() => handleQuickSelect(addDays(new Date(), 7))}
+                className="flex-1 px-3 py-2 text-sm font-medium text-surface-600 dark:text-surface-400 bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 rounded-lg transition-colors"
+              >
+                Next week
+              </button>
+            </div>
+
+            {/* Month navigation */}
+            <div className="flex items-center justify-between mb-3">
+              <button
+                type="button"
+                onClick={() => setCurrentMonth(subMonths(currentMonth, 1))}
+                className="p-1 rounded hover:bg-surface-100 dark:hover:bg-surface-700 text-surface-600 dark:text-surface-400 transition-colors"
+              >
+                <ChevronLeft className="w-5 h-5" />
+              </button>
+              <span className="text-sm font-medium text-surface-800 dark:text-surface-200">
+                {format(currentMonth, 'MMMM yyyy')}
+              </span>
+              <button
+                type="button"
+                onClick={() => setCurrentMonth(addMonths(currentMonth, 1))}
+                className="p-1 rounded hover:bg-surface-100 dark:hover:bg-surface-700 text-surface-600 dark:text-surface-400 transition-colors"
+              >
+                <ChevronRight className="w-5 h-5" />
+              </button>
+            </div>
+
+            {/* Days of week header */}
+            <div className="grid grid-cols-7 gap-1 mb-2">
+              {daysOfWeek.map((day) => (
+                <div
+                  key={day}
+                  className="text-center text-xs font-medium text-surface-500 dark:text-surface-400"
+                >
+                  {day}
+                </div>
+              ))}
+            </div>
+
+            {/* Calendar days */}
+            <div className="grid grid-cols-7 gap-1 mb-4">
+              {paddedDays.map((day, index) => {
+                if (!day) {
+                  return <div key={`empty-${index}`} />;
+                }
+
+                const isSelected = selectedDate && isSameDay(day, selectedDate);
+                const isCurrentMonth = isSameMonth(day, currentMonth);
+                const isTodayDate = isToday(day);
+
+                return (
+                  <button
+                    key={day.toISOString()}
+                    type="button"
+                    onClick={() => handleDayClick(day)}
+                    className={`
+                      w-8 h-8 rounded-full text-sm flex items-center justify-center transition-colors
+                      ${
+                        isSelected
+                          ? 'bg-primary-600 text-white'
+                          : isTodayDate
+                            ? 'bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300 font-medium'
+                            : isCurrentMonth
+                              ? 'text-surface-700 dark:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700'
+                              : 'text-surface-400 dark:text-surface-600'
+                      }
+                    `}
+                  >
+                    {format(day, 'd')}
+                  </button>
+                );
+              })}
+            </div>
+
+            {/* Time Picker */}
+            <div className="flex items-center gap-2 py-3 border-t border-surface-200 dark:border-surface-700">
+              <Clock className="w-4 h-4 text-surface-400" />
+              <span className="text-sm text-surface-600 dark:text-surface-400">Time</span>
+              <div className="flex-1 flex items-center justify-end gap-1">
+                <select
+                  value={selectedTime.hours}
+                  onChange={(e) => handleTimeChange('hours', parseInt(e.target.value, 10))}
+                  className="px-2 py-1 text-sm bg-surface-100 dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded text-surface-700 dark:text-surface-300 focus:outline-none focus:border-primary-300"
+                >
+                  {Array.from({ length: 24 }, (_, i) => (
+                    <option key={i} value={i}>
+                      {i.toString().padStart(2, '0')}
+                    </option>
+                  ))}
+                </select>
+                <span className="text-surface-500">:</span>
+                <select
+                  value={selectedTime.minutes}
+                  onChange={(e) => handleTimeChange('minutes', parseInt(e.target.value, 10))}
+                  className="px-2 py-1 text-sm bg-surface-100 dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded text-surface-700 dark:text-surface-300 focus:outline-none focus:border-primary-300"
+                >
+                  {Array.from({ length: 60 }, (_, i) => (
+                    <option key={i} value={i}>
+                      {i.toString().padStart(2, '0')}
+                    </option>
+                  ))}
+                </select>
+              </div>
             </div>
-          </div>
+            </>
+          )}
         </div>
 
         {/* Footer */}
@@ -271,7 +363,7 @@ export function ReminderPickerModal({
           <button
             type="button"
             onClick={handleSave}
-            disabled={!selectedDate}
+            disabled={related ? !relativeTrigger : !selectedDate}
             className="px-4 py-2 text-sm font-medium text-white bg-primary-600 hover:bg-primary-700 disabled:bg-surface-300 dark:disabled:bg-surface-600 disabled:cursor-not-allowed rounded-lg transition-colors"
           >
             {value ? 'Save' : 'Add Reminder'}
@@ -1,26 +1,43 @@
 import Bell from 'lucide-react/icons/bell';
+import Bug from 'lucide-react/icons/bug';
 import Database from 'lucide-react/icons/database';
+import FileCode from 'lucide-react/icons/file-code';
+import Globe from 'lucide-react/icons/globe';
 import Info from 'lucide-react/icons/info';
 import Keyboard from 'lucide-react/icons/keyboard';
+import Mail from 'lucide-react/icons/mail';
 import ListTodo from 'lucide-react/icons/list-todo';
 import Palette from 'lucide-react/icons/palette';
+import Plug from 'lucide-react/icons/plug';
 import RefreshCw from 'lucide-react/icons/refresh-cw';
+import Server from 'lucide-react/icons/server';
 import Settings from 'lucide-react/icons/settings';
+import Shield from 'lucide-react/icons/shield';
 import User from 'lucide-react/icons/user';
+import Webhook from 'lucide-react/icons/webhook';
+import Workflow from 'lucide-react/icons/workflow';
 import X from 'lucide-react/icons/x';
 import { useState } from 'react';
 import { useAccounts } from '@/hooks/queries';
 import { useModalEscapeKey } from '@/hooks/useModalEscapeKey';
 import {
   AboutSettings,
+  ApiServerSettings,
   AppearanceSettings,
   BehaviorSettings,
   ConnectionsSettings,
   DataSettings,
+  EmailSettings,
+  HttpActionSettings,
   NotificationSettings,
+  RuleSettings,
+  ScriptSettings,
+  SecuritySettings,
   ShortcutsSettings,
   SyncSettings,
   TaskDefaultsSettings,
+  TroubleshootingSettings,
+  WebhookSettings,
 } from './settings';
 
 interface SettingsModalProps {
@@ -29,17 +46,25 @@ interface SettingsModalProps {
   initialSubtab?: SettingsSubtab;
 }
 
-type SettingsCategory = 'general' | 'account' | 'about';
+type SettingsCategory = 'general' | 'account' | 'integrations' | 'about';
 export type SettingsSubtab =
   | 'behavior'
   | 'appearance'
   | 'notifications'
   | 'shortcuts'
+  | 'security'
   | 'defaults'
   | 'connections'
   | 'sync'
   | 'data'
-  | 'version';
+  | 'webhooks'
+  | 'scripts'
+  | 'rules'
+  | 'api'
+  | 'email'
+  | 'actions'
+  | 'version'
+  | 'troubleshooting';
 
 type SettingsSubtabInfo = { id: SettingsSubtab; label: string; icon: React.ReactNode };
 
@@ -50,6 +75,7 @@ export function SettingsModal({ onClose, initialCategory, initialSubtab }: Setti
   const [activeSubtabs, setActiveSubtabs] = useState<Record<SettingsCategory, SettingsSubtab>>({
     general: initialCategory === 'general' && initialSubtab ? initialSubtab : 'behavior',
     account: initialCategory === 'account' && initialSubtab ? initialSubtab : 'connections',
+    integrations: initialCategory === 'integrations' && initialSubtab ? initialSubtab : 'webhooks',
     about: initialCategory === 'about' && initialSubtab ? initialSubtab : 'version',
   });
   const [isChildModalOpen, setIsChildModalOpen] = useState(false);
@@ -73,13 +99,14 @@ export function SettingsModal({ onClose, initialCategory, initialSubtab }: Setti
       id: 'general',
       label: 'General',
       icon: <Settings className="w-4 h-4" />,
-      description: 'Behavior, appearance, notifications, shortcuts',
+      description: 'Behavior, appearance, notifications, shortcuts, security',
       subtabs: [
         { id: 'behavior', label: 'Behavior', icon: <Settings className="w-4 h-4" /> },
         { id: 'defaults', label: 'Task Defaults', icon: <ListTodo className="w-4 h-4" /> },
         { id: 'appearance', label: 'Appearance', icon: <Palette className="w-4 h-4" /> },
         { id: 'notifications', label: 'Notifications', icon: <Bell className="w-4 h-4" /> },
         { id: 'shortcuts', label: 'Shortcuts', icon: <Keyboard className="w-4 h-4" /> },
+        { id: 'security', label: 'Security', icon: <Shield className="w-4 h-4" /> },
       ],
     },
     {
@@ -93,12 +120,29 @@ export function SettingsModal({ onClose, initialCategory, initialSubtab }: Setti
         { id: 'data', label: 'Data', icon: <Database className="w-4 h-4" /> },
       ],
     },
+    {
+      id: 'integrations',
+      label: 'Integrations',
+      icon: <Plug className="w-4 h-4" />,
+      description: 'Webhooks, scripts, rules, API server, email, HTTP actions',
+      subtabs: [
+        { id: 'webhooks', label: 'Webhooks', icon: <Webhook className="w-4 h-4" /> },
+        { id: 'scripts', label: 'Scripts', icon: <FileCode className="w-4 h-4" /> },
+        { id: 'rules', label: 'Rules', icon: <Workflow className="w-4 h-4" /> },
+        { id: 'api', label: 'API Server', icon: <Server className="w-4 h-4" /> },
+        { id: 'email', label: 'Email', icon: <Mail className="w-4 h-4" /> },
+        { id: 'actions', label: 'HTTP Actions', icon: <Globe className="w-4 h-4" /> },
+      ],
+    },
     {
       id: 'about',
       label: 'About',
       icon: <Info className="w-4 h-4" />,
       description: 'Version',
-      subtabs: [{ id: 'version', label: 'Version', icon: <Info className="w-4 h-4" /> }],
+      subtabs: [
+        { id: 'version', label: 'Version', icon: <Info className="w-4 h-4" /> },
+        { id: 'troubleshooting', label: 'Troubleshooting', icon: <Bug className="w-4 h-4" /> },
+      ],
     },
   ];
 
@@ -178,6 +222,7 @@ export function SettingsModal({ onClose, initialCategory, initialSubtab }: Setti
                 {currentSubtab === 'shortcuts' && (
                   <ShortcutsSettings onEditingShortcutChange={setIsChildModalOpen} />
                 )}
+                {currentSubtab === 'security' && <SecuritySettings />}
               </div>
             )}
 
@@ -189,7 +234,23 @@ export function SettingsModal({ onClose, initialCategory, initialSubtab }: Setti
               </div>
             )}
 
-            {activeCategory === 'about' && currentSubtab === 'version' && <AboutSettings />}
+            {activeCategory === 'integrations' && (
+              <div className="space-y-6">
+                {currentSubtab === 'webhooks' && <WebhookSettings />}
+                {currentSubtab === 'scripts' && <ScriptSettings />}
+                {currentSubtab === 'rules' && <RuleSettings />}
+                {currentSubtab === 'api' && <ApiServerSettings />}
+                {currentSubtab === 'email' && <EmailSettings />}
+                {currentSubtab === 'actions' && <HttpActionSettings />}
+              </div>
+            )}
+
+            {activeCategory === 'about' && (
+              <div className="space-y-6">
+                {currentSubtab === 'version' && <AboutSettings />}
+                {currentSubtab === 'troubleshooting' && <TroubleshootingSettings />}
+              </div>
+            )}
           </div>
         </div>
       </div>
@@ -3,7 +3,7 @@ import Download from 'lucide-react/icons/download';
 import ExternalLink from 'lucide-react/icons/external-link';
 import X from 'lucide-react/icons/x';
 import { useEffect } from 'react';
-import type { UpdateInfo } from '@/hooks/useUpdateChecker';
+import type { UpdateInfo } from '@/lib/database';
 
 interface UpdateModalProps {
   updateInfo: UpdateInfo;
@@ -59,6 +59,12 @@ export function UpdateModal({
             Version {updateInfo.version} is now available
           </p>
 
+          {!updateInfo.canInstall && (
+            <p className="text-sm text-surface-600 dark:text-surface-400">
+              This build is updated by its package manager or by hand, not by the app.
+            </p>
+          )}
+
           <div className="text-sm text-surface-600 dark:text-surface-400 space-y-1">
             <p>Current version: {updateInfo.currentVersion}</p>
             {updateInfo.date && <p>Released: {new Date(updateInfo.date).toLocaleDateString()}</p>}
@@ -103,8 +109,17 @@ export function UpdateModal({
             disabled={isDownloading}
             className="px-4 py-2 text-sm bg-primary-600 text-white rounded-lg hover:bg-primary-700 disabled:opacity-50 disabled:cursor-not-allowed flex items-center gap-2 transition-colors"
           >
-            <Download className="w-4 h-4" />
-            {isDownloading ? 'Downloading...' : 'Download & Install'}
+            {updateInfo.canInstall ? (
+              <>
+                <Download className="w-4 h-4" />
+                {isDownloading ? 'Downloading...' : 'Download & Install'}
+              </>
+            ) : (
+              <>
+                <ExternalLink className="w-4 h-4" />
+                Open Release Page
+              </>
+            )}
           </button>
         </div>
       </div>
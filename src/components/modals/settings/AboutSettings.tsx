@@ -1,5 +1,23 @@
+import RefreshCw from 'lucide-react/icons/refresh-cw';
+import { useEffect, useState } from 'react';
+import {
+  checkForUpdates,
+  getUpdateMode,
+  getUpdateSettings,
+  setUpdateSettings,
+  type UpdateMode,
+  type UpdateSettings,
+} from '@/lib/database';
 import packageJson from '../../../../package.json';
 
+// why a build can't update itself, as told in the settings
+const MANUAL_REASONS: Record<string, string> = {
+  packaged: 'This package is updated by your package manager.',
+  flatpak: 'Flatpak updates this app.',
+  portable: 'Portable installs are updated by hand.',
+  disabled: 'Self-updating is turned off for this installation.',
+};
+
 export function AboutSettings() {
   const appInfo = packageJson as {
     version?: string;
@@ -12,6 +30,41 @@ export function AboutSettings() {
   const appDescription = appInfo.description || 'A CalDAV-compatible task management client.';
   const appAuthor = appInfo.author;
 
+  const [updates, setUpdates] = useState<UpdateSettings | null>(null);
+  const [updateMode, setUpdateMode] = useState<UpdateMode | null>(null);
+  const [isChecking, setIsChecking] = useState(false);
+  const [checkResult, setCheckResult] = useState<string | null>(null);
+
+  useEffect(() => {
+    getUpdateSettings()
+      .then(setUpdates)
+      .catch(() => setUpdates(null));
+    getUpdateMode()
+      .then(setUpdateMode)
+      .catch(() => setUpdateMode(null));
+  }, []);
+
+  const saveUpdateSettings = (settings: UpdateSettings) => {
+    setUpdates(settings);
+    setUpdateSettings(settings).catch((err) => setCheckResult(String(err)));
+  };
+
+  const handleCheck = async () => {
+    setIsChecking(true);
+    setCheckResult(null);
+    try {
+      // a new release opens the update dialog as well
+      const update = await checkForUpdates();
+      setCheckResult(update ? `Version ${update.version} is available` : "You're up to date");
+    } catch (err) {
+      setCheckResult(String(err));
+    } finally {
+      setIsChecking(false);
+    }
+  };
+
+  const manual = updateMode?.mode === 'manual';
+
   return (
     <div className="space-y-6">
       <div className="text-center py-4">
@@ -33,6 +86,64 @@ export function AboutSettings() {
           </h3>
           <p className="text-sm text-surface-600 dark:text-surface-400">{appAuthor}</p>
         </div>
+
+        <div className="p-4 bg-surface-50 dark:bg-surface-700 rounded-lg space-y-3">
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200">Updates</h3>
+          {manual && updateMode?.reason && (
+            <p className="text-xs text-surface-500 dark:text-surface-400">
+              {MANUAL_REASONS[updateMode.reason]} New releases are only looked for when you check.
+            </p>
+          )}
+          {updates && (
+            <>
+              <div className="flex items-center justify-between">
+                <span className="text-sm text-surface-600 dark:text-surface-400">Channel</span>
+                <select
+                  value={updates.channel}
+                  onChange={(e) =>
+                    saveUpdateSettings({
+                      ...updates,
+                      channel: e.target.value as UpdateSettings['channel'],
+                    })
+                  }
+                  className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-800 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+                >
+                  <option value="stable">Stable</option>
+                  <option value="beta">Beta</option>
+                </select>
+              </div>
+              {!manual && (
+                <label className="flex items-center justify-between">
+                  <span className="text-sm text-surface-600 dark:text-surface-400">
+                    Check for updates automatically
+                  </span>
+                  <input
+                    type="checkbox"
+                    checked={updates.autoCheck}
+                    onChange={(e) =>
+                      saveUpdateSettings({ ...updates, autoCheck: e.target.checked })
+                    }
+                    className="rounded border-surface-300"
+                  />
+                </label>
+              )}
+            </>
+          )}
+          <div className="flex items-center gap-3">
+            <button
+              type="button"
+              onClick={handleCheck}
+              disabled={isChecking}
+              className="flex items-center gap-2 px-3 py-2 text-sm bg-surface-200 dark:bg-surface-600 hover:bg-surface-300 dark:hover:bg-surface-500 text-surface-700 dark:text-surface-200 rounded-lg transition-colors disabled:opacity-50"
+            >
+              <RefreshCw className={`w-4 h-4 ${isChecking ? 'animate-spin' : ''}`} />
+              Check for Updates
+            </button>
+            {checkResult && (
+              <span className="text-sm text-surface-600 dark:text-surface-400">{checkResult}</span>
+            )}
+          </div>
+        </div>
       </div>
     </div>
   );
@@ -0,0 +1,115 @@
+import KeyRound from 'lucide-react/icons/key-round';
+import { useState } from 'react';
+import { useAccountEncryption, useSetAccountEncryption } from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import type { Account } from '@/types';
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-1.5 text-xs bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded transition-colors disabled:opacity-50';
+
+interface AccountEncryptionSettingsProps {
+  account: Account;
+}
+
+/**
+ * end-to-end encryption of task titles and descriptions for one account; every device needs
+ * the same passphrase to read them
+ */
+export function AccountEncryptionSettings({ account }: AccountEncryptionSettingsProps) {
+  const { data: enabled = false } = useAccountEncryption(account.id);
+  const setEncryptionMutation = useSetAccountEncryption();
+  const { confirm } = useConfirmDialog();
+  const [passphrase, setPassphrase] = useState('');
+  const [confirmPassphrase, setConfirmPassphrase] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const save = (value: string | null) => {
+    setError(null);
+    setEncryptionMutation.mutate(
+      { accountId: account.id, passphrase: value },
+      {
+        onSuccess: () => {
+          setPassphrase('');
+          setConfirmPassphrase('');
+        },
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  const handleSetPassphrase = async () => {
+    if (passphrase !== confirmPassphrase) {
+      setError("The passphrases don't match");
+      return;
+    }
+    const confirmed = await confirm({
+      title: enabled ? 'Change passphrase' : 'Encrypt tasks',
+      subtitle: account.name,
+      message:
+        'Every task of this account is uploaded again on the next sync. Other devices and apps can only read the titles and descriptions with the same passphrase.',
+      confirmLabel: enabled ? 'Change' : 'Encrypt',
+      cancelLabel: 'Cancel',
+    });
+    if (confirmed) save(passphrase);
+  };
+
+  const handleDisable = async () => {
+    const confirmed = await confirm({
+      title: 'Stop encrypting tasks',
+      subtitle: account.name,
+      message: 'Every task of this account is uploaded again in plain text on the next sync.',
+      confirmLabel: 'Stop Encrypting',
+      cancelLabel: 'Cancel',
+      destructive: true,
+    });
+    if (confirmed) save(null);
+  };
+
+  return (
+    <div className="space-y-2">
+      <p className="text-xs text-surface-500 dark:text-surface-400">
+        End-to-end encryption {enabled ? 'is on' : 'is off'}
+      </p>
+      <input
+        type="password"
+        autoComplete="off"
+        value={passphrase}
+        onChange={(e) => setPassphrase(e.target.value)}
+        placeholder={enabled ? 'New passphrase' : 'Passphrase'}
+        className={inputClass}
+      />
+      <input
+        type="password"
+        autoComplete="off"
+        value={confirmPassphrase}
+        onChange={(e) => setConfirmPassphrase(e.target.value)}
+        placeholder="Repeat the passphrase"
+        className={inputClass}
+      />
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+      <div className="flex gap-2">
+        <button
+          type="button"
+          disabled={!passphrase || setEncryptionMutation.isPending}
+          onClick={handleSetPassphrase}
+          className={buttonClass}
+        >
+          <KeyRound className="w-3 h-3" />
+          {enabled ? 'Change Passphrase' : 'Encrypt Tasks'}
+        </button>
+        {enabled && (
+          <button
+            type="button"
+            disabled={setEncryptionMutation.isPending}
+            onClick={handleDisable}
+            className={buttonClass}
+          >
+            Stop Encrypting
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
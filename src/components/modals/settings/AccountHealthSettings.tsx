@@ -0,0 +1,104 @@
+import { formatDistanceToNow } from 'date-fns';
+import RefreshCw from 'lucide-react/icons/refresh-cw';
+import { useState } from 'react';
+import { useAccountHealth, useRevalidateCredentials } from '@/hooks/queries';
+import type { AccountHealthState } from '@/lib/database';
+import type { Account } from '@/types';
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-1.5 text-xs bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded transition-colors disabled:opacity-50';
+
+const stateLabels: Record<AccountHealthState, string> = {
+  healthy: 'Syncing normally',
+  authFailed: 'The server rejected the password',
+  networkError: "The server can't be reached",
+  serverError: 'The server reported an error',
+};
+
+interface AccountHealthSettingsProps {
+  account: Account;
+}
+
+/**
+ * whether the account's last syncs worked; the credentials can be checked again and a
+ * changed password entered here
+ */
+export function AccountHealthSettings({ account }: AccountHealthSettingsProps) {
+  const { data: healthList = [] } = useAccountHealth();
+  const revalidateCredentialsMutation = useRevalidateCredentials();
+  const [password, setPassword] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const health = healthList.find((h) => h.accountId === account.id);
+  const state = health?.state ?? 'healthy';
+
+  const revalidate = (newPassword: string | null) => {
+    setError(null);
+    revalidateCredentialsMutation.mutate(
+      { accountId: account.id, password: newPassword },
+      {
+        onSuccess: (result) => {
+          if (result.state === 'healthy') setPassword('');
+        },
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  return (
+    <div className="space-y-2">
+      <p
+        className={`text-xs ${health?.attentionNeeded ? 'text-red-600 dark:text-red-400' : 'text-surface-500 dark:text-surface-400'}`}
+      >
+        {stateLabels[state]}
+        {state !== 'healthy' &&
+          health &&
+          health.consecutiveFailures > 1 &&
+          ` (${health.consecutiveFailures} times in a row)`}
+        {health?.nextRetryAt &&
+          `, tried again ${formatDistanceToNow(new Date(health.nextRetryAt), { addSuffix: true })}`}
+      </p>
+      {state !== 'healthy' && health?.lastError && (
+        <p className="text-xs font-mono text-surface-500 dark:text-surface-400 break-all">
+          {health.lastError}
+        </p>
+      )}
+      {state === 'authFailed' && (
+        <input
+          type="password"
+          autoComplete="off"
+          value={password}
+          onChange={(e) => setPassword(e.target.value)}
+          placeholder="New password"
+          className={inputClass}
+        />
+      )}
+      <div className="flex gap-2">
+        {state === 'authFailed' && (
+          <button
+            type="button"
+            disabled={!password || revalidateCredentialsMutation.isPending}
+            onClick={() => revalidate(password)}
+            className={buttonClass}
+          >
+            Save Password
+          </button>
+        )}
+        <button
+          type="button"
+          disabled={revalidateCredentialsMutation.isPending}
+          onClick={() => revalidate(null)}
+          className={buttonClass}
+        >
+          <RefreshCw
+            className={`w-3 h-3 ${revalidateCredentialsMutation.isPending ? 'animate-spin' : ''}`}
+          />
+          Check Connection
+        </button>
+      </div>
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  );
+}
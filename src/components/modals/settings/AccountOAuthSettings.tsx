@@ -0,0 +1,206 @@
+import { formatDistanceToNow } from 'date-fns';
+import KeyRound from 'lucide-react/icons/key-round';
+import { useState } from 'react';
+import { useDeleteOAuthTokens, useOAuthStatus, useSetOAuthTokens } from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import type { Account } from '@/types';
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-1.5 text-xs bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded transition-colors disabled:opacity-50';
+
+const emptyForm = {
+  tokenUrl: '',
+  clientId: '',
+  clientSecret: '',
+  scope: '',
+  accessToken: '',
+  refreshToken: '',
+  expiresIn: '',
+};
+
+interface AccountOAuthSettingsProps {
+  account: Account;
+}
+
+/**
+ * OAuth sign-in of one account: the tokens of an authorization are entered here, and
+ * renewed before syncs while the refresh token lasts
+ */
+export function AccountOAuthSettings({ account }: AccountOAuthSettingsProps) {
+  const { data: statuses = [] } = useOAuthStatus();
+  const setOAuthTokensMutation = useSetOAuthTokens();
+  const deleteOAuthTokensMutation = useDeleteOAuthTokens();
+  const { confirm } = useConfirmDialog();
+  const [editing, setEditing] = useState(false);
+  const [form, setForm] = useState(emptyForm);
+  const [error, setError] = useState<string | null>(null);
+
+  const status = statuses.find((s) => s.accountId === account.id);
+
+  const update = (changes: Partial<typeof emptyForm>) => setForm({ ...form, ...changes });
+
+  const handleSave = () => {
+    setError(null);
+    // token responses give the lifetime in seconds
+    const expiresIn = Number(form.expiresIn);
+    setOAuthTokensMutation.mutate(
+      {
+        accountId: account.id,
+        tokens: {
+          accessToken: form.accessToken.trim(),
+          refreshToken: form.refreshToken.trim() || null,
+          expiresAt: expiresIn > 0 ? new Date(Date.now() + expiresIn * 1000).toISOString() : null,
+          tokenUrl: form.tokenUrl.trim(),
+          clientId: form.clientId.trim(),
+          clientSecret: form.clientSecret.trim() || null,
+          scope: form.scope.trim() || null,
+        },
+      },
+      {
+        onSuccess: () => {
+          setForm(emptyForm);
+          setEditing(false);
+        },
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  const handleRemove = async () => {
+    const confirmed = await confirm({
+      title: 'Remove OAuth tokens',
+      subtitle: account.name,
+      message: 'The account signs in with its password again.',
+      confirmLabel: 'Remove',
+      cancelLabel: 'Cancel',
+      destructive: true,
+    });
+    if (confirmed) {
+      deleteOAuthTokensMutation.mutate(account.id, { onError: (err) => setError(String(err)) });
+    }
+  };
+
+  return (
+    <div className="space-y-2">
+      <p
+        className={`text-xs ${status?.reauthorizationRequired ? 'text-red-600 dark:text-red-400' : 'text-surface-500 dark:text-surface-400'}`}
+      >
+        {!status
+          ? 'Signs in with a password'
+          : status.reauthorizationRequired
+            ? 'The tokens expired, enter the tokens of a new authorization'
+            : 'Signs in with OAuth'}
+        {status?.expiresAt &&
+          !status.reauthorizationRequired &&
+          `, the access token expires ${formatDistanceToNow(new Date(status.expiresAt), { addSuffix: true })}`}
+        {status?.canRefresh && !status.reauthorizationRequired && ' and is renewed before syncs'}
+      </p>
+      {editing ? (
+        <>
+          <input
+            type="url"
+            value={form.tokenUrl}
+            onChange={(e) => update({ tokenUrl: e.target.value })}
+            placeholder="Token endpoint"
+            className={inputClass}
+          />
+          <div className="flex gap-2">
+            <input
+              type="text"
+              value={form.clientId}
+              onChange={(e) => update({ clientId: e.target.value })}
+              placeholder="Client ID"
+              className={inputClass}
+            />
+            <input
+              type="password"
+              autoComplete="off"
+              value={form.clientSecret}
+              onChange={(e) => update({ clientSecret: e.target.value })}
+              placeholder="Client secret (optional)"
+              className={inputClass}
+            />
+          </div>
+          <input
+            type="text"
+            value={form.scope}
+            onChange={(e) => update({ scope: e.target.value })}
+            placeholder="Scope (optional)"
+            className={inputClass}
+          />
+          <input
+            type="password"
+            autoComplete="off"
+            value={form.accessToken}
+            onChange={(e) => update({ accessToken: e.target.value })}
+            placeholder="Access token"
+            className={inputClass}
+          />
+          <div className="flex gap-2">
+            <input
+              type="password"
+              autoComplete="off"
+              value={form.refreshToken}
+              onChange={(e) => update({ refreshToken: e.target.value })}
+              placeholder="Refresh token (optional)"
+              className={inputClass}
+            />
+            <input
+              type="number"
+              min={0}
+              value={form.expiresIn}
+              onChange={(e) => update({ expiresIn: e.target.value })}
+              placeholder="Expires in (seconds)"
+              className={inputClass}
+            />
+          </div>
+          <div className="flex gap-2">
+            <button
+              type="button"
+              disabled={
+                !form.tokenUrl.trim() ||
+                !form.clientId.trim() ||
+                !form.accessToken.trim() ||
+                setOAuthTokensMutation.isPending
+              }
+              onClick={handleSave}
+              className={buttonClass}
+            >
+              Save Tokens
+            </button>
+            <button
+              type="button"
+              onClick={() => {
+                setForm(emptyForm);
+                setEditing(false);
+              }}
+              className={buttonClass}
+            >
+              Cancel
+            </button>
+          </div>
+        </>
+      ) : (
+        <div className="flex gap-2">
+          <button type="button" onClick={() => setEditing(true)} className={buttonClass}>
+            <KeyRound className="w-3 h-3" />
+            {status ? 'Replace Tokens' : 'Use OAuth Tokens'}
+          </button>
+          {status && (
+            <button
+              type="button"
+              disabled={deleteOAuthTokensMutation.isPending}
+              onClick={handleRemove}
+              className={buttonClass}
+            >
+              Remove Tokens
+            </button>
+          )}
+        </div>
+      )}
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,100 @@
+import ShieldCheck from 'lucide-react/icons/shield-check';
+import { useState } from 'react';
+import { useAccountTls, useSetAccountTls } from '@/hooks/queries';
+import type { Account, TlsSettings } from '@/types';
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const selectClass =
+  'px-2 py-1 text-xs text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-1.5 text-xs bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded transition-colors disabled:opacity-50';
+
+interface AccountTlsSettingsProps {
+  account: Account;
+}
+
+/**
+ * how strict the connection to the account's server is: the oldest TLS version accepted,
+ * and a certificate of its own for servers the system doesn't trust (old NAS firmware)
+ */
+export function AccountTlsSettings({ account }: AccountTlsSettingsProps) {
+  const { data: tls } = useAccountTls(account.id);
+  const setAccountTlsMutation = useSetAccountTls();
+  const [draft, setDraft] = useState<TlsSettings | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  if (!tls) return null;
+
+  const current = draft ?? tls;
+  const pinned = !!current.pinnedCertificate?.trim();
+
+  const update = (changes: Partial<TlsSettings>) => setDraft({ ...current, ...changes });
+
+  const handleSave = () => {
+    setError(null);
+    setAccountTlsMutation.mutate(
+      {
+        accountId: account.id,
+        // a hostname can only be skipped for a certificate of its own
+        tls: { ...current, allowInvalidHostnames: pinned && current.allowInvalidHostnames },
+      },
+      {
+        onSuccess: () => setDraft(null),
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center justify-between gap-4">
+        <span className="text-xs text-surface-500 dark:text-surface-400">Oldest TLS version</span>
+        <select
+          value={current.minVersion}
+          onChange={(e) => update({ minVersion: e.target.value as TlsSettings['minVersion'] })}
+          className={selectClass}
+        >
+          <option value="1.2">TLS 1.2</option>
+          <option value="1.3">TLS 1.3</option>
+        </select>
+      </div>
+      <textarea
+        value={current.pinnedCertificate ?? ''}
+        onChange={(e) => update({ pinnedCertificate: e.target.value })}
+        rows={3}
+        spellCheck={false}
+        placeholder="Pinned certificate (PEM), trusted instead of the system's certificates"
+        className={`${inputClass} font-mono text-xs`}
+      />
+      {pinned && (
+        <label className="flex items-center gap-2 text-xs text-surface-600 dark:text-surface-400">
+          <input
+            type="checkbox"
+            checked={current.allowInvalidHostnames}
+            onChange={(e) => update({ allowInvalidHostnames: e.target.checked })}
+            className="rounded border-surface-300"
+          />
+          Accept the certificate when its hostname doesn't match the server
+        </label>
+      )}
+      {draft && (
+        <div className="flex gap-2">
+          <button
+            type="button"
+            disabled={setAccountTlsMutation.isPending}
+            onClick={handleSave}
+            className={buttonClass}
+          >
+            <ShieldCheck className="w-3 h-3" />
+            Save TLS Settings
+          </button>
+          <button type="button" onClick={() => setDraft(null)} className={buttonClass}>
+            Cancel
+          </button>
+        </div>
+      )}
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  );
+}
@@ -0,0 +1,254 @@
+import Copy from 'lucide-react/icons/copy';
+import RefreshCw from 'lucide-react/icons/refresh-cw';
+import { useEffect, useState } from 'react';
+import {
+  useAccounts,
+  useApiServerSettings,
+  useIcsFeed,
+  useMcpEnabled,
+  useRegenerateApiServerToken,
+  useRegenerateIcsFeedToken,
+  useSetApiServerSettings,
+  useSetIcsFeedCalendars,
+  useSetMcpEnabled,
+} from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import { getApiServerToken } from '@/lib/database';
+
+const inputClass =
+  'w-28 px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+
+export function ApiServerSettings() {
+  const { data: settings } = useApiServerSettings();
+  const setApiServerSettingsMutation = useSetApiServerSettings();
+  const regenerateApiServerTokenMutation = useRegenerateApiServerToken();
+  const { data: mcpEnabled = false } = useMcpEnabled();
+  const setMcpEnabledMutation = useSetMcpEnabled();
+  const { data: accounts = [] } = useAccounts();
+  const { data: feed } = useIcsFeed();
+  const setIcsFeedCalendarsMutation = useSetIcsFeedCalendars();
+  const regenerateIcsFeedTokenMutation = useRegenerateIcsFeedToken();
+  const { confirm } = useConfirmDialog();
+  const [port, setPort] = useState('');
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (settings) setPort(String(settings.port));
+  }, [settings]);
+
+  const apply = (enabled: boolean) => {
+    setError(null);
+    setApiServerSettingsMutation.mutate(
+      { enabled, port: Number(port) },
+      { onError: (err) => setError(String(err)) },
+    );
+  };
+
+  const copyToken = async () => {
+    setError(null);
+    try {
+      await navigator.clipboard.writeText(await getApiServerToken());
+      setMessage('Token copied');
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleRegenerate = async () => {
+    const confirmed = await confirm({
+      title: 'Regenerate token',
+      message: 'Scripts and apps using the current token have to be given the new one.',
+      confirmLabel: 'Regenerate',
+      destructive: true,
+    });
+    if (!confirmed) return;
+    regenerateApiServerTokenMutation.mutate(undefined, {
+      onSuccess: async (token) => {
+        await navigator.clipboard.writeText(token);
+        setMessage('New token copied');
+      },
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  const toggleFeedCalendar = (calendarId: string, included: boolean) => {
+    const current = feed?.calendarIds ?? [];
+    setIcsFeedCalendarsMutation.mutate(
+      included ? [...current, calendarId] : current.filter((id) => id !== calendarId),
+      { onError: (err) => setError(String(err)) },
+    );
+  };
+
+  const handleRegenerateFeed = async () => {
+    const confirmed = await confirm({
+      title: 'New feed address',
+      message: 'Calendar apps subscribed to the current address stop getting updates.',
+      confirmLabel: 'Change Address',
+      destructive: true,
+    });
+    if (confirmed) {
+      regenerateIcsFeedTokenMutation.mutate(undefined, {
+        onError: (err) => setError(String(err)),
+      });
+    }
+  };
+
+  const portChanged = !!settings && Number(port) !== settings.port;
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">API Server</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          A REST API on localhost for scripts and other apps: GET and POST /tasks, GET, PATCH and
+          DELETE /tasks/:id, GET /lists and POST /sync. Requests need an{' '}
+          <code className="text-xs">Authorization: Bearer</code> header with the token.
+        </p>
+
+        <label className="flex items-center gap-2 text-sm text-surface-700 dark:text-surface-300">
+          <input
+            type="checkbox"
+            checked={settings?.enabled ?? false}
+            onChange={(e) => apply(e.target.checked)}
+            disabled={setApiServerSettingsMutation.isPending}
+            className="rounded border-surface-300"
+          />
+          Run the API server
+          {settings?.enabled && (
+            <span
+              className={`text-xs ${settings.running ? 'text-green-600 dark:text-green-400' : 'text-red-600 dark:text-red-400'}`}
+            >
+              {settings.running ? 'running' : 'not running'}
+            </span>
+          )}
+        </label>
+
+        <div className="flex items-center gap-2">
+          <span className="text-sm text-surface-700 dark:text-surface-300">Port</span>
+          <input
+            type="number"
+            min={1024}
+            max={65535}
+            value={port}
+            onChange={(e) => setPort(e.target.value)}
+            className={inputClass}
+          />
+          {portChanged && (
+            <button
+              type="button"
+              onClick={() => apply(settings?.enabled ?? false)}
+              className={buttonClass}
+            >
+              Apply
+            </button>
+          )}
+        </div>
+        {settings?.enabled && (
+          <p className="text-xs text-surface-500 dark:text-surface-400">
+            http://127.0.0.1:{settings.port}
+          </p>
+        )}
+
+        <div>
+          <label className="flex items-center gap-2 text-sm text-surface-700 dark:text-surface-300">
+            <input
+              type="checkbox"
+              checked={mcpEnabled}
+              onChange={(e) =>
+                setMcpEnabledMutation.mutate(e.target.checked, {
+                  onError: (err) => setError(String(err)),
+                })
+              }
+              className="rounded border-surface-300"
+            />
+            Serve MCP for AI assistants
+          </label>
+          <p className="mt-1 ml-6 text-xs text-surface-500 dark:text-surface-400">
+            Assistants can read and change tasks at http://127.0.0.1:{settings?.port ?? port}/mcp
+            with the same token, while the API server runs.
+          </p>
+        </div>
+
+        <div className="flex gap-2">
+          <button type="button" onClick={copyToken} className={buttonClass}>
+            <Copy className="w-4 h-4" />
+            Copy Token
+          </button>
+          <button
+            type="button"
+            disabled={regenerateApiServerTokenMutation.isPending}
+            onClick={handleRegenerate}
+            className={buttonClass}
+          >
+            <RefreshCw className="w-4 h-4" />
+            Regenerate Token
+          </button>
+        </div>
+        {message && <p className="text-xs text-surface-500 dark:text-surface-400">{message}</p>}
+        {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+      </div>
+
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">Calendar Feed</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          A read-only ICS feed of the due dates in the chosen lists, for calendar apps to
+          subscribe to. It is served by the API server.
+        </p>
+        <div className="space-y-2">
+          {accounts.map((account) =>
+            account.calendars.map((calendar) => (
+              <label
+                key={calendar.id}
+                className="flex items-center gap-2 text-sm text-surface-700 dark:text-surface-300"
+              >
+                <input
+                  type="checkbox"
+                  checked={feed?.calendarIds.includes(calendar.id) ?? false}
+                  onChange={(e) => toggleFeedCalendar(calendar.id, e.target.checked)}
+                  className="rounded border-surface-300"
+                />
+                {calendar.displayName}
+                <span className="text-xs text-surface-400">{account.name}</span>
+              </label>
+            )),
+          )}
+        </div>
+        {feed?.url && (
+          <div className="flex items-center gap-2">
+            <code className="flex-1 truncate text-xs text-surface-600 dark:text-surface-400">
+              {feed.url}
+            </code>
+            <button
+              type="button"
+              onClick={async () => {
+                await navigator.clipboard.writeText(feed.url ?? '');
+                setMessage('Feed address copied');
+              }}
+              className={buttonClass}
+            >
+              <Copy className="w-4 h-4" />
+              Copy
+            </button>
+          </div>
+        )}
+        {feed && !feed.serverRunning && feed.calendarIds.length > 0 && (
+          <p className="text-xs text-amber-600 dark:text-amber-400">
+            Turn on the API server for the feed to be reachable.
+          </p>
+        )}
+        <button
+          type="button"
+          disabled={regenerateIcsFeedTokenMutation.isPending}
+          onClick={handleRegenerateFeed}
+          className={buttonClass}
+        >
+          <RefreshCw className="w-4 h-4" />
+          New Feed Address
+        </button>
+      </div>
+    </div>
+  );
+}
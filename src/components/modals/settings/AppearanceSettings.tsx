@@ -1,11 +1,37 @@
 import Monitor from 'lucide-react/icons/monitor';
 import Moon from 'lucide-react/icons/moon';
 import Sun from 'lucide-react/icons/sun';
+import { useEffect, useState } from 'react';
+import { useSetWindowEffect, useWindowEffect } from '@/hooks/queries';
+import { getSystemAppearance, type WindowEffect } from '@/lib/database';
 import { type Theme, useSettingsStore } from '@/store/settingsStore';
+import { getContrastTextColor } from '@/utils/color';
 import { ACCENT_COLORS } from '@/utils/constants';
 
+const WINDOW_EFFECT_LABELS: Record<WindowEffect, string> = {
+  none: 'None',
+  acrylic: 'Acrylic',
+  mica: 'Mica',
+};
+
 export function AppearanceSettings() {
-  const { theme, setTheme, accentColor, setAccentColor } = useSettingsStore();
+  const {
+    theme,
+    setTheme,
+    accentColor,
+    setAccentColor,
+    systemAccentColor,
+    setSystemAccentColor,
+  } = useSettingsStore();
+  const { data: windowEffect } = useWindowEffect();
+  const setWindowEffectMutation = useSetWindowEffect();
+  const [systemAccent, setSystemAccent] = useState<string | null>(null);
+
+  useEffect(() => {
+    getSystemAppearance()
+      .then((appearance) => setSystemAccent(appearance.accentColor))
+      .catch(() => setSystemAccent(null));
+  }, []);
 
   return (
     <div className="space-y-4">
@@ -45,18 +71,63 @@ export function AppearanceSettings() {
               <button
                 type="button"
                 key={color.value}
-                onClick={() => setAccentColor(color.value)}
+                onClick={() => {
+                  setSystemAccentColor(false);
+                  setAccentColor(color.value);
+                }}
                 title={color.name}
                 className={`w-8 h-8 rounded-full border-2 transition-all ${
-                  accentColor === color.value
+                  !systemAccentColor && accentColor === color.value
                     ? 'border-surface-800 dark:border-white scale-110'
                     : 'border-transparent hover:scale-105'
                 }`}
                 style={{ backgroundColor: color.value }}
               />
             ))}
+            {systemAccent && (
+              <button
+                type="button"
+                onClick={() => {
+                  setSystemAccentColor(true);
+                  setAccentColor(systemAccent);
+                }}
+                title="System"
+                className={`flex items-center justify-center w-8 h-8 rounded-full border-2 transition-all ${
+                  systemAccentColor
+                    ? 'border-surface-800 dark:border-white scale-110'
+                    : 'border-transparent hover:scale-105'
+                }`}
+                style={{ backgroundColor: systemAccent }}
+              >
+                <Monitor className="w-4 h-4" style={{ color: getContrastTextColor(systemAccent) }} />
+              </button>
+            )}
           </div>
         </div>
+
+        {/* platforms without effects only have 'none' */}
+        {windowEffect && windowEffect.supported.length > 1 && (
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-surface-700 dark:text-surface-300">Window effect</p>
+              <p className="text-xs text-surface-500 dark:text-surface-400">
+                Lets the desktop shine through behind the window
+              </p>
+            </div>
+            <select
+              value={windowEffect.effect}
+              disabled={setWindowEffectMutation.isPending}
+              onChange={(e) => setWindowEffectMutation.mutate(e.target.value as WindowEffect)}
+              className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+            >
+              {windowEffect.supported.map((effect) => (
+                <option key={effect} value={effect}>
+                  {WINDOW_EFFECT_LABELS[effect]}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
       </div>
     </div>
   );
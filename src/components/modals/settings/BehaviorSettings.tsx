@@ -1,11 +1,24 @@
 import { relaunch } from '@tauri-apps/plugin-process';
-import { useAccounts } from '@/hooks/queries';
+import {
+  useAccounts,
+  useFetchLinkTitles,
+  useLanguage,
+  useLocaleSettings,
+  useSetFetchLinkTitles,
+  useSetLanguage,
+  useSetStreakInTray,
+  useStreakInTray,
+  useTraySupported,
+} from '@/hooks/queries';
+import type { BackendLanguage } from '@/lib/database';
 import {
   type StartOfWeek,
   type SubtaskDeletionBehavior,
   useSettingsStore,
 } from '@/store/settingsStore';
 
+const WEEKDAYS = ['Sunday', 'Monday', 'Tuesday', 'Wednesday', 'Thursday', 'Friday', 'Saturday'];
+
 export function BehaviorSettings() {
   const {
     confirmBeforeDelete,
@@ -30,6 +43,14 @@ export function BehaviorSettings() {
     setSystemTrayAppliedValue,
   } = useSettingsStore();
   const { data: accounts = [] } = useAccounts();
+  const { data: language } = useLanguage();
+  const { data: locale } = useLocaleSettings();
+  const setLanguageMutation = useSetLanguage();
+  const { data: fetchLinkTitles = false } = useFetchLinkTitles();
+  const setFetchLinkTitlesMutation = useSetFetchLinkTitles();
+  const { data: streakInTray = false } = useStreakInTray();
+  const setStreakInTrayMutation = useSetStreakInTray();
+  const { data: traySupported = true } = useTraySupported();
 
   const systemTrayChanged = enableSystemTray !== systemTrayAppliedValue;
 
@@ -106,6 +127,21 @@ export function BehaviorSettings() {
           />
         </label>
 
+        <label className="flex items-center justify-between">
+          <div>
+            <p className="text-sm text-surface-700 dark:text-surface-300">Fetch link titles</p>
+            <p className="text-xs text-surface-500 dark:text-surface-400">
+              Name untitled tasks after the page their URL points to. The site sees the visit
+            </p>
+          </div>
+          <input
+            type="checkbox"
+            checked={fetchLinkTitles}
+            onChange={(e) => setFetchLinkTitlesMutation.mutate(e.target.checked)}
+            className="rounded border-surface-300"
+          />
+        </label>
+
         <div>
           <div className="mb-2">
             <p className="text-sm text-surface-700 dark:text-surface-300">
@@ -130,6 +166,9 @@ export function BehaviorSettings() {
             <p className="text-sm text-surface-700 dark:text-surface-300">Week starts on</p>
             <p className="text-xs text-surface-500 dark:text-surface-400">
               Choose how dates are shown in calendars
+              {locale &&
+                locale.firstDayOfWeek !== (startOfWeek === 'monday' ? 1 : 0) &&
+                `, the system starts weeks on ${WEEKDAYS[locale.firstDayOfWeek]}`}
             </p>
           </div>
           <select
@@ -142,6 +181,29 @@ export function BehaviorSettings() {
           </select>
         </div>
 
+        <div className="flex items-center justify-between">
+          <div>
+            <p className="text-sm text-surface-700 dark:text-surface-300">System text language</p>
+            <p className="text-xs text-surface-500 dark:text-surface-400">
+              Menus, tray, notifications, reports and error messages
+            </p>
+          </div>
+          <select
+            value={language?.chosen ?? 'system'}
+            disabled={!language || setLanguageMutation.isPending}
+            onChange={(e) =>
+              setLanguageMutation.mutate(
+                e.target.value === 'system' ? null : (e.target.value as BackendLanguage),
+              )
+            }
+            className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+          >
+            <option value="system">Same as the system</option>
+            <option value="en">English</option>
+            <option value="de">Deutsch</option>
+          </select>
+        </div>
+
         <label className="flex items-center justify-between">
           <div>
             <p className="text-sm text-surface-700 dark:text-surface-300">
@@ -194,6 +256,11 @@ export function BehaviorSettings() {
             <p className="text-xs text-surface-500 dark:text-surface-400">
               Show app in system tray. Requires restart.
             </p>
+            {enableSystemTray && !traySupported && (
+              <p className="text-xs text-amber-600 dark:text-amber-400">
+                This desktop has no system tray, so closing the window minimizes it instead.
+              </p>
+            )}
           </div>
           <input
             type="checkbox"
@@ -203,6 +270,25 @@ export function BehaviorSettings() {
           />
         </label>
 
+        {enableSystemTray && (
+          <label className="flex items-center justify-between">
+            <div>
+              <p className="text-sm text-surface-700 dark:text-surface-300">
+                Show streak in tray tooltip
+              </p>
+              <p className="text-xs text-surface-500 dark:text-surface-400">
+                Days in a row with at least one completed task
+              </p>
+            </div>
+            <input
+              type="checkbox"
+              checked={streakInTray}
+              onChange={(e) => setStreakInTrayMutation.mutate(e.target.checked)}
+              className="rounded border-surface-300"
+            />
+          </label>
+        )}
+
         {systemTrayChanged && (
           <div className="flex items-center justify-between rounded-lg bg-blue-50 dark:bg-blue-950 p-3 border border-blue-200 dark:border-blue-800">
             <p className="text-sm text-blue-700 dark:text-blue-300">
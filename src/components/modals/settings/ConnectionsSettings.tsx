@@ -5,6 +5,10 @@ import { useDeleteAccount } from '@/hooks/queries';
 import { useConfirmDialog } from '@/hooks/useConfirmDialog';
 import { useSettingsStore } from '@/store/settingsStore';
 import type { Account } from '@/types';
+import { AccountEncryptionSettings } from './AccountEncryptionSettings';
+import { AccountHealthSettings } from './AccountHealthSettings';
+import { AccountOAuthSettings } from './AccountOAuthSettings';
+import { AccountTlsSettings } from './AccountTlsSettings';
 
 interface ConnectionsSettingsProps {
   accounts: Account[];
@@ -12,7 +16,7 @@ interface ConnectionsSettingsProps {
 
 export function ConnectionsSettings({ accounts }: ConnectionsSettingsProps) {
   const deleteAccountMutation = useDeleteAccount();
-  const { confirm } = useConfirmDialog();
+  const { confirmWithAlternate } = useConfirmDialog();
   const { confirmBeforeDeleteAccount } = useSettingsStore();
   const [expandedAccounts, setExpandedAccounts] = useState<Set<string>>(new Set());
 
@@ -29,20 +33,21 @@ export function ConnectionsSettings({ accounts }: ConnectionsSettingsProps) {
   };
 
   const handleDeleteAccount = async (account: { id: string; name: string }) => {
-    if (confirmBeforeDeleteAccount) {
-      const confirmed = await confirm({
-        title: 'Remove account',
-        subtitle: account.name,
-        message: `Are you sure? All tasks from this account will be removed from the app. They will remain on the server.`,
-        confirmLabel: 'Remove',
-        cancelLabel: 'Cancel',
-        destructive: true,
-      });
-      if (!confirmed) {
-        return;
-      }
+    if (!confirmBeforeDeleteAccount) {
+      deleteAccountMutation.mutate({ id: account.id });
+      return;
     }
-    deleteAccountMutation.mutate(account.id);
+    const result = await confirmWithAlternate({
+      title: 'Remove account',
+      subtitle: account.name,
+      message: `Are you sure? All tasks from this account will be removed from the app. They will remain on the server, or can be kept here as local tasks in the Recovered list.`,
+      confirmLabel: 'Remove',
+      alternateLabel: 'Keep Tasks',
+      cancelLabel: 'Cancel',
+      destructive: true,
+    });
+    if (result === 'cancel') return;
+    deleteAccountMutation.mutate({ id: account.id, keepTasks: result === 'alternate' });
   };
 
   return (
@@ -112,6 +117,24 @@ export function ConnectionsSettings({ accounts }: ConnectionsSettingsProps) {
                       </div>
                     )}
 
+                    <div className="pt-2 border-t border-surface-200 dark:border-surface-600">
+                      <AccountHealthSettings account={account} />
+                    </div>
+
+                    <div className="pt-2 border-t border-surface-200 dark:border-surface-600">
+                      <AccountOAuthSettings account={account} />
+                    </div>
+
+                    <div className="pt-2 border-t border-surface-200 dark:border-surface-600">
+                      <AccountEncryptionSettings account={account} />
+                    </div>
+
+                    {account.serverType !== 'local' && account.serverType !== 'demo' && (
+                      <div className="pt-2 border-t border-surface-200 dark:border-surface-600">
+                        <AccountTlsSettings account={account} />
+                      </div>
+                    )}
+
                     <div className="pt-2 border-t border-surface-200 dark:border-surface-600">
                       <button
                         type="button"
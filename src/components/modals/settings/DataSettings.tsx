@@ -2,14 +2,198 @@ import { open, save } from '@tauri-apps/plugin-dialog';
 import { readTextFile, writeTextFile } from '@tauri-apps/plugin-fs';
 import ChevronDown from 'lucide-react/icons/chevron-down';
 import Download from 'lucide-react/icons/download';
+import Trash2 from 'lucide-react/icons/trash-2';
 import Upload from 'lucide-react/icons/upload';
-import { useState } from 'react';
+import Wrench from 'lucide-react/icons/wrench';
+import { useEffect, useState } from 'react';
+import { useArchivePolicy, useSetArchivePolicy } from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import {
+  type AttachmentCacheUsage,
+  type CleanupReport,
+  cleanupDatabase,
+  clearAttachmentCache,
+  type DbSnapshot,
+  exportAccountConfig,
+  getAttachmentCacheUsage,
+  getDbSnapshots,
+  importAccountConfig,
+  repairOrphans,
+  rollbackToSnapshot,
+  setAttachmentCacheLimit,
+} from '@/lib/database';
 import { useSettingsStore } from '@/store/settingsStore';
 import { downloadFile } from '@/utils/file';
+import { pluralize } from '@/utils/format';
+
+const MB = 1024 * 1024;
+const CACHE_LIMITS = [100 * MB, 250 * MB, 500 * MB, 1024 * MB, 2048 * MB];
+// days after completion a task is archived; 0 keeps everything in the lists
+const ARCHIVE_AFTER_DAYS = [0, 30, 90, 180, 365];
+
+// what a cleanup removes, e.g. "3 tasks, 1 list and 12 unused tags"
+function describeCleanup(report: CleanupReport): string {
+  const counts: [number, string, string?][] = [
+    [report.tasks.length, 'task'],
+    [report.calendars.length, 'list'],
+    [report.pendingDeletions.length, 'queued server deletion'],
+    [report.tags.length, 'unused tag'],
+    [report.cachedObjects, 'cached server copy', 'cached server copies'],
+  ];
+  const parts = counts
+    .filter(([count]) => count > 0)
+    .map(([count, singular, plural]) => `${count} ${pluralize(count, singular, plural)}`);
+  return parts.length > 1
+    ? `${parts.slice(0, -1).join(', ')} and ${parts[parts.length - 1]}`
+    : parts.join('');
+}
+
+function formatSize(bytes: number): string {
+  if (bytes >= 1024 * MB) return `${(bytes / (1024 * MB)).toFixed(1)} GB`;
+  return `${Math.round(bytes / MB)} MB`;
+}
 
 export function DataSettings() {
   const { exportSettings, importSettings } = useSettingsStore();
   const [showIncluded, setShowIncluded] = useState(false);
+  const [cacheUsage, setCacheUsage] = useState<AttachmentCacheUsage | null>(null);
+  const { data: archivePolicy } = useArchivePolicy();
+  const setArchivePolicyMutation = useSetArchivePolicy();
+  const [archived, setArchived] = useState<number | null>(null);
+  const [accountConfigMessage, setAccountConfigMessage] = useState<string | null>(null);
+  const [accountConfigError, setAccountConfigError] = useState<string | null>(null);
+  const [snapshots, setSnapshots] = useState<DbSnapshot[]>([]);
+  const [snapshotError, setSnapshotError] = useState<string | null>(null);
+  const [maintenanceMessage, setMaintenanceMessage] = useState<string | null>(null);
+  const [maintenanceError, setMaintenanceError] = useState<string | null>(null);
+  const { confirm, confirmWithAlternate } = useConfirmDialog();
+
+  const refreshCacheUsage = () => {
+    getAttachmentCacheUsage()
+      .then(setCacheUsage)
+      .catch(() => setCacheUsage(null));
+  };
+
+  useEffect(() => {
+    getAttachmentCacheUsage()
+      .then(setCacheUsage)
+      .catch(() => setCacheUsage(null));
+    getDbSnapshots()
+      .then(setSnapshots)
+      .catch(() => setSnapshots([]));
+  }, []);
+
+  const handleRollback = async (snapshot: DbSnapshot) => {
+    const confirmed = await confirm({
+      title: 'Restore snapshot',
+      subtitle: new Date(snapshot.createdAt * 1000).toLocaleString(),
+      message:
+        'The database goes back to how it was before the update, changes made since then are lost. The app restarts.',
+      confirmLabel: 'Restore',
+      cancelLabel: 'Cancel',
+      destructive: true,
+    });
+    if (!confirmed) return;
+    setSnapshotError(null);
+    try {
+      await rollbackToSnapshot(snapshot.fileName);
+    } catch (e) {
+      setSnapshotError(String(e));
+    }
+  };
+
+  const handleExportAccounts = async () => {
+    setAccountConfigMessage(null);
+    setAccountConfigError(null);
+    try {
+      const path = await save({
+        defaultPath: 'caldav-tasks-accounts.json',
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+      });
+      if (!path) return;
+      await exportAccountConfig(path);
+      setAccountConfigMessage('Accounts exported');
+    } catch (e) {
+      setAccountConfigError(String(e));
+    }
+  };
+
+  const handleImportAccounts = async () => {
+    setAccountConfigMessage(null);
+    setAccountConfigError(null);
+    try {
+      const path = await open({
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+        multiple: false,
+      });
+      if (!path) return;
+      const imported = await importAccountConfig(path as string);
+      const { accounts, calendars, existingAccounts, needsPassword } = imported;
+      let message = `${accounts} ${pluralize(accounts, 'account')} and ${calendars} ${pluralize(calendars, 'list')} set up.`;
+      if (existingAccounts > 0) {
+        message += ` ${existingAccounts} ${pluralize(existingAccounts, 'account was', 'accounts were')} already here.`;
+      }
+      if (needsPassword.length > 0) {
+        message += ' Enter the passwords of the new accounts under Connections before they sync.';
+      }
+      setAccountConfigMessage(message);
+    } catch (e) {
+      setAccountConfigError(String(e));
+    }
+  };
+
+  const handleRepairOrphans = async () => {
+    const result = await confirmWithAlternate({
+      title: 'Repair orphaned tasks',
+      message:
+        'Tasks whose list or account no longer exists can be kept as local tasks, or deleted.',
+      confirmLabel: 'Delete Tasks',
+      alternateLabel: 'Keep Tasks',
+      cancelLabel: 'Cancel',
+      destructive: true,
+    });
+    if (result === 'cancel') return;
+    setMaintenanceMessage(null);
+    setMaintenanceError(null);
+    try {
+      const report = await repairOrphans(result === 'confirm' ? 'delete' : 'reassign');
+      const tasks = report.tasksReassigned + report.tasksDeleted;
+      if (tasks === 0 && report.calendarsRemoved === 0) {
+        setMaintenanceMessage('Nothing to repair.');
+        return;
+      }
+      setMaintenanceMessage(
+        `${tasks} ${pluralize(tasks, 'task')} ${report.tasksDeleted > 0 ? 'deleted' : 'kept as local tasks'}, ${report.calendarsRemoved} ${pluralize(report.calendarsRemoved, 'list')} removed.`,
+      );
+    } catch (e) {
+      setMaintenanceError(String(e));
+    }
+  };
+
+  const handleCleanup = async () => {
+    setMaintenanceMessage(null);
+    setMaintenanceError(null);
+    try {
+      const preview = await cleanupDatabase(false);
+      const removed = describeCleanup(preview);
+      if (!removed) {
+        setMaintenanceMessage('Nothing to clean up.');
+        return;
+      }
+      const confirmed = await confirm({
+        title: 'Clean up database',
+        message: `The database holds ${removed} left behind by removed accounts and lists or not used anymore. They're deleted for good.`,
+        confirmLabel: 'Clean Up',
+        cancelLabel: 'Cancel',
+        destructive: true,
+      });
+      if (!confirmed) return;
+      const report = await cleanupDatabase(true);
+      setMaintenanceMessage(`Removed ${describeCleanup(report)}.`);
+    } catch (e) {
+      setMaintenanceError(String(e));
+    }
+  };
 
   return (
     <div className="space-y-4">
@@ -113,6 +297,203 @@ export function DataSettings() {
           )}
         </div>
       </div>
+
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+            Account Setup
+          </h3>
+          <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+            Export the accounts, their lists and the sync settings to set up another computer.
+            Passwords and tokens are left out.
+          </p>
+          <div className="flex gap-2">
+            <button
+              type="button"
+              onClick={handleExportAccounts}
+              className="flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors"
+            >
+              <Download className="w-4 h-4" />
+              Export Accounts
+            </button>
+            <button
+              type="button"
+              onClick={handleImportAccounts}
+              className="flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors"
+            >
+              <Upload className="w-4 h-4" />
+              Import Accounts
+            </button>
+          </div>
+          {accountConfigMessage && (
+            <p className="mt-2 text-xs text-surface-500 dark:text-surface-400">
+              {accountConfigMessage}
+            </p>
+          )}
+          {accountConfigError && (
+            <p className="mt-2 text-xs text-red-600 dark:text-red-400">{accountConfigError}</p>
+          )}
+        </div>
+      </div>
+
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+            Attachment Cache
+          </h3>
+          <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+            Attachments are kept after they're opened. When the cache is full, the ones opened
+            longest ago are removed.
+          </p>
+          {cacheUsage && (
+            <p className="text-sm text-surface-700 dark:text-surface-300 mb-4">
+              {formatSize(cacheUsage.usedBytes)} of {formatSize(cacheUsage.maxBytes)} used (
+              {cacheUsage.files} {cacheUsage.files === 1 ? 'file' : 'files'})
+            </p>
+          )}
+          <div className="flex items-center justify-between mb-4">
+            <p className="text-sm text-surface-700 dark:text-surface-300">Maximum size</p>
+            <select
+              value={cacheUsage?.maxBytes ?? 500 * MB}
+              onChange={async (e) => {
+                await setAttachmentCacheLimit(Number(e.target.value));
+                refreshCacheUsage();
+              }}
+              className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+            >
+              {CACHE_LIMITS.map((limit) => (
+                <option key={limit} value={limit}>
+                  {formatSize(limit)}
+                </option>
+              ))}
+            </select>
+          </div>
+          <button
+            type="button"
+            onClick={async () => {
+              await clearAttachmentCache();
+              refreshCacheUsage();
+            }}
+            className="flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors"
+          >
+            <Trash2 className="w-4 h-4" />
+            Clear Cache
+          </button>
+        </div>
+      </div>
+
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+            Archive
+          </h3>
+          <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+            Tasks completed long ago are moved out of the lists into the Archive view, where they
+            can be searched and restored. A sync doesn't bring them back.
+          </p>
+          <div className="flex items-center justify-between">
+            <p className="text-sm text-surface-700 dark:text-surface-300">
+              Archive completed tasks
+            </p>
+            <select
+              value={archivePolicy?.afterDays ?? 0}
+              onChange={(e) =>
+                setArchivePolicyMutation.mutate(
+                  { afterDays: Number(e.target.value) },
+                  { onSuccess: setArchived },
+                )
+              }
+              className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300"
+            >
+              {ARCHIVE_AFTER_DAYS.map((days) => (
+                <option key={days} value={days}>
+                  {days === 0 ? 'Never' : `After ${days} days`}
+                </option>
+              ))}
+            </select>
+          </div>
+          {archived !== null && archived > 0 && (
+            <p className="mt-2 text-xs text-surface-500 dark:text-surface-400">
+              {archived} {archived === 1 ? 'task was' : 'tasks were'} archived.
+            </p>
+          )}
+        </div>
+      </div>
+
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+            Database Maintenance
+          </h3>
+          <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+            Tasks can be left behind without a list when their account or list is removed while
+            they're changed, and then don't show up anywhere. Cleaning up also removes tags no task
+            uses anymore.
+          </p>
+          <div className="flex gap-2">
+            <button
+              type="button"
+              onClick={handleRepairOrphans}
+              className="flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors"
+            >
+              <Wrench className="w-4 h-4" />
+              Repair Orphaned Tasks
+            </button>
+            <button
+              type="button"
+              onClick={handleCleanup}
+              className="flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors"
+            >
+              <Trash2 className="w-4 h-4" />
+              Clean Up
+            </button>
+          </div>
+          {maintenanceMessage && (
+            <p className="mt-2 text-xs text-surface-500 dark:text-surface-400">
+              {maintenanceMessage}
+            </p>
+          )}
+          {maintenanceError && (
+            <p className="mt-2 text-xs text-red-600 dark:text-red-400">{maintenanceError}</p>
+          )}
+        </div>
+      </div>
+
+      {snapshots.length > 0 && (
+        <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+          <div>
+            <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+              Database Snapshots
+            </h3>
+            <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+              A copy of the database is kept before an update changes its layout. If tasks went
+              missing after an update, the database can go back to one of them.
+            </p>
+            <ul className="space-y-2">
+              {snapshots.map((snapshot) => (
+                <li key={snapshot.fileName} className="flex items-center justify-between gap-2">
+                  <span className="text-sm text-surface-700 dark:text-surface-300">
+                    {new Date(snapshot.createdAt * 1000).toLocaleString()}
+                    <span className="ml-2 text-xs text-surface-400">
+                      version {snapshot.version}, {(snapshot.size / MB).toFixed(1)} MB
+                    </span>
+                  </span>
+                  <button
+                    type="button"
+                    onClick={() => handleRollback(snapshot)}
+                    className="px-3 py-1.5 text-xs text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 rounded-lg transition-colors"
+                  >
+                    Restore
+                  </button>
+                </li>
+              ))}
+            </ul>
+            {snapshotError && (
+              <p className="mt-2 text-xs text-red-600 dark:text-red-400">{snapshotError}</p>
+            )}
+          </div>
+        </div>
+      )}
     </div>
   );
 }
@@ -0,0 +1,428 @@
+import { formatDistanceToNow } from 'date-fns';
+import RefreshCw from 'lucide-react/icons/refresh-cw';
+import Send from 'lucide-react/icons/send';
+import { useEffect, useState } from 'react';
+import {
+  useAccounts,
+  useEmailDigestSettings,
+  useEmailDigestStatus,
+  useEmailToTaskSettings,
+  useEmailToTaskStatus,
+  usePollEmailNow,
+  useSendEmailDigestNow,
+  useSetEmailDigestSettings,
+  useSetEmailToTaskSettings,
+} from '@/hooks/queries';
+import type { EmailDigestSettings, EmailToTaskSettings } from '@/lib/database';
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const labelClass = 'block text-xs font-medium text-surface-600 dark:text-surface-400 mb-1';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+
+function EmailToTask() {
+  const { data: saved } = useEmailToTaskSettings();
+  const { data: status } = useEmailToTaskStatus();
+  const { data: accounts = [] } = useAccounts();
+  const setEmailToTaskSettingsMutation = useSetEmailToTaskSettings();
+  const pollEmailNowMutation = usePollEmailNow();
+  const [settings, setSettings] = useState<EmailToTaskSettings | null>(null);
+  const [password, setPassword] = useState('');
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (saved) setSettings(saved);
+  }, [saved]);
+
+  if (!settings) return null;
+
+  const update = (changes: Partial<EmailToTaskSettings>) =>
+    setSettings({ ...settings, ...changes });
+
+  const handleSave = () => {
+    setError(null);
+    setMessage(null);
+    setEmailToTaskSettingsMutation.mutate(
+      { settings, password: password || null },
+      {
+        onSuccess: () => {
+          setPassword('');
+          setMessage('Saved');
+        },
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  const handlePoll = () => {
+    setError(null);
+    setMessage(null);
+    pollEmailNowMutation.mutate(undefined, {
+      onSuccess: (count) => setMessage(`${count} new ${count === 1 ? 'task' : 'tasks'}`),
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">Email to Task</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          New mail in an IMAP folder becomes a task, with the subject as the title and the
+          message as the notes.
+        </p>
+
+        <label className="flex items-center gap-2 text-sm text-surface-700 dark:text-surface-300">
+          <input
+            type="checkbox"
+            checked={settings.enabled}
+            onChange={(e) => update({ enabled: e.target.checked })}
+            className="rounded border-surface-300"
+          />
+          Check for new mail
+        </label>
+
+        <div className="grid grid-cols-[1fr_6rem] gap-2">
+          <div>
+            <span className={labelClass}>IMAP server</span>
+            <input
+              type="text"
+              value={settings.host}
+              onChange={(e) => update({ host: e.target.value })}
+              placeholder="imap.example.com"
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <span className={labelClass}>Port (TLS)</span>
+            <input
+              type="number"
+              value={settings.port}
+              onChange={(e) => update({ port: Number(e.target.value) })}
+              className={inputClass}
+            />
+          </div>
+        </div>
+        <div className="grid grid-cols-2 gap-2">
+          <div>
+            <span className={labelClass}>Username</span>
+            <input
+              type="text"
+              value={settings.username}
+              onChange={(e) => update({ username: e.target.value })}
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <span className={labelClass}>Password</span>
+            <input
+              type="password"
+              value={password}
+              onChange={(e) => setPassword(e.target.value)}
+              placeholder="Unchanged"
+              className={inputClass}
+            />
+          </div>
+        </div>
+        <div className="grid grid-cols-2 gap-2">
+          <div>
+            <span className={labelClass}>Folder</span>
+            <input
+              type="text"
+              value={settings.folder}
+              onChange={(e) => update({ folder: e.target.value })}
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <span className={labelClass}>Move processed mail to</span>
+            <input
+              type="text"
+              value={settings.processedFolder ?? ''}
+              onChange={(e) => update({ processedFolder: e.target.value || null })}
+              placeholder="Only mark as read"
+              className={inputClass}
+            />
+          </div>
+        </div>
+        <div className="grid grid-cols-2 gap-2">
+          <div>
+            <span className={labelClass}>Add tasks to</span>
+            <select
+              value={settings.calendarId ?? ''}
+              onChange={(e) => update({ calendarId: e.target.value || null })}
+              className={inputClass}
+            >
+              <option value="">The first list</option>
+              {accounts.map((account) => (
+                <optgroup key={account.id} label={account.name}>
+                  {account.calendars.map((calendar) => (
+                    <option key={calendar.id} value={calendar.id}>
+                      {calendar.displayName}
+                    </option>
+                  ))}
+                </optgroup>
+              ))}
+            </select>
+          </div>
+          <div>
+            <span className={labelClass}>Check every (minutes)</span>
+            <input
+              type="number"
+              min={1}
+              value={settings.intervalMinutes}
+              onChange={(e) => update({ intervalMinutes: Number(e.target.value) })}
+              className={inputClass}
+            />
+          </div>
+        </div>
+
+        <div className="flex gap-2">
+          <button
+            type="button"
+            disabled={setEmailToTaskSettingsMutation.isPending}
+            onClick={handleSave}
+            className={buttonClass}
+          >
+            Save
+          </button>
+          <button
+            type="button"
+            disabled={!saved?.host || pollEmailNowMutation.isPending}
+            onClick={handlePoll}
+            className={buttonClass}
+          >
+            <RefreshCw
+              className={`w-4 h-4 ${pollEmailNowMutation.isPending ? 'animate-spin' : ''}`}
+            />
+            Check Now
+          </button>
+        </div>
+        {status?.lastPollAt && (
+          <p className="text-xs text-surface-500 dark:text-surface-400">
+            Last checked{' '}
+            {formatDistanceToNow(new Date(status.lastPollAt), { addSuffix: true })}.{' '}
+            {status.imported} {status.imported === 1 ? 'task' : 'tasks'} made since the app
+            started.
+          </p>
+        )}
+        {status?.lastError && (
+          <p className="text-xs text-red-600 dark:text-red-400">{status.lastError}</p>
+        )}
+        {message && <p className="text-xs text-surface-500 dark:text-surface-400">{message}</p>}
+        {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+      </div>
+    </div>
+  );
+}
+
+function EmailDigest() {
+  const { data: saved } = useEmailDigestSettings();
+  const { data: status } = useEmailDigestStatus();
+  const setEmailDigestSettingsMutation = useSetEmailDigestSettings();
+  const sendEmailDigestNowMutation = useSendEmailDigestNow();
+  const [settings, setSettings] = useState<EmailDigestSettings | null>(null);
+  const [password, setPassword] = useState('');
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (saved) setSettings(saved);
+  }, [saved]);
+
+  if (!settings) return null;
+
+  const update = (changes: Partial<EmailDigestSettings>) =>
+    setSettings({ ...settings, ...changes });
+
+  const handleSave = () => {
+    setError(null);
+    setMessage(null);
+    setEmailDigestSettingsMutation.mutate(
+      { settings, password: password || null },
+      {
+        onSuccess: () => {
+          setPassword('');
+          setMessage('Saved');
+        },
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  const handleSend = () => {
+    setError(null);
+    setMessage(null);
+    sendEmailDigestNowMutation.mutate(undefined, {
+      onSuccess: () => setMessage('Digest sent'),
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">Email Digest</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          A summary of the tasks completed and still open, mailed every day or week.
+        </p>
+
+        <label className="flex items-center gap-2 text-sm text-surface-700 dark:text-surface-300">
+          <input
+            type="checkbox"
+            checked={settings.enabled}
+            onChange={(e) => update({ enabled: e.target.checked })}
+            className="rounded border-surface-300"
+          />
+          Send the digest
+        </label>
+
+        <div className="grid grid-cols-[1fr_6rem_7rem] gap-2">
+          <div>
+            <span className={labelClass}>SMTP server</span>
+            <input
+              type="text"
+              value={settings.host}
+              onChange={(e) => update({ host: e.target.value })}
+              placeholder="smtp.example.com"
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <span className={labelClass}>Port</span>
+            <input
+              type="number"
+              value={settings.port}
+              onChange={(e) => update({ port: Number(e.target.value) })}
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <span className={labelClass}>Security</span>
+            <select
+              value={settings.security}
+              onChange={(e) =>
+                update({ security: e.target.value as EmailDigestSettings['security'] })
+              }
+              className={inputClass}
+            >
+              <option value="tls">TLS</option>
+              <option value="starttls">STARTTLS</option>
+            </select>
+          </div>
+        </div>
+        <div className="grid grid-cols-2 gap-2">
+          <div>
+            <span className={labelClass}>Username</span>
+            <input
+              type="text"
+              value={settings.username}
+              onChange={(e) => update({ username: e.target.value })}
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <span className={labelClass}>Password</span>
+            <input
+              type="password"
+              value={password}
+              onChange={(e) => setPassword(e.target.value)}
+              placeholder="Unchanged"
+              className={inputClass}
+            />
+          </div>
+        </div>
+        <div className="grid grid-cols-2 gap-2">
+          <div>
+            <span className={labelClass}>From</span>
+            <input
+              type="email"
+              value={settings.from}
+              onChange={(e) => update({ from: e.target.value })}
+              className={inputClass}
+            />
+          </div>
+          <div>
+            <span className={labelClass}>To (comma separated)</span>
+            <input
+              type="text"
+              value={settings.to}
+              onChange={(e) => update({ to: e.target.value })}
+              className={inputClass}
+            />
+          </div>
+        </div>
+        <div className="grid grid-cols-2 gap-2">
+          <div>
+            <span className={labelClass}>Every</span>
+            <select
+              value={settings.frequency}
+              onChange={(e) =>
+                update({ frequency: e.target.value as EmailDigestSettings['frequency'] })
+              }
+              className={inputClass}
+            >
+              <option value="daily">Day</option>
+              <option value="weekly">Week, on its first day</option>
+            </select>
+          </div>
+          <div>
+            <span className={labelClass}>At</span>
+            <select
+              value={settings.hour}
+              onChange={(e) => update({ hour: Number(e.target.value) })}
+              className={inputClass}
+            >
+              {Array.from({ length: 24 }, (_, hour) => (
+                <option key={hour} value={hour}>
+                  {String(hour).padStart(2, '0')}:00
+                </option>
+              ))}
+            </select>
+          </div>
+        </div>
+
+        <div className="flex gap-2">
+          <button
+            type="button"
+            disabled={setEmailDigestSettingsMutation.isPending}
+            onClick={handleSave}
+            className={buttonClass}
+          >
+            Save
+          </button>
+          <button
+            type="button"
+            disabled={!saved?.host || sendEmailDigestNowMutation.isPending}
+            onClick={handleSend}
+            className={buttonClass}
+          >
+            <Send className="w-4 h-4" />
+            Send Now
+          </button>
+        </div>
+        {status?.lastSentAt && (
+          <p className="text-xs text-surface-500 dark:text-surface-400">
+            Last sent {formatDistanceToNow(new Date(status.lastSentAt), { addSuffix: true })}
+          </p>
+        )}
+        {status?.lastError && (
+          <p className="text-xs text-red-600 dark:text-red-400">{status.lastError}</p>
+        )}
+        {message && <p className="text-xs text-surface-500 dark:text-surface-400">{message}</p>}
+        {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+      </div>
+    </div>
+  );
+}
+
+export function EmailSettings() {
+  return (
+    <>
+      <EmailToTask />
+      <EmailDigest />
+    </>
+  );
+}
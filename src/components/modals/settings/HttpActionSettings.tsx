@@ -0,0 +1,54 @@
+import { useState } from 'react';
+import { HttpActionForm, HttpActionItem, newHttpAction } from '@/components/HttpActionForm';
+import { useAccounts, useHttpActions, useTasks } from '@/hooks/queries';
+
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+
+export function HttpActionSettings() {
+  const { data: actions = [] } = useHttpActions();
+  const { data: accounts = [] } = useAccounts();
+  const { data: tasks = [] } = useTasks();
+  const [adding, setAdding] = useState(false);
+
+  const calendars = accounts.flatMap((a) => a.calendars);
+
+  // what the action is attached to, and a task of it to test with
+  const target = (targetId: string, isList: boolean) => {
+    if (isList) {
+      return {
+        label: calendars.find((c) => c.id === targetId)?.displayName ?? 'removed list',
+        task: tasks.find((t) => t.calendarId === targetId),
+      };
+    }
+    const task = tasks.find((t) => t.uid === targetId);
+    return { label: task?.title || 'removed task', task };
+  };
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">HTTP Actions</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          Requests sent when a task, or any task of a list, is completed. Failed requests are
+          retried. Actions for a single task are added in its editor.
+        </p>
+
+        {actions.map((action) => {
+          const { label, task } = target(action.targetId, action.target === 'list');
+          return (
+            <HttpActionItem key={action.id} action={action} label={label} testTaskId={task?.id} />
+          );
+        })}
+
+        {adding ? (
+          <HttpActionForm draft={newHttpAction('list', '')} onDone={() => setAdding(false)} />
+        ) : (
+          <button type="button" onClick={() => setAdding(true)} className={buttonClass}>
+            Add List Action
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
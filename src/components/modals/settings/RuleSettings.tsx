@@ -0,0 +1,497 @@
+import Pencil from 'lucide-react/icons/pencil';
+import Plus from 'lucide-react/icons/plus';
+import Trash2 from 'lucide-react/icons/trash-2';
+import X from 'lucide-react/icons/x';
+import { useState } from 'react';
+import {
+  useAccounts,
+  useDeleteRule,
+  useRules,
+  useSaveRule,
+  useTags,
+  useTestRule,
+} from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import type { Rule, RuleAction, RuleCondition, RuleEvent, RuleTestMatch } from '@/lib/database';
+import type { Priority } from '@/types';
+
+const EVENTS: { value: RuleEvent; label: string }[] = [
+  { value: 'created', label: 'Created here' },
+  { value: 'updated', label: 'Edited here' },
+  { value: 'synced', label: 'Changed on the server' },
+];
+
+const CONDITIONS: { value: RuleCondition['type']; label: string }[] = [
+  { value: 'inList', label: 'Is in list' },
+  { value: 'titleContains', label: 'Title contains' },
+  { value: 'textContains', label: 'Title or notes contain' },
+  { value: 'hasTag', label: 'Has tag' },
+  { value: 'lacksTag', label: "Doesn't have tag" },
+  { value: 'priorityIs', label: 'Priority is' },
+  { value: 'noDueDate', label: 'Has no due date' },
+];
+
+const ACTIONS: { value: RuleAction['type']; label: string }[] = [
+  { value: 'addTag', label: 'Add tag' },
+  { value: 'removeTag', label: 'Remove tag' },
+  { value: 'setPriority', label: 'Set priority' },
+  { value: 'addContext', label: 'Add context' },
+  { value: 'setDueInDays', label: 'Set due in days' },
+];
+
+const PRIORITIES: Priority[] = ['high', 'medium', 'low', 'none'];
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+const smallButtonClass =
+  'flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors disabled:opacity-50';
+
+function newCondition(type: RuleCondition['type']): RuleCondition {
+  switch (type) {
+    case 'inList':
+      return { type, calendarId: '' };
+    case 'titleContains':
+    case 'textContains':
+      return { type, text: '' };
+    case 'hasTag':
+    case 'lacksTag':
+      return { type, tagId: '' };
+    case 'priorityIs':
+      return { type, priority: 'high' };
+    case 'noDueDate':
+      return { type };
+  }
+}
+
+function newAction(type: RuleAction['type']): RuleAction {
+  switch (type) {
+    case 'addTag':
+    case 'removeTag':
+      return { type, tagId: '' };
+    case 'setPriority':
+      return { type, priority: 'high' };
+    case 'addContext':
+      return { type, name: '' };
+    case 'setDueInDays':
+      return { type, days: 1 };
+  }
+}
+
+// the value part of a condition or an action
+function ValueInput({
+  item,
+  onChange,
+}: {
+  item: RuleCondition | RuleAction;
+  onChange: (item: RuleCondition | RuleAction) => void;
+}) {
+  const { data: accounts = [] } = useAccounts();
+  const { data: tags = [] } = useTags();
+
+  if ('calendarId' in item) {
+    return (
+      <select
+        value={item.calendarId}
+        onChange={(e) => onChange({ ...item, calendarId: e.target.value })}
+        className={inputClass}
+      >
+        <option value="">Choose a list</option>
+        {accounts.map((account) => (
+          <optgroup key={account.id} label={account.name}>
+            {account.calendars.map((calendar) => (
+              <option key={calendar.id} value={calendar.id}>
+                {calendar.displayName}
+              </option>
+            ))}
+          </optgroup>
+        ))}
+      </select>
+    );
+  }
+  if ('tagId' in item) {
+    return (
+      <select
+        value={item.tagId}
+        onChange={(e) => onChange({ ...item, tagId: e.target.value })}
+        className={inputClass}
+      >
+        <option value="">Choose a tag</option>
+        {tags.map((tag) => (
+          <option key={tag.id} value={tag.id}>
+            {tag.name}
+          </option>
+        ))}
+      </select>
+    );
+  }
+  if ('priority' in item) {
+    return (
+      <select
+        value={item.priority}
+        onChange={(e) => onChange({ ...item, priority: e.target.value as Priority })}
+        className={inputClass}
+      >
+        {PRIORITIES.map((priority) => (
+          <option key={priority} value={priority}>
+            {priority}
+          </option>
+        ))}
+      </select>
+    );
+  }
+  if ('text' in item) {
+    return (
+      <input
+        type="text"
+        value={item.text}
+        onChange={(e) => onChange({ ...item, text: e.target.value })}
+        className={inputClass}
+      />
+    );
+  }
+  if ('name' in item) {
+    return (
+      <input
+        type="text"
+        value={item.name}
+        onChange={(e) => onChange({ ...item, name: e.target.value })}
+        className={inputClass}
+      />
+    );
+  }
+  if ('days' in item) {
+    return (
+      <input
+        type="number"
+        min={0}
+        value={item.days}
+        onChange={(e) => onChange({ ...item, days: Number(e.target.value) })}
+        className={inputClass}
+      />
+    );
+  }
+  return <div className="w-full" />;
+}
+
+function RuleEditor({ draft, onDone }: { draft: Rule; onDone: () => void }) {
+  const saveRuleMutation = useSaveRule();
+  const testRuleMutation = useTestRule();
+  const [rule, setRule] = useState(draft);
+  const [matches, setMatches] = useState<RuleTestMatch[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSave = () => {
+    setError(null);
+    saveRuleMutation.mutate(
+      { ...rule, name: rule.name.trim() },
+      { onSuccess: onDone, onError: (err) => setError(String(err)) },
+    );
+  };
+
+  const handleTest = () => {
+    setError(null);
+    testRuleMutation.mutate(rule, {
+      onSuccess: setMatches,
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  return (
+    <div className="space-y-3 rounded-lg border border-primary-200 dark:border-primary-800 p-3">
+      <input
+        type="text"
+        value={rule.name}
+        onChange={(e) => setRule({ ...rule, name: e.target.value })}
+        placeholder="Name"
+        className={inputClass}
+      />
+
+      <div className="space-y-1">
+        <div className="text-xs font-medium text-surface-600 dark:text-surface-400">
+          When a task is
+        </div>
+        <div className="flex flex-wrap gap-3">
+          {EVENTS.map((event) => (
+            <label
+              key={event.value}
+              className="flex items-center gap-1.5 text-sm text-surface-700 dark:text-surface-300"
+            >
+              <input
+                type="checkbox"
+                checked={rule.events.includes(event.value)}
+                onChange={(e) =>
+                  setRule({
+                    ...rule,
+                    events: e.target.checked
+                      ? [...rule.events, event.value]
+                      : rule.events.filter((v) => v !== event.value),
+                  })
+                }
+                className="rounded border-surface-300"
+              />
+              {event.label}
+            </label>
+          ))}
+        </div>
+      </div>
+
+      <div className="space-y-1">
+        <div className="text-xs font-medium text-surface-600 dark:text-surface-400">
+          And all of these hold
+        </div>
+        {rule.conditions.map((condition, i) => (
+          // biome-ignore lint/suspicious/noArrayIndexKey: conditions have no id of their own
+          <div key={i} className="flex gap-2">
+            <select
+              value={condition.type}
+              onChange={(e) =>
+                setRule({
+                  ...rule,
+                  conditions: rule.conditions.map((c, j) =>
+                    j === i ? newCondition(e.target.value as RuleCondition['type']) : c,
+                  ),
+                })
+              }
+              className={inputClass}
+            >
+              {CONDITIONS.map((c) => (
+                <option key={c.value} value={c.value}>
+                  {c.label}
+                </option>
+              ))}
+            </select>
+            <ValueInput
+              item={condition}
+              onChange={(changed) =>
+                setRule({
+                  ...rule,
+                  conditions: rule.conditions.map((c, j) =>
+                    j === i ? (changed as RuleCondition) : c,
+                  ),
+                })
+              }
+            />
+            <button
+              type="button"
+              onClick={() =>
+                setRule({ ...rule, conditions: rule.conditions.filter((_, j) => j !== i) })
+              }
+              className={smallButtonClass}
+            >
+              <X className="w-3.5 h-3.5" />
+            </button>
+          </div>
+        ))}
+        <button
+          type="button"
+          onClick={() =>
+            setRule({ ...rule, conditions: [...rule.conditions, newCondition('inList')] })
+          }
+          className={smallButtonClass}
+        >
+          <Plus className="w-3.5 h-3.5" />
+          Condition
+        </button>
+      </div>
+
+      <div className="space-y-1">
+        <div className="text-xs font-medium text-surface-600 dark:text-surface-400">Then</div>
+        {rule.actions.map((action, i) => (
+          // biome-ignore lint/suspicious/noArrayIndexKey: actions have no id of their own
+          <div key={i} className="flex gap-2">
+            <select
+              value={action.type}
+              onChange={(e) =>
+                setRule({
+                  ...rule,
+                  actions: rule.actions.map((a, j) =>
+                    j === i ? newAction(e.target.value as RuleAction['type']) : a,
+                  ),
+                })
+              }
+              className={inputClass}
+            >
+              {ACTIONS.map((a) => (
+                <option key={a.value} value={a.value}>
+                  {a.label}
+                </option>
+              ))}
+            </select>
+            <ValueInput
+              item={action}
+              onChange={(changed) =>
+                setRule({
+                  ...rule,
+                  actions: rule.actions.map((a, j) => (j === i ? (changed as RuleAction) : a)),
+                })
+              }
+            />
+            <button
+              type="button"
+              onClick={() => setRule({ ...rule, actions: rule.actions.filter((_, j) => j !== i) })}
+              className={smallButtonClass}
+            >
+              <X className="w-3.5 h-3.5" />
+            </button>
+          </div>
+        ))}
+        <button
+          type="button"
+          onClick={() => setRule({ ...rule, actions: [...rule.actions, newAction('addTag')] })}
+          className={smallButtonClass}
+        >
+          <Plus className="w-3.5 h-3.5" />
+          Action
+        </button>
+      </div>
+
+      <div className="flex gap-2">
+        <button
+          type="button"
+          disabled={
+            !rule.name.trim() ||
+            rule.events.length === 0 ||
+            rule.actions.length === 0 ||
+            saveRuleMutation.isPending
+          }
+          onClick={handleSave}
+          className={buttonClass}
+        >
+          Save
+        </button>
+        <button
+          type="button"
+          disabled={rule.actions.length === 0 || testRuleMutation.isPending}
+          onClick={handleTest}
+          className={buttonClass}
+        >
+          Test
+        </button>
+        <button type="button" onClick={onDone} className={buttonClass}>
+          Cancel
+        </button>
+      </div>
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+      {matches && (
+        <div className="text-xs text-surface-600 dark:text-surface-400">
+          {matches.length === 0 ? (
+            'No task would change right now.'
+          ) : (
+            <>
+              <div>
+                {matches.length} {matches.length === 1 ? 'task' : 'tasks'} would change:
+              </div>
+              <ul className="mt-1 max-h-32 overflow-y-auto list-disc pl-4">
+                {matches.map((match) => (
+                  <li key={match.taskId} className="truncate">
+                    {match.title || 'Untitled task'}
+                  </li>
+                ))}
+              </ul>
+            </>
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
+
+function RuleCard({ rule, onEdit }: { rule: Rule; onEdit: () => void }) {
+  const saveRuleMutation = useSaveRule();
+  const deleteRuleMutation = useDeleteRule();
+  const { confirm } = useConfirmDialog();
+
+  const handleDelete = async () => {
+    const confirmed = await confirm({
+      title: 'Delete rule',
+      subtitle: rule.name,
+      message: 'The rule is removed. Tasks it already changed stay as they are.',
+      confirmLabel: 'Delete',
+      destructive: true,
+    });
+    if (confirmed) deleteRuleMutation.mutate(rule.id);
+  };
+
+  return (
+    <div className="flex items-center gap-2 rounded-lg border border-surface-200 dark:border-surface-700 p-3">
+      <div className="flex-1 min-w-0">
+        <div className="text-sm font-medium text-surface-800 dark:text-surface-200 truncate">
+          {rule.name}
+        </div>
+        <div className="text-xs text-surface-500 dark:text-surface-400">
+          {rule.conditions.length} {rule.conditions.length === 1 ? 'condition' : 'conditions'},{' '}
+          {rule.actions.length} {rule.actions.length === 1 ? 'action' : 'actions'}
+        </div>
+      </div>
+      <label className="flex items-center gap-1.5 text-xs text-surface-600 dark:text-surface-400">
+        <input
+          type="checkbox"
+          checked={rule.enabled}
+          onChange={(e) => saveRuleMutation.mutate({ ...rule, enabled: e.target.checked })}
+          className="rounded border-surface-300"
+        />
+        Enabled
+      </label>
+      <button type="button" onClick={onEdit} className={smallButtonClass}>
+        <Pencil className="w-3.5 h-3.5" />
+      </button>
+      <button
+        type="button"
+        onClick={handleDelete}
+        className="flex items-center gap-1 px-2 py-1 text-xs text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 rounded-lg transition-colors"
+      >
+        <Trash2 className="w-3.5 h-3.5" />
+      </button>
+    </div>
+  );
+}
+
+export function RuleSettings() {
+  const { data: rules = [] } = useRules();
+  const [editing, setEditing] = useState<Rule | null>(null);
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">Rules</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          Rules change tasks when they are created, edited or synced and match every condition.
+          They run from top to bottom, each seeing what the ones before it did.
+        </p>
+
+        {rules.map((rule) =>
+          editing?.id === rule.id ? (
+            <RuleEditor key={rule.id} draft={editing} onDone={() => setEditing(null)} />
+          ) : (
+            <RuleCard key={rule.id} rule={rule} onEdit={() => setEditing(rule)} />
+          ),
+        )}
+
+        {editing && editing.id === '' ? (
+          <RuleEditor draft={editing} onDone={() => setEditing(null)} />
+        ) : (
+          <button
+            type="button"
+            onClick={() =>
+              setEditing({
+                id: '',
+                name: '',
+                events: ['created'],
+                conditions: [],
+                actions: [newAction('addTag')],
+                enabled: true,
+                sortOrder: 0,
+                createdAt: '',
+              })
+            }
+            className={buttonClass}
+          >
+            Add Rule
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,274 @@
+import { formatDistanceToNow } from 'date-fns';
+import Pencil from 'lucide-react/icons/pencil';
+import Play from 'lucide-react/icons/play';
+import Trash2 from 'lucide-react/icons/trash-2';
+import { useEffect, useState } from 'react';
+import { useDeleteScript, useRunScript, useSaveScript, useScripts } from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import { checkScript, type Script, type ScriptTrigger, type TaskEvent } from '@/lib/database';
+
+const EXAMPLE = `// runs with tasks, lists and tags in scope
+for task in tasks {
+  if task.list == "Inbox" && task.tags.contains("errand") {
+    move_to_list(task.id, "Errands");
+  }
+}`;
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+const smallButtonClass =
+  'flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors disabled:opacity-50';
+
+function triggerLabel(trigger: ScriptTrigger): string {
+  if (trigger.type === 'daily') return `Daily at ${trigger.at}`;
+  if (trigger.type === 'event') return `When a task is ${trigger.event}`;
+  return 'Manual';
+}
+
+interface Draft {
+  id: string | null;
+  name: string;
+  source: string;
+  trigger: ScriptTrigger;
+  enabled: boolean;
+}
+
+function ScriptEditor({ draft, onDone }: { draft: Draft; onDone: () => void }) {
+  const saveScriptMutation = useSaveScript();
+  const [value, setValue] = useState(draft);
+  const [compileError, setCompileError] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  // compile while typing, a little after the last key
+  useEffect(() => {
+    const timeout = setTimeout(() => {
+      checkScript(value.source)
+        .then(() => setCompileError(null))
+        .catch((err) => setCompileError(String(err)));
+    }, 500);
+    return () => clearTimeout(timeout);
+  }, [value.source]);
+
+  const setTriggerType = (type: ScriptTrigger['type']) => {
+    if (type === 'daily') setValue({ ...value, trigger: { type, at: '08:00' } });
+    else if (type === 'event') setValue({ ...value, trigger: { type, event: 'created' } });
+    else setValue({ ...value, trigger: { type } });
+  };
+
+  const handleSave = () => {
+    setError(null);
+    saveScriptMutation.mutate(
+      { ...value, name: value.name.trim() },
+      { onSuccess: onDone, onError: (err) => setError(String(err)) },
+    );
+  };
+
+  return (
+    <div className="space-y-3 rounded-lg border border-primary-200 dark:border-primary-800 p-3">
+      <input
+        type="text"
+        value={value.name}
+        onChange={(e) => setValue({ ...value, name: e.target.value })}
+        placeholder="Name"
+        className={inputClass}
+      />
+      <div className="flex gap-2">
+        <select
+          value={value.trigger.type}
+          onChange={(e) => setTriggerType(e.target.value as ScriptTrigger['type'])}
+          className={inputClass}
+        >
+          <option value="manual">Manual</option>
+          <option value="daily">Daily</option>
+          <option value="event">On a task event</option>
+        </select>
+        {value.trigger.type === 'daily' && (
+          <input
+            type="time"
+            value={value.trigger.at}
+            onChange={(e) => setValue({ ...value, trigger: { type: 'daily', at: e.target.value } })}
+            className={inputClass}
+          />
+        )}
+        {value.trigger.type === 'event' && (
+          <select
+            value={value.trigger.event}
+            onChange={(e) =>
+              setValue({ ...value, trigger: { type: 'event', event: e.target.value as TaskEvent } })
+            }
+            className={inputClass}
+          >
+            <option value="created">Created</option>
+            <option value="completed">Completed</option>
+            <option value="overdue">Overdue</option>
+          </select>
+        )}
+      </div>
+      <textarea
+        value={value.source}
+        onChange={(e) => setValue({ ...value, source: e.target.value })}
+        rows={10}
+        spellCheck={false}
+        className={`${inputClass} font-mono text-xs`}
+      />
+      {compileError && (
+        <p className="text-xs font-mono text-red-600 dark:text-red-400">{compileError}</p>
+      )}
+      <p className="text-xs text-surface-500 dark:text-surface-400">
+        Scripts change tasks with complete, reopen, set_due, clear_due, add_tag and move_to_list.
+        Event scripts get the task in `task`.
+      </p>
+      <div className="flex gap-2">
+        <button
+          type="button"
+          disabled={!value.name.trim() || !!compileError || saveScriptMutation.isPending}
+          onClick={handleSave}
+          className={buttonClass}
+        >
+          Save
+        </button>
+        <button type="button" onClick={onDone} className={buttonClass}>
+          Cancel
+        </button>
+      </div>
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  );
+}
+
+function ScriptCard({ script, onEdit }: { script: Script; onEdit: () => void }) {
+  const saveScriptMutation = useSaveScript();
+  const deleteScriptMutation = useDeleteScript();
+  const runScriptMutation = useRunScript();
+  const { confirm } = useConfirmDialog();
+  const [output, setOutput] = useState<string[] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleRun = () => {
+    setError(null);
+    runScriptMutation.mutate(script.id, {
+      onSuccess: (run) =>
+        setOutput([
+          ...run.output,
+          `Changed ${run.changed} ${run.changed === 1 ? 'task' : 'tasks'}`,
+        ]),
+      onError: (err) => setError(String(err)),
+    });
+  };
+
+  const handleDelete = async () => {
+    const confirmed = await confirm({
+      title: 'Delete script',
+      subtitle: script.name,
+      message: 'The script is removed. Tasks it already changed stay as they are.',
+      confirmLabel: 'Delete',
+      destructive: true,
+    });
+    if (confirmed) deleteScriptMutation.mutate(script.id);
+  };
+
+  return (
+    <div className="space-y-2 rounded-lg border border-surface-200 dark:border-surface-700 p-3">
+      <div className="flex items-center gap-2">
+        <div className="flex-1 min-w-0">
+          <div className="text-sm font-medium text-surface-800 dark:text-surface-200 truncate">
+            {script.name}
+          </div>
+          <div className="text-xs text-surface-500 dark:text-surface-400">
+            {triggerLabel(script.trigger)}
+            {script.lastRunAt &&
+              ` · ran ${formatDistanceToNow(new Date(script.lastRunAt), { addSuffix: true })}`}
+          </div>
+        </div>
+        <label className="flex items-center gap-1.5 text-xs text-surface-600 dark:text-surface-400">
+          <input
+            type="checkbox"
+            checked={script.enabled}
+            onChange={(e) => saveScriptMutation.mutate({ ...script, enabled: e.target.checked })}
+            className="rounded border-surface-300"
+          />
+          Enabled
+        </label>
+      </div>
+      {script.lastError && (
+        <p className="text-xs font-mono text-red-600 dark:text-red-400">{script.lastError}</p>
+      )}
+      <div className="flex gap-1">
+        <button
+          type="button"
+          disabled={runScriptMutation.isPending}
+          onClick={handleRun}
+          className={smallButtonClass}
+        >
+          <Play className="w-3.5 h-3.5" />
+          Run now
+        </button>
+        <button type="button" onClick={onEdit} className={smallButtonClass}>
+          <Pencil className="w-3.5 h-3.5" />
+          Edit
+        </button>
+        <button
+          type="button"
+          onClick={handleDelete}
+          className="flex items-center gap-1 px-2 py-1 text-xs text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 rounded-lg transition-colors"
+        >
+          <Trash2 className="w-3.5 h-3.5" />
+          Delete
+        </button>
+      </div>
+      {output && (
+        <pre className="max-h-40 overflow-auto p-2 text-xs font-mono bg-surface-50 dark:bg-surface-900 text-surface-700 dark:text-surface-300 rounded">
+          {output.join('\n')}
+        </pre>
+      )}
+      {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+    </div>
+  );
+}
+
+export function ScriptSettings() {
+  const { data: scripts = [] } = useScripts();
+  const [editing, setEditing] = useState<Draft | null>(null);
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">Scripts</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          Rhai scripts that change tasks, run by hand, every day at a set time or when a task is
+          created, completed or becomes overdue.
+        </p>
+
+        {scripts.map((script) =>
+          editing?.id === script.id ? (
+            <ScriptEditor key={script.id} draft={editing} onDone={() => setEditing(null)} />
+          ) : (
+            <ScriptCard key={script.id} script={script} onEdit={() => setEditing(script)} />
+          ),
+        )}
+
+        {editing && editing.id === null ? (
+          <ScriptEditor draft={editing} onDone={() => setEditing(null)} />
+        ) : (
+          <button
+            type="button"
+            onClick={() =>
+              setEditing({
+                id: null,
+                name: '',
+                source: EXAMPLE,
+                trigger: { type: 'manual' },
+                enabled: true,
+              })
+            }
+            className={buttonClass}
+          >
+            Add Script
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,172 @@
+import Lock from 'lucide-react/icons/lock';
+import { useState } from 'react';
+import { useAppLockStatus, useReadOnly, useSetAppLock, useSetReadOnly } from '@/hooks/queries';
+import { lockApp } from '@/lib/database';
+
+// seconds without input before the app locks itself; 0 only locks it on launch
+const IDLE_TIMEOUTS = [
+  { value: 0, label: 'Only on launch' },
+  { value: 60, label: 'After 1 minute' },
+  { value: 5 * 60, label: 'After 5 minutes' },
+  { value: 15 * 60, label: 'After 15 minutes' },
+  { value: 60 * 60, label: 'After 1 hour' },
+];
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+
+export function SecuritySettings() {
+  const { data: lock } = useAppLockStatus();
+  const setAppLockMutation = useSetAppLock();
+  const { data: readOnly } = useReadOnly();
+  const setReadOnlyMutation = useSetReadOnly();
+  const [currentPin, setCurrentPin] = useState('');
+  const [newPin, setNewPin] = useState('');
+  const [confirmPin, setConfirmPin] = useState('');
+  const [error, setError] = useState<string | null>(null);
+
+  const enabled = lock?.enabled ?? false;
+  const idleTimeout = lock?.idleTimeout ?? 0;
+
+  const save = (pin: string | null, timeout: number) => {
+    setError(null);
+    setAppLockMutation.mutate(
+      { pin, currentPin: enabled ? currentPin : null, idleTimeout: timeout },
+      {
+        onSuccess: () => {
+          setCurrentPin('');
+          setNewPin('');
+          setConfirmPin('');
+        },
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  const handleSetPin = () => {
+    if (newPin !== confirmPin) {
+      setError("The PINs don't match");
+      return;
+    }
+    save(newPin, idleTimeout);
+  };
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">Security</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+            App Lock
+          </h3>
+          <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+            {enabled
+              ? 'The app asks for the PIN on launch before showing any tasks.'
+              : 'Set a PIN to keep your tasks hidden until it is entered.'}
+          </p>
+
+          <div className="space-y-3">
+            {enabled && (
+              <input
+                type="password"
+                autoComplete="off"
+                value={currentPin}
+                onChange={(e) => setCurrentPin(e.target.value)}
+                placeholder="Current PIN (needed for changes)"
+                className={inputClass}
+              />
+            )}
+            <input
+              type="password"
+              autoComplete="off"
+              value={newPin}
+              onChange={(e) => setNewPin(e.target.value)}
+              placeholder={enabled ? 'New PIN' : 'PIN'}
+              className={inputClass}
+            />
+            <input
+              type="password"
+              autoComplete="off"
+              value={confirmPin}
+              onChange={(e) => setConfirmPin(e.target.value)}
+              placeholder="Repeat the PIN"
+              className={inputClass}
+            />
+            {error && <p className="text-sm text-red-600 dark:text-red-400">{error}</p>}
+            <div className="flex gap-2">
+              <button
+                type="button"
+                disabled={!newPin || setAppLockMutation.isPending}
+                onClick={handleSetPin}
+                className={buttonClass}
+              >
+                {enabled ? 'Change PIN' : 'Set PIN'}
+              </button>
+              {enabled && (
+                <>
+                  <button
+                    type="button"
+                    disabled={!currentPin || setAppLockMutation.isPending}
+                    onClick={() => save(null, idleTimeout)}
+                    className={buttonClass}
+                  >
+                    Remove PIN
+                  </button>
+                  <button
+                    type="button"
+                    onClick={() => lockApp().catch((err) => setError(String(err)))}
+                    className={buttonClass}
+                  >
+                    <Lock className="w-4 h-4" />
+                    Lock Now
+                  </button>
+                </>
+              )}
+            </div>
+          </div>
+        </div>
+
+        {enabled && (
+          <div className="flex items-center justify-between">
+            <p className="text-sm text-surface-700 dark:text-surface-300">Lock automatically</p>
+            <select
+              value={idleTimeout}
+              disabled={!currentPin}
+              title={currentPin ? undefined : 'Enter the current PIN to change this'}
+              // the backend takes the PIN along with the timeout, the current one stays
+              onChange={(e) => save(currentPin, Number(e.target.value))}
+              className="px-3 py-1.5 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300 disabled:opacity-50"
+            >
+              {IDLE_TIMEOUTS.map((timeout) => (
+                <option key={timeout.value} value={timeout.value}>
+                  {timeout.label}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
+      </div>
+
+      <div className="rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <label className="flex items-center justify-between">
+          <div>
+            <p className="text-sm text-surface-700 dark:text-surface-300">Read-only mode</p>
+            <p className="text-xs text-surface-500 dark:text-surface-400">
+              {readOnly?.forced ??
+                'Tasks can be browsed but not changed, e.g. when showing them on a screen.'}
+            </p>
+          </div>
+          <input
+            type="checkbox"
+            checked={readOnly?.enabled ?? false}
+            disabled={!!readOnly?.forced || setReadOnlyMutation.isPending}
+            onChange={(e) => setReadOnlyMutation.mutate(e.target.checked)}
+            className="rounded border-surface-300"
+          />
+        </label>
+      </div>
+    </div>
+  );
+}
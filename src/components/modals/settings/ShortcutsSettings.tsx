@@ -1,7 +1,15 @@
+import { open, save } from '@tauri-apps/plugin-dialog';
+import Download from 'lucide-react/icons/download';
 import Pencil from 'lucide-react/icons/pencil';
 import RotateCcw from 'lucide-react/icons/rotate-ccw';
+import Upload from 'lucide-react/icons/upload';
 import { useState } from 'react';
-import { type KeyboardShortcut, useSettingsStore } from '@/store/settingsStore';
+import { checkShortcuts, exportShortcuts, importShortcuts, saveShortcuts } from '@/lib/database';
+import {
+  type KeyboardShortcut,
+  useSettingsStore,
+  withDefaultShortcuts,
+} from '@/store/settingsStore';
 import { getAltKeyLabel, getMetaKeyLabel, getShiftKeyLabel } from '@/utils/keyboard';
 import { KeyboardShortcutModal } from '../KeyboardShortcutModal';
 
@@ -12,8 +20,10 @@ export function ShortcutsSettings({
 }: {
   onEditingShortcutChange?: (editing: boolean) => void;
 }) {
-  const { keyboardShortcuts, updateShortcut, resetShortcuts } = useSettingsStore();
+  const { keyboardShortcuts, setKeyboardShortcuts, resetShortcuts } = useSettingsStore();
   const [editingShortcut, setEditingShortcut] = useState<KeyboardShortcut | null>(null);
+  // why the last change, import or export didn't go through
+  const [error, setError] = useState<string | null>(null);
 
   const formatShortcut = (shortcut: KeyboardShortcut | Partial<KeyboardShortcut>): string => {
     const parts: string[] = [];
@@ -33,10 +43,63 @@ export function ShortcutsSettings({
     return parts.join(' + ') || 'Press keys...';
   };
 
-  const handleSave = (id: string, updates: Partial<KeyboardShortcut>) => {
-    updateShortcut(id, updates);
+  const describe = (id: string) => keyboardShortcuts.find((s) => s.id === id)?.description ?? id;
+
+  // keys taken by another shortcut, the app menu or the system leave the shortcut as it was
+  const handleSave = async (id: string, updates: Partial<KeyboardShortcut>) => {
     setEditingShortcut(null);
     onEditingShortcutChange?.(false);
+    const shortcuts = keyboardShortcuts.map((s) => (s.id === id ? { ...s, ...updates } : s));
+    try {
+      const conflict = (await checkShortcuts(shortcuts)).find((c) => c.id === id);
+      if (conflict) {
+        const owner = conflict.reserved ? conflict.conflictsWith : describe(conflict.conflictsWith);
+        setError(`These keys are already used by "${owner}".`);
+        return;
+      }
+      await saveShortcuts(shortcuts);
+      setKeyboardShortcuts(shortcuts);
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleReset = async () => {
+    try {
+      await saveShortcuts(null);
+      resetShortcuts();
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleExport = async () => {
+    try {
+      const path = await save({
+        defaultPath: 'caldav-tasks-shortcuts.json',
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+      });
+      if (path) await exportShortcuts(path, keyboardShortcuts);
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleImport = async () => {
+    try {
+      const path = await open({
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+        multiple: false,
+      });
+      if (!path) return;
+      setKeyboardShortcuts(withDefaultShortcuts(await importShortcuts(path)));
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
   };
 
   const handleOpenEdit = (shortcut: KeyboardShortcut) => {
@@ -56,17 +119,39 @@ export function ShortcutsSettings({
           <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">
             Keyboard Shortcuts
           </h3>
-          <button
-            type="button"
-            onClick={resetShortcuts}
-            className="flex items-center gap-1 px-2 py-1 text-xs text-surface-500 hover:text-surface-700 dark:hover:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 rounded transition-colors"
-            title="Reset to defaults"
-          >
-            <RotateCcw className="w-3 h-3" />
-            Reset to Defaults
-          </button>
+          <div className="flex items-center gap-1">
+            <button
+              type="button"
+              onClick={handleImport}
+              className="flex items-center gap-1 px-2 py-1 text-xs text-surface-500 hover:text-surface-700 dark:hover:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 rounded transition-colors"
+              title="Use the shortcuts of a profile file"
+            >
+              <Upload className="w-3 h-3" />
+              Import
+            </button>
+            <button
+              type="button"
+              onClick={handleExport}
+              className="flex items-center gap-1 px-2 py-1 text-xs text-surface-500 hover:text-surface-700 dark:hover:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 rounded transition-colors"
+              title="Save the shortcuts to a profile file"
+            >
+              <Download className="w-3 h-3" />
+              Export
+            </button>
+            <button
+              type="button"
+              onClick={handleReset}
+              className="flex items-center gap-1 px-2 py-1 text-xs text-surface-500 hover:text-surface-700 dark:hover:text-surface-300 hover:bg-surface-100 dark:hover:bg-surface-700 rounded transition-colors"
+              title="Reset to defaults"
+            >
+              <RotateCcw className="w-3 h-3" />
+              Reset to Defaults
+            </button>
+          </div>
         </div>
 
+        {error && <p className="text-sm text-red-600 dark:text-red-400">{error}</p>}
+
         <div className="rounded-lg border border-surface-200 dark:border-surface-700 overflow-hidden">
           {keyboardShortcuts.map((shortcut) => (
             <div
@@ -1,8 +1,71 @@
+import { useEffect, useState } from 'react';
+import { useAccounts, useSetSyncSchedule, useSyncSchedules } from '@/hooks/queries';
+import { getPayloadLimits, type SyncSchedule, setPayloadLimits } from '@/lib/database';
 import { useSettingsStore } from '@/store/settingsStore';
 
+const selectClass =
+  'px-2 py-1 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300';
+
+// minutes between syncs an account can have, next to the app-wide interval and by hand
+const ACCOUNT_INTERVALS = [5, 15, 30, 60, 120, 240];
+
+// the largest task that is fetched or uploaded, in KB
+const MAX_OBJECT_SIZES = [256, 512, 1024, 2048, 5120, 10240];
+
+function formatInterval(minutes: number): string {
+  if (minutes < 60) return `Every ${minutes} minutes`;
+  return minutes === 60 ? 'Every hour' : `Every ${minutes / 60} hours`;
+}
+
 export function SyncSettings() {
-  const { autoSync, setAutoSync, syncInterval, setSyncInterval, syncOnStartup, setSyncOnStartup } =
-    useSettingsStore();
+  const {
+    autoSync,
+    setAutoSync,
+    syncInterval,
+    setSyncInterval,
+    syncOnStartup,
+    setSyncOnStartup,
+    syncTrackedTime,
+    setSyncTrackedTime,
+  } = useSettingsStore();
+  const { data: accounts = [] } = useAccounts();
+  const { data: schedules = [] } = useSyncSchedules();
+  const setSyncScheduleMutation = useSetSyncSchedule();
+  const [error, setError] = useState<string | null>(null);
+  const [maxObjectKb, setMaxObjectKb] = useState<number | null>(null);
+  const [limitError, setLimitError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getPayloadLimits()
+      .then((limits) => setMaxObjectKb(limits.maxObjectKb))
+      .catch(() => setMaxObjectKb(null));
+  }, []);
+
+  const updateMaxObjectKb = (value: number) => {
+    setLimitError(null);
+    setPayloadLimits({ maxObjectKb: value })
+      .then(() => setMaxObjectKb(value))
+      .catch((err) => setLimitError(String(err)));
+  };
+
+  // local and demo accounts have no server to sync with
+  const syncedAccounts = accounts.filter(
+    (account) => account.serverType !== 'local' && account.serverType !== 'demo',
+  );
+
+  const updateSchedule = (accountId: string, changes: Partial<SyncSchedule>) => {
+    const current = schedules.find((s) => s.accountId === accountId) ?? {
+      accountId,
+      interval: null,
+      quietStart: null,
+      quietEnd: null,
+    };
+    setError(null);
+    setSyncScheduleMutation.mutate(
+      { ...current, ...changes },
+      { onError: (err) => setError(String(err)) },
+    );
+  };
 
   return (
     <div className="space-y-4">
@@ -56,7 +119,131 @@ export function SyncSettings() {
             className="rounded border-surface-300 dark:border-surface-600"
           />
         </label>
+
+        <label className="flex items-center justify-between">
+          <div>
+            <span className="text-sm text-surface-700 dark:text-surface-300">
+              Sync tracked time
+            </span>
+            <p className="text-xs text-surface-500 dark:text-surface-400">
+              Store time entries with each task so other devices keep them
+            </p>
+          </div>
+          <input
+            type="checkbox"
+            checked={syncTrackedTime}
+            onChange={(e) => setSyncTrackedTime(e.target.checked)}
+            className="rounded border-surface-300 dark:border-surface-600"
+          />
+        </label>
+
+        {maxObjectKb !== null && (
+          <div className="flex items-center justify-between gap-4">
+            <div>
+              <span className="text-sm text-surface-700 dark:text-surface-300">Largest task</span>
+              <p className="text-xs text-surface-500 dark:text-surface-400">
+                Larger tasks, e.g. with huge descriptions, are skipped so the rest still syncs
+              </p>
+            </div>
+            <select
+              value={maxObjectKb}
+              onChange={(e) => updateMaxObjectKb(Number(e.target.value))}
+              className={selectClass}
+            >
+              {[...new Set([...MAX_OBJECT_SIZES, maxObjectKb])]
+                .sort((a, b) => a - b)
+                .map((kb) => (
+                  <option key={kb} value={kb}>
+                    {kb >= 1024 ? `${kb / 1024} MB` : `${kb} KB`}
+                  </option>
+                ))}
+            </select>
+          </div>
+        )}
+        {limitError && <p className="text-xs text-red-600 dark:text-red-400">{limitError}</p>}
       </div>
+
+      {autoSync && syncedAccounts.length > 0 && (
+        <>
+          <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">
+            Per Account
+          </h3>
+          <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+            <p className="text-xs text-surface-500 dark:text-surface-400">
+              An account can sync more or less often than the others, and leave out some hours
+              of the day, e.g. a work server at night.
+            </p>
+            {syncedAccounts.map((account) => {
+              const schedule = schedules.find((s) => s.accountId === account.id);
+              const quiet = !!schedule?.quietStart && !!schedule.quietEnd;
+              return (
+                <div key={account.id} className="space-y-2">
+                  <div className="flex items-center justify-between gap-2">
+                    <span className="text-sm text-surface-700 dark:text-surface-300 truncate">
+                      {account.name}
+                    </span>
+                    <select
+                      value={schedule?.interval?.toString() ?? ''}
+                      onChange={(e) =>
+                        updateSchedule(account.id, {
+                          interval: e.target.value === '' ? null : Number(e.target.value),
+                        })
+                      }
+                      className={selectClass}
+                    >
+                      <option value="">Like the others</option>
+                      {ACCOUNT_INTERVALS.map((minutes) => (
+                        <option key={minutes} value={minutes}>
+                          {formatInterval(minutes)}
+                        </option>
+                      ))}
+                      <option value="0">Only by hand</option>
+                    </select>
+                  </div>
+                  {schedule?.interval !== 0 && (
+                    <div className="flex items-center gap-2 text-xs text-surface-600 dark:text-surface-400">
+                      <label className="flex items-center gap-2">
+                        <input
+                          type="checkbox"
+                          checked={quiet}
+                          onChange={(e) =>
+                            updateSchedule(
+                              account.id,
+                              e.target.checked
+                                ? { quietStart: '22:00', quietEnd: '07:00' }
+                                : { quietStart: null, quietEnd: null },
+                            )
+                          }
+                          className="rounded border-surface-300 dark:border-surface-600"
+                        />
+                        Not from
+                      </label>
+                      <input
+                        type="time"
+                        value={schedule?.quietStart ?? '22:00'}
+                        required
+                        disabled={!quiet}
+                        onChange={(e) => updateSchedule(account.id, { quietStart: e.target.value })}
+                        className={selectClass}
+                      />
+                      to
+                      <input
+                        type="time"
+                        value={schedule?.quietEnd ?? '07:00'}
+                        required
+                        disabled={!quiet}
+                        onChange={(e) => updateSchedule(account.id, { quietEnd: e.target.value })}
+                        className={selectClass}
+                      />
+                    </div>
+                  )}
+                </div>
+              );
+            })}
+            {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+          </div>
+        </>
+      )}
     </div>
   );
 }
@@ -0,0 +1,404 @@
+import { save } from '@tauri-apps/plugin-dialog';
+import { writeTextFile } from '@tauri-apps/plugin-fs';
+import { format } from 'date-fns';
+import ChevronDown from 'lucide-react/icons/chevron-down';
+import Copy from 'lucide-react/icons/copy';
+import FileArchive from 'lucide-react/icons/file-archive';
+import FolderOpen from 'lucide-react/icons/folder-open';
+import Gauge from 'lucide-react/icons/gauge';
+import RefreshCw from 'lucide-react/icons/refresh-cw';
+import Save from 'lucide-react/icons/save';
+import Trash2 from 'lucide-react/icons/trash-2';
+import { useState } from 'react';
+import {
+  useClearWireTrace,
+  useLogLevel,
+  useSetLogLevel,
+  useSetWireTraceEnabled,
+  useWireTrace,
+  useWireTraceEnabled,
+} from '@/hooks/queries';
+import {
+  type BenchmarkReport,
+  exportDiagnostics,
+  type LatencyStats,
+  type LogLevel,
+  openLogFolder,
+  runBenchmark,
+  type WireTraceEntry,
+} from '@/lib/database';
+
+const LOG_LEVELS: { value: LogLevel; label: string }[] = [
+  { value: 'off', label: 'Off' },
+  { value: 'error', label: 'Errors' },
+  { value: 'warn', label: 'Warnings' },
+  { value: 'info', label: 'Info' },
+  { value: 'debug', label: 'Debug' },
+  { value: 'trace', label: 'Everything (trace)' },
+];
+
+const selectClass =
+  'px-2 py-1 text-sm border border-surface-200 dark:border-surface-600 bg-white dark:bg-surface-700 text-surface-800 dark:text-surface-200 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+const smallButtonClass =
+  'flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors disabled:opacity-50';
+
+const headerLines = (headers: Record<string, string>) =>
+  Object.entries(headers).map(([name, value]) => `${name}: ${value}`);
+
+/**
+ * one recorded request; the headers and bodies show when it's opened
+ */
+function WireTraceItem({ entry }: { entry: WireTraceEntry }) {
+  const [open, setOpen] = useState(false);
+
+  return (
+    <li className="rounded border border-surface-200 dark:border-surface-700">
+      <button
+        type="button"
+        onClick={() => setOpen(!open)}
+        className="w-full flex items-center gap-2 px-2 py-1 text-left text-xs"
+      >
+        <ChevronDown
+          className={`w-3 h-3 flex-shrink-0 transition-transform ${open ? 'rotate-180' : ''}`}
+        />
+        <span className="font-mono text-surface-700 dark:text-surface-300">{entry.method}</span>
+        <span className="flex-1 truncate text-surface-500 dark:text-surface-400">{entry.url}</span>
+        <span
+          className={
+            entry.error || (entry.status ?? 0) >= 400
+              ? 'text-red-600 dark:text-red-400'
+              : 'text-surface-500 dark:text-surface-400'
+          }
+        >
+          {entry.status ?? 'failed'}
+        </span>
+        {entry.durationMs !== null && (
+          <span className="text-surface-400 tabular-nums">{entry.durationMs} ms</span>
+        )}
+        <span className="text-surface-400">{format(new Date(entry.timestamp), 'HH:mm:ss')}</span>
+      </button>
+      {open && (
+        <pre className="max-h-64 overflow-auto border-t border-surface-200 dark:border-surface-700 p-2 text-xs font-mono whitespace-pre-wrap break-all text-surface-600 dark:text-surface-400">
+          {[
+            ...headerLines(entry.requestHeaders),
+            '',
+            entry.requestBody ?? '',
+            '',
+            entry.error ?? `HTTP ${entry.status}`,
+            ...headerLines(entry.responseHeaders),
+            '',
+            entry.responseBody ?? '',
+          ].join('\n')}
+        </pre>
+      )}
+    </li>
+  );
+}
+
+const formatMs = (value: number) => `${value.toFixed(value < 10 ? 2 : 0)} ms`;
+
+function LatencyRow({ stats }: { stats: LatencyStats }) {
+  return (
+    <tr>
+      <td className="py-0.5 pr-4 text-surface-600 dark:text-surface-400">{stats.name}</td>
+      <td className="py-0.5 pr-4 text-right tabular-nums">{formatMs(stats.medianMs)}</td>
+      <td className="py-0.5 text-right tabular-nums">{formatMs(stats.p95Ms)}</td>
+    </tr>
+  );
+}
+
+/**
+ * the timings of a benchmark run, medians and 95th percentiles
+ */
+function BenchmarkResults({ report }: { report: BenchmarkReport }) {
+  const { sync } = report;
+
+  return (
+    <div className="mt-3 space-y-2 text-xs text-surface-700 dark:text-surface-300">
+      <p className="text-surface-500 dark:text-surface-400">
+        {report.taskCount} tasks, finished in {formatMs(report.totalMs)}
+      </p>
+      <table>
+        <thead>
+          <tr className="text-surface-500 dark:text-surface-400">
+            <th className="pr-4 text-left font-medium">Measurement</th>
+            <th className="pr-4 text-right font-medium">Median</th>
+            <th className="text-right font-medium">95%</th>
+          </tr>
+        </thead>
+        <tbody>
+          {report.database.map((stats) => (
+            <LatencyRow key={stats.name} stats={stats} />
+          ))}
+          <LatencyRow stats={report.serialization.serialize} />
+          <LatencyRow stats={report.serialization.deserialize} />
+        </tbody>
+      </table>
+      <p>
+        Sync: {sync.objects} tasks in {formatMs(sync.durationMs)},{' '}
+        {Math.round(sync.objectsPerSec)} tasks/s and {sync.megabytesPerSec.toFixed(1)} MB/s
+      </p>
+    </div>
+  );
+}
+
+/**
+ * what helps to find out why something doesn't work: the log of the backend, a
+ * diagnostics bundle to attach to bug reports, a trace of what syncs sent and got back
+ * and a benchmark for when the app is slow
+ */
+export function TroubleshootingSettings() {
+  const { data: logLevel } = useLogLevel();
+  const setLogLevelMutation = useSetLogLevel();
+  const [error, setError] = useState<string | null>(null);
+  const [isExporting, setIsExporting] = useState(false);
+  const [exportMessage, setExportMessage] = useState<string | null>(null);
+  const { data: wireTraceEnabled = false } = useWireTraceEnabled();
+  const setWireTraceEnabledMutation = useSetWireTraceEnabled();
+  const { data: wireTrace = [], refetch: refetchWireTrace } = useWireTrace(wireTraceEnabled);
+  const clearWireTraceMutation = useClearWireTrace();
+  const [benchmark, setBenchmark] = useState<BenchmarkReport | null>(null);
+  const [isBenchmarking, setIsBenchmarking] = useState(false);
+
+  const handleOpenLogFolder = () => {
+    setError(null);
+    openLogFolder().catch((err) => setError(String(err)));
+  };
+
+  const handleExportDiagnostics = async () => {
+    setError(null);
+    setExportMessage(null);
+    try {
+      const path = await save({
+        defaultPath: 'caldav-tasks-diagnostics.zip',
+        filters: [{ name: 'Zip', extensions: ['zip'] }],
+      });
+      if (!path) return;
+      setIsExporting(true);
+      await exportDiagnostics(path);
+      setExportMessage('Diagnostics saved');
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  const handleSaveWireTrace = async () => {
+    setError(null);
+    try {
+      const path = await save({
+        defaultPath: 'caldav-tasks-wire-trace.json',
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+      });
+      if (path) await writeTextFile(path, JSON.stringify(wireTrace, null, 2));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleRunBenchmark = async () => {
+    setError(null);
+    setIsBenchmarking(true);
+    try {
+      setBenchmark(await runBenchmark());
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsBenchmarking(false);
+    }
+  };
+
+  const handleSaveBenchmark = async () => {
+    if (!benchmark) return;
+    setError(null);
+    try {
+      const path = await save({
+        defaultPath: `caldav-tasks-benchmark-${benchmark.appVersion}.json`,
+        filters: [{ name: 'JSON', extensions: ['json'] }],
+      });
+      if (path) await writeTextFile(path, JSON.stringify(benchmark, null, 2));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleCopyBenchmark = () => {
+    if (!benchmark) return;
+    navigator.clipboard
+      .writeText(JSON.stringify(benchmark, null, 2))
+      .catch((err) => setError(String(err)));
+  };
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">
+        Troubleshooting
+      </h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">Log</h3>
+          <div className="space-y-3">
+            <div className="flex items-center justify-between gap-4">
+              <div>
+                <span className="text-sm text-surface-700 dark:text-surface-300">Log level</span>
+                <p className="text-xs text-surface-500 dark:text-surface-400">
+                  Passwords and tokens are left out of the log at every level
+                </p>
+              </div>
+              {logLevel && (
+                <select
+                  value={logLevel}
+                  onChange={(e) =>
+                    setLogLevelMutation.mutate(e.target.value as LogLevel, {
+                      onError: (err) => setError(String(err)),
+                    })
+                  }
+                  className={selectClass}
+                >
+                  {LOG_LEVELS.map(({ value, label }) => (
+                    <option key={value} value={value}>
+                      {label}
+                    </option>
+                  ))}
+                </select>
+              )}
+            </div>
+            <button type="button" onClick={handleOpenLogFolder} className={buttonClass}>
+              <FolderOpen className="w-4 h-4" />
+              Open Log Folder
+            </button>
+          </div>
+        </div>
+
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+            Diagnostics
+          </h3>
+          <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+            One file with the log, the app and system versions, the servers of the accounts and
+            the latest syncs, to attach to a bug report. Passwords and tokens aren't included.
+          </p>
+          <button
+            type="button"
+            disabled={isExporting}
+            onClick={handleExportDiagnostics}
+            className={buttonClass}
+          >
+            <FileArchive className="w-4 h-4" />
+            {isExporting ? 'Exporting...' : 'Export Diagnostics'}
+          </button>
+          {exportMessage && (
+            <p className="mt-2 text-xs text-surface-500 dark:text-surface-400">{exportMessage}</p>
+          )}
+        </div>
+
+        <div>
+          <label className="flex items-center justify-between">
+            <div>
+              <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200">
+                Sync Trace
+              </h3>
+              <p className="text-xs text-surface-500 dark:text-surface-400">
+                Record what syncs send to the servers and what comes back, without passwords and
+                tokens. Turning it off drops the recording.
+              </p>
+            </div>
+            <input
+              type="checkbox"
+              checked={wireTraceEnabled}
+              onChange={(e) =>
+                setWireTraceEnabledMutation.mutate(e.target.checked, {
+                  onError: (err) => setError(String(err)),
+                })
+              }
+              className="rounded border-surface-300"
+            />
+          </label>
+          {wireTraceEnabled && (
+            <div className="mt-3 space-y-2">
+              <div className="flex gap-1">
+                <button
+                  type="button"
+                  onClick={() => refetchWireTrace()}
+                  className={smallButtonClass}
+                >
+                  <RefreshCw className="w-3.5 h-3.5" />
+                  Refresh
+                </button>
+                <button
+                  type="button"
+                  disabled={wireTrace.length === 0}
+                  onClick={handleSaveWireTrace}
+                  className={smallButtonClass}
+                >
+                  <Save className="w-3.5 h-3.5" />
+                  Save
+                </button>
+                <button
+                  type="button"
+                  disabled={wireTrace.length === 0 || clearWireTraceMutation.isPending}
+                  onClick={() => clearWireTraceMutation.mutate()}
+                  className={smallButtonClass}
+                >
+                  <Trash2 className="w-3.5 h-3.5" />
+                  Clear
+                </button>
+              </div>
+              {wireTrace.length === 0 ? (
+                <p className="text-xs text-surface-500 dark:text-surface-400">
+                  Nothing recorded yet, sync to record the requests.
+                </p>
+              ) : (
+                <ul className="space-y-1">
+                  {[...wireTrace].reverse().map((entry, i) => (
+                    // biome-ignore lint/suspicious/noArrayIndexKey: entries have no id; counted from the oldest so new ones don't shift them
+                    <WireTraceItem key={wireTrace.length - i} entry={entry} />
+                  ))}
+                </ul>
+              )}
+            </div>
+          )}
+        </div>
+
+        <div>
+          <h3 className="text-sm font-medium text-surface-800 dark:text-surface-200 mb-3">
+            Benchmark
+          </h3>
+          <p className="text-sm text-surface-500 dark:text-surface-400 mb-4">
+            Time the database queries, a sync against a test server on this computer and the
+            conversion of tasks. Nothing is sent to your servers. Attach the report to performance
+            issues, or compare it across releases.
+          </p>
+          <div className="flex gap-2">
+            <button
+              type="button"
+              disabled={isBenchmarking}
+              onClick={handleRunBenchmark}
+              className={buttonClass}
+            >
+              <Gauge className="w-4 h-4" />
+              {isBenchmarking ? 'Running...' : 'Run Benchmark'}
+            </button>
+            {benchmark && (
+              <>
+                <button type="button" onClick={handleSaveBenchmark} className={buttonClass}>
+                  <Save className="w-4 h-4" />
+                  Save Report
+                </button>
+                <button type="button" onClick={handleCopyBenchmark} className={buttonClass}>
+                  <Copy className="w-4 h-4" />
+                  Copy
+                </button>
+              </>
+            )}
+          </div>
+          {benchmark && <BenchmarkResults report={benchmark} />}
+        </div>
+        {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,259 @@
+import { formatDistanceToNow } from 'date-fns';
+import ChevronDown from 'lucide-react/icons/chevron-down';
+import Copy from 'lucide-react/icons/copy';
+import Send from 'lucide-react/icons/send';
+import Trash2 from 'lucide-react/icons/trash-2';
+import { useState } from 'react';
+import {
+  useDeleteWebhook,
+  useSaveWebhook,
+  useTestWebhook,
+  useWebhookDeliveries,
+  useWebhooks,
+} from '@/hooks/queries';
+import { useConfirmDialog } from '@/hooks/useConfirmDialog';
+import { getWebhookSecret, type TaskEvent, type Webhook } from '@/lib/database';
+
+const EVENTS: { value: TaskEvent; label: string }[] = [
+  { value: 'created', label: 'Created' },
+  { value: 'completed', label: 'Completed' },
+  { value: 'overdue', label: 'Overdue' },
+];
+
+const inputClass =
+  'w-full px-3 py-1.5 text-sm text-surface-800 dark:text-surface-200 bg-white dark:bg-surface-700 border border-surface-200 dark:border-surface-600 rounded-lg focus:outline-none focus:border-primary-300';
+const buttonClass =
+  'flex items-center gap-2 px-3 py-2 text-sm bg-surface-100 dark:bg-surface-700 hover:bg-surface-200 dark:hover:bg-surface-600 text-surface-700 dark:text-surface-300 rounded-lg transition-colors disabled:opacity-50';
+const smallButtonClass =
+  'flex items-center gap-1 px-2 py-1 text-xs text-surface-600 dark:text-surface-400 hover:bg-surface-100 dark:hover:bg-surface-700 rounded-lg transition-colors disabled:opacity-50';
+
+function EventCheckboxes({
+  events,
+  onChange,
+}: {
+  events: TaskEvent[];
+  onChange: (events: TaskEvent[]) => void;
+}) {
+  return (
+    <div className="flex gap-3">
+      {EVENTS.map((event) => (
+        <label
+          key={event.value}
+          className="flex items-center gap-1.5 text-sm text-surface-700 dark:text-surface-300"
+        >
+          <input
+            type="checkbox"
+            checked={events.includes(event.value)}
+            onChange={(e) =>
+              onChange(
+                e.target.checked
+                  ? [...events, event.value]
+                  : events.filter((v) => v !== event.value),
+              )
+            }
+            className="rounded border-surface-300"
+          />
+          {event.label}
+        </label>
+      ))}
+    </div>
+  );
+}
+
+function Deliveries({ webhookId }: { webhookId: string }) {
+  const { data: deliveries = [] } = useWebhookDeliveries(webhookId);
+
+  if (deliveries.length === 0) {
+    return <p className="text-xs text-surface-500 dark:text-surface-400">Nothing sent yet.</p>;
+  }
+  return (
+    <ul className="space-y-1 text-xs">
+      {deliveries.map((delivery) => (
+        <li key={delivery.id} className="flex items-baseline gap-2">
+          <span
+            className={
+              delivery.status === 'delivered'
+                ? 'text-green-600 dark:text-green-400'
+                : delivery.status === 'failed'
+                  ? 'text-red-600 dark:text-red-400'
+                  : 'text-amber-600 dark:text-amber-400'
+            }
+          >
+            {delivery.status}
+          </span>
+          <span className="text-surface-700 dark:text-surface-300">{delivery.event}</span>
+          <span className="flex-1 truncate text-surface-500 dark:text-surface-400">
+            {delivery.responseStatus !== null && `HTTP ${delivery.responseStatus} `}
+            {delivery.error}
+            {delivery.status === 'pending' &&
+              delivery.nextAttemptAt &&
+              `retry ${formatDistanceToNow(new Date(delivery.nextAttemptAt), { addSuffix: true })}`}
+          </span>
+          <span className="text-surface-400 flex-shrink-0">
+            {formatDistanceToNow(new Date(delivery.createdAt), { addSuffix: true })}
+          </span>
+        </li>
+      ))}
+    </ul>
+  );
+}
+
+function WebhookCard({ webhook }: { webhook: Webhook }) {
+  const saveWebhookMutation = useSaveWebhook();
+  const deleteWebhookMutation = useDeleteWebhook();
+  const testWebhookMutation = useTestWebhook();
+  const { confirm } = useConfirmDialog();
+  const [showDeliveries, setShowDeliveries] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const save = (updates: Partial<Pick<Webhook, 'events' | 'enabled'>>) => {
+    const next = { ...webhook, ...updates };
+    saveWebhookMutation.mutate(
+      { id: webhook.id, url: webhook.url, events: next.events, enabled: next.enabled },
+      { onError: (err) => setMessage(String(err)) },
+    );
+  };
+
+  const handleDelete = async () => {
+    const confirmed = await confirm({
+      title: 'Delete webhook',
+      subtitle: webhook.url,
+      message: 'The webhook and its delivery log are removed.',
+      confirmLabel: 'Delete',
+      destructive: true,
+    });
+    if (confirmed) deleteWebhookMutation.mutate(webhook.id);
+  };
+
+  return (
+    <div className="space-y-3 rounded-lg border border-surface-200 dark:border-surface-700 p-3">
+      <div className="flex items-center gap-2">
+        <span className="flex-1 text-sm font-medium text-surface-800 dark:text-surface-200 truncate">
+          {webhook.url}
+        </span>
+        <label className="flex items-center gap-1.5 text-xs text-surface-600 dark:text-surface-400">
+          <input
+            type="checkbox"
+            checked={webhook.enabled}
+            onChange={(e) => save({ enabled: e.target.checked })}
+            className="rounded border-surface-300"
+          />
+          Enabled
+        </label>
+      </div>
+      <EventCheckboxes events={webhook.events} onChange={(events) => save({ events })} />
+      <div className="flex flex-wrap gap-1">
+        <button
+          type="button"
+          disabled={testWebhookMutation.isPending}
+          onClick={() =>
+            testWebhookMutation.mutate(webhook.id, {
+              onSuccess: (delivery) =>
+                setMessage(
+                  delivery.status === 'delivered'
+                    ? `Delivered (HTTP ${delivery.responseStatus})`
+                    : `Not delivered: ${delivery.error ?? `HTTP ${delivery.responseStatus}`}`,
+                ),
+              onError: (err) => setMessage(String(err)),
+            })
+          }
+          className={smallButtonClass}
+        >
+          <Send className="w-3.5 h-3.5" />
+          Send test
+        </button>
+        <button
+          type="button"
+          onClick={async () => {
+            try {
+              await navigator.clipboard.writeText(await getWebhookSecret(webhook.id));
+              setMessage('Signing secret copied');
+            } catch (err) {
+              setMessage(String(err));
+            }
+          }}
+          className={smallButtonClass}
+        >
+          <Copy className="w-3.5 h-3.5" />
+          Copy signing secret
+        </button>
+        <button
+          type="button"
+          onClick={() => setShowDeliveries(!showDeliveries)}
+          className={smallButtonClass}
+        >
+          <ChevronDown
+            className={`w-3.5 h-3.5 transition-transform ${showDeliveries ? 'rotate-180' : ''}`}
+          />
+          Deliveries
+        </button>
+        <button
+          type="button"
+          onClick={handleDelete}
+          className="flex items-center gap-1 px-2 py-1 text-xs text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 rounded-lg transition-colors"
+        >
+          <Trash2 className="w-3.5 h-3.5" />
+          Delete
+        </button>
+      </div>
+      {message && <p className="text-xs text-surface-500 dark:text-surface-400">{message}</p>}
+      {showDeliveries && <Deliveries webhookId={webhook.id} />}
+    </div>
+  );
+}
+
+export function WebhookSettings() {
+  const { data: webhooks = [] } = useWebhooks();
+  const saveWebhookMutation = useSaveWebhook();
+  const [url, setUrl] = useState('');
+  const [events, setEvents] = useState<TaskEvent[]>(['completed']);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleAdd = () => {
+    setError(null);
+    saveWebhookMutation.mutate(
+      { id: null, url: url.trim(), events, enabled: true },
+      {
+        onSuccess: () => setUrl(''),
+        onError: (err) => setError(String(err)),
+      },
+    );
+  };
+
+  return (
+    <div className="space-y-4">
+      <h3 className="text-sm font-semibold text-surface-800 dark:text-surface-200">Webhooks</h3>
+      <div className="space-y-4 rounded-lg border border-surface-200 dark:border-surface-700 p-4 bg-white dark:bg-surface-800">
+        <p className="text-sm text-surface-500 dark:text-surface-400">
+          A JSON payload is posted to each URL when a task is created, completed or becomes
+          overdue. It is signed with the webhook's secret in the X-Caldav-Tasks-Signature header,
+          and failed deliveries are retried.
+        </p>
+
+        {webhooks.map((webhook) => (
+          <WebhookCard key={webhook.id} webhook={webhook} />
+        ))}
+
+        <div className="space-y-3">
+          <input
+            type="url"
+            value={url}
+            onChange={(e) => setUrl(e.target.value)}
+            placeholder="https://example.com/hook"
+            className={inputClass}
+          />
+          <EventCheckboxes events={events} onChange={setEvents} />
+          <button
+            type="button"
+            disabled={!url.trim() || events.length === 0 || saveWebhookMutation.isPending}
+            onClick={handleAdd}
+            className={buttonClass}
+          >
+            Add Webhook
+          </button>
+          {error && <p className="text-xs text-red-600 dark:text-red-400">{error}</p>}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,9 +1,17 @@
 export { AboutSettings } from './AboutSettings';
+export { ApiServerSettings } from './ApiServerSettings';
 export { AppearanceSettings } from './AppearanceSettings';
 export { BehaviorSettings } from './BehaviorSettings';
 export { ConnectionsSettings } from './ConnectionsSettings';
 export { DataSettings } from './DataSettings';
+export { EmailSettings } from './EmailSettings';
+export { HttpActionSettings } from './HttpActionSettings';
 export { NotificationSettings } from './NotificationSettings';
+export { RuleSettings } from './RuleSettings';
+export { ScriptSettings } from './ScriptSettings';
+export { SecuritySettings } from './SecuritySettings';
 export { ShortcutsSettings } from './ShortcutsSettings';
 export { SyncSettings } from './SyncSettings';
 export { TaskDefaultsSettings } from './TaskDefaultsSettings';
+export { TroubleshootingSettings } from './TroubleshootingSettings';
+export { WebhookSettings } from './WebhookSettings';
@@ -1,5 +1,39 @@
+export * from './useAccountHealth';
 export * from './useAccounts';
+export * from './useApiServer';
+export * from './useAppLock';
+export * from './useArchive';
+export * from './useConflicts';
+export * from './useContexts';
+export * from './useDependencies';
+export * from './useEmail';
+export * from './useHttpActions';
+export * from './useIcsFeed';
+export * from './useKanban';
+export * from './useLanguage';
+export * from './useLinkTitles';
+export * from './useLocale';
+export * from './useLogging';
+export * from './useOAuth';
+export * from './usePendingChanges';
+export * from './usePlanning';
+export * from './useQuickAdd';
+export * from './useReadOnly';
+export * from './useRecurrence';
+export * from './useRules';
+export * from './useScripts';
+export * from './useSearch';
+export * from './useServerHealth';
+export * from './useStats';
+export * from './useStreaks';
 export * from './useSync';
+export * from './useSyncSchedules';
 export * from './useTags';
+export * from './useTaskNag';
 export * from './useTasks';
+export * from './useTimeTracking';
+export * from './useTraySupport';
 export * from './useUIState';
+export * from './useWebhooks';
+export * from './useWindowEffect';
+export * from './useWireTrace';
@@ -0,0 +1,61 @@
+/**
+ * TanStack Query hooks for whether accounts sync, and why not
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { type AccountHealth, getAccountHealth, revalidateCredentials } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+// replace one account's entry in the cached list
+function mergeHealth(list: AccountHealth[] | undefined, health: AccountHealth): AccountHealth[] {
+  return [...(list ?? []).filter((h) => h.accountId !== health.accountId), health];
+}
+
+/**
+ * Hook to get the health of every account that has synced; accounts without an entry are fine
+ */
+export function useAccountHealth() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen<AccountHealth>('account-health-changed', (event) => {
+      queryClient.setQueryData<AccountHealth[]>(queryKeys.accountHealth, (list) =>
+        mergeHealth(list, event.payload),
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.accountHealth,
+    queryFn: () => getAccountHealth(),
+  });
+}
+
+/**
+ * Hook to check an account's credentials now, optionally with a new password that is saved
+ * when the server accepts it
+ */
+export function useRevalidateCredentials() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ accountId, password }: { accountId: string; password: string | null }) =>
+      revalidateCredentials(accountId, password),
+    onSuccess: (health, { accountId, password }) => {
+      queryClient.setQueryData<AccountHealth[]>(queryKeys.accountHealth, (list) =>
+        mergeHealth(list, health),
+      );
+      // the next sync connects with it
+      if (password && health.state === 'healthy') {
+        taskData.updateAccount(accountId, { password });
+        queryClient.invalidateQueries({ queryKey: queryKeys.accounts.all });
+      }
+    },
+  });
+}
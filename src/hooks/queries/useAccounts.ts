@@ -4,9 +4,21 @@
 
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import { useEffect } from 'react';
+import {
+  getAccountEncryption,
+  getAccountTls,
+  reorderAccounts,
+  reorderCalendars,
+  setAccountActive,
+  setAccountColor,
+  setAccountEncryption,
+  setAccountTls,
+  setCalendarArchived,
+} from '@/lib/database';
 import { queryKeys } from '@/lib/queryClient';
+import { syncBackendFor } from '@/lib/syncBackend';
 import * as taskData from '@/lib/taskData';
-import type { Account, Calendar } from '@/types';
+import type { Account, Calendar, TlsSettings } from '@/types';
 
 // ============================================================================
 // Query Hooks
@@ -91,16 +103,49 @@ export function useUpdateAccount() {
 }
 
 /**
- * Hook to delete an account
+ * Hook to delete an account, optionally keeping its tasks in the local Recovered list
  */
 export function useDeleteAccount() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: (id: string) => {
-      taskData.deleteAccount(id);
-      return Promise.resolve();
+    mutationFn: async ({ id, keepTasks = false }: { id: string; keepTasks?: boolean }) => {
+      if (keepTasks) {
+        await taskData.deleteAccountKeepingTasks(id);
+      } else {
+        taskData.deleteAccount(id);
+      }
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.accounts.all });
+      queryClient.invalidateQueries({ queryKey: queryKeys.tasks.all });
+    },
+  });
+}
+
+/**
+ * Hook to pause an account or make it active again
+ */
+export function useSetAccountActive() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, active }: { id: string; active: boolean }) => setAccountActive(id, active),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.accounts.all });
     },
+  });
+}
+
+/**
+ * Hook to archive a calendar or restore it
+ */
+export function useSetCalendarArchived() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, archived }: { id: string; archived: boolean }) =>
+      setCalendarArchived(id, archived),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: queryKeys.accounts.all });
       queryClient.invalidateQueries({ queryKey: queryKeys.tasks.all });
@@ -108,6 +153,49 @@ export function useDeleteAccount() {
   });
 }
 
+/**
+ * Hook to put the accounts in a new sidebar order
+ */
+export function useReorderAccounts() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (orderedIds: string[]) => reorderAccounts(orderedIds),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.accounts.all });
+    },
+  });
+}
+
+/**
+ * Hook to set the color an account is shown with, or go back to the default with null
+ */
+export function useSetAccountColor() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, color }: { id: string; color: string | null }) => setAccountColor(id, color),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.accounts.all });
+    },
+  });
+}
+
+/**
+ * Hook to put an account's calendars in a new sidebar order
+ */
+export function useReorderCalendars() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ accountId, orderedIds }: { accountId: string; orderedIds: string[] }) =>
+      reorderCalendars(accountId, orderedIds),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.accounts.all });
+    },
+  });
+}
+
 /**
  * Hook to add a calendar to an account
  */
@@ -130,3 +218,55 @@ export function useAddCalendar() {
     },
   });
 }
+
+/**
+ * Hook to get whether an account encrypts task content end to end
+ */
+export function useAccountEncryption(accountId: string) {
+  return useQuery({
+    queryKey: queryKeys.accountEncryption(accountId),
+    queryFn: () => getAccountEncryption(accountId),
+  });
+}
+
+/**
+ * Hook to set or change the passphrase of an account, or turn encryption off with null
+ */
+export function useSetAccountEncryption() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ accountId, passphrase }: { accountId: string; passphrase: string | null }) =>
+      setAccountEncryption(accountId, passphrase),
+    onSuccess: (_, { accountId }) => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.accountEncryption(accountId) });
+    },
+  });
+}
+
+/**
+ * Hook to get the TLS settings of an account
+ */
+export function useAccountTls(accountId: string) {
+  return useQuery({
+    queryKey: queryKeys.accountTls(accountId),
+    queryFn: () => getAccountTls(accountId),
+  });
+}
+
+/**
+ * Hook to save the TLS settings of an account; it connects with them on the next sync
+ */
+export function useSetAccountTls() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ accountId, tls }: { accountId: string; tls: TlsSettings }) =>
+      setAccountTls(accountId, tls),
+    onSuccess: (_, { accountId }) => {
+      const account = taskData.getAccountById(accountId);
+      if (account) syncBackendFor(account).disconnect(accountId);
+      queryClient.invalidateQueries({ queryKey: queryKeys.accountTls(accountId) });
+    },
+  });
+}
@@ -0,0 +1,73 @@
+/**
+ * TanStack Query hooks for the local REST API server and the MCP endpoint on it
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  getApiServerSettings,
+  getMcpEnabled,
+  regenerateApiServerToken,
+  setApiServerSettings,
+  setMcpEnabled,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get whether the API server is on, its port and whether it is running
+ */
+export function useApiServerSettings() {
+  return useQuery({
+    queryKey: queryKeys.apiServer,
+    queryFn: () => getApiServerSettings(),
+  });
+}
+
+/**
+ * Hook to turn the API server on or off or move it to another port
+ */
+export function useSetApiServerSettings() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ enabled, port }: { enabled: boolean; port: number }) =>
+      setApiServerSettings(enabled, port),
+    onSuccess: (settings) => {
+      queryClient.setQueryData(queryKeys.apiServer, settings);
+      // the feed is served by it
+      queryClient.invalidateQueries({ queryKey: queryKeys.icsFeed });
+    },
+  });
+}
+
+/**
+ * Hook to replace the bearer token; clients using the old one stop working
+ */
+export function useRegenerateApiServerToken() {
+  return useMutation({
+    mutationFn: () => regenerateApiServerToken(),
+  });
+}
+
+/**
+ * Hook to get whether MCP is served at /mcp of the API server
+ */
+export function useMcpEnabled() {
+  return useQuery({
+    queryKey: queryKeys.mcpEnabled,
+    queryFn: () => getMcpEnabled(),
+  });
+}
+
+/**
+ * Hook to turn the MCP endpoint on or off
+ */
+export function useSetMcpEnabled() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (enabled: boolean) => setMcpEnabled(enabled),
+    onSuccess: (_, enabled) => {
+      queryClient.setQueryData(queryKeys.mcpEnabled, enabled);
+    },
+  });
+}
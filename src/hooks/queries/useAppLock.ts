@@ -0,0 +1,41 @@
+/**
+ * TanStack Query hooks for the app's PIN lock
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { getAppLockStatus, setAppLock } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get whether a PIN is set and after how long the app locks itself
+ */
+export function useAppLockStatus() {
+  return useQuery({
+    queryKey: queryKeys.appLock,
+    queryFn: () => getAppLockStatus(),
+  });
+}
+
+/**
+ * Hook to set, change or remove the PIN
+ */
+export function useSetAppLock() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      pin,
+      currentPin,
+      idleTimeout,
+    }: {
+      pin: string | null;
+      currentPin: string | null;
+      idleTimeout: number;
+    }) => setAppLock(pin, currentPin, idleTimeout),
+    // a wrong PIN stays wrong
+    retry: false,
+    onSuccess: (status) => {
+      queryClient.setQueryData(queryKeys.appLock, status);
+    },
+  });
+}
@@ -0,0 +1,86 @@
+/**
+ * TanStack Query hooks for the archive of old completed tasks
+ */
+
+import {
+  keepPreviousData,
+  useInfiniteQuery,
+  useMutation,
+  useQuery,
+  useQueryClient,
+} from '@tanstack/react-query';
+import { useEffect } from 'react';
+import {
+  type ArchivePolicy,
+  getArchivePolicy,
+  queryArchivedTasks,
+  restoreArchivedTasks,
+  setArchivePolicy,
+  type TaskPage,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+// archived tasks loaded at a time
+const ARCHIVE_PAGE_SIZE = 100;
+
+function nextArchiveOffset(last: TaskPage) {
+  const next = last.offset + last.tasks.length;
+  return next < last.total ? next : undefined;
+}
+
+/**
+ * Hook to get after how many days completed tasks are archived
+ */
+export function useArchivePolicy() {
+  return useQuery({
+    queryKey: queryKeys.archivePolicy,
+    queryFn: () => getArchivePolicy(),
+  });
+}
+
+/**
+ * Hook to save the archive policy; resolves to how many tasks it archived right away
+ */
+export function useSetArchivePolicy() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (policy: ArchivePolicy) => setArchivePolicy(policy),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.archivePolicy });
+    },
+  });
+}
+
+/**
+ * Hook to browse archived tasks matching a search, most recently completed first,
+ * one page at a time
+ */
+export function useArchivedTasks(search: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.archivedTasks.all });
+    });
+  }, [queryClient]);
+
+  return useInfiniteQuery({
+    queryKey: queryKeys.archivedTasks.bySearch(search),
+    queryFn: ({ pageParam }) =>
+      queryArchivedTasks(search || undefined, pageParam, ARCHIVE_PAGE_SIZE),
+    initialPageParam: 0,
+    getNextPageParam: nextArchiveOffset,
+    placeholderData: keepPreviousData,
+  });
+}
+
+/**
+ * Hook to restore archived tasks with their sub-tasks
+ */
+export function useRestoreArchivedTasks() {
+  return useMutation({
+    mutationFn: (ids: string[]) => restoreArchivedTasks(ids),
+  });
+}
@@ -0,0 +1,80 @@
+/**
+ * TanStack Query hooks for the sync conflict review queue
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import {
+  getSyncConflicts,
+  resolveSyncConflict,
+  type SyncConflict,
+  type SyncConflictResolution,
+} from '@/lib/database';
+import { createLogger } from '@/lib/logger';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+const log = createLogger('Conflicts', '#ef4444');
+
+/**
+ * Hook to get the conflicts waiting for review
+ */
+export function useSyncConflicts() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen('sync-conflicts-changed', () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.syncConflicts });
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.syncConflicts,
+    queryFn: () => getSyncConflicts(),
+  });
+}
+
+/**
+ * Hook to settle a conflict. the chosen side goes into the local task and the next sync does
+ * the rest: keeping the local edit uploads it over the server's current version (or creates
+ * the task again when it was deleted there), keeping the server's version lets the next sync
+ * replace the local task with it (or remove it)
+ */
+export function useResolveSyncConflict() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async ({
+      conflict,
+      resolution,
+    }: {
+      conflict: SyncConflict;
+      resolution: SyncConflictResolution;
+    }) => {
+      const task = taskData.getTaskById(conflict.taskId);
+      if (task) {
+        if (resolution === 'keepLocal') {
+          taskData.updateTask(task.id, {
+            href: conflict.remote ? task.href : undefined,
+            etag: conflict.remote?.etag,
+            modifiedAt: task.modifiedAt,
+            synced: false,
+          });
+        } else {
+          taskData.updateTask(task.id, { etag: '', modifiedAt: task.modifiedAt, synced: true });
+        }
+      } else {
+        log.warn(`The task of conflict ${conflict.id} is gone, only closing the conflict`);
+      }
+      await resolveSyncConflict(conflict.id, resolution);
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.syncConflicts });
+      queryClient.invalidateQueries({ queryKey: queryKeys.tasks.all });
+    },
+  });
+}
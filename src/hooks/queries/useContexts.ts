@@ -0,0 +1,84 @@
+/**
+ * TanStack Query hooks for GTD contexts and next actions
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+import {
+  deleteContext,
+  getContexts,
+  getNextActions,
+  renameContext,
+  saveContext,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+/**
+ * Hook to get every context with its number of open tasks
+ */
+export function useContexts() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.contexts });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.contexts,
+    queryFn: () => getContexts(),
+  });
+}
+
+/**
+ * Hook to get the next actions per context, or of a single context
+ */
+export function useNextActions(context?: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.nextActions.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.nextActions.byContext(context),
+    queryFn: () => getNextActions(context),
+  });
+}
+
+/**
+ * Hook to add a context or change its color; resolves to the name as stored
+ */
+export function useSaveContext() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ name, color }: { name: string; color?: string }) => saveContext(name, color),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.contexts });
+    },
+  });
+}
+
+/**
+ * Hook to rename a context on every task that has it
+ */
+export function useRenameContext() {
+  return useMutation({
+    mutationFn: ({ name, newName }: { name: string; newName: string }) =>
+      renameContext(name, newName),
+  });
+}
+
+/**
+ * Hook to remove a context from every task that has it
+ */
+export function useDeleteContext() {
+  return useMutation({
+    mutationFn: (name: string) => deleteContext(name),
+  });
+}
@@ -0,0 +1,80 @@
+/**
+ * TanStack Query hooks for task dependencies (blocked by / blocks)
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+import {
+  addDependency,
+  getActionableTasks,
+  getDependencies,
+  removeDependency,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+/**
+ * Hook to get the tasks a task waits for and the tasks waiting for it
+ */
+export function useTaskDependencies(uid: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.dependencies.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.dependencies.byUid(uid),
+    queryFn: () => getDependencies(uid),
+  });
+}
+
+/**
+ * Hook to get the open tasks nothing is blocking
+ */
+export function useActionableTasks() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.actionableTasks });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.actionableTasks,
+    queryFn: () => getActionableTasks(),
+  });
+}
+
+/**
+ * Hook to make a task wait for another one; fails when they would wait for each other
+ */
+export function useAddDependency() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ taskUid, dependsOnUid }: { taskUid: string; dependsOnUid: string }) =>
+      addDependency(taskUid, dependsOnUid),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.dependencies.all });
+    },
+  });
+}
+
+/**
+ * Hook to stop a task from waiting for another one
+ */
+export function useRemoveDependency() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ taskUid, dependsOnUid }: { taskUid: string; dependsOnUid: string }) =>
+      removeDependency(taskUid, dependsOnUid),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.dependencies.all });
+    },
+  });
+}
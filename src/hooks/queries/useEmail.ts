@@ -0,0 +1,128 @@
+/**
+ * TanStack Query hooks for turning email into tasks and for the email digest
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  type EmailDigestSettings,
+  type EmailToTaskSettings,
+  getEmailDigestSettings,
+  getEmailDigestStatus,
+  getEmailToTaskSettings,
+  getEmailToTaskStatus,
+  pollEmailNow,
+  sendEmailDigestNow,
+  setEmailDigestSettings,
+  setEmailToTaskSettings,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get the IMAP settings of email to task
+ */
+export function useEmailToTaskSettings() {
+  return useQuery({
+    queryKey: queryKeys.emailToTask.settings,
+    queryFn: () => getEmailToTaskSettings(),
+  });
+}
+
+/**
+ * Hook to save the IMAP settings; a null password keeps the saved one
+ */
+export function useSetEmailToTaskSettings() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      settings,
+      password,
+    }: {
+      settings: EmailToTaskSettings;
+      password: string | null;
+    }) => setEmailToTaskSettings(settings, password),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.emailToTask.settings });
+    },
+  });
+}
+
+/**
+ * Hook to get when mail was last checked, the last error and how many tasks were made
+ */
+export function useEmailToTaskStatus() {
+  return useQuery({
+    queryKey: queryKeys.emailToTask.status,
+    queryFn: () => getEmailToTaskStatus(),
+    // polling happens in the background
+    refetchInterval: 60_000,
+  });
+}
+
+/**
+ * Hook to check for new mail now; resolves to the number of tasks made
+ */
+export function usePollEmailNow() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => pollEmailNow(),
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.emailToTask.status });
+    },
+  });
+}
+
+/**
+ * Hook to get the SMTP settings and schedule of the email digest
+ */
+export function useEmailDigestSettings() {
+  return useQuery({
+    queryKey: queryKeys.emailDigest.settings,
+    queryFn: () => getEmailDigestSettings(),
+  });
+}
+
+/**
+ * Hook to save the digest settings; a null password keeps the saved one
+ */
+export function useSetEmailDigestSettings() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      settings,
+      password,
+    }: {
+      settings: EmailDigestSettings;
+      password: string | null;
+    }) => setEmailDigestSettings(settings, password),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.emailDigest.settings });
+    },
+  });
+}
+
+/**
+ * Hook to get when the digest was last sent and the last error
+ */
+export function useEmailDigestStatus() {
+  return useQuery({
+    queryKey: queryKeys.emailDigest.status,
+    queryFn: () => getEmailDigestStatus(),
+  });
+}
+
+/**
+ * Hook to send a digest right away, whatever the schedule
+ */
+export function useSendEmailDigestNow() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => sendEmailDigestNow(),
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.emailDigest.status });
+    },
+  });
+}
@@ -0,0 +1,78 @@
+/**
+ * TanStack Query hooks for HTTP actions run when tasks are completed
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  deleteHttpAction,
+  getHttpActionRuns,
+  getHttpActions,
+  type HttpAction,
+  saveHttpAction,
+  testHttpAction,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get every HTTP action
+ */
+export function useHttpActions() {
+  return useQuery({
+    queryKey: queryKeys.httpActions.all,
+    queryFn: () => getHttpActions(),
+  });
+}
+
+/**
+ * Hook to get the latest runs of an action, newest first
+ */
+export function useHttpActionRuns(actionId: string) {
+  return useQuery({
+    queryKey: queryKeys.httpActions.runs(actionId),
+    queryFn: () => getHttpActionRuns(actionId),
+    // retries happen in the background
+    refetchInterval: 30_000,
+  });
+}
+
+/**
+ * Hook to add an action (empty id) or change one
+ */
+export function useSaveHttpAction() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (action: HttpAction) => saveHttpAction(action),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.httpActions.all });
+    },
+  });
+}
+
+/**
+ * Hook to delete an action with its runs
+ */
+export function useDeleteHttpAction() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => deleteHttpAction(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.httpActions.all });
+    },
+  });
+}
+
+/**
+ * Hook to run an action for a task now, as if it was completed; resolves to the run
+ */
+export function useTestHttpAction() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, taskId }: { id: string; taskId: string }) => testHttpAction(id, taskId),
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.httpActions.all });
+    },
+  });
+}
@@ -0,0 +1,45 @@
+/**
+ * TanStack Query hooks for the ICS feed of due dates
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { getIcsFeed, regenerateIcsFeedToken, setIcsFeedCalendars } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get the lists in the feed and its url
+ */
+export function useIcsFeed() {
+  return useQuery({
+    queryKey: queryKeys.icsFeed,
+    queryFn: () => getIcsFeed(),
+  });
+}
+
+/**
+ * Hook to choose the lists whose due dates are in the feed
+ */
+export function useSetIcsFeedCalendars() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (calendarIds: string[]) => setIcsFeedCalendars(calendarIds),
+    onSuccess: (feed) => {
+      queryClient.setQueryData(queryKeys.icsFeed, feed);
+    },
+  });
+}
+
+/**
+ * Hook to give the feed a new url; subscriptions to the old one stop updating
+ */
+export function useRegenerateIcsFeedToken() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => regenerateIcsFeedToken(),
+    onSuccess: (feed) => {
+      queryClient.setQueryData(queryKeys.icsFeed, feed);
+    },
+  });
+}
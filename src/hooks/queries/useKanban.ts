@@ -0,0 +1,59 @@
+/**
+ * TanStack Query hooks for the board (kanban) view
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+import { addKanbanColumn, getKanbanBoard, moveToColumn, removeKanbanColumn } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+/**
+ * Hook to get the board columns with their tasks, optionally of a single calendar
+ */
+export function useKanbanBoard(calendarId?: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.kanbanBoard.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.kanbanBoard.byCalendar(calendarId),
+    queryFn: () => getKanbanBoard(calendarId),
+  });
+}
+
+/**
+ * Hook to move a task to another column
+ */
+export function useMoveToColumn() {
+  return useMutation({
+    mutationFn: ({ id, column }: { id: string; column: string }) => moveToColumn(id, column),
+  });
+}
+
+/**
+ * Hook to add a custom column to the board
+ */
+export function useAddKanbanColumn() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (name: string) => addKanbanColumn(name),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.kanbanBoard.all });
+    },
+  });
+}
+
+/**
+ * Hook to remove a custom column; its tasks go back to the to do column
+ */
+export function useRemoveKanbanColumn() {
+  return useMutation({
+    mutationFn: (name: string) => removeKanbanColumn(name),
+  });
+}
@@ -0,0 +1,31 @@
+/**
+ * TanStack Query hooks for the language of backend text
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { type BackendLanguage, getLanguage, setLanguage } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get the chosen and the active backend language
+ */
+export function useLanguage() {
+  return useQuery({
+    queryKey: queryKeys.language,
+    queryFn: () => getLanguage(),
+  });
+}
+
+/**
+ * Hook to pick the backend language, null to follow the OS
+ */
+export function useSetLanguage() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (language: BackendLanguage | null) => setLanguage(language),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.language });
+    },
+  });
+}
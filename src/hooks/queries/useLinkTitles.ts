@@ -0,0 +1,31 @@
+/**
+ * TanStack Query hooks for fetching the titles of task links
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { getFetchLinkTitles, setFetchLinkTitles } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get whether the titles of task links are fetched
+ */
+export function useFetchLinkTitles() {
+  return useQuery({
+    queryKey: queryKeys.fetchLinkTitles,
+    queryFn: () => getFetchLinkTitles(),
+  });
+}
+
+/**
+ * Hook to turn fetching the titles of task links on or off
+ */
+export function useSetFetchLinkTitles() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (enabled: boolean) => setFetchLinkTitles(enabled),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.fetchLinkTitles });
+    },
+  });
+}
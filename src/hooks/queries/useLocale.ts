@@ -0,0 +1,19 @@
+/**
+ * TanStack Query hooks for the OS locale conventions
+ */
+
+import { useQuery } from '@tanstack/react-query';
+import { getLocaleSettings } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get the first day of the week and the date and time formats of the OS locale,
+ * which the backend also groups weeks and formats dates by
+ */
+export function useLocaleSettings() {
+  return useQuery({
+    queryKey: queryKeys.localeSettings,
+    queryFn: () => getLocaleSettings(),
+    staleTime: Infinity,
+  });
+}
@@ -0,0 +1,33 @@
+/**
+ * TanStack Query hooks for the backend log
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { getLogLevel, type LogLevel, setLogLevel } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import { useSettingsStore } from '@/store/settingsStore';
+
+/**
+ * Hook to get how much the backend logs
+ */
+export function useLogLevel() {
+  return useQuery({
+    queryKey: queryKeys.logLevel,
+    queryFn: () => getLogLevel(),
+  });
+}
+
+/**
+ * Hook to change how much the backend logs; the level is kept for the next launch
+ */
+export function useSetLogLevel() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (level: LogLevel) => setLogLevel(level),
+    onSuccess: (_, level) => {
+      useSettingsStore.getState().setLogLevel(level);
+      queryClient.invalidateQueries({ queryKey: queryKeys.logLevel });
+    },
+  });
+}
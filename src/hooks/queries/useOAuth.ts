@@ -0,0 +1,73 @@
+/**
+ * TanStack Query hooks for the OAuth tokens of accounts
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import {
+  deleteOAuthTokens,
+  type OAuthStatus,
+  type OAuthTokens,
+  refreshOAuthTokens,
+  setOAuthTokens,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+export interface OAuthReauthorizationRequired {
+  accountId: string;
+  error: string;
+}
+
+/**
+ * Hook to get the token state of every OAuth account; tokens about to expire are renewed
+ * on the way
+ */
+export function useOAuthStatus() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen<OAuthReauthorizationRequired>('oauth-reauthorization-required', () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.oauthStatus });
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.oauthStatus,
+    queryFn: () => refreshOAuthTokens(),
+  });
+}
+
+/**
+ * Hook to save the tokens of a (new) authorization
+ */
+export function useSetOAuthTokens() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ accountId, tokens }: { accountId: string; tokens: OAuthTokens }) =>
+      setOAuthTokens(accountId, tokens),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.oauthStatus });
+    },
+  });
+}
+
+/**
+ * Hook to forget an account's tokens, it signs in with its password again
+ */
+export function useDeleteOAuthTokens() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (accountId: string) => deleteOAuthTokens(accountId),
+    onSuccess: (_, accountId) => {
+      queryClient.setQueryData<OAuthStatus[]>(queryKeys.oauthStatus, (list) =>
+        list?.filter((status) => status.accountId !== accountId),
+      );
+    },
+  });
+}
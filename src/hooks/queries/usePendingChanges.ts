@@ -0,0 +1,42 @@
+/**
+ * TanStack Query hooks for the changes that haven't reached the server yet
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+import { discardPendingChange, getPendingChanges, type PendingChange } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+/**
+ * Hook to get the unsynced tasks and queued deletions, with why they're held back
+ */
+export function usePendingChanges() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.pendingChanges });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.pendingChanges,
+    queryFn: () => getPendingChanges(),
+  });
+}
+
+/**
+ * Hook to give up on a change. the backend reverts it and reloads the task cache
+ */
+export function useDiscardPendingChange() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (change: PendingChange) => discardPendingChange(change.kind, change.id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.pendingChanges });
+      queryClient.invalidateQueries({ queryKey: queryKeys.tasks.all });
+    },
+  });
+}
@@ -0,0 +1,46 @@
+/**
+ * TanStack Query hooks for planned (estimated) work and the agenda
+ */
+
+import { useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+import { type AgendaRange, getAgenda, getPlannedTime, type PlanGrouping } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+/**
+ * Hook to get the estimated work per day or week for tasks due in [from, to)
+ */
+export function usePlannedTime(groupBy: PlanGrouping, from?: string, to?: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.plannedTime.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.plannedTime.byRange(groupBy, from, to),
+    queryFn: () => getPlannedTime(groupBy, from, to),
+  });
+}
+
+/**
+ * Hook to get the open tasks with a due date, grouped into overdue, today, tomorrow,
+ * this week and later
+ */
+export function useAgenda(range: AgendaRange) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.agenda.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.agenda.byRange(range),
+    queryFn: () => getAgenda(range),
+  });
+}
@@ -0,0 +1,19 @@
+/**
+ * TanStack Query hooks for adding tasks from a line of text
+ */
+
+import { keepPreviousData, useQuery } from '@tanstack/react-query';
+import { parseQuickAdd } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get what quick-add text like `call mom tomorrow 5pm #family` is understood as
+ */
+export function useQuickAddDraft(text: string) {
+  return useQuery({
+    queryKey: queryKeys.quickAddDraft(text),
+    queryFn: () => parseQuickAdd(text),
+    enabled: text.trim().length > 0,
+    placeholderData: keepPreviousData,
+  });
+}
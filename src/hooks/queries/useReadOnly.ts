@@ -0,0 +1,44 @@
+/**
+ * TanStack Query hooks for read-only mode
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { getReadOnly, type ReadOnlyStatus, setReadOnly } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get whether the app is read-only, kept current when it's switched elsewhere
+ */
+export function useReadOnly() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen<boolean>('read-only-changed', (event) => {
+      queryClient.setQueryData<ReadOnlyStatus>(queryKeys.readOnly, (status) => ({
+        enabled: event.payload,
+        forced: status?.forced ?? null,
+      }));
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.readOnly,
+    queryFn: () => getReadOnly(),
+  });
+}
+
+/**
+ * Hook to turn read-only mode on or off
+ */
+export function useSetReadOnly() {
+  return useMutation({
+    // the read-only-changed event updates the cached state
+    mutationFn: (enabled: boolean) => setReadOnly(enabled),
+  });
+}
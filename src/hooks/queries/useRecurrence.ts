@@ -0,0 +1,23 @@
+/**
+ * TanStack Query hooks for recurring tasks
+ */
+
+import { useQuery } from '@tanstack/react-query';
+import { previewOccurrences } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import type { Task } from '@/types';
+
+// occurrences shown in the task editor
+const UPCOMING_OCCURRENCES = 3;
+
+/**
+ * Hook to get the next occurrences of a task, none when it doesn't repeat; they're
+ * fetched again when its start or due date changes, the series starts there
+ */
+export function useTaskOccurrences(task: Task) {
+  return useQuery({
+    queryKey: queryKeys.occurrences(task.uid, task.startDate, task.dueDate),
+    // a task without a rule is an error for the backend, and simply has no occurrences here
+    queryFn: () => previewOccurrences(task.uid, UPCOMING_OCCURRENCES).catch(() => []),
+  });
+}
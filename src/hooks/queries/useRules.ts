@@ -0,0 +1,54 @@
+/**
+ * TanStack Query hooks for task rules
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { deleteRule, getRules, type Rule, saveRule, testRule } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get every rule in the order they run
+ */
+export function useRules() {
+  return useQuery({
+    queryKey: queryKeys.rules,
+    queryFn: () => getRules(),
+  });
+}
+
+/**
+ * Hook to add a rule (empty id) or change one
+ */
+export function useSaveRule() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (rule: Rule) => saveRule(rule),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.rules });
+    },
+  });
+}
+
+/**
+ * Hook to delete a rule
+ */
+export function useDeleteRule() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => deleteRule(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.rules });
+    },
+  });
+}
+
+/**
+ * Hook to dry-run a rule, saved or not, against the current tasks
+ */
+export function useTestRule() {
+  return useMutation({
+    mutationFn: (rule: Rule) => testRule(rule),
+  });
+}
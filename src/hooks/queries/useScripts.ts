@@ -0,0 +1,78 @@
+/**
+ * TanStack Query hooks for automation scripts
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  deleteScript,
+  getScripts,
+  runScript,
+  saveScript,
+  type ScriptTrigger,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get every script
+ */
+export function useScripts() {
+  return useQuery({
+    queryKey: queryKeys.scripts,
+    queryFn: () => getScripts(),
+  });
+}
+
+/**
+ * Hook to add a script (id null) or change one; fails when the source doesn't compile
+ */
+export function useSaveScript() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      id,
+      name,
+      source,
+      trigger,
+      enabled,
+    }: {
+      id: string | null;
+      name: string;
+      source: string;
+      trigger: ScriptTrigger;
+      enabled: boolean;
+    }) => saveScript(id, name, source, trigger, enabled),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.scripts });
+    },
+  });
+}
+
+/**
+ * Hook to delete a script
+ */
+export function useDeleteScript() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => deleteScript(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.scripts });
+    },
+  });
+}
+
+/**
+ * Hook to run a script now; the tasks it changed arrive through the tasks-changed event
+ */
+export function useRunScript() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => runScript(id),
+    onSettled: () => {
+      // last run and last error
+      queryClient.invalidateQueries({ queryKey: queryKeys.scripts });
+    },
+  });
+}
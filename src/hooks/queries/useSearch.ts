@@ -0,0 +1,49 @@
+/**
+ * TanStack Query hooks for the search query language and jumping to tasks
+ */
+
+import { keepPreviousData, useQuery } from '@tanstack/react-query';
+import { fuzzyFind, globalSearch, validateSearchQuery } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get what's wrong with a search query such as `tag:work due<2025-01-01`,
+ * null while it's valid. an invalid query is searched for as plain text
+ */
+export function useSearchQueryError(query: string) {
+  return useQuery({
+    queryKey: queryKeys.searchQueryError(query),
+    queryFn: () =>
+      validateSearchQuery(query).then(
+        () => null,
+        (error) => String(error),
+      ),
+    enabled: query.trim().length > 0,
+    placeholderData: keepPreviousData,
+    staleTime: Infinity,
+  });
+}
+
+/**
+ * Hook to get the ranked hits for a query across every account, grouped per list
+ */
+export function useGlobalSearch(query: string) {
+  return useQuery({
+    queryKey: queryKeys.globalSearch(query),
+    queryFn: () => globalSearch(query),
+    enabled: query.trim().length > 0,
+    placeholderData: keepPreviousData,
+  });
+}
+
+/**
+ * Hook to get typo-tolerant matches of task titles and list names, best first
+ */
+export function useFuzzyFind(query: string, limit?: number) {
+  return useQuery({
+    queryKey: queryKeys.fuzzyFind(query, limit),
+    queryFn: () => fuzzyFind(query, limit),
+    enabled: query.trim().length > 0,
+    placeholderData: keepPreviousData,
+  });
+}
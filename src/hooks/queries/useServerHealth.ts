@@ -0,0 +1,30 @@
+/**
+ * TanStack Query hooks for servers that keep failing to sync
+ */
+
+import { useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { getUnreachableServers, type UnreachableServer } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get the servers that scheduled syncs are backing off from
+ */
+export function useUnreachableServers() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen<UnreachableServer[]>('servers-unreachable-changed', (event) => {
+      queryClient.setQueryData(queryKeys.unreachableServers, event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.unreachableServers,
+    queryFn: () => getUnreachableServers(),
+  });
+}
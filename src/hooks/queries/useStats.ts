@@ -0,0 +1,46 @@
+/**
+ * TanStack Query hooks for completion statistics
+ */
+
+import { useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+import { getCompletionStats, getOverdueTrend, type StatsGrouping } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+/**
+ * Hook to get completed, late and average age per day, week, list or tag for tasks completed
+ * in [from, to)
+ */
+export function useCompletionStats(groupBy: StatsGrouping, from: string, to: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.stats.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.stats.completion(groupBy, from, to),
+    queryFn: () => getCompletionStats(groupBy, from, to),
+  });
+}
+
+/**
+ * Hook to get the overdue tasks at the end of every day in [from, to)
+ */
+export function useOverdueTrend(from: string, to: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.stats.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.stats.overdue(from, to),
+    queryFn: () => getOverdueTrend(from, to),
+  });
+}
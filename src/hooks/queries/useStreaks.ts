@@ -0,0 +1,51 @@
+/**
+ * TanStack Query hooks for completion streaks
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { useEffect } from 'react';
+import { getStreakInTray, getStreaks, setStreakInTray } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+
+/**
+ * Hook to get the current and longest completion and no-overdue streaks
+ */
+export function useStreaks() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.streaks });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.streaks,
+    queryFn: () => getStreaks(),
+  });
+}
+
+/**
+ * Hook to get whether the tray tooltip shows the completion streak
+ */
+export function useStreakInTray() {
+  return useQuery({
+    queryKey: queryKeys.streakInTray,
+    queryFn: () => getStreakInTray(),
+  });
+}
+
+/**
+ * Hook to show or hide the completion streak in the tray tooltip
+ */
+export function useSetStreakInTray() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (enabled: boolean) => setStreakInTray(enabled),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.streakInTray });
+    },
+  });
+}
@@ -4,18 +4,40 @@
  */
 
 import { useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
 import { useCallback, useEffect, useRef, useState } from 'react';
-import { caldavService } from '@/lib/caldav';
+import { v4 as uuidv4 } from 'uuid';
+import { isOwnUpload, normalizeHrefs, sameCollection, SyncConflictError } from '@/lib/caldav';
+import {
+  getArchivedUids,
+  getDueAccounts,
+  getSyncConflicts,
+  isReadOnly,
+  recordSyncConflict,
+  recoverCalendarTasks,
+  refreshOAuthTokens,
+  reportPushResult,
+  reportSyncResult,
+  startPull,
+} from '@/lib/database';
 import { createLogger } from '@/lib/logger';
 import { queryKeys } from '@/lib/queryClient';
+import { allSyncBackends, syncBackendFor } from '@/lib/syncBackend';
 import * as taskData from '@/lib/taskData';
 import { useSettingsStore } from '@/store/settingsStore';
 import type { Calendar, Task } from '@/types';
 import { generateTagColor } from '@/utils/color';
+import { foldText } from '@/utils/misc';
 import { useOffline } from '../useOffline';
 
 const log = createLogger('Sync', '#06b6d4');
 
+// how often the per-account sync schedules are checked
+const SCHEDULE_CHECK_INTERVAL = 60 * 1000;
+
+// the backend's message for a 404, see status_message in caldav.rs
+const isNotFound = (message: string) => message.startsWith('Nothing found at');
+
 // Check if we're in a Tauri environment
 const isTauri = typeof window !== 'undefined' && '__TAURI__' in window;
 
@@ -63,8 +85,15 @@ export function useSyncQuery() {
   const pendingSyncRef = useRef(false);
   const autoSyncIntervalRef = useRef<NodeJS.Timeout | null>(null);
 
-  // Get current accounts from data layer
-  const getAccounts = () => taskData.getAllAccounts();
+  // Get current accounts from data layer (demo and local accounts have no server to sync with,
+  // paused ones shouldn't)
+  const getAccounts = () =>
+    taskData
+      .getAllAccounts()
+      .filter(
+        (account) =>
+          account.serverType !== 'demo' && account.serverType !== 'local' && account.isActive,
+      );
 
   // Handle online/offline status
   const { isOffline } = useOffline({
@@ -84,9 +113,10 @@ export function useSyncQuery() {
   const reconnectAccounts = useCallback(async () => {
     const accounts = getAccounts();
     for (const account of accounts) {
-      if (!caldavService.isConnected(account.id)) {
+      const backend = syncBackendFor(account);
+      if (!backend.isConnected(account.id)) {
         try {
-          await caldavService.reconnect(account);
+          await backend.reconnect(account);
           log.info(`Reconnected to account: ${account.name}`);
         } catch (error) {
           log.error(`Failed to reconnect account ${account.name}:`, error);
@@ -105,26 +135,33 @@ export function useSyncQuery() {
       if (!account) return;
 
       // Ensure we're connected
-      if (!caldavService.isConnected(accountId)) {
-        await caldavService.reconnect(account);
+      const backend = syncBackendFor(account);
+      if (!backend.isConnected(accountId)) {
+        await backend.reconnect(account);
       }
 
-      const remoteCalendars = await caldavService.fetchCalendars(accountId);
+      const remoteCalendars = await backend.fetchCalendars(accountId);
       log.info(`Found ${remoteCalendars.length} calendars on server for ${account.name}`);
 
       const localCalendars = account.calendars;
-      const remoteCalendarIds = new Set(remoteCalendars.map((c) => c.id));
+      // urls stored before hrefs were normalized still find their calendar
+      const localUrls = await normalizeHrefs(account.serverUrl, localCalendars.map((c) => c.url));
 
       // Build updated calendar list
       const updatedCalendars: Calendar[] = [];
 
       // Add/update calendars from server
       for (const remoteCalendar of remoteCalendars) {
-        const localCalendar = localCalendars.find((c) => c.id === remoteCalendar.id);
+        // a calendar that moved to another iCloud partition, or whose url the server escapes
+        // differently now, keeps its id and tasks
+        const localCalendar =
+          localCalendars.find((c) => c.id === remoteCalendar.id) ??
+          localCalendars.find((_, i) => sameCollection(localUrls[i], remoteCalendar.url));
 
         if (localCalendar) {
           // Calendar exists - check if properties changed
           if (
+            localCalendar.url !== remoteCalendar.url ||
             localCalendar.displayName !== remoteCalendar.displayName ||
             localCalendar.color !== remoteCalendar.color ||
             localCalendar.ctag !== remoteCalendar.ctag ||
@@ -132,6 +169,7 @@ export function useSyncQuery() {
           ) {
             updatedCalendars.push({
               ...localCalendar,
+              url: remoteCalendar.url,
               displayName: remoteCalendar.displayName,
               color: remoteCalendar.color,
               ctag: remoteCalendar.ctag,
@@ -150,18 +188,22 @@ export function useSyncQuery() {
       const currentUIState = taskData.getUIState();
       let needsRedirectToAllTasks = false;
 
-      // Remove calendars that were deleted on server
+      // Remove calendars that were deleted on server, their tasks are kept as local tasks in
+      // the Recovered list
+      const remainingIds = new Set(updatedCalendars.map((c) => c.id));
       for (const localCalendar of localCalendars) {
-        if (!remoteCalendarIds.has(localCalendar.id)) {
+        if (!remainingIds.has(localCalendar.id)) {
+          // moving its tasks is a local change, so in read-only mode the calendar stays
+          if (isReadOnly()) {
+            updatedCalendars.push(localCalendar);
+            continue;
+          }
           // check if this was the active calendar
           if (currentUIState.activeCalendarId === localCalendar.id) {
             needsRedirectToAllTasks = true;
           }
-          // Remove tasks for this calendar
-          const tasks = taskData.getTasksByCalendar(localCalendar.id);
-          for (const task of tasks) {
-            taskData.deleteTask(task.id);
-          }
+          const kept = await recoverCalendarTasks(localCalendar.id);
+          log.info(`${localCalendar.displayName} was deleted on the server, kept ${kept} tasks`);
         }
       }
 
@@ -187,7 +229,7 @@ export function useSyncQuery() {
    */
   const ensureTagExists = useCallback((tagName: string): string => {
     const currentTags = taskData.getAllTags();
-    const existing = currentTags.find((t) => t.name.toLowerCase() === tagName.toLowerCase());
+    const existing = currentTags.find((t) => foldText(t.name) === foldText(tagName));
 
     if (existing) {
       return existing.id;
@@ -218,123 +260,218 @@ export function useSyncQuery() {
         log.error('Calendar not found');
         return;
       }
+      // archived calendars keep what they have until they're restored
+      if (calendar.archived) return;
 
       // Ensure we're connected
-      if (!caldavService.isConnected(account.id)) {
-        await caldavService.reconnect(account);
+      const backend = syncBackendFor(account);
+      if (!backend.isConnected(account.id)) {
+        await backend.reconnect(account);
       }
 
+      // read-only mode only pulls; local changes wait until it's turned off
+      const readOnly = isReadOnly();
+
       // STEP 0: Process pending deletions for this calendar
-      const pendingDeletions = taskData.getPendingDeletions();
+      const pendingDeletions = readOnly ? [] : taskData.getPendingDeletions();
       const calendarDeletions = pendingDeletions.filter((d) => d.calendarId === calendarId);
+      const deletedUids = new Set(calendarDeletions.map((d) => d.uid));
 
+      // a deletion the server refuses stays queued with its error, for the pending changes
       for (const deletion of calendarDeletions) {
+        let error: string | null;
         try {
-          await caldavService.deleteTask(account.id, { href: deletion.href } as any);
-          taskData.clearPendingDeletion(deletion.uid);
-        } catch (error) {
-          log.error(`Failed to delete task from server:`, error);
-          // Still clear the pending deletion to avoid infinite retries
-          taskData.clearPendingDeletion(deletion.uid);
+          const deleted = await backend.deleteTask(account.id, {
+            href: deletion.href,
+            uid: deletion.uid,
+          } as Task);
+          error = deleted ? null : (backend.takeFailure(deletion.uid) ?? 'Deletion failed');
+        } catch (e) {
+          log.error(`Failed to delete task from server:`, e);
+          error = String(e);
         }
+        if (!error) taskData.clearPendingDeletion(deletion.uid);
+        await reportPushResult('deletion', deletion.uid, error).catch(() => {});
       }
 
       // Get local tasks for this calendar
       const localCalendarTasks = taskData.getTasksByCalendar(calendarId);
 
-      // STEP 1: Push unsynced local tasks to server
-      const unsyncedTasks = localCalendarTasks.filter((t) => !t.synced);
-
-      for (const task of unsyncedTasks) {
+      // STEP 1: Push unsynced local tasks to server; tasks waiting in the conflict queue stay
+      // local until the user picks a side
+      const unsyncedTasks = readOnly ? [] : localCalendarTasks.filter((t) => !t.synced);
+      const inReview = new Set((await getSyncConflicts()).map((c) => c.taskId));
+      const conflicted: Task[] = [];
+
+      for (const unsynced of unsyncedTasks) {
+        if (inReview.has(unsynced.id)) continue;
+        // every upload carries a new change id, so it's recognized when it comes back below
+        const task = { ...unsynced, changeId: uuidv4() };
+        const { changeId, modifiedAt } = task;
+        // what's saved once the upload went through. an edit made while it was on its way
+        // isn't on the server, so then the task keeps it and stays unsynced for the next sync
+        const uploaded = (saved: Partial<Task>): Partial<Task> => {
+          const stored = taskData.getTaskById(task.id);
+          if (
+            stored &&
+            (stored.changeId !== unsynced.changeId ||
+              new Date(stored.modifiedAt).getTime() !== new Date(unsynced.modifiedAt).getTime())
+          ) {
+            return { ...saved, modifiedAt: stored.modifiedAt, synced: false };
+          }
+          return { ...saved, changeId, modifiedAt, synced: true };
+        };
+        let error: string | null = null;
         try {
           if (task.href) {
             // Update existing task on server
-            const result = await caldavService.updateTask(account.id, task);
+            const result = await backend.updateTask(account.id, task, calendar);
             if (result) {
-              taskData.updateTask(task.id, { etag: result.etag, synced: true });
+              taskData.updateTask(task.id, uploaded({ etag: result.etag }));
             }
+            error = result ? null : (backend.takeFailure(task.uid) ?? 'Upload failed');
           } else {
             // Create new task on server
-            const result = await caldavService.createTask(account.id, calendar, task);
+            const result = await backend.createTask(account.id, calendar, task);
             if (result) {
-              taskData.updateTask(task.id, { href: result.href, etag: result.etag, synced: true });
+              taskData.updateTask(task.id, uploaded({ href: result.href, etag: result.etag }));
             }
+            error = result ? null : (backend.takeFailure(task.uid) ?? 'Upload failed');
           }
-        } catch (error) {
-          log.error(`Failed to push task ${task.title}:`, error);
+        } catch (e) {
+          if (e instanceof SyncConflictError) {
+            conflicted.push(task);
+          } else {
+            log.error(`Failed to push task ${task.id}:`, e);
+          }
+          error = e instanceof Error ? e.message : String(e);
         }
+        await reportPushResult('task', task.id, error).catch(() => {});
       }
 
       // STEP 2: Fetch tasks from server
-      const remoteTasks = await caldavService.fetchTasks(account.id, calendar);
+      let remoteTasks = await backend.fetchTasks(
+        account.id,
+        calendar,
+        taskData.getTasksByCalendar(calendarId),
+      );
       log.info(`Fetched ${remoteTasks.length} tasks from ${calendar.displayName}`);
 
+      for (const task of conflicted) {
+        log.warn(`Task ${task.uid} changed both here and on the server`);
+        await recordSyncConflict({
+          taskId: task.id,
+          taskUid: task.uid,
+          accountId: account.id,
+          calendarId,
+          title: task.title,
+          local: task,
+          remote: remoteTasks.find((t) => t.uid === task.uid) ?? null,
+        }).catch((error) => log.error(`Failed to queue the conflict of ${task.uid}:`, error));
+      }
+
+      if (calendar.checklistAsTasks && deletedUids.size > 0) {
+        // checklist items of tasks deleted above come back as tasks of their own
+        const orphanedItems = remoteTasks.filter(
+          (t) => t.parentUid && deletedUids.has(t.parentUid) && t.uid.startsWith(`${t.parentUid}-`),
+        );
+        for (const item of orphanedItems) {
+          await backend.deleteTask(account.id, item);
+        }
+        remoteTasks = remoteTasks.filter((t) => !orphanedItems.includes(t));
+      }
+
+      // archived tasks are still on the server but no longer in the local cache
+      const archivedUids = new Set(await getArchivedUids(calendarId));
+      if (archivedUids.size > 0) {
+        remoteTasks = remoteTasks.filter((t) => !archivedUids.has(t.uid));
+      }
+
       // Re-get local tasks (may have been updated by push)
       const updatedLocalTasks = taskData.getTasksByCalendar(calendarId);
-      const localUids = new Set(updatedLocalTasks.map((t) => t.uid));
       const remoteUids = new Set(remoteTasks.map((t) => t.uid));
 
-      // Find new tasks from server (not in local)
+      // collect new and changed tasks, then save them in batches instead of one write per task
+      const upserts: Task[] = [];
+      const localByUid = new Map(updatedLocalTasks.map((t) => [t.uid, t]));
+
       for (const remoteTask of remoteTasks) {
-        if (!localUids.has(remoteTask.uid)) {
+        // Extract category/tag from the task and create if needed
+        let remoteTagIds: string[] = [];
+        if (remoteTask.categoryId) {
+          const categoryNames = remoteTask.categoryId
+            .split(',')
+            .map((s: string) => s.trim())
+            .filter(Boolean);
+          // names differing only in case are one tag; duplicates would never match the local tags
+          remoteTagIds = [...new Set(categoryNames.map((name: string) => ensureTagExists(name)))];
+        }
+
+        const localTask = localByUid.get(remoteTask.uid);
+        if (!localTask) {
           // New task from server
+          upserts.push({ ...remoteTask, tags: remoteTagIds });
+          continue;
+        }
 
-          // Extract category/tag from the task and create if needed
-          let tagIds: string[] = [];
-          if (remoteTask.categoryId) {
-            const categoryNames = remoteTask.categoryId
-              .split(',')
-              .map((s: string) => s.trim())
-              .filter(Boolean);
-            tagIds = categoryNames.map((name: string) => ensureTagExists(name));
+        // Task exists locally - check if local task is missing tags that exist on server
+        const localTagIds = localTask.tags || [];
+        const tagsMatch =
+          remoteTagIds.length === localTagIds.length &&
+          remoteTagIds.every((id) => localTagIds.includes(id));
+
+        if (remoteTask.etag !== localTask.etag) {
+          // Only update from server if local task is synced (no local changes)
+          if (localTask.synced && isOwnUpload(localTask, remoteTask)) {
+            // our own upload coming back, only its etag and href are news
+            upserts.push({ ...localTask, etag: remoteTask.etag, href: remoteTask.href });
+          } else if (localTask.synced) {
+            upserts.push({
+              ...localTask,
+              ...remoteTask,
+              id: localTask.id, // Keep local ID
+              tags: remoteTagIds,
+              modifiedAt: remoteTask.modifiedAt ?? new Date(),
+              synced: true,
+            });
           }
-
-          // Add the task with tags
-          taskData.createTask({
-            ...remoteTask,
-            tags: tagIds,
-          });
-        } else {
-          // Task exists locally - check if server version is newer
-          const localTask = updatedLocalTasks.find((t) => t.uid === remoteTask.uid);
-          if (localTask) {
-            // Check if tags need to be synced from server
-            let remoteTagIds: string[] = [];
-            if (remoteTask.categoryId) {
-              const categoryNames = remoteTask.categoryId
-                .split(',')
-                .map((s: string) => s.trim())
-                .filter(Boolean);
-              remoteTagIds = categoryNames.map((name: string) => ensureTagExists(name));
-            }
-
-            // Check if local task is missing tags that exist on server
-            const localTagIds = localTask.tags || [];
-            const tagsMatch =
-              remoteTagIds.length === localTagIds.length &&
-              remoteTagIds.every((id) => localTagIds.includes(id));
-
-            if (remoteTask.etag !== localTask.etag) {
-              // Only update from server if local task is synced (no local changes)
-              if (localTask.synced) {
-                taskData.updateTask(localTask.id, {
-                  ...remoteTask,
-                  id: localTask.id, // Keep local ID
-                  tags: remoteTagIds,
-                  synced: true,
-                });
-              }
-            } else if (!tagsMatch && localTask.synced) {
-              // Etag matches but tags don't - sync tags without marking as unsynced
-              taskData.updateTask(localTask.id, {
-                tags: remoteTagIds,
-                synced: true,
-              });
+        } else if (localTask.synced) {
+          // the task itself is unchanged, but checklist items uploaded as tasks can have been
+          // edited or removed on the server, and items removed here are still up there
+          let subtasks = localTask.subtasks;
+          if (calendar.checklistAsTasks) {
+            const remoteItems = new Map(remoteTask.subtasks.map((item) => [item.id, item]));
+            const localItemIds = new Set(localTask.subtasks.map((item) => item.id));
+            const removedHere = remoteTask.subtasks
+              .filter((item) => !localItemIds.has(item.id))
+              .map((item) => item.id);
+            if (removedHere.length > 0 && !readOnly) {
+              await backend.deleteChecklistItems(
+                account.id,
+                calendar,
+                localTask.uid,
+                removedHere,
+              );
             }
+            subtasks = localTask.subtasks.flatMap((item) => remoteItems.get(item.id) ?? []);
+          }
+          const checklistMatches = JSON.stringify(subtasks) === JSON.stringify(localTask.subtasks);
+
+          // Etag matches but tags or checklist don't - sync them without marking as unsynced.
+          // this catches up with the server, it isn't an edit, so modifiedAt stays
+          if (!tagsMatch || !checklistMatches) {
+            upserts.push({
+              ...localTask,
+              tags: remoteTagIds,
+              subtasks,
+              synced: true,
+            });
           }
         }
       }
 
+      await taskData.upsertTasks(upserts);
+
       // Find tasks deleted on server (in local but not in remote)
       for (const localTask of updatedLocalTasks) {
         if (localTask.synced && !remoteUids.has(localTask.uid)) {
@@ -352,25 +489,34 @@ export function useSyncQuery() {
   );
 
   /**
-   * Sync all calendars for all accounts
+   * Sync all calendars of the given accounts, or of all accounts
    */
-  const syncAll = useCallback(async () => {
+  const syncAccounts = useCallback(async (accountIds: string[] | null) => {
     // Skip if offline
     if (!navigator.onLine) {
       log.info('Skipping sync - offline');
       setLastSyncError('You are offline. Changes will sync when you reconnect.');
       return;
     }
-
-    log.info('Starting sync...');
+    log.info(isReadOnly() ? 'Starting sync, only pulling in read-only mode' : 'Starting sync...');
     setIsSyncing(true);
     setLastSyncError(null);
 
+    // what a sync pulls is written in read-only mode too
+    const endPull = startPull();
     try {
       await reconnectAccounts();
+      // renew access tokens before they run out mid-sync
+      await refreshOAuthTokens()
+        .then(() => Promise.all(allSyncBackends().map((backend) => backend.refreshBearerTokens())))
+        .catch((e) => log.warn('Failed to refresh OAuth tokens:', e));
 
       // get fresh accounts from data layer
-      let freshAccounts = getAccounts();
+      const selected = (accounts: ReturnType<typeof getAccounts>) =>
+        accountIds ? accounts.filter((account) => accountIds.includes(account.id)) : accounts;
+      let freshAccounts = selected(getAccounts());
+      // first error per account, for the account health
+      const failures = new Map<string, string>();
 
       // sync calendars for each account (add/remove/update calendars)
       for (const account of freshAccounts) {
@@ -378,13 +524,14 @@ export function useSyncQuery() {
           await syncCalendarsForAccount(account.id);
         } catch (error) {
           const errorMessage = error instanceof Error ? error.message : 'Unknown error';
+          failures.set(account.id, errorMessage);
           log.error(`Failed to sync calendars for ${account.name}:`, error);
           await showSyncErrorNotification(account.name, errorMessage);
         }
       }
 
       // re-fetch accounts after calendar sync (calendars may have been added/removed)
-      freshAccounts = getAccounts();
+      freshAccounts = selected(getAccounts());
 
       // sync tasks for each calendar
       for (const account of freshAccounts) {
@@ -393,10 +540,20 @@ export function useSyncQuery() {
             await syncCalendar(calendar.id);
           } catch (error) {
             const errorMessage = error instanceof Error ? error.message : 'Unknown error';
+            // a calendar that 404s may have been deleted on the server; listing the calendars
+            // again keeps its tasks in the Recovered list when it's gone
+            if (isNotFound(errorMessage)) {
+              const listed = await syncCalendarsForAccount(account.id).catch(() => null);
+              if (listed && !listed.some((c) => c.id === calendar.id)) continue;
+            }
+            if (!failures.has(account.id)) failures.set(account.id, errorMessage);
             log.error(`Failed to sync calendar ${calendar.displayName}:`, error);
             await showSyncErrorNotification(calendar.displayName, errorMessage);
           }
         }
+        reportSyncResult(account.id, failures.get(account.id) ?? null).catch((e) =>
+          log.error('Failed to record account health:', e),
+        );
       }
     } catch (error) {
       const message = error instanceof Error ? error.message : 'Sync failed';
@@ -404,16 +561,25 @@ export function useSyncQuery() {
       log.error('Sync error:', error);
       await showSyncErrorNotification('Sync', message);
     } finally {
+      endPull();
       setIsSyncing(false);
       setLastSyncTime(new Date());
+      queryClient.invalidateQueries({ queryKey: queryKeys.pendingChanges });
     }
-  }, [reconnectAccounts, syncCalendar, syncCalendarsForAccount]);
+  }, [queryClient, reconnectAccounts, syncCalendar, syncCalendarsForAccount]);
+
+  /**
+   * Sync all calendars for all accounts
+   */
+  const syncAll = useCallback(() => syncAccounts(null), [syncAccounts]);
 
   /**
    * Push a task to the server
    */
   const pushTask = useCallback(
     async (task: Task) => {
+      // nothing is uploaded in read-only mode
+      if (isReadOnly()) return;
       const accounts = getAccounts();
       const account = accounts.find((a) => a.id === task.accountId);
       if (!account) return;
@@ -421,19 +587,20 @@ export function useSyncQuery() {
       const calendar = account.calendars.find((c) => c.id === task.calendarId);
       if (!calendar) return;
 
-      if (!caldavService.isConnected(account.id)) {
-        await caldavService.reconnect(account);
+      const backend = syncBackendFor(account);
+      if (!backend.isConnected(account.id)) {
+        await backend.reconnect(account);
       }
 
       if (task.href) {
         // Update existing
-        const result = await caldavService.updateTask(account.id, task);
+        const result = await backend.updateTask(account.id, task, calendar);
         if (result) {
           taskData.updateTask(task.id, { etag: result.etag, synced: true });
         }
       } else {
         // Create new
-        const result = await caldavService.createTask(account.id, calendar, task);
+        const result = await backend.createTask(account.id, calendar, task);
         if (result) {
           taskData.updateTask(task.id, { href: result.href, etag: result.etag, synced: true });
         }
@@ -449,16 +616,18 @@ export function useSyncQuery() {
    */
   const removeTaskFromServer = useCallback(async (task: Task) => {
     if (!task.href) return true; // Not on server yet
+    if (isReadOnly()) return false;
 
     const accounts = getAccounts();
     const account = accounts.find((a) => a.id === task.accountId);
     if (!account) return false;
 
-    if (!caldavService.isConnected(account.id)) {
-      await caldavService.reconnect(account);
+    const backend = syncBackendFor(account);
+    if (!backend.isConnected(account.id)) {
+      await backend.reconnect(account);
     }
 
-    return caldavService.deleteTask(account.id, task);
+    return backend.deleteTask(account.id, task);
   }, []);
 
   // Initial sync on mount
@@ -473,13 +642,28 @@ export function useSyncQuery() {
   const activeCalendarId = taskData.getUIState().activeCalendarId;
   useEffect(() => {
     if (activeCalendarId) {
-      syncCalendar(activeCalendarId).catch((error) =>
-        log.error('Active calendar sync failed:', error),
-      );
+      const endPull = startPull();
+      syncCalendar(activeCalendarId)
+        .catch((error) => log.error('Active calendar sync failed:', error))
+        .finally(endPull);
     }
   }, [activeCalendarId, syncCalendar]);
 
-  // Auto-sync interval
+  // another program changed the folder of a local folder account (Syncthing, git, an editor)
+  useEffect(() => {
+    const unlisten = listen<string>('local-folder-changed', (event) => {
+      if (isSyncing) return;
+      syncAccounts([event.payload]).catch((error) =>
+        log.error('Local folder sync failed:', error),
+      );
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [isSyncing, syncAccounts]);
+
+  // Auto-sync: every account on its own interval, outside its quiet hours
   useEffect(() => {
     // Clear existing interval
     if (autoSyncIntervalRef.current) {
@@ -489,15 +673,18 @@ export function useSyncQuery() {
 
     const accounts = getAccounts();
     // Set up new interval if autosync is enabled
-    if (autoSync && syncInterval > 0 && accounts.length > 0) {
-      autoSyncIntervalRef.current = setInterval(
-        () => {
-          if (!isOffline && !isSyncing) {
-            syncAll();
+    if (autoSync && accounts.length > 0) {
+      autoSyncIntervalRef.current = setInterval(async () => {
+        if (isOffline || isSyncing) return;
+        try {
+          const due = await getDueAccounts(syncInterval);
+          if (due.length > 0) {
+            await syncAccounts(due);
           }
-        },
-        syncInterval * 60 * 1000,
-      );
+        } catch (error) {
+          log.error('Scheduled sync failed:', error);
+        }
+      }, SCHEDULE_CHECK_INTERVAL);
     }
 
     return () => {
@@ -505,7 +692,7 @@ export function useSyncQuery() {
         clearInterval(autoSyncIntervalRef.current);
       }
     };
-  }, [autoSync, syncInterval, isOffline, isSyncing, syncAll]);
+  }, [autoSync, syncInterval, isOffline, isSyncing, syncAccounts]);
 
   return {
     isSyncing,
@@ -0,0 +1,33 @@
+/**
+ * TanStack Query hooks for when each account syncs on its own
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { getSyncSchedules, type SyncSchedule, setSyncSchedule } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get the sync interval and quiet hours of every account
+ */
+export function useSyncSchedules() {
+  return useQuery({
+    queryKey: queryKeys.syncSchedules,
+    queryFn: () => getSyncSchedules(),
+  });
+}
+
+/**
+ * Hook to change an account's sync interval or quiet hours
+ */
+export function useSetSyncSchedule() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (schedule: SyncSchedule) => setSyncSchedule(schedule),
+    onSuccess: (_, schedule) => {
+      queryClient.setQueryData<SyncSchedule[]>(queryKeys.syncSchedules, (list) =>
+        list?.map((s) => (s.accountId === schedule.accountId ? schedule : s)),
+      );
+    },
+  });
+}
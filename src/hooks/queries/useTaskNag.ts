@@ -0,0 +1,61 @@
+/**
+ * TanStack Query hooks for nagging: reminders that fire again until the task is done
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { dismissTaskNag, getTaskNag, setTaskNag } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get how a task nags, null when it doesn't
+ */
+export function useTaskNag(uid: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen<string>('nag-changed', (event) => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.taskNag(event.payload) });
+    });
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.taskNag(uid),
+    queryFn: () => getTaskNag(uid),
+    // whether it nags right now changes as reminders go off
+    refetchInterval: 60 * 1000,
+  });
+}
+
+/**
+ * Hook to turn nagging on (every `intervalMinutes`) or off (null)
+ */
+export function useSetTaskNag() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ uid, intervalMinutes }: { uid: string; intervalMinutes: number | null }) =>
+      setTaskNag(uid, intervalMinutes),
+    onSuccess: (_, { uid }) => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.taskNag(uid) });
+    },
+  });
+}
+
+/**
+ * Hook to stop the nagging about the reminder that went off
+ */
+export function useDismissTaskNag() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (uid: string) => dismissTaskNag(uid),
+    onSuccess: (_, uid) => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.taskNag(uid) });
+    },
+  });
+}
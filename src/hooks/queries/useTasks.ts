@@ -2,12 +2,20 @@
  * TanStack Query hooks for tasks
  */
 
-import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  keepPreviousData,
+  useInfiniteQuery,
+  useMutation,
+  useQuery,
+  useQueryClient,
+} from '@tanstack/react-query';
 import { useEffect } from 'react';
+import { queryVisibleTasks, type TaskView, type VisibleTaskPage } from '@/lib/database';
 import { queryKeys } from '@/lib/queryClient';
 import * as taskData from '@/lib/taskData';
-import type { SortConfig, Subtask, Task } from '@/types';
+import type { RelativeTrigger, SortConfig, Subtask, Task } from '@/types';
 import type { FlattenedTask } from '@/utils/tree';
+import { useUIState } from './useUIState';
 
 // ============================================================================
 // Query Hooks
@@ -51,6 +59,49 @@ export function useFilteredTasks() {
   });
 }
 
+// how many tasks of the list are loaded at a time; more are loaded when scrolling down
+const VISIBLE_TASKS_PAGE_SIZE = 200;
+
+function nextVisibleTasksOffset(last: VisibleTaskPage) {
+  const next = last.offset + last.tasks.length;
+  return next < last.total ? next : undefined;
+}
+
+/**
+ * Hook to get the task list exactly as shown (filtered, sorted and flattened by the backend),
+ * one page at a time
+ */
+export function useVisibleTasks() {
+  const queryClient = useQueryClient();
+  const { data: uiState } = useUIState();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: ['visibleTasks'] });
+    });
+  }, [queryClient]);
+
+  const view: TaskView = {
+    calendarId: uiState?.activeCalendarId ?? null,
+    tagId: uiState?.activeTagId ?? null,
+    search: uiState?.searchQuery ?? '',
+    showCompleted: uiState?.showCompletedTasks ?? true,
+    sort: uiState?.sortConfig ?? { mode: 'manual', direction: 'asc' },
+  };
+
+  return useInfiniteQuery({
+    queryKey: ['visibleTasks', view],
+    queryFn: ({ pageParam }) => queryVisibleTasks(view, pageParam, VISIBLE_TASKS_PAGE_SIZE),
+    initialPageParam: 0,
+    getNextPageParam: nextVisibleTasksOffset,
+    // the pages loaded so far, as one list
+    select: (data) => data.pages.flatMap((page) => page.tasks),
+    staleTime: Infinity,
+    // keep showing the previous list while the next one loads
+    placeholderData: keepPreviousData,
+  });
+}
+
 /**
  * Hook to get sorted tasks
  */
@@ -84,6 +135,38 @@ export function useTask(id: string | null) {
   });
 }
 
+/**
+ * Hook to get the open tasks of one calendar in display order, apart from the UI state (for
+ * a window showing just that calendar)
+ */
+export function useCalendarView(calendarId: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: ['visibleTasks'] });
+    });
+  }, [queryClient]);
+
+  const view: TaskView = {
+    calendarId,
+    tagId: null,
+    search: '',
+    showCompleted: false,
+    sort: { mode: 'manual', direction: 'asc' },
+  };
+
+  return useInfiniteQuery({
+    queryKey: ['visibleTasks', view],
+    queryFn: ({ pageParam }) => queryVisibleTasks(view, pageParam, VISIBLE_TASKS_PAGE_SIZE),
+    initialPageParam: 0,
+    getNextPageParam: nextVisibleTasksOffset,
+    select: (data) => data.pages.flatMap((page) => page.tasks),
+    staleTime: Infinity,
+    placeholderData: keepPreviousData,
+  });
+}
+
 /**
  * Hook to get tasks by calendar
  */
@@ -251,8 +334,7 @@ export function useReorderTasks() {
       flattenedItems: FlattenedTask[];
       targetIndent?: number;
     }) => {
-      taskData.reorderTasks(activeId, overId, flattenedItems, targetIndent);
-      return Promise.resolve();
+      return taskData.reorderTasks(activeId, overId, flattenedItems, targetIndent);
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: queryKeys.tasks.all });
@@ -368,8 +450,16 @@ export function useAddReminder() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: ({ taskId, trigger }: { taskId: string; trigger: Date }) => {
-      taskData.addReminder(taskId, trigger);
+    mutationFn: ({
+      taskId,
+      trigger,
+      relative,
+    }: {
+      taskId: string;
+      trigger: Date;
+      relative?: RelativeTrigger;
+    }) => {
+      taskData.addReminder(taskId, trigger, relative);
       return Promise.resolve();
     },
     onSuccess: () => {
@@ -400,12 +490,14 @@ export function useUpdateReminder() {
       taskId,
       reminderId,
       trigger,
+      relative,
     }: {
       taskId: string;
       reminderId: string;
       trigger: Date;
+      relative?: RelativeTrigger;
     }) => {
-      taskData.updateReminder(taskId, reminderId, trigger);
+      taskData.updateReminder(taskId, reminderId, trigger, relative);
       return Promise.resolve();
     },
     onSuccess: () => {
@@ -0,0 +1,104 @@
+/**
+ * TanStack Query hooks for time tracking
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import {
+  deleteTimeEntry,
+  getRunningTimer,
+  getTimeEntries,
+  getTimeTotals,
+  startTimer,
+  type TimeGrouping,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+import * as taskData from '@/lib/taskData';
+import type { TimeEntry } from '@/types';
+
+/**
+ * Hook to get the running timer, null when none runs
+ */
+export function useRunningTimer() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    // the tray and other windows start and stop timers too
+    const unlisten = listen<TimeEntry | null>('timer-changed', (event) => {
+      queryClient.setQueryData(queryKeys.runningTimer, event.payload);
+      queryClient.invalidateQueries({ queryKey: queryKeys.timeEntries.all });
+      queryClient.invalidateQueries({ queryKey: queryKeys.timeTotals.all });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.runningTimer,
+    queryFn: getRunningTimer,
+    staleTime: Infinity,
+  });
+}
+
+/**
+ * Hook to get every entry tracked for a task, oldest first
+ */
+export function useTimeEntries(taskUid: string) {
+  return useQuery({
+    queryKey: queryKeys.timeEntries.byTask(taskUid),
+    queryFn: () => getTimeEntries(taskUid),
+  });
+}
+
+/**
+ * Hook to get tracked time per task, tag or day for entries started in [from, to)
+ */
+export function useTimeTotals(groupBy: TimeGrouping, from?: string, to?: string) {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    return taskData.subscribeToDataChanges(() => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.timeTotals.all });
+    });
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.timeTotals.byRange(groupBy, from, to),
+    queryFn: () => getTimeTotals(groupBy, from, to),
+  });
+}
+
+/**
+ * Hook to start timing a task; a running timer is stopped first
+ */
+export function useStartTimer() {
+  return useMutation({
+    mutationFn: (taskUid: string) => startTimer(taskUid),
+  });
+}
+
+/**
+ * Hook to stop the running timer
+ */
+export function useStopTimer() {
+  return useMutation({
+    mutationFn: () => taskData.stopTimer(),
+  });
+}
+
+/**
+ * Hook to delete a tracked entry
+ */
+export function useDeleteTimeEntry() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => deleteTimeEntry(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.timeEntries.all });
+      queryClient.invalidateQueries({ queryKey: queryKeys.timeTotals.all });
+    },
+  });
+}
@@ -0,0 +1,18 @@
+/**
+ * TanStack Query hooks for the system tray
+ */
+
+import { useQuery } from '@tanstack/react-query';
+import { isTraySupported } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get whether the desktop can show a tray icon
+ */
+export function useTraySupported() {
+  return useQuery({
+    queryKey: queryKeys.traySupported,
+    queryFn: () => isTraySupported(),
+    staleTime: Infinity,
+  });
+}
@@ -0,0 +1,88 @@
+/**
+ * TanStack Query hooks for outgoing webhooks
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  deleteWebhook,
+  getWebhookDeliveries,
+  getWebhooks,
+  saveWebhook,
+  type TaskEvent,
+  testWebhook,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get every webhook
+ */
+export function useWebhooks() {
+  return useQuery({
+    queryKey: queryKeys.webhooks.all,
+    queryFn: () => getWebhooks(),
+  });
+}
+
+/**
+ * Hook to get the latest deliveries of a webhook, newest first
+ */
+export function useWebhookDeliveries(webhookId: string) {
+  return useQuery({
+    queryKey: queryKeys.webhooks.deliveries(webhookId),
+    queryFn: () => getWebhookDeliveries(webhookId),
+    // retries happen in the background
+    refetchInterval: 30_000,
+  });
+}
+
+/**
+ * Hook to add a webhook (id null) or change one
+ */
+export function useSaveWebhook() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      id,
+      url,
+      events,
+      enabled,
+    }: {
+      id: string | null;
+      url: string;
+      events: TaskEvent[];
+      enabled: boolean;
+    }) => saveWebhook(id, url, events, enabled),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.webhooks.all });
+    },
+  });
+}
+
+/**
+ * Hook to delete a webhook with its delivery log
+ */
+export function useDeleteWebhook() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => deleteWebhook(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.webhooks.all });
+    },
+  });
+}
+
+/**
+ * Hook to send a test payload to a webhook; resolves to its delivery
+ */
+export function useTestWebhook() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (id: string) => testWebhook(id),
+    onSettled: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.webhooks.all });
+    },
+  });
+}
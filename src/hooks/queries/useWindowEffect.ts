@@ -0,0 +1,31 @@
+/**
+ * TanStack Query hooks for the effect behind the app's windows
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { getWindowEffect, setWindowEffect, type WindowEffect } from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get the window effect and the ones this platform supports
+ */
+export function useWindowEffect() {
+  return useQuery({
+    queryKey: queryKeys.windowEffect,
+    queryFn: () => getWindowEffect(),
+  });
+}
+
+/**
+ * Hook to pick the window effect, applied to every open window and kept for the next launch
+ */
+export function useSetWindowEffect() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (effect: WindowEffect) => setWindowEffect(effect),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.windowEffect });
+    },
+  });
+}
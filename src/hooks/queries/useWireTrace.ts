@@ -0,0 +1,75 @@
+/**
+ * TanStack Query hooks for the CalDAV wire trace
+ */
+
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import {
+  clearWireTrace,
+  getWireTrace,
+  getWireTraceEnabled,
+  setWireTraceEnabled,
+} from '@/lib/database';
+import { queryKeys } from '@/lib/queryClient';
+
+/**
+ * Hook to get whether the requests of syncs are recorded
+ */
+export function useWireTraceEnabled() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen<boolean>('wire-trace-changed', () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.wireTrace.all });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: queryKeys.wireTrace.enabled,
+    queryFn: () => getWireTraceEnabled(),
+  });
+}
+
+/**
+ * Hook to start or stop recording the requests of syncs
+ */
+export function useSetWireTraceEnabled() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (enabled: boolean) => setWireTraceEnabled(enabled),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.wireTrace.all });
+    },
+  });
+}
+
+/**
+ * Hook to get the recorded requests, oldest first
+ */
+export function useWireTrace(enabled: boolean) {
+  return useQuery({
+    queryKey: queryKeys.wireTrace.entries,
+    queryFn: () => getWireTrace(),
+    enabled,
+  });
+}
+
+/**
+ * Hook to drop the recorded requests
+ */
+export function useClearWireTrace() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: () => clearWireTrace(),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.wireTrace.entries });
+    },
+  });
+}
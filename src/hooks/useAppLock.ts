@@ -0,0 +1,25 @@
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { closeDatabase } from '@/lib/database';
+import { createLogger } from '@/lib/logger';
+
+const log = createLogger('AppLock', '#e11d48');
+
+/**
+ * Hook to leave the app when it locks (after being idle, or by hand): the connection and
+ * everything cached from it go, and the reload starts over at the lock screen
+ * Should be used in the root App component
+ */
+export function useAppLock() {
+  useEffect(() => {
+    const unlisten = listen('app-locked', async () => {
+      log.info('App locked, reloading to the lock screen');
+      await closeDatabase().catch((error) => log.warn('Failed to close the database:', error));
+      window.location.reload();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+}
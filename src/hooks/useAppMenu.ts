@@ -1,5 +1,5 @@
 import { useEffect } from 'react';
-import { useAccounts, useTasks, useUIState } from '@/hooks/queries';
+import { useAccounts, useLanguage, useTasks, useUIState } from '@/hooks/queries';
 import { loggers } from '@/lib/logger';
 import { useSettingsStore } from '@/store/settingsStore';
 import { rebuildAppMenu, updateMenuState } from '@/utils/menu';
@@ -7,13 +7,14 @@ import { rebuildAppMenu, updateMenuState } from '@/utils/menu';
 const log = loggers.app;
 
 /**
- * hook to manage macOS app menu state synchronization
+ * hook to keep the app menu (macOS menu bar, main window menu elsewhere) in sync with the app
  */
 export function useAppMenu() {
   const { data: accounts = [] } = useAccounts();
   const { data: tasks = [] } = useTasks();
   const { data: uiState } = useUIState();
   const { keyboardShortcuts } = useSettingsStore();
+  const { data: language } = useLanguage();
 
   // update menu state when accounts or tasks change
   useEffect(() => {
@@ -37,7 +38,8 @@ export function useAppMenu() {
     });
   }, [accounts.length, tasks.length, uiState?.showCompletedTasks, uiState?.sortConfig?.mode]);
 
-  // Rebuild menu when keyboard shortcuts change
+  // Rebuild menu when keyboard shortcuts or the backend language change
+  // biome-ignore lint/correctness/useExhaustiveDependencies: the menu labels come from the backend in its language
   useEffect(() => {
     const sortMode = uiState?.sortConfig?.mode ?? 'manual';
     const menuSortMode:
@@ -54,5 +56,5 @@ export function useAppMenu() {
       sortMode: menuSortMode,
       shortcuts: keyboardShortcuts,
     });
-  }, [keyboardShortcuts, uiState?.showCompletedTasks, uiState?.sortConfig?.mode]);
+  }, [keyboardShortcuts, uiState?.showCompletedTasks, uiState?.sortConfig?.mode, language?.active]);
 }
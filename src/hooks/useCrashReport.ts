@@ -0,0 +1,62 @@
+import { save } from '@tauri-apps/plugin-dialog';
+import { writeTextFile } from '@tauri-apps/plugin-fs';
+import { format } from 'date-fns';
+import { useEffect } from 'react';
+import { type CrashReport, dismissCrashReport, getLastCrashReport } from '@/lib/database';
+import { createLogger } from '@/lib/logger';
+import { useConfirmDialog } from './useConfirmDialog';
+
+const log = createLogger('CrashReport', '#dc2626');
+
+// the prompt is shown once per launch, not again when the app component remounts
+let prompted = false;
+
+// save or copy the report; false when the save dialog was cancelled
+async function shareReport(report: CrashReport, how: 'save' | 'copy'): Promise<boolean> {
+  if (how === 'copy') {
+    await navigator.clipboard.writeText(report.contents);
+    return true;
+  }
+  const path = await save({
+    defaultPath: report.fileName,
+    filters: [{ name: 'Text', extensions: ['txt'] }],
+  });
+  if (!path) return false;
+  await writeTextFile(path, report.contents);
+  return true;
+}
+
+/**
+ * Hook to offer the crash report of the last run, if the app crashed, for sharing;
+ * the report is deleted once it was shared or dismissed
+ * Should be used in the root App component
+ */
+export function useCrashReport() {
+  const { confirmWithAlternate } = useConfirmDialog();
+
+  useEffect(() => {
+    if (prompted) return;
+    prompted = true;
+
+    getLastCrashReport()
+      .then(async (report) => {
+        if (!report) return;
+        const choice = await confirmWithAlternate({
+          title: 'The app crashed',
+          subtitle: format(new Date(report.createdAt * 1000), 'PPpp'),
+          message:
+            'A crash report was written the last time the app closed unexpectedly. It has the error, a backtrace and the last lines of the log, and helps to find the cause when it is attached to an issue.',
+          confirmLabel: 'Save Report',
+          alternateLabel: 'Copy Report',
+          cancelLabel: 'Dismiss',
+        });
+        if (choice !== 'cancel') {
+          const shared = await shareReport(report, choice === 'confirm' ? 'save' : 'copy');
+          // kept for the next launch when saving was cancelled
+          if (!shared) return;
+        }
+        await dismissCrashReport(report.fileName);
+      })
+      .catch((error) => log.warn('Failed to handle the crash report:', error));
+  }, [confirmWithAlternate]);
+}
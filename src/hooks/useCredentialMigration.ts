@@ -0,0 +1,51 @@
+import { listen } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+import { type CredentialMigrationReport, getCredentialMigrationReport } from '@/lib/database';
+import { useConfirmDialog } from './useConfirmDialog';
+
+// the report is kept until the app quits, so it's only pointed out once
+let reported = false;
+
+/**
+ * Hook to point out once which passwords the startup audit moved from the database into
+ * the system credential store, and which it couldn't move
+ * Should be used in the root App component
+ */
+export function useCredentialMigration() {
+  const { confirm } = useConfirmDialog();
+
+  useEffect(() => {
+    const show = (report: CredentialMigrationReport | null) => {
+      if (!report || reported) return;
+      if (report.migrated.length === 0 && report.failed.length === 0) return;
+      reported = true;
+
+      const lines: string[] = [];
+      if (report.migrated.length > 0) {
+        const names = report.migrated.map((account) => account.name).join(', ');
+        lines.push(`Now kept in the system credential store: ${names}.`);
+      }
+      for (const account of report.failed) {
+        lines.push(`${account.name} still has its password in the database (${account.error}).`);
+      }
+      confirm({
+        title: 'Passwords moved',
+        message: lines.join(' '),
+        confirmLabel: 'OK',
+        cancelLabel: 'Close',
+      });
+    };
+
+    // the audit usually finishes before the app is shown, so the event is missed
+    getCredentialMigrationReport()
+      .then(show)
+      .catch(() => {});
+    const unlisten = listen<CredentialMigrationReport>('credentials-migrated', (event) => {
+      show(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [confirm]);
+}
@@ -2,20 +2,18 @@ import { useCallback, useEffect, useMemo } from 'react';
 import { useModalState } from '@/context/modalStateContext';
 import {
   useCreateTask,
-  useFilteredTasks,
   useSetEditorOpen,
   useSetSearchQuery,
   useSetSelectedTask,
   useSetShowCompletedTasks,
   useToggleTaskComplete,
   useUIState,
+  useVisibleTasks,
 } from '@/hooks/queries';
 import { useConfirmDialog } from '@/hooks/useConfirmDialog';
 import { useConfirmTaskDelete } from '@/hooks/useConfirmTaskDelete';
 import { getIsKeyboardDragging } from '@/lib/dragState';
-import * as taskData from '@/lib/taskData';
 import { type KeyboardShortcut, useSettingsStore } from '@/store/settingsStore';
-import { flattenTasks } from '@/utils/tree';
 import {
   getAltKeyLabel,
   getMetaKeyLabel,
@@ -26,12 +24,14 @@ import {
 interface UseKeyboardShortcutsOptions {
   onOpenSettings?: () => void;
   onSync?: () => void;
+  onQuickOpen?: () => void;
 }
 
 export function useKeyboardShortcuts(options: UseKeyboardShortcutsOptions = {}) {
-  const { onOpenSettings, onSync } = options;
+  const { onOpenSettings, onSync, onQuickOpen } = options;
   const { data: uiState } = useUIState();
-  const { data: filteredTasks = [] } = useFilteredTasks();
+  // same list (and order) as the task list renders, for keyboard navigation
+  const { data: flattenedTasks = [], hasNextPage, fetchNextPage } = useVisibleTasks();
   const createTaskMutation = useCreateTask();
   const setSearchQueryMutation = useSetSearchQuery();
   const toggleTaskCompleteMutation = useToggleTaskComplete();
@@ -41,31 +41,12 @@ export function useKeyboardShortcuts(options: UseKeyboardShortcutsOptions = {})
 
   const selectedTaskId = uiState?.selectedTaskId ?? null;
   const showCompletedTasks = uiState?.showCompletedTasks ?? true;
-  const sortConfig = uiState?.sortConfig ?? { mode: 'manual' as const, direction: 'asc' as const };
 
   const { keyboardShortcuts } = useSettingsStore();
   const { confirmAndDelete } = useConfirmTaskDelete();
   const { isOpen: isConfirmDialogOpen } = useConfirmDialog();
   const { isAnyModalOpen } = useModalState();
 
-  // build the flattened task list that matches visual rendering order of tasks when using keyboard navigation
-  const flattenedTasks = useMemo(() => {
-    const topLevelTasks = filteredTasks.filter((task) => !task.parentUid);
-    const sortedTopLevel = taskData.getSortedTasks(topLevelTasks, sortConfig);
-
-    const getFilteredChildTasks = (parentUid: string) => {
-      const children = taskData.getChildTasks(parentUid);
-      if (!showCompletedTasks) {
-        return children.filter((task) => !task.completed);
-      }
-      return children;
-    };
-
-    return flattenTasks(sortedTopLevel, getFilteredChildTasks, (tasks) =>
-      taskData.getSortedTasks(tasks, sortConfig),
-    );
-  }, [filteredTasks, sortConfig, showCompletedTasks]);
-
   const handleNewTask = useCallback(() => {
     createTaskMutation.mutate(
       { title: '' },
@@ -134,7 +115,11 @@ export function useKeyboardShortcuts(options: UseKeyboardShortcutsOptions = {})
     if (currentIndex < flattenedTasks.length - 1) {
       setSelectedTaskMutation.mutate(flattenedTasks[currentIndex + 1].id);
     }
-  }, [selectedTaskId, flattenedTasks, setSelectedTaskMutation]);
+    // the list is loaded a page at a time; get the next one before running out
+    if (hasNextPage && currentIndex >= flattenedTasks.length - 2) {
+      fetchNextPage();
+    }
+  }, [selectedTaskId, flattenedTasks, setSelectedTaskMutation, hasNextPage, fetchNextPage]);
 
   const handleOpenSettings = useCallback(() => {
     // If settings is already open, this will close it (toggle behavior)
@@ -145,6 +130,10 @@ export function useKeyboardShortcuts(options: UseKeyboardShortcutsOptions = {})
     onSync?.();
   }, [onSync]);
 
+  const handleQuickOpen = useCallback(() => {
+    onQuickOpen?.();
+  }, [onQuickOpen]);
+
   const handleToggleShowCompleted = useCallback(() => {
     setShowCompletedMutation.mutate(!showCompletedTasks);
   }, [setShowCompletedMutation, showCompletedTasks]);
@@ -154,6 +143,7 @@ export function useKeyboardShortcuts(options: UseKeyboardShortcutsOptions = {})
     () => ({
       'new-task': handleNewTask,
       search: handleSearch,
+      'quick-open': handleQuickOpen,
       settings: handleOpenSettings,
       sync: handleSync,
       delete: handleDelete,
@@ -166,6 +156,7 @@ export function useKeyboardShortcuts(options: UseKeyboardShortcutsOptions = {})
     [
       handleNewTask,
       handleSearch,
+      handleQuickOpen,
       handleOpenSettings,
       handleSync,
       handleDelete,
@@ -213,6 +204,7 @@ export function useKeyboardShortcuts(options: UseKeyboardShortcutsOptions = {})
       const blockedInModal = [
         'new-task',
         'search',
+        'quick-open',
         'sync',
         'delete',
         'toggle-complete',
@@ -0,0 +1,29 @@
+import { useCallback, useRef } from 'react';
+
+/**
+ * Hook to load the next page of a list once its end scrolls into view
+ * Returns a ref for an element placed after the last item
+ */
+export function useLoadMore(hasNextPage: boolean, isFetchingNextPage: boolean, load: () => void) {
+  const observerRef = useRef<IntersectionObserver | null>(null);
+
+  return useCallback(
+    (element: HTMLElement | null) => {
+      observerRef.current?.disconnect();
+      observerRef.current = null;
+      if (!element || !hasNextPage || isFetchingNextPage) return;
+
+      observerRef.current = new IntersectionObserver(
+        (entries) => {
+          if (entries.some((entry) => entry.isIntersecting)) {
+            load();
+          }
+        },
+        // start loading a little before the end is reached
+        { rootMargin: '400px' },
+      );
+      observerRef.current.observe(element);
+    },
+    [hasNextPage, isFetchingNextPage, load],
+  );
+}
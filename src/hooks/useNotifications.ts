@@ -1,9 +1,11 @@
 import { isTauri } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { differenceInSeconds, isPast } from 'date-fns';
 import { useEffect, useRef } from 'react';
 import { useTasks } from '@/hooks/queries';
 import { createLogger } from '@/lib/logger';
 import { useSettingsStore } from '@/store/settingsStore';
+import { reminderTrigger } from '@/utils/date';
 
 const log = createLogger('Notifications', '#f43f5e');
 
@@ -87,19 +89,20 @@ export function useNotifications() {
         // Check reminders (VALARM)
         if (task.reminders && task.reminders.length > 0) {
           for (const reminder of task.reminders) {
-            const reminderKey = `reminder-${task.id}-${reminder.id}`;
+            // reminders related to the start or due date move with it and fire again
+            const reminderDate = reminderTrigger(task, reminder);
+            const reminderKey = `reminder-${task.id}-${reminder.id}-${reminderDate.getTime()}`;
 
             // skip if we already notified about this reminder
             if (notifiedRemindersRef.current.has(reminderKey)) continue;
 
-            const reminderDate = new Date(reminder.trigger);
             const secondsUntilReminder = differenceInSeconds(reminderDate, now);
 
             // Fire reminder when the time has arrived (0 or past, within 60 second window to avoid missing)
             // Using seconds for precision - fire when secondsUntilReminder is between 0 and -60
             if (secondsUntilReminder <= 0 && secondsUntilReminder >= -60) {
               showNotification({
-                title: 'Task Reminder',
+                title: reminder.related === 'start' ? 'Ready to Start' : 'Task Reminder',
                 body: task.title,
               });
               notifiedRemindersRef.current.add(reminderKey);
@@ -149,4 +152,22 @@ export function useNotifications() {
       }
     };
   }, [tasks, notifications]);
+
+  // completing a task can unblock the ones that depended on it
+  useEffect(() => {
+    if (!notifications || !isTauri()) return;
+
+    const unlisten = listen<{ title: string }[]>('tasks-unblocked', (event) => {
+      for (const task of event.payload) {
+        showNotification({
+          title: 'Task Unblocked',
+          body: task.title,
+        });
+      }
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [notifications]);
 }
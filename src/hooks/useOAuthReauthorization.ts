@@ -0,0 +1,37 @@
+import { listen } from '@tauri-apps/api/event';
+import { useEffect, useRef } from 'react';
+import { getAccountById } from '@/lib/taskData';
+import type { OAuthReauthorizationRequired } from './queries';
+import { useConfirmDialog } from './useConfirmDialog';
+
+/**
+ * Hook to ask once for a new authorization when an account's OAuth tokens can't be
+ * refreshed anymore, instead of letting every sync fail on them
+ * Should be used in the root App component
+ */
+export function useOAuthReauthorization(onOpenAccountSettings: () => void) {
+  const { confirm } = useConfirmDialog();
+  const onOpenRef = useRef(onOpenAccountSettings);
+  onOpenRef.current = onOpenAccountSettings;
+
+  useEffect(() => {
+    const unlisten = listen<OAuthReauthorizationRequired>(
+      'oauth-reauthorization-required',
+      async (event) => {
+        const account = getAccountById(event.payload.accountId);
+        const confirmed = await confirm({
+          title: 'Sign in again',
+          subtitle: account?.name,
+          message: `The access token can't be renewed (${event.payload.error}). The account doesn't sync until the tokens of a new authorization are entered in its settings.`,
+          confirmLabel: 'Open Settings',
+          cancelLabel: 'Later',
+        });
+        if (confirmed) onOpenRef.current();
+      },
+    );
+
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, [confirm]);
+}
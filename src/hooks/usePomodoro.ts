@@ -0,0 +1,73 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { useCallback, useEffect, useState } from 'react';
+
+export type PomodoroPhase = 'idle' | 'work' | 'shortBreak' | 'longBreak';
+
+export interface PomodoroStatus {
+  phase: PomodoroPhase;
+  running: boolean;
+  remainingSecs: number;
+  completed: number; // pomodoros finished since the last long break
+  taskUid: string | null;
+}
+
+export interface PomodoroSettings {
+  workMinutes: number;
+  shortBreakMinutes: number;
+  longBreakMinutes: number;
+  longBreakEvery: number;
+}
+
+/**
+ * the pomodoro timer runs in the backend (so it keeps going while the window is hidden);
+ * this mirrors its state and counts down locally between backend updates
+ */
+export function usePomodoro() {
+  const [status, setStatus] = useState<PomodoroStatus | null>(null);
+
+  useEffect(() => {
+    invoke<PomodoroStatus>('get_pomodoro_status')
+      .then(setStatus)
+      .catch((err) => {
+        console.error('Failed to get pomodoro status:', err);
+      });
+
+    const unlisten = listen<PomodoroStatus>('pomodoro-changed', (event) => {
+      setStatus(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const running = status?.running ?? false;
+  useEffect(() => {
+    if (!running) return;
+
+    const interval = setInterval(() => {
+      setStatus((current) =>
+        current ? { ...current, remainingSecs: Math.max(0, current.remainingSecs - 1) } : current,
+      );
+    }, 1000);
+
+    return () => clearInterval(interval);
+  }, [running]);
+
+  const run = useCallback((command: string, args?: Record<string, unknown>) => {
+    invoke<PomodoroStatus>(command, args)
+      .then(setStatus)
+      .catch((err) => {
+        console.error(`Failed to run ${command}:`, err);
+      });
+  }, []);
+
+  return {
+    status,
+    start: (taskUid?: string) => run('start_pomodoro', { taskUid: taskUid ?? null }),
+    pause: () => run('pause_pomodoro'),
+    skip: () => run('skip_pomodoro'),
+    stop: () => run('stop_pomodoro'),
+  };
+}
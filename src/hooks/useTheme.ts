@@ -1,11 +1,28 @@
-import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { useEffect, useState } from 'react';
+import { getSystemAppearance, type SystemAppearance } from '@/lib/database';
 import { applyAccentColor, applyTheme, useSettingsStore } from '@/store/settingsStore';
 
 /**
  * hook that applies the theme and accent color, and listens for system preference changes
  */
 export function useTheme() {
-  const { theme, accentColor } = useSettingsStore();
+  const { theme, accentColor, systemAccentColor, setAccentColor } = useSettingsStore();
+  // how the system looks as the backend sees it; it also notices changes the webview misses
+  const [system, setSystem] = useState<SystemAppearance | null>(null);
+
+  useEffect(() => {
+    getSystemAppearance()
+      .then(setSystem)
+      .catch(() => {});
+    const unlisten = listen<SystemAppearance>('appearance-changed', (event) => {
+      setSystem(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn()).catch(() => {});
+    };
+  }, []);
 
   // apply theme
   useEffect(() => {
@@ -14,6 +31,8 @@ export function useTheme() {
 
     // listen for system theme changes if using system theme
     if (theme === 'system') {
+      if (system) document.documentElement.classList.toggle('dark', system.dark);
+
       const mediaQuery = window.matchMedia('(prefers-color-scheme: dark)');
 
       const handleChange = () => {
@@ -23,7 +42,14 @@ export function useTheme() {
       mediaQuery.addEventListener('change', handleChange);
       return () => mediaQuery.removeEventListener('change', handleChange);
     }
-  }, [theme]);
+  }, [theme, system]);
+
+  // keep the accent color at the system's while it's followed
+  useEffect(() => {
+    if (systemAccentColor && system?.accentColor && system.accentColor !== accentColor) {
+      setAccentColor(system.accentColor);
+    }
+  }, [systemAccentColor, system, accentColor, setAccentColor]);
 
   // apply accent color
   useEffect(() => {
@@ -32,7 +32,7 @@ export function useTray({ isSyncing, lastSyncTime, onSyncRequest }: UseTrayOptio
 
   useEffect(() => {
     if (isSyncing) {
-      invoke('update_tray_sync_time', { timeStr: 'Last sync: Syncing...' }).catch((err) => {
+      invoke('update_tray_sync_time', { timeStr: null }).catch((err) => {
         console.error('Failed to update sync status:', err);
       });
     } else if (lastSyncTime) {
@@ -41,7 +41,7 @@ export function useTray({ isSyncing, lastSyncTime, onSyncRequest }: UseTrayOptio
         minute: '2-digit',
         hour12: false,
       });
-      invoke('update_tray_sync_time', { timeStr: `Last sync: ${timeStr}` }).catch((err) => {
+      invoke('update_tray_sync_time', { timeStr }).catch((err) => {
         console.error('Failed to update sync time:', err);
       });
     }
@@ -1,17 +1,12 @@
 import { isTauri } from '@tauri-apps/api/core';
-import { relaunch } from '@tauri-apps/plugin-process';
+import { listen } from '@tauri-apps/api/event';
+import { openUrl } from '@tauri-apps/plugin-opener';
 import { useCallback, useEffect, useState } from 'react';
+import { checkForUpdates as checkNow, installUpdate, type UpdateInfo } from '@/lib/database';
 import { createLogger } from '@/lib/logger';
 
 const log = createLogger('Updater', '#10b981');
 
-export interface UpdateInfo {
-  version: string;
-  body?: string;
-  date?: string;
-  currentVersion: string;
-}
-
 export interface UseUpdateCheckerResult {
   updateAvailable: UpdateInfo | null;
   isChecking: boolean;
@@ -23,6 +18,10 @@ export interface UseUpdateCheckerResult {
   downloadProgress: number;
 }
 
+/**
+ * Hook to follow the backend's updater: it checks on its own schedule (if the settings let
+ * it) and announces new releases, this only shows them and starts the install
+ */
 export function useUpdateChecker(): UseUpdateCheckerResult {
   const [updateAvailable, setUpdateAvailable] = useState<UpdateInfo | null>(null);
   const [isChecking, setIsChecking] = useState(false);
@@ -41,31 +40,14 @@ export function useUpdateChecker(): UseUpdateCheckerResult {
     setError(null);
 
     try {
-      log.info('Starting update check...');
-      const { check } = await import('@tauri-apps/plugin-updater');
-      const { getVersion } = await import('@tauri-apps/api/app');
-
-      const currentVersion = await getVersion();
-      log.info(`Current version: ${currentVersion}`);
-
-      log.info('Checking for updates from endpoint...');
-      const update = await check();
-      if (update) {
-        log.info(`Update available: ${update.version}`);
-        setUpdateAvailable({
-          version: update.version,
-          body: update.body,
-          date: update.date,
-          currentVersion,
-        });
-      } else {
+      const update = await checkNow();
+      if (!update) {
         log.info('No updates available');
-        setUpdateAvailable(null);
       }
+      setUpdateAvailable(update);
     } catch (err) {
-      const message = err instanceof Error ? err.message : 'Failed to check for updates';
       log.error('Update check failed:', err);
-      setError(message);
+      setError(String(err));
     } finally {
       setIsChecking(false);
     }
@@ -76,45 +58,19 @@ export function useUpdateChecker(): UseUpdateCheckerResult {
       return;
     }
 
-    setIsDownloading(true);
-    setDownloadProgress(0);
+    // packaged and portable builds are updated outside the app
+    if (!updateAvailable.canInstall) {
+      await openUrl(updateAvailable.releaseUrl);
+      return;
+    }
 
+    setIsDownloading(true);
     try {
-      const { check } = await import('@tauri-apps/plugin-updater');
-
-      const update = await check();
-      if (!update) {
-        throw new Error('Update no longer available');
-      }
-
-      let downloaded = 0;
-      let contentLength = 0;
-
-      await update.downloadAndInstall((event) => {
-        switch (event.event) {
-          case 'Started':
-            contentLength = event.data.contentLength ?? 0;
-            log.info(`Download started: ${contentLength} bytes`);
-            break;
-          case 'Progress': {
-            downloaded += event.data.chunkLength;
-            const progress = contentLength > 0 ? (downloaded / contentLength) * 100 : 0;
-            setDownloadProgress(progress);
-            break;
-          }
-          case 'Finished':
-            log.info('Download finished');
-            setDownloadProgress(100);
-            break;
-        }
-      });
-
-      log.info('Update installed, relaunching...');
-      await relaunch();
+      // the app restarts once it's installed
+      await installUpdate();
     } catch (err) {
-      const message = err instanceof Error ? err.message : 'Failed to download update';
-      log.error('Update download failed:', err);
-      setError(message);
+      log.error('Update install failed:', err);
+      setError(String(err));
     } finally {
       setIsDownloading(false);
     }
@@ -130,14 +86,28 @@ export function useUpdateChecker(): UseUpdateCheckerResult {
       return;
     }
 
-    const timer = setTimeout(() => {
-      checkForUpdates();
-    }, 5000); // Check 5 seconds after app starts
+    const unlistenAvailable = listen<UpdateInfo>('update-available', (event) => {
+      setDismissed(false);
+      setUpdateAvailable(event.payload);
+    });
+    const unlistenProgress = listen<{ downloaded: number; total: number | null }>(
+      'update-download-progress',
+      (event) => {
+        const { downloaded, total } = event.payload;
+        setDownloadProgress(total ? (downloaded / total) * 100 : 0);
+      },
+    );
+    const unlistenDownloaded = listen<string>('update-downloaded', (event) => {
+      log.info(`Update ${event.payload} downloaded`);
+      setDownloadProgress(100);
+    });
 
     return () => {
-      clearTimeout(timer);
+      unlistenAvailable.then((fn) => fn());
+      unlistenProgress.then((fn) => fn());
+      unlistenDownloaded.then((fn) => fn());
     };
-  }, [checkForUpdates]);
+  }, []);
 
   return {
     updateAvailable: dismissed ? null : updateAvailable,
@@ -1,11 +1,12 @@
+import { invoke } from '@tauri-apps/api/core';
 import { BaseDirectory, remove } from '@tauri-apps/plugin-fs';
 import { openUrl } from '@tauri-apps/plugin-opener';
 import { arch, exeExtension, locale, platform, version } from '@tauri-apps/plugin-os';
 import { relaunch } from '@tauri-apps/plugin-process';
-import { getUIState } from '@/lib/database';
+import { getShortcuts, getUIState, saveShortcuts, setLogLevel } from '@/lib/database';
 import { createLogger, initLogger } from '@/lib/logger';
 import { initializeDataStore } from '@/lib/taskData';
-import { useSettingsStore } from '@/store/settingsStore';
+import { defaultShortcuts, useSettingsStore, withDefaultShortcuts } from '@/store/settingsStore';
 import { initAppMenu } from '@/utils/menu';
 import { version as AppVersion } from '../../package.json';
 
@@ -19,25 +20,40 @@ export interface BootstrapResult {
   error?: Error;
 }
 
+// keep the backend's app lock idle timer from expiring while the user is active
+function trackActivity(): void {
+  let lastActivityReport = 0;
+  const reportActivity = () => {
+    const now = Date.now();
+    if (now - lastActivityReport < 30_000) return;
+    lastActivityReport = now;
+    invoke('report_activity').catch(() => {});
+  };
+  window.addEventListener('pointerdown', reportActivity, { passive: true });
+  window.addEventListener('keydown', reportActivity, { passive: true });
+}
+
 export async function initializeApp(): Promise<void> {
   // Initialize logger first so all subsequent logs are captured
   await initLogger();
   log.info('Starting application initialization...');
 
+  const { logLevel } = useSettingsStore.getState();
+  setLogLevel(logLevel).catch((error) => log.warn('Failed to apply the log level:', error));
+
   log.debug('Initializing data store...');
   await initializeDataStore();
   log.debug('Data store initialized');
 
   // initialize system tray based on settings
   log.debug('Initializing system tray...');
-  const { invoke } = await import('@tauri-apps/api/core');
   const enableSystemTray = useSettingsStore.getState().enableSystemTray;
-  try {
-    await invoke('initialize_tray', { enabled: enableSystemTray });
-    log.debug(`System tray initialized (enabled: ${enableSystemTray})`);
-  } catch (error) {
-    log.error('Failed to initialize system tray:', error);
-  }
+  // don't block startup on tray construction
+  invoke('initialize_tray', { enabled: enableSystemTray })
+    .then(() => log.debug(`System tray initialized (enabled: ${enableSystemTray})`))
+    .catch((error) => log.error('Failed to initialize system tray:', error));
+
+  trackActivity();
 
   log.debug('Getting UI state...');
   const uiState = await getUIState();
@@ -51,10 +67,23 @@ export async function initializeApp(): Promise<void> {
     | 'created'
     | 'modified' = sortMode === 'start-date' ? 'manual' : sortMode;
 
+  // custom shortcuts live in the backend; ones only stored here from before move over
+  const { keyboardShortcuts, setKeyboardShortcuts } = useSettingsStore.getState();
+  const custom = await getShortcuts().catch((error) => {
+    log.warn('Failed to load keyboard shortcuts:', error);
+    return null;
+  });
+  if (custom) {
+    setKeyboardShortcuts(withDefaultShortcuts(custom));
+  } else if (JSON.stringify(keyboardShortcuts) !== JSON.stringify(defaultShortcuts)) {
+    await saveShortcuts(keyboardShortcuts).catch((error) =>
+      log.warn('Failed to save keyboard shortcuts:', error),
+    );
+  }
   const shortcuts = useSettingsStore.getState().keyboardShortcuts;
   log.debug('Loaded keyboard shortcuts');
 
-  // initialize macOS application menu with current state and user shortcuts
+  // initialize the application menu with current state and user shortcuts
   log.debug('Initializing app menu...');
   await initAppMenu({
     showCompleted: uiState.showCompletedTasks,
@@ -65,6 +94,17 @@ export async function initializeApp(): Promise<void> {
   log.info('Application initialization complete');
 }
 
+/**
+ * start a window showing a single calendar: only the data store, the main window keeps
+ * the tray, the app menu and the sync
+ */
+export async function initializeCalendarWindow(): Promise<void> {
+  await initLogger();
+  log.info('Starting calendar window...');
+  await initializeDataStore();
+  trackActivity();
+}
+
 export async function showWindow(delay: number = 200): Promise<void> {
   return new Promise((resolve) => {
     setTimeout(async () => {
@@ -1,7 +1,25 @@
-import type { Account, Calendar, Task } from '@/types';
-import { taskToVTodo, vtodoToTask } from '../utils/ical';
+import { Channel, invoke } from '@tauri-apps/api/core';
+import { useSettingsStore } from '@/store/settingsStore';
+import type { Account, Calendar, Task, TimeEntry, TlsSettings } from '@/types';
+import {
+  type ChecklistItem,
+  checklistItemToVTodo,
+  checklistItemUid,
+  taskToVTodo,
+  vtodoToChecklistItem,
+  vtodoToTask,
+} from '../utils/ical';
+import {
+  getOAuthAccessToken,
+  getPayloadLimits,
+  reorderCalendars,
+  setAccountAuthMethod,
+} from './database';
 import { createLogger } from './logger';
+import type { SyncBackend } from './syncBackend';
+import * as taskData from './taskData';
 import {
+  type AuthMethod,
   type CalDAVCredentials,
   del,
   mkcalendar,
@@ -9,21 +27,294 @@ import {
   propfind,
   proppatch,
   put,
-  report,
 } from './tauri-http';
 
 const log = createLogger('CalDAV', '#3b82f6');
 
+/**
+ * the server's copy of a task changed since it was last synced, so the local edit was not
+ * uploaded over it
+ */
+export class SyncConflictError extends Error {
+  constructor(uid: string) {
+    super(`Task ${uid} was changed on the server`);
+    this.name = 'SyncConflictError';
+  }
+}
+
 interface AccountConnection {
   serverUrl: string;
   credentials: CalDAVCredentials;
   principalUrl: string;
   calendarHome: string;
   serverType: 'rustical' | 'radicale' | 'baikal' | 'nextcloud' | 'generic';
+  tls?: TlsSettings;
+}
+
+// iCloud serves each user's collections from a partition host (pXX-caldav.icloud.com) that
+// the calendar home points to, and that can change when Apple moves the account around
+function isICloud(url: string): boolean {
+  try {
+    return new URL(url).hostname.endsWith('.icloud.com');
+  } catch {
+    return false;
+  }
+}
+
+/**
+ * whether two collection urls are the same calendar: iCloud's differ only in the partition
+ * host when the account moved
+ */
+export function sameCollection(a: string, b: string): boolean {
+  if (a === b) return true;
+  return isICloud(a) && isICloud(b) && new URL(a).pathname === new URL(b).pathname;
+}
+
+/**
+ * whether a fetched task is only the last upload from here coming back: the server gives it a
+ * new etag (or had sent none for the upload), and may rewrite the data, but the change id and
+ * LAST-MODIFIED are still the ones uploaded. other clients keep unknown properties like the
+ * change id, so an edit made elsewhere is told apart by its newer LAST-MODIFIED
+ */
+export function isOwnUpload(local: Task, remote: Task): boolean {
+  if (!local.changeId || remote.changeId !== local.changeId) return false;
+  // LAST-MODIFIED only has whole seconds
+  const seconds = (date: Date) => Math.floor(new Date(date).getTime() / 1000);
+  return seconds(remote.modifiedAt) === seconds(local.modifiedAt);
+}
+
+/**
+ * hrefs resolved against `base` and escaped the one way the backend does, whatever mix of raw
+ * UTF-8, lowercase or doubled escapes and relative paths the server sent
+ */
+export async function normalizeHrefs(base: string, hrefs: string[]): Promise<string[]> {
+  if (hrefs.length === 0) return [];
+  return invoke<string[]>('normalize_hrefs', { base, hrefs });
+}
+
+// `href` on the host the account's collections are served from now
+function onCurrentHost(conn: AccountConnection, href: string): string {
+  if (!isICloud(conn.calendarHome)) return href;
+  const url = new URL(href, conn.calendarHome);
+  url.host = new URL(conn.calendarHome).host;
+  return url.toString();
+}
+
+// the account as the backend's CalDAV commands take it
+function backendAccount(conn: AccountConnection) {
+  return {
+    serverUrl: conn.serverUrl,
+    username: conn.credentials.username,
+    password: conn.credentials.password,
+    serverType: conn.serverType,
+    tls: conn.tls,
+    bearerToken: conn.credentials.bearerToken,
+    authMethod: conn.credentials.authMethod,
+  };
+}
+
+// settings that belong to this machine rather than follow the user around
+const MACHINE_SETTINGS = new Set([
+  'version',
+  'sidebarWidth',
+  'sidebarCollapsed',
+  'onboardingCompleted',
+  'expandedAccountIds',
+  'enableSystemTray',
+  'systemTrayRestartNeeded',
+  'systemTrayAppliedValue',
+]);
+
+// the sidebar order of the calendars roams along, by url since the ids differ per machine
+const CALENDAR_ORDER = 'calendarOrder';
+
+function roamingSettings(): Record<string, unknown> {
+  const all = JSON.parse(useSettingsStore.getState().exportSettings());
+  return {
+    ...Object.fromEntries(Object.entries(all).filter(([key]) => !MACHINE_SETTINGS.has(key))),
+    [CALENDAR_ORDER]: taskData
+      .getAllAccounts()
+      .flatMap((account) => account.calendars.map((calendar) => calendar.url)),
+  };
+}
+
+// calendars the roamed order doesn't know keep their place after the ones it does
+function applyCalendarOrder(urls: string[]): void {
+  const position = new Map(urls.map((url, i) => [url, i]));
+  const rank = (calendar: Calendar) => position.get(calendar.url) ?? urls.length;
+  for (const account of taskData.getAllAccounts()) {
+    const ids = [...account.calendars].sort((a, b) => rank(a) - rank(b)).map((c) => c.id);
+    if (ids.every((id, i) => id === account.calendars[i].id)) continue;
+    reorderCalendars(account.id, ids).catch((error) =>
+      log.error('Failed to apply the roamed calendar order:', error),
+    );
+  }
+}
+
+function applyRoamingSettings(settings: Record<string, unknown>): void {
+  const { [CALENDAR_ORDER]: calendarOrder, ...rest } = settings;
+  const { exportSettings, importSettings } = useSettingsStore.getState();
+  const current = JSON.parse(exportSettings());
+  importSettings(JSON.stringify({ ...current, ...rest, version: current.version }));
+  if (Array.isArray(calendarOrder)) applyCalendarOrder(calendarOrder);
+}
+
+// a VTODO resource streamed from the backend
+interface CalendarObject {
+  href: string;
+  etag: string | null;
+  /** null when the object is unchanged since the last fetch */
+  data: string | null;
+  /** over the object size limit and skipped */
+  tooLarge: boolean;
+}
+
+/** one occurrence of a calendar event, for the agenda */
+export interface CalendarEvent {
+  uid: string;
+  calendarUrl: string;
+  calendarName: string;
+  calendarColor: string | null;
+  title: string;
+  location: string | null;
+  start: string;
+  /** exclusive */
+  end: string;
+  allDay: boolean;
+}
+
+/** one email address of an address book contact */
+export interface Contact {
+  name: string;
+  email: string;
+}
+
+/** an attachment downloaded to the attachment cache */
+export interface CachedAttachment {
+  path: string;
+  contentType: string | null;
+  size: number;
+}
+
+interface CachedIcs {
+  href: string;
+  etag: string;
+  ics: string;
+}
+
+/**
+ * encrypt title and description for accounts with end-to-end encryption and mark the task
+ * (the backend has nothing to encrypt for other accounts)
+ */
+async function encryptTask(accountId: string, task: Task): Promise<Task> {
+  const encrypted = await invoke<string[] | null>('encrypt_texts', {
+    accountId,
+    texts: [task.title, task.description],
+  });
+  if (!encrypted) return task;
+  const [title, description] = encrypted;
+  return { ...task, title, description, encrypted: true };
+}
+
+/**
+ * the task as it is uploaded: encrypted, and with its finished time entries when the user
+ * syncs tracked time
+ */
+export async function prepareTask(accountId: string, task: Task): Promise<Task> {
+  const encrypted = await encryptTask(accountId, task);
+  if (!useSettingsStore.getState().syncTrackedTime) return encrypted;
+
+  const entries = await invoke<TimeEntry[]>('get_time_entries', { taskUid: task.uid });
+  return { ...encrypted, timeEntries: entries.filter((entry) => entry.endedAt) };
+}
+
+/**
+ * the VTODO, refused with a clear error when it's over the object size limit instead of
+ * failing (or timing out) at the server
+ */
+export async function withinLimit(ics: string): Promise<string> {
+  const { maxObjectKb } = await getPayloadLimits();
+  const size = Math.ceil(new TextEncoder().encode(ics).length / 1024);
+  if (size > maxObjectKb) {
+    throw new Error(
+      `The task is too large to upload (${size} KB, the limit is ${maxObjectKb} KB)`,
+    );
+  }
+  return ics;
+}
+
+/**
+ * decrypt titles and descriptions of fetched tasks marked as encrypted in one round trip;
+ * throws when one can't be decrypted, so the sync stops instead of storing the ciphertext
+ */
+export async function decryptTasks(accountId: string, tasks: Task[]): Promise<Task[]> {
+  const encrypted = tasks.filter((task) => task.encrypted);
+  if (encrypted.length === 0) return tasks;
+
+  const texts = encrypted.flatMap((task) => [task.title, task.description]);
+  const decrypted = await invoke<string[]>('decrypt_texts', { accountId, texts });
+  const byId = new Map(
+    encrypted.map((task, i) => [
+      task.id,
+      { title: decrypted[i * 2], description: decrypted[i * 2 + 1] },
+    ]),
+  );
+  return tasks.map((task) => {
+    const plain = byId.get(task.id);
+    return plain ? { ...task, ...plain, encrypted: undefined } : task;
+  });
+}
+
+/**
+ * where the child VTODO of a checklist item lives, next to its task
+ */
+function checklistItemUrl(calendar: Calendar, parentUid: string, itemId: string): string {
+  return `${calendar.url.replace(/\/$/, '')}/${checklistItemUid(parentUid, itemId)}.ics`;
 }
 
-class CalDAVService {
+/**
+ * fold fetched checklist item VTODOs back into the subtasks of their tasks, which replace
+ * whatever checklist the tasks had. items whose task isn't on the server (anymore) stay
+ * regular tasks so nothing goes missing
+ */
+function combineChecklistItems(tasks: Task[], items: Map<string, ChecklistItem>): Task[] {
+  const taskUids = new Set(tasks.filter((t) => !items.has(t.uid)).map((t) => t.uid));
+  const byParent = new Map<string, ChecklistItem[]>();
+  const rest: Task[] = [];
+
+  for (const task of tasks) {
+    const checklistItem = items.get(task.uid);
+    if (!checklistItem || !taskUids.has(checklistItem.parentUid)) {
+      rest.push(task);
+      continue;
+    }
+    // the parsed task has the decrypted title
+    const siblings = byParent.get(checklistItem.parentUid) ?? [];
+    siblings.push({ ...checklistItem, item: { ...checklistItem.item, title: task.title } });
+    byParent.set(checklistItem.parentUid, siblings);
+  }
+
+  return rest.map((task) => ({
+    ...task,
+    subtasks: (byParent.get(task.uid) ?? [])
+      .sort((a, b) => a.sortOrder - b.sortOrder)
+      .map(({ item }) => item),
+  }));
+}
+
+class CalDAVService implements SyncBackend {
   private connections: Map<string, AccountConnection> = new Map();
+  // why the last upload or deletion of a task failed, by uid, for the pending changes
+  private failures: Map<string, string> = new Map();
+
+  /**
+   * why the last upload or deletion of the task failed, once
+   */
+  takeFailure(uid: string): string | undefined {
+    const failure = this.failures.get(uid);
+    this.failures.delete(uid);
+    return failure;
+  }
 
   /**
    * connect to a CalDAV account
@@ -40,8 +331,22 @@ class CalDAVService {
     username: string,
     password: string,
     serverType: 'rustical' | 'radicale' | 'baikal' | 'nextcloud' | 'generic' = 'rustical',
+    tls?: TlsSettings,
+    bearerToken?: string,
+    authMethod?: AuthMethod,
   ): Promise<{ principalUrl: string; displayName: string }> {
-    const credentials: CalDAVCredentials = { username, password };
+    const credentials: CalDAVCredentials = {
+      username,
+      password,
+      bearerToken,
+      authMethod,
+      tls,
+      onAuthMethodChange: (method) => {
+        setAccountAuthMethod(accountId, method).catch((error) =>
+          log.warn(`Failed to remember the auth method of account ${accountId}:`, error),
+        );
+      },
+    };
 
     // normalize server URL - strip trailing slashes and common CalDAV paths
     // This allows users to paste full URLs like https://example.org/remote.php/dav/
@@ -110,6 +415,11 @@ class CalDAVService {
             'Server reported a 429 error (Rate limit exceeded). Please wait a moment and try again.',
           );
         }
+        if (wellKnownResponse.status === 401 && isICloud(baseUrl)) {
+          throw new Error(
+            'iCloud rejected the password. iCloud needs an app-specific password rather than your Apple Account password.',
+          );
+        }
         if (wellKnownResponse.status === 401) {
           throw new Error(
             'Server reported a 401 error (Authentication failed). Please check your username and password.',
@@ -164,10 +474,10 @@ class CalDAVService {
           throw new Error('Failed to discover calendar-home-set. Server may not support CalDAV.');
         }
 
-        // make calendar home URL absolute
-        calendarHome = discoveredCalendarHome.startsWith('http')
-          ? discoveredCalendarHome
-          : new URL(discoveredCalendarHome, baseUrl).toString();
+        // make calendar home URL absolute; it can be on another host than the principal
+        // (iCloud's partitions), and spelling out the default port would make the calendar
+        // urls differ from the same calendar's urls elsewhere
+        [calendarHome] = await normalizeHrefs(principalUrl, [discoveredCalendarHome]);
 
         break;
       }
@@ -205,6 +515,7 @@ class CalDAVService {
       principalUrl,
       calendarHome,
       serverType,
+      tls,
     });
 
     return { principalUrl, displayName };
@@ -240,16 +551,17 @@ class CalDAVService {
 
     const calendars: Calendar[] = [];
 
-    // extract the path from calendar home for comparison
-    const calendarHomePath = new URL(conn.calendarHome, conn.serverUrl).pathname;
+    // absolute and escaped alike, so the same calendar keeps the same url (and id)
+    const [homeUrl, ...calendarUrls] = await normalizeHrefs(conn.calendarHome, [
+      conn.calendarHome,
+      ...results.map((result) => result.href),
+    ]);
 
-    for (const result of results) {
-      // skip the calendar home itself (exact match only)
-      const resultPath = result.href.startsWith('http')
-        ? new URL(result.href).pathname
-        : result.href;
+    for (const [i, result] of results.entries()) {
+      const calendarUrl = calendarUrls[i];
 
-      if (resultPath === calendarHomePath || resultPath === calendarHomePath.replace(/\/$/, '')) {
+      // skip the calendar home itself (exact match only)
+      if (calendarUrl === homeUrl || calendarUrl === homeUrl.replace(/\/$/, '')) {
         continue;
       }
 
@@ -274,12 +586,6 @@ class CalDAVService {
         continue;
       }
 
-      // build absolute URL
-      let calendarUrl = result.href;
-      if (!calendarUrl.startsWith('http')) {
-        calendarUrl = new URL(result.href, conn.serverUrl).toString();
-      }
-
       calendars.push({
         id: calendarUrl,
         displayName: result.props.displayname || 'Calendar',
@@ -297,55 +603,214 @@ class CalDAVService {
 
   /**
    * fetch tasks from a calendar
+   * objects that are unchanged since the last fetch are taken from `localTasks` when a synced
+   * copy with the same etag exists, otherwise from the backend's ICS cache. in calendars that
+   * sync checklist items as tasks the item VTODOs come back as the subtasks of their task
    */
-  async fetchTasks(accountId: string, calendar: Calendar): Promise<Task[]> {
+  async fetchTasks(
+    accountId: string,
+    calendar: Calendar,
+    localTasks: Task[] = [],
+  ): Promise<Task[]> {
     const conn = this.connections.get(accountId);
     if (!conn) throw new Error('Account not connected');
 
-    // use calendar-query REPORT to fetch VTODOs
-    const reportBody = `<?xml version="1.0" encoding="utf-8"?>
-<c:calendar-query xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav">
-  <d:prop>
-    <d:getetag/>
-    <c:calendar-data/>
-  </d:prop>
-  <c:filter>
-    <c:comp-filter name="VCALENDAR">
-      <c:comp-filter name="VTODO"/>
-    </c:comp-filter>
-  </c:filter>
-</c:calendar-query>`;
-
-    const response = await report(calendar.url, conn.credentials, reportBody, '1');
-
-    if (response.status !== 207) {
-      log.error(`Failed to fetch tasks: HTTP ${response.status}`);
-      log.error(`Response body:`, response.body);
-      return [];
-    }
-
-    const results = parseMultiStatus(response.body);
-    const tasks: Task[] = [];
+    // keyed the way the backend writes the fetched hrefs, also for hrefs stored before
+    const synced = localTasks.filter((t) => t.href && t.synced);
+    const syncedHrefs = await normalizeHrefs(
+      calendar.url,
+      synced.map((t) => onCurrentHost(conn, t.href as string)),
+    );
+    const localByHref = new Map(synced.map((t, i) => [syncedHrefs[i], t]));
+    const checklistItems = new Map<string, ChecklistItem>();
+    const parse = (ics: string, href: string, etag?: string) => {
+      const task = vtodoToTask(ics, accountId, calendar.id, href, etag);
+      if (task && calendar.checklistAsTasks) {
+        const checklistItem = vtodoToChecklistItem(ics);
+        if (checklistItem) checklistItems.set(task.uid, checklistItem);
+      }
+      return task;
+    };
 
-    for (const result of results) {
-      const calendarData = result.props['calendar-data'];
-      const etag = result.props.getetag?.replace(/"/g, '');
+    // the backend streams the calendar-query REPORT and hands over the objects in chunks,
+    // so a large calendar is never held as one response body
+    const parsed: Task[] = [];
+    const reused: Task[] = [];
+    const uncached: string[] = [];
+    let received = 0;
+    let expected: number | null = null;
+    let allReceived: () => void = () => {};
+    const done = new Promise<void>((resolve) => {
+      allReceived = resolve;
+    });
 
-      if (calendarData) {
-        // build absolute URL
-        let href = result.href;
-        if (!href.startsWith('http')) {
-          href = new URL(result.href, conn.serverUrl).toString();
+    const onChunk = new Channel<CalendarObject[]>();
+    onChunk.onmessage = (objects) => {
+      for (const object of objects) {
+        if (object.tooLarge) {
+          // the local copy stays as it is, otherwise the task isn't shown
+          const local = localByHref.get(object.href);
+          if (local) reused.push(local);
+          log.warn(`Skipped ${object.href}, it's over the size limit`);
+          continue;
+        }
+        if (object.data === null) {
+          const local = localByHref.get(object.href);
+          if (local && local.etag === object.etag) {
+            reused.push(local.href === object.href ? local : { ...local, href: object.href });
+          } else {
+            uncached.push(object.href);
+          }
+          continue;
         }
+        // one broken object mustn't stop the chunk from being counted, or the fetch never ends
+        try {
+          const task = parse(object.data, object.href, object.etag || undefined);
+          if (task) {
+            parsed.push(task);
+          }
+        } catch (error) {
+          log.error(`Failed to parse ${object.href}:`, error);
+        }
+      }
+      received += objects.length;
+      if (expected !== null && received >= expected) allReceived();
+    };
+
+    expected = await invoke<number>('fetch_calendar_objects', {
+      account: backendAccount(conn),
+      calendarId: calendar.id,
+      calendarUrl: onCurrentHost(conn, calendar.url),
+      onChunk,
+    });
+    // channel messages can still be in flight when the command returns
+    if (received < expected) await done;
 
-        const task = vtodoToTask(calendarData, accountId, calendar.id, href, etag || undefined);
+    if (uncached.length > 0) {
+      const cached = await invoke<CachedIcs[]>('get_cached_objects', { hrefs: uncached });
+      for (const object of cached) {
+        const task = parse(object.ics, object.href, object.etag);
         if (task) {
-          tasks.push(task);
+          parsed.push(task);
         }
       }
     }
 
-    return tasks;
+    log.debug(`Parsed ${parsed.length} objects, reused ${reused.length} unchanged tasks`);
+    const tasks = [...reused, ...(await decryptTasks(accountId, parsed))];
+    return calendar.checklistAsTasks ? combineChecklistItems(tasks, checklistItems) : tasks;
+  }
+
+  /**
+   * events (VEVENTs) of all of the account's calendars that overlap the range, read-only,
+   * with recurring events expanded to their occurrences
+   */
+  async fetchEvents(accountId: string, start: Date, end: Date): Promise<CalendarEvent[]> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    return invoke<CalendarEvent[]>('fetch_events', {
+      account: backendAccount(conn),
+      calendarHome: conn.calendarHome,
+      start: start.toISOString(),
+      end: end.toISOString(),
+    });
+  }
+
+  /**
+   * time-block a task as an event in `calendarUrl` (from fetchEvents). with `keepLinked` the
+   * task stays and starts with the event, otherwise it is deleted. returns the event's url
+   */
+  async promoteToEvent(
+    accountId: string,
+    task: Task,
+    start: Date,
+    end: Date,
+    calendarUrl: string,
+    keepLinked = true,
+  ): Promise<string> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    return invoke<string>('promote_to_event', {
+      account: backendAccount(conn),
+      uid: task.uid,
+      start: start.toISOString(),
+      end: end.toISOString(),
+      calendar: calendarUrl,
+      keepLinked,
+    });
+  }
+
+  /**
+   * replace the settings document in the account's WebDAV space with this machine's settings;
+   * `url` defaults to the files space of servers that have one
+   */
+  async pushSettings(accountId: string, url: string | null = null): Promise<void> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    await invoke('push_roaming_settings', {
+      account: backendAccount(conn),
+      url,
+      settings: roamingSettings(),
+    });
+  }
+
+  /**
+   * apply the settings document from the account's WebDAV space
+   */
+  async pullSettings(accountId: string, url: string | null = null): Promise<void> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    const settings = await invoke<Record<string, unknown>>('pull_roaming_settings', {
+      account: backendAccount(conn),
+      url,
+    });
+    applyRoamingSettings(settings);
+  }
+
+  /**
+   * merge this machine's settings with the roamed ones, the newer change of each setting
+   * winning, and apply the result here and on the server
+   */
+  async mergeSettings(accountId: string, url: string | null = null): Promise<void> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    const settings = await invoke<Record<string, unknown>>('merge_roaming_settings', {
+      account: backendAccount(conn),
+      url,
+      settings: roamingSettings(),
+    });
+    applyRoamingSettings(settings);
+  }
+
+  /**
+   * the attachment at `url` from the attachment cache, downloaded with the account's
+   * credentials when it isn't cached yet
+   */
+  async fetchAttachment(accountId: string, url: string): Promise<CachedAttachment> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    return invoke<CachedAttachment>('fetch_attachment', { account: backendAccount(conn), url });
+  }
+
+  /**
+   * name and email suggestions from the account's CardDAV address books
+   */
+  async searchContacts(accountId: string, query: string, limit = 10): Promise<Contact[]> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    return invoke<Contact[]>('search_contacts', {
+      account: backendAccount(conn),
+      principalUrl: conn.principalUrl,
+      query,
+      limit,
+    });
   }
 
   async createTask(
@@ -357,9 +822,13 @@ class CalDAVService {
     if (!conn) throw new Error('Account not connected');
 
     try {
-      const icalData = taskToVTodo(task);
+      if (calendar.checklistAsTasks && !(await this.putChecklistItems(accountId, calendar, task))) {
+        return null;
+      }
+      const upload = calendar.checklistAsTasks ? { ...task, subtasks: [] } : task;
+      const icalData = await withinLimit(taskToVTodo(await prepareTask(accountId, upload)));
       const filename = `${task.uid}.ics`;
-      const url = `${calendar.url.replace(/\/$/, '')}/${filename}`;
+      const url = onCurrentHost(conn, `${calendar.url.replace(/\/$/, '')}/${filename}`);
 
       const response = await put(url, conn.credentials, icalData);
 
@@ -369,14 +838,24 @@ class CalDAVService {
       }
 
       log.error(`Failed to create task: HTTP ${response.status}`);
+      this.failures.set(task.uid, `The server rejected the new task (HTTP ${response.status})`);
       return null;
     } catch (error) {
       log.error('Error creating task:', error);
+      this.failures.set(task.uid, String(error));
       return null;
     }
   }
 
-  async updateTask(accountId: string, task: Task): Promise<{ etag: string } | null> {
+  /**
+   * upload a changed task; pass its calendar so checklist items are uploaded as tasks where
+   * the calendar asks for it. throws a SyncConflictError when the server's copy changed
+   */
+  async updateTask(
+    accountId: string,
+    task: Task,
+    calendar?: Calendar,
+  ): Promise<{ etag: string } | null> {
     const conn = this.connections.get(accountId);
     if (!conn) throw new Error('Account not connected');
 
@@ -386,22 +865,104 @@ class CalDAVService {
     }
 
     try {
-      const icalData = taskToVTodo(task);
-      const response = await put(task.href, conn.credentials, icalData, task.etag);
+      if (
+        calendar?.checklistAsTasks &&
+        !(await this.putChecklistItems(accountId, calendar, task))
+      ) {
+        return null;
+      }
+      const upload = calendar?.checklistAsTasks ? { ...task, subtasks: [] } : task;
+      const icalData = await withinLimit(taskToVTodo(await prepareTask(accountId, upload)));
+      const response = await put(
+        onCurrentHost(conn, task.href),
+        conn.credentials,
+        icalData,
+        task.etag,
+      );
 
       if (response.status === 200 || response.status === 201 || response.status === 204) {
         const etag = response.headers.etag?.replace(/"/g, '') || '';
         return { etag };
       }
+      if (response.status === 412) {
+        throw new SyncConflictError(task.uid);
+      }
 
       log.error(`Failed to update task: HTTP ${response.status}`);
+      this.failures.set(task.uid, `The server rejected the change (HTTP ${response.status})`);
       return null;
     } catch (error) {
+      if (error instanceof SyncConflictError) throw error;
       log.error('Error updating task:', error);
+      this.failures.set(task.uid, String(error));
       return null;
     }
   }
 
+  /**
+   * upload every checklist item of a task as a child VTODO. the items go up before the task
+   * itself, so a task whose etag hasn't changed has all of its items on the server
+   */
+  private async putChecklistItems(
+    accountId: string,
+    calendar: Calendar,
+    task: Task,
+  ): Promise<boolean> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+    if (task.subtasks.length === 0) return true;
+
+    const titles = await invoke<string[] | null>('encrypt_texts', {
+      accountId,
+      texts: task.subtasks.map((item) => item.title),
+    });
+    for (const [index, item] of task.subtasks.entries()) {
+      const icalData = checklistItemToVTodo(
+        task,
+        { ...item, title: titles?.[index] ?? item.title },
+        index,
+        titles !== null,
+      );
+      const response = await put(
+        onCurrentHost(conn, checklistItemUrl(calendar, task.uid, item.id)),
+        conn.credentials,
+        icalData,
+      );
+      if (response.status !== 200 && response.status !== 201 && response.status !== 204) {
+        log.error(`Failed to upload checklist item of ${task.uid}: HTTP ${response.status}`);
+        return false;
+      }
+    }
+    return true;
+  }
+
+  /**
+   * remove the child VTODOs of checklist items that are gone from their task
+   */
+  async deleteChecklistItems(
+    accountId: string,
+    calendar: Calendar,
+    parentUid: string,
+    itemIds: string[],
+  ): Promise<void> {
+    const conn = this.connections.get(accountId);
+    if (!conn) throw new Error('Account not connected');
+
+    for (const itemId of itemIds) {
+      try {
+        const response = await del(
+          onCurrentHost(conn, checklistItemUrl(calendar, parentUid, itemId)),
+          conn.credentials,
+        );
+        if (response.status !== 200 && response.status !== 204 && response.status !== 404) {
+          log.warn(`Failed to delete checklist item ${itemId}: HTTP ${response.status}`);
+        }
+      } catch (error) {
+        log.warn(`Error deleting checklist item ${itemId}:`, error);
+      }
+    }
+  }
+
   async deleteTask(accountId: string, task: Task): Promise<boolean> {
     const conn = this.connections.get(accountId);
     if (!conn) throw new Error('Account not connected');
@@ -412,10 +973,18 @@ class CalDAVService {
     }
 
     try {
-      const response = await del(task.href, conn.credentials, task.etag);
-      return response.status === 204 || response.status === 200;
+      const response = await del(onCurrentHost(conn, task.href), conn.credentials, task.etag);
+      // already gone counts as deleted
+      if (response.status === 204 || response.status === 200 || response.status === 404) {
+        return true;
+      }
+      if (task.uid) {
+        this.failures.set(task.uid, `The server refused the deletion (HTTP ${response.status})`);
+      }
+      return false;
     } catch (error) {
       log.error('Error deleting task:', error);
+      if (task.uid) this.failures.set(task.uid, String(error));
       return false;
     }
   }
@@ -429,7 +998,7 @@ class CalDAVService {
     updated: Task[];
     deleted: string[];
   }> {
-    const remoteTasks = await this.fetchTasks(accountId, calendar);
+    const remoteTasks = await this.fetchTasks(accountId, calendar, localTasks);
 
     const created: Task[] = [];
     const updated: Task[] = [];
@@ -732,6 +1301,13 @@ class CalDAVService {
     };
   }
 
+  /**
+   * the auth method the account's connection ended up with, to remember for a new account
+   */
+  getAuthMethod(accountId: string): AuthMethod | undefined {
+    return this.connections.get(accountId)?.credentials.authMethod;
+  }
+
   /**
    * disconnect an account
    */
@@ -750,7 +1326,9 @@ class CalDAVService {
    * reconnect an account using stored credentials
    */
   async reconnect(account: Account): Promise<void> {
-    if (!account.serverUrl || !account.username || !account.password) {
+    // OAuth accounts don't need a password
+    const bearerToken = (await getOAuthAccessToken(account.id)) ?? undefined;
+    if (!account.serverUrl || !account.username || !(account.password || bearerToken)) {
       throw new Error('Missing account credentials');
     }
 
@@ -760,8 +1338,27 @@ class CalDAVService {
       account.username,
       account.password,
       account.serverType || 'rustical',
+      account.tls,
+      bearerToken,
+      account.authMethod,
     );
   }
+
+  /**
+   * swap in the current access token of every connected OAuth account, after
+   * refreshOAuthTokens renewed the ones about to expire
+   */
+  async refreshBearerTokens(): Promise<void> {
+    for (const [accountId, conn] of this.connections) {
+      if (!conn.credentials.bearerToken) continue;
+      try {
+        const token = await getOAuthAccessToken(accountId);
+        if (token) conn.credentials.bearerToken = token;
+      } catch (error) {
+        log.warn(`Failed to refresh the access token of account ${accountId}:`, error);
+      }
+    }
+  }
 }
 
 export const caldavService = new CalDAVService();
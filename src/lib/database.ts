@@ -3,11 +3,25 @@
  * Replaces localStorage-based persistence
  */
 
+import { invoke } from '@tauri-apps/api/core';
+import { emit, listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import Database from '@tauri-apps/plugin-sql';
 import { v4 as uuidv4 } from 'uuid';
-import { useSettingsStore } from '@/store/settingsStore';
-import type { Account, Calendar, Priority, SortConfig, Tag, Task } from '@/types';
+import { type KeyboardShortcut, useSettingsStore } from '@/store/settingsStore';
+import type {
+  Account,
+  Calendar,
+  Context,
+  Priority,
+  SortConfig,
+  Tag,
+  Task,
+  TimeEntry,
+  TlsSettings,
+} from '@/types';
 import { toAppleEpoch } from '@/utils/ical';
+import type { FlattenedTask } from '@/utils/tree';
 import { createLogger } from './logger';
 
 const log = createLogger('Database', '#8b5cf6');
@@ -15,219 +29,2019 @@ const log = createLogger('Database', '#8b5cf6');
 // Database connection instance
 let db: Database | null = null;
 
-// Database name
-const DB_NAME = 'sqlite:caldav-tasks.db';
+// Pending deletion interface
+export interface PendingDeletion {
+  uid: string;
+  href: string;
+  accountId: string;
+  calendarId: string;
+}
+
+// UI State interface
+export interface UIState {
+  activeAccountId: string | null;
+  activeCalendarId: string | null;
+  activeTagId: string | null;
+  selectedTaskId: string | null;
+  searchQuery: string;
+  sortConfig: SortConfig;
+  showCompletedTasks: boolean;
+  isEditorOpen: boolean;
+}
+
+// Complete data store interface
+export interface DataStore {
+  tasks: Task[];
+  tags: Tag[];
+  accounts: Account[];
+  pendingDeletions: PendingDeletion[];
+  ui: UIState;
+}
+
+// Default UI state
+const defaultUIState: UIState = {
+  activeAccountId: null,
+  activeCalendarId: null,
+  activeTagId: null,
+  selectedTaskId: null,
+  searchQuery: '',
+  sortConfig: { mode: 'manual', direction: 'asc' },
+  showCompletedTasks: true,
+  isEditorOpen: false,
+};
+
+// Event listeners for data changes
+type DataChangeListener = () => void;
+const listeners: Set<DataChangeListener> = new Set();
+
+export function subscribeToDataChanges(listener: DataChangeListener): () => void {
+  listeners.add(listener);
+  return () => listeners.delete(listener);
+}
+
+function notifyListeners(): void {
+  listeners.forEach((listener) => {
+    listener();
+  });
+}
+
+// read-only mode as last reported by the backend; the sql plugin doesn't ask it, so writes
+// from here are checked against it before they reach the database
+let readOnlyMode = false;
+// only what is selected and shown can change in read-only mode
+const UI_STATE_UPDATE = /^\s*UPDATE\s+ui_state\s+SET\b/i;
+let readOnlyListener: Promise<() => void> | null = null;
+
+export function isReadOnly(): boolean {
+  return readOnlyMode;
+}
+
+// syncs still pull in read-only mode, so a display keeps up with the server; what they
+// write to the cache reaches the database a moment after they finish
+const PULL_SETTLE_DELAY = 1000;
+let pulling = 0;
+
+// let the writes of a sync through read-only mode until the returned function is called
+export function startPull(): () => void {
+  pulling++;
+  return () => {
+    setTimeout(() => {
+      pulling--;
+    }, PULL_SETTLE_DELAY);
+  };
+}
+
+// other windows of the app (calendars opened on their own) reload after this one wrote;
+// a sync writes many rows at once, so they're told once it settles
+const WRITE_ANNOUNCE_DELAY = 300;
+let writeAnnounceTimer: ReturnType<typeof setTimeout> | null = null;
+
+function announceWrite(): void {
+  if (writeAnnounceTimer) clearTimeout(writeAnnounceTimer);
+  writeAnnounceTimer = setTimeout(() => {
+    writeAnnounceTimer = null;
+    emit('window-data-written', getCurrentWindow().label).catch(() => {});
+  }, WRITE_ANNOUNCE_DELAY);
+}
+
+// Initialize database connection
+export async function initDatabase(): Promise<Database> {
+  if (db) return db;
+
+  try {
+    // migrations run in the backend on startup, wait for them before loading
+    await invoke('wait_for_database');
+    // nothing is read while the app is locked; bootstrap shows the lock screen first
+    if ((await getAppLockStatus()).locked) {
+      throw new Error('The app is locked');
+    }
+    const schema = await getSchemaCompatibility();
+    // a temporary database in demo mode
+    const url = await invoke<string>('get_database_url');
+    if (schema.compatible) {
+      readOnlyMode = (await getReadOnly()).enabled;
+      readOnlyListener ??= listen<boolean>('read-only-changed', (event) => {
+        readOnlyMode = event.payload;
+      });
+      const database = await Database.load(url);
+      // the backend reloads everything when another program changes the database, it has to
+      // know which writes are this app's own
+      const execute = database.execute.bind(database);
+      database.execute = async (query, bindValues) => {
+        if (readOnlyMode && pulling === 0 && !UI_STATE_UPDATE.test(query)) {
+          throw new Error('The app is in read-only mode');
+        }
+        const result = await execute(query, bindValues);
+        invoke('note_local_write').catch(() => {});
+        announceWrite();
+        return result;
+      };
+      db = database;
+    } else {
+      // a newer release's database: a connection the sql plugin doesn't migrate, and writes
+      // refused up front instead of failing inside sqlite
+      const readOnly = await Database.load(`${url}?mode=ro`);
+      readOnly.execute = () =>
+        Promise.reject(new Error('The database was written by a newer version of the app'));
+      db = readOnly;
+      const { databaseVersion, supportedVersion } = schema;
+      log.warn(`Database schema ${databaseVersion} is newer than ${supportedVersion}, read-only`);
+    }
+    log.info('Connected to SQLite database');
+
+    return db;
+  } catch (error) {
+    log.error('Failed to connect:', error);
+    throw error;
+  }
+}
+
+// drop the connection, e.g. when the app locks
+export async function closeDatabase(): Promise<void> {
+  if (!db) return;
+  const database = db;
+  db = null;
+  await database.close();
+}
+
+// Get database instance (ensures initialization)
+async function getDb(): Promise<Database> {
+  if (!db) {
+    await initDatabase();
+  }
+  return db!;
+}
+
+// Helper to convert database row to Task
+function rowToTask(row: any): Task {
+  return {
+    id: row.id,
+    uid: row.uid,
+    etag: row.etag || undefined,
+    href: row.href || undefined,
+    changeId: row.change_id ?? undefined,
+    title: row.title,
+    description: row.description,
+    completed: row.completed === 1,
+    completedAt: row.completed_at ? new Date(row.completed_at) : undefined,
+    tags: row.tags ? JSON.parse(row.tags) : undefined,
+    categoryId: row.category_id || undefined,
+    contexts: row.contexts ? JSON.parse(row.contexts) : undefined,
+    priority: row.priority as Priority,
+    startDate: row.start_date ? new Date(row.start_date) : undefined,
+    startDateAllDay: row.start_date_all_day === 1,
+    dueDate: row.due_date ? new Date(row.due_date) : undefined,
+    dueDateAllDay: row.due_date_all_day === 1,
+    createdAt: new Date(row.created_at),
+    modifiedAt: new Date(row.modified_at),
+    reminders: row.reminders
+      ? JSON.parse(row.reminders).map((r: any) => ({
+          ...r,
+          trigger: new Date(r.trigger),
+        }))
+      : undefined,
+    subtasks: row.subtasks ? JSON.parse(row.subtasks) : [],
+    parentUid: row.parent_uid || undefined,
+    blockedBy: row.blocked_by ? JSON.parse(row.blocked_by) : undefined,
+    isCollapsed: row.is_collapsed === 1,
+    sortOrder: row.sort_order,
+    url: row.url || undefined,
+    estimatedDuration: row.estimated_duration ?? undefined,
+    kanbanColumn: row.kanban_column ?? undefined,
+    accountId: row.account_id || '',
+    calendarId: row.calendar_id || '',
+    synced: row.synced === 1,
+    localOnly: row.local_only === 1,
+  };
+}
+
+// passwords read from the os credential store, so it isn't queried on every account load
+const passwordCache = new Map<string, string>();
+
+// the password column is only used when the credential store is unavailable
+async function resolvePassword(row: any): Promise<string> {
+  if (row.password) return row.password;
+
+  const cached = passwordCache.get(row.id);
+  if (cached !== undefined) return cached;
+
+  try {
+    const password =
+      (await invoke<string | null>('get_account_password', { accountId: row.id })) ?? '';
+    passwordCache.set(row.id, password);
+    return password;
+  } catch (error) {
+    log.warn(`Failed to read password for account ${row.id} from the credential store:`, error);
+    return '';
+  }
+}
+
+// save a password in the credential store; returns what should go into the password column
+async function storePassword(accountId: string, password: string): Promise<string> {
+  if (!password) return '';
+
+  try {
+    await invoke('set_account_password', { accountId, password });
+    passwordCache.set(accountId, password);
+    return '';
+  } catch (error) {
+    log.warn('Credential store unavailable, keeping the password in the database:', error);
+    return password;
+  }
+}
+
+// Helper to convert database row to Account (with calendars)
+function rowToAccount(row: any, calendars: Calendar[], password: string): Account {
+  return {
+    id: row.id,
+    name: row.name,
+    serverUrl: row.server_url,
+    username: row.username,
+    password,
+    serverType: row.server_type || undefined,
+    backend: row.backend || 'caldav',
+    tls: {
+      minVersion: row.tls_min_version || '1.2',
+      pinnedCertificate: row.tls_pinned_certificate || undefined,
+      allowInvalidHostnames: row.tls_allow_invalid_hostnames === 1,
+    },
+    calendars: calendars.filter((c) => c.accountId === row.id),
+    lastSync: row.last_sync ? new Date(row.last_sync) : undefined,
+    isActive: row.is_active === 1,
+    color: row.color || undefined,
+    sortOrder: row.sort_order,
+    collapsed: row.collapsed === 1,
+    authMethod: row.auth_method || undefined,
+  };
+}
+
+// Helper to convert database row to Calendar
+function rowToCalendar(row: any): Calendar {
+  return {
+    id: row.id,
+    displayName: row.display_name,
+    url: row.url,
+    ctag: row.ctag || undefined,
+    syncToken: row.sync_token || undefined,
+    color: row.color || undefined,
+    icon: row.icon || undefined,
+    accountId: row.account_id,
+    supportedComponents: row.supported_components
+      ? JSON.parse(row.supported_components)
+      : undefined,
+    checklistAsTasks: row.checklist_as_tasks === 1,
+    sortOrder: row.sort_order,
+    archived: row.archived === 1,
+  };
+}
+
+// Helper to convert database row to Tag
+function rowToTag(row: any): Tag {
+  return {
+    id: row.id,
+    name: row.name,
+    color: row.color,
+    icon: row.icon || undefined,
+  };
+}
+
+// task operations
+
+export async function getAllTasks(): Promise<Task[]> {
+  const database = await getDb();
+  const rows = await database.select<any[]>(
+    `SELECT *, (SELECT json_group_array(depends_on_uid) FROM task_dependencies
+       WHERE task_dependencies.task_uid = tasks.uid) AS blocked_by
+     FROM tasks
+     WHERE archived_at IS NULL`,
+  );
+  return rows.map(rowToTask);
+}
+
+export async function getTaskById(id: string): Promise<Task | undefined> {
+  const database = await getDb();
+  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE id = $1', [id]);
+  return rows.length > 0 ? rowToTask(rows[0]) : undefined;
+}
+
+export async function getTaskByUid(uid: string): Promise<Task | undefined> {
+  const database = await getDb();
+  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE uid = $1', [uid]);
+  return rows.length > 0 ? rowToTask(rows[0]) : undefined;
+}
+
+export async function getTasksByCalendar(calendarId: string): Promise<Task[]> {
+  const database = await getDb();
+  const rows = await database.select<any[]>(
+    'SELECT * FROM tasks WHERE calendar_id = $1 AND archived_at IS NULL',
+    [calendarId],
+  );
+  return rows.map(rowToTask);
+}
+
+export async function getTasksByTag(tagId: string): Promise<Task[]> {
+  const database = await getDb();
+  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE tags LIKE $1', [
+    `%"${tagId}"%`,
+  ]);
+  return rows.map(rowToTask);
+}
+
+export async function getChildTasks(parentUid: string): Promise<Task[]> {
+  const database = await getDb();
+  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE parent_uid = $1', [
+    parentUid,
+  ]);
+  return rows.map(rowToTask);
+}
+
+export async function countChildren(parentUid: string): Promise<number> {
+  const database = await getDb();
+  const rows = await database.select<any[]>(
+    'SELECT COUNT(*) as count FROM tasks WHERE parent_uid = $1',
+    [parentUid],
+  );
+  return rows[0]?.count || 0;
+}
+
+// filter for backend task queries; unset fields don't filter
+export interface TaskPage {
+  tasks: Task[];
+  total: number;
+  offset: number;
+}
+
+// tasks from backend commands keep their dates as iso strings
+export function backendToTask(task: any): Task {
+  return {
+    ...task,
+    etag: task.etag ?? undefined,
+    href: task.href ?? undefined,
+    changeId: task.changeId ?? undefined,
+    completedAt: task.completedAt ? new Date(task.completedAt) : undefined,
+    categoryId: task.categoryId ?? undefined,
+    startDate: task.startDate ? new Date(task.startDate) : undefined,
+    dueDate: task.dueDate ? new Date(task.dueDate) : undefined,
+    createdAt: new Date(task.createdAt),
+    modifiedAt: new Date(task.modifiedAt),
+    reminders: task.reminders
+      ? task.reminders.map((r: any) => ({ ...r, trigger: new Date(r.trigger) }))
+      : undefined,
+    parentUid: task.parentUid ?? undefined,
+    blockedBy: task.blockedBy ?? undefined,
+    url: task.url ?? undefined,
+    estimatedDuration: task.estimatedDuration ?? undefined,
+    kanbanColumn: task.kanbanColumn ?? undefined,
+  };
+}
+
+// check a search query such as `tag:work due<2025-01-01 -is:done`; rejects with a message
+// explaining what's wrong
+export async function validateSearchQuery(query: string): Promise<void> {
+  await invoke('validate_search_query', { query });
+}
+
+// a task found by globalSearch; matched says where the query matched first
+export interface SearchHit {
+  id: string;
+  uid: string;
+  title: string;
+  completed: boolean;
+  dueDate: string | null;
+  matched: 'title' | 'tag' | 'description';
+  score: number;
+}
+
+// the hits in one list, best list first
+export interface SearchGroup {
+  calendarId: string | null;
+  list: string;
+  account: string;
+  hits: SearchHit[];
+}
+
+// ranked search over every account and list, for jumping to a task
+export async function globalSearch(query: string): Promise<SearchGroup[]> {
+  return invoke<SearchGroup[]>('global_search', { query });
+}
+
+// a task or list found by fuzzyFind; positions are code point indices of the matched
+// characters of the title, for Array.from(title)
+export interface FuzzyMatch {
+  kind: 'task' | 'list';
+  id: string;
+  title: string;
+  calendarId: string | null;
+  // the list of a task, the account of a list
+  list: string;
+  completed: boolean;
+  score: number;
+  positions: number[];
+}
+
+// typo-tolerant matching of task titles and list names for a quick-open palette
+export async function fuzzyFind(query: string, limit?: number): Promise<FuzzyMatch[]> {
+  return invoke<FuzzyMatch[]>('fuzzy_find', { query, limit });
+}
+
+// a task as typed into quick add, before it's created
+export interface QuickAddDraft {
+  title: string;
+  dueDate: string | null;
+  dueDateAllDay: boolean;
+  /** tag names without the # */
+  tags: string[];
+  priority: Priority | null;
+  /** list name as typed after the @ */
+  list: string | null;
+  /** the calendar the list name matched, if any */
+  calendarId: string | null;
+  accountId: string | null;
+  /** RRULE value for phrases like "every 2 weeks" */
+  rrule: string | null;
+  /** the next few occurrences of the rule, to check it was understood */
+  occurrences: string[];
+  /** the first link in the text, taken out of the title */
+  url: string | null;
+}
+
+// turn text like "call mom tomorrow 5pm #family !high @Home" into a task draft,
+// in the system locale
+export async function parseQuickAdd(text: string): Promise<QuickAddDraft> {
+  return invoke<QuickAddDraft>('parse_quick_add', { text });
+}
+
+// the next occurrences of a recurring task from today on, as ISO timestamps; rrule previews a
+// rule in place of the task's own. fails for a task that doesn't repeat
+export async function previewOccurrences(
+  uid: string,
+  count: number,
+  rrule?: string,
+): Promise<string[]> {
+  return invoke<string[]>('preview_occurrences', { uid, count, rrule });
+}
+
+export type TaskEvent = 'created' | 'completed' | 'overdue';
+
+export interface Webhook {
+  id: string;
+  url: string;
+  events: TaskEvent[];
+  enabled: boolean;
+  createdAt: string;
+}
+
+// one entry of the webhook delivery log
+export interface WebhookDelivery {
+  id: string;
+  webhookId: string;
+  event: string;
+  taskUid: string | null;
+  status: 'pending' | 'delivered' | 'failed';
+  attempts: number;
+  responseStatus: number | null;
+  error: string | null;
+  createdAt: string;
+  nextAttemptAt: string | null;
+  deliveredAt: string | null;
+}
+
+export async function getWebhooks(): Promise<Webhook[]> {
+  return invoke<Webhook[]>('get_webhooks');
+}
+
+// adds a webhook when id is null
+export async function saveWebhook(
+  id: string | null,
+  url: string,
+  events: TaskEvent[],
+  enabled: boolean,
+): Promise<Webhook> {
+  return invoke<Webhook>('save_webhook', { id, url, events, enabled });
+}
+
+export async function deleteWebhook(id: string): Promise<void> {
+  await invoke('delete_webhook', { id });
+}
+
+// the secret payloads are signed with (X-Caldav-Tasks-Signature: sha256=<hmac>)
+export async function getWebhookSecret(id: string): Promise<string> {
+  return invoke<string>('get_webhook_secret', { id });
+}
+
+export async function getWebhookDeliveries(
+  webhookId: string | null,
+  limit = 50,
+): Promise<WebhookDelivery[]> {
+  return invoke<WebhookDelivery[]>('get_webhook_deliveries', { webhookId, limit });
+}
+
+export async function testWebhook(id: string): Promise<WebhookDelivery> {
+  return invoke<WebhookDelivery>('test_webhook', { id });
+}
+
+// when a script runs by itself; daily times are local HH:MM
+export type ScriptTrigger =
+  | { type: 'manual' }
+  | { type: 'daily'; at: string }
+  | { type: 'event'; event: TaskEvent };
+
+// a Rhai automation script; it sees tasks, lists and tags, and changes tasks through
+// complete, reopen, set_due, clear_due, add_tag and move_to_list
+export interface Script {
+  id: string;
+  name: string;
+  source: string;
+  trigger: ScriptTrigger;
+  enabled: boolean;
+  lastRunAt: string | null;
+  lastError: string | null;
+  createdAt: string;
+}
+
+export interface ScriptRun {
+  changed: number;
+  output: string[];
+}
+
+export async function getScripts(): Promise<Script[]> {
+  return invoke<Script[]>('get_scripts');
+}
+
+// adds a script when id is null; fails when the source doesn't compile
+export async function saveScript(
+  id: string | null,
+  name: string,
+  source: string,
+  trigger: ScriptTrigger,
+  enabled: boolean,
+): Promise<Script> {
+  return invoke<Script>('save_script', { id, name, source, trigger, enabled });
+}
+
+export async function deleteScript(id: string): Promise<void> {
+  await invoke('delete_script', { id });
+}
+
+export async function runScript(id: string): Promise<ScriptRun> {
+  return invoke<ScriptRun>('run_script', { id });
+}
+
+export async function checkScript(source: string): Promise<void> {
+  await invoke('check_script', { source });
+}
+
+export type RuleEvent = 'created' | 'updated' | 'synced';
+
+// every condition of a rule has to hold; text matches ignore case
+export type RuleCondition =
+  | { type: 'inList'; calendarId: string }
+  | { type: 'titleContains'; text: string }
+  | { type: 'textContains'; text: string }
+  | { type: 'hasTag'; tagId: string }
+  | { type: 'lacksTag'; tagId: string }
+  | { type: 'priorityIs'; priority: Priority }
+  | { type: 'noDueDate' };
+
+// setDueInDays only applies to tasks without a due date
+export type RuleAction =
+  | { type: 'addTag'; tagId: string }
+  | { type: 'removeTag'; tagId: string }
+  | { type: 'setPriority'; priority: Priority }
+  | { type: 'addContext'; name: string }
+  | { type: 'setDueInDays'; days: number };
+
+// an id of '' saves a new rule
+export interface Rule {
+  id: string;
+  name: string;
+  events: RuleEvent[];
+  conditions: RuleCondition[];
+  actions: RuleAction[];
+  enabled: boolean;
+  sortOrder: number;
+  createdAt: string;
+}
+
+export interface RuleTestMatch {
+  taskId: string;
+  title: string;
+  changes: RuleAction[];
+}
+
+export async function getRules(): Promise<Rule[]> {
+  return invoke<Rule[]>('get_rules');
+}
+
+export async function saveRule(rule: Rule): Promise<Rule> {
+  return invoke<Rule>('save_rule', { rule });
+}
+
+export async function deleteRule(id: string): Promise<void> {
+  await invoke('delete_rule', { id });
+}
+
+// changed tasks arrive through the tasks-changed event
+export async function applyRules(ids: string[], event: RuleEvent): Promise<number> {
+  return invoke<number>('apply_rules', { ids, event });
+}
+
+// dry run of a possibly unsaved rule against the current tasks
+export async function testRule(rule: Rule): Promise<RuleTestMatch[]> {
+  return invoke<RuleTestMatch[]>('test_rule', { rule });
+}
+
+// the localhost REST API (GET/POST /tasks, GET/PATCH/DELETE /tasks/:id, GET /lists,
+// POST /sync), authenticated with `Authorization: Bearer <token>`
+export interface ApiServerSettings {
+  enabled: boolean;
+  port: number;
+  running: boolean;
+}
+
+export async function getApiServerSettings(): Promise<ApiServerSettings> {
+  return invoke<ApiServerSettings>('get_api_server_settings');
+}
+
+export async function setApiServerSettings(
+  enabled: boolean,
+  port: number,
+): Promise<ApiServerSettings> {
+  return invoke<ApiServerSettings>('set_api_server_settings', { enabled, port });
+}
+
+export async function getApiServerToken(): Promise<string> {
+  return invoke<string>('get_api_server_token');
+}
+
+export async function regenerateApiServerToken(): Promise<string> {
+  return invoke<string>('regenerate_api_server_token');
+}
+
+// read-only ICS feed of the due dates of chosen lists at GET /feed.ics of the API server,
+// with its own token in the url for calendar apps to subscribe to
+export interface IcsFeed {
+  calendarIds: string[];
+  url: string | null;
+  serverRunning: boolean;
+}
+
+export async function getIcsFeed(): Promise<IcsFeed> {
+  return invoke<IcsFeed>('get_ics_feed');
+}
+
+export async function setIcsFeedCalendars(calendarIds: string[]): Promise<IcsFeed> {
+  return invoke<IcsFeed>('set_ics_feed_calendars', { calendarIds });
+}
+
+export async function regenerateIcsFeedToken(): Promise<IcsFeed> {
+  return invoke<IcsFeed>('regenerate_ics_feed_token');
+}
+
+// MCP for AI assistants at http://127.0.0.1:<port>/mcp of the API server, same token
+export async function getMcpEnabled(): Promise<boolean> {
+  return invoke<boolean>('get_mcp_enabled');
+}
+
+export async function setMcpEnabled(enabled: boolean): Promise<void> {
+  await invoke('set_mcp_enabled', { enabled });
+}
+
+export interface EmailToTaskSettings {
+  enabled: boolean;
+  host: string;
+  port: number;
+  username: string;
+  folder: string;
+  processedFolder: string | null;
+  calendarId: string | null;
+  intervalMinutes: number;
+}
+
+export interface EmailToTaskStatus {
+  lastPollAt: string | null;
+  lastError: string | null;
+  imported: number;
+}
+
+export async function getEmailToTaskSettings(): Promise<EmailToTaskSettings> {
+  return invoke<EmailToTaskSettings>('get_email_to_task_settings');
+}
+
+// the saved password is kept when `password` is null
+export async function setEmailToTaskSettings(
+  settings: EmailToTaskSettings,
+  password: string | null = null,
+): Promise<void> {
+  await invoke('set_email_to_task_settings', { settings, password });
+}
+
+export async function getEmailToTaskStatus(): Promise<EmailToTaskStatus> {
+  return invoke<EmailToTaskStatus>('get_email_to_task_status');
+}
+
+export async function pollEmailNow(): Promise<number> {
+  return invoke<number>('poll_email_now');
+}
+
+// daily or weekly summary mail of completed and open tasks, sent through SMTP
+export interface EmailDigestSettings {
+  enabled: boolean;
+  host: string;
+  port: number;
+  // tls from the start (usually port 465) or STARTTLS (usually port 587)
+  security: 'tls' | 'starttls';
+  username: string;
+  from: string;
+  // comma separated
+  to: string;
+  frequency: 'daily' | 'weekly';
+  // local hour of the day
+  hour: number;
+}
+
+export interface EmailDigestStatus {
+  lastSentAt: string | null;
+  lastError: string | null;
+}
+
+export async function getEmailDigestSettings(): Promise<EmailDigestSettings> {
+  return invoke<EmailDigestSettings>('get_email_digest_settings');
+}
+
+// the saved password is kept when `password` is null
+export async function setEmailDigestSettings(
+  settings: EmailDigestSettings,
+  password: string | null = null,
+): Promise<void> {
+  await invoke('set_email_digest_settings', { settings, password });
+}
+
+export async function getEmailDigestStatus(): Promise<EmailDigestStatus> {
+  return invoke<EmailDigestStatus>('get_email_digest_status');
+}
+
+export async function sendEmailDigestNow(): Promise<void> {
+  await invoke('send_email_digest_now');
+}
+
+// a request sent when a task, or any task of a list, is completed; `{{title}}`, `{{description}}`,
+// `{{uid}}`, `{{list}}`, `{{priority}}`, `{{dueDate}}`, `{{completedAt}}` and `{{url}}` in the
+// url and body are filled in from the task
+export interface HttpAction {
+  id: string;
+  name: string;
+  target: 'task' | 'list';
+  /** the task's uid or the list's id */
+  targetId: string;
+  method: 'GET' | 'POST' | 'PUT' | 'PATCH' | 'DELETE';
+  url: string;
+  contentType: string;
+  body: string;
+  enabled: boolean;
+  createdAt: string;
+}
+
+export interface HttpActionRun {
+  id: string;
+  actionId: string;
+  taskUid: string | null;
+  url: string;
+  body: string;
+  status: 'pending' | 'delivered' | 'failed';
+  attempts: number;
+  responseStatus: number | null;
+  error: string | null;
+  createdAt: string;
+  nextAttemptAt: string | null;
+  deliveredAt: string | null;
+}
+
+export async function getHttpActions(): Promise<HttpAction[]> {
+  return invoke<HttpAction[]>('get_http_actions');
+}
+
+// a new action has an empty id
+export async function saveHttpAction(action: HttpAction): Promise<HttpAction> {
+  return invoke<HttpAction>('save_http_action', { action });
+}
+
+export async function deleteHttpAction(id: string): Promise<void> {
+  await invoke('delete_http_action', { id });
+}
+
+export async function getHttpActionRuns(
+  actionId: string | null = null,
+  limit = 50,
+): Promise<HttpActionRun[]> {
+  return invoke<HttpActionRun[]>('get_http_action_runs', { actionId, limit });
+}
+
+export async function testHttpAction(id: string, taskId: string): Promise<HttpActionRun> {
+  return invoke<HttpActionRun>('test_http_action', { id, taskId });
+}
+
+export type AccountHealthState = 'healthy' | 'authFailed' | 'networkError' | 'serverError';
+
+export interface AccountHealth {
+  accountId: string;
+  state: AccountHealthState;
+  consecutiveFailures: number;
+  lastError: string | null;
+  lastFailureAt: string | null;
+  lastSuccessAt: string | null;
+  /** the password was rejected, or syncing kept failing */
+  attentionNeeded: boolean;
+  /** the server kept failing to answer, so scheduled syncs back off */
+  unreachable: boolean;
+  nextRetryAt: string | null;
+}
+
+export async function getAccountHealth(): Promise<AccountHealth[]> {
+  return invoke<AccountHealth[]>('get_account_health');
+}
+
+export interface UnreachableServer {
+  accountId: string;
+  accountName: string;
+  serverUrl: string;
+  reason: 'networkError' | 'serverError';
+  lastError: string | null;
+  consecutiveFailures: number;
+  lastSuccessAt: string | null;
+  /** when a scheduled sync tries it again */
+  nextRetryAt: string | null;
+}
+
+export async function getUnreachableServers(): Promise<UnreachableServer[]> {
+  return invoke<UnreachableServer[]>('get_unreachable_servers');
+}
+
+// the outcome of syncing an account, null when everything worked
+export async function reportSyncResult(
+  accountId: string,
+  error: string | null,
+): Promise<AccountHealth> {
+  return invoke<AccountHealth>('report_sync_result', { accountId, error });
+}
+
+// check the credentials right away; a given password is saved when the server accepts it
+export async function revalidateCredentials(
+  accountId: string,
+  password: string | null = null,
+): Promise<AccountHealth> {
+  return invoke<AccountHealth>('revalidate_credentials', { accountId, password });
+}
+
+export interface CredentialMigrationReport {
+  /** accounts whose password moved from the database to the credential store */
+  migrated: { id: string; name: string }[];
+  /** accounts whose password stayed in the database */
+  failed: { id: string; name: string; error: string }[];
+}
+
+// what the startup audit moved out of the database; null until it has run
+export async function getCredentialMigrationReport(): Promise<CredentialMigrationReport | null> {
+  return invoke<CredentialMigrationReport | null>('get_credential_migration_report');
+}
+
+export interface OAuthTokens {
+  accessToken: string;
+  refreshToken: string | null;
+  /** null for tokens that don't expire */
+  expiresAt: string | null;
+  tokenUrl: string;
+  clientId: string;
+  clientSecret: string | null;
+  scope: string | null;
+}
+
+export interface OAuthStatus {
+  accountId: string;
+  expiresAt: string | null;
+  canRefresh: boolean;
+  /** refreshing failed for good; `oauth-reauthorization-required` was emitted once */
+  reauthorizationRequired: boolean;
+}
+
+// the tokens of a (new) authorization, kept in the system keyring
+export async function setOAuthTokens(accountId: string, tokens: OAuthTokens): Promise<void> {
+  return invoke('set_oauth_tokens', { accountId, tokens });
+}
+
+export async function deleteOAuthTokens(accountId: string): Promise<void> {
+  return invoke('delete_oauth_tokens', { accountId });
+}
+
+// a usable access token, refreshed when it is about to expire; null without OAuth
+export async function getOAuthAccessToken(accountId: string): Promise<string | null> {
+  return invoke<string | null>('get_oauth_access_token', { accountId });
+}
+
+// refresh every account's tokens that are about to expire, before a sync
+export async function refreshOAuthTokens(): Promise<OAuthStatus[]> {
+  return invoke<OAuthStatus[]>('refresh_oauth_tokens');
+}
+
+export interface ImportedAccountConfig {
+  accounts: number;
+  calendars: number;
+  /** accounts already set up here, which only got their missing calendars */
+  existingAccounts: number;
+  syncSettings: SyncSettings | null;
+  /** the new accounts, which still need a password before they sync */
+  needsPassword: string[];
+}
+
+interface SyncSettings {
+  autoSync: boolean;
+  syncInterval: number;
+  syncOnStartup: boolean;
+  syncTrackedTime: boolean;
+}
+
+// accounts, calendars and sync settings to a file for setting up another machine; never
+// passwords or tokens
+export async function exportAccountConfig(path: string): Promise<void> {
+  const { autoSync, syncInterval, syncOnStartup, syncTrackedTime } = useSettingsStore.getState();
+  const syncSettings: SyncSettings = { autoSync, syncInterval, syncOnStartup, syncTrackedTime };
+  return invoke('export_account_config', { path, syncSettings });
+}
+
+// set up the accounts of an exported file and apply its sync settings
+export async function importAccountConfig(path: string): Promise<ImportedAccountConfig> {
+  const imported = await invoke<ImportedAccountConfig>('import_account_config', { path });
+  const sync = imported.syncSettings;
+  if (sync) {
+    const { setAutoSync, setSyncInterval, setSyncOnStartup, setSyncTrackedTime } =
+      useSettingsStore.getState();
+    setAutoSync(sync.autoSync ?? true);
+    setSyncInterval(sync.syncInterval ?? 5);
+    setSyncOnStartup(sync.syncOnStartup ?? true);
+    setSyncTrackedTime(sync.syncTrackedTime ?? false);
+  }
+  notifyListeners();
+  return imported;
+}
+
+// a keyboard shortcut whose keys are taken by an earlier one, the app menu or the system
+export interface ShortcutConflict {
+  id: string;
+  conflictsWith: string; // shortcut id, or the name of the menu item
+  reserved: boolean; // taken by the app menu or the system
+}
+
+// the custom keyboard shortcuts kept in the backend; null while the defaults are in use
+export async function getShortcuts(): Promise<KeyboardShortcut[] | null> {
+  return invoke<KeyboardShortcut[] | null>('get_shortcuts');
+}
+
+// shortcuts that share their keys with an earlier one, the app menu or the system
+export async function checkShortcuts(
+  shortcuts: KeyboardShortcut[],
+): Promise<ShortcutConflict[]> {
+  return invoke<ShortcutConflict[]>('check_shortcuts', { shortcuts });
+}
+
+// save the custom shortcuts, refused while any conflict; null goes back to the defaults
+export async function saveShortcuts(shortcuts: KeyboardShortcut[] | null): Promise<void> {
+  return invoke('set_shortcuts', { shortcuts });
+}
+
+// write the shortcuts to a profile file for another machine
+export async function exportShortcuts(
+  path: string,
+  shortcuts: KeyboardShortcut[],
+): Promise<void> {
+  return invoke('export_shortcuts', { path, shortcuts });
+}
+
+// save the shortcuts of a profile file, unless they conflict, and return them
+export async function importShortcuts(path: string): Promise<KeyboardShortcut[]> {
+  return invoke<KeyboardShortcut[]>('import_shortcuts', { path });
+}
+
+export interface SystemAppearance {
+  dark: boolean;
+  /** #rrggbb, null when the system has no accent color */
+  accentColor: string | null;
+}
+
+// the system's dark mode and accent color; changes arrive as the appearance-changed event
+export async function getSystemAppearance(): Promise<SystemAppearance> {
+  return invoke<SystemAppearance>('get_system_appearance');
+}
+
+export interface AttachmentCacheUsage {
+  usedBytes: number;
+  maxBytes: number;
+  files: number;
+}
+
+// how full the attachment cache is
+export async function getAttachmentCacheUsage(): Promise<AttachmentCacheUsage> {
+  return invoke<AttachmentCacheUsage>('get_attachment_cache_usage');
+}
+
+// change the size limit of the attachment cache, null for the default
+export async function setAttachmentCacheLimit(maxBytes: number | null): Promise<void> {
+  return invoke('set_attachment_cache_limit', { maxBytes });
+}
+
+// remove every cached attachment
+export async function clearAttachmentCache(): Promise<void> {
+  return invoke('clear_attachment_cache');
+}
+
+export interface SyncSchedule {
+  accountId: string;
+  /** minutes between syncs: null for the app-wide interval, 0 to only sync by hand */
+  interval: number | null;
+  /** local "HH:MM"; the account doesn't sync from quietStart to quietEnd */
+  quietStart: string | null;
+  quietEnd: string | null;
+}
+
+export async function getSyncSchedules(): Promise<SyncSchedule[]> {
+  return invoke<SyncSchedule[]>('get_sync_schedules');
+}
+
+export async function setSyncSchedule(schedule: SyncSchedule): Promise<void> {
+  return invoke('set_sync_schedule', { schedule });
+}
+
+// the accounts whose interval ran out and that aren't in their quiet hours
+export async function getDueAccounts(defaultInterval: number): Promise<string[]> {
+  return invoke<string[]>('get_due_accounts', { defaultInterval });
+}
+
+// pause an account (no syncing, no edits) or make it active again; its data stays
+export async function setAccountActive(accountId: string, active: boolean): Promise<void> {
+  return invoke('set_account_active', { accountId, active });
+}
+
+// archive a finished calendar (its tasks stay, but it doesn't sync or show up in the lists
+// and counts) or restore it
+export async function setCalendarArchived(calendarId: string, archived: boolean): Promise<void> {
+  return invoke('set_calendar_archived', { calendarId, archived });
+}
+
+// the sidebar order of the accounts, top first
+export async function reorderAccounts(orderedIds: string[]): Promise<void> {
+  return invoke('reorder_accounts', { orderedIds });
+}
+
+// the sidebar order of an account's calendars, top first
+export async function reorderCalendars(accountId: string, orderedIds: string[]): Promise<void> {
+  return invoke('reorder_calendars', { accountId, orderedIds });
+}
+
+// "#rrggbb", or null for the default color
+export async function setAccountColor(accountId: string, color: string | null): Promise<void> {
+  return invoke('set_account_color', { accountId, color });
+}
+
+export async function setAccountCollapsed(accountId: string, collapsed: boolean): Promise<void> {
+  return invoke('set_account_collapsed', { accountId, collapsed });
+}
+
+// null forgets the method, so the next connection negotiates it again
+export async function setAccountAuthMethod(
+  accountId: string,
+  method: 'basic' | 'digest' | 'bearer' | null,
+): Promise<void> {
+  return invoke('set_account_auth_method', { accountId, method });
+}
+
+export async function getAccountEncryption(accountId: string): Promise<boolean> {
+  return invoke<boolean>('get_account_encryption', { accountId });
+}
+
+// null turns it off; either way every task of the account is uploaded again on the next sync
+export async function setAccountEncryption(
+  accountId: string,
+  passphrase: string | null,
+): Promise<void> {
+  return invoke('set_account_encryption', { accountId, passphrase });
+}
+
+export async function getAccountTls(accountId: string): Promise<TlsSettings> {
+  const tls = await invoke<TlsSettings>('get_account_tls', { accountId });
+  // null when no certificate is pinned
+  return { ...tls, pinnedCertificate: tls.pinnedCertificate || undefined };
+}
+
+// checked by the backend: invalid hostnames are only allowed with a pinned certificate
+export async function setAccountTls(accountId: string, tls: TlsSettings): Promise<void> {
+  return invoke('set_account_tls', { accountId, tls });
+}
+
+export type SyncConflictResolution = 'keepLocal' | 'keepRemote';
+
+// a local edit that collided with a change on the server; the versions are the tasks as
+// json, so their dates are strings
+export interface SyncConflict {
+  id: string;
+  taskId: string;
+  taskUid: string;
+  accountId: string;
+  calendarId: string;
+  title: string;
+  local: Task;
+  /** null when the task was deleted on the server */
+  remote: Task | null;
+  detectedAt: string;
+  resolvedAt: string | null;
+  resolution: SyncConflictResolution | null;
+}
+
+// queue the conflict for review and notify; a task already in the queue is only updated
+export async function recordSyncConflict(
+  conflict: Pick<
+    SyncConflict,
+    'taskId' | 'taskUid' | 'accountId' | 'calendarId' | 'title' | 'local' | 'remote'
+  >,
+): Promise<SyncConflict> {
+  return invoke<SyncConflict>('record_sync_conflict', { conflict });
+}
+
+export async function getSyncConflicts(includeResolved = false): Promise<SyncConflict[]> {
+  return invoke<SyncConflict[]>('get_sync_conflicts', { includeResolved });
+}
+
+export async function resolveSyncConflict(
+  id: string,
+  resolution: SyncConflictResolution,
+): Promise<void> {
+  return invoke('resolve_sync_conflict', { id, resolution });
+}
+
+export type PendingKind = 'task' | 'deletion';
+
+// a local change that hasn't reached the server; `id` is the task's id, or the deleted
+// task's uid for deletions
+export interface PendingChange {
+  kind: PendingKind;
+  id: string;
+  taskUid: string;
+  /** null for deletions, whose task is gone */
+  title: string | null;
+  accountId: string;
+  accountName: string | null;
+  calendarId: string;
+  calendarName: string | null;
+  operation: 'create' | 'update' | 'delete';
+  modifiedAt: string | null;
+  /** null when the next sync tries it */
+  blocked: 'accountPaused' | 'conflict' | 'authFailed' | null;
+  lastError: string | null;
+  attempts: number;
+  lastAttemptAt: string | null;
+}
+
+export async function getPendingChanges(): Promise<PendingChange[]> {
+  return invoke<PendingChange[]>('get_pending_changes');
+}
+
+// the outcome of uploading a change during a sync, null when it went through
+export async function reportPushResult(
+  kind: PendingKind,
+  id: string,
+  error: string | null,
+): Promise<void> {
+  return invoke('report_push_result', { kind, id, error });
+}
+
+// drop a change: new tasks are removed, edits and deletions give way to the server's copy
+export async function discardPendingChange(kind: PendingKind, id: string): Promise<void> {
+  return invoke('discard_pending_change', { kind, id });
+}
+
+// a copy of the database taken before new migrations ran
+export interface DbSnapshot {
+  fileName: string;
+  /** the schema version the database had */
+  version: number;
+  /** unix seconds */
+  createdAt: number;
+  size: number;
+}
+
+export async function getDbSnapshots(): Promise<DbSnapshot[]> {
+  return invoke<DbSnapshot[]>('get_db_snapshots');
+}
+
+// replace the database with a snapshot; the app restarts
+export async function rollbackToSnapshot(fileName: string): Promise<void> {
+  return invoke('rollback_to_snapshot', { fileName });
+}
+
+export interface OrphanReport {
+  tasksReassigned: number;
+  tasksDeleted: number;
+  calendarsRemoved: number;
+}
+
+// fix tasks whose calendar or account is gone: 'reassign' keeps them as local tasks
+export async function repairOrphans(mode: 'reassign' | 'delete'): Promise<OrphanReport> {
+  return invoke<OrphanReport>('repair_orphans', { mode });
+}
+
+export interface CleanupReport {
+  /** tasks whose calendar or account was deleted */
+  tasks: { id: string; name: string }[];
+  /** calendars of deleted accounts */
+  calendars: { id: string; name: string }[];
+  /** server deletions still queued for deleted accounts */
+  pendingDeletions: { id: string; name: string }[];
+  /** tags no task uses anymore */
+  tags: { id: string; name: string }[];
+  /** cached ics of deleted calendars */
+  cachedObjects: number;
+  /** false for a dry run */
+  applied: boolean;
+}
+
+// remove rows left behind by deleted accounts and calendars, and unused tags;
+// without apply it only reports what would be removed
+export async function cleanupDatabase(apply: boolean): Promise<CleanupReport> {
+  return invoke<CleanupReport>('cleanup_database', { apply });
+}
+
+// what the panic hook wrote when the app crashed
+export interface CrashReport {
+  fileName: string;
+  /** unix seconds */
+  createdAt: number;
+  contents: string;
+}
+
+// the newest crash report that wasn't shared or dismissed yet
+export async function getLastCrashReport(): Promise<CrashReport | null> {
+  return invoke<CrashReport | null>('get_last_crash_report');
+}
+
+export async function dismissCrashReport(fileName: string): Promise<void> {
+  return invoke('dismiss_crash_report', { fileName });
+}
+
+export type LogLevel = 'off' | 'error' | 'warn' | 'info' | 'debug' | 'trace';
+
+export async function getLogLevel(): Promise<LogLevel> {
+  return invoke<LogLevel>('get_log_level');
+}
+
+// how much the backend logs from now on; it starts at debug until this is applied
+export async function setLogLevel(level: LogLevel): Promise<void> {
+  return invoke('set_log_level', { level });
+}
+
+// show the log files in the system file manager
+export async function openLogFolder(): Promise<void> {
+  return invoke('open_log_folder');
+}
+
+// zip the logs, versions, account metadata (no credentials) and recent syncs for a bug report
+export async function exportDiagnostics(path: string): Promise<void> {
+  return invoke('export_diagnostics', { path });
+}
+
+// one request of a sync and the server's answer, with credentials removed
+export interface WireTraceEntry {
+  /** unix milliseconds */
+  timestamp: number;
+  method: string;
+  url: string;
+  requestHeaders: Record<string, string>;
+  /** cut off after 64 KB */
+  requestBody: string | null;
+  status: number | null;
+  responseHeaders: Record<string, string>;
+  responseBody: string | null;
+  durationMs: number | null;
+  error: string | null;
+}
+
+export async function getWireTraceEnabled(): Promise<boolean> {
+  return invoke<boolean>('get_wire_trace_enabled');
+}
+
+// record the requests of syncs; turning it off drops what was recorded
+export async function setWireTraceEnabled(enabled: boolean): Promise<void> {
+  return invoke('set_wire_trace_enabled', { enabled });
+}
+
+// the recorded requests, oldest first; only the latest 200 are kept
+export async function getWireTrace(): Promise<WireTraceEntry[]> {
+  return invoke<WireTraceEntry[]>('get_wire_trace');
+}
+
+export async function clearWireTrace(): Promise<void> {
+  return invoke('clear_wire_trace');
+}
+
+/** timings of one repeated measurement, in milliseconds */
+export interface LatencyStats {
+  name: string;
+  iterations: number;
+  minMs: number;
+  medianMs: number;
+  p95Ms: number;
+  maxMs: number;
+}
+
+export interface BenchmarkReport {
+  reportVersion: number;
+  appVersion: string;
+  os: string;
+  osVersion: string;
+  arch: string;
+  startedAt: string;
+  /** tasks in the database the queries ran against */
+  taskCount: number;
+  database: LatencyStats[];
+  sync: {
+    objects: number;
+    bytes: number;
+    durationMs: number;
+    objectsPerSec: number;
+    megabytesPerSec: number;
+  };
+  serialization: {
+    tasks: number;
+    bytes: number;
+    serialize: LatencyStats;
+    deserialize: LatencyStats;
+  };
+  totalMs: number;
+}
+
+// time database queries, a sync against a local mock server and task serialization
+export async function runBenchmark(): Promise<BenchmarkReport> {
+  return invoke<BenchmarkReport>('run_benchmark');
+}
+
+export interface SchemaCompatibility {
+  databaseVersion: number;
+  /** the newest schema this version knows */
+  supportedVersion: number;
+  /** false for a database written by a newer release, which is only read */
+  compatible: boolean;
+}
+
+export async function getSchemaCompatibility(): Promise<SchemaCompatibility> {
+  return invoke<SchemaCompatibility>('get_schema_compatibility');
+}
+
+// open a calendar in a window of its own, or focus the one already showing it
+export async function openCalendarWindow(calendarId: string, title?: string): Promise<void> {
+  await invoke('open_calendar_window', { calendarId, title: title ?? null });
+}
+
+// read-only mode, switched on by the user or forced by `--read-only`
+export interface ReadOnlyStatus {
+  enabled: boolean;
+  /** why it can't be turned off, if it can't */
+  forced: string | null;
+}
+
+export async function getReadOnly(): Promise<ReadOnlyStatus> {
+  return invoke<ReadOnlyStatus>('get_read_only');
+}
+
+export async function setReadOnly(enabled: boolean): Promise<void> {
+  return invoke('set_read_only', { enabled });
+}
+
+// self-updating; packaged and portable builds only check, and link the release instead
+export interface UpdateInfo {
+  version: string;
+  currentVersion: string;
+  notes: string | null;
+  date: string | null;
+  channel: string;
+  /** false when the update has to come from the package manager or the release page */
+  canInstall: boolean;
+  releaseUrl: string;
+}
+
+export interface UpdateSettings {
+  channel: 'stable' | 'beta';
+  /** check on a schedule and download new releases in the background */
+  autoCheck: boolean;
+}
+
+export interface UpdateMode {
+  mode: 'auto' | 'manual';
+  /** why the app doesn't update itself: 'packaged', 'flatpak', 'portable' or 'disabled' */
+  reason: string | null;
+}
+
+export async function getUpdateSettings(): Promise<UpdateSettings> {
+  return invoke<UpdateSettings>('get_update_settings');
+}
+
+export async function setUpdateSettings(settings: UpdateSettings): Promise<void> {
+  await invoke('set_update_settings', { settings });
+}
+
+export async function getUpdateMode(): Promise<UpdateMode> {
+  return invoke<UpdateMode>('get_update_mode');
+}
+
+// a new release is also announced with `update-available`
+export async function checkForUpdates(): Promise<UpdateInfo | null> {
+  return invoke<UpdateInfo | null>('check_for_updates');
+}
+
+// downloads the update unless that happened in the background, then restarts the app
+export async function installUpdate(): Promise<void> {
+  await invoke('install_update');
+}
+
+// PIN lock of the app; while it's locked the database isn't loaded
+export interface AppLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  /** seconds without input before the app locks itself, 0 to only lock on launch */
+  idleTimeout: number;
+}
+
+export async function getAppLockStatus(): Promise<AppLockStatus> {
+  return invoke<AppLockStatus>('get_app_lock_status');
+}
+
+export async function unlockApp(pin: string): Promise<void> {
+  await invoke('unlock', { pin });
+}
+
+export async function lockApp(): Promise<void> {
+  await invoke('lock_app');
+}
+
+// set or change the PIN, or remove it with `pin` null; an existing PIN has to be given as
+// `currentPin`
+export async function setAppLock(
+  pin: string | null,
+  currentPin: string | null,
+  idleTimeout: number,
+): Promise<AppLockStatus> {
+  return invoke<AppLockStatus>('set_app_lock', { pin, currentPin, idleTimeout });
+}
+
+export interface TaskNag {
+  taskUid: string;
+  /** the reminder fires again this often until the task is done or the reminder dismissed */
+  intervalMinutes: number;
+  lastNaggedAt: string | null;
+  dismissedAt: string | null;
+  /** the reminder went off and wasn't dismissed, so the task nags right now */
+  active: boolean;
+}
+
+export async function getTaskNag(uid: string): Promise<TaskNag | null> {
+  return invoke<TaskNag | null>('get_task_nag', { uid });
+}
+
+/** null stops nagging */
+export async function setTaskNag(uid: string, intervalMinutes: number | null): Promise<void> {
+  return invoke('set_task_nag', { uid, intervalMinutes });
+}
+
+export async function dismissTaskNag(uid: string): Promise<void> {
+  return invoke('dismiss_task_nag', { uid });
+}
+
+export interface PayloadLimits {
+  /** largest task (its whole VTODO) that is fetched or uploaded, in KB */
+  maxObjectKb: number;
+}
+
+export async function getPayloadLimits(): Promise<PayloadLimits> {
+  return invoke<PayloadLimits>('get_payload_limits');
+}
+
+export async function setPayloadLimits(limits: PayloadLimits): Promise<void> {
+  return invoke('set_payload_limits', { limits });
+}
+
+export type DateOrder = 'dayMonthYear' | 'monthDayYear' | 'yearMonthDay';
+
+/** calendar conventions of the OS locale, as the backend uses them */
+export interface LocaleSettings {
+  locale: string;
+  /** 0 for sunday through 6 for saturday, like Date.getDay */
+  firstDayOfWeek: number;
+  dateOrder: DateOrder;
+  /** chrono (strftime) format, like "%d.%m.%Y" */
+  dateFormat: string;
+  timeFormat: string;
+  hour12: boolean;
+}
+
+export async function getLocaleSettings(): Promise<LocaleSettings> {
+  return invoke<LocaleSettings>('get_locale_settings');
+}
+
+/** language of the text the backend writes: menus, the tray, notifications, reports, errors */
+export type BackendLanguage = 'en' | 'de';
+
+export interface LanguageSettings {
+  /** null follows the OS */
+  chosen: BackendLanguage | null;
+  active: BackendLanguage;
+}
+
+export async function getLanguage(): Promise<LanguageSettings> {
+  return invoke<LanguageSettings>('get_language');
+}
+
+export async function setLanguage(language: BackendLanguage | null): Promise<void> {
+  return invoke('set_language', { language });
+}
+
+/** effect behind the window's content; only Windows has any besides 'none' */
+export type WindowEffect = 'none' | 'acrylic' | 'mica';
+
+export interface WindowEffectSettings {
+  effect: WindowEffect;
+  supported: WindowEffect[];
+}
+
+export async function getWindowEffect(): Promise<WindowEffectSettings> {
+  const [effect, supported] = await Promise.all([
+    invoke<WindowEffect>('get_window_effect'),
+    invoke<WindowEffect[]>('get_supported_window_effects'),
+  ]);
+  return { effect, supported };
+}
+
+export async function setWindowEffect(effect: WindowEffect): Promise<void> {
+  return invoke('set_window_effect', { effect });
+}
+
+// labels of the app menu in the backend's language, English ones it doesn't know stay as they are
+export async function translate(messages: string[]): Promise<string[]> {
+  return invoke<string[]>('translate', { messages });
+}
+
+// whether the titles of task links are fetched; off unless turned on, the site sees the visit
+export async function getFetchLinkTitles(): Promise<boolean> {
+  return invoke<boolean>('get_fetch_link_titles');
+}
+
+export async function setFetchLinkTitles(enabled: boolean): Promise<void> {
+  return invoke('set_fetch_link_titles', { enabled });
+}
+
+// the title of the page behind a link, null when fetching titles is off or there is none
+export async function fetchPageTitle(url: string): Promise<string | null> {
+  return invoke<string | null>('fetch_page_title', { url });
+}
+
+// the task list as the user currently sees it
+export interface TaskView {
+  calendarId: string | null;
+  tagId: string | null;
+  search: string;
+  showCompleted: boolean;
+  sort: SortConfig;
+}
+
+export interface VisibleTaskPage {
+  tasks: FlattenedTask[];
+  total: number;
+  offset: number;
+}
+
+// one page of the visible tasks of a view in display order, filtered, sorted and flattened
+// by the backend
+
+export async function queryVisibleTasks(
+  view: TaskView,
+  offset: number,
+  limit: number,
+): Promise<VisibleTaskPage> {
+  const page = await invoke<{ tasks: any[]; total: number; offset: number }>(
+    'query_visible_tasks',
+    { view, offset, limit },
+  );
+  return {
+    ...page,
+    tasks: page.tasks.map((task) => ({
+      ...backendToTask(task),
+      depth: task.depth,
+      ancestorIds: task.ancestorIds,
+    })),
+  };
+}
+
+// what `agenda` covers: overdue and today, up to the end of the week, or every due date
+export type AgendaRange = 'today' | 'week' | 'all';
+
+export type AgendaBucket = 'overdue' | 'today' | 'tomorrow' | 'thisWeek' | 'later';
+
+// open tasks of one agenda bucket, each with the local yyyy-mm-dd it's due on
+export interface AgendaGroup {
+  bucket: AgendaBucket;
+  tasks: (Task & { day: string })[];
+}
+
+// open tasks with a due date grouped by day, worked out by the backend in local time
+export async function getAgenda(range: AgendaRange): Promise<AgendaGroup[]> {
+  const groups = await invoke<{ bucket: AgendaBucket; tasks: any[] }[]>('agenda', { range });
+  return groups.map((group) => ({
+    bucket: group.bucket,
+    tasks: group.tasks.map((task) => ({ ...backendToTask(task), day: task.day })),
+  }));
+}
+
+// position of a task after a reorder
+export interface TaskPosition {
+  id: string;
+  uid: string;
+  sortOrder: number;
+  parentUid: string | null;
+  calendarId: string | null;
+  accountId: string | null;
+}
+
+// persist the order of sibling tasks (ascending sort order) in a single transaction;
+// returns the tasks whose position changed
+export async function reorderTasks(
+  orderedIds: string[],
+  parentUid: string | undefined,
+): Promise<TaskPosition[]> {
+  const positions = await invoke<TaskPosition[]>('reorder_tasks', {
+    orderedIds,
+    parentUid: parentUid ?? null,
+  });
+  notifyListeners();
+  return positions;
+}
+
+// one change applied to many tasks by bulkUpdate (dates as iso strings)
+export type BulkOperation =
+  | { type: 'complete'; completed?: boolean }
+  | { type: 'delete' }
+  | { type: 'moveToList'; calendarId: string }
+  | { type: 'setDue'; dueDate: string | null; allDay?: boolean }
+  | { type: 'addTag'; tagId: string };
+
+// apply one operation to many tasks in a single transaction; returns how many changed
+export async function bulkUpdate(ids: string[], op: BulkOperation): Promise<number> {
+  const count = await invoke<number>('bulk_update', { ids, op });
+  notifyListeners();
+  return count;
+}
 
-// Pending deletion interface
-export interface PendingDeletion {
-  uid: string;
-  href: string;
-  accountId: string;
-  calendarId: string;
+// fold duplicates into the primary task and delete them; returns the merged task
+export async function mergeTasks(primary: string, duplicates: string[]): Promise<Task> {
+  const merged = await invoke<unknown>('merge_tasks', { primary, duplicates });
+  notifyListeners();
+  return backendToTask(merged);
 }
 
-// UI State interface
-export interface UIState {
-  activeAccountId: string | null;
-  activeCalendarId: string | null;
-  activeTagId: string | null;
-  selectedTaskId: string | null;
-  searchQuery: string;
-  sortConfig: SortConfig;
-  showCompletedTasks: boolean;
-  isEditorOpen: boolean;
+export interface TaskDependencies {
+  blockedBy: Task[];
+  blocks: Task[];
 }
 
-// Complete data store interface
-export interface DataStore {
-  tasks: Task[];
-  tags: Tag[];
-  accounts: Account[];
-  pendingDeletions: PendingDeletion[];
-  ui: UIState;
+// the tasks a task waits for and the tasks waiting for it
+export async function getDependencies(uid: string): Promise<TaskDependencies> {
+  const dependencies = await invoke<{ blockedBy: unknown[]; blocks: unknown[] }>(
+    'get_dependencies',
+    { uid },
+  );
+  return {
+    blockedBy: dependencies.blockedBy.map(backendToTask),
+    blocks: dependencies.blocks.map(backendToTask),
+  };
 }
 
-// Default UI state
-const defaultUIState: UIState = {
-  activeAccountId: null,
-  activeCalendarId: null,
-  activeTagId: null,
-  selectedTaskId: null,
-  searchQuery: '',
-  sortConfig: { mode: 'manual', direction: 'asc' },
-  showCompletedTasks: true,
-  isEditorOpen: false,
-};
+// make a task wait for another one; rejected when the tasks would wait for each other
+export async function addDependency(taskUid: string, dependsOnUid: string): Promise<void> {
+  await invoke('add_dependency', { taskUid, dependsOnUid });
+  notifyListeners();
+}
 
-// Event listeners for data changes
-type DataChangeListener = () => void;
-const listeners: Set<DataChangeListener> = new Set();
+export async function removeDependency(taskUid: string, dependsOnUid: string): Promise<void> {
+  await invoke('remove_dependency', { taskUid, dependsOnUid });
+  notifyListeners();
+}
 
-export function subscribeToDataChanges(listener: DataChangeListener): () => void {
-  listeners.add(listener);
-  return () => listeners.delete(listener);
+// open tasks that nothing is blocking, optionally in a single calendar
+export async function getActionableTasks(calendarId?: string): Promise<Task[]> {
+  const tasks = await invoke<unknown[]>('query_actionable_tasks', {
+    calendarId: calendarId ?? null,
+  });
+  return tasks.map(backendToTask);
 }
 
-function notifyListeners(): void {
-  listeners.forEach((listener) => {
-    listener();
+// complete or reopen a task; returns the tasks that completing it unblocked
+export async function completeTask(id: string, completed: boolean): Promise<Task[]> {
+  const unblocked = await invoke<unknown[]>('complete_task', { id, completed });
+  notifyListeners();
+  return unblocked.map(backendToTask);
+}
+
+export type TimeGrouping = 'task' | 'tag' | 'day';
+
+// tracked time per task uid, tag id or day (yyyy-mm-dd)
+export interface TimeTotal {
+  key: string;
+  label: string;
+  seconds: number;
+}
+
+export type PlanGrouping = 'day' | 'week';
+
+// estimated work for the tasks due in a day, or in the week starting on key (yyyy-mm-dd)
+export interface PlannedTime {
+  key: string;
+  seconds: number;
+  remainingSeconds: number;
+  tasks: number;
+  unestimated: number; // open tasks without an estimate
+}
+
+export type StatsGrouping = 'day' | 'week' | 'list' | 'tag';
+
+// completed tasks per day, per week starting on key (yyyy-mm-dd), per calendar id or tag id
+export interface CompletionStats {
+  key: string;
+  label: string;
+  completed: number;
+  completedLate: number;
+  averageAgeDays: number | null; // from creation to completion
+}
+
+// tasks overdue at the end of a day (yyyy-mm-dd)
+export interface OverdueDay {
+  day: string;
+  overdue: number;
+  completedLate: number;
+}
+
+// start timing a task; a running timer is stopped first
+export async function startTimer(taskUid: string): Promise<TimeEntry> {
+  return invoke<TimeEntry>('start_timer', { taskUid });
+}
+
+// stop the running timer; returns the finished entry, if there was one
+export async function stopTimer(): Promise<TimeEntry | null> {
+  return invoke<TimeEntry | null>('stop_timer');
+}
+
+export async function getRunningTimer(): Promise<TimeEntry | null> {
+  return invoke<TimeEntry | null>('get_running_timer');
+}
+
+export async function getTimeEntries(taskUid: string): Promise<TimeEntry[]> {
+  return invoke<TimeEntry[]>('get_time_entries', { taskUid });
+}
+
+export async function deleteTimeEntry(id: string): Promise<void> {
+  await invoke('delete_time_entry', { id });
+}
+
+// totals for entries started in [from, to); dates as iso strings
+export async function getTimeTotals(
+  groupBy: TimeGrouping,
+  from?: string,
+  to?: string,
+): Promise<TimeTotal[]> {
+  return invoke<TimeTotal[]>('get_time_totals', { groupBy, from: from ?? null, to: to ?? null });
+}
+
+// summed estimated durations of the tasks due in [from, to), per day or week
+export async function getPlannedTime(
+  groupBy: PlanGrouping,
+  from?: string,
+  to?: string,
+): Promise<PlannedTime[]> {
+  return invoke<PlannedTime[]>('get_planned_time', { groupBy, from: from ?? null, to: to ?? null });
+}
+
+// completion statistics for tasks completed in [from, to); dates as iso strings
+export async function getCompletionStats(
+  groupBy: StatsGrouping,
+  from?: string,
+  to?: string,
+): Promise<CompletionStats[]> {
+  return invoke<CompletionStats[]>('get_completion_stats', {
+    groupBy,
+    from: from ?? null,
+    to: to ?? null,
   });
 }
 
-// Initialize database connection
-export async function initDatabase(): Promise<Database> {
-  if (db) return db;
+// overdue tasks at the end of every day from `from` up to `to`
+export async function getOverdueTrend(from: string, to: string): Promise<OverdueDay[]> {
+  return invoke<OverdueDay[]>('get_overdue_trend', { from, to });
+}
 
-  try {
-    db = await Database.load(DB_NAME);
-    log.info('Connected to SQLite database');
+// days in a row; a streak stays current until a whole day passes without keeping it
+export interface Streak {
+  current: number;
+  longest: number;
+}
 
-    return db;
-  } catch (error) {
-    log.error('Failed to connect:', error);
-    throw error;
-  }
+export interface Streaks {
+  completion: Streak; // days with at least one completed task
+  noOverdue: Streak; // days that ended without overdue tasks
 }
 
-// Get database instance (ensures initialization)
-async function getDb(): Promise<Database> {
-  if (!db) {
-    await initDatabase();
-  }
-  return db!;
+export async function getStreaks(): Promise<Streaks> {
+  return invoke<Streaks>('get_streaks');
 }
 
-// Helper to convert database row to Task
-function rowToTask(row: any): Task {
-  return {
-    id: row.id,
-    uid: row.uid,
-    etag: row.etag || undefined,
-    href: row.href || undefined,
-    title: row.title,
-    description: row.description,
-    completed: row.completed === 1,
-    completedAt: row.completed_at ? new Date(row.completed_at) : undefined,
-    tags: row.tags ? JSON.parse(row.tags) : undefined,
-    categoryId: row.category_id || undefined,
-    priority: row.priority as Priority,
-    startDate: row.start_date ? new Date(row.start_date) : undefined,
-    startDateAllDay: row.start_date_all_day === 1,
-    dueDate: row.due_date ? new Date(row.due_date) : undefined,
-    dueDateAllDay: row.due_date_all_day === 1,
-    createdAt: new Date(row.created_at),
-    modifiedAt: new Date(row.modified_at),
-    reminders: row.reminders
-      ? JSON.parse(row.reminders).map((r: any) => ({
-          ...r,
-          trigger: new Date(r.trigger),
-        }))
-      : undefined,
-    subtasks: row.subtasks ? JSON.parse(row.subtasks) : [],
-    parentUid: row.parent_uid || undefined,
-    isCollapsed: row.is_collapsed === 1,
-    sortOrder: row.sort_order,
-    url: row.url || undefined,
-    accountId: row.account_id || '',
-    calendarId: row.calendar_id || '',
-    synced: row.synced === 1,
-    localOnly: row.local_only === 1,
-  };
+// false when the desktop has no tray host; closing the window then minimizes it
+export async function isTraySupported(): Promise<boolean> {
+  return invoke<boolean>('is_tray_supported');
 }
 
-// Helper to convert database row to Account (with calendars)
-function rowToAccount(row: any, calendars: Calendar[]): Account {
-  return {
-    id: row.id,
-    name: row.name,
-    serverUrl: row.server_url,
-    username: row.username,
-    password: row.password,
-    serverType: row.server_type || undefined,
-    calendars: calendars.filter((c) => c.accountId === row.id),
-    lastSync: row.last_sync ? new Date(row.last_sync) : undefined,
-    isActive: row.is_active === 1,
-  };
+export async function getStreakInTray(): Promise<boolean> {
+  return invoke<boolean>('get_streak_in_tray');
 }
 
-// Helper to convert database row to Calendar
-function rowToCalendar(row: any): Calendar {
-  return {
-    id: row.id,
-    displayName: row.display_name,
-    url: row.url,
-    ctag: row.ctag || undefined,
-    syncToken: row.sync_token || undefined,
-    color: row.color || undefined,
-    icon: row.icon || undefined,
-    accountId: row.account_id,
-    supportedComponents: row.supported_components
-      ? JSON.parse(row.supported_components)
-      : undefined,
-  };
+// show the completion streak in the tray tooltip
+export async function setStreakInTray(enabled: boolean): Promise<void> {
+  await invoke('set_streak_in_tray', { enabled });
 }
 
-// Helper to convert database row to Tag
-function rowToTag(row: any): Tag {
-  return {
-    id: row.id,
-    name: row.name,
-    color: row.color,
-    icon: row.icon || undefined,
-  };
+export type ReportFormat = 'html' | 'markdown';
+
+// summary of the tasks completed in [from, to) and of the open ones, grouped by list and tag
+export async function generateReport(
+  from: string,
+  to: string,
+  format: ReportFormat,
+): Promise<string> {
+  return invoke<string>('generate_report', { range: { from, to }, format });
 }
 
-// task operations
+// a board column and its tasks; 'todo', 'in-progress' and 'done' are built in
+export interface KanbanColumn {
+  id: string;
+  custom: boolean;
+  tasks: Task[];
+}
 
-export async function getAllTasks(): Promise<Task[]> {
-  const database = await getDb();
-  const rows = await database.select<any[]>('SELECT * FROM tasks');
-  return rows.map(rowToTask);
+// tasks grouped per board column, optionally of a single calendar
+export async function getKanbanBoard(calendarId?: string): Promise<KanbanColumn[]> {
+  const board = await invoke<{ id: string; custom: boolean; tasks: unknown[] }[]>(
+    'get_kanban_board',
+    { calendarId: calendarId ?? null },
+  );
+  return board.map((column) => ({ ...column, tasks: column.tasks.map(backendToTask) }));
 }
 
-export async function getTaskById(id: string): Promise<Task | undefined> {
-  const database = await getDb();
-  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE id = $1', [id]);
-  return rows.length > 0 ? rowToTask(rows[0]) : undefined;
+// move a task to another column; 'done' completes it, any other column reopens it
+export async function moveToColumn(id: string, column: string): Promise<void> {
+  await invoke('move_to_column', { id, column });
+  notifyListeners();
 }
 
-export async function getTaskByUid(uid: string): Promise<Task | undefined> {
-  const database = await getDb();
-  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE uid = $1', [uid]);
-  return rows.length > 0 ? rowToTask(rows[0]) : undefined;
+// add a custom column; returns the custom columns in board order
+export async function addKanbanColumn(name: string): Promise<string[]> {
+  return invoke<string[]>('add_kanban_column', { name });
 }
 
-export async function getTasksByCalendar(calendarId: string): Promise<Task[]> {
-  const database = await getDb();
-  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE calendar_id = $1', [
-    calendarId,
-  ]);
-  return rows.map(rowToTask);
+// remove a custom column, its tasks go back to 'todo'; returns the remaining custom columns
+export async function removeKanbanColumn(name: string): Promise<string[]> {
+  const columns = await invoke<string[]>('remove_kanban_column', { name });
+  notifyListeners();
+  return columns;
 }
 
-export async function getTasksByTag(tagId: string): Promise<Task[]> {
-  const database = await getDb();
-  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE tags LIKE $1', [
-    `%"${tagId}"%`,
-  ]);
-  return rows.map(rowToTask);
+// every GTD context with its number of open tasks, alphabetically
+export async function getContexts(): Promise<Context[]> {
+  const contexts = await invoke<Context[]>('get_contexts');
+  return contexts.map((context) => ({ ...context, color: context.color ?? undefined }));
 }
 
-export async function getChildTasks(parentUid: string): Promise<Task[]> {
-  const database = await getDb();
-  const rows = await database.select<any[]>('SELECT * FROM tasks WHERE parent_uid = $1', [
-    parentUid,
-  ]);
-  return rows.map(rowToTask);
+// add a context or change its color; returns the name as stored (with a leading '@')
+export async function saveContext(name: string, color?: string): Promise<string> {
+  return invoke<string>('save_context', { name, color: color ?? null });
 }
 
-export async function countChildren(parentUid: string): Promise<number> {
-  const database = await getDb();
-  const rows = await database.select<any[]>(
-    'SELECT COUNT(*) as count FROM tasks WHERE parent_uid = $1',
-    [parentUid],
+// rename a context on every task that has it; returns the name as stored
+export async function renameContext(name: string, newName: string): Promise<string> {
+  const stored = await invoke<string>('rename_context', { name, newName });
+  notifyListeners();
+  return stored;
+}
+
+// remove a context from every task that has it
+export async function deleteContext(name: string): Promise<void> {
+  await invoke('delete_context', { name });
+  notifyListeners();
+}
+
+// the next actions of a context
+export interface NextActions {
+  context: string;
+  tasks: Task[];
+}
+
+// open, unblocked tasks that have started, grouped per context (or of a single context)
+export async function getNextActions(context?: string): Promise<NextActions[]> {
+  const groups = await invoke<{ context: string; tasks: unknown[] }[]>('query_next_actions', {
+    context: context ?? null,
+  });
+  return groups.map((group) => ({ ...group, tasks: group.tasks.map(backendToTask) }));
+}
+
+export interface ArchivePolicy {
+  // archive tasks completed this many days ago; 0 turns archiving off
+  afterDays: number;
+}
+
+export async function getArchivePolicy(): Promise<ArchivePolicy> {
+  return invoke<ArchivePolicy>('get_archive_policy');
+}
+
+// save the policy and apply it right away; returns how many tasks were archived
+export async function setArchivePolicy(policy: ArchivePolicy): Promise<number> {
+  const archived = await invoke<number>('set_archive_policy', { policy });
+  notifyListeners();
+  return archived;
+}
+
+// one page of archived tasks, most recently completed first
+export async function queryArchivedTasks(
+  search: string | undefined,
+  offset: number,
+  limit: number,
+): Promise<TaskPage> {
+  const page = await invoke<{ tasks: any[]; total: number; offset: number }>(
+    'query_archived_tasks',
+    { search: search ?? null, offset, limit },
   );
-  return rows[0]?.count || 0;
+  return { ...page, tasks: page.tasks.map(backendToTask) };
+}
+
+// bring archived tasks back together with their sub-tasks; returns how many were restored
+export async function restoreArchivedTasks(ids: string[]): Promise<number> {
+  const restored = await invoke<number>('restore_archived_tasks', { ids });
+  notifyListeners();
+  return restored;
+}
+
+// uids of a calendar's archived tasks, which a sync must not download again
+export async function getArchivedUids(calendarId: string): Promise<string[]> {
+  return invoke<string[]>('get_archived_uids', { calendarId });
+}
+
+// move tasks and their sub-tasks to another calendar; the next sync removes them from the old
+// collection and uploads them to the new one. returns how many tasks moved
+export async function moveTasks(ids: string[], calendarId: string): Promise<number> {
+  const count = await invoke<number>('move_tasks', { ids, calendarId });
+  notifyListeners();
+  return count;
+}
+
+// copy a task (and optionally its sub-tasks) with fresh uids; returns the copies, the top one first
+export async function duplicateTask(
+  uid: string,
+  includeSubtasks: boolean,
+  offsetDays?: number,
+): Promise<Task[]> {
+  const copies = await invoke<unknown[]>('duplicate_task', {
+    uid,
+    includeSubtasks,
+    offsetDays: offsetDays ?? null,
+  });
+  notifyListeners();
+  return copies.map(backendToTask);
+}
+
+// insert or replace many tasks at once; the backend writes them in chunked transactions
+export async function upsertTasks(tasks: Task[]): Promise<void> {
+  if (tasks.length === 0) return;
+  await invoke<number>('upsert_tasks', { tasks });
+  notifyListeners();
 }
 
 export async function createTask(taskData: Partial<Task>): Promise<Task> {
@@ -309,8 +2123,9 @@ export async function createTask(taskData: Partial<Task>): Promise<Task> {
       tags, category_id, priority, start_date, start_date_all_day,
       due_date, due_date_all_day, created_at, modified_at, reminders,
       subtasks, parent_uid, is_collapsed, sort_order, account_id,
-      calendar_id, synced, local_only, url
-    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27)`,
+      calendar_id, synced, local_only, url, estimated_duration, kanban_column, contexts,
+      change_id
+    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31)`,
     [
       task.id,
       task.uid,
@@ -339,6 +2154,10 @@ export async function createTask(taskData: Partial<Task>): Promise<Task> {
       task.synced ? 1 : 0,
       task.localOnly ? 1 : 0,
       task.url || null,
+      task.estimatedDuration ?? null,
+      task.kanbanColumn ?? null,
+      task.contexts && task.contexts.length > 0 ? JSON.stringify(task.contexts) : null,
+      task.changeId ?? null,
     ],
   );
 
@@ -366,8 +2185,9 @@ export async function updateTask(id: string, updates: Partial<Task>): Promise<Ta
       due_date = $13, due_date_all_day = $14, modified_at = $15,
       reminders = $16, subtasks = $17, parent_uid = $18, is_collapsed = $19,
       sort_order = $20, account_id = $21, calendar_id = $22, synced = $23,
-      local_only = $24, url = $25
-     WHERE id = $26`,
+      local_only = $24, url = $25, estimated_duration = $26, kanban_column = $27,
+      contexts = $28, change_id = $29
+     WHERE id = $30`,
     [
       updatedTask.uid,
       updatedTask.etag || null,
@@ -396,6 +2216,12 @@ export async function updateTask(id: string, updates: Partial<Task>): Promise<Ta
       updatedTask.synced ? 1 : 0,
       updatedTask.localOnly ? 1 : 0,
       updatedTask.url || null,
+      updatedTask.estimatedDuration ?? null,
+      updatedTask.kanbanColumn ?? null,
+      updatedTask.contexts && updatedTask.contexts.length > 0
+        ? JSON.stringify(updatedTask.contexts)
+        : null,
+      updatedTask.changeId ?? null,
       id,
     ],
   );
@@ -543,11 +2369,15 @@ export async function deleteTag(id: string): Promise<void> {
 export async function getAllAccounts(): Promise<Account[]> {
   const database = await getDb();
 
-  const accountRows = await database.select<any[]>('SELECT * FROM accounts');
-  const calendarRows = await database.select<any[]>('SELECT * FROM calendars');
+  const accountRows = await database.select<any[]>('SELECT * FROM accounts ORDER BY sort_order');
+  const calendarRows = await database.select<any[]>(
+    'SELECT * FROM calendars ORDER BY sort_order',
+  );
   const calendars = calendarRows.map(rowToCalendar);
 
-  return accountRows.map((row) => rowToAccount(row, calendars));
+  return Promise.all(
+    accountRows.map(async (row) => rowToAccount(row, calendars, await resolvePassword(row))),
+  );
 }
 
 export async function getAccountById(id: string): Promise<Account | undefined> {
@@ -565,22 +2395,25 @@ export async function createAccount(accountData: Partial<Account>): Promise<Acco
     username: accountData.username || '',
     password: accountData.password || '',
     serverType: accountData.serverType,
+    backend: accountData.backend ?? 'caldav',
     calendars: [],
     isActive: true,
   };
 
   await database.execute(
-    `INSERT INTO accounts (id, name, server_url, username, password, server_type, last_sync, is_active)
-     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)`,
+    `INSERT INTO accounts (id, name, server_url, username, password, server_type, backend, last_sync, is_active, collapsed, sort_order)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM accounts))`,
     [
       account.id,
       account.name,
       account.serverUrl,
       account.username,
-      account.password,
+      await storePassword(account.id, account.password),
       account.serverType || null,
+      account.backend,
       account.lastSync ? account.lastSync.toISOString() : null,
       account.isActive ? 1 : 0,
+      account.collapsed ? 1 : 0,
     ],
   );
 
@@ -611,7 +2444,7 @@ export async function updateAccount(
       updatedAccount.name,
       updatedAccount.serverUrl,
       updatedAccount.username,
-      updatedAccount.password,
+      await storePassword(id, updatedAccount.password),
       updatedAccount.serverType || null,
       updatedAccount.lastSync ? updatedAccount.lastSync.toISOString() : null,
       updatedAccount.isActive ? 1 : 0,
@@ -619,16 +2452,52 @@ export async function updateAccount(
     ],
   );
 
+  // calendars added or removed go through addCalendar/deleteCalendar, this only keeps the
+  // properties of the existing ones
+  for (const calendar of updates.calendars ?? []) {
+    await database.execute(
+      `UPDATE calendars SET display_name = $1, ctag = $2, sync_token = $3, color = $4, icon = $5,
+        checklist_as_tasks = $6, url = $7
+       WHERE id = $8`,
+      [
+        calendar.displayName,
+        calendar.ctag || null,
+        calendar.syncToken || null,
+        calendar.color || null,
+        calendar.icon || null,
+        calendar.checklistAsTasks ? 1 : 0,
+        calendar.url,
+        calendar.id,
+      ],
+    );
+  }
+
   notifyListeners();
   return updatedAccount;
 }
 
+// keep the tasks of a calendar that's gone from the server as local tasks in the Recovered
+// list instead of deleting them; the calendar is removed. returns how many tasks were kept
+export async function recoverCalendarTasks(calendarId: string): Promise<number> {
+  return invoke<number>('recover_calendar_tasks', { calendarId });
+}
+
+// the same for all calendars of an account that's about to be removed
+export async function recoverAccountTasks(accountId: string): Promise<number> {
+  return invoke<number>('recover_account_tasks', { accountId });
+}
+
 export async function deleteAccount(id: string): Promise<void> {
   const database = await getDb();
 
   // Delete cascades to calendars and tasks via foreign keys
   await database.execute('DELETE FROM accounts WHERE id = $1', [id]);
 
+  passwordCache.delete(id);
+  await invoke('delete_account_password', { accountId: id }).catch((error) =>
+    log.warn(`Failed to remove password for account ${id} from the credential store:`, error),
+  );
+
   // Update UI state
   const accounts = await getAllAccounts();
   const uiState = await getUIState();
@@ -658,8 +2527,8 @@ export async function addCalendar(
   log.debug(`Adding calendar: ${calendar.displayName} with ID: ${calendar.id}`);
 
   await database.execute(
-    `INSERT INTO calendars (id, account_id, display_name, url, ctag, sync_token, color, icon, supported_components)
-     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)`,
+    `INSERT INTO calendars (id, account_id, display_name, url, ctag, sync_token, color, icon, supported_components, checklist_as_tasks, sort_order)
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM calendars))`,
     [
       calendar.id,
       accountId,
@@ -670,6 +2539,7 @@ export async function addCalendar(
       calendar.color || null,
       calendar.icon || null,
       calendar.supportedComponents ? JSON.stringify(calendar.supportedComponents) : null,
+      calendar.checklistAsTasks ? 1 : 0,
     ],
   );
 
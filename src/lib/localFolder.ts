@@ -0,0 +1,254 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Account, Calendar, Task } from '@/types';
+import { taskToVTodo, vtodoToTask } from '../utils/ical';
+import { decryptTasks, prepareTask, SyncConflictError, withinLimit } from './caldav';
+import { createLogger } from './logger';
+import type { SyncBackend } from './syncBackend';
+
+const log = createLogger('LocalFolder', '#22c55e');
+
+interface FolderCalendar {
+  path: string;
+  displayName: string;
+  color: string | null;
+}
+
+interface FolderObject {
+  href: string;
+  etag: string | null;
+  data: string | null;
+  tooLarge: boolean;
+}
+
+function toCalendar(accountId: string, folder: FolderCalendar): Calendar {
+  return {
+    id: folder.path,
+    displayName: folder.displayName,
+    url: folder.path,
+    color: folder.color ?? undefined,
+    accountId,
+    supportedComponents: ['VTODO'],
+  };
+}
+
+/**
+ * accounts without a server: a folder on disk with a subfolder per list and an .ics file per
+ * task, for keeping tasks in Syncthing, git or anything else that syncs files. the backend
+ * watches the folders and emits `local-folder-changed` when another program changes them
+ */
+class LocalFolderBackend implements SyncBackend {
+  // the folder of each connected account
+  private roots: Map<string, string> = new Map();
+  // why the last write or deletion of a task failed, by uid, for the pending changes
+  private failures: Map<string, string> = new Map();
+
+  takeFailure(uid: string): string | undefined {
+    const failure = this.failures.get(uid);
+    this.failures.delete(uid);
+    return failure;
+  }
+
+  /**
+   * connect an account to its folder, which has to exist
+   */
+  async connect(accountId: string, folder: string): Promise<void> {
+    // fails when the folder can't be read
+    await invoke<FolderCalendar[]>('list_folder_calendars', { root: folder });
+    this.roots.set(accountId, folder);
+    log.info(`Connected account ${accountId} to ${folder}`);
+  }
+
+  async reconnect(account: Account): Promise<void> {
+    if (!account.serverUrl) throw new Error('The account has no folder');
+    await this.connect(account.id, account.serverUrl);
+  }
+
+  isConnected(accountId: string): boolean {
+    return this.roots.has(accountId);
+  }
+
+  disconnect(accountId: string): void {
+    this.roots.delete(accountId);
+  }
+
+  async refreshBearerTokens(): Promise<void> {
+    // nothing expires
+  }
+
+  private root(accountId: string): string {
+    const root = this.roots.get(accountId);
+    if (!root) throw new Error('Account not connected');
+    return root;
+  }
+
+  /**
+   * the lists in the folder; an empty folder gets a first one so there's somewhere to put tasks
+   */
+  async fetchCalendars(accountId: string): Promise<Calendar[]> {
+    const root = this.root(accountId);
+    const folders = await invoke<FolderCalendar[]>('list_folder_calendars', { root });
+    if (folders.length === 0) {
+      return [await this.createCalendar(accountId, 'Tasks')];
+    }
+    return folders.map((folder) => toCalendar(accountId, folder));
+  }
+
+  async createCalendar(accountId: string, displayName: string, color?: string): Promise<Calendar> {
+    const folder = await invoke<FolderCalendar>('create_folder_calendar', {
+      root: this.root(accountId),
+      displayName,
+      color: color ?? null,
+    });
+    return toCalendar(accountId, folder);
+  }
+
+  async updateCalendar(
+    accountId: string,
+    calendarUrl: string,
+    updates: { displayName?: string; color?: string },
+  ): Promise<{ success: boolean; failedProperties: string[] }> {
+    this.root(accountId);
+    try {
+      await invoke('update_folder_calendar', {
+        path: calendarUrl,
+        displayName: updates.displayName ?? null,
+        color: updates.color ?? null,
+      });
+      return { success: true, failedProperties: [] };
+    } catch (error) {
+      log.error('Failed to update the list folder:', error);
+      return { success: false, failedProperties: Object.keys(updates) };
+    }
+  }
+
+  async deleteCalendar(accountId: string, calendarUrl: string): Promise<boolean> {
+    this.root(accountId);
+    try {
+      await invoke('delete_folder_calendar', { path: calendarUrl });
+      return true;
+    } catch (error) {
+      log.error('Failed to delete the list folder:', error);
+      return false;
+    }
+  }
+
+  /**
+   * every task file in the list's folder; files that are unchanged since the last sync keep
+   * their synced copy instead of being parsed again
+   */
+  async fetchTasks(
+    accountId: string,
+    calendar: Calendar,
+    localTasks: Task[] = [],
+  ): Promise<Task[]> {
+    this.root(accountId);
+    const localByHref = new Map(
+      localTasks.filter((t) => t.href && t.synced).map((t) => [t.href as string, t]),
+    );
+    const objects = await invoke<FolderObject[]>('read_folder_objects', { path: calendar.url });
+
+    const parsed: Task[] = [];
+    const reused: Task[] = [];
+    for (const object of objects) {
+      const local = localByHref.get(object.href);
+      if (object.tooLarge || object.data === null) {
+        // the local copy stays as it is, otherwise the task isn't shown
+        if (local) reused.push(local);
+        log.warn(`Skipped ${object.href}, it's over the size limit`);
+        continue;
+      }
+      if (local && local.etag === object.etag) {
+        reused.push(local);
+        continue;
+      }
+      try {
+        const task = vtodoToTask(
+          object.data,
+          accountId,
+          calendar.id,
+          object.href,
+          object.etag ?? undefined,
+        );
+        if (task) parsed.push(task);
+      } catch (error) {
+        log.error(`Failed to parse ${object.href}:`, error);
+      }
+    }
+
+    log.debug(`Parsed ${parsed.length} files, reused ${reused.length} unchanged tasks`);
+    return [...reused, ...(await decryptTasks(accountId, parsed))];
+  }
+
+  async createTask(
+    accountId: string,
+    calendar: Calendar,
+    task: Task,
+  ): Promise<{ href: string; etag: string } | null> {
+    this.root(accountId);
+    try {
+      const ics = await withinLimit(taskToVTodo(await prepareTask(accountId, task)));
+      return await invoke<{ href: string; etag: string }>('create_folder_object', {
+        calendar: calendar.url,
+        uid: task.uid,
+        ics,
+      });
+    } catch (error) {
+      log.error('Error writing task:', error);
+      this.failures.set(task.uid, String(error));
+      return null;
+    }
+  }
+
+  /**
+   * write a changed task; throws a SyncConflictError when another program changed or removed
+   * the file since the last sync
+   */
+  async updateTask(accountId: string, task: Task): Promise<{ etag: string } | null> {
+    this.root(accountId);
+    if (!task.href) {
+      log.error('Task has no href for update');
+      return null;
+    }
+
+    let etag: string | null;
+    try {
+      const ics = await withinLimit(taskToVTodo(await prepareTask(accountId, task)));
+      etag = await invoke<string | null>('update_folder_object', {
+        href: task.href,
+        ics,
+        etag: task.etag ?? null,
+      });
+    } catch (error) {
+      log.error('Error writing task:', error);
+      this.failures.set(task.uid, String(error));
+      return null;
+    }
+    if (etag === null) throw new SyncConflictError(task.uid);
+    return { etag };
+  }
+
+  async deleteTask(accountId: string, task: Task): Promise<boolean> {
+    this.root(accountId);
+    if (!task.href) return true;
+    try {
+      const deleted = await invoke<boolean>('delete_folder_object', {
+        href: task.href,
+        etag: task.etag ?? null,
+      });
+      if (!deleted) {
+        this.failures.set(task.uid, 'The file was changed by another program');
+      }
+      return deleted;
+    } catch (error) {
+      log.error('Error deleting task:', error);
+      if (task.uid) this.failures.set(task.uid, String(error));
+      return false;
+    }
+  }
+
+  async deleteChecklistItems(): Promise<void> {
+    // checklist items are always written into their task's file
+  }
+}
+
+export const localFolderBackend = new LocalFolderBackend();
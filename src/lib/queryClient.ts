@@ -30,7 +30,102 @@ export const queryKeys = {
     all: ['tags'] as const,
     byId: (id: string) => ['tags', id] as const,
   },
+  accountEncryption: (id: string) => ['accountEncryption', id] as const,
+  accountHealth: ['accountHealth'] as const,
+  accountTls: (id: string) => ['accountTls', id] as const,
+  actionableTasks: ['actionableTasks'] as const,
+  agenda: {
+    all: ['agenda'] as const,
+    byRange: (range: string) => ['agenda', range] as const,
+  },
+  apiServer: ['apiServer'] as const,
+  appLock: ['appLock'] as const,
+  archivePolicy: ['archivePolicy'] as const,
+  archivedTasks: {
+    all: ['archivedTasks'] as const,
+    bySearch: (search: string) => ['archivedTasks', search] as const,
+  },
+  contexts: ['contexts'] as const,
+  dependencies: {
+    all: ['dependencies'] as const,
+    byUid: (uid: string) => ['dependencies', uid] as const,
+  },
+  readOnly: ['readOnly'] as const,
+  emailDigest: {
+    settings: ['emailDigest', 'settings'] as const,
+    status: ['emailDigest', 'status'] as const,
+  },
+  emailToTask: {
+    settings: ['emailToTask', 'settings'] as const,
+    status: ['emailToTask', 'status'] as const,
+  },
+  fetchLinkTitles: ['fetchLinkTitles'] as const,
+  fuzzyFind: (query: string, limit?: number) => ['fuzzyFind', query, limit] as const,
+  globalSearch: (query: string) => ['globalSearch', query] as const,
+  httpActions: {
+    all: ['httpActions'] as const,
+    runs: (actionId: string) => ['httpActions', 'runs', actionId] as const,
+  },
+  icsFeed: ['icsFeed'] as const,
+  kanbanBoard: {
+    all: ['kanbanBoard'] as const,
+    byCalendar: (calendarId?: string) => ['kanbanBoard', calendarId] as const,
+  },
+  language: ['language'] as const,
+  localeSettings: ['localeSettings'] as const,
+  logLevel: ['logLevel'] as const,
+  mcpEnabled: ['mcpEnabled'] as const,
+  nextActions: {
+    all: ['nextActions'] as const,
+    byContext: (context?: string) => ['nextActions', context] as const,
+  },
+  oauthStatus: ['oauthStatus'] as const,
+  occurrences: (uid: string, start?: Date, due?: Date) =>
+    ['occurrences', uid, start?.valueOf(), due?.valueOf()] as const,
+  pendingChanges: ['pendingChanges'] as const,
   pendingDeletions: ['pendingDeletions'] as const,
+  plannedTime: {
+    all: ['plannedTime'] as const,
+    byRange: (groupBy: string, from?: string, to?: string) =>
+      ['plannedTime', groupBy, from, to] as const,
+  },
+  quickAddDraft: (text: string) => ['quickAddDraft', text] as const,
+  rules: ['rules'] as const,
+  runningTimer: ['runningTimer'] as const,
+  scripts: ['scripts'] as const,
+  searchQueryError: (query: string) => ['searchQueryError', query] as const,
+  stats: {
+    all: ['stats'] as const,
+    completion: (groupBy: string, from: string, to: string) =>
+      ['stats', 'completion', groupBy, from, to] as const,
+    overdue: (from: string, to: string) => ['stats', 'overdue', from, to] as const,
+  },
+  streakInTray: ['streakInTray'] as const,
+  streaks: ['streaks'] as const,
+  syncConflicts: ['syncConflicts'] as const,
+  syncSchedules: ['syncSchedules'] as const,
+  taskNag: (uid: string) => ['taskNag', uid] as const,
+  timeEntries: {
+    all: ['timeEntries'] as const,
+    byTask: (taskUid: string) => ['timeEntries', taskUid] as const,
+  },
+  timeTotals: {
+    all: ['timeTotals'] as const,
+    byRange: (groupBy: string, from?: string, to?: string) =>
+      ['timeTotals', groupBy, from, to] as const,
+  },
+  traySupported: ['traySupported'] as const,
+  unreachableServers: ['unreachableServers'] as const,
+  webhooks: {
+    all: ['webhooks'] as const,
+    deliveries: (webhookId: string) => ['webhooks', 'deliveries', webhookId] as const,
+  },
+  windowEffect: ['windowEffect'] as const,
+  wireTrace: {
+    all: ['wireTrace'] as const,
+    enabled: ['wireTrace', 'enabled'] as const,
+    entries: ['wireTrace', 'entries'] as const,
+  },
   ui: {
     activeCalendar: ['ui', 'activeCalendar'] as const,
     activeTag: ['ui', 'activeTag'] as const,
@@ -0,0 +1,75 @@
+import type { Account, Calendar, SyncBackendType, Task } from '@/types';
+import { caldavService } from './caldav';
+import { localFolderBackend } from './localFolder';
+
+/**
+ * what the sync engine needs from a server: its calendars, the tasks in them and uploading
+ * changes back. the engine only talks to accounts through this, so another kind of server
+ * (a folder on disk, a plain WebDAV folder, EteSync, ...) is a new implementation registered in BACKENDS,
+ * without touching the task logic
+ */
+export interface SyncBackend {
+  /** set up the connection to the account's server from its stored details */
+  reconnect(account: Account): Promise<void>;
+  isConnected(accountId: string): boolean;
+  disconnect(accountId: string): void;
+  /** renew credentials that expire, for every connected account */
+  refreshBearerTokens(): Promise<void>;
+
+  fetchCalendars(accountId: string): Promise<Calendar[]>;
+  createCalendar(accountId: string, displayName: string, color?: string): Promise<Calendar>;
+  updateCalendar(
+    accountId: string,
+    calendarUrl: string,
+    updates: { displayName?: string; color?: string },
+  ): Promise<{ success: boolean; failedProperties: string[] }>;
+  deleteCalendar(accountId: string, calendarUrl: string): Promise<boolean>;
+
+  /**
+   * every task in the calendar; `localTasks` are the synced copies, whose unchanged tasks
+   * the backend may reuse instead of downloading them again
+   */
+  fetchTasks(accountId: string, calendar: Calendar, localTasks?: Task[]): Promise<Task[]>;
+  /** upload a new task, null when it failed (see takeFailure) */
+  createTask(
+    accountId: string,
+    calendar: Calendar,
+    task: Task,
+  ): Promise<{ href: string; etag: string } | null>;
+  /**
+   * upload a changed task, null when it failed (see takeFailure). throws a SyncConflictError
+   * when the server's copy changed since the last sync
+   */
+  updateTask(accountId: string, task: Task, calendar?: Calendar): Promise<{ etag: string } | null>;
+  /** remove a task from the server; one that's already gone counts as deleted */
+  deleteTask(accountId: string, task: Task): Promise<boolean>;
+  /** remove what the backend keeps of checklist items that are gone from their task */
+  deleteChecklistItems(
+    accountId: string,
+    calendar: Calendar,
+    parentUid: string,
+    itemIds: string[],
+  ): Promise<void>;
+
+  /** why the last upload or deletion of the task failed, once */
+  takeFailure(uid: string): string | undefined;
+}
+
+const BACKENDS: Record<SyncBackendType, SyncBackend> = {
+  caldav: caldavService,
+  localFolder: localFolderBackend,
+};
+
+/**
+ * the backend an account syncs through; accounts from before there was a choice are CalDAV
+ */
+export function syncBackendFor(account: Pick<Account, 'backend'> | undefined): SyncBackend {
+  return BACKENDS[account?.backend ?? 'caldav'] ?? caldavService;
+}
+
+/**
+ * every backend, for work that spans all accounts
+ */
+export function allSyncBackends(): SyncBackend[] {
+  return [...new Set(Object.values(BACKENDS))];
+}
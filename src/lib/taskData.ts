@@ -4,18 +4,23 @@
  * Uses SQLite via Tauri SQL plugin with in-memory cache for synchronous access
  */
 
+import { listen } from '@tauri-apps/api/event';
+import { getCurrentWindow } from '@tauri-apps/api/window';
 import { v4 as uuidv4 } from 'uuid';
 import { useSettingsStore } from '@/store/settingsStore';
 import type {
   Account,
   Calendar,
   Priority,
+  RelativeTrigger,
   Reminder,
   SortConfig,
   Subtask,
   Tag,
   Task,
+  TimeEntry,
 } from '@/types';
+import { reminderTrigger } from '@/utils/date';
 import { toAppleEpoch } from '@/utils/ical';
 import * as db from './database';
 import { loggers } from './logger';
@@ -106,11 +111,62 @@ export async function initializeDataStore(): Promise<void> {
     await refreshCache();
     isInitialized = true;
     log.info('Data store initialized with SQLite');
+
+    // the backend wrote to the database directly (e.g. demo data), reload the cache
+    await listen('database-changed', async () => {
+      await refreshCache();
+      notifyListeners();
+    });
+
+    // another window of the app wrote to the database through its own connection
+    const windowLabel = getCurrentWindow().label;
+    await listen<string>('window-data-written', async (event) => {
+      if (event.payload === windowLabel) return;
+      await refreshCache();
+      notifyListeners();
+    });
+
+    // backend commands report the tasks they touched, patch just those into the cache
+    await listen<TasksChangedEvent>('tasks-changed', (event) => {
+      applyTaskChanges(event.payload);
+    });
   })();
 
   return initPromise;
 }
 
+// tasks created, updated or deleted by a backend command (dates still iso strings)
+interface TasksChangedEvent {
+  created: unknown[];
+  updated: unknown[];
+  deleted: string[];
+}
+
+function applyTaskChanges(changes: TasksChangedEvent): void {
+  const data = loadDataStore();
+  const changed = new Map<string, Task>();
+  for (const task of [...changes.created, ...changes.updated].map(db.backendToTask)) {
+    changed.set(task.id, task);
+  }
+  const deleted = new Set(changes.deleted);
+
+  const tasks = data.tasks
+    .filter((task) => !deleted.has(task.id))
+    .map((task) => {
+      const update = changed.get(task.id);
+      if (!update) return task;
+      changed.delete(task.id);
+      return update;
+    });
+  // whatever is left wasn't in the cache yet
+  tasks.push(...changed.values());
+
+  log.debug(
+    `Applied task changes: ${changes.created.length} created, ${changes.updated.length} updated, ${deleted.size} deleted`,
+  );
+  saveDataStore({ ...data, tasks });
+}
+
 // Refresh cache from database
 async function refreshCache(): Promise<void> {
   try {
@@ -184,6 +240,11 @@ export function getAllDescendants(parentUid: string): Task[] {
   return getDescendants(parentUid);
 }
 
+// tasks of a paused account stay as they are until it's active again
+function isPaused(data: DataStore, accountId: string | undefined): boolean {
+  return !!accountId && data.accounts.some((a) => a.id === accountId && !a.isActive);
+}
+
 // biome-ignore lint/complexity/noExcessiveCognitiveComplexity: complexity is acceptable. it'd be hard to reduce this even further
 export function createTask(taskData: Partial<Task>): Task {
   const data = loadDataStore();
@@ -230,6 +291,10 @@ export function createTask(taskData: Partial<Task>): Task {
     }
   }
 
+  if (isPaused(data, accountId ?? undefined)) {
+    throw new Error('The account is paused');
+  }
+
   // Determine if this is a local-only task (no calendar/account assigned)
   const isLocalOnly = !calendarId || !accountId;
 
@@ -242,7 +307,7 @@ export function createTask(taskData: Partial<Task>): Task {
   const task: Task = {
     id: uuidv4(),
     uid: uuidv4(),
-    title: taskData.title || 'New Task',
+    title: (taskData.title || 'New Task').normalize('NFC'),
     description: taskData.description || '',
     completed: false,
     priority: taskData.priority || defaultPriority,
@@ -266,19 +331,90 @@ export function createTask(taskData: Partial<Task>): Task {
 
   // Persist to SQLite (including local-only tasks)
   if (isInitialized) {
-    db.createTask(task).catch((e) => log.error('Failed to sync task to database:', e));
+    db.createTask(task)
+      .then(() => db.applyRules([task.id], 'created'))
+      .catch((e) => log.error('Failed to sync task to database:', e));
   }
 
   return task;
 }
 
+// Insert or replace complete tasks in one go (used by sync for large batches)
+export async function upsertTasks(tasks: Task[]): Promise<void> {
+  if (tasks.length === 0) return;
+  const data = loadDataStore();
+  const incoming = new Map(tasks.map((task) => [task.id, task]));
+
+  const merged = data.tasks.map((task) => {
+    const replacement = incoming.get(task.id);
+    if (!replacement) return task;
+    incoming.delete(task.id);
+    return replacement;
+  });
+  saveDataStore({ ...data, tasks: [...merged, ...incoming.values()] });
+
+  await db.upsertTasks(tasks);
+}
+
+// Stop the running timer; when tracked time is synced the task is marked for upload so the
+// new entry reaches the server
+export async function stopTimer(): Promise<TimeEntry | null> {
+  const entry = await db.stopTimer();
+  if (entry?.taskUid && useSettingsStore.getState().syncTrackedTime) {
+    const task = getTaskByUid(entry.taskUid);
+    if (task) updateTask(task.id, {});
+  }
+  return entry;
+}
+
+// Move a task (with its sub-tasks) to another calendar; the backend queues the server copy
+// for deletion and reloads the cache through the database-changed event
+export async function moveTask(id: string, calendarId: string): Promise<number> {
+  return db.moveTasks([id], calendarId);
+}
+
+// Apply one operation to many tasks at once; the backend reports the changes through the
+// tasks-changed event (or database-changed for deletions), so the cache isn't touched here
+export async function bulkUpdate(ids: string[], op: db.BulkOperation): Promise<number> {
+  if (ids.length === 0) return 0;
+  return db.bulkUpdate(ids, op);
+}
+
+// Merge duplicates into one task; the backend reloads the cache through the
+// database-changed event
+export async function mergeTasks(primaryId: string, duplicateIds: string[]): Promise<Task> {
+  return db.mergeTasks(primaryId, duplicateIds);
+}
+
+// Duplicate a task tree in the backend; the copies arrive through the tasks-changed event
+export async function duplicateTask(
+  id: string,
+  includeSubtasks: boolean = true,
+  offsetDays?: number,
+): Promise<Task | undefined> {
+  const task = getTaskById(id);
+  if (!task) return undefined;
+  const [copy] = await db.duplicateTask(task.uid, includeSubtasks, offsetDays);
+  return copy;
+}
+
 export function updateTask(id: string, updates: Partial<Task>): Task | undefined {
   const data = loadDataStore();
   let updatedTask: Task | undefined;
 
+  const existing = data.tasks.find((task) => task.id === id);
+  if (existing && isPaused(data, existing.accountId)) {
+    log.warn(`Not updating task ${id}: its account is paused`);
+    return undefined;
+  }
+
+  if (updates.title !== undefined) {
+    updates = { ...updates, title: updates.title.normalize('NFC') };
+  }
+
   const tasks = data.tasks.map((task) => {
     if (task.id === id) {
-      updatedTask = {
+      const merged: Task = {
         ...task,
         ...updates,
         // Only update modifiedAt if not provided in updates (local changes)
@@ -286,6 +422,13 @@ export function updateTask(id: string, updates: Partial<Task>): Task | undefined
         // Only mark as unsynced if synced is not explicitly set in updates
         synced: updates.synced !== undefined ? updates.synced : false,
       };
+      // reminders related to the start or due date move with it
+      if (merged.reminders?.some((r) => r.related)) {
+        merged.reminders = merged.reminders.map((r) =>
+          r.related ? { ...r, trigger: reminderTrigger(merged, r) } : r,
+        );
+      }
+      updatedTask = merged;
       return updatedTask;
     }
     return task;
@@ -293,7 +436,11 @@ export function updateTask(id: string, updates: Partial<Task>): Task | undefined
 
   // Persist to SQLite
   if (updatedTask) {
-    db.updateTask(id, updatedTask).catch((e) => log.error('Failed to persist task update:', e));
+    // rules only run on edits made here, not on sync bookkeeping that sets synced itself
+    const edited = updates.synced === undefined;
+    db.updateTask(id, updatedTask)
+      .then(() => (edited ? db.applyRules([id], 'updated') : 0))
+      .catch((e) => log.error('Failed to persist task update:', e));
   }
 
   saveDataStore({ ...data, tasks });
@@ -303,7 +450,7 @@ export function updateTask(id: string, updates: Partial<Task>): Task | undefined
 export function deleteTask(id: string, deleteChildren: boolean = true): void {
   const data = loadDataStore();
   const task = data.tasks.find((t) => t.id === id);
-  if (!task) return;
+  if (!task || isPaused(data, task.accountId)) return;
 
   // Persist to SQLite
   db.deleteTask(id, deleteChildren).catch((e) => log.error('Failed to persist task deletion:', e));
@@ -358,7 +505,7 @@ export function deleteTask(id: string, deleteChildren: boolean = true): void {
 export function toggleTaskComplete(id: string): void {
   const data = loadDataStore();
   const task = data.tasks.find((t) => t.id === id);
-  if (!task) return;
+  if (!task || isPaused(data, task.accountId)) return;
 
   const updates = {
     completed: !task.completed,
@@ -367,8 +514,10 @@ export function toggleTaskComplete(id: string): void {
     synced: false,
   };
 
-  // Persist to SQLite
-  db.updateTask(id, updates).catch((e) => log.error('Failed to persist task toggle:', e));
+  // Persist to SQLite; the backend reports tasks this unblocks with a tasks-unblocked event
+  db.completeTask(id, updates.completed).catch((e) =>
+    log.error('Failed to persist task toggle:', e),
+  );
 
   const tasks = data.tasks.map((t) => (t.id === id ? { ...t, ...updates } : t));
   saveDataStore({ ...data, tasks });
@@ -561,11 +710,12 @@ export function removeTagFromTask(taskId: string, tagId: string): void {
 }
 
 // Reminder operations
-export function addReminder(taskId: string, trigger: Date): void {
+export function addReminder(taskId: string, trigger: Date, relative?: RelativeTrigger): void {
   const data = loadDataStore();
   const reminder: Reminder = {
     id: uuidv4(),
     trigger,
+    ...relative,
   };
   const tasks = data.tasks.map((task) =>
     task.id === taskId
@@ -595,14 +745,21 @@ export function removeReminder(taskId: string, reminderId: string): void {
   saveDataStore({ ...data, tasks });
 }
 
-export function updateReminder(taskId: string, reminderId: string, trigger: Date): void {
+export function updateReminder(
+  taskId: string,
+  reminderId: string,
+  trigger: Date,
+  relative?: RelativeTrigger,
+): void {
   const data = loadDataStore();
   const tasks = data.tasks.map((task) =>
     task.id === taskId
       ? {
           ...task,
           reminders: (task.reminders || []).map((r) =>
-            r.id === reminderId ? { ...r, trigger } : r,
+            r.id === reminderId
+              ? { ...r, trigger, related: relative?.related, offset: relative?.offset }
+              : r,
           ),
           modifiedAt: new Date(),
           synced: false,
@@ -624,12 +781,12 @@ interface FlattenedTask {
 }
 
 // biome-ignore lint/complexity/noExcessiveCognitiveComplexity: while this function is long, it does its job well
-export function reorderTasks(
+export async function reorderTasks(
   activeId: string,
   overId: string,
   flattenedItems: FlattenedTask[],
   targetIndent?: number,
-): void {
+): Promise<void> {
   const data = loadDataStore();
   const tasks = data.tasks;
   const activeTask = tasks.find((t) => t.id === activeId);
@@ -761,89 +918,33 @@ export function reorderTasks(
   const newOrder = [...sortedSiblings];
   newOrder.splice(Math.min(insertIndex, newOrder.length), 0, activeTask);
 
-  // If the task is becoming a child of another task, inherit the parent's calendar
-  let inheritedCalendarId: string | undefined;
-  let inheritedAccountId: string | undefined;
-
-  if (newParentUid && newParentUid !== activeTask.parentUid) {
-    const parentTask = tasks.find((t) => t.uid === newParentUid);
-    if (parentTask && parentTask.calendarId !== activeTask.calendarId) {
-      inheritedCalendarId = parentTask.calendarId;
-      inheritedAccountId = parentTask.accountId;
-    }
+  // the backend writes all positions in one transaction, keeping existing sort orders where
+  // it can, and moves a re-parented task (with its sub-tasks) into the parent's calendar
+  const orderedIds = newOrder.map((task) => task.id);
+  if (data.ui.sortConfig.direction === 'desc') {
+    orderedIds.reverse();
   }
+  const positions = await db.reorderTasks(orderedIds, newParentUid);
+  const changed = new Map(positions.map((position) => [position.id, position]));
+  const modifiedAt = new Date();
 
-  // Assign sort orders with consistent gaps
-  const updates: Map<
-    string,
-    { sortOrder: number; parentUid: string | undefined; calendarId?: string; accountId?: string }
-  > = new Map();
-
-  newOrder.forEach((task, index) => {
-    const newSortOrder = (index + 1) * 100;
-    const updateData: {
-      sortOrder: number;
-      parentUid: string | undefined;
-      calendarId?: string;
-      accountId?: string;
-    } = {
-      sortOrder: newSortOrder,
-      parentUid: task.id === activeId ? newParentUid : task.parentUid,
-    };
-
-    if (task.id === activeId && inheritedCalendarId) {
-      updateData.calendarId = inheritedCalendarId;
-      updateData.accountId = inheritedAccountId;
-    }
-
-    updates.set(task.id, updateData);
-  });
-
-  // Also update all descendants of the moved task to inherit the new calendar
-  const getAllDescendantIds = (parentUid: string): string[] => {
-    const children = tasks.filter((t) => t.parentUid === parentUid);
-    return children.flatMap((c) => [c.id, ...getAllDescendantIds(c.uid)]);
-  };
-
-  if (inheritedCalendarId) {
-    const descendantIds = getAllDescendantIds(activeTask.uid);
-    descendantIds.forEach((id) => {
-      const existing = updates.get(id);
-      if (existing) {
-        existing.calendarId = inheritedCalendarId;
-        existing.accountId = inheritedAccountId;
-      } else {
-        const task = tasks.find((t) => t.id === id);
-        if (task) {
-          updates.set(id, {
-            sortOrder: task.sortOrder,
-            parentUid: task.parentUid,
-            calendarId: inheritedCalendarId,
-            accountId: inheritedAccountId,
-          });
-        }
-      }
-    });
-  }
-
-  // Apply all updates
-  const updatedTasks = tasks.map((task) => {
-    const update = updates.get(task.id);
-    if (update) {
+  const current = loadDataStore();
+  saveDataStore({
+    ...current,
+    tasks: current.tasks.map((task) => {
+      const position = changed.get(task.id);
+      if (!position) return task;
       return {
         ...task,
-        sortOrder: update.sortOrder,
-        parentUid: update.parentUid,
-        ...(update.calendarId && { calendarId: update.calendarId }),
-        ...(update.accountId && { accountId: update.accountId }),
+        sortOrder: position.sortOrder,
+        parentUid: position.parentUid ?? undefined,
+        calendarId: position.calendarId ?? task.calendarId,
+        accountId: position.accountId ?? task.accountId,
         synced: false,
-        modifiedAt: new Date(),
+        modifiedAt,
       };
-    }
-    return task;
+    }),
   });
-
-  saveDataStore({ ...data, tasks: updatedTasks });
 }
 
 // Tag operations
@@ -864,7 +965,7 @@ export function createTag(tagData: Partial<Tag>): Tag {
   const data = loadDataStore();
   const tag: Tag = {
     id: uuidv4(),
-    name: tagData.name ?? 'New Tag',
+    name: (tagData.name ?? 'New Tag').normalize('NFC'),
     color: tagData.color ?? '#3b82f6',
     icon: tagData.icon,
   };
@@ -879,6 +980,9 @@ export function createTag(tagData: Partial<Tag>): Tag {
 export function updateTag(id: string, updates: Partial<Tag>): Tag | undefined {
   const data = loadDataStore();
   let updatedTag: Tag | undefined;
+  if (updates.name !== undefined) {
+    updates = { ...updates, name: updates.name.normalize('NFC') };
+  }
 
   const tags = data.tags.map((tag) => {
     if (tag.id === id) {
@@ -935,8 +1039,10 @@ export function createAccount(accountData: Partial<Account>): Account {
     username: accountData.username || '',
     password: accountData.password || '',
     serverType: accountData.serverType,
+    backend: accountData.backend ?? 'caldav',
     calendars: [],
     isActive: true,
+    collapsed: !useSettingsStore.getState().defaultAccountsExpanded,
   };
 
   // Persist to SQLite
@@ -950,6 +1056,20 @@ export function createAccount(accountData: Partial<Account>): Account {
   return account;
 }
 
+// show or hide an account's calendars in the sidebar
+export function setAccountCollapsed(id: string, collapsed: boolean): void {
+  const data = loadDataStore();
+
+  db.setAccountCollapsed(id, collapsed).catch((e) =>
+    log.error('Failed to persist account collapse state:', e),
+  );
+
+  saveDataStore({
+    ...data,
+    accounts: data.accounts.map((acc) => (acc.id === id ? { ...acc, collapsed } : acc)),
+  });
+}
+
 export function updateAccount(id: string, updates: Partial<Account>): Account | undefined {
   const data = loadDataStore();
   let updatedAccount: Account | undefined;
@@ -1002,6 +1122,24 @@ export function deleteAccount(id: string): void {
   });
 }
 
+// Remove an account but keep its tasks as local tasks in the Recovered list; the cache is
+// reloaded once the backend moved them and the account is gone
+export async function deleteAccountKeepingTasks(id: string): Promise<number> {
+  const kept = await db.recoverAccountTasks(id);
+  await db.deleteAccount(id);
+  await refreshCache();
+
+  // the active calendar may have been one of the account's
+  const data = loadDataStore();
+  const calendarIds = new Set(data.accounts.flatMap((a) => a.calendars.map((c) => c.id)));
+  if (data.ui.activeCalendarId && !calendarIds.has(data.ui.activeCalendarId)) {
+    setAllTasksView();
+  } else {
+    notifyListeners();
+  }
+  return kept;
+}
+
 export function addCalendar(accountId: string, calendarData: Partial<Calendar>): void {
   const data = loadDataStore();
   const calendar: Calendar = {
@@ -1026,7 +1164,7 @@ export function addCalendar(accountId: string, calendarData: Partial<Calendar>):
   if (isFirstCalendar) {
     updatedTasks = data.tasks.map((task) => {
       if (task.localOnly || !task.calendarId || !task.accountId) {
-        log.info(`Assigning local-only task "${task.title}" to calendar: ${calendar.displayName}`);
+        log.info(`Assigning local-only task ${task.id} to calendar: ${calendar.displayName}`);
 
         const updatedTask = {
           ...task,
@@ -1277,6 +1415,10 @@ const priorityOrder: Record<Priority, number> = {
 export function getFilteredTasks(): Task[] {
   const data = loadDataStore();
   const { searchQuery, showCompletedTasks, activeCalendarId, activeTagId } = data.ui;
+  // archived calendars only show in their own view
+  const archived = new Set(
+    data.accounts.flatMap((a) => a.calendars.filter((c) => c.archived).map((c) => c.id)),
+  );
 
   return data.tasks.filter((task) => {
     // Filter by tag
@@ -1290,6 +1432,9 @@ export function getFilteredTasks(): Task[] {
         return false;
       }
     }
+    if (task.calendarId !== activeCalendarId && archived.has(task.calendarId)) {
+      return false;
+    }
 
     // Filter by completion status
     if (!showCompletedTasks && task.completed) {
@@ -1338,7 +1483,11 @@ export function getSortedTasks(tasks: Task[], sortConfig?: SortConfig): Task[] {
         return (priorityOrder[a.priority] - priorityOrder[b.priority]) * multiplier;
 
       case 'title':
-        return a.title.localeCompare(b.title) * multiplier;
+        // same natural order as the backend: "Task 2" before "Task 10"
+        return (
+          a.title.localeCompare(b.title, undefined, { numeric: true, sensitivity: 'base' }) *
+          multiplier
+        );
 
       case 'modified':
         return (new Date(b.modifiedAt).getTime() - new Date(a.modifiedAt).getTime()) * multiplier;
@@ -1,19 +1,102 @@
-import { fetch as tauriFetch } from '@tauri-apps/plugin-http';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import type { TlsSettings } from '@/types';
 import { createLogger } from './logger';
 
 const log = createLogger('HTTP', '#6366f1');
 
+// wire trace toggle, mirrored from the backend so untraced requests cost nothing
+let wireTraceEnabled = false;
+invoke<boolean>('get_wire_trace_enabled')
+  .then((enabled) => {
+    wireTraceEnabled = enabled;
+  })
+  .catch(() => {});
+listen<boolean>('wire-trace-changed', (event) => {
+  wireTraceEnabled = event.payload;
+}).catch(() => {});
+
 export interface HttpResponse {
   status: number;
   headers: Record<string, string>;
   body: string;
 }
 
+export type AuthMethod = 'basic' | 'digest' | 'bearer';
+
 export interface CalDAVCredentials {
   username: string;
   password: string;
   /** OAuth Bearer token - if provided, uses Bearer auth instead of Basic */
   bearerToken?: string;
+  /** the method the server accepted last; renegotiated when it answers 401 with another */
+  authMethod?: AuthMethod;
+  /** the server's latest Digest challenge (its WWW-Authenticate value) and its use count */
+  digestChallenge?: string;
+  digestCount?: number;
+  /** called when the server made the client switch methods, to remember the new one */
+  onAuthMethodChange?: (method: AuthMethod) => void;
+  /** the account's minimum TLS version and pinned certificate, the defaults if unset */
+  tls?: TlsSettings;
+}
+
+function currentAuthMethod(credentials: CalDAVCredentials): AuthMethod {
+  return credentials.authMethod ?? (credentials.bearerToken ? 'bearer' : 'basic');
+}
+
+async function authorization(
+  url: string,
+  method: string,
+  credentials: CalDAVCredentials,
+): Promise<string | undefined> {
+  switch (currentAuthMethod(credentials)) {
+    case 'bearer':
+      if (credentials.bearerToken) return `Bearer ${credentials.bearerToken}`;
+      break;
+    case 'digest': {
+      // the first request goes without, the 401 brings the challenge
+      if (!credentials.digestChallenge) return undefined;
+      credentials.digestCount = (credentials.digestCount ?? 0) + 1;
+      const { pathname, search } = new URL(url);
+      return invoke<string>('digest_auth_header', {
+        wwwAuthenticate: credentials.digestChallenge,
+        username: credentials.username,
+        password: credentials.password,
+        method,
+        uri: `${pathname}${search}`,
+        nc: credentials.digestCount,
+      });
+    }
+  }
+  return `Basic ${btoa(`${credentials.username}:${credentials.password}`)}`;
+}
+
+// switch to the method a 401 asks for; false when retrying can't help
+async function renegotiate(
+  credentials: CalDAVCredentials,
+  wwwAuthenticate: string,
+): Promise<boolean> {
+  const current = currentAuthMethod(credentials);
+  const next =
+    current === 'digest' && !credentials.digestChallenge
+      ? 'digest'
+      : await invoke<AuthMethod | null>('negotiate_auth', {
+          wwwAuthenticate,
+          current,
+          hasBearerToken: !!credentials.bearerToken,
+        });
+  if (!next) return false;
+
+  if (next === 'digest') {
+    credentials.digestChallenge = wwwAuthenticate;
+    credentials.digestCount = 0;
+  }
+  if (next !== current) {
+    log.info(`Server asks for ${next} authentication instead of ${current}`);
+    credentials.authMethod = next;
+    credentials.onAuthMethodChange?.(next);
+  }
+  return true;
 }
 
 export async function tauriRequest(
@@ -22,28 +105,37 @@ export async function tauriRequest(
   credentials: CalDAVCredentials,
   body?: string,
   headers?: Record<string, string>,
+  renegotiated = false,
 ): Promise<HttpResponse> {
   log.debug(`${method} ${url}`);
 
-  // use bearer token if provided, otherwise fall back to Basic auth
-  const authHeader = credentials.bearerToken
-    ? `Bearer ${credentials.bearerToken}`
-    : `Basic ${btoa(`${credentials.username}:${credentials.password}`)}`;
-
+  const authHeader = await authorization(url, method, credentials);
   const requestHeaders: Record<string, string> = {
-    Authorization: authHeader,
+    ...(authHeader ? { Authorization: authHeader } : {}),
     'Content-Type': 'application/xml; charset=utf-8',
     ...headers,
   };
 
-  const response = await tauriFetch(url, {
-    method: method,
+  const startedAt = Date.now();
+  // sent by the backend, the http plugin can't apply the account's TLS settings
+  const response = await invoke<HttpResponse>('dav_request', {
+    tls: credentials.tls ?? null,
+    method,
+    url,
     headers: requestHeaders,
-    body: body,
+    body: body ?? null,
   });
 
   log.debug(`Response: ${response.status}`);
 
+  // the server may have switched schemes since the method was remembered
+  const offered = response.headers['www-authenticate'];
+  if (response.status === 401 && offered && !renegotiated) {
+    if (await renegotiate(credentials, offered)) {
+      return tauriRequest(url, method, credentials, body, headers, true);
+    }
+  }
+
   // handle redirects manually for CalDAV
   if (
     response.status === 301 ||
@@ -51,28 +143,31 @@ export async function tauriRequest(
     response.status === 307 ||
     response.status === 308
   ) {
-    const location = response.headers.get('location') || response.headers.get('Location');
+    const location = response.headers.location;
     if (location) {
       // resolve relative URLs
       const redirectUrl = new URL(location, url).toString();
-      return tauriRequest(redirectUrl, method, credentials, body, headers);
+      return tauriRequest(redirectUrl, method, credentials, body, headers, renegotiated);
     }
   }
 
-  // convert response text
-  const responseBody = await response.text();
-
-  // convert Headers to plain object
-  const headersObj: Record<string, string> = {};
-  response.headers.forEach((value, key) => {
-    headersObj[key] = value;
-  });
+  // the backend redacts credentials and caps body sizes before storing anything
+  if (wireTraceEnabled) {
+    invoke('record_wire_trace', {
+      entry: {
+        method,
+        url,
+        requestHeaders,
+        requestBody: body ?? null,
+        status: response.status,
+        responseHeaders: response.headers,
+        responseBody: response.body,
+        durationMs: Date.now() - startedAt,
+      },
+    }).catch(() => {});
+  }
 
-  return {
-    status: response.status,
-    headers: headersObj,
-    body: responseBody,
-  };
+  return response;
 }
 
 /**
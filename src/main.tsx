@@ -3,7 +3,16 @@ import React from 'react';
 import ReactDOM from 'react-dom/client';
 import App from './App';
 import './styles/index.css';
-import { forceShowWindow, initializeApp, showBootstrapError, showWindow } from '@/lib/bootstrap';
+import { CalendarWindow } from '@/components/CalendarWindow';
+import { LockScreen } from '@/components/LockScreen';
+import {
+  forceShowWindow,
+  initializeApp,
+  initializeCalendarWindow,
+  showBootstrapError,
+  showWindow,
+} from '@/lib/bootstrap';
+import { getAppLockStatus } from '@/lib/database';
 import { createLogger } from '@/lib/logger';
 import { queryClient } from '@/lib/queryClient';
 import { ConfirmDialogProvider } from '@/providers/ConfirmDialogProvider';
@@ -25,9 +34,39 @@ function renderApp() {
   );
 }
 
+// a calendar opened in its own window gets a view of just that calendar
+function renderCalendarWindow(calendarId: string) {
+  ReactDOM.createRoot(document.getElementById('root') as HTMLElement).render(
+    <React.StrictMode>
+      <QueryClientProvider client={queryClient}>
+        <CalendarWindow calendarId={calendarId} />
+      </QueryClientProvider>
+    </React.StrictMode>,
+  );
+}
+
+// a locked app shows the lock screen before anything is loaded from the database
+async function unlockFirst(): Promise<void> {
+  const { locked } = await getAppLockStatus();
+  if (!locked) return;
+  const root = ReactDOM.createRoot(document.getElementById('root') as HTMLElement);
+  await new Promise<void>((resolve) => {
+    root.render(<LockScreen onUnlock={resolve} />);
+    showWindow();
+  });
+  root.unmount();
+}
+
 async function bootstrap(): Promise<void> {
-  await initializeApp();
-  renderApp();
+  await unlockFirst();
+  const calendarId = new URLSearchParams(window.location.search).get('calendar');
+  if (calendarId) {
+    await initializeCalendarWindow();
+    renderCalendarWindow(calendarId);
+  } else {
+    await initializeApp();
+    renderApp();
+  }
   await showWindow();
 }
 
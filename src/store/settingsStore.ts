@@ -1,5 +1,6 @@
 import { create } from 'zustand';
 import { persist } from 'zustand/middleware';
+import type { LogLevel } from '@/lib/database';
 import { createLogger } from '@/lib/logger';
 import type { Priority } from '@/types';
 
@@ -22,6 +23,7 @@ export interface KeyboardShortcut {
 export const defaultShortcuts: KeyboardShortcut[] = [
   { id: 'new-task', key: 'n', meta: true, description: 'Create new task' },
   { id: 'search', key: 'f', meta: true, description: 'Focus search' },
+  { id: 'quick-open', key: 'k', meta: true, description: 'Jump to a task' },
   { id: 'settings', key: ',', meta: true, description: 'Open settings' },
   { id: 'sync', key: 'r', meta: true, description: 'Sync with server' },
   { id: 'delete', key: 'Backspace', meta: true, description: 'Delete selected task' },
@@ -38,14 +40,24 @@ export const defaultShortcuts: KeyboardShortcut[] = [
   { id: 'nav-down', key: 'ArrowDown', description: 'Navigate to next task' },
 ];
 
+// custom bindings over the defaults, so descriptions and shortcuts added since stay current
+export function withDefaultShortcuts(custom: KeyboardShortcut[]): KeyboardShortcut[] {
+  return defaultShortcuts.map((shortcut) => {
+    const binding = custom.find((c) => c.id === shortcut.id);
+    return binding ? { ...binding, description: shortcut.description } : shortcut;
+  });
+}
+
 export type SubtaskDeletionBehavior = 'delete' | 'keep';
 
 interface SettingsStore {
   theme: Theme;
   accentColor: AccentColor;
+  systemAccentColor: boolean; // accentColor follows the OS accent color
   autoSync: boolean;
   syncInterval: number; // minutes
   syncOnStartup: boolean;
+  syncTrackedTime: boolean; // upload tracked time with tasks (X-CALDAV-TASKS-TIME)
   showCompletedByDefault: boolean;
   confirmBeforeDelete: boolean;
   confirmBeforeDeleteCalendar: boolean;
@@ -57,6 +69,7 @@ interface SettingsStore {
   defaultCalendarId: string | null; // default calendar for new tasks when in "All Tasks" view
   keyboardShortcuts: KeyboardShortcut[];
   enableSystemTray: boolean; // Whether to show system tray icon (requires restart)
+  logLevel: LogLevel; // how much the backend logs, applied on startup
 
   // Task defaults
   defaultPriority: Priority;
@@ -82,9 +95,11 @@ interface SettingsStore {
   setSidebarCollapsed: (collapsed: boolean) => void;
   setSidebarWidth: (width: number) => void;
   setAccentColor: (color: AccentColor) => void;
+  setSystemAccentColor: (enabled: boolean) => void;
   setAutoSync: (enabled: boolean) => void;
   setSyncInterval: (interval: number) => void;
   setSyncOnStartup: (enabled: boolean) => void;
+  setSyncTrackedTime: (enabled: boolean) => void;
   setShowCompletedByDefault: (show: boolean) => void;
   setConfirmBeforeDelete: (confirm: boolean) => void;
   setConfirmBeforeDeleteCalendar: (confirm: boolean) => void;
@@ -105,6 +120,7 @@ interface SettingsStore {
   toggleAccountExpanded: (accountId: string) => void;
   setDefaultAccountsExpanded: (expanded: boolean) => void;
   setEnableSystemTray: (enabled: boolean) => void;
+  setLogLevel: (level: LogLevel) => void;
   setSystemTrayRestartNeeded: (needed: boolean) => void;
   setSystemTrayAppliedValue: (value: boolean) => void;
   exportSettings: () => string;
@@ -116,9 +132,11 @@ export const useSettingsStore = create<SettingsStore>()(
     (set, get) => ({
       theme: 'system',
       accentColor: '#3b82f6',
+      systemAccentColor: false,
       autoSync: true,
       syncInterval: 5,
       syncOnStartup: true,
+      syncTrackedTime: false,
       showCompletedByDefault: true,
       confirmBeforeDelete: true,
       confirmBeforeDeleteCalendar: true,
@@ -137,6 +155,7 @@ export const useSettingsStore = create<SettingsStore>()(
       expandedAccountIds: [], // Will be populated with account IDs as they're expanded
       defaultAccountsExpanded: true, // New accounts are expanded by default
       enableSystemTray: true, // System tray is enabled by default
+      logLevel: 'debug',
       systemTrayRestartNeeded: false, // Track if restart is needed for system tray changes
       systemTrayAppliedValue: true, // The currently applied system tray value
 
@@ -145,9 +164,11 @@ export const useSettingsStore = create<SettingsStore>()(
       setSidebarWidth: (sidebarWidth) => set({ sidebarWidth }),
       toggleSidebarCollapsed: () => set((state) => ({ sidebarCollapsed: !state.sidebarCollapsed })),
       setAccentColor: (accentColor) => set({ accentColor }),
+      setSystemAccentColor: (systemAccentColor) => set({ systemAccentColor }),
       setAutoSync: (autoSync) => set({ autoSync }),
       setSyncInterval: (syncInterval) => set({ syncInterval }),
       setSyncOnStartup: (syncOnStartup) => set({ syncOnStartup }),
+      setSyncTrackedTime: (syncTrackedTime) => set({ syncTrackedTime }),
       setShowCompletedByDefault: (showCompletedByDefault) => set({ showCompletedByDefault }),
       setConfirmBeforeDelete: (confirmBeforeDelete) => set({ confirmBeforeDelete }),
       setConfirmBeforeDeleteCalendar: (confirmBeforeDeleteCalendar) =>
@@ -181,6 +202,7 @@ export const useSettingsStore = create<SettingsStore>()(
       },
       setDefaultAccountsExpanded: (defaultAccountsExpanded) => set({ defaultAccountsExpanded }),
       setEnableSystemTray: (enableSystemTray) => set({ enableSystemTray }),
+      setLogLevel: (logLevel) => set({ logLevel }),
       setSystemTrayRestartNeeded: (systemTrayRestartNeeded) => set({ systemTrayRestartNeeded }),
       setSystemTrayAppliedValue: (systemTrayAppliedValue) => set({ systemTrayAppliedValue }),
 
@@ -190,9 +212,11 @@ export const useSettingsStore = create<SettingsStore>()(
           version: 1,
           theme: state.theme,
           accentColor: state.accentColor,
+          systemAccentColor: state.systemAccentColor,
           autoSync: state.autoSync,
           syncInterval: state.syncInterval,
           syncOnStartup: state.syncOnStartup,
+          syncTrackedTime: state.syncTrackedTime,
           showCompletedByDefault: state.showCompletedByDefault,
           confirmBeforeDelete: state.confirmBeforeDelete,
           confirmBeforeDeleteCalendar: state.confirmBeforeDeleteCalendar,
@@ -211,6 +235,7 @@ export const useSettingsStore = create<SettingsStore>()(
           expandedAccountIds: state.expandedAccountIds,
           defaultAccountsExpanded: state.defaultAccountsExpanded,
           enableSystemTray: state.enableSystemTray,
+          logLevel: state.logLevel,
           systemTrayRestartNeeded: state.systemTrayRestartNeeded,
           systemTrayAppliedValue: state.systemTrayAppliedValue,
         };
@@ -227,9 +252,11 @@ export const useSettingsStore = create<SettingsStore>()(
           set({
             theme: data.theme ?? 'system',
             accentColor: data.accentColor ?? '#3b82f6',
+            systemAccentColor: data.systemAccentColor ?? false,
             autoSync: data.autoSync ?? true,
             syncInterval: data.syncInterval ?? 5,
             syncOnStartup: data.syncOnStartup ?? true,
+            syncTrackedTime: data.syncTrackedTime ?? false,
             showCompletedByDefault: data.showCompletedByDefault ?? true,
             confirmBeforeDelete: data.confirmBeforeDelete ?? true,
             confirmBeforeDeleteCalendar: data.confirmBeforeDeleteCalendar ?? true,
@@ -248,6 +275,7 @@ export const useSettingsStore = create<SettingsStore>()(
             expandedAccountIds: data.expandedAccountIds ?? [],
             defaultAccountsExpanded: data.defaultAccountsExpanded ?? true,
             enableSystemTray: data.enableSystemTray ?? true,
+            logLevel: data.logLevel ?? 'debug',
             systemTrayRestartNeeded: data.systemTrayRestartNeeded ?? false,
             systemTrayAppliedValue: data.systemTrayAppliedValue ?? true,
           });
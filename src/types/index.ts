@@ -12,6 +12,24 @@ export type SortMode =
 
 export type SortDirection = 'asc' | 'desc';
 
+// what the main area shows: the task list of the sidebar selection, or one of the views
+export type MainView =
+  | 'tasks'
+  | 'agenda'
+  | 'actionable'
+  | 'next'
+  | 'board'
+  | 'time'
+  | 'planned'
+  | 'stats'
+  | 'archive';
+
+export interface Context {
+  name: string; // always starts with '@'
+  color?: string;
+  openTasks: number;
+}
+
 export interface Subtask {
   id: string;
   title: string;
@@ -21,6 +39,24 @@ export interface Subtask {
 export interface Reminder {
   id: string;
   trigger: Date; // absolute date/time when the reminder should fire
+  // a reminder that moves with the start or due date fires `offset` seconds after it
+  // (before it when negative); `trigger` then holds the time worked out from the date
+  related?: RelativeTrigger['related'];
+  offset?: number;
+}
+
+// what a relative reminder fires after, see Reminder
+export interface RelativeTrigger {
+  related: 'start' | 'due';
+  offset: number; // seconds, negative for before the date
+}
+
+// a stretch of time spent on a task (dates stay iso strings, like the backend sends them)
+export interface TimeEntry {
+  id: string;
+  taskUid?: string;
+  startedAt: string;
+  endedAt?: string | null; // missing while the timer runs
 }
 
 export interface Task {
@@ -28,6 +64,12 @@ export interface Task {
   uid: string; // CalDAV UID
   etag?: string; // CalDAV ETag for sync
   href?: string; // CalDAV href
+  // id of the last change uploaded from here (X-CALDAV-TASKS-CHANGE), to recognize that upload
+  // when the next sync brings it back
+  changeId?: string;
+  // title and description are end-to-end encrypted (X-CALDAV-TASKS-E2E); only set between
+  // encrypting for an upload, or parsing, and decrypting, never on stored tasks
+  encrypted?: boolean;
 
   // core fields
   title: string;
@@ -38,6 +80,7 @@ export interface Task {
   // categorization
   tags?: string[]; // Array of tag IDs (maps to iCal CATEGORIES)
   categoryId?: string; // Raw CATEGORIES string from CalDAV (used during sync, mapped to tags)
+  contexts?: string[]; // GTD context names like '@home' (X-CALDAV-TASKS-CONTEXTS), not tags
   priority: Priority;
 
   // dates
@@ -58,6 +101,19 @@ export interface Task {
   parentUid?: string; // UID of parent task
   isCollapsed?: boolean; // Whether subtasks are collapsed in UI
 
+  // dependencies (RELATED-TO;RELTYPE=DEPENDS-ON in CalDAV)
+  blockedBy?: string[]; // UIDs of tasks that have to be completed first
+
+  // tracked time carried through sync (X-CALDAV-TASKS-TIME), not loaded from the database
+  timeEntries?: TimeEntry[];
+
+  // planned effort in seconds (X-ESTIMATED-DURATION, as written by Tasks.org)
+  estimatedDuration?: number;
+
+  // board column of the open task (STATUS:IN-PROCESS, X-CALDAV-TASKS-COLUMN for custom ones);
+  // unset is the to-do column, completed tasks are always done
+  kanbanColumn?: string;
+
   // sorting
   sortOrder: number; // x-apple-sort-order
 
@@ -88,9 +144,28 @@ export interface Calendar {
   icon?: string; // Icon name from lucide-react
   accountId: string;
   supportedComponents?: string[]; // e.g., ['VTODO', 'VEVENT']
+  checklistAsTasks?: boolean; // upload checklist items as child VTODOs other clients can see
+  sortOrder?: number; // position within its account in the sidebar
+  archived?: boolean; // left out of the task lists, counts and sync until it's restored
 }
 
-export type ServerType = 'rustical' | 'radicale' | 'baikal' | 'nextcloud' | 'generic';
+export type ServerType =
+  | 'rustical'
+  | 'radicale'
+  | 'baikal'
+  | 'nextcloud'
+  | 'generic'
+  | 'demo' // local demo data, never synced
+  | 'local'; // holds tasks recovered from removed calendars, never synced
+
+// the protocol an account syncs through, see lib/syncBackend.ts
+export type SyncBackendType = 'caldav' | 'localFolder';
+
+export interface TlsSettings {
+  minVersion: '1.2' | '1.3';
+  pinnedCertificate?: string; // PEM, replaces the system trust store for this account
+  allowInvalidHostnames: boolean; // only honored together with a pinned certificate
+}
 
 export interface Account {
   id: string;
@@ -99,9 +174,15 @@ export interface Account {
   username: string;
   password: string; // stored locally, for now
   serverType?: ServerType; // defaults to 'rustical' for backward compatibility
+  backend?: SyncBackendType; // defaults to 'caldav'
+  tls?: TlsSettings;
   calendars: Calendar[];
   lastSync?: Date;
   isActive: boolean;
+  color?: string; // shown in the sidebar
+  sortOrder?: number; // position in the sidebar
+  collapsed?: boolean; // calendars hidden in the sidebar
+  authMethod?: 'basic' | 'digest' | 'bearer'; // what the server accepted last
 }
 
 export interface SortConfig {
@@ -6,6 +6,7 @@ import {
   isToday,
   isTomorrow,
 } from 'date-fns';
+import type { Reminder, Task } from '@/types';
 
 /**
  * Standard date format strings for consistent formatting across the app
@@ -110,3 +111,18 @@ export function formatDueDate(date: Date): {
     ...colors,
   };
 }
+
+/**
+ * when a reminder fires: one related to the start or due date follows the date, and falls
+ * back to its last worked out time when the task no longer has that date
+ */
+export function reminderTrigger(
+  task: Pick<Task, 'startDate' | 'dueDate'>,
+  reminder: Reminder,
+): Date {
+  const date = reminder.related === 'start' ? task.startDate : task.dueDate;
+  if (!reminder.related || reminder.offset === undefined || !date) {
+    return new Date(reminder.trigger);
+  }
+  return new Date(new Date(date).getTime() + reminder.offset * 1000);
+}
@@ -8,3 +8,13 @@
 export const pluralize = (count: number, singular: string, plural?: string) => {
   return count === 1 ? singular : plural || `${singular}s`;
 };
+
+/**
+ * format a number of seconds as hours and minutes, e.g. "2h 05m" or "12m"
+ */
+export const formatDuration = (seconds: number) => {
+  const minutes = Math.round(seconds / 60);
+  const hours = Math.floor(minutes / 60);
+  if (hours === 0) return `${minutes}m`;
+  return `${hours}h ${String(minutes % 60).padStart(2, '0')}m`;
+};
@@ -6,7 +6,7 @@
 import { v4 as uuidv4 } from 'uuid';
 import { createLogger } from '@/lib/logger';
 import * as taskData from '@/lib/taskData';
-import type { Priority, Reminder, Subtask, Task } from '@/types';
+import type { Priority, Reminder, Subtask, Task, TimeEntry } from '@/types';
 
 const log = createLogger('iCal', '#22c55e');
 
@@ -151,6 +151,38 @@ function parseICalDate(value: string): Date | undefined {
   return undefined;
 }
 
+/**
+ * Format seconds as an iCalendar duration (RFC 5545 3.3.6), e.g. PT1H30M
+ */
+function formatICalDuration(seconds: number): string {
+  const days = Math.floor(seconds / 86400);
+  const hours = Math.floor((seconds % 86400) / 3600);
+  const minutes = Math.floor((seconds % 3600) / 60);
+  const secs = seconds % 60;
+
+  let time = '';
+  if (hours) time += `${hours}H`;
+  if (minutes) time += `${minutes}M`;
+  if (secs) time += `${secs}S`;
+  if (!days && !time) return 'PT0S';
+  return `P${days ? `${days}D` : ''}${time ? `T${time}` : ''}`;
+}
+
+/**
+ * Parse an iCalendar duration to seconds
+ * Supports: P1W, P1DT2H, PT1H30M, PT45S (the sign is ignored)
+ */
+function parseICalDuration(value: string): number | undefined {
+  const match = value
+    .trim()
+    .toUpperCase()
+    .match(/^[+-]?P(?:(\d+)W)?(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)S)?)?$/);
+  if (!match) return undefined;
+
+  const [, weeks, days, hours, minutes, seconds] = match.map((part) => parseInt(part ?? '0', 10));
+  return weeks * 604800 + days * 86400 + hours * 3600 + minutes * 60 + seconds;
+}
+
 /**
  * Escape text for iCalendar format
  * Escapes: backslash, semicolon, comma, newline
@@ -250,6 +282,9 @@ function parseProperty(line: string): ICalProperty | null {
 interface ParsedVAlarm {
   action?: string;
   trigger?: Date;
+  // relative triggers: seconds from the start or due date
+  related?: 'start' | 'due';
+  offset?: number;
   description?: string;
 }
 
@@ -260,6 +295,7 @@ interface ParsedVTodo {
   status?: string;
   priority?: number;
   categories?: string[];
+  contexts?: string[];
   dtstart?: Date;
   dtstartAllDay?: boolean;
   due?: Date;
@@ -267,10 +303,17 @@ interface ParsedVTodo {
   completed?: Date;
   created?: Date;
   lastModified?: Date;
+  changeId?: string;
+  encrypted?: boolean;
   sortOrder?: number;
   subtasksJson?: string;
+  timeJson?: string;
+  estimatedDuration?: number;
+  checklistItemId?: string;
+  kanbanColumn?: string;
   isCollapsed?: boolean;
   parentUid?: string;
+  dependsOn?: string[];
   alarms?: ParsedVAlarm[];
   url?: string;
 }
@@ -301,9 +344,14 @@ function parseVAlarm(valarmContent: string): ParsedVAlarm {
         // Support both VALUE=DATE-TIME and relative triggers
         if (prop.params.VALUE === 'DATE-TIME') {
           result.trigger = parseICalDate(prop.value);
-        } else if (prop.value.startsWith('P') || prop.value.startsWith('-P')) {
-          // Relative trigger (e.g., -PT15M = 15 minutes before)
-          // For now, we skip relative triggers since we use absolute times
+        } else if (/^[+-]?P/i.test(prop.value.trim())) {
+          // Relative trigger (e.g., -PT15M = 15 minutes before), to DTSTART unless
+          // RELATED=END says DUE, like RFC 5545 says
+          const seconds = parseICalDuration(prop.value);
+          if (seconds !== undefined) {
+            result.related = prop.params.RELATED?.toUpperCase() === 'END' ? 'due' : 'start';
+            result.offset = prop.value.trim().startsWith('-') ? -seconds : seconds;
+          }
         } else {
           // Try parsing as absolute time
           result.trigger = parseICalDate(prop.value);
@@ -356,7 +404,9 @@ function parseVTodo(vtodoContent: string): ParsedVTodo {
   // Extract and parse VALARMs first
   const alarmContents = extractVAlarms(vtodoContent);
   if (alarmContents.length > 0) {
-    result.alarms = alarmContents.map(parseVAlarm).filter((a) => a.trigger);
+    result.alarms = alarmContents
+      .map(parseVAlarm)
+      .filter((a) => a.trigger || a.offset !== undefined);
   }
 
   for (const line of lines) {
@@ -372,7 +422,7 @@ function parseVTodo(vtodoContent: string): ParsedVTodo {
         result.uid = prop.value;
         break;
       case 'SUMMARY':
-        result.summary = unescapeICalText(prop.value);
+        result.summary = unescapeICalText(prop.value).normalize('NFC');
         break;
       case 'DESCRIPTION':
         result.description = unescapeICalText(prop.value);
@@ -385,7 +435,12 @@ function parseVTodo(vtodoContent: string): ParsedVTodo {
         break;
       case 'CATEGORIES':
         // Categories can be comma-separated
-        result.categories = prop.value.split(',').map((c) => unescapeICalText(c.trim()));
+        result.categories = prop.value
+          .split(',')
+          .map((c) => unescapeICalText(c.trim()).normalize('NFC'));
+        break;
+      case 'X-CALDAV-TASKS-CONTEXTS':
+        result.contexts = prop.value.split(',').map((c) => unescapeICalText(c.trim()));
         break;
       case 'DTSTART':
         result.dtstart = parseICalDate(prop.value);
@@ -406,20 +461,41 @@ function parseVTodo(vtodoContent: string): ParsedVTodo {
       case 'LAST-MODIFIED':
         result.lastModified = parseICalDate(prop.value);
         break;
+      case 'X-CALDAV-TASKS-CHANGE':
+        result.changeId = prop.value;
+        break;
+      case 'X-CALDAV-TASKS-E2E':
+        result.encrypted = prop.value === '1';
+        break;
       case 'X-APPLE-SORT-ORDER':
         result.sortOrder = parseInt(prop.value, 10);
         break;
       case 'X-CALDAV-TASKS-SUBTASKS':
         result.subtasksJson = prop.value;
         break;
+      case 'X-CALDAV-TASKS-TIME':
+        result.timeJson = prop.value;
+        break;
+      case 'X-CALDAV-TASKS-COLUMN':
+        result.kanbanColumn = unescapeICalText(prop.value);
+        break;
+      case 'X-CALDAV-TASKS-CHECKLIST-ITEM':
+        result.checklistItemId = prop.value;
+        break;
+      case 'X-ESTIMATED-DURATION':
+        // written by Tasks.org
+        result.estimatedDuration = parseICalDuration(prop.value);
+        break;
       case 'X-APPLE-COLLAPSED':
         result.isCollapsed = prop.value === '1';
         break;
       case 'RELATED-TO': {
-        // Only use PARENT relationship
-        const relType = prop.params.RELTYPE;
-        if (!relType || relType.toUpperCase() === 'PARENT') {
+        // PARENT for subtasks, DEPENDS-ON (RFC 9253) for dependencies
+        const relType = prop.params.RELTYPE?.toUpperCase();
+        if (!relType || relType === 'PARENT') {
           result.parentUid = prop.value;
+        } else if (relType === 'DEPENDS-ON') {
+          result.dependsOn = [...(result.dependsOn ?? []), prop.value];
         }
         break;
       }
@@ -463,20 +539,62 @@ function extractVTodos(icalContent: string): string[] {
   return vtodos;
 }
 
+/**
+ * the reminders of the VTODO's alarms; relative ones are worked out from the start or due
+ * date, and dropped when the task doesn't have that date
+ */
+function alarmsToReminders(parsed: ParsedVTodo): Reminder[] | undefined {
+  if (!parsed.alarms || parsed.alarms.length === 0) return undefined;
+  return parsed.alarms.flatMap((alarm): Reminder[] => {
+    if (alarm.related && alarm.offset !== undefined) {
+      const date = alarm.related === 'start' ? parsed.dtstart : parsed.due;
+      if (!date) return [];
+      const trigger = new Date(date.getTime() + alarm.offset * 1000);
+      return [{ id: uuidv4(), trigger, related: alarm.related, offset: alarm.offset }];
+    }
+    return alarm.trigger ? [{ id: uuidv4(), trigger: alarm.trigger }] : [];
+  });
+}
+
 /**
  * Generate a VALARM component as string
  */
-function generateVAlarm(reminder: Reminder): string {
+function generateVAlarm(reminder: Reminder, task: Task): string {
   const lines: string[] = [];
 
   lines.push('BEGIN:VALARM');
   lines.push('ACTION:DISPLAY');
-  lines.push(`TRIGGER;VALUE=DATE-TIME:${formatICalDate(new Date(reminder.trigger))}`);
+  // relative to a date the task still has, otherwise at the time it worked out to
+  const date = reminder.related === 'start' ? task.startDate : task.dueDate;
+  if (reminder.related && reminder.offset !== undefined && date) {
+    const related = reminder.related === 'start' ? 'START' : 'END';
+    const sign = reminder.offset < 0 ? '-' : '';
+    const duration = formatICalDuration(Math.abs(reminder.offset));
+    lines.push(`TRIGGER;RELATED=${related}:${sign}${duration}`);
+  } else {
+    lines.push(`TRIGGER;VALUE=DATE-TIME:${formatICalDate(new Date(reminder.trigger))}`);
+  }
   lines.push('END:VALARM');
 
   return lines.join('\r\n');
 }
 
+/**
+ * STATUS of a task; open tasks in the in-progress board column are IN-PROCESS
+ */
+function taskStatus(task: Task): string {
+  if (task.completed) return 'COMPLETED';
+  return task.kanbanColumn === 'in-progress' ? 'IN-PROCESS' : 'NEEDS-ACTION';
+}
+
+/**
+ * Board column of a parsed VTODO (see taskStatus)
+ */
+function kanbanColumn(parsed: ParsedVTodo): string | undefined {
+  if (parsed.kanbanColumn) return parsed.kanbanColumn;
+  return parsed.status === 'IN-PROCESS' ? 'in-progress' : undefined;
+}
+
 /**
  * Generate a VTODO component as string
  */
@@ -488,13 +606,26 @@ function generateVTodo(task: Task): string {
   lines.push(`DTSTAMP:${formatICalDate(new Date())}`);
   lines.push(`CREATED:${formatICalDate(new Date(task.createdAt))}`);
   lines.push(`LAST-MODIFIED:${formatICalDate(new Date(task.modifiedAt))}`);
+  // the local change this version came from (app-specific), see isOwnUpload in caldav.ts
+  if (task.changeId) {
+    lines.push(`X-CALDAV-TASKS-CHANGE:${task.changeId}`);
+  }
+  // the title and description are ciphertext for other devices with the passphrase
+  if (task.encrypted) {
+    lines.push('X-CALDAV-TASKS-E2E:1');
+  }
   lines.push(`SUMMARY:${escapeICalText(task.title)}`);
 
   if (task.description) {
     lines.push(`DESCRIPTION:${escapeICalText(task.description)}`);
   }
 
-  lines.push(`STATUS:${task.completed ? 'COMPLETED' : 'NEEDS-ACTION'}`);
+  lines.push(`STATUS:${taskStatus(task)}`);
+
+  // board columns other than to do / in progress / done (app-specific)
+  if (task.kanbanColumn && task.kanbanColumn !== 'in-progress') {
+    lines.push(`X-CALDAV-TASKS-COLUMN:${escapeICalText(task.kanbanColumn)}`);
+  }
 
   if (task.completed && task.completedAt) {
     lines.push(`COMPLETED:${formatICalDate(new Date(task.completedAt))}`);
@@ -533,11 +664,22 @@ function generateVTodo(task: Task): string {
     }
   }
 
+  // GTD contexts (app-specific, kept apart from CATEGORIES)
+  if (task.contexts && task.contexts.length > 0) {
+    const escaped = task.contexts.map((c) => escapeICalText(c));
+    lines.push(`X-CALDAV-TASKS-CONTEXTS:${escaped.join(',')}`);
+  }
+
   // Parent relationship
   if (task.parentUid) {
     lines.push(`RELATED-TO;RELTYPE=PARENT:${task.parentUid}`);
   }
 
+  // Dependencies (RFC 9253)
+  for (const uid of task.blockedBy ?? []) {
+    lines.push(`RELATED-TO;RELTYPE=DEPENDS-ON:${uid}`);
+  }
+
   // Collapsed state
   if (task.isCollapsed) {
     lines.push('X-APPLE-COLLAPSED:1');
@@ -549,6 +691,21 @@ function generateVTodo(task: Task): string {
     lines.push(`X-CALDAV-TASKS-SUBTASKS:${subtasksJson}`);
   }
 
+  // Tracked time (app-specific, only attached when the user syncs it)
+  if (task.timeEntries && task.timeEntries.length > 0) {
+    const entries = task.timeEntries.map(({ id, startedAt, endedAt }) => ({
+      id,
+      startedAt,
+      endedAt,
+    }));
+    lines.push(`X-CALDAV-TASKS-TIME:${JSON.stringify(entries)}`);
+  }
+
+  // Estimated duration (Tasks.org-compatible)
+  if (task.estimatedDuration) {
+    lines.push(`X-ESTIMATED-DURATION:${formatICalDuration(task.estimatedDuration)}`);
+  }
+
   // URL (RFC 7986)
   if (task.url) {
     lines.push(`URL:${escapeICalText(task.url)}`);
@@ -557,7 +714,7 @@ function generateVTodo(task: Task): string {
   // Reminders as VALARMs
   if (task.reminders && task.reminders.length > 0) {
     for (const reminder of task.reminders) {
-      lines.push(generateVAlarm(reminder));
+      lines.push(generateVAlarm(reminder, task));
     }
   }
 
@@ -567,6 +724,35 @@ function generateVTodo(task: Task): string {
   return lines.map(foldLine).join('\r\n');
 }
 
+/**
+ * Generate a child VTODO that carries one checklist item of a task
+ * (for calendars that sync checklist items as real tasks)
+ */
+function generateChecklistVTodo(
+  parent: Task,
+  item: Subtask,
+  index: number,
+  encrypted: boolean,
+): string {
+  const lines: string[] = [];
+
+  lines.push('BEGIN:VTODO');
+  lines.push(`UID:${checklistItemUid(parent.uid, item.id)}`);
+  lines.push(`DTSTAMP:${formatICalDate(new Date())}`);
+  lines.push(`LAST-MODIFIED:${formatICalDate(new Date(parent.modifiedAt))}`);
+  if (encrypted) {
+    lines.push('X-CALDAV-TASKS-E2E:1');
+  }
+  lines.push(`SUMMARY:${escapeICalText(item.title)}`);
+  lines.push(`STATUS:${item.completed ? 'COMPLETED' : 'NEEDS-ACTION'}`);
+  lines.push(`X-APPLE-SORT-ORDER:${index}`);
+  lines.push(`RELATED-TO;RELTYPE=PARENT:${parent.uid}`);
+  lines.push(`X-CALDAV-TASKS-CHECKLIST-ITEM:${item.id}`);
+  lines.push('END:VTODO');
+
+  return lines.map(foldLine).join('\r\n');
+}
+
 /**
  * Generate a complete VCALENDAR with VTODOs
  */
@@ -623,17 +809,18 @@ export function vtodoToTask(
       }
     }
 
-    // Parse reminders from alarms
-    let reminders: Reminder[] | undefined;
-    if (parsed.alarms && parsed.alarms.length > 0) {
-      reminders = parsed.alarms
-        .filter((a) => a.trigger)
-        .map((a) => ({
-          id: uuidv4(),
-          trigger: a.trigger!,
-        }));
+    // Parse tracked time
+    let timeEntries: TimeEntry[] | undefined;
+    if (parsed.timeJson) {
+      try {
+        timeEntries = JSON.parse(parsed.timeJson);
+      } catch {
+        timeEntries = undefined;
+      }
     }
 
+    const reminders = alarmsToReminders(parsed);
+
     // Calculate sort order
     const createdDate = parsed.created || new Date();
     let sortOrder: number;
@@ -648,12 +835,15 @@ export function vtodoToTask(
       uid: parsed.uid || uuidv4(),
       etag,
       href,
+      changeId: parsed.changeId,
+      encrypted: parsed.encrypted || undefined,
       title: parsed.summary || 'Untitled Task',
       description: filterCalDavDescription(parsed.description),
       completed: parsed.status === 'COMPLETED',
       completedAt: parsed.completed,
       priority: icalToPriority(parsed.priority || 0),
       categoryId: parsed.categories?.join(',') || undefined,
+      contexts: parsed.contexts,
       startDate: parsed.dtstart,
       startDateAllDay: parsed.dtstartAllDay,
       dueDate: parsed.due,
@@ -662,6 +852,11 @@ export function vtodoToTask(
       modifiedAt: parsed.lastModified || new Date(),
       subtasks,
       parentUid: parsed.parentUid,
+      // always set, so dependencies removed on the server are removed here too
+      blockedBy: parsed.dependsOn ?? [],
+      timeEntries,
+      estimatedDuration: parsed.estimatedDuration,
+      kanbanColumn: kanbanColumn(parsed),
       isCollapsed: parsed.isCollapsed || false,
       sortOrder,
       url: parsed.url,
@@ -678,6 +873,55 @@ export function vtodoToTask(
   }
 }
 
+/**
+ * A checklist item read back from its child VTODO
+ */
+export interface ChecklistItem {
+  parentUid: string;
+  sortOrder: number;
+  item: Subtask;
+}
+
+/**
+ * UID of the child VTODO that holds a checklist item
+ */
+export function checklistItemUid(parentUid: string, itemId: string): string {
+  return `${parentUid}-${itemId}`;
+}
+
+/**
+ * Convert a checklist item of a task to a child VTODO, marked when its title is encrypted
+ */
+export function checklistItemToVTodo(
+  parent: Task,
+  item: Subtask,
+  index: number,
+  encrypted = false,
+): string {
+  return generateVCalendar([generateChecklistVTodo(parent, item, index, encrypted)]);
+}
+
+/**
+ * Parse a child VTODO written by checklistItemToVTodo; null for any other task
+ */
+export function vtodoToChecklistItem(icalString: string): ChecklistItem | null {
+  const vtodos = extractVTodos(icalString);
+  if (vtodos.length === 0) return null;
+
+  const parsed = parseVTodo(vtodos[0]);
+  if (!parsed.checklistItemId || !parsed.parentUid) return null;
+
+  return {
+    parentUid: parsed.parentUid,
+    sortOrder: parsed.sortOrder ?? 0,
+    item: {
+      id: parsed.checklistItemId,
+      title: parsed.summary || '',
+      completed: parsed.status === 'COMPLETED',
+    },
+  };
+}
+
 /**
  * Generate a unique iCalendar UID
  */
@@ -803,16 +1047,7 @@ export function parseIcsFile(icsContent: string): Partial<Task>[] {
         }
       }
 
-      // Parse reminders from alarms
-      let reminders: Reminder[] | undefined;
-      if (parsed.alarms && parsed.alarms.length > 0) {
-        reminders = parsed.alarms
-          .filter((a) => a.trigger)
-          .map((a) => ({
-            id: uuidv4(),
-            trigger: a.trigger!,
-          }));
-      }
+      const reminders = alarmsToReminders(parsed);
 
       // Calculate sort order
       const createdDate = parsed.created || new Date();
@@ -832,6 +1067,7 @@ export function parseIcsFile(icsContent: string): Partial<Task>[] {
         completedAt: parsed.completed,
         priority: icalToPriority(parsed.priority || 0),
         categoryId: parsed.categories?.join(',') || undefined,
+        contexts: parsed.contexts,
         startDate: parsed.dtstart,
         startDateAllDay: parsed.dtstartAllDay,
         dueDate: parsed.due,
@@ -840,6 +1076,9 @@ export function parseIcsFile(icsContent: string): Partial<Task>[] {
         modifiedAt: parsed.lastModified || new Date(),
         subtasks,
         parentUid: parsed.parentUid,
+        blockedBy: parsed.dependsOn,
+        estimatedDuration: parsed.estimatedDuration,
+        kanbanColumn: kanbanColumn(parsed),
         isCollapsed: parsed.isCollapsed || false,
         sortOrder,
         url: parsed.url,
@@ -7,6 +7,8 @@ import {
   PredefinedMenuItem,
   Submenu,
 } from '@tauri-apps/api/menu';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { translate } from '@/lib/database';
 import { loggers } from '@/lib/logger';
 import type { KeyboardShortcut } from '@/store/settingsStore';
 
@@ -89,11 +91,93 @@ function getAcceleratorById(
   return shortcutToAccelerator(shortcut);
 }
 
+const isMac = navigator.userAgent.includes('Macintosh');
+const isLinux = !isMac && navigator.userAgent.includes('Linux');
+
+// sort modes of the View menu with their labels
+const SORT_LABELS: Record<string, string> = {
+  manual: 'Manual',
+  smart: 'Smart Sort',
+  'due-date': 'Due Date',
+  priority: 'Priority',
+  title: 'Title',
+  created: 'Date Created',
+  modified: 'Date Modified',
+};
+
+// every label of the menu, translated by the backend before it's built
+const MENU_LABELS = [
+  'About caldav-tasks',
+  'Preferences...',
+  'Services',
+  'Hide caldav-tasks',
+  'Hide Others',
+  'Show All',
+  'Quit caldav-tasks',
+  'Quit',
+  'File',
+  'New Task',
+  'Sync',
+  'Import...',
+  'Export...',
+  'Close Window',
+  'Edit',
+  'Undo',
+  'Redo',
+  'Cut',
+  'Copy',
+  'Paste',
+  'Select All',
+  'Search Tasks...',
+  'View',
+  'Show Completed Tasks',
+  'Sort By',
+  ...Object.values(SORT_LABELS),
+  'Enter Full Screen',
+  'Calendar',
+  'Add Account...',
+  'Add Calendar...',
+  'Window',
+  'Minimize',
+  'Zoom',
+  'Help',
+  'Keyboard Shortcuts',
+];
+
+let translations = new Map<string, string>();
+
+async function loadTranslations(): Promise<void> {
+  try {
+    const translated = await translate(MENU_LABELS);
+    translations = new Map(MENU_LABELS.map((label, i) => [label, translated[i]]));
+  } catch (error) {
+    log.warn('Failed to translate the menu:', error);
+  }
+}
+
+/**
+ * a menu label in the backend's language
+ */
+function t(label: string): string {
+  return translations.get(label) ?? label;
+}
+
+function sortLabel(mode: string, active: boolean): string {
+  return active ? `✓ ${t(SORT_LABELS[mode])}` : t(SORT_LABELS[mode]);
+}
+
+// accelerators work while the window is hidden to the tray, so these bring it back
+async function emitShown(event: string): Promise<void> {
+  const window = getCurrentWindow();
+  await window.show();
+  await window.setFocus();
+  await emit(event);
+}
+
 /**
- * creates and sets the macOS application menu
- * only called on macOS
+ * creates the application menu: the macOS app menu, or the main window's menu bar elsewhere
  */
-export async function createMacMenu(options?: {
+export async function createAppMenu(options?: {
   showCompleted?: boolean;
   sortMode?: 'manual' | 'smart' | 'due-date' | 'priority' | 'title' | 'created' | 'modified';
   shortcuts?: KeyboardShortcut[];
@@ -102,67 +186,61 @@ export async function createMacMenu(options?: {
   const sortMode = options?.sortMode ?? 'manual';
   const shortcuts = options?.shortcuts;
 
-  const appSubmenu = await Submenu.new({
-    text: 'caldav-tasks',
-    items: [
-      await MenuItem.new({
-        id: 'about',
-        text: 'About CalDAV Tasks',
-        action: () => {
-          emit(MENU_EVENTS.ABOUT);
-        },
-      }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-      await MenuItem.new({
-        id: 'preferences',
-        text: 'Preferences...',
-        accelerator: getAcceleratorById(shortcuts, 'settings') || 'CmdOrCtrl+,',
-        action: () => {
-          emit(MENU_EVENTS.PREFERENCES);
-        },
-      }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({
-        text: 'Services',
-        item: 'Services',
-      }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({
-        text: 'Hide CalDAV Tasks',
-        item: 'Hide',
-      }),
-      await PredefinedMenuItem.new({
-        text: 'Hide Others',
-        item: 'HideOthers',
-      }),
-      await PredefinedMenuItem.new({
-        text: 'Show All',
-        item: 'ShowAll',
-      }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({
-        text: 'Quit CalDAV Tasks',
-        item: 'Quit',
-      }),
-    ],
+  await loadTranslations();
+  const separator = () => PredefinedMenuItem.new({ item: 'Separator' });
+
+  const aboutItem = await MenuItem.new({
+    id: 'about',
+    text: t('About caldav-tasks'),
+    action: () => {
+      emit(MENU_EVENTS.ABOUT);
+    },
+  });
+  const preferencesItem = await MenuItem.new({
+    id: 'preferences',
+    text: t('Preferences...'),
+    accelerator: getAcceleratorById(shortcuts, 'settings') || 'CmdOrCtrl+,',
+    action: () => {
+      emit(MENU_EVENTS.PREFERENCES);
+    },
   });
 
+  // macOS keeps these in the app submenu, elsewhere they go to File and Help
+  const appSubmenu = isMac
+    ? await Submenu.new({
+        text: 'caldav-tasks',
+        items: [
+          aboutItem,
+          await separator(),
+          preferencesItem,
+          await separator(),
+          await PredefinedMenuItem.new({ text: t('Services'), item: 'Services' }),
+          await separator(),
+          await PredefinedMenuItem.new({ text: t('Hide caldav-tasks'), item: 'Hide' }),
+          await PredefinedMenuItem.new({ text: t('Hide Others'), item: 'HideOthers' }),
+          await PredefinedMenuItem.new({ text: t('Show All'), item: 'ShowAll' }),
+          await separator(),
+          await PredefinedMenuItem.new({ text: t('Quit caldav-tasks'), item: 'Quit' }),
+        ],
+      })
+    : null;
+
   const fileSubmenu = await Submenu.new({
-    text: 'File',
+    text: t('File'),
     items: [
       await IconMenuItem.new({
         id: 'new-task',
-        text: 'New Task',
+        text: t('New Task'),
         icon: 'Add',
         accelerator: getAcceleratorById(shortcuts, 'new-task') || 'CmdOrCtrl+N',
         action: () => {
-          emit(MENU_EVENTS.NEW_TASK);
+          emitShown(MENU_EVENTS.NEW_TASK);
         },
       }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
+      await separator(),
       (menuItemRefs.sync = await IconMenuItem.new({
         id: 'sync',
-        text: 'Sync',
+        text: t('Sync'),
         icon: 'Refresh',
         accelerator: getAcceleratorById(shortcuts, 'sync') || 'CmdOrCtrl+R',
         enabled: false,
@@ -170,10 +248,10 @@ export async function createMacMenu(options?: {
           emit(MENU_EVENTS.SYNC);
         },
       })),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
+      await separator(),
       await IconMenuItem.new({
         id: 'import',
-        text: 'Import...',
+        text: t('Import...'),
         icon: 'Bookmarks',
         accelerator: 'CmdOrCtrl+I',
         action: () => {
@@ -182,7 +260,7 @@ export async function createMacMenu(options?: {
       }),
       (menuItemRefs.export = await IconMenuItem.new({
         id: 'export',
-        text: 'Export...',
+        text: t('Export...'),
         icon: 'Share',
         accelerator: 'CmdOrCtrl+E',
         enabled: false,
@@ -190,145 +268,103 @@ export async function createMacMenu(options?: {
           emit(MENU_EVENTS.EXPORT_TASKS);
         },
       })),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({
-        text: 'Close Window',
-        item: 'CloseWindow',
-      }),
+      await separator(),
+      ...(isMac
+        ? [await PredefinedMenuItem.new({ text: t('Close Window'), item: 'CloseWindow' })]
+        : [
+            preferencesItem,
+            await separator(),
+            await PredefinedMenuItem.new({ text: t('Quit'), item: 'Quit' }),
+          ]),
     ],
   });
 
   const editSubmenu = await Submenu.new({
-    text: 'Edit',
+    text: t('Edit'),
     items: [
-      await PredefinedMenuItem.new({
-        text: 'Undo',
-        item: 'Undo',
-      }),
-      await PredefinedMenuItem.new({
-        text: 'Redo',
-        item: 'Redo',
-      }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({
-        text: 'Cut',
-        item: 'Cut',
-      }),
-      await PredefinedMenuItem.new({
-        text: 'Copy',
-        item: 'Copy',
-      }),
-      await PredefinedMenuItem.new({
-        text: 'Paste',
-        item: 'Paste',
-      }),
-      await PredefinedMenuItem.new({
-        text: 'Select All',
-        item: 'SelectAll',
-      }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
+      // GTK has no predefined undo and redo
+      ...(isLinux
+        ? []
+        : [
+            await PredefinedMenuItem.new({ text: t('Undo'), item: 'Undo' }),
+            await PredefinedMenuItem.new({ text: t('Redo'), item: 'Redo' }),
+            await separator(),
+          ]),
+      await PredefinedMenuItem.new({ text: t('Cut'), item: 'Cut' }),
+      await PredefinedMenuItem.new({ text: t('Copy'), item: 'Copy' }),
+      await PredefinedMenuItem.new({ text: t('Paste'), item: 'Paste' }),
+      await PredefinedMenuItem.new({ text: t('Select All'), item: 'SelectAll' }),
+      await separator(),
       await MenuItem.new({
         id: 'search',
-        text: 'Search Tasks...',
+        text: t('Search Tasks...'),
         accelerator: getAcceleratorById(shortcuts, 'search') || 'CmdOrCtrl+F',
         action: () => {
-          emit(MENU_EVENTS.SEARCH);
+          emitShown(MENU_EVENTS.SEARCH);
         },
       }),
     ],
   });
 
+  const sortItem = async (mode: string, event: string) =>
+    MenuItem.new({
+      id: `sort-${mode}`,
+      text: sortLabel(mode, sortMode === mode),
+      action: () => {
+        emit(event);
+      },
+    });
+
   // View submenu
   const viewSubmenu = await Submenu.new({
-    text: 'View',
+    text: t('View'),
     items: [
       (menuItemRefs.toggleCompleted = await CheckMenuItem.new({
         id: 'toggle-completed',
-        text: 'Show Completed Tasks',
+        text: t('Show Completed Tasks'),
         accelerator: getAcceleratorById(shortcuts, 'toggle-show-completed') || 'CmdOrCtrl+Shift+H',
         checked: showCompleted,
         action: () => {
           emit(MENU_EVENTS.TOGGLE_COMPLETED);
         },
       })),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
+      await separator(),
       await Submenu.new({
         icon: 'ListView',
-        text: 'Sort By',
+        text: t('Sort By'),
         items: [
-          (menuItemRefs.sortManual = await MenuItem.new({
-            id: 'sort-manual',
-            text: sortMode === 'manual' ? '✓ Manual' : 'Manual',
-            action: () => {
-              emit(MENU_EVENTS.SORT_MANUAL);
-            },
-          })),
-          (menuItemRefs.sortSmart = await MenuItem.new({
-            id: 'sort-smart',
-            text: sortMode === 'smart' ? '✓ Smart Sort' : 'Smart Sort',
-            action: () => {
-              emit(MENU_EVENTS.SORT_SMART);
-            },
-          })),
-          (menuItemRefs.sortDueDate = await MenuItem.new({
-            id: 'sort-due-date',
-            text: sortMode === 'due-date' ? '✓ Due Date' : 'Due Date',
-            action: () => {
-              emit(MENU_EVENTS.SORT_DUE_DATE);
-            },
-          })),
-          (menuItemRefs.sortPriority = await MenuItem.new({
-            id: 'sort-priority',
-            text: sortMode === 'priority' ? '✓ Priority' : 'Priority',
-            action: () => {
-              emit(MENU_EVENTS.SORT_PRIORITY);
-            },
-          })),
-          (menuItemRefs.sortTitle = await MenuItem.new({
-            id: 'sort-title',
-            text: sortMode === 'title' ? '✓ Title' : 'Title',
-            action: () => {
-              emit(MENU_EVENTS.SORT_TITLE);
-            },
-          })),
-          (menuItemRefs.sortCreated = await MenuItem.new({
-            id: 'sort-created',
-            text: sortMode === 'created' ? '✓ Date Created' : 'Date Created',
-            action: () => {
-              emit(MENU_EVENTS.SORT_CREATED);
-            },
-          })),
-          (menuItemRefs.sortModified = await MenuItem.new({
-            id: 'sort-modified',
-            text: sortMode === 'modified' ? '✓ Date Modified' : 'Date Modified',
-            action: () => {
-              emit(MENU_EVENTS.SORT_MODIFIED);
-            },
-          })),
+          (menuItemRefs.sortManual = await sortItem('manual', MENU_EVENTS.SORT_MANUAL)),
+          (menuItemRefs.sortSmart = await sortItem('smart', MENU_EVENTS.SORT_SMART)),
+          (menuItemRefs.sortDueDate = await sortItem('due-date', MENU_EVENTS.SORT_DUE_DATE)),
+          (menuItemRefs.sortPriority = await sortItem('priority', MENU_EVENTS.SORT_PRIORITY)),
+          (menuItemRefs.sortTitle = await sortItem('title', MENU_EVENTS.SORT_TITLE)),
+          (menuItemRefs.sortCreated = await sortItem('created', MENU_EVENTS.SORT_CREATED)),
+          (menuItemRefs.sortModified = await sortItem('modified', MENU_EVENTS.SORT_MODIFIED)),
         ],
       }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-      await PredefinedMenuItem.new({
-        text: 'Enter Full Screen',
-        item: 'Fullscreen',
-      }),
+      ...(isMac
+        ? [
+            await separator(),
+            await PredefinedMenuItem.new({ text: t('Enter Full Screen'), item: 'Fullscreen' }),
+          ]
+        : []),
     ],
   });
 
   // Calendar submenu
   const calendarSubmenu = await Submenu.new({
-    text: 'Calendar',
+    text: t('Calendar'),
     items: [
       await MenuItem.new({
         id: 'add-account',
-        text: 'Add Account...',
+        text: t('Add Account...'),
         action: () => {
           emit(MENU_EVENTS.ADD_ACCOUNT);
         },
       }),
       (menuItemRefs.addCalendar = await MenuItem.new({
         id: 'add-calendar',
-        text: 'Add Calendar...',
+        text: t('Add Calendar...'),
         enabled: false,
         action: () => {
           emit(MENU_EVENTS.ADD_CALENDAR);
@@ -337,40 +373,39 @@ export async function createMacMenu(options?: {
     ],
   });
 
-  // Window submenu
-  const windowSubmenu = await Submenu.new({
-    text: 'Window',
-    items: [
-      await PredefinedMenuItem.new({
-        text: 'Minimize',
-        item: 'Minimize',
-      }),
-      await PredefinedMenuItem.new({
-        text: 'Zoom',
-        item: 'Maximize',
-      }),
-      await PredefinedMenuItem.new({ item: 'Separator' }),
-    ],
-  });
+  // Window submenu, only macOS has one
+  const windowSubmenu = isMac
+    ? await Submenu.new({
+        text: t('Window'),
+        items: [
+          await PredefinedMenuItem.new({ text: t('Minimize'), item: 'Minimize' }),
+          await PredefinedMenuItem.new({ text: t('Zoom'), item: 'Maximize' }),
+          await separator(),
+        ],
+      })
+    : null;
 
-  await windowSubmenu.setAsWindowsMenuForNSApp();
+  await windowSubmenu?.setAsWindowsMenuForNSApp();
 
   // Help submenu
   const helpSubmenu = await Submenu.new({
-    text: 'Help',
+    text: t('Help'),
     items: [
       await MenuItem.new({
         id: 'keyboard-shortcuts',
-        text: 'Keyboard Shortcuts',
+        text: t('Keyboard Shortcuts'),
         accelerator: 'CmdOrCtrl+/',
         action: () => {
           emit(MENU_EVENTS.SHOW_KEYBOARD_SHORTCUTS);
         },
       }),
+      ...(isMac ? [] : [await separator(), aboutItem]),
     ],
   });
 
-  await helpSubmenu.setAsHelpMenuForNSApp().catch(() => {});
+  if (isMac) {
+    await helpSubmenu.setAsHelpMenuForNSApp().catch(() => {});
+  }
 
   // Create the main menu
   const menu = await Menu.new({
@@ -382,7 +417,7 @@ export async function createMacMenu(options?: {
       calendarSubmenu,
       windowSubmenu,
       helpSubmenu,
-    ],
+    ].filter((submenu): submenu is Submenu => submenu !== null),
   });
 
   return menu;
@@ -397,15 +432,15 @@ export async function initAppMenu(options?: {
   sortMode?: 'manual' | 'smart' | 'due-date' | 'priority' | 'title' | 'created' | 'modified';
   shortcuts?: KeyboardShortcut[];
 }): Promise<void> {
-  // Only create menu on macOS
-  if (!navigator.userAgent.includes('Macintosh')) {
-    return;
-  }
-
   try {
-    const menu = await createMacMenu(options);
-    await menu.setAsAppMenu();
-    log.info('macOS menu initialized successfully');
+    const menu = await createAppMenu(options);
+    if (isMac) {
+      await menu.setAsAppMenu();
+    } else {
+      // only the main window calls this, calendar windows go without a menu bar
+      await menu.setAsWindowMenu();
+    }
+    log.info('App menu initialized successfully');
   } catch (error) {
     log.error('Failed to initialize menu:', error);
   }
@@ -516,23 +551,11 @@ export async function updateMenuState(options: {
   }
   if (options.sortMode !== undefined) {
     // update sort menu items with checkmarks in text (radio button behavior)
-    const sortOptions: Record<string, string> = {
-      manual: 'Manual',
-      smart: 'Smart Sort',
-      'due-date': 'Due Date',
-      priority: 'Priority',
-      title: 'Title',
-      created: 'Date Created',
-      modified: 'Date Modified',
-    };
-
     log.debug('Updating sort menu checkmarks, active mode:', options.sortMode);
-    for (const [mode, label] of Object.entries(sortOptions)) {
-      const hasCheck = mode === options.sortMode;
-      log.debug(`Setting sort-${mode} to: ${hasCheck ? '✓ ' : ''}${label}`);
-      await updateMenuItem(`sort-${mode}`, {
-        text: hasCheck ? `✓ ${label}` : label,
-      });
+    for (const mode of Object.keys(SORT_LABELS)) {
+      const text = sortLabel(mode, mode === options.sortMode);
+      log.debug(`Setting sort-${mode} to: ${text}`);
+      await updateMenuItem(`sort-${mode}`, { text });
     }
   }
 }
@@ -4,3 +4,11 @@ export function isMacPlatform(): boolean {
   }
   return /Mac/.test(navigator.userAgent);
 }
+
+/**
+ * Case and accent folded form of a name for comparing, so "Café", "cafe" and a decomposed
+ * "café" from macOS all match
+ */
+export function foldText(text: string): string {
+  return text.normalize('NFD').replace(/\p{M}/gu, '').toLowerCase();
+}